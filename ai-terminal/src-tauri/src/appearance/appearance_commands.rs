@@ -0,0 +1,51 @@
+use crate::appearance::types::appearance_manager::{Appearance, AppearanceManager};
+use portable_pty::CommandBuilder;
+use serde::Serialize;
+use tauri::{command, AppHandle, Emitter, Manager, Runtime, State};
+
+#[derive(Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct AppearanceInfo {
+    pub appearance: Appearance,
+    pub colorfgbg: String,
+}
+
+fn appearance_info(appearance: Appearance) -> AppearanceInfo {
+    AppearanceInfo {
+        appearance,
+        colorfgbg: appearance.colorfgbg().to_string(),
+    }
+}
+
+// Records the OS theme and, when it flipped, tells the UI so the terminal palette follows.
+// Sessions started afterwards get the matching COLORFGBG; programs already running learn
+// the new background by querying it (OSC 11), which the terminal answers from its palette.
+pub fn handle_theme_changed<R: Runtime>(app_handle: &AppHandle<R>, theme: tauri::Theme) {
+    let appearance = Appearance::from(theme);
+    if app_handle.state::<AppearanceManager>().set(appearance) {
+        let _ = app_handle.emit("appearance_changed", appearance_info(appearance));
+    }
+}
+
+// Seeds the appearance from the main window at startup
+pub fn init_appearance<R: Runtime>(app_handle: &AppHandle<R>) {
+    if let Some(theme) = app_handle
+        .get_webview_window("main")
+        .and_then(|window| window.theme().ok())
+    {
+        app_handle
+            .state::<AppearanceManager>()
+            .set(Appearance::from(theme));
+    }
+}
+
+// Sets the palette hints on a PTY child
+pub fn apply_appearance_env<R: Runtime>(app_handle: &AppHandle<R>, command: &mut CommandBuilder) {
+    let appearance = app_handle.state::<AppearanceManager>().current();
+    command.env("COLORFGBG", appearance.colorfgbg());
+}
+
+#[command]
+pub fn get_appearance(appearance_manager: State<'_, AppearanceManager>) -> AppearanceInfo {
+    appearance_info(appearance_manager.current())
+}
@@ -0,0 +1,2 @@
+pub mod appearance_commands;
+pub mod types;
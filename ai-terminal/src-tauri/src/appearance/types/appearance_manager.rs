@@ -0,0 +1,66 @@
+use serde::Serialize;
+use std::sync::Mutex;
+
+#[derive(Serialize, Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum Appearance {
+    Light,
+    #[default]
+    Dark,
+}
+
+impl Appearance {
+    // rxvt-style "foreground;background" ANSI colour indices that TUI programs (vim, mc,
+    // htop, ...) read to pick a palette
+    pub fn colorfgbg(self) -> &'static str {
+        match self {
+            Appearance::Light => "0;15",
+            Appearance::Dark => "15;0",
+        }
+    }
+}
+
+impl From<tauri::Theme> for Appearance {
+    fn from(theme: tauri::Theme) -> Self {
+        match theme {
+            tauri::Theme::Light => Appearance::Light,
+            _ => Appearance::Dark,
+        }
+    }
+}
+
+// The OS appearance as last reported by the windows
+pub struct AppearanceManager {
+    appearance: Mutex<Appearance>,
+}
+
+impl AppearanceManager {
+    pub fn new() -> Self {
+        Self {
+            appearance: Mutex::new(Appearance::default()),
+        }
+    }
+
+    pub fn current(&self) -> Appearance {
+        self.appearance
+            .lock()
+            .map(|appearance| *appearance)
+            .unwrap_or_default()
+    }
+
+    // Returns true if the appearance changed
+    pub fn set(&self, appearance: Appearance) -> bool {
+        let Ok(mut current) = self.appearance.lock() else {
+            return false;
+        };
+        let changed = *current != appearance;
+        *current = appearance;
+        changed
+    }
+}
+
+impl Default for AppearanceManager {
+    fn default() -> Self {
+        Self::new()
+    }
+}
@@ -0,0 +1,40 @@
+use crate::audit::audit_log::{audit_log_path, verify_audit_file, AuditVerification};
+use crate::utils::file_system_utils::expand_home;
+use serde::Serialize;
+use std::fs;
+use tauri::command;
+
+#[derive(Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct AuditExport {
+    pub path: String,
+    pub verification: AuditVerification,
+}
+
+#[command]
+pub fn verify_audit_log() -> Result<AuditVerification, String> {
+    verify_audit_file(&audit_log_path()?)
+}
+
+// Copies the log verbatim (so the chain can be re-verified elsewhere) and reports its integrity
+#[command]
+pub fn export_audit_log(path: String) -> Result<AuditExport, String> {
+    let source = audit_log_path()?;
+    if !source.exists() {
+        return Err("The audit log is empty".to_string());
+    }
+    let verification = verify_audit_file(&source)?;
+
+    let target = expand_home(&path)?;
+    if let Some(parent) = target.parent() {
+        fs::create_dir_all(parent)
+            .map_err(|e| format!("Failed to create {}: {}", parent.display(), e))?;
+    }
+    fs::copy(&source, &target)
+        .map_err(|e| format!("Failed to export audit log to {}: {}", target.display(), e))?;
+
+    Ok(AuditExport {
+        path: target.to_string_lossy().to_string(),
+        verification,
+    })
+}
@@ -0,0 +1,241 @@
+use crate::settings::types::settings_manager::SettingsManager;
+use crate::utils::config_utils::{app_data_dir, unix_timestamp_millis};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::fs::{self, OpenOptions};
+use std::io::Write;
+use std::os::unix::fs::OpenOptionsExt;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use tauri::{AppHandle, Manager, Runtime};
+
+pub const AUDIT_LOG_FILE: &str = "audit.jsonl";
+const GENESIS_HASH: &str = "0000000000000000000000000000000000000000000000000000000000000000";
+
+// Fields covered by the hash; field order is fixed by the struct so re-serializing is stable
+#[derive(Serialize, Deserialize, Clone, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct AuditPayload {
+    pub seq: u64,
+    pub timestamp: u64,
+    pub user: String,
+    pub host: String,
+    pub session_id: String,
+    pub command: String,
+    pub cwd: String,
+    pub remote: bool,
+    pub exit_code: Option<i32>,
+    pub prev_hash: String,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct AuditRecord {
+    #[serde(flatten)]
+    pub payload: AuditPayload,
+    pub hash: String,
+}
+
+#[derive(Serialize, Clone, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct AuditVerification {
+    pub valid: bool,
+    pub records: u64,
+    pub first_invalid_line: Option<u64>,
+    pub error: Option<String>,
+}
+
+struct ChainHead {
+    seq: u64,
+    hash: String,
+}
+
+// Append-only, hash-chained command log: each record hashes its payload including the
+// previous record's hash, so edits or deletions break verification from that point on
+pub struct AuditLog {
+    head: Mutex<Option<ChainHead>>,
+}
+
+pub fn audit_log_path() -> Result<PathBuf, String> {
+    Ok(app_data_dir()?.join(AUDIT_LOG_FILE))
+}
+
+fn hash_payload(payload: &AuditPayload) -> Result<String, String> {
+    let json = serde_json::to_string(payload).map_err(|e| e.to_string())?;
+    Ok(Sha256::digest(json.as_bytes())
+        .iter()
+        .map(|byte| format!("{:02x}", byte))
+        .collect())
+}
+
+fn current_user() -> String {
+    std::env::var("USER")
+        .or_else(|_| std::env::var("USERNAME"))
+        .unwrap_or_else(|_| "unknown".to_string())
+}
+
+fn current_host() -> String {
+    nix::unistd::gethostname()
+        .ok()
+        .and_then(|name| name.into_string().ok())
+        .unwrap_or_else(|| "unknown".to_string())
+}
+
+impl AuditLog {
+    pub fn new() -> Self {
+        Self {
+            head: Mutex::new(None),
+        }
+    }
+
+    // Reads the last record so new entries continue the existing chain
+    fn load_head(path: &Path) -> Result<ChainHead, String> {
+        let contents = match fs::read_to_string(path) {
+            Ok(contents) => contents,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => String::new(),
+            Err(e) => return Err(format!("Failed to read {}: {}", path.display(), e)),
+        };
+        match contents.lines().rev().find(|line| !line.trim().is_empty()) {
+            Some(line) => {
+                let record: AuditRecord = serde_json::from_str(line)
+                    .map_err(|e| format!("Audit log is corrupted: {}", e))?;
+                Ok(ChainHead {
+                    seq: record.payload.seq,
+                    hash: record.hash,
+                })
+            }
+            None => Ok(ChainHead {
+                seq: 0,
+                hash: GENESIS_HASH.to_string(),
+            }),
+        }
+    }
+
+    pub fn append(
+        &self,
+        session_id: &str,
+        command: &str,
+        cwd: &str,
+        remote: bool,
+        exit_code: Option<i32>,
+    ) -> Result<(), String> {
+        let path = audit_log_path()?;
+        let mut head = self.head.lock().map_err(|e| e.to_string())?;
+        if head.is_none() {
+            *head = Some(Self::load_head(&path)?);
+        }
+        let chain = head.as_mut().ok_or("Audit log chain unavailable")?;
+
+        let payload = AuditPayload {
+            seq: chain.seq + 1,
+            timestamp: unix_timestamp_millis(),
+            user: current_user(),
+            host: current_host(),
+            session_id: session_id.to_string(),
+            command: command.to_string(),
+            cwd: cwd.to_string(),
+            remote,
+            exit_code,
+            prev_hash: chain.hash.clone(),
+        };
+        let record = AuditRecord {
+            hash: hash_payload(&payload)?,
+            payload,
+        };
+        let line = serde_json::to_string(&record).map_err(|e| e.to_string())?;
+
+        let mut file = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .mode(0o600)
+            .open(&path)
+            .map_err(|e| format!("Failed to open {}: {}", path.display(), e))?;
+        writeln!(file, "{}", line)
+            .map_err(|e| format!("Failed to write {}: {}", path.display(), e))?;
+
+        chain.seq = record.payload.seq;
+        chain.hash = record.hash;
+        Ok(())
+    }
+}
+
+impl Default for AuditLog {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+pub fn verify_audit_file(path: &Path) -> Result<AuditVerification, String> {
+    let contents = match fs::read_to_string(path) {
+        Ok(contents) => contents,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => String::new(),
+        Err(e) => return Err(format!("Failed to read {}: {}", path.display(), e)),
+    };
+
+    let mut expected_prev = GENESIS_HASH.to_string();
+    let mut expected_seq = 1;
+    let mut records = 0;
+    for (index, line) in contents.lines().enumerate() {
+        if line.trim().is_empty() {
+            continue;
+        }
+        let invalid = |error: String| AuditVerification {
+            valid: false,
+            records,
+            first_invalid_line: Some(index as u64 + 1),
+            error: Some(error),
+        };
+        let record: AuditRecord = match serde_json::from_str(line) {
+            Ok(record) => record,
+            Err(e) => return Ok(invalid(format!("Unparseable record: {}", e))),
+        };
+        if record.payload.seq != expected_seq {
+            return Ok(invalid(format!(
+                "Expected sequence {}, found {}",
+                expected_seq, record.payload.seq
+            )));
+        }
+        if record.payload.prev_hash != expected_prev {
+            return Ok(invalid("Previous-hash link does not match".to_string()));
+        }
+        if hash_payload(&record.payload)? != record.hash {
+            return Ok(invalid(
+                "Record hash does not match its contents".to_string(),
+            ));
+        }
+        expected_prev = record.hash;
+        expected_seq += 1;
+        records += 1;
+    }
+
+    Ok(AuditVerification {
+        valid: true,
+        records,
+        first_invalid_line: None,
+        error: None,
+    })
+}
+
+// Records an executed command when audit logging is enabled in settings
+pub fn record_audit_event<R: Runtime>(
+    app_handle: &AppHandle<R>,
+    session_id: &str,
+    command: &str,
+    cwd: &str,
+    remote: bool,
+    exit_code: Option<i32>,
+) {
+    if !app_handle
+        .state::<SettingsManager>()
+        .current()
+        .audit_log_enabled
+    {
+        return;
+    }
+    if let Err(e) = app_handle
+        .state::<AuditLog>()
+        .append(session_id, command, cwd, remote, exit_code)
+    {
+        eprintln!("[audit] Failed to record command: {}", e);
+    }
+}
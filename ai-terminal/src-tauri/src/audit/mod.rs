@@ -0,0 +1,2 @@
+pub mod audit_commands;
+pub mod audit_log;
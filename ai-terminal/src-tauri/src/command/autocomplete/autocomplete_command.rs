@@ -1,16 +1,36 @@
-use crate::command::constants::COMMON_COMMANDS;
+use crate::command::autocomplete::command_names::known_command_names;
+use crate::command::autocomplete::tldr::usage_hint;
 use crate::command::types::command_manager::CommandManager;
+use crate::command::types::learned_command_store::LearnedCommandStore;
+use crate::settings::types::settings_manager::SettingsManager;
 use crate::utils::file_system_utils::split_path_prefix;
+use serde::Serialize;
 use std::fs;
 use std::path::{Path, PathBuf};
 use tauri::{command, State};
 
+#[derive(Serialize, Clone, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct Completion {
+    pub value: String,
+    // Argument template for completed command names, e.g. `tar -xf <archive> -C <dir>`
+    pub usage: Option<String>,
+}
+
+impl Completion {
+    fn path(value: String) -> Self {
+        Completion { value, usage: None }
+    }
+}
+
 #[command]
 pub fn autocomplete(
     input: String,
     session_id: String,
     command_manager: State<'_, CommandManager>,
-) -> Result<Vec<String>, String> {
+    settings_manager: State<'_, SettingsManager>,
+    learned_command_store: State<'_, LearnedCommandStore>,
+) -> Result<Vec<Completion>, String> {
     let states = command_manager.commands.lock().map_err(|e| e.to_string())?;
     let key = session_id;
 
@@ -26,10 +46,23 @@ pub fn autocomplete(
     if input_parts.len() <= 1 && input_parts.first() != Some(&"cd") {
         let input_prefix = input_parts.first().unwrap_or(&"");
 
-        let matches: Vec<String> = autocomplete_base_command(input_prefix);
+        let known = {
+            let learned = learned_command_store
+                .commands
+                .lock()
+                .map_err(|e| e.to_string())?;
+            known_command_names(&settings_manager.current(), &learned)
+        };
+        let matches: Vec<String> = autocomplete_base_command(&known, input_prefix);
 
         if !matches.is_empty() {
-            return Ok(matches);
+            return Ok(matches
+                .into_iter()
+                .map(|name| Completion {
+                    usage: usage_hint(&name),
+                    value: name,
+                })
+                .collect());
         }
     }
 
@@ -117,7 +150,7 @@ pub fn autocomplete(
             if !matches.is_empty() {
                 // Sort matches alphabetically, case-insensitive
                 matches.sort_by_key(|a| a.to_lowercase());
-                return Ok(matches);
+                return Ok(matches.into_iter().map(Completion::path).collect());
             }
         }
     }
@@ -125,10 +158,10 @@ pub fn autocomplete(
     Ok(Vec::new())
 }
 
-fn autocomplete_base_command(input_prefix: &str) -> Vec<String> {
-    COMMON_COMMANDS
+fn autocomplete_base_command(known: &[String], input_prefix: &str) -> Vec<String> {
+    known
         .iter()
-        .filter(|&command| command.starts_with(input_prefix))
-        .map(|&command| command.to_string())
+        .filter(|command| command.starts_with(input_prefix))
+        .cloned()
         .collect()
 }
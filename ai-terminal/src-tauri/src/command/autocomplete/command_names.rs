@@ -0,0 +1,124 @@
+use crate::command::constants::COMMON_COMMANDS;
+use crate::command::correction::command_not_found::{command_program, COMMAND_NOT_FOUND_EXIT_CODE};
+use crate::command::types::learned_command_store::{LearnedCommandStore, LearnedCommands};
+use crate::history::types::history_store::HistoryStore;
+use crate::settings::types::app_settings::AppSettings;
+use crate::settings::types::settings_manager::SettingsManager;
+use serde::Serialize;
+use tauri::{command, AppHandle, Manager, Runtime, State};
+
+#[derive(Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct KnownCommands {
+    pub builtin: Vec<String>,
+    // From the customCommands setting
+    pub custom: Vec<String>,
+    pub learned: Vec<String>,
+}
+
+// Bare program names only: paths, assignments and shell syntax are never offered
+fn is_command_name(word: &str) -> bool {
+    !word.is_empty()
+        && !word.starts_with('-')
+        && word
+            .chars()
+            .all(|c| c.is_alphanumeric() || matches!(c, '-' | '_' | '.' | '+'))
+}
+
+// Names offered for the first word: the built-in list, then the user's, then learned ones
+pub fn known_command_names(settings: &AppSettings, learned: &LearnedCommands) -> Vec<String> {
+    let mut names: Vec<String> = Vec::new();
+    let candidates = COMMON_COMMANDS
+        .iter()
+        .map(|name| name.to_string())
+        .chain(settings.custom_commands.iter().cloned())
+        .chain(learned.learned.iter().cloned());
+    for name in candidates {
+        if !names.contains(&name) {
+            names.push(name);
+        }
+    }
+    names
+}
+
+// Called for every history entry: a program that is not known yet is learned once history
+// holds learn_command_threshold runs of it that did not fail with "command not found"
+pub fn learn_command<R: Runtime>(app_handle: &AppHandle<R>, command: &str, exit_code: Option<i32>) {
+    let settings = app_handle.state::<SettingsManager>().current();
+    if !settings.learn_commands_enabled || exit_code == Some(COMMAND_NOT_FOUND_EXIT_CODE) {
+        return;
+    }
+    let Some(program) = command_program(command).filter(|word| is_command_name(word)) else {
+        return;
+    };
+    if COMMON_COMMANDS.contains(&program) || settings.custom_commands.iter().any(|c| c == program) {
+        return;
+    }
+
+    let store = app_handle.state::<LearnedCommandStore>();
+    let Ok(mut learned) = store.commands.lock() else {
+        return;
+    };
+    if learned.learned.contains(program) || learned.forgotten.contains(program) {
+        return;
+    }
+    let runs = match app_handle.state::<HistoryStore>().entries() {
+        Ok(entries) => entries
+            .iter()
+            .flatten()
+            .filter(|entry| {
+                entry.exit_code != Some(COMMAND_NOT_FOUND_EXIT_CODE)
+                    && command_program(&entry.command) == Some(program)
+            })
+            .count(),
+        Err(_) => return,
+    };
+    if runs < settings.learn_command_threshold as usize {
+        return;
+    }
+    learned.learned.insert(program.to_string());
+    if let Err(e) = store.persist(&learned) {
+        eprintln!("[autocomplete] Failed to save learned commands: {}", e);
+    }
+}
+
+pub fn validate_custom_commands(commands: &[String]) -> Result<(), String> {
+    match commands.iter().find(|name| !is_command_name(name)) {
+        Some(name) => Err(format!("Invalid command name '{}'", name)),
+        None => Ok(()),
+    }
+}
+
+#[command]
+pub fn get_known_commands(
+    settings_manager: State<'_, SettingsManager>,
+    learned_command_store: State<'_, LearnedCommandStore>,
+) -> Result<KnownCommands, String> {
+    let learned = learned_command_store
+        .commands
+        .lock()
+        .map_err(|e| e.to_string())?;
+    Ok(KnownCommands {
+        builtin: COMMON_COMMANDS
+            .iter()
+            .map(|name| name.to_string())
+            .collect(),
+        custom: settings_manager.current().custom_commands,
+        learned: learned.learned.iter().cloned().collect(),
+    })
+}
+
+// Removes a learned command from autocompletion and stops it from being learned again
+#[command]
+pub fn forget_learned_command(
+    name: String,
+    learned_command_store: State<'_, LearnedCommandStore>,
+) -> Result<(), String> {
+    let mut learned = learned_command_store
+        .commands
+        .lock()
+        .map_err(|e| e.to_string())?;
+    learned.learned.remove(&name);
+    learned.forgotten.insert(name);
+    learned_command_store.persist(&learned)
+}
@@ -1 +1,3 @@
 pub mod autocomplete_command;
+pub mod command_names;
+pub mod tldr;
@@ -0,0 +1,108 @@
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::sync::OnceLock;
+use tauri::command;
+
+// Bundled subset of tldr-pages; examples keep tldr's {{placeholder}} syntax
+const TLDR_PAGES: &str = include_str!("../../../data/tldr.json");
+
+#[derive(Deserialize)]
+struct TldrFile {
+    pages: HashMap<String, RawPage>,
+}
+
+#[derive(Deserialize)]
+struct RawPage {
+    description: String,
+    examples: Vec<RawExample>,
+}
+
+#[derive(Deserialize)]
+struct RawExample {
+    description: String,
+    command: String,
+}
+
+#[derive(Serialize, Clone, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct TldrExample {
+    pub description: String,
+    // The example with placeholders written as <name>, e.g. `tar -xf <archive> -C <dir>`
+    pub usage: String,
+}
+
+#[derive(Serialize, Clone, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct TldrPage {
+    pub command: String,
+    pub description: String,
+    pub examples: Vec<TldrExample>,
+}
+
+// `{{path/to/file}}` -> `<path/to/file>`
+pub fn placeholders_to_usage(example: &str) -> String {
+    let mut usage = String::with_capacity(example.len());
+    let mut rest = example;
+    while let Some(open) = rest.find("{{") {
+        let Some(close) = rest[open + 2..].find("}}") else {
+            break;
+        };
+        usage.push_str(&rest[..open]);
+        usage.push('<');
+        usage.push_str(&rest[open + 2..open + 2 + close]);
+        usage.push('>');
+        rest = &rest[open + 2 + close + 2..];
+    }
+    usage.push_str(rest);
+    usage
+}
+
+fn tldr_pages() -> &'static HashMap<String, TldrPage> {
+    static PAGES: OnceLock<HashMap<String, TldrPage>> = OnceLock::new();
+    PAGES.get_or_init(|| {
+        let file: TldrFile = serde_json::from_str(TLDR_PAGES).expect("valid bundled tldr pages");
+        file.pages
+            .into_iter()
+            .map(|(command, page)| {
+                let examples = page
+                    .examples
+                    .into_iter()
+                    .map(|example| TldrExample {
+                        description: example.description,
+                        usage: placeholders_to_usage(&example.command),
+                    })
+                    .collect();
+                let page = TldrPage {
+                    command: command.clone(),
+                    description: page.description,
+                    examples,
+                };
+                (command, page)
+            })
+            .collect()
+    })
+}
+
+// Every bundled page
+pub fn tldr_commands() -> impl Iterator<Item = &'static TldrPage> {
+    tldr_pages().values()
+}
+
+pub fn tldr_page(command: &str) -> Option<&'static TldrPage> {
+    tldr_pages().get(command)
+}
+
+// Inline hint for a completed command name: its first example that takes arguments
+pub fn usage_hint(command: &str) -> Option<String> {
+    let page = tldr_page(command)?;
+    page.examples
+        .iter()
+        .find(|example| example.usage.contains('<'))
+        .or_else(|| page.examples.first())
+        .map(|example| example.usage.clone())
+}
+
+#[command]
+pub fn get_tldr(command: String) -> Option<TldrPage> {
+    tldr_page(command.trim()).cloned()
+}
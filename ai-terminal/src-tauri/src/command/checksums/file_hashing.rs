@@ -0,0 +1,343 @@
+use crate::command::constants::{
+    HASH_BUFFER_BYTES, HASH_PROGRESS_INTERVAL_MS, HASH_PROGRESS_MIN_BYTES,
+};
+use crate::command::types::command_manager::CommandManager;
+use crate::utils::file_system_utils::resolve_session_path;
+use md5::Md5;
+use serde::{Deserialize, Serialize};
+use sha1::Sha1;
+use sha2::{Digest, Sha224, Sha256, Sha384, Sha512};
+use std::fs::{self, File};
+use std::io::Read;
+use std::path::Path;
+use std::time::{Duration, Instant};
+use tauri::{command, AppHandle, Emitter, Manager, Runtime};
+
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum HashAlgorithm {
+    Md5,
+    Sha1,
+    Sha224,
+    Sha256,
+    Sha384,
+    Sha512,
+}
+
+impl HashAlgorithm {
+    const ALL: &'static [HashAlgorithm] = &[
+        HashAlgorithm::Md5,
+        HashAlgorithm::Sha1,
+        HashAlgorithm::Sha224,
+        HashAlgorithm::Sha256,
+        HashAlgorithm::Sha384,
+        HashAlgorithm::Sha512,
+    ];
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            HashAlgorithm::Md5 => "md5",
+            HashAlgorithm::Sha1 => "sha1",
+            HashAlgorithm::Sha224 => "sha224",
+            HashAlgorithm::Sha256 => "sha256",
+            HashAlgorithm::Sha384 => "sha384",
+            HashAlgorithm::Sha512 => "sha512",
+        }
+    }
+
+    // Accepts the spellings tools use: sha256, SHA-256, sha256sum
+    pub fn parse(name: &str) -> Result<Self, String> {
+        let normalized = name
+            .trim()
+            .to_lowercase()
+            .replace('-', "")
+            .trim_end_matches("sum")
+            .to_string();
+        HashAlgorithm::ALL
+            .iter()
+            .copied()
+            .find(|algorithm| algorithm.as_str() == normalized)
+            .ok_or_else(|| format!("Unsupported hash algorithm: {}", name))
+    }
+
+    // The algorithm a hex digest of this length comes from; sha256 for 64 digits
+    fn from_digest_length(length: usize) -> Option<Self> {
+        match length {
+            32 => Some(HashAlgorithm::Md5),
+            40 => Some(HashAlgorithm::Sha1),
+            56 => Some(HashAlgorithm::Sha224),
+            64 => Some(HashAlgorithm::Sha256),
+            96 => Some(HashAlgorithm::Sha384),
+            128 => Some(HashAlgorithm::Sha512),
+            _ => None,
+        }
+    }
+
+    fn hasher(&self) -> Box<dyn DynDigest> {
+        match self {
+            HashAlgorithm::Md5 => Box::new(Md5::new()),
+            HashAlgorithm::Sha1 => Box::new(Sha1::new()),
+            HashAlgorithm::Sha224 => Box::new(Sha224::new()),
+            HashAlgorithm::Sha256 => Box::new(Sha256::new()),
+            HashAlgorithm::Sha384 => Box::new(Sha384::new()),
+            HashAlgorithm::Sha512 => Box::new(Sha512::new()),
+        }
+    }
+}
+
+// Object-safe view of the digest types so one read loop serves every algorithm
+trait DynDigest {
+    fn update(&mut self, data: &[u8]);
+    fn finalize_hex(self: Box<Self>) -> String;
+}
+
+impl<D: Digest> DynDigest for D {
+    fn update(&mut self, data: &[u8]) {
+        Digest::update(self, data);
+    }
+
+    fn finalize_hex(self: Box<Self>) -> String {
+        Digest::finalize(*self)
+            .iter()
+            .map(|byte| format!("{:02x}", byte))
+            .collect()
+    }
+}
+
+#[derive(Serialize, Clone, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct FileHash {
+    pub path: String,
+    pub algorithm: HashAlgorithm,
+    // Lowercase hex
+    pub digest: String,
+    pub size: u64,
+    pub duration_ms: u64,
+}
+
+#[derive(Serialize, Clone, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct ChecksumVerification {
+    pub path: String,
+    pub algorithm: HashAlgorithm,
+    pub expected: String,
+    pub actual: String,
+    pub matches: bool,
+}
+
+#[derive(Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+struct HashProgressEvent {
+    path: String,
+    algorithm: HashAlgorithm,
+    bytes_hashed: u64,
+    total_bytes: u64,
+    percent: f64,
+}
+
+// Streams the file through the hasher, emitting hash_progress for large files
+fn hash_path<R: Runtime>(
+    app_handle: &AppHandle<R>,
+    path: &Path,
+    algorithm: HashAlgorithm,
+) -> Result<FileHash, String> {
+    let started_at = Instant::now();
+    let display = path.to_string_lossy().to_string();
+    let mut file =
+        File::open(path).map_err(|e| format!("Failed to open {}: {}", path.display(), e))?;
+    let total_bytes = file.metadata().map(|metadata| metadata.len()).unwrap_or(0);
+    let report_progress = total_bytes >= HASH_PROGRESS_MIN_BYTES;
+
+    let mut hasher = algorithm.hasher();
+    let mut buffer = vec![0; HASH_BUFFER_BYTES];
+    let mut bytes_hashed = 0u64;
+    let mut reported_at = Instant::now();
+    loop {
+        let read = match file.read(&mut buffer) {
+            Ok(0) => break,
+            Ok(read) => read,
+            Err(e) if e.kind() == std::io::ErrorKind::Interrupted => continue,
+            Err(e) => return Err(format!("Failed to read {}: {}", path.display(), e)),
+        };
+        hasher.update(&buffer[..read]);
+        bytes_hashed += read as u64;
+        if report_progress
+            && reported_at.elapsed() >= Duration::from_millis(HASH_PROGRESS_INTERVAL_MS)
+        {
+            reported_at = Instant::now();
+            let _ = app_handle.emit(
+                "hash_progress",
+                HashProgressEvent {
+                    path: display.clone(),
+                    algorithm,
+                    bytes_hashed,
+                    total_bytes,
+                    percent: (bytes_hashed as f64 / total_bytes as f64 * 1000.0).round() / 10.0,
+                },
+            );
+        }
+    }
+    if report_progress {
+        let _ = app_handle.emit(
+            "hash_progress",
+            HashProgressEvent {
+                path: display.clone(),
+                algorithm,
+                bytes_hashed,
+                total_bytes,
+                percent: 100.0,
+            },
+        );
+    }
+
+    Ok(FileHash {
+        path: display,
+        algorithm,
+        digest: hasher.finalize_hex(),
+        size: bytes_hashed,
+        duration_ms: started_at.elapsed().as_millis() as u64,
+    })
+}
+
+fn is_hex_digest(text: &str) -> bool {
+    HashAlgorithm::from_digest_length(text.len()).is_some()
+        && text.chars().all(|c| c.is_ascii_hexdigit())
+}
+
+// A digest in one of the usual notations, with the algorithm when it says so:
+// `<hex>`, `sha256:<hex>`, `<hex>  file` (sha256sum) or `SHA256 (file) = <hex>` (BSD)
+fn parse_checksum_line(
+    line: &str,
+    file_name: Option<&str>,
+) -> Option<(Option<HashAlgorithm>, String)> {
+    let line = line.trim();
+    if let Some((label, digest)) = line.split_once(") = ") {
+        let (algorithm, name) = label.split_once(" (")?;
+        if file_name
+            .is_some_and(|file_name| Path::new(name).file_name() != Some(file_name.as_ref()))
+        {
+            return None;
+        }
+        let digest = digest.trim();
+        return is_hex_digest(digest)
+            .then(|| (HashAlgorithm::parse(algorithm).ok(), digest.to_lowercase()));
+    }
+    if let Some((algorithm, digest)) = line.split_once(':') {
+        if let Ok(algorithm) = HashAlgorithm::parse(algorithm) {
+            let digest = digest.trim();
+            return is_hex_digest(digest).then(|| (Some(algorithm), digest.to_lowercase()));
+        }
+    }
+    let mut words = line.split_whitespace();
+    let digest = words.next()?;
+    if !is_hex_digest(digest) {
+        return None;
+    }
+    // sha256sum marks binary mode with a leading `*`
+    let name = words.next().map(|name| name.trim_start_matches('*'));
+    if let (Some(file_name), Some(name)) = (file_name, name) {
+        if Path::new(name).file_name() != Some(file_name.as_ref()) {
+            return None;
+        }
+    }
+    Some((None, digest.to_lowercase()))
+}
+
+// `expected` is a digest (optionally prefixed with its algorithm), or a checksum file such as
+// SHA256SUMS or foo.tar.gz.sha256, from which the line for the file is used
+fn expected_checksum(
+    expected: &str,
+    expected_path: Option<&Path>,
+    file_name: Option<&str>,
+) -> Result<(HashAlgorithm, String), String> {
+    let (algorithm, digest) = match expected_path.filter(|path| path.is_file()) {
+        Some(checksum_file) => {
+            let contents = fs::read_to_string(checksum_file)
+                .map_err(|e| format!("Failed to read {}: {}", checksum_file.display(), e))?;
+            let lines: Vec<&str> = contents
+                .lines()
+                .filter(|line| !line.trim().is_empty())
+                .collect();
+            // A single-entry file may name the file differently (or not at all)
+            let named = lines
+                .iter()
+                .find_map(|line| parse_checksum_line(line, file_name));
+            let single = (lines.len() == 1)
+                .then(|| parse_checksum_line(lines[0], None))
+                .flatten();
+            named.or(single).ok_or_else(|| {
+                format!(
+                    "No checksum for {} in {}",
+                    file_name.unwrap_or("the file"),
+                    checksum_file.display()
+                )
+            })?
+        }
+        None => parse_checksum_line(expected, None)
+            .ok_or_else(|| format!("'{}' is not a checksum", expected.trim()))?,
+    };
+    let algorithm = match algorithm {
+        Some(algorithm) => algorithm,
+        None => HashAlgorithm::from_digest_length(digest.len())
+            .ok_or_else(|| format!("Cannot tell the algorithm of '{}'", digest))?,
+    };
+    Ok((algorithm, digest))
+}
+
+// Hashes a file (sha256 unless `algorithm` says otherwise: md5, sha1, sha224, sha384, sha512)
+#[command]
+pub async fn hash_file<R: Runtime>(
+    path: String,
+    algorithm: Option<String>,
+    session_id: Option<String>,
+    app_handle: AppHandle<R>,
+) -> Result<FileHash, String> {
+    let algorithm = match algorithm {
+        Some(algorithm) => HashAlgorithm::parse(&algorithm)?,
+        None => HashAlgorithm::Sha256,
+    };
+    let path = resolve_session_path(
+        &app_handle.state::<CommandManager>(),
+        session_id.as_deref(),
+        &path,
+    )?;
+    tauri::async_runtime::spawn_blocking(move || hash_path(&app_handle, &path, algorithm))
+        .await
+        .map_err(|e| e.to_string())?
+}
+
+// Compares a file with an expected checksum: a digest in any common notation, or the path
+// of a checksum file. The algorithm follows from the notation or the digest length.
+#[command]
+pub async fn verify_checksum<R: Runtime>(
+    path: String,
+    expected: String,
+    session_id: Option<String>,
+    app_handle: AppHandle<R>,
+) -> Result<ChecksumVerification, String> {
+    let (path, expected_path) = {
+        let command_manager = app_handle.state::<CommandManager>();
+        let path = resolve_session_path(&command_manager, session_id.as_deref(), &path)?;
+        let expected_path =
+            resolve_session_path(&command_manager, session_id.as_deref(), &expected).ok();
+        (path, expected_path)
+    };
+    let file_name = path
+        .file_name()
+        .map(|name| name.to_string_lossy().to_string());
+    let (algorithm, expected) =
+        expected_checksum(&expected, expected_path.as_deref(), file_name.as_deref())?;
+
+    tauri::async_runtime::spawn_blocking(move || {
+        let hash = hash_path(&app_handle, &path, algorithm)?;
+        Ok(ChecksumVerification {
+            path: hash.path,
+            algorithm,
+            matches: hash.digest == expected,
+            expected,
+            actual: hash.digest,
+        })
+    })
+    .await
+    .map_err(|e| e.to_string())?
+}
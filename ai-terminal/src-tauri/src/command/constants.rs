@@ -2,3 +2,221 @@ pub const COMMON_COMMANDS: &[&str] = &[
     "cd", "ls", "pwd", "mkdir", "touch", "cat", "echo", "grep", "find", "cp", "mv", "rm", "tar",
     "gzip", "ssh", "curl", "wget", "history", "exit", "clear", "top", "ps", "kill", "ping",
 ];
+
+// Upper bound for retained output per session before the oldest entries are dropped
+pub const MAX_SCROLLBACK_BYTES: usize = 2 * 1024 * 1024;
+
+// Per-session history of individual command runs kept for diffing and analysis
+pub const MAX_RECORDED_RUNS: usize = 50;
+pub const MAX_RUN_OUTPUT_BYTES: usize = 256 * 1024;
+
+// Lines kept in the optional plain-text copy of a session's output
+pub const MAX_PLAIN_TEXT_LINES: usize = 10_000;
+// Viewport height of copy mode when the frontend does not say
+pub const COPY_MODE_DEFAULT_ROWS: usize = 24;
+
+// How long a confirmation token stays valid after being issued
+pub const CONFIRMATION_TTL_MS: u64 = 2 * 60 * 1000;
+
+// Events kept per session in the activity timeline
+pub const MAX_TIMELINE_EVENTS: usize = 5_000;
+
+// Commands kept in the persistent history store
+pub const MAX_HISTORY_ENTRIES: usize = 50_000;
+// Commands get_host_history returns when no limit is given
+pub const HOST_HISTORY_LIMIT: usize = 500;
+
+// Directory visits kept for the recent folders and projects menus
+pub const MAX_DIRECTORY_VISITS: usize = 5_000;
+pub const DEFAULT_RECENT_DIRECTORIES: usize = 20;
+
+// Per-directory command suggestions: how many are returned, and how quickly old runs stop
+// counting
+pub const DEFAULT_DIRECTORY_SUGGESTIONS: usize = 8;
+pub const DIRECTORY_SUGGESTION_HALF_LIFE_MS: u64 = 30 * 24 * 60 * 60 * 1000;
+
+// execute_with_retry limits; the delay doubles after every failed attempt
+pub const DEFAULT_RETRY_ATTEMPTS: u32 = 3;
+pub const MAX_RETRY_ATTEMPTS: u32 = 10;
+pub const DEFAULT_RETRY_BACKOFF_MS: u64 = 1000;
+pub const MAX_RETRY_BACKOFF_MS: u64 = 60_000;
+
+// Kubernetes exec sessions poll the pod this many times (every interval) after it restarts
+pub const K8S_RECONNECT_ATTEMPTS: u32 = 30;
+pub const K8S_RECONNECT_INTERVAL_MS: u64 = 2000;
+
+// Output kept verbatim in an agent command receipt; the hash always covers the full capture
+pub const RECEIPT_OUTPUT_PREVIEW_BYTES: usize = 8 * 1024;
+
+// command_progress events are sent when progress moves this many points, or this often
+pub const PROGRESS_MIN_STEP_PERCENT: f64 = 1.0;
+pub const PROGRESS_MIN_INTERVAL_MS: u64 = 250;
+
+// Diagnostics extracted from one command's output, and how much output is scanned
+pub const MAX_DIAGNOSTICS: usize = 200;
+pub const DIAGNOSTICS_SCAN_BYTES: usize = 256 * 1024;
+
+// Code blocks from AI answers are written under the session's temp dir and removed after this
+// long, or with the session
+pub const CODE_BLOCK_DIR: &str = "code-blocks";
+pub const CODE_BLOCK_RETENTION_MS: u64 = 60 * 60 * 1000;
+
+// Session temp dirs (get_session_tmpdir) live under <per-user runtime or cache dir>/<this>/<app pid>
+pub const SESSION_TMPDIR_ROOT: &str = "ai-terminal-sessions";
+
+// Session variables (set_var) are small scratch values, not file contents
+pub const MAX_SESSION_VARIABLES: usize = 100;
+pub const MAX_SESSION_VARIABLE_BYTES: usize = 4096;
+
+// capture_output keeps at most this much stdout per capture, and this many captures per session
+pub const MAX_CAPTURE_BYTES: usize = 64 * 1024;
+pub const MAX_CAPTURES: usize = 50;
+
+// Input prompt detection: how often a running command is checked, how long it must be silent
+// after a known prompt / after any question-like line, and how much of the last line is kept
+pub const INPUT_PROMPT_CHECK_INTERVAL_MS: u64 = 100;
+pub const INPUT_PROMPT_QUIET_MS: u64 = 300;
+pub const INPUT_IDLE_MS: u64 = 3_000;
+pub const INPUT_PROMPT_TAIL_BYTES: usize = 512;
+
+// Stderr kept from an SSH session to explain why it ended, and how long the wait thread gives
+// the stderr reader to drain after ssh exits
+pub const SSH_ERROR_TAIL_BYTES: usize = 2048;
+pub const SSH_ERROR_DRAIN_MS: u64 = 500;
+
+// Control socket of interactive SSH sessions (ssh expands ~ and %C itself), and the limits of
+// an ssh_exec call
+pub const SSH_CONTROL_PATH: &str = "~/.ssh/ai-terminal-%C";
+pub const SSH_EXEC_TIMEOUT_MS: u64 = 30_000;
+pub const SSH_EXEC_CONNECT_TIMEOUT_SECS: u64 = 10;
+pub const MAX_SSH_EXEC_OUTPUT_BYTES: usize = 1024 * 1024;
+
+// Initial size of the PTY a terminal-only command is moved to; the frontend resizes it
+pub const PTY_FALLBACK_COLS: u16 = 120;
+pub const PTY_FALLBACK_ROWS: u16 = 32;
+
+// Largest scrollback selection attached to an AI question; longer selections keep their end
+pub const MAX_AI_SELECTION_BYTES: usize = 16 * 1024;
+
+// Longest pipeline build_pipeline accepts
+pub const MAX_PIPELINE_STAGES: usize = 16;
+
+// tail_detached_log returns this many lines by default, read from at most this much of the log
+pub const DETACHED_LOG_TAIL_LINES: usize = 200;
+pub const DETACHED_LOG_TAIL_BYTES: u64 = 256 * 1024;
+
+// How often the idle-lock monitor checks window activity
+pub const IDLE_LOCK_CHECK_INTERVAL_MS: u64 = 5_000;
+
+// Line editor: kills remembered for yank/yank-pop, and undo steps kept per session
+pub const KILL_RING_SIZE: usize = 30;
+pub const LINE_EDITOR_UNDO_LIMIT: usize = 200;
+
+// How often do-not-disturb is re-checked, and the most notifications held back while it is on
+pub const DND_CHECK_INTERVAL_MS: u64 = 15_000;
+pub const MAX_QUEUED_NOTIFICATIONS: usize = 100;
+// Bells closer together than this (a shell beeping on every failed completion) ring once
+pub const BELL_MIN_INTERVAL_MS: u64 = 200;
+// Pauses longer than this while recording a macro are shortened to it on playback
+pub const MACRO_MAX_STEP_DELAY_MS: u64 = 5_000;
+pub const MACRO_MAX_STEPS: usize = 10_000;
+
+// Tab stops used when expanding tabs in exported transcripts
+pub const TRANSCRIPT_TAB_WIDTH: usize = 8;
+
+// Background scheduler: tick resolution, tasks allowed to run at once, how much battery-aware
+// tasks slow down on battery power, and how often the power source is re-checked
+pub const SCHEDULER_TICK_MS: u64 = 1_000;
+pub const MAX_CONCURRENT_BACKGROUND_TASKS: usize = 2;
+pub const BATTERY_INTERVAL_MULTIPLIER: u64 = 4;
+pub const POWER_CHECK_INTERVAL_MS: u64 = 60_000;
+// Delay after the first window before history, the PATH cache and Ollama are warmed up
+pub const STARTUP_WARMUP_DELAY_MS: u64 = 1_500;
+
+// Background git fetch: how often the task runs, and the minimum gap between fetches of one
+// repository however many sessions sit in it
+pub const GIT_FETCH_TASK_INTERVAL_MS: u64 = 60_000;
+pub const GIT_FETCH_MIN_INTERVAL_MS: u64 = 10 * 60 * 1000;
+pub const GIT_FETCH_TIMEOUT_MS: u64 = 30_000;
+
+// Prompt git status: how long a prompt refresh waits for a scan before showing the cached
+// (stale) result, the gap between scans of one directory as a multiple of the last scan's
+// duration, the scan time above which counts are limited to the cwd subtree, and how many
+// directories are remembered
+pub const GIT_STATUS_WAIT_MS: u64 = 150;
+pub const GIT_STATUS_THROTTLE_FACTOR: u64 = 4;
+pub const GIT_STATUS_SUBTREE_MS: u64 = 1_000;
+pub const GIT_STATUS_CACHE_ENTRIES: usize = 64;
+
+pub const PATH_CACHE_REFRESH_INTERVAL_MS: u64 = 5 * 60 * 1000;
+
+// Offline cheatsheets: where the tldr-pages index and pages come from, how often the refresh
+// task checks them, when a downloaded copy is considered stale, and how many search results
+// are returned
+pub const TLDR_INDEX_URL: &str = "https://tldr.sh/assets/index.json";
+pub const TLDR_PAGES_URL: &str = "https://raw.githubusercontent.com/tldr-pages/tldr/main/pages";
+pub const TLDR_REFRESH_TASK_INTERVAL_MS: u64 = 6 * 60 * 60 * 1000;
+pub const TLDR_MAX_AGE_MS: u64 = 7 * 24 * 60 * 60 * 1000;
+pub const TLDR_DOWNLOAD_TIMEOUT_MS: u64 = 30_000;
+pub const MAX_CHEATSHEET_RESULTS: usize = 50;
+pub const OLLAMA_HEALTH_INTERVAL_MS: u64 = 60_000;
+
+// Daily digests: how often the task checks whether the digest time has passed, and how many
+// distinct and failed commands per project go into the prompt
+pub const DAILY_DIGEST_CHECK_INTERVAL_MS: u64 = 60_000;
+pub const DAILY_DIGEST_COMMANDS_PER_PROJECT: usize = 25;
+pub const DAILY_DIGEST_FAILURES_PER_PROJECT: usize = 10;
+
+// Crash recovery: how often session state is written to disk, and where
+pub const STATE_SNAPSHOT_INTERVAL_MS: u64 = 30_000;
+pub const STATE_SNAPSHOT_FILE: &str = "session_snapshot.json";
+
+// How long the pre-start check for a dev server's port waits for a localhost connection
+pub const PORT_CHECK_TIMEOUT_MS: u64 = 150;
+
+// hash_file reads in chunks of this size and reports hash_progress for files of at least
+// HASH_PROGRESS_MIN_BYTES, at most once per interval
+pub const HASH_BUFFER_BYTES: usize = 256 * 1024;
+pub const HASH_PROGRESS_MIN_BYTES: u64 = 16 * 1024 * 1024;
+pub const HASH_PROGRESS_INTERVAL_MS: u64 = 200;
+
+// File operations (fs_copy, fs_move, fs_delete): copy buffer size and how often
+// file_operation_progress is emitted
+pub const FS_COPY_BUFFER_BYTES: usize = 1024 * 1024;
+pub const FS_PROGRESS_INTERVAL_MS: u64 = 200;
+
+// Outputs larger than this are not checked for JSON/YAML when a command finishes
+pub const MAX_STRUCTURED_OUTPUT_BYTES: usize = 8 * 1024 * 1024;
+
+// /http special command: default request timeout and how much of the body is kept
+pub const HTTP_COMMAND_TIMEOUT_SECS: u64 = 30;
+pub const MAX_HTTP_COMMAND_BODY_BYTES: usize = 1024 * 1024;
+
+// dns_lookup and inspect_tls give dig/openssl this long before killing them
+pub const DNS_LOOKUP_TIMEOUT_MS: u64 = 10_000;
+pub const TLS_INSPECT_TIMEOUT_MS: u64 = 15_000;
+// inspect_tls flags a leaf certificate expiring within this many days
+pub const TLS_EXPIRY_WARNING_DAYS: i64 = 14;
+
+// Files larger than this are fingerprinted by size and mtime instead of content when
+// working out what an agent command changed
+pub const AGENT_CHANGE_HASH_MAX_BYTES: u64 = 16 * 1024 * 1024;
+
+// Dirty and untracked files, and the files an agent command names, are copied aside before it
+// runs so undo_agent_run can restore them; a command touching larger files is reported as not
+// undoable, and only the most recent runs' backups are kept
+pub const AGENT_BACKUP_MAX_FILE_BYTES: u64 = 32 * 1024 * 1024;
+// A named directory holding more than this (e.g. `rm -rf target`) is not backed up at all
+pub const AGENT_BACKUP_MAX_NAMED_FILES: usize = 2_000;
+pub const AGENT_BACKUP_MAX_NAMED_BYTES: u64 = 256 * 1024 * 1024;
+pub const AGENT_RUN_BACKUPS_KEPT: usize = 50;
+
+// Sessions the agent can hold open at once per conversation (open_agent_session), and the
+// output tail read_agent_session_output returns by default
+pub const AGENT_SESSIONS_MAX: usize = 8;
+pub const AGENT_SESSION_OUTPUT_LINES: usize = 200;
+
+// Tokens of the context window kept free for the model's answer when fitting ask_ai context,
+// and how many earlier commands the history piece lists
+pub const AI_RESPONSE_TOKEN_RESERVE: usize = 1024;
+pub const AI_CONTEXT_HISTORY_COMMANDS: usize = 20;
@@ -0,0 +1,130 @@
+use crate::command::core::pty::spawn_pty_session;
+use crate::command::types::pty_manager::{PtyManager, SessionTarget};
+use crate::settings::types::settings_manager::SettingsManager;
+use crate::utils::env_scrubbing::scrubbed_environment;
+use portable_pty::CommandBuilder;
+use serde::{Deserialize, Serialize};
+use std::process::Command;
+use tauri::{command, AppHandle, Manager, Runtime, State};
+
+// Starts bash when the image has it, otherwise the POSIX shell every image ships
+pub const CONTAINER_SHELL: &str = "command -v bash >/dev/null 2>&1 && exec bash || exec sh";
+
+#[derive(Serialize, Clone, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct ContainerInfo {
+    pub id: String,
+    pub name: String,
+    pub image: String,
+    pub status: String,
+}
+
+// One line of `docker ps --format '{{json .}}'`
+#[derive(Deserialize)]
+#[serde(rename_all = "PascalCase")]
+struct DockerPsLine {
+    #[serde(rename = "ID")]
+    id: String,
+    names: String,
+    image: String,
+    status: String,
+}
+
+fn run_docker(args: &[&str]) -> Result<String, String> {
+    let output = Command::new("docker")
+        .args(args)
+        .output()
+        .map_err(|e| format!("Failed to run docker: {}", e))?;
+    if !output.status.success() {
+        return Err(String::from_utf8_lossy(&output.stderr).trim().to_string());
+    }
+    Ok(String::from_utf8_lossy(&output.stdout).to_string())
+}
+
+fn container_os(container_id: &str) -> Option<String> {
+    let os_release = run_docker(&["exec", container_id, "cat", "/etc/os-release"]).ok()?;
+    os_release.lines().find_map(|line| {
+        line.strip_prefix("PRETTY_NAME=")
+            .map(|name| name.trim_matches('"').to_string())
+    })
+}
+
+// Running containers, as shown by `docker ps`
+#[command]
+pub fn list_containers() -> Result<Vec<ContainerInfo>, String> {
+    let output = run_docker(&["ps", "--format", "{{json .}}"])?;
+    Ok(output
+        .lines()
+        .filter_map(|line| serde_json::from_str::<DockerPsLine>(line).ok())
+        .map(|line| ContainerInfo {
+            id: line.id,
+            name: line.names,
+            image: line.image,
+            status: line.status,
+        })
+        .collect())
+}
+
+// Opens a PTY session running an interactive shell inside a running container
+#[command]
+pub fn create_container_session<R: Runtime>(
+    session_id: String,
+    container_id: String,
+    cols: u16,
+    rows: u16,
+    app_handle: AppHandle<R>,
+    pty_manager: State<'_, PtyManager>,
+) -> Result<(), String> {
+    let inspect = run_docker(&[
+        "inspect",
+        "--format",
+        "{{.Id}}\t{{.Name}}\t{{.Config.Image}}\t{{.State.Running}}",
+        &container_id,
+    ])?;
+    let fields: Vec<&str> = inspect.trim().split('\t').collect();
+    let [id, name, image, running] = fields[..] else {
+        return Err(format!(
+            "Unexpected docker inspect output for '{}'",
+            container_id
+        ));
+    };
+    if running != "true" {
+        return Err(format!("Container '{}' is not running", container_id));
+    }
+    let id = id.chars().take(12).collect::<String>();
+
+    let mut command = CommandBuilder::new("docker");
+    command.env_clear();
+    for (name, value) in scrubbed_environment(&app_handle.state::<SettingsManager>().current()) {
+        command.env(name, value);
+    }
+    // -it gives the exec its own TTY; TERM is passed through so colours and line editing work
+    command.args([
+        "exec",
+        "-it",
+        "-e",
+        "TERM=xterm-256color",
+        "-e",
+        "COLORTERM=truecolor",
+        &id,
+        "sh",
+        "-c",
+        CONTAINER_SHELL,
+    ]);
+
+    let target = SessionTarget::Container {
+        os: container_os(&id),
+        id,
+        name: name.trim_start_matches('/').to_string(),
+        image: image.to_string(),
+    };
+    spawn_pty_session(
+        &app_handle,
+        &pty_manager,
+        session_id,
+        cols,
+        rows,
+        command,
+        Some(target),
+    )
+}
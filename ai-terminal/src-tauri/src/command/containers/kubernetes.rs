@@ -0,0 +1,240 @@
+use crate::command::constants::{K8S_RECONNECT_ATTEMPTS, K8S_RECONNECT_INTERVAL_MS};
+use crate::command::containers::docker::CONTAINER_SHELL;
+use crate::command::core::pty::spawn_pty_session;
+use crate::command::types::pty_manager::{PtyManager, SessionTarget};
+use crate::command::windows::session_windows::emit_session_event;
+use crate::settings::types::settings_manager::SettingsManager;
+use crate::utils::env_scrubbing::scrubbed_environment;
+use portable_pty::{CommandBuilder, PtySize};
+use serde::Serialize;
+use std::process::Command;
+use std::thread;
+use std::time::Duration;
+use tauri::{command, AppHandle, Manager, Runtime, State};
+
+#[derive(Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct PtyReconnectEvent {
+    pub session_id: String,
+    pub attempt: u32,
+    pub max_attempts: u32,
+}
+
+// Phase of the pod plus restart count and readiness of the exec'd container
+struct PodStatus {
+    phase: String,
+    restart_count: u32,
+    ready: bool,
+}
+
+fn kubectl_base_args(context: Option<&str>, namespace: &str) -> Vec<String> {
+    let mut args = Vec::new();
+    if let Some(context) = context {
+        args.push("--context".to_string());
+        args.push(context.to_string());
+    }
+    args.push("--namespace".to_string());
+    args.push(namespace.to_string());
+    args
+}
+
+fn pod_status(
+    context: Option<&str>,
+    namespace: &str,
+    pod: &str,
+    container: Option<&str>,
+) -> Result<PodStatus, String> {
+    let output = Command::new("kubectl")
+        .args(kubectl_base_args(context, namespace))
+        .args([
+            "get",
+            "pod",
+            pod,
+            "--output",
+            "jsonpath={.status.phase}|{range .status.containerStatuses[*]}{.name}={.restartCount}={.ready};{end}",
+        ])
+        .output()
+        .map_err(|e| format!("Failed to run kubectl: {}", e))?;
+    if !output.status.success() {
+        return Err(String::from_utf8_lossy(&output.stderr).trim().to_string());
+    }
+
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let (phase, statuses) = stdout.trim().split_once('|').unwrap_or((stdout.trim(), ""));
+    // Without an explicit container kubectl exec picks the first one, so match that
+    let status = statuses
+        .split(';')
+        .filter_map(|status| {
+            let mut fields = status.split('=');
+            Some((fields.next()?, fields.next()?, fields.next()?))
+        })
+        .find(|(name, _, _)| container.is_none_or(|container| container == *name))
+        .ok_or_else(|| format!("No matching container in pod '{}'", pod))?;
+
+    Ok(PodStatus {
+        phase: phase.to_string(),
+        restart_count: status.1.parse().unwrap_or(0),
+        ready: status.2 == "true",
+    })
+}
+
+fn kubectl_exec_command<R: Runtime>(
+    app_handle: &AppHandle<R>,
+    target: &SessionTarget,
+) -> CommandBuilder {
+    let mut command = CommandBuilder::new("kubectl");
+    command.env_clear();
+    for (name, value) in scrubbed_environment(&app_handle.state::<SettingsManager>().current()) {
+        command.env(name, value);
+    }
+    command.env("TERM", "xterm-256color");
+    if let SessionTarget::Kubernetes {
+        context,
+        namespace,
+        pod,
+        container,
+        ..
+    } = target
+    {
+        command.args(kubectl_base_args(context.as_deref(), namespace));
+        command.args(["exec", "-it", pod.as_str()]);
+        if let Some(container) = container {
+            command.args(["--container", container.as_str()]);
+        }
+        command.args(["--", "sh", "-c", CONTAINER_SHELL]);
+    }
+    command
+}
+
+// Called when a Kubernetes exec session ends on its own. If the container restarted (or is
+// restarting) it waits for it to become ready and re-attaches under the same session id,
+// returning true; otherwise the session is over and the caller reports pty_exit.
+pub fn reconnect_kubernetes_session<R: Runtime>(
+    app_handle: &AppHandle<R>,
+    session_id: &str,
+    target: SessionTarget,
+    size: Option<PtySize>,
+) -> bool {
+    let SessionTarget::Kubernetes {
+        context,
+        namespace,
+        pod,
+        container,
+        restart_count,
+    } = target
+    else {
+        return false;
+    };
+
+    // A plain `exit` leaves the container running with the same restart count
+    let restarted = match pod_status(context.as_deref(), &namespace, &pod, container.as_deref()) {
+        Ok(status) => {
+            status.restart_count != restart_count || status.phase != "Running" || !status.ready
+        }
+        Err(_) => true,
+    };
+    if !restarted {
+        return false;
+    }
+
+    for attempt in 1..=K8S_RECONNECT_ATTEMPTS {
+        emit_session_event(
+            app_handle,
+            session_id,
+            "pty_reconnecting",
+            PtyReconnectEvent {
+                session_id: session_id.to_string(),
+                attempt,
+                max_attempts: K8S_RECONNECT_ATTEMPTS,
+            },
+        );
+        thread::sleep(Duration::from_millis(K8S_RECONNECT_INTERVAL_MS));
+
+        // The user closed the tab or opened something else under this id meanwhile
+        let pty_manager = app_handle.state::<PtyManager>();
+        if pty_manager
+            .sessions
+            .lock()
+            .map(|sessions| sessions.contains_key(session_id))
+            .unwrap_or(true)
+        {
+            return false;
+        }
+
+        let Ok(status) = pod_status(context.as_deref(), &namespace, &pod, container.as_deref())
+        else {
+            continue;
+        };
+        if status.phase != "Running" || !status.ready {
+            continue;
+        }
+
+        let target = SessionTarget::Kubernetes {
+            context: context.clone(),
+            namespace: namespace.clone(),
+            pod: pod.clone(),
+            container: container.clone(),
+            restart_count: status.restart_count,
+        };
+        let size = size.unwrap_or_default();
+        let command = kubectl_exec_command(app_handle, &target);
+        if spawn_pty_session(
+            app_handle,
+            &pty_manager,
+            session_id.to_string(),
+            size.cols,
+            size.rows,
+            command,
+            Some(target),
+        )
+        .is_ok()
+        {
+            emit_session_event(
+                app_handle,
+                session_id,
+                "pty_reconnected",
+                session_id.to_string(),
+            );
+            return true;
+        }
+    }
+    false
+}
+
+// Opens a PTY session running an interactive shell inside a pod via `kubectl exec -it`
+#[command]
+#[allow(clippy::too_many_arguments)]
+pub fn create_k8s_session<R: Runtime>(
+    session_id: String,
+    context: Option<String>,
+    namespace: String,
+    pod: String,
+    container: Option<String>,
+    cols: u16,
+    rows: u16,
+    app_handle: AppHandle<R>,
+    pty_manager: State<'_, PtyManager>,
+) -> Result<(), String> {
+    let status = pod_status(context.as_deref(), &namespace, &pod, container.as_deref())?;
+    if status.phase != "Running" {
+        return Err(format!("Pod '{}' is {}", pod, status.phase));
+    }
+
+    let target = SessionTarget::Kubernetes {
+        context,
+        namespace,
+        pod,
+        container,
+        restart_count: status.restart_count,
+    };
+    let command = kubectl_exec_command(&app_handle, &target);
+    spawn_pty_session(
+        &app_handle,
+        &pty_manager,
+        session_id,
+        cols,
+        rows,
+        command,
+        Some(target),
+    )
+}
@@ -0,0 +1,3 @@
+pub mod docker;
+pub mod kubernetes;
+pub mod wsl;
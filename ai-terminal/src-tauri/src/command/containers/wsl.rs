@@ -0,0 +1,176 @@
+use crate::command::types::pty_manager::PtyManager;
+use serde::Serialize;
+use tauri::{command, AppHandle, Runtime, State};
+
+#[derive(Serialize, Clone, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct WslDistro {
+    pub name: String,
+    pub is_default: bool,
+}
+
+const WSL_UNC_PREFIXES: &[&str] = &[r"\\wsl.localhost\", r"\\wsl$\"];
+
+// `C:\Users\me` -> `/mnt/c/Users/me`, `\\wsl.localhost\Ubuntu\home\me` -> `/home/me`.
+// Paths that are already Linux paths are returned unchanged.
+pub fn windows_to_wsl_path(path: &str) -> String {
+    for prefix in WSL_UNC_PREFIXES {
+        if path
+            .get(..prefix.len())
+            .is_some_and(|head| head.eq_ignore_ascii_case(prefix))
+        {
+            let rest = &path[prefix.len()..];
+            // Drop the distro name; what follows is the path inside it
+            let inner = rest.split_once('\\').map(|(_, inner)| inner).unwrap_or("");
+            return format!("/{}", inner.replace('\\', "/"));
+        }
+    }
+
+    let bytes = path.as_bytes();
+    if bytes.len() >= 2 && bytes[0].is_ascii_alphabetic() && bytes[1] == b':' {
+        let drive = (bytes[0] as char).to_ascii_lowercase();
+        let rest = path[2..].replace('\\', "/");
+        let rest = rest.trim_start_matches('/');
+        return if rest.is_empty() {
+            format!("/mnt/{}", drive)
+        } else {
+            format!("/mnt/{}/{}", drive, rest)
+        };
+    }
+    path.replace('\\', "/")
+}
+
+// `/mnt/c/Users/me` -> `C:\Users\me`; anything outside /mnt/<drive> is reached through the
+// distro's `\\wsl.localhost\<distro>` share.
+pub fn wsl_to_windows_path(path: &str, distro: &str) -> String {
+    if let Some(rest) = path.strip_prefix("/mnt/") {
+        let (drive, inner) = rest.split_once('/').unwrap_or((rest, ""));
+        if drive.len() == 1 && drive.as_bytes()[0].is_ascii_alphabetic() {
+            return format!(
+                r"{}:\{}",
+                drive.to_ascii_uppercase(),
+                inner.replace('/', "\\")
+            );
+        }
+    }
+    format!(r"\\wsl.localhost\{}{}", distro, path.replace('/', "\\"))
+}
+
+// Translates a dropped or typed path between the Windows and WSL views of the filesystem
+#[command]
+pub fn translate_wsl_path(path: String, distro: String, to_linux: bool) -> String {
+    if to_linux {
+        windows_to_wsl_path(&path)
+    } else {
+        wsl_to_windows_path(&path, &distro)
+    }
+}
+
+#[cfg(target_os = "windows")]
+mod platform {
+    use super::{windows_to_wsl_path, WslDistro};
+    use crate::command::core::pty::spawn_pty_session;
+    use crate::command::types::pty_manager::{PtyManager, SessionTarget};
+    use portable_pty::CommandBuilder;
+    use std::process::Command;
+    use tauri::AppHandle;
+
+    // wsl.exe writes its listings as UTF-16LE
+    fn decode_wsl_output(bytes: &[u8]) -> String {
+        let units: Vec<u16> = bytes
+            .chunks_exact(2)
+            .map(|pair| u16::from_le_bytes([pair[0], pair[1]]))
+            .collect();
+        String::from_utf16_lossy(&units).replace('\0', "")
+    }
+
+    pub fn list_distros() -> Result<Vec<WslDistro>, String> {
+        let output = Command::new("wsl.exe")
+            .args(["--list", "--verbose"])
+            .output()
+            .map_err(|e| format!("Failed to run wsl.exe: {}", e))?;
+        if !output.status.success() {
+            return Err(decode_wsl_output(&output.stderr).trim().to_string());
+        }
+
+        // "  NAME      STATE      VERSION" followed by one row per distro; `*` marks the default
+        Ok(decode_wsl_output(&output.stdout)
+            .lines()
+            .skip(1)
+            .filter_map(|line| {
+                let line = line.trim();
+                let is_default = line.starts_with('*');
+                let name = line.trim_start_matches('*').split_whitespace().next()?;
+                Some(WslDistro {
+                    name: name.to_string(),
+                    is_default,
+                })
+            })
+            .collect())
+    }
+
+    pub fn create_session<R: Runtime>(
+        app_handle: &AppHandle<R>,
+        pty_manager: &PtyManager,
+        session_id: String,
+        distro: String,
+        cwd: Option<String>,
+        cols: u16,
+        rows: u16,
+    ) -> Result<(), String> {
+        let mut command = CommandBuilder::new("wsl.exe");
+        command.args(["--distribution", distro.as_str()]);
+        let start_dir = cwd
+            .map(|cwd| windows_to_wsl_path(&cwd))
+            .unwrap_or_else(|| "~".to_string());
+        command.args(["--cd", start_dir.as_str()]);
+        command.env("TERM", "xterm-256color");
+        spawn_pty_session(
+            app_handle,
+            pty_manager,
+            session_id,
+            cols,
+            rows,
+            command,
+            Some(SessionTarget::Wsl { distro }),
+        )
+    }
+}
+
+#[command]
+pub fn list_wsl_distros() -> Result<Vec<WslDistro>, String> {
+    #[cfg(target_os = "windows")]
+    return platform::list_distros();
+
+    #[cfg(not(target_os = "windows"))]
+    Err("WSL is only available on Windows".to_string())
+}
+
+// Opens a PTY session inside a WSL distribution, starting in `cwd` (a Windows or Linux path)
+#[command]
+pub fn create_wsl_session<R: Runtime>(
+    session_id: String,
+    distro: String,
+    cwd: Option<String>,
+    cols: u16,
+    rows: u16,
+    app_handle: AppHandle<R>,
+    pty_manager: State<'_, PtyManager>,
+) -> Result<(), String> {
+    #[cfg(target_os = "windows")]
+    return platform::create_session(
+        &app_handle,
+        &pty_manager,
+        session_id,
+        distro,
+        cwd,
+        cols,
+        rows,
+    );
+
+    #[cfg(not(target_os = "windows"))]
+    {
+        let _ = (session_id, distro, cwd, cols, rows, app_handle, pty_manager);
+        Err("WSL is only available on Windows".to_string())
+    }
+}
@@ -0,0 +1,207 @@
+use crate::command::types::command_manager::CommandManager;
+use crate::command::types::pty_manager::{PtyManager, SessionTarget};
+use crate::utils::config_utils::unix_timestamp_millis;
+use serde::Serialize;
+use std::process::Command;
+use tauri::{command, State};
+
+#[derive(Serialize, Clone, Copy, Debug, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum ActiveCommandKind {
+    // Started by execute_command (or a retry), output on command_output
+    Command,
+    // A persistent SSH session commands are forwarded to
+    Ssh,
+    // Foreground process of a PTY session
+    Pty,
+}
+
+#[derive(Serialize, Clone, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct ActiveCommand {
+    pub session_id: String,
+    pub kind: ActiveCommandKind,
+    // Process group leader; kill_active_command signals the whole group
+    pub pid: u32,
+    pub command: String,
+    // Unix milliseconds
+    pub started_at: Option<u64>,
+    pub duration_ms: Option<u64>,
+}
+
+// ps etime: [[dd-]hh:]mm:ss
+fn parse_elapsed(etime: &str) -> Option<u64> {
+    let (days, clock) = match etime.split_once('-') {
+        Some((days, clock)) => (days.parse::<u64>().ok()?, clock),
+        None => (0, etime),
+    };
+    let seconds = clock.split(':').try_fold(0u64, |total, part| {
+        Some(total * 60 + part.parse::<u64>().ok()?)
+    })?;
+    Some((days * 86_400 + seconds) * 1000)
+}
+
+// Command line and start time of a process, from ps
+fn process_info(pid: u32) -> Option<(String, u64)> {
+    if cfg!(windows) {
+        return None;
+    }
+    let output = Command::new("ps")
+        .args(["-o", "etime=,args=", "-p", &pid.to_string()])
+        .output()
+        .ok()?;
+    let text = String::from_utf8_lossy(&output.stdout);
+    let (etime, args) = text.trim().split_once(char::is_whitespace)?;
+    let elapsed = parse_elapsed(etime)?;
+    Some((
+        args.trim().to_string(),
+        unix_timestamp_millis().saturating_sub(elapsed),
+    ))
+}
+
+// A running process found under a session lock; ps is only consulted once the lock is released
+struct Candidate {
+    session_id: String,
+    kind: ActiveCommandKind,
+    pid: u32,
+    // Command line and start time, when the app started the process itself
+    known: Option<(String, u64)>,
+}
+
+impl Candidate {
+    fn into_active_command(self) -> ActiveCommand {
+        let (command, started_at) = match self.known.or_else(|| process_info(self.pid)) {
+            Some((command, started_at)) => (command, Some(started_at)),
+            None => (String::new(), None),
+        };
+        ActiveCommand {
+            session_id: self.session_id,
+            kind: self.kind,
+            pid: self.pid,
+            command,
+            started_at,
+            duration_ms: started_at
+                .map(|started_at| unix_timestamp_millis().saturating_sub(started_at)),
+        }
+    }
+}
+
+pub fn collect_active_commands(
+    command_manager: &CommandManager,
+    pty_manager: &PtyManager,
+) -> Result<Vec<ActiveCommand>, String> {
+    let mut candidates: Vec<Candidate> = {
+        let states = command_manager.commands.lock().map_err(|e| e.to_string())?;
+        states
+            .iter()
+            .filter_map(|(session_id, state)| {
+                let pid = state.pid?;
+                Some(Candidate {
+                    session_id: session_id.clone(),
+                    kind: if state.is_ssh_session_active {
+                        ActiveCommandKind::Ssh
+                    } else {
+                        ActiveCommandKind::Command
+                    },
+                    pid,
+                    known: state
+                        .running
+                        .as_ref()
+                        .filter(|running| running.pid == pid)
+                        .map(|running| (running.command.clone(), running.started_at)),
+                })
+            })
+            .collect()
+    };
+
+    // PTYs count when their foreground is something other than the idle shell; REPL and
+    // moved-command sessions are their process
+    {
+        let sessions = pty_manager.sessions.lock().map_err(|e| e.to_string())?;
+        candidates.extend(sessions.iter().filter_map(|(session_id, session)| {
+            let child_pid = session.child.lock().ok()?.process_id()?;
+            let (pid, known) = match &session.target {
+                Some(SessionTarget::Command { command }) => {
+                    (child_pid, Some((command.clone(), session.started_at)))
+                }
+                Some(SessionTarget::Repl { .. }) => (child_pid, None),
+                _ => {
+                    #[cfg(unix)]
+                    let leader = session
+                        .master
+                        .process_group_leader()
+                        .and_then(|leader| u32::try_from(leader).ok());
+                    #[cfg(not(unix))]
+                    let leader: Option<u32> = None;
+                    (leader.filter(|leader| *leader != child_pid)?, None)
+                }
+            };
+            Some(Candidate {
+                session_id: session_id.clone(),
+                kind: ActiveCommandKind::Pty,
+                pid,
+                known,
+            })
+        }));
+    }
+
+    let mut active: Vec<ActiveCommand> = candidates
+        .into_iter()
+        .map(Candidate::into_active_command)
+        .collect();
+    active.sort_by_key(|command| command.started_at.unwrap_or(u64::MAX));
+    Ok(active)
+}
+
+#[cfg(unix)]
+pub fn signal_process_group(pid: u32, force: bool) -> Result<(), String> {
+    use nix::sys::signal::{kill, killpg, Signal};
+    use nix::unistd::Pid;
+
+    let signal = if force {
+        Signal::SIGKILL
+    } else {
+        Signal::SIGTERM
+    };
+    // Commands not started in their own process group only get the signal themselves
+    killpg(Pid::from_raw(pid as i32), signal)
+        .or_else(|_| kill(Pid::from_raw(pid as i32), signal))
+        .map_err(|e| format!("Failed to signal process {}: {}", pid, e))
+}
+
+#[cfg(not(unix))]
+pub fn signal_process_group(_pid: u32, _force: bool) -> Result<(), String> {
+    Err("Stopping processes from the activity view is only supported on Unix".to_string())
+}
+
+// Everything running in any session, oldest first, for the global activity view
+#[command]
+pub fn get_active_commands(
+    command_manager: State<'_, CommandManager>,
+    pty_manager: State<'_, PtyManager>,
+) -> Result<Vec<ActiveCommand>, String> {
+    collect_active_commands(&command_manager, &pty_manager)
+}
+
+// Sends SIGTERM (SIGKILL with `force`) to the process group of an entry from
+// get_active_commands. Only processes that are still listed can be signalled.
+#[command]
+pub fn kill_active_command(
+    session_id: String,
+    pid: u32,
+    force: Option<bool>,
+    command_manager: State<'_, CommandManager>,
+    pty_manager: State<'_, PtyManager>,
+) -> Result<(), String> {
+    let listed = collect_active_commands(&command_manager, &pty_manager)?
+        .iter()
+        .any(|active| active.session_id == session_id && active.pid == pid);
+    if !listed {
+        return Err(format!(
+            "Process {} is not running in session {}",
+            pid, session_id
+        ));
+    }
+
+    signal_process_group(pid, force.unwrap_or(false))
+}
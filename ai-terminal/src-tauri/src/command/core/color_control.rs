@@ -0,0 +1,106 @@
+use crate::command::core::command_words::{insert_program_flags, program_name, program_words};
+use crate::settings::types::app_settings::{AppSettings, ColorMode, ColorRule};
+use std::collections::HashMap;
+use std::path::Path;
+use std::process::Command;
+
+// Honored by BSD tools (CLICOLOR_FORCE), Node (FORCE_COLOR) and most CLI color libraries
+const COLOR_ENVIRONMENT: &[(&str, &str)] = &[
+    ("CLICOLOR", "1"),
+    ("CLICOLOR_FORCE", "1"),
+    ("FORCE_COLOR", "1"),
+];
+
+fn enabled_rule<'a>(settings: &'a AppSettings, program: &str) -> Option<&'a ColorRule> {
+    settings
+        .color_rules
+        .iter()
+        .find(|rule| rule.enabled && rule.program == program)
+}
+
+// Adds each matching color rule's flag after its program
+pub fn color_command(settings: &AppSettings, command: &str) -> String {
+    if settings.command_color == ColorMode::Off {
+        return command.to_string();
+    }
+    insert_program_flags(command, |program| {
+        enabled_rule(settings, program).and_then(|rule| rule.flag.as_deref())
+    })
+}
+
+// Applied to the environment of every command while color is forced. NO_COLOR would win over
+// the variables set here, so it is dropped.
+pub fn apply_color_environment(settings: &AppSettings, env_map: &mut HashMap<String, String>) {
+    if settings.command_color == ColorMode::Off {
+        return;
+    }
+    env_map.remove("NO_COLOR");
+    for (name, value) in COLOR_ENVIRONMENT {
+        env_map.insert(name.to_string(), value.to_string());
+    }
+    env_map
+        .entry("TERM".to_string())
+        .or_insert_with(|| "xterm-256color".to_string());
+}
+
+fn find_script_binary(path: &str) -> Option<String> {
+    std::env::split_paths(path)
+        .map(|dir| dir.join("script"))
+        .find(|candidate| Path::new(candidate).is_file())
+        .map(|candidate| candidate.to_string_lossy().to_string())
+}
+
+// In Pty mode, a `script` invocation that runs `sh -c shell_command` on a pseudo-terminal, for
+// commands whose program has a color rule. None runs the command on pipes as usual.
+pub fn color_pty_command(
+    settings: &AppSettings,
+    command: &str,
+    shell_command: &str,
+    env_map: &HashMap<String, String>,
+) -> Option<Command> {
+    if settings.command_color != ColorMode::Pty || cfg!(windows) {
+        return None;
+    }
+    let has_rule = program_words(command)
+        .into_iter()
+        .any(|word| enabled_rule(settings, program_name(&command[word])).is_some());
+    if !has_rule {
+        return None;
+    }
+    let script = find_script_binary(env_map.get("PATH")?)?;
+    let mut wrapper = Command::new(script);
+    if cfg!(target_os = "macos") {
+        // BSD script takes the command as arguments and passes its exit status through
+        wrapper.args(["-q", "/dev/null", "sh", "-c", shell_command]);
+    } else {
+        // util-linux script runs -c through $SHELL; -e keeps the child's exit status
+        wrapper.args([
+            "-q",
+            "-e",
+            "-f",
+            "-c",
+            &format!("exec sh -c '{}'", shell_command.replace('\'', "'\\''")),
+            "/dev/null",
+        ]);
+    }
+    Some(wrapper)
+}
+
+pub fn validate_color_rules(rules: &[ColorRule]) -> Result<(), String> {
+    for rule in rules {
+        if rule.program.is_empty() || rule.program.contains(char::is_whitespace) {
+            return Err(format!("Invalid color rule program '{}'", rule.program));
+        }
+        if rule
+            .flag
+            .as_deref()
+            .is_some_and(|flag| flag.trim().is_empty())
+        {
+            return Err(format!(
+                "Color rule for '{}' has an empty flag",
+                rule.program
+            ));
+        }
+    }
+    Ok(())
+}
@@ -0,0 +1,43 @@
+use crate::command::core::resource_usage::ResourceUsage;
+use crate::i18n::types::app_error::AppError;
+use serde::Serialize;
+use tauri::{AppHandle, Emitter, Runtime};
+
+// Payload of command_end. Builtins and failures before a process started only fill in the
+// message; commands that ran report their exit code, duration and resource usage.
+#[derive(Serialize, Clone, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct CommandEndEvent {
+    pub session_id: String,
+    pub success: bool,
+    pub message: String,
+    pub exit_code: Option<i32>,
+    pub duration_ms: Option<u64>,
+    pub resources: Option<ResourceUsage>,
+    // Set when the failure has a known cause the UI can react to
+    pub error: Option<AppError>,
+}
+
+impl CommandEndEvent {
+    pub fn new(session_id: &str, success: bool, message: impl Into<String>) -> Self {
+        Self {
+            session_id: session_id.to_string(),
+            success,
+            message: message.into(),
+            exit_code: None,
+            duration_ms: None,
+            resources: None,
+            error: None,
+        }
+    }
+
+    pub fn with_error(mut self, error: AppError) -> Self {
+        self.success = false;
+        self.error = Some(error);
+        self
+    }
+}
+
+pub fn emit_command_end<R: Runtime>(app_handle: &AppHandle<R>, event: CommandEndEvent) {
+    let _ = app_handle.emit("command_end", event);
+}
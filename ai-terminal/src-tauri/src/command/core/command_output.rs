@@ -0,0 +1,43 @@
+use crate::command::windows::session_windows::emit_session_event;
+use serde::Serialize;
+use tauri::{AppHandle, Runtime};
+
+pub const COMMAND_OUTPUT_EVENT: &str = "command_output";
+pub const COMMAND_ERROR_EVENT: &str = "command_error";
+
+// Payload of command_output (stdout) and command_error (stderr, or a failure to run)
+#[derive(Serialize, Clone, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct CommandOutputEvent {
+    pub session_id: String,
+    pub data: String,
+}
+
+fn emit_output<R: Runtime>(app_handle: &AppHandle<R>, session_id: &str, event: &str, data: String) {
+    emit_session_event(
+        app_handle,
+        session_id,
+        event,
+        CommandOutputEvent {
+            session_id: session_id.to_string(),
+            data,
+        },
+    );
+}
+
+// Sent to the window showing the session, like pty_output
+pub fn emit_command_output<R: Runtime>(
+    app_handle: &AppHandle<R>,
+    session_id: &str,
+    data: impl Into<String>,
+) {
+    emit_output(app_handle, session_id, COMMAND_OUTPUT_EVENT, data.into());
+}
+
+pub fn emit_command_error<R: Runtime>(
+    app_handle: &AppHandle<R>,
+    session_id: &str,
+    data: impl Into<String>,
+) {
+    emit_output(app_handle, session_id, COMMAND_ERROR_EVENT, data.into());
+}
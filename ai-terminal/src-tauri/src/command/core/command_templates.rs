@@ -0,0 +1,267 @@
+use crate::command::git_commands::git::read_git_branch;
+use crate::command::types::command_manager::CommandManager;
+use std::collections::BTreeMap;
+use std::process::Command;
+use tauri::{command, State};
+
+// Prefix for session variables set with set_var, e.g. {{var:host}}
+pub const SESSION_VARIABLE_PREFIX: &str = "var:";
+// Prefix for outputs stored with capture_output, e.g. {{capture:version}}
+pub const CAPTURE_PREFIX: &str = "capture:";
+
+// Placeholders expanded before a command runs. Anything else between {{ }} is left alone so
+// tools with their own templates (docker --format '{{.Names}}', helm, ...) keep working.
+pub const TEMPLATE_VARIABLES: &[&str] = &[
+    "cwd",
+    "git_branch",
+    "date",
+    "time",
+    "datetime",
+    "timestamp",
+    "clipboard",
+    "home",
+    "user",
+];
+
+// Session context the variables are resolved against
+pub struct TemplateContext {
+    pub cwd: String,
+    pub is_remote: bool,
+    pub variables: BTreeMap<String, String>,
+    pub captures: BTreeMap<String, String>,
+}
+
+fn read_clipboard() -> Result<String, String> {
+    let candidates: &[(&str, &[&str])] = if cfg!(target_os = "macos") {
+        &[("pbpaste", &[])]
+    } else if cfg!(target_os = "windows") {
+        &[("powershell", &["-NoProfile", "-Command", "Get-Clipboard"])]
+    } else {
+        &[
+            ("wl-paste", &["--no-newline"]),
+            ("xclip", &["-selection", "clipboard", "-o"]),
+            ("xsel", &["--clipboard", "--output"]),
+        ]
+    };
+    for (program, args) in candidates {
+        if let Ok(output) = Command::new(program).args(*args).output() {
+            if output.status.success() {
+                return Ok(String::from_utf8_lossy(&output.stdout)
+                    .trim_end_matches(['\r', '\n'])
+                    .to_string());
+            }
+        }
+    }
+    Err("Could not read the clipboard".to_string())
+}
+
+fn resolve_variable(name: &str, context: &TemplateContext) -> Result<String, String> {
+    if let Some(variable) = name.strip_prefix(SESSION_VARIABLE_PREFIX) {
+        return context
+            .variables
+            .get(variable.trim())
+            .cloned()
+            .ok_or_else(|| format!("Session variable '{}' is not set", variable.trim()));
+    }
+    if let Some(capture) = name.strip_prefix(CAPTURE_PREFIX) {
+        return context
+            .captures
+            .get(capture.trim())
+            .cloned()
+            .ok_or_else(|| format!("No output captured as '{}'", capture.trim()));
+    }
+    let now = chrono::Local::now();
+    match name {
+        "cwd" => Ok(context.cwd.clone()),
+        "git_branch" if context.is_remote => {
+            Err("{{git_branch}} is not available in an SSH session".to_string())
+        }
+        "git_branch" => read_git_branch(&context.cwd)?
+            .ok_or_else(|| format!("{} is not inside a git repository", context.cwd)),
+        "date" => Ok(now.format("%Y-%m-%d").to_string()),
+        "time" => Ok(now.format("%H:%M:%S").to_string()),
+        "datetime" => Ok(now.format("%Y-%m-%dT%H-%M-%S").to_string()),
+        "timestamp" => Ok(now.timestamp().to_string()),
+        "clipboard" => read_clipboard(),
+        "home" => dirs::home_dir()
+            .map(|home| home.to_string_lossy().to_string())
+            .ok_or_else(|| "Could not determine home directory".to_string()),
+        "user" => std::env::var("USER")
+            .or_else(|_| std::env::var("USERNAME"))
+            .map_err(|_| "Could not determine the current user".to_string()),
+        other => Err(format!("Unknown template variable: {}", other)),
+    }
+}
+
+#[derive(Clone, Copy, PartialEq)]
+enum QuoteState {
+    Unquoted,
+    Single,
+    Double,
+}
+
+// Tracks which shell quoting context the end of `text` is in
+fn advance_quote_state(mut state: QuoteState, text: &str) -> QuoteState {
+    let mut escaped = false;
+    for c in text.chars() {
+        state = match (state, c) {
+            (QuoteState::Single, '\'') => QuoteState::Unquoted,
+            (QuoteState::Single, _) => QuoteState::Single,
+            (_, _) if escaped => {
+                escaped = false;
+                state
+            }
+            (_, '\\') => {
+                escaped = true;
+                state
+            }
+            (QuoteState::Unquoted, '\'') => QuoteState::Single,
+            (QuoteState::Unquoted, '"') => QuoteState::Double,
+            (QuoteState::Double, '"') => QuoteState::Unquoted,
+            (state, _) => state,
+        };
+    }
+    state
+}
+
+// Escapes a value for the quoting context it is inserted into, so e.g. clipboard contents
+// cannot inject shell syntax
+fn shell_escape(value: &str, state: QuoteState) -> String {
+    match state {
+        QuoteState::Single => value.replace('\'', "'\\''"),
+        QuoteState::Double => {
+            let mut escaped = String::with_capacity(value.len());
+            for c in value.chars() {
+                if matches!(c, '"' | '\\' | '$' | '`') {
+                    escaped.push('\\');
+                }
+                escaped.push(c);
+            }
+            escaped
+        }
+        QuoteState::Unquoted => {
+            let is_safe = !value.is_empty()
+                && value
+                    .chars()
+                    .all(|c| c.is_alphanumeric() || "_@%+=:,./-~".contains(c));
+            if is_safe {
+                value.to_string()
+            } else {
+                format!("'{}'", value.replace('\'', "'\\''"))
+            }
+        }
+    }
+}
+
+// Replaces {{name}} with its value, escaped for where it appears; `\{{name}}` produces a
+// literal {{name}}
+pub fn expand_templates(input: &str, context: &TemplateContext) -> Result<String, String> {
+    let mut output = String::with_capacity(input.len());
+    let mut quote_state = QuoteState::Unquoted;
+    let mut rest = input;
+    while let Some(start) = rest.find("{{") {
+        let Some(length) = rest[start..].find("}}") else {
+            break;
+        };
+        let name = rest[start + 2..start + length].trim();
+        let end = start + length + 2;
+        let is_variable = TEMPLATE_VARIABLES.contains(&name)
+            || name.starts_with(SESSION_VARIABLE_PREFIX)
+            || name.starts_with(CAPTURE_PREFIX);
+        if !is_variable {
+            output.push_str(&rest[..end]);
+            quote_state = advance_quote_state(quote_state, &rest[..end]);
+        } else if rest[..start].ends_with('\\') {
+            output.push_str(&rest[..start - 1]);
+            output.push_str(&rest[start..end]);
+            quote_state = advance_quote_state(quote_state, &rest[..start - 1]);
+        } else {
+            output.push_str(&rest[..start]);
+            quote_state = advance_quote_state(quote_state, &rest[..start]);
+            output.push_str(&shell_escape(
+                &resolve_variable(name, context)?,
+                quote_state,
+            ));
+        }
+        rest = &rest[end..];
+    }
+    output.push_str(rest);
+    Ok(output)
+}
+
+// Inserts captured outputs into an AI question as plain text; unknown names are left as typed
+pub fn expand_prompt_captures(input: &str, captures: &BTreeMap<String, String>) -> String {
+    let mut output = String::with_capacity(input.len());
+    let mut rest = input;
+    while let Some(start) = rest.find("{{") {
+        let Some(length) = rest[start..].find("}}") else {
+            break;
+        };
+        let end = start + length + 2;
+        let value = rest[start + 2..start + length]
+            .trim()
+            .strip_prefix(CAPTURE_PREFIX)
+            .and_then(|name| captures.get(name.trim()));
+        output.push_str(&rest[..start]);
+        match value {
+            Some(value) => output.push_str(value),
+            None => output.push_str(&rest[start..end]),
+        }
+        rest = &rest[end..];
+    }
+    output.push_str(rest);
+    output
+}
+
+pub fn session_template_context(
+    command_manager: &CommandManager,
+    session_id: &str,
+) -> Result<TemplateContext, String> {
+    let states = command_manager.commands.lock().map_err(|e| e.to_string())?;
+    Ok(match states.get(session_id) {
+        Some(state) if state.is_ssh_session_active => TemplateContext {
+            cwd: state
+                .remote_current_dir
+                .clone()
+                .unwrap_or_else(|| "~".to_string()),
+            is_remote: true,
+            variables: state.variables.clone(),
+            captures: state.captures.clone(),
+        },
+        Some(state) => TemplateContext {
+            cwd: state.current_dir.clone(),
+            is_remote: false,
+            variables: state.variables.clone(),
+            captures: state.captures.clone(),
+        },
+        None => TemplateContext {
+            cwd: std::env::current_dir()
+                .unwrap_or_default()
+                .to_string_lossy()
+                .to_string(),
+            is_remote: false,
+            variables: BTreeMap::new(),
+            captures: BTreeMap::new(),
+        },
+    })
+}
+
+// Expands a command the way execute_command would, so the UI can preview it
+#[command]
+pub fn expand_command_template(
+    command: String,
+    session_id: String,
+    command_manager: State<'_, CommandManager>,
+) -> Result<String, String> {
+    let context = session_template_context(&command_manager, &session_id)?;
+    expand_templates(&command, &context)
+}
+
+// Names of the supported variables, for autocompletion in the input
+#[command]
+pub fn list_template_variables() -> Vec<String> {
+    TEMPLATE_VARIABLES
+        .iter()
+        .map(|name| name.to_string())
+        .collect()
+}
@@ -0,0 +1,79 @@
+use crate::command::core::cost_warnings::is_command_prefix;
+use std::ops::Range;
+
+// Byte range of the program word in each `;`, `&&`, `||`, `|` or newline separated part of the
+// command, after sudo/env/time and VAR=value prefixes. Separators inside quotes do not count.
+pub fn program_words(command: &str) -> Vec<Range<usize>> {
+    let mut words = Vec::new();
+    let mut quote: Option<char> = None;
+    let mut escaped = false;
+    let mut word_start: Option<usize> = None;
+    let mut expecting_program = true;
+    let mut previous = ' ';
+    for (index, c) in command.char_indices().chain([(command.len(), '\n')]) {
+        let before = std::mem::replace(&mut previous, c);
+        if escaped {
+            escaped = false;
+            continue;
+        }
+        if let Some(open) = quote {
+            if c == open {
+                quote = None;
+            } else if c == '\\' && open == '"' {
+                escaped = true;
+            }
+            continue;
+        }
+        // `2>&1` and `<&3` redirect, they do not start a new command
+        let is_separator =
+            matches!(c, ';' | '|' | '\n') || (c == '&' && !matches!(before, '>' | '<'));
+        if c.is_whitespace() || is_separator {
+            if let Some(start) = word_start.take() {
+                if expecting_program && !is_command_prefix(&command[start..index]) {
+                    words.push(start..index);
+                    expecting_program = false;
+                }
+            }
+            if is_separator {
+                expecting_program = true;
+            }
+            continue;
+        }
+        word_start.get_or_insert(index);
+        match c {
+            '\\' => escaped = true,
+            '\'' | '"' => quote = Some(c),
+            _ => {}
+        }
+    }
+    words
+}
+
+// `/usr/bin/git` and `git` are the same program for per-program rules
+pub fn program_name(word: &str) -> &str {
+    word.rsplit('/').next().unwrap_or(word)
+}
+
+// Inserts the flag `flag_for` returns for a program right after it, unless the flag is
+// already there
+pub fn insert_program_flags<'a>(
+    command: &str,
+    flag_for: impl Fn(&str) -> Option<&'a str>,
+) -> String {
+    let mut rewritten = String::with_capacity(command.len());
+    let mut copied = 0;
+    for word in program_words(command) {
+        let Some(flag) = flag_for(program_name(&command[word.clone()])) else {
+            continue;
+        };
+        if command[word.end..].trim_start().starts_with(flag) {
+            continue;
+        }
+        rewritten.push_str(&command[copied..word.end]);
+        rewritten.push(' ');
+        rewritten.push_str(flag);
+        copied = word.end;
+    }
+    rewritten.push_str(&command[copied..]);
+    rewritten
+}
@@ -0,0 +1,138 @@
+use crate::command::types::confirmation_manager::{ConfirmationManager, ConfirmationRequest};
+use crate::settings::types::app_settings::{AppSettings, CostRule};
+use crate::settings::types::settings_manager::SettingsManager;
+use regex::Regex;
+use serde::Serialize;
+use tauri::{command, AppHandle, Emitter, Manager, Runtime, State};
+
+pub const COST_WARNING_EVENT: &str = "cost_warning";
+
+#[derive(Serialize, Clone, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct CostWarning {
+    // The part of a chained command that matched
+    pub matched_command: String,
+    pub rule: CostRule,
+}
+
+#[derive(Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+struct CostWarningEvent {
+    session_id: String,
+    command: String,
+    warning: CostWarning,
+    // Present when the rule requires confirmation before the command may run
+    confirmation: Option<ConfirmationRequest>,
+}
+
+// Words that do not change which program runs: `sudo`, `env`, `time` and VAR=value assignments
+pub fn is_command_prefix(word: &str) -> bool {
+    word == "sudo"
+        || word == "env"
+        || word == "time"
+        || (word.contains('=') && !word.starts_with('='))
+}
+
+// The segment from its program word on
+pub fn program_part(segment: &str) -> &str {
+    let mut rest = segment.trim();
+    loop {
+        let Some((word, tail)) = rest.split_once(char::is_whitespace) else {
+            return rest;
+        };
+        if !is_command_prefix(word) {
+            return rest;
+        }
+        rest = tail.trim_start();
+    }
+}
+
+// First rule matching any command in a `;`, `&&`, `||` or `|` chain
+pub fn classify_command_cost(settings: &AppSettings, command: &str) -> Option<CostWarning> {
+    if !settings.cost_warnings_enabled {
+        return None;
+    }
+    let rules: Vec<(&CostRule, Regex)> = settings
+        .cost_rules
+        .iter()
+        .filter_map(|rule| Regex::new(&rule.pattern).ok().map(|regex| (rule, regex)))
+        .collect();
+    command
+        .split([';', '&', '|', '\n'])
+        .map(program_part)
+        .filter(|segment| !segment.is_empty())
+        .find_map(|segment| {
+            rules
+                .iter()
+                .find(|(_, regex)| regex.is_match(segment))
+                .map(|(rule, _)| CostWarning {
+                    matched_command: segment.to_string(),
+                    rule: (*rule).clone(),
+                })
+        })
+}
+
+pub fn validate_cost_rules(rules: &[CostRule]) -> Result<(), String> {
+    for rule in rules {
+        Regex::new(&rule.pattern)
+            .map_err(|e| format!("Invalid cost rule pattern '{}': {}", rule.pattern, e))?;
+    }
+    Ok(())
+}
+
+// Emits cost_warning for a matching command. Returns false when the matching rule needs
+// confirmation and `confirmation_token` is not a valid token for this command; the caller
+// must then stop and let the frontend re-run it with the token from the event.
+pub fn check_cost_gate<R: Runtime>(
+    app_handle: &AppHandle<R>,
+    session_id: &str,
+    command: &str,
+    confirmation_token: Option<&str>,
+) -> Result<bool, String> {
+    let settings = app_handle.state::<SettingsManager>().current();
+    let Some(warning) = classify_command_cost(&settings, command) else {
+        return Ok(true);
+    };
+    let confirmation_manager = app_handle.state::<ConfirmationManager>();
+    let scope = format!("cost:{}:{}", session_id, command);
+    if warning.rule.require_confirmation {
+        let confirmed = confirmation_token
+            .map(|token| confirmation_manager.consume(token, &scope))
+            .unwrap_or(false);
+        if confirmed {
+            return Ok(true);
+        }
+    }
+
+    let confirmation = if warning.rule.require_confirmation {
+        Some(confirmation_manager.issue(
+            &scope,
+            "Run potentially expensive command?",
+            &format!("{}\n\n{}", command, warning.rule.message),
+        )?)
+    } else {
+        None
+    };
+    let allowed = confirmation.is_none();
+    app_handle
+        .emit(
+            COST_WARNING_EVENT,
+            CostWarningEvent {
+                session_id: session_id.to_string(),
+                command: command.to_string(),
+                warning,
+                confirmation,
+            },
+        )
+        .map_err(|e| e.to_string())?;
+    Ok(allowed)
+}
+
+// Lets the input show the warning while the command is being typed
+#[command]
+pub fn check_command_cost(
+    command: String,
+    settings_manager: State<'_, SettingsManager>,
+) -> Option<CostWarning> {
+    classify_command_cost(&settings_manager.current(), &command)
+}
@@ -1,27 +1,87 @@
+use crate::audit::audit_log::record_audit_event;
+use crate::command::constants::{SSH_CONTROL_PATH, SSH_ERROR_DRAIN_MS, SSH_ERROR_TAIL_BYTES};
+use crate::command::core::color_control::{
+    apply_color_environment, color_command, color_pty_command,
+};
+use crate::command::core::command_end::{emit_command_end, CommandEndEvent};
+use crate::command::core::command_output::{emit_command_error, emit_command_output};
+use crate::command::core::command_templates::{expand_templates, session_template_context};
+use crate::command::core::input_prompts::{spawn_prompt_watcher, PromptWatch};
+use crate::command::core::pager_disabling::{no_pager_command, pager_environment};
+use crate::command::core::port_conflicts::warn_port_conflict;
+use crate::command::core::protected_sessions::check_command_gates;
+use crate::command::core::pty_fallback::{start_pty_fallback, tty_command_match};
+use crate::command::core::resource_usage::wait_with_usage;
+use crate::command::core::shell_options::{ShellOptions, TraceSplitter, TRACE_PS4};
+use crate::command::correction::command_not_found::{
+    command_program, emit_command_suggestion, COMMAND_NOT_FOUND_EXIT_CODE,
+};
+use crate::command::correction::directory_suggestions::emit_directory_suggestion;
+use crate::command::environment::directory_env::{refresh_directory_env, session_env_overrides};
+use crate::command::output::post_processors::process_output;
+use crate::command::output::progress::{finish_progress, track_progress};
+use crate::command::output::structured_output::announce_structured_output;
+use crate::command::ssh::connection_phases::SshPhaseTracker;
+use crate::command::timeline::session_timeline::record_timeline_event;
 use crate::command::types::command_manager::CommandManager;
-use crate::command::types::command_state::CommandState;
-use crate::utils::file_system_utils::get_shell_path;
+use crate::command::types::command_state::{CommandState, LocalInput, RunningCommand};
+use crate::command::types::confirmation_manager::CONFIRMATION_REQUIRED_MARKER;
+use crate::command::types::scrollback_manager::{OutputStream, ScrollbackManager};
+use crate::command::types::timeline_manager::TimelineEventKind;
+use crate::history::directory_history::record_directory_visit;
+use crate::history::host_history::ssh_host;
+use crate::history::recorder::{record_history_entry, record_remote_history_entry};
+use crate::hooks::dispatch::{fire_command_finished_hooks, fire_hooks};
+use crate::hooks::types::hook::HookEvent;
+use crate::i18n::types::app_error::AppError;
+use crate::i18n::types::error_code::ErrorCode;
+use crate::receipts::recorder::complete_command_receipt;
+use crate::settings::types::settings_manager::SettingsManager;
+use crate::utils::env_scrubbing::scrubbed_environment;
+use crate::utils::file_system_utils::{get_shell_path, resolve_cd_target, split_cd_flags};
 use std::collections::HashMap;
 use std::io::{BufReader, Read, Write};
 use std::os::unix::process::CommandExt;
 use std::path::Path;
 use std::process::{Child, Command, Stdio};
-use std::sync::{Arc, Mutex, MutexGuard};
+use std::sync::{mpsc, Arc, Mutex, MutexGuard};
+use std::time::Duration;
 use std::{env, thread};
-use tauri::{command, AppHandle, Emitter, Manager, State};
+use tauri::{command, AppHandle, Emitter, Manager, Runtime, State};
+
+// Returned once a command has been spawned and its output is streaming
+pub const COMMAND_STARTED_MESSAGE: &str = "Command started. Output will stream in real-time.";
 
 #[command]
-pub fn execute_command(
+pub fn execute_command<R: Runtime>(
     command: String,
     session_id: String,
     ssh_password: Option<String>,
-    app_handle: AppHandle,
+    confirmation_token: Option<String>,
+    shell_options: Option<ShellOptions>,
+    app_handle: AppHandle<R>,
     command_manager: State<'_, CommandManager>,
-) -> Result<String, String> {
+) -> Result<String, AppError> {
     const SSH_NEEDS_PASSWORD_MARKER: &str = "SSH_INTERACTIVE_PASSWORD_PROMPT_REQUESTED";
     const SSH_PRE_EXEC_PASSWORD_EVENT: &str = "ssh_pre_exec_password_request";
     const COMMAND_FORWARDED_TO_ACTIVE_SSH_MARKER: &str = "COMMAND_FORWARDED_TO_ACTIVE_SSH";
 
+    // Phase 0: Expand {{variables}} against the session before anything else sees the command
+    let command = expand_templates(
+        &command,
+        &session_template_context(&command_manager, &session_id)?,
+    )?;
+
+    // Phase 0.5: Protected-session and cost gates
+    if !check_command_gates(
+        &app_handle,
+        &session_id,
+        &command,
+        confirmation_token.as_deref(),
+    )? {
+        return Ok(CONFIRMATION_REQUIRED_MARKER.to_string());
+    }
+
     // Phase 1: Check and handle active SSH session
     {
         let mut states_guard = command_manager.commands.lock().map_err(|e| e.to_string())?;
@@ -32,6 +92,34 @@ pub fn execute_command(
             if let Some(stdin_arc_for_thread) = state.child_stdin.clone() {
                 let active_pid_for_log = state.pid.unwrap_or(0);
 
+                app_handle.state::<ScrollbackManager>().append(
+                    &session_id,
+                    OutputStream::Input,
+                    &command,
+                );
+                record_timeline_event(
+                    &app_handle,
+                    &session_id,
+                    TimelineEventKind::CommandStarted,
+                    &command,
+                    serde_json::json!({ "command": command, "remote": true }),
+                );
+                record_audit_event(
+                    &app_handle,
+                    &session_id,
+                    &command,
+                    state.remote_current_dir.as_deref().unwrap_or("remote"),
+                    true,
+                    None,
+                );
+                record_remote_history_entry(
+                    &app_handle,
+                    &session_id,
+                    &command,
+                    state.remote_current_dir.as_deref().unwrap_or("remote"),
+                    state.ssh_target.as_deref().and_then(ssh_host),
+                );
+
                 if let Err(e) = app_handle.emit("command_forwarded_to_ssh", command.clone()) {
                     eprintln!(
                         "[Rust EXEC DEBUG] Failed to emit command_forwarded_to_ssh: {}",
@@ -65,15 +153,22 @@ pub fn execute_command(
                                 }
                             }
                             let _ = app_handle_clone_for_thread.emit("ssh_session_ended", serde_json::json!({ "pid": active_pid_for_log, "reason": format!("SSH session error (stdin lock): {}", e)}));
-                            let _ = app_handle_clone_for_thread.emit(
-                                "command_error",
+                            emit_command_error(
+                                &app_handle_clone_for_thread,
+                                &session_id_clone_for_thread,
                                 format!(
                                     "Failed to send to SSH (stdin lock '{}'): {}",
                                     command_clone_for_thread, e
                                 ),
                             );
-                            let _ =
-                                app_handle_clone_for_thread.emit("command_end", "Command failed.");
+                            emit_command_end(
+                                &app_handle_clone_for_thread,
+                                CommandEndEvent::new(
+                                    &session_id_clone_for_thread,
+                                    false,
+                                    "Command failed.",
+                                ),
+                            );
                             return;
                         }
                     };
@@ -122,14 +217,22 @@ pub fn execute_command(
                             }
                         }
                         let _ = app_handle_clone_for_thread.emit("ssh_session_ended", serde_json::json!({ "pid": active_pid_for_log, "reason": format!("SSH session ended (stdin write/flush error): {}", e)}));
-                        let _ = app_handle_clone_for_thread.emit(
-                            "command_error",
+                        emit_command_error(
+                            &app_handle_clone_for_thread,
+                            &session_id_clone_for_thread,
                             format!(
                                 "Failed to send to SSH (stdin write/flush '{}'): {}",
                                 command_clone_for_thread, e
                             ),
                         );
-                        let _ = app_handle_clone_for_thread.emit("command_end", "Command failed.");
+                        emit_command_end(
+                            &app_handle_clone_for_thread,
+                            CommandEndEvent::new(
+                                &session_id_clone_for_thread,
+                                false,
+                                "Command failed.",
+                            ),
+                        );
                     }
                 });
 
@@ -143,7 +246,7 @@ pub fn execute_command(
                 state.remote_current_dir = None;
                 drop(states_guard);
                 let _ = app_handle.emit("ssh_session_ended", serde_json::json!({ "pid": active_pid_for_log, "reason": "SSH session inconsistency: active but no stdin."}));
-                return Err("SSH session conflict: active but no stdin. Please retry.".to_string());
+                return Err(AppError::new(ErrorCode::SshSessionConflict, &[]));
             }
         }
     }
@@ -155,19 +258,60 @@ pub fn execute_command(
         // It will lock `command_manager.commands` internally.
         let mut states_guard_cd = command_manager.commands.lock().map_err(|e| e.to_string())?;
         let command_state_cd = get_command_state(&mut states_guard_cd, session_id.clone());
+        app_handle
+            .state::<ScrollbackManager>()
+            .append(&session_id, OutputStream::Input, &command);
 
-        let path = command.trim_start_matches("cd").trim();
+        let (cd_mode, path) = split_cd_flags(
+            command.trim_start_matches("cd"),
+            app_handle.state::<SettingsManager>().current().cd_mode,
+        );
         if path.is_empty() || path == "~" || path == "~/" {
             return if let Some(home_dir) = dirs::home_dir() {
-                let home_path = home_dir.to_string_lossy().to_string();
+                let home_path = resolve_cd_target(&home_dir, cd_mode)
+                    .to_string_lossy()
+                    .to_string();
                 command_state_cd.current_dir = home_path.clone();
                 drop(states_guard_cd); // Release lock before emitting and returning
-                let _ = app_handle.emit("command_end", "Command completed successfully.");
+                record_audit_event(
+                    &app_handle,
+                    &session_id,
+                    &command,
+                    &home_path,
+                    false,
+                    Some(0),
+                );
+                record_history_entry(
+                    &app_handle,
+                    &session_id,
+                    &command,
+                    &home_path,
+                    Some(0),
+                    None,
+                );
+                record_timeline_event(
+                    &app_handle,
+                    &session_id,
+                    TimelineEventKind::DirectoryChanged,
+                    &home_path,
+                    serde_json::json!({ "path": home_path }),
+                );
+                record_directory_visit(&app_handle, &session_id, &home_path);
+                refresh_directory_env(&app_handle, &session_id, &home_path);
+                emit_command_end(
+                    &app_handle,
+                    CommandEndEvent::new(&session_id, true, "Command completed successfully."),
+                );
                 Ok(format!("Changed directory to {}", home_path))
             } else {
                 drop(states_guard_cd);
-                let _ = app_handle.emit("command_end", "Command failed.");
-                Err("Could not determine home directory".to_string())
+                let error = AppError::new(ErrorCode::HomeDirectoryUnknown, &[]);
+                emit_command_end(
+                    &app_handle,
+                    CommandEndEvent::new(&session_id, false, "Command failed.")
+                        .with_error(error.clone()),
+                );
+                Err(error)
             };
         }
         let current_path = Path::new(&command_state_cd.current_dir);
@@ -182,7 +326,7 @@ pub fn execute_command(
                 }
             } else {
                 drop(states_guard_cd);
-                return Err("Could not determine home directory".to_string());
+                return Err(AppError::new(ErrorCode::HomeDirectoryUnknown, &[]));
             }
         } else if path.starts_with('/') {
             std::path::PathBuf::from(path)
@@ -195,8 +339,13 @@ pub fn execute_command(
                         result_path = parent.to_path_buf();
                     } else {
                         drop(states_guard_cd);
-                        let _ = app_handle.emit("command_end", "Command failed.");
-                        return Err("Already at root directory".to_string());
+                        let error = AppError::new(ErrorCode::AlreadyAtRoot, &[]);
+                        emit_command_end(
+                            &app_handle,
+                            CommandEndEvent::new(&session_id, false, "Command failed.")
+                                .with_error(error.clone()),
+                        );
+                        return Err(error);
                     }
                 } else if component != "." && !component.is_empty() {
                     result_path = result_path.join(component);
@@ -204,16 +353,81 @@ pub fn execute_command(
             }
             result_path
         };
+        let new_path = resolve_cd_target(&new_path, cd_mode);
         return if new_path.exists() {
             command_state_cd.current_dir = new_path.to_string_lossy().to_string();
             let current_dir_for_ok = command_state_cd.current_dir.clone();
             drop(states_guard_cd);
-            let _ = app_handle.emit("command_end", "Command completed successfully.");
+            record_audit_event(
+                &app_handle,
+                &session_id,
+                &command,
+                &current_dir_for_ok,
+                false,
+                Some(0),
+            );
+            record_history_entry(
+                &app_handle,
+                &session_id,
+                &command,
+                &current_dir_for_ok,
+                Some(0),
+                None,
+            );
+            record_timeline_event(
+                &app_handle,
+                &session_id,
+                TimelineEventKind::DirectoryChanged,
+                &current_dir_for_ok,
+                serde_json::json!({ "path": current_dir_for_ok }),
+            );
+            record_directory_visit(&app_handle, &session_id, &current_dir_for_ok);
+            refresh_directory_env(&app_handle, &session_id, &current_dir_for_ok);
+            emit_command_end(
+                &app_handle,
+                CommandEndEvent::new(&session_id, true, "Command completed successfully."),
+            );
             Ok(format!("Changed directory to {}", current_dir_for_ok))
         } else {
+            let current_dir_for_suggestions = current_path.to_path_buf();
             drop(states_guard_cd);
-            let _ = app_handle.emit("command_end", "Command failed.");
-            Err(format!("Directory not found: {}", path))
+            record_audit_event(
+                &app_handle,
+                &session_id,
+                &command,
+                &current_dir_for_suggestions.to_string_lossy(),
+                false,
+                Some(1),
+            );
+            record_history_entry(
+                &app_handle,
+                &session_id,
+                &command,
+                &current_dir_for_suggestions.to_string_lossy(),
+                Some(1),
+                None,
+            );
+            let suggestions = emit_directory_suggestion(
+                &app_handle,
+                &session_id,
+                path,
+                &new_path,
+                &current_dir_for_suggestions,
+            );
+            let error = if suggestions.is_empty() {
+                AppError::new(ErrorCode::DirectoryNotFound, &[("path", path)])
+            } else {
+                AppError::new(
+                    ErrorCode::DirectoryNotFoundWithSuggestions,
+                    &[("path", path), ("suggestions", &suggestions.join(", "))],
+                )
+            };
+            emit_command_end(
+                &app_handle,
+                CommandEndEvent::new(&session_id, false, "Command failed.")
+                    .with_error(error.clone()),
+            );
+            Err(error)
         };
     }
 
@@ -224,25 +438,66 @@ pub fn execute_command(
         state_dir.current_dir.clone()
     }; // Lock for current_dir released.
 
+    // Editors, pagers, `docker exec -it` and the like need a terminal and go to a PTY instead;
+    // ssh prompts for its own password there
+    let settings = app_handle.state::<SettingsManager>().current();
+    let tty_pattern = tty_command_match(&settings, &command);
+
     // Proactive SSH password handling (if not in an SSH session)
     let is_plain_ssh_attempt =
         command.contains("ssh ") && !command.trim_start().starts_with("sudo ssh ");
-    if is_plain_ssh_attempt && ssh_password.is_none() {
+    if is_plain_ssh_attempt && ssh_password.is_none() && tty_pattern.is_none() {
         app_handle
             .emit(SSH_PRE_EXEC_PASSWORD_EVENT, command.clone())
             .map_err(|e| e.to_string())?;
         return Ok(SSH_NEEDS_PASSWORD_MARKER.to_string());
     }
 
+    app_handle
+        .state::<ScrollbackManager>()
+        .append(&session_id, OutputStream::Input, &command);
+
     let mut command_to_run = command.clone();
     let app_handle_clone = app_handle.clone();
 
-    let mut env_map: HashMap<String, String> = std::env::vars().collect();
+    let mut env_map: HashMap<String, String> = scrubbed_environment(&settings);
     if !env_map.contains_key("PATH") {
         if let Some(path_val) = get_shell_path() {
             env_map.insert("PATH".to_string(), path_val);
         }
     }
+    // Children see the session's logical directory, not the app's own working directory
+    env_map.insert("PWD".to_string(), current_dir_clone.clone());
+    // Output is streamed rather than shown on a terminal: pagers would block, and most tools
+    // drop color unless it is forced
+    if !is_plain_ssh_attempt && tty_pattern.is_none() {
+        env_map.extend(pager_environment(&settings, &command));
+        apply_color_environment(&settings, &mut env_map);
+    }
+    env_map.extend(session_env_overrides(&command_manager, &session_id));
+    if settings.port_conflict_warnings_enabled && !is_plain_ssh_attempt {
+        warn_port_conflict(&app_handle, &session_id, &command, &env_map);
+    }
+
+    if let Some(pattern) = tty_pattern {
+        return start_pty_fallback(
+            &app_handle,
+            &session_id,
+            &command,
+            &current_dir_clone,
+            &env_map,
+            pattern,
+        )
+        .map_err(AppError::from);
+    }
+
+    // Interactive SSH sessions cannot be wrapped in a script
+    let shell_options = shell_options
+        .filter(|options| !options.is_empty() && !is_plain_ssh_attempt)
+        .unwrap_or_default();
+    if shell_options.trace {
+        env_map.insert("PS4".to_string(), TRACE_PS4.to_string());
+    }
 
     // let script_path_option: Option<String> = None; // Removed unused variable
 
@@ -254,6 +509,9 @@ pub fn execute_command(
 
     let mut cmd_to_spawn: Command;
     let mut child: Child;
+    // ssh runs verbosely so connection phases can be reported; whether the user asked for it too
+    let mut ssh_verbose: Option<bool> = None;
+    let mut ssh_target: Option<Vec<String>> = None;
 
     // Prepare command_to_run if it's an SSH command, before deciding on sshpass
     if is_potential_ssh_session_starter && !original_command_is_sudo_ssh {
@@ -278,7 +536,17 @@ pub fn execute_command(
                 None => false, // e.g., "ssh -p 22" without host, or just "ssh"
             };
 
-            let ssh_options_prefix = "ssh -t -t -o StrictHostKeyChecking=accept-new";
+            // The session is the control master ssh_exec multiplexes its channels over
+            let ssh_options_prefix = format!(
+                "ssh -t -t -v -o StrictHostKeyChecking=accept-new -o ControlMaster=auto -o ControlPath={}",
+                SSH_CONTROL_PATH
+            );
+            ssh_verbose = Some(
+                original_command_parts
+                    .iter()
+                    .skip(idx_ssh + 1)
+                    .any(|part| part.starts_with("-v")),
+            );
             // Arguments are everything after "ssh" in the original command
             let args_after_ssh_keyword_in_original = original_command_parts
                 .iter()
@@ -288,6 +556,13 @@ pub fn execute_command(
                 .join(" ");
 
             if is_likely_interactive_ssh {
+                ssh_target = Some(
+                    original_command_parts
+                        .iter()
+                        .skip(idx_ssh + 1)
+                        .map(|part| part.to_string())
+                        .collect(),
+                );
                 // For interactive: ssh -options user@host
                 command_to_run = format!(
                     "{} {}",
@@ -332,7 +607,8 @@ pub fn execute_command(
                 return Err(format!(
                     "Failed to parse SSH command for direct execution: {}",
                     command_to_run
-                ));
+                )
+                .into());
             }
             executable_name = parts[0].clone(); // Should be "ssh"
             arguments.extend(parts.iter().skip(1).cloned());
@@ -347,6 +623,7 @@ pub fn execute_command(
         // sshpass should take precedence or ssh (in key auth) would ignore it if not needed.
         cmd_to_spawn
             .current_dir(&current_dir_clone)
+            .env_clear()
             .envs(&env_map)
             .stdout(Stdio::piped())
             .stderr(Stdio::piped())
@@ -357,25 +634,35 @@ pub fn execute_command(
         child = match cmd_to_spawn.spawn() {
             Ok(c) => c,
             Err(e) => {
-                return Err(format!(
-                    "Failed to start direct command ({}): {}",
-                    executable_name, e
+                return Err(AppError::new(
+                    ErrorCode::SpawnFailed,
+                    &[("program", &executable_name), ("reason", &e.to_string())],
                 ))
             }
         };
     } else {
         // Fallback to sh -c for non-SSH or sudo commands
-        let final_shell_command = if original_command_is_sudo && !original_command_is_sudo_ssh {
+        let command_to_run =
+            color_command(&settings, &no_pager_command(&settings, &command_to_run));
+        let final_shell_command = if !shell_options.is_empty() {
+            shell_options.wrap(&command_to_run)
+        } else if original_command_is_sudo && !original_command_is_sudo_ssh {
             command_to_run.clone()
         } else {
             format!("exec {}", command_to_run)
         };
 
-        let mut sh_cmd_to_spawn = Command::new("sh");
+        let mut sh_cmd_to_spawn =
+            color_pty_command(&settings, &command, &final_shell_command, &env_map).unwrap_or_else(
+                || {
+                    let mut sh = Command::new("sh");
+                    sh.arg("-c").arg(&final_shell_command);
+                    sh
+                },
+            );
         sh_cmd_to_spawn
-            .arg("-c")
-            .arg(&final_shell_command)
             .current_dir(&current_dir_clone)
+            .env_clear()
             .envs(&env_map)
             .stdout(Stdio::piped())
             .stderr(Stdio::piped())
@@ -394,16 +681,38 @@ pub fn execute_command(
 
         child = match sh_cmd_to_spawn.spawn() {
             Ok(c) => c,
-            Err(e) => return Err(format!("Failed to start command via sh -c: {}", e)),
+            Err(e) => {
+                return Err(AppError::new(
+                    ErrorCode::SpawnFailed,
+                    &[("program", "sh"), ("reason", &e.to_string())],
+                ))
+            }
         };
     }
 
     let pid = child.id();
+    let started_at = std::time::Instant::now();
+    if !is_potential_ssh_session_starter {
+        record_timeline_event(
+            &app_handle_clone,
+            &session_id,
+            TimelineEventKind::CommandStarted,
+            &command,
+            serde_json::json!({ "command": command, "cwd": current_dir_clone, "pid": pid }),
+        );
+    }
     // Take IO handles before moving child into Arc<Mutex<Child>>
     let child_stdin_handle = child.stdin.take().map(|stdin| Arc::new(Mutex::new(stdin)));
     let child_stdout_handle = child.stdout.take();
     let child_stderr_handle = child.stderr.take();
     let child_wait_handle_arc = Arc::new(Mutex::new(child)); // Now 'child' has no IO handles
+    let mut ssh_phases = ssh_verbose
+        .map(|show_verbose| SshPhaseTracker::new(&session_id, pid, started_at, show_verbose));
+    let ssh_phase_reached = ssh_phases.as_ref().map(SshPhaseTracker::reached);
+    if let Some(tracker) = ssh_phases.as_mut() {
+        tracker.start(&app_handle_clone);
+    }
+    let prompt_watch = PromptWatch::new();
     let session_id_for_wait_thread = session_id.clone();
 
     {
@@ -411,13 +720,27 @@ pub fn execute_command(
         let state_to_update = get_command_state(&mut states_guard_update, session_id.clone());
 
         state_to_update.pid = Some(pid);
+        state_to_update.running = Some(RunningCommand::new(pid, &command));
         state_to_update.child_wait_handle = Some(child_wait_handle_arc.clone()); // Store wait handle
 
         if is_potential_ssh_session_starter {
             state_to_update.child_stdin = child_stdin_handle; // Store stdin handle for SSH
             state_to_update.is_ssh_session_active = true;
+            state_to_update.ssh_target = ssh_target;
             state_to_update.remote_current_dir = Some("remote:~".to_string()); // Initial placeholder
             let _ = app_handle_clone.emit("ssh_session_started", serde_json::json!({ "pid": pid }));
+            fire_hooks(
+                &app_handle_clone,
+                HookEvent::SshConnected,
+                serde_json::json!({ "sessionId": session_id, "pid": pid, "command": command }),
+            );
+            record_timeline_event(
+                &app_handle_clone,
+                &session_id,
+                TimelineEventKind::SshConnected,
+                &command,
+                serde_json::json!({ "pid": pid, "command": command }),
+            );
 
             // Attempt to send initial PWD command
             if let Some(stdin_arc_for_init_pwd) = state_to_update.child_stdin.clone() {
@@ -495,6 +818,17 @@ pub fn execute_command(
             state_to_update.is_ssh_session_active = false;
             state_to_update.child_stdin = None; // Ensure stdin is None for non-SSH commands
             state_to_update.remote_current_dir = None; // Ensure remote_dir is None for non-SSH
+                                                       // Left open so a command that stops at a prompt can be answered
+            state_to_update.input = child_stdin_handle.map(|stdin| LocalInput {
+                stdin,
+                prompts: prompt_watch.clone(),
+            });
+            spawn_prompt_watcher(
+                app_handle_clone.clone(),
+                session_id.clone(),
+                pid,
+                prompt_watch.clone(),
+            );
         }
     } // states_guard_update lock released
 
@@ -504,6 +838,7 @@ pub fn execute_command(
         let app_handle_for_stdout_emit = app_handle_clone.clone();
         let current_pid_for_stdout_context = pid;
         let session_id_for_stdout_thread = session_id.clone();
+        let prompt_watch_for_stdout = prompt_watch.clone();
 
         thread::spawn(move || {
             let mut reader = BufReader::new(stdout_stream);
@@ -518,22 +853,40 @@ pub fn execute_command(
             let mut pwd_marker_state = PwdMarkerParseState::Idle;
 
             let current_thread_id = std::thread::current().id();
+            let scrollback = app_handle_for_stdout_mgr.state::<ScrollbackManager>();
 
             loop {
                 match reader.read(&mut buffer) {
                     Ok(0) => {
                         if !line_buffer.is_empty() {
-                            if let Err(e) = app_handle_for_stdout_emit
-                                .emit("command_output", line_buffer.clone())
-                            {
-                                println!("[Rust STDOUT Thread {:?} PID {}] Error emitting final command_output: {}", current_thread_id, current_pid_for_stdout_context, e);
-                            }
+                            let line_buffer = process_output(
+                                &app_handle_for_stdout_emit,
+                                &session_id_for_stdout_thread,
+                                OutputStream::Stdout,
+                                std::mem::take(&mut line_buffer),
+                            );
+                            scrollback.append(
+                                &session_id_for_stdout_thread,
+                                OutputStream::Stdout,
+                                &line_buffer,
+                            );
+                            emit_command_output(
+                                &app_handle_for_stdout_emit,
+                                &session_id_for_stdout_thread,
+                                line_buffer.clone(),
+                            );
                         }
                         break;
                     }
                     Ok(n) => {
                         let output_chunk_str = String::from_utf8_lossy(&buffer[..n]).to_string();
                         line_buffer.push_str(&output_chunk_str);
+                        prompt_watch_for_stdout.observe(&output_chunk_str);
+                        track_progress(
+                            &app_handle_for_stdout_emit,
+                            &session_id_for_stdout_thread,
+                            &output_chunk_str,
+                        );
 
                         while let Some(newline_pos) = line_buffer.find('\n') {
                             let line_segment =
@@ -543,11 +896,16 @@ pub fn execute_command(
                             if current_line_trimmed.is_empty() {
                                 match pwd_marker_state {
                                     PwdMarkerParseState::Idle => {
-                                        if let Err(e) = app_handle_for_stdout_emit
-                                            .emit("command_output", line_segment.clone())
-                                        {
-                                            println!("[Rust STDOUT Thread {:?} PID {}] Error emitting whitespace/newline: {}", current_thread_id, current_pid_for_stdout_context, e);
-                                        }
+                                        scrollback.append(
+                                            &session_id_for_stdout_thread,
+                                            OutputStream::Stdout,
+                                            &line_segment,
+                                        );
+                                        emit_command_output(
+                                            &app_handle_for_stdout_emit,
+                                            &session_id_for_stdout_thread,
+                                            line_segment.clone(),
+                                        );
                                     }
                                     _ => {}
                                 }
@@ -617,11 +975,22 @@ pub fn execute_command(
                             }
 
                             if emit_this_segment_to_frontend {
-                                if let Err(e) = app_handle_for_stdout_emit
-                                    .emit("command_output", line_segment.clone())
-                                {
-                                    println!("[Rust STDOUT Thread {:?} PID {}] Error emitting command_output: {}", current_thread_id, current_pid_for_stdout_context, e);
-                                }
+                                let line_segment = process_output(
+                                    &app_handle_for_stdout_emit,
+                                    &session_id_for_stdout_thread,
+                                    OutputStream::Stdout,
+                                    line_segment,
+                                );
+                                scrollback.append(
+                                    &session_id_for_stdout_thread,
+                                    OutputStream::Stdout,
+                                    &line_segment,
+                                );
+                                emit_command_output(
+                                    &app_handle_for_stdout_emit,
+                                    &session_id_for_stdout_thread,
+                                    line_segment.clone(),
+                                );
                             }
                         }
                     }
@@ -630,11 +999,22 @@ pub fn execute_command(
                             continue;
                         }
                         if !line_buffer.is_empty() {
-                            if let Err(emit_e) = app_handle_for_stdout_emit
-                                .emit("command_output", line_buffer.clone())
-                            {
-                                println!("[Rust STDOUT Thread {:?} PID {}] Error emitting final command_output on error: {}", current_thread_id, current_pid_for_stdout_context, emit_e);
-                            }
+                            let line_buffer = process_output(
+                                &app_handle_for_stdout_emit,
+                                &session_id_for_stdout_thread,
+                                OutputStream::Stdout,
+                                std::mem::take(&mut line_buffer),
+                            );
+                            scrollback.append(
+                                &session_id_for_stdout_thread,
+                                OutputStream::Stdout,
+                                &line_buffer,
+                            );
+                            emit_command_output(
+                                &app_handle_for_stdout_emit,
+                                &session_id_for_stdout_thread,
+                                line_buffer.clone(),
+                            );
                         }
                         break;
                     }
@@ -643,30 +1023,76 @@ pub fn execute_command(
         });
     }
 
+    // The end of an SSH session's stderr goes to the wait thread, which explains the failure
+    let (stderr_tail_sender, stderr_tail_receiver) = mpsc::channel::<String>();
     if let Some(stderr_stream) = child_stderr_handle {
         // Use the taken stderr
         let app_handle_stderr = app_handle.clone();
+        let session_id_for_stderr_thread = session_id.clone();
+        let mut trace_splitter = shell_options.trace.then(TraceSplitter::default);
+        let prompt_watch_for_stderr = prompt_watch.clone();
+        let keep_stderr_tail = is_potential_ssh_session_starter;
         thread::spawn(move || {
             let mut reader = BufReader::new(stderr_stream);
             let mut buffer = [0; 2048];
-            let current_thread_id = std::thread::current().id(); // Get thread ID once
+            let scrollback = app_handle_stderr.state::<ScrollbackManager>();
+            let mut stderr_tail = String::new();
+            let mut ssh_phases = ssh_phases;
             loop {
                 match reader.read(&mut buffer) {
                     Ok(0) => {
+                        if let Some(splitter) = trace_splitter.as_mut() {
+                            splitter.finish(&app_handle_stderr, &session_id_for_stderr_thread);
+                        }
                         break;
                     }
                     Ok(n) => {
-                        let error_chunk = String::from_utf8_lossy(&buffer[..n]).to_string();
-                        if !error_chunk.contains("[sudo] password") {
-                            if let Err(e) =
-                                app_handle_stderr.emit("command_error", error_chunk.clone())
-                            {
-                                println!(
-                                    "[Rust STDERR Thread {:?}] Error emitting command_error: {}",
-                                    current_thread_id, e
-                                );
+                        let mut error_chunk = String::from_utf8_lossy(&buffer[..n]).to_string();
+                        if let Some(tracker) = ssh_phases.as_mut() {
+                            error_chunk = tracker.observe(&app_handle_stderr, &error_chunk);
+                        }
+                        if keep_stderr_tail {
+                            stderr_tail.push_str(&error_chunk);
+                            if stderr_tail.len() > SSH_ERROR_TAIL_BYTES {
+                                let mut start = stderr_tail.len() - SSH_ERROR_TAIL_BYTES;
+                                while !stderr_tail.is_char_boundary(start) {
+                                    start += 1;
+                                }
+                                stderr_tail.drain(..start);
                             }
                         }
+                        if let Some(splitter) = trace_splitter.as_mut() {
+                            error_chunk = splitter.split(
+                                &app_handle_stderr,
+                                &session_id_for_stderr_thread,
+                                &error_chunk,
+                            );
+                        }
+                        prompt_watch_for_stderr.observe(&error_chunk);
+                        if !error_chunk.is_empty() && !error_chunk.contains("[sudo] password") {
+                            let error_chunk = process_output(
+                                &app_handle_stderr,
+                                &session_id_for_stderr_thread,
+                                OutputStream::Stderr,
+                                error_chunk,
+                            );
+                            scrollback.append(
+                                &session_id_for_stderr_thread,
+                                OutputStream::Stderr,
+                                &error_chunk,
+                            );
+                            // Most tools draw their progress bars on stderr
+                            track_progress(
+                                &app_handle_stderr,
+                                &session_id_for_stderr_thread,
+                                &error_chunk,
+                            );
+                            emit_command_error(
+                                &app_handle_stderr,
+                                &session_id_for_stderr_thread,
+                                error_chunk.clone(),
+                            );
+                        }
                     }
                     Err(e) => {
                         if e.kind() == std::io::ErrorKind::Interrupted {
@@ -676,6 +1102,7 @@ pub fn execute_command(
                     }
                 }
             }
+            let _ = stderr_tail_sender.send(stderr_tail);
         });
     }
 
@@ -684,6 +1111,8 @@ pub fn execute_command(
     let app_handle_for_thread_state = app_handle.clone();
     let was_ssh_session_starter = is_potential_ssh_session_starter;
     let initial_child_pid_for_wait_thread = pid;
+    let command_for_wait_thread = command.clone();
+    let cwd_for_wait_thread = current_dir_clone.clone();
 
     thread::spawn(move || {
         let status_result = {
@@ -692,17 +1121,44 @@ pub fn execute_command(
                 Ok(guard) => guard,
                 Err(e) => {
                     // Emit error and end messages
-                    let _ = app_handle_wait.emit(
-                        "command_error",
+                    emit_command_error(
+                        &app_handle_wait,
+                        &session_id_for_wait_thread,
                         format!("Error locking child for wait: {}", e),
                     );
-                    let _ = app_handle_wait
-                        .emit("command_end", "Command failed due to wait lock error.");
+                    emit_command_end(
+                        &app_handle_wait,
+                        CommandEndEvent::new(
+                            &session_id_for_wait_thread,
+                            false,
+                            "Command failed due to wait lock error.",
+                        ),
+                    );
                     return;
                 }
             };
             // child_guard is MutexGuard<Child>
-            child_guard.wait()
+            wait_with_usage(&mut child_guard)
+        };
+        let resources = status_result
+            .as_ref()
+            .ok()
+            .and_then(|(_, resources)| resources.clone());
+        let status_result = status_result.map(|(status, _)| status);
+        prompt_watch.finish();
+
+        // Known causes of failure, reported with command_end and ssh_session_ended
+        let failure = match &status_result {
+            Ok(status) if !status.success() && was_ssh_session_starter => stderr_tail_receiver
+                .recv_timeout(Duration::from_millis(SSH_ERROR_DRAIN_MS))
+                .ok()
+                .and_then(|stderr_tail| AppError::from_ssh_output(&stderr_tail)),
+            Ok(status) if status.code() == Some(COMMAND_NOT_FOUND_EXIT_CODE) => {
+                command_program(&command_for_wait_thread).map(|program| {
+                    AppError::new(ErrorCode::CommandNotFound, &[("program", program)])
+                })
+            }
+            _ => None,
         };
 
         {
@@ -723,12 +1179,21 @@ pub fn execute_command(
                 if state_to_clear.pid == Some(initial_child_pid_for_wait_thread) {
                     state_to_clear.child_wait_handle = None;
                     state_to_clear.pid = None; // PID is cleared here
+                    state_to_clear.input = None;
                     if was_ssh_session_starter && state_to_clear.is_ssh_session_active {
                         state_to_clear.is_ssh_session_active = false;
                         state_to_clear.child_stdin = None; // Also clear stdin if it was an SSH session
                         state_to_clear.remote_current_dir = None; // Clear remote dir
 
-                        let _ = app_handle_wait.emit("ssh_session_ended", serde_json::json!({ "pid": initial_child_pid_for_wait_thread, "reason": "SSH session ended normally."}));
+                        let reason = match &failure {
+                            Some(error) => error.message.clone(),
+                            None => "SSH session ended normally.".to_string(),
+                        };
+                        // How far the connection got, so a failure can be placed in a phase
+                        let phase = ssh_phase_reached
+                            .as_ref()
+                            .and_then(|reached| reached.lock().ok().and_then(|reached| *reached));
+                        let _ = app_handle_wait.emit("ssh_session_ended", serde_json::json!({ "pid": initial_child_pid_for_wait_thread, "reason": reason, "error": failure, "phase": phase}));
                     } else if was_ssh_session_starter {
                         // SSH session starter but was already marked inactive (e.g. by write thread error)
                         // Ensure remote_current_dir is also cleared if it hasn't been.
@@ -739,51 +1204,155 @@ pub fn execute_command(
             }
         } // states_guard_cleanup lock released
 
+        let exit_code = status_result.as_ref().ok().and_then(|status| status.code());
+        let duration_ms = started_at.elapsed().as_millis() as u64;
+        record_audit_event(
+            &app_handle_wait,
+            &session_id_for_wait_thread,
+            &command_for_wait_thread,
+            &cwd_for_wait_thread,
+            false,
+            exit_code,
+        );
+        record_history_entry(
+            &app_handle_wait,
+            &session_id_for_wait_thread,
+            &command_for_wait_thread,
+            &cwd_for_wait_thread,
+            exit_code,
+            Some(duration_ms),
+        );
+        if was_ssh_session_starter {
+            record_timeline_event(
+                &app_handle_wait,
+                &session_id_for_wait_thread,
+                TimelineEventKind::SshDisconnected,
+                &command_for_wait_thread,
+                serde_json::json!({ "exitCode": exit_code, "durationMs": duration_ms }),
+            );
+            fire_hooks(
+                &app_handle_wait,
+                HookEvent::SshDisconnected,
+                serde_json::json!({
+                    "sessionId": session_id_for_wait_thread,
+                    "pid": initial_child_pid_for_wait_thread,
+                    "command": command_for_wait_thread,
+                }),
+            );
+        } else {
+            record_timeline_event(
+                &app_handle_wait,
+                &session_id_for_wait_thread,
+                TimelineEventKind::CommandFinished,
+                &command_for_wait_thread,
+                serde_json::json!({
+                    "command": command_for_wait_thread,
+                    "cwd": cwd_for_wait_thread,
+                    "exitCode": exit_code,
+                    "durationMs": duration_ms,
+                }),
+            );
+        }
+
+        app_handle_wait
+            .state::<ScrollbackManager>()
+            .finish_run(&session_id_for_wait_thread, exit_code);
+        finish_progress(&app_handle_wait, &session_id_for_wait_thread);
+        if !was_ssh_session_starter {
+            announce_structured_output(&app_handle_wait, &session_id_for_wait_thread);
+        }
+        complete_command_receipt(
+            &app_handle_wait,
+            &session_id_for_wait_thread,
+            exit_code,
+            None,
+        );
+
         match status_result {
             Ok(status) => {
+                if !was_ssh_session_starter {
+                    fire_command_finished_hooks(
+                        &app_handle_wait,
+                        &session_id_for_wait_thread,
+                        &command_for_wait_thread,
+                        &cwd_for_wait_thread,
+                        exit_code,
+                        duration_ms,
+                    );
+                    if status.code() == Some(COMMAND_NOT_FOUND_EXIT_CODE) {
+                        emit_command_suggestion(
+                            &app_handle_wait,
+                            &session_id_for_wait_thread,
+                            &command_for_wait_thread,
+                        );
+                    }
+                }
                 let exit_msg = if status.success() {
                     "Command completed successfully."
                 } else {
                     "Command failed."
                 };
-                let _ = app_handle_wait.emit("command_end", exit_msg);
+                emit_command_end(
+                    &app_handle_wait,
+                    CommandEndEvent {
+                        exit_code,
+                        duration_ms: Some(duration_ms),
+                        resources,
+                        error: failure,
+                        ..CommandEndEvent::new(
+                            &session_id_for_wait_thread,
+                            status.success(),
+                            exit_msg,
+                        )
+                    },
+                );
             }
             Err(e) => {
-                let _ = app_handle_wait
-                    .emit("command_error", format!("Error waiting for command: {}", e));
+                emit_command_error(
+                    &app_handle_wait,
+                    &session_id_for_wait_thread,
+                    format!("Error waiting for command: {}", e),
+                );
                 // Also emit command_end because the command effectively ended, albeit with an error during wait
-                let _ = app_handle_wait.emit("command_end", "Command failed due to wait error.");
+                emit_command_end(
+                    &app_handle_wait,
+                    CommandEndEvent::new(
+                        &session_id_for_wait_thread,
+                        false,
+                        "Command failed due to wait error.",
+                    ),
+                );
             }
         }
     });
 
-    Ok("Command started. Output will stream in real-time.".to_string())
+    Ok(COMMAND_STARTED_MESSAGE.to_string())
 }
 
 #[command]
-pub fn execute_sudo_command(
+pub fn execute_sudo_command<R: Runtime>(
     command: String,
     session_id: String,
     password: String,
-    app_handle: AppHandle,
+    app_handle: AppHandle<R>,
     command_manager: State<'_, CommandManager>,
-) -> Result<String, String> {
+) -> Result<String, AppError> {
     let mut states = command_manager.commands.lock().map_err(|e| e.to_string())?;
 
     let key = session_id;
-    let state = states.entry(key.clone()).or_insert_with(|| CommandState {
-        current_dir: env::current_dir()
-            .unwrap_or_default()
-            .to_string_lossy()
-            .to_string(),
-        child_wait_handle: None,
-        child_stdin: None,
-        pid: None,
-        is_ssh_session_active: false,
-        remote_current_dir: None,
+    let state = states.entry(key.clone()).or_insert_with(|| {
+        CommandState::new(
+            env::current_dir()
+                .unwrap_or_default()
+                .to_string_lossy()
+                .to_string(),
+        )
     });
 
     let current_dir = state.current_dir.clone();
+    app_handle
+        .state::<ScrollbackManager>()
+        .append(&key, OutputStream::Input, &command);
 
     let mut child_process = match Command::new("sudo")
         .arg("-S")
@@ -797,6 +1366,11 @@ pub fn execute_sudo_command(
                 .join(" "),
         ) // Skip "sudo" and join the rest
         .current_dir(&current_dir)
+        .env_clear()
+        .envs(scrubbed_environment(
+            &app_handle.state::<SettingsManager>().current(),
+        ))
+        .env("PWD", &current_dir)
         .stdin(Stdio::piped())
         .stdout(Stdio::piped())
         .stderr(Stdio::piped())
@@ -804,7 +1378,10 @@ pub fn execute_sudo_command(
     {
         Ok(child) => child,
         Err(e) => {
-            return Err(format!("Failed to start sudo command: {}", e));
+            return Err(AppError::new(
+                ErrorCode::SpawnFailed,
+                &[("program", "sudo"), ("reason", &e.to_string())],
+            ));
         }
     };
 
@@ -817,17 +1394,19 @@ pub fn execute_sudo_command(
 
     state.child_wait_handle = Some(child_arc.clone()); // Store wait handle
     state.pid = Some(child_pid); // Store PID
-                                 // For sudo, is_ssh_session_active remains false, child_stdin for SSH is not set.
+    state.running = Some(RunningCommand::new(child_pid, &format!("sudo {}", command)));
+    // For sudo, is_ssh_session_active remains false, child_stdin for SSH is not set.
 
     // Send password to stdin
     if let Some(stdin_arc) = sudo_stdin {
         // Use the taken and Arc-wrapped stdin
         let app_handle_stdin = app_handle.clone();
+        let session_id_for_stdin = key.clone();
         thread::spawn(move || {
             let mut stdin_guard = match stdin_arc.lock() {
                 Ok(guard) => guard,
                 Err(e) => {
-                    let _ = app_handle_stdin.emit("command_error", e.to_string());
+                    emit_command_error(&app_handle_stdin, &session_id_for_stdin, e.to_string());
                     return;
                 }
             };
@@ -835,7 +1414,11 @@ pub fn execute_sudo_command(
                 .write_all(format!("{}", password).as_bytes())
                 .is_err()
             {
-                let _ = app_handle_stdin.emit("command_error", "Failed to send password to sudo");
+                emit_command_error(
+                    &app_handle_stdin,
+                    &session_id_for_stdin,
+                    "Failed to send password to sudo",
+                );
             }
         });
     }
@@ -843,6 +1426,7 @@ pub fn execute_sudo_command(
     // Use the taken stdout_stream
     if let Some(stdout_stream) = sudo_stdout {
         let app_handle_stdout = app_handle.clone();
+        let session_id_for_stdout = key.clone();
         thread::spawn(move || {
             let mut reader = BufReader::new(stdout_stream);
             let mut buffer = [0; 2048]; // Read in chunks
@@ -850,15 +1434,32 @@ pub fn execute_sudo_command(
                 match reader.read(&mut buffer) {
                     Ok(0) => break, // EOF
                     Ok(n) => {
-                        let output_chunk = String::from_utf8_lossy(&buffer[..n]).to_string();
-                        let _ = app_handle_stdout.emit("command_output", output_chunk);
+                        let output_chunk = process_output(
+                            &app_handle_stdout,
+                            &session_id_for_stdout,
+                            OutputStream::Stdout,
+                            String::from_utf8_lossy(&buffer[..n]).to_string(),
+                        );
+                        app_handle_stdout.state::<ScrollbackManager>().append(
+                            &session_id_for_stdout,
+                            OutputStream::Stdout,
+                            &output_chunk,
+                        );
+                        emit_command_output(
+                            &app_handle_stdout,
+                            &session_id_for_stdout,
+                            output_chunk,
+                        );
                     }
                     Err(e) => {
                         if e.kind() == std::io::ErrorKind::Interrupted {
                             continue;
                         }
-                        let _ = app_handle_stdout
-                            .emit("command_output", format!("Error reading stdout: {}", e));
+                        emit_command_output(
+                            &app_handle_stdout,
+                            &session_id_for_stdout,
+                            format!("Error reading stdout: {}", e),
+                        );
                         break;
                     }
                 }
@@ -869,6 +1470,7 @@ pub fn execute_sudo_command(
     // Use the taken stderr_stream
     if let Some(stderr_stream) = sudo_stderr {
         let app_handle_stderr = app_handle.clone();
+        let session_id_for_stderr = key.clone();
         thread::spawn(move || {
             let mut reader = BufReader::new(stderr_stream);
             let mut buffer = [0; 2048]; // Read in chunks
@@ -878,15 +1480,33 @@ pub fn execute_sudo_command(
                     Ok(n) => {
                         let error_chunk = String::from_utf8_lossy(&buffer[..n]).to_string();
                         if !error_chunk.contains("[sudo] password") {
-                            let _ = app_handle_stderr.emit("command_error", error_chunk.clone());
+                            let error_chunk = process_output(
+                                &app_handle_stderr,
+                                &session_id_for_stderr,
+                                OutputStream::Stderr,
+                                error_chunk,
+                            );
+                            app_handle_stderr.state::<ScrollbackManager>().append(
+                                &session_id_for_stderr,
+                                OutputStream::Stderr,
+                                &error_chunk,
+                            );
+                            emit_command_error(
+                                &app_handle_stderr,
+                                &session_id_for_stderr,
+                                error_chunk.clone(),
+                            );
                         }
                     }
                     Err(e) => {
                         if e.kind() == std::io::ErrorKind::Interrupted {
                             continue;
                         }
-                        let _ = app_handle_stderr
-                            .emit("command_error", format!("Error reading stderr: {}", e));
+                        emit_command_error(
+                            &app_handle_stderr,
+                            &session_id_for_stderr,
+                            format!("Error reading stderr: {}", e),
+                        );
                         break;
                     }
                 }
@@ -896,20 +1516,53 @@ pub fn execute_sudo_command(
 
     let child_arc_clone = child_arc.clone();
     let app_handle_wait = app_handle.clone();
+    let command_for_audit = format!("sudo {}", command);
     thread::spawn(move || {
-        let status = {
+        let started_at = std::time::Instant::now();
+        let (status, resources) = {
             let mut child_guard = child_arc_clone.lock().unwrap();
-            match child_guard.wait() {
-                Ok(status) => status,
+            match wait_with_usage(&mut child_guard) {
+                Ok(result) => result,
                 Err(e) => {
-                    let _ = app_handle_wait
-                        .emit("command_error", format!("Error waiting for command: {}", e));
+                    emit_command_error(
+                        &app_handle_wait,
+                        &key,
+                        format!("Error waiting for command: {}", e),
+                    );
                     return;
                 }
             }
         };
-
-        let _ = app_handle_wait.emit("command_end", format!("Success: {}", status.success()));
+        record_audit_event(
+            &app_handle_wait,
+            &key,
+            &command_for_audit,
+            &current_dir,
+            false,
+            status.code(),
+        );
+        record_history_entry(
+            &app_handle_wait,
+            &key,
+            &command_for_audit,
+            &current_dir,
+            status.code(),
+            None,
+        );
+
+        emit_command_end(
+            &app_handle_wait,
+            CommandEndEvent {
+                exit_code: status.code(),
+                duration_ms: Some(started_at.elapsed().as_millis() as u64),
+                resources,
+                ..CommandEndEvent::new(
+                    &key,
+                    status.success(),
+                    format!("Success: {}", status.success()),
+                )
+            },
+        );
     });
 
     Ok("Command started. Output will stream in realtime.".to_string())
@@ -919,17 +1572,12 @@ fn get_command_state<'a>(
     command_state_guard: &'a mut MutexGuard<HashMap<String, CommandState>>,
     session_id: String,
 ) -> &'a mut CommandState {
-    command_state_guard
-        .entry(session_id)
-        .or_insert_with(|| CommandState {
-            current_dir: env::current_dir()
+    command_state_guard.entry(session_id).or_insert_with(|| {
+        CommandState::new(
+            env::current_dir()
                 .unwrap_or_default()
                 .to_string_lossy()
                 .to_string(),
-            child_wait_handle: None,
-            child_stdin: None,
-            pid: None,
-            is_ssh_session_active: false, // ensure default
-            remote_current_dir: None,
-        })
+        )
+    })
 }
@@ -0,0 +1,238 @@
+use crate::command::constants::{
+    INPUT_IDLE_MS, INPUT_PROMPT_CHECK_INTERVAL_MS, INPUT_PROMPT_QUIET_MS, INPUT_PROMPT_TAIL_BYTES,
+};
+use crate::command::types::command_manager::CommandManager;
+use crate::command::windows::session_windows::emit_session_event;
+use regex::Regex;
+use serde::Serialize;
+use std::io::Write;
+use std::sync::{Arc, Mutex, OnceLock};
+use std::thread;
+use std::time::{Duration, Instant};
+use tauri::{command, AppHandle, Manager, Runtime, State};
+
+#[derive(Serialize, Clone, Copy, Debug, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum PromptKind {
+    // Input should be masked
+    Password,
+    Confirm,
+    // "Press Enter to continue"
+    Continue,
+    // Unrecognized line that looks like a question and has been waiting a while
+    Text,
+}
+
+// Matched against the unfinished last line of output; the first match decides the kind
+const PROMPT_RULES: &[(PromptKind, &str)] = &[
+    (
+        PromptKind::Password,
+        r"(?i)\b(password|passphrase|passcode|pin)\b[^\n:]*:\s*$",
+    ),
+    (
+        PromptKind::Confirm,
+        r"(?i)[\[(]\s*y(es)?\s*/\s*n(o)?\s*(/[^\])]*)?[\])]\s*[?:]?\s*$",
+    ),
+    (
+        PromptKind::Confirm,
+        r"(?i)\b(are you sure|continue|proceed|overwrite|replace|remove|delete)\b[^\n]*\?\s*$",
+    ),
+    (
+        PromptKind::Continue,
+        r"(?i)\bpress (enter|return|any key)\b[^\n]*$",
+    ),
+];
+
+fn prompt_rules() -> &'static Vec<(PromptKind, Regex)> {
+    static COMPILED: OnceLock<Vec<(PromptKind, Regex)>> = OnceLock::new();
+    COMPILED.get_or_init(|| {
+        PROMPT_RULES
+            .iter()
+            .map(|(kind, pattern)| (*kind, Regex::new(pattern).expect("valid prompt rule")))
+            .collect()
+    })
+}
+
+// Known prompts are reported after a short pause; anything else ending like a question only
+// once the command has been silent for INPUT_IDLE_MS
+fn classify_prompt(tail: &str, quiet: Duration) -> Option<PromptKind> {
+    if tail.trim().is_empty() || quiet < Duration::from_millis(INPUT_PROMPT_QUIET_MS) {
+        return None;
+    }
+    if let Some((kind, _)) = prompt_rules().iter().find(|(_, rule)| rule.is_match(tail)) {
+        return Some(*kind);
+    }
+    let looks_like_question = tail.trim_end().ends_with(['?', ':', '>']);
+    (looks_like_question && quiet >= Duration::from_millis(INPUT_IDLE_MS))
+        .then_some(PromptKind::Text)
+}
+
+struct WatchState {
+    // Output after the last newline (or carriage return) on stdout and stderr
+    tail: String,
+    last_output: Instant,
+    // input_requested already sent for the current tail
+    requested: bool,
+    finished: bool,
+}
+
+// Follows a local command's output to notice when it stops to ask for input
+pub struct PromptWatch {
+    state: Mutex<WatchState>,
+}
+
+impl PromptWatch {
+    pub fn new() -> Arc<Self> {
+        Arc::new(PromptWatch {
+            state: Mutex::new(WatchState {
+                tail: String::new(),
+                last_output: Instant::now(),
+                requested: false,
+                finished: false,
+            }),
+        })
+    }
+
+    // Called with every chunk read from the command's stdout or stderr
+    pub fn observe(&self, chunk: &str) {
+        let Ok(mut state) = self.state.lock() else {
+            return;
+        };
+        match chunk.rfind(['\n', '\r']) {
+            Some(pos) => state.tail = chunk[pos + 1..].to_string(),
+            None => state.tail.push_str(chunk),
+        }
+        if state.tail.len() > INPUT_PROMPT_TAIL_BYTES {
+            let mut start = state.tail.len() - INPUT_PROMPT_TAIL_BYTES;
+            while !state.tail.is_char_boundary(start) {
+                start += 1;
+            }
+            state.tail.drain(..start);
+        }
+        state.last_output = Instant::now();
+        state.requested = false;
+    }
+
+    // The answer went to stdin, so the prompt on screen is no longer pending
+    pub fn input_sent(&self) {
+        if let Ok(mut state) = self.state.lock() {
+            state.tail.clear();
+            state.last_output = Instant::now();
+            state.requested = false;
+        }
+    }
+
+    pub fn finish(&self) {
+        if let Ok(mut state) = self.state.lock() {
+            state.finished = true;
+        }
+    }
+}
+
+#[derive(Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+struct InputRequestedEvent {
+    session_id: String,
+    pid: u32,
+    prompt: String,
+    kind: PromptKind,
+    quiet_ms: u64,
+}
+
+// Polls the watch until the command exits and emits input_requested when it appears to be
+// waiting on stdin. Only commands whose stdin is still open can be answered.
+pub fn spawn_prompt_watcher<R: Runtime>(
+    app_handle: AppHandle<R>,
+    session_id: String,
+    pid: u32,
+    watch: Arc<PromptWatch>,
+) {
+    thread::spawn(move || loop {
+        thread::sleep(Duration::from_millis(INPUT_PROMPT_CHECK_INTERVAL_MS));
+        let request = {
+            let Ok(mut state) = watch.state.lock() else {
+                return;
+            };
+            if state.finished {
+                return;
+            }
+            if state.requested {
+                continue;
+            }
+            let quiet = state.last_output.elapsed();
+            let Some(kind) = classify_prompt(&state.tail, quiet) else {
+                continue;
+            };
+            state.requested = true;
+            (kind, state.tail.trim().to_string(), quiet)
+        };
+
+        let (still_running, stdin_open) = app_handle
+            .state::<CommandManager>()
+            .commands
+            .lock()
+            .ok()
+            .and_then(|states| {
+                states
+                    .get(&session_id)
+                    .map(|state| (state.pid == Some(pid), state.input.is_some()))
+            })
+            .unwrap_or((false, false));
+        if !still_running {
+            return;
+        }
+        if !stdin_open {
+            continue;
+        }
+
+        let (kind, prompt, quiet) = request;
+        emit_session_event(
+            &app_handle,
+            &session_id,
+            "input_requested",
+            InputRequestedEvent {
+                session_id: session_id.clone(),
+                pid,
+                prompt,
+                kind,
+                quiet_ms: quiet.as_millis() as u64,
+            },
+        );
+    });
+}
+
+// Writes one line to the stdin of the session's running local command
+#[command]
+pub fn send_command_input(
+    session_id: String,
+    input: String,
+    command_manager: State<'_, CommandManager>,
+) -> Result<(), String> {
+    let input_handle = {
+        let states = command_manager.commands.lock().map_err(|e| e.to_string())?;
+        states
+            .get(&session_id)
+            .and_then(|state| state.input.clone())
+            .ok_or_else(|| "No running command is accepting input".to_string())?
+    };
+    let mut stdin = input_handle.stdin.lock().map_err(|e| e.to_string())?;
+    stdin
+        .write_all(format!("{}\n", input.trim_end_matches(['\r', '\n'])).as_bytes())
+        .and_then(|_| stdin.flush())
+        .map_err(|e| format!("Failed to send input: {}", e))?;
+    input_handle.prompts.input_sent();
+    Ok(())
+}
+
+// Closes the command's stdin so it reads end-of-file, like Ctrl-D in a terminal
+#[command]
+pub fn close_command_input(
+    session_id: String,
+    command_manager: State<'_, CommandManager>,
+) -> Result<(), String> {
+    let mut states = command_manager.commands.lock().map_err(|e| e.to_string())?;
+    if let Some(state) = states.get_mut(&session_id) {
+        state.input = None;
+    }
+    Ok(())
+}
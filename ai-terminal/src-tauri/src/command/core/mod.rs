@@ -1,3 +1,22 @@
+pub mod active_commands;
+pub mod color_control;
+pub mod command_end;
+pub mod command_output;
+pub mod command_templates;
+pub mod command_words;
+pub mod cost_warnings;
 pub mod execute_command;
+pub mod input_prompts;
+pub mod output_capture;
+pub mod pager_disabling;
+pub mod pipeline_builder;
+pub mod port_conflicts;
+pub mod protected_sessions;
 pub mod pty;
+pub mod pty_fallback;
+pub mod pty_mouse;
+pub mod resource_usage;
+pub mod retry_command;
+pub mod session_variables;
+pub mod shell_options;
 pub mod terminate_command;
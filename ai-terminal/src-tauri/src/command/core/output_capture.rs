@@ -0,0 +1,160 @@
+use crate::audit::audit_log::record_audit_event;
+use crate::command::constants::{MAX_CAPTURES, MAX_CAPTURE_BYTES};
+use crate::command::core::command_templates::{expand_templates, session_template_context};
+use crate::command::core::session_variables::validate_name;
+use crate::command::environment::directory_env::session_env_overrides;
+use crate::command::types::command_manager::CommandManager;
+use crate::command::types::command_state::CommandState;
+use crate::history::recorder::record_history_entry;
+use crate::settings::types::settings_manager::SettingsManager;
+use crate::utils::env_scrubbing::scrubbed_environment;
+use crate::utils::file_system_utils::get_shell_path;
+use serde::Serialize;
+use std::collections::BTreeMap;
+use std::process::{Command, Stdio};
+use tauri::{command, AppHandle, Manager, Runtime, State};
+
+#[derive(Serialize, Clone, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct CapturedOutput {
+    pub name: String,
+    pub value: String,
+    // Stdout was longer than MAX_CAPTURE_BYTES; the value keeps its beginning
+    pub truncated: bool,
+    pub stderr: String,
+}
+
+// Like $(...): trailing newlines are dropped
+fn capture_value(stdout: &[u8]) -> (String, bool) {
+    let text = String::from_utf8_lossy(stdout);
+    let text = text.trim_end_matches(['\r', '\n']);
+    if text.len() <= MAX_CAPTURE_BYTES {
+        return (text.to_string(), false);
+    }
+    let mut end = MAX_CAPTURE_BYTES;
+    while !text.is_char_boundary(end) {
+        end -= 1;
+    }
+    (text[..end].to_string(), true)
+}
+
+// Runs a command in the session's directory and environment without streaming it, and stores
+// its stdout as {{capture:name}} for later commands and AI questions. Nothing is stored when
+// the command fails; the error carries its stderr.
+#[command]
+pub async fn capture_output<R: Runtime>(
+    command: String,
+    name: String,
+    session_id: String,
+    app_handle: AppHandle<R>,
+) -> Result<CapturedOutput, String> {
+    let name = name.trim().to_string();
+    validate_name(&name)?;
+    let (command, cwd, env_map) = {
+        let command_manager = app_handle.state::<CommandManager>();
+        let context = session_template_context(&command_manager, &session_id)?;
+        if context.is_remote {
+            return Err("Output can only be captured in a local session".to_string());
+        }
+        let command = expand_templates(command.trim(), &context)?;
+        if command.is_empty() {
+            return Err("No command to capture".to_string());
+        }
+
+        let mut env_map = scrubbed_environment(&app_handle.state::<SettingsManager>().current());
+        if !env_map.contains_key("PATH") {
+            if let Some(path_value) = get_shell_path() {
+                env_map.insert("PATH".to_string(), path_value);
+            }
+        }
+        env_map.insert("PWD".to_string(), context.cwd.clone());
+        env_map.extend(session_env_overrides(&command_manager, &session_id));
+        (command, context.cwd, env_map)
+    };
+
+    let output = {
+        let script = command.clone();
+        let dir = cwd.clone();
+        tauri::async_runtime::spawn_blocking(move || {
+            Command::new("sh")
+                .arg("-c")
+                .arg(&script)
+                .current_dir(&dir)
+                .env_clear()
+                .envs(&env_map)
+                .stdin(Stdio::null())
+                .output()
+        })
+        .await
+        .map_err(|e| e.to_string())?
+        .map_err(|e| format!("Failed to run '{}': {}", command, e))?
+    };
+
+    let exit_code = output.status.code();
+    record_audit_event(&app_handle, &session_id, &command, &cwd, false, exit_code);
+    record_history_entry(&app_handle, &session_id, &command, &cwd, exit_code, None);
+
+    let stderr = String::from_utf8_lossy(&output.stderr)
+        .trim_end()
+        .to_string();
+    if !output.status.success() {
+        return Err(match exit_code {
+            Some(code) => format!("'{}' exited with status {}: {}", command, code, stderr),
+            None => format!("'{}' was terminated by a signal: {}", command, stderr),
+        });
+    }
+
+    let (value, truncated) = capture_value(&output.stdout);
+    let command_manager = app_handle.state::<CommandManager>();
+    let mut states = command_manager.commands.lock().map_err(|e| e.to_string())?;
+    let state = states
+        .entry(session_id)
+        .or_insert_with(|| CommandState::new(cwd.clone()));
+    if !state.captures.contains_key(&name) && state.captures.len() >= MAX_CAPTURES {
+        return Err(format!(
+            "A session can hold at most {} captures",
+            MAX_CAPTURES
+        ));
+    }
+    state.captures.insert(name.clone(), value.clone());
+
+    Ok(CapturedOutput {
+        name,
+        value,
+        truncated,
+        stderr,
+    })
+}
+
+pub fn session_captures(
+    command_manager: &CommandManager,
+    session_id: &str,
+) -> BTreeMap<String, String> {
+    command_manager
+        .commands
+        .lock()
+        .ok()
+        .and_then(|states| states.get(session_id).map(|state| state.captures.clone()))
+        .unwrap_or_default()
+}
+
+#[command]
+pub fn list_captures(
+    session_id: String,
+    command_manager: State<'_, CommandManager>,
+) -> Result<BTreeMap<String, String>, String> {
+    Ok(session_captures(&command_manager, &session_id))
+}
+
+#[command]
+pub fn clear_capture(
+    session_id: String,
+    name: String,
+    command_manager: State<'_, CommandManager>,
+) -> Result<(), String> {
+    let mut states = command_manager.commands.lock().map_err(|e| e.to_string())?;
+    if let Some(state) = states.get_mut(&session_id) {
+        state.captures.remove(name.trim());
+    }
+    Ok(())
+}
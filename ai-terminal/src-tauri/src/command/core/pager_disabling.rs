@@ -0,0 +1,67 @@
+use crate::command::core::command_words::{insert_program_flags, program_name, program_words};
+use crate::settings::types::app_settings::{AppSettings, PagerRule};
+use std::collections::HashMap;
+
+// Pager variables set for commands run through execute_command; output is streamed, so a
+// pager would only sit waiting for keys that never come
+const PAGER_ENVIRONMENT: &[(&str, &str)] = &[
+    ("PAGER", "cat"),
+    ("GIT_PAGER", "cat"),
+    ("MANPAGER", "cat"),
+    ("SYSTEMD_PAGER", "cat"),
+    ("GH_PAGER", "cat"),
+    ("AWS_PAGER", ""),
+];
+
+fn rule_for<'a>(settings: &'a AppSettings, program: &str) -> Option<&'a PagerRule> {
+    settings
+        .pager_rules
+        .iter()
+        .find(|rule| rule.program == program)
+}
+
+// Adds each matching rule's flag after its program, unless the flag is already there
+pub fn no_pager_command(settings: &AppSettings, command: &str) -> String {
+    if !settings.pager_disabling_enabled {
+        return command.to_string();
+    }
+    insert_program_flags(command, |program| {
+        rule_for(settings, program)
+            .filter(|rule| rule.enabled)
+            .and_then(|rule| rule.flag.as_deref())
+    })
+}
+
+// PAGER_ENVIRONMENT, unless pager disabling is off or the command runs a program whose rule is
+// disabled
+pub fn pager_environment(settings: &AppSettings, command: &str) -> HashMap<String, String> {
+    let keeps_pager = program_words(command).into_iter().any(|word| {
+        rule_for(settings, program_name(&command[word])).is_some_and(|rule| !rule.enabled)
+    });
+    if !settings.pager_disabling_enabled || keeps_pager {
+        return HashMap::new();
+    }
+    PAGER_ENVIRONMENT
+        .iter()
+        .map(|(name, value)| (name.to_string(), value.to_string()))
+        .collect()
+}
+
+pub fn validate_pager_rules(rules: &[PagerRule]) -> Result<(), String> {
+    for rule in rules {
+        if rule.program.is_empty() || rule.program.contains(char::is_whitespace) {
+            return Err(format!("Invalid pager rule program '{}'", rule.program));
+        }
+        if rule
+            .flag
+            .as_deref()
+            .is_some_and(|flag| flag.trim().is_empty())
+        {
+            return Err(format!(
+                "Pager rule for '{}' has an empty flag",
+                rule.program
+            ));
+        }
+    }
+    Ok(())
+}
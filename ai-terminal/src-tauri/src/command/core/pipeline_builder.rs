@@ -0,0 +1,430 @@
+use crate::audit::audit_log::record_audit_event;
+use crate::command::constants::MAX_PIPELINE_STAGES;
+use crate::command::core::protected_sessions::require_command_gates;
+use crate::command::environment::directory_env::session_env_overrides;
+use crate::command::types::command_manager::CommandManager;
+use crate::command::types::scrollback_manager::{OutputStream, ScrollbackManager};
+use crate::history::recorder::record_history_entry;
+use crate::settings::types::settings_manager::SettingsManager;
+use crate::utils::env_scrubbing::scrubbed_environment;
+use crate::utils::file_system_utils::get_shell_path;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::io::Read;
+use std::path::{Path, PathBuf};
+use std::process::{Child, Command, ExitStatus, Stdio};
+use std::thread::{self, JoinHandle};
+use std::time::Instant;
+use tauri::{command, AppHandle, Emitter, Manager, Runtime, State};
+
+// One stage of a pipeline: a program and its arguments, never a shell string
+#[derive(Deserialize, Clone, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct PipelineStep {
+    pub command: String,
+    #[serde(default)]
+    pub args: Vec<String>,
+}
+
+#[derive(Serialize, Clone, Copy, PartialEq, Eq, Debug)]
+#[serde(rename_all = "lowercase")]
+pub enum StageStatus {
+    Running,
+    Succeeded,
+    Failed,
+    // Not started because an earlier stage could not be spawned
+    Skipped,
+}
+
+#[derive(Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct PipelineStarted {
+    pub pipeline_id: String,
+    // Shell-equivalent rendering of the pipeline, for display and history
+    pub display: String,
+}
+
+#[derive(Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+struct StageEvent {
+    pipeline_id: String,
+    session_id: String,
+    stage: usize,
+    command: String,
+    status: StageStatus,
+    exit_code: Option<i32>,
+    error: Option<String>,
+}
+
+#[derive(Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+struct PipelineOutputEvent {
+    pipeline_id: String,
+    session_id: String,
+    stage: usize,
+    stream: OutputStream,
+    data: String,
+}
+
+#[derive(Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+struct PipelineFinishedEvent {
+    pipeline_id: String,
+    session_id: String,
+    success: bool,
+    // First stage that exited unsuccessfully or could not be started
+    failed_stage: Option<usize>,
+    exit_code: Option<i32>,
+}
+
+fn display_word(word: &str) -> String {
+    let is_plain = !word.is_empty()
+        && word
+            .chars()
+            .all(|c| c.is_alphanumeric() || "_@%+=:,./-".contains(c));
+    if is_plain {
+        word.to_string()
+    } else {
+        format!("'{}'", word.replace('\'', "'\\''"))
+    }
+}
+
+fn display_step(step: &PipelineStep) -> String {
+    std::iter::once(&step.command)
+        .chain(&step.args)
+        .map(|word| display_word(word))
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+// Finds the program the way execvp would, but against the session's PATH
+fn resolve_program(program: &str, cwd: &str, path_value: &str) -> Option<PathBuf> {
+    if program.contains('/') {
+        let path = Path::new(cwd).join(program);
+        return path.is_file().then_some(path);
+    }
+    std::env::split_paths(path_value)
+        .map(|dir| dir.join(program))
+        .find(|candidate| candidate.is_file())
+}
+
+fn validate_steps(
+    steps: &[PipelineStep],
+    cwd: &str,
+    path_value: &str,
+) -> Result<Vec<PathBuf>, String> {
+    if steps.is_empty() {
+        return Err("A pipeline needs at least one step".to_string());
+    }
+    if steps.len() > MAX_PIPELINE_STAGES {
+        return Err(format!(
+            "A pipeline can have at most {} steps",
+            MAX_PIPELINE_STAGES
+        ));
+    }
+    steps
+        .iter()
+        .enumerate()
+        .map(|(index, step)| {
+            let program = step.command.as_str();
+            if program.is_empty() || program.chars().any(char::is_whitespace) {
+                return Err(format!(
+                    "Step {}: the command must be a single program name, with arguments in args",
+                    index + 1
+                ));
+            }
+            if std::iter::once(&step.command)
+                .chain(&step.args)
+                .any(|word| word.contains('\0'))
+            {
+                return Err(format!(
+                    "Step {}: arguments cannot contain NUL bytes",
+                    index + 1
+                ));
+            }
+            resolve_program(program, cwd, path_value).ok_or_else(|| {
+                format!(
+                    "Step {}: '{}' is not an executable on PATH (shell builtins cannot be used)",
+                    index + 1,
+                    program
+                )
+            })
+        })
+        .collect()
+}
+
+// A stage killed by SIGPIPE stopped because a later stage (e.g. `head`) finished reading,
+// which is not a failure
+fn stage_succeeded(status: &ExitStatus) -> bool {
+    #[cfg(unix)]
+    {
+        use std::os::unix::process::ExitStatusExt;
+        if status.signal() == Some(nix::libc::SIGPIPE) {
+            return true;
+        }
+    }
+    status.success()
+}
+
+fn forward_output<R: Runtime>(
+    app_handle: AppHandle<R>,
+    pipeline_id: String,
+    session_id: String,
+    stage: usize,
+    stream: OutputStream,
+    mut reader: impl Read + Send + 'static,
+) -> JoinHandle<()> {
+    thread::spawn(move || {
+        let mut buffer = [0u8; 8192];
+        loop {
+            let read = match reader.read(&mut buffer) {
+                Ok(0) | Err(_) => break,
+                Ok(read) => read,
+            };
+            let data = String::from_utf8_lossy(&buffer[..read]).to_string();
+            app_handle
+                .state::<ScrollbackManager>()
+                .append(&session_id, stream, &data);
+            let _ = app_handle.emit(
+                "pipeline_output",
+                PipelineOutputEvent {
+                    pipeline_id: pipeline_id.clone(),
+                    session_id: session_id.clone(),
+                    stage,
+                    stream,
+                    data,
+                },
+            );
+        }
+    })
+}
+
+struct PipelineRun<R: Runtime> {
+    app_handle: AppHandle<R>,
+    pipeline_id: String,
+    session_id: String,
+    steps: Vec<PipelineStep>,
+    // `a | b | c`, as audited and kept in history
+    display: String,
+}
+
+impl<R: Runtime> PipelineRun<R> {
+    fn emit_stage(
+        &self,
+        stage: usize,
+        status: StageStatus,
+        exit_code: Option<i32>,
+        error: Option<String>,
+    ) {
+        let _ = self.app_handle.emit(
+            "pipeline_stage",
+            StageEvent {
+                pipeline_id: self.pipeline_id.clone(),
+                session_id: self.session_id.clone(),
+                stage,
+                command: display_step(&self.steps[stage]),
+                status,
+                exit_code,
+                error,
+            },
+        );
+    }
+
+    fn forward(
+        &self,
+        stage: usize,
+        stream: OutputStream,
+        reader: impl Read + Send + 'static,
+    ) -> JoinHandle<()> {
+        forward_output(
+            self.app_handle.clone(),
+            self.pipeline_id.clone(),
+            self.session_id.clone(),
+            stage,
+            stream,
+            reader,
+        )
+    }
+
+    // Spawns every stage with each stdout feeding the next stdin, then waits for all of them
+    fn run(self, programs: Vec<PathBuf>, cwd: String, env_map: HashMap<String, String>) {
+        let started_at = Instant::now();
+        let mut children: Vec<Child> = Vec::new();
+        let mut readers: Vec<JoinHandle<()>> = Vec::new();
+        let mut previous_stdout: Option<Stdio> = None;
+        let mut spawn_failure: Option<usize> = None;
+        let last = self.steps.len() - 1;
+
+        for (stage, program) in programs.iter().enumerate() {
+            let spawned = Command::new(program)
+                .args(&self.steps[stage].args)
+                .current_dir(&cwd)
+                .env_clear()
+                .envs(&env_map)
+                .stdin(previous_stdout.take().unwrap_or_else(Stdio::null))
+                .stdout(Stdio::piped())
+                .stderr(Stdio::piped())
+                .spawn();
+            let mut child = match spawned {
+                Ok(child) => child,
+                Err(e) => {
+                    self.emit_stage(
+                        stage,
+                        StageStatus::Failed,
+                        None,
+                        Some(format!("Failed to start: {}", e)),
+                    );
+                    spawn_failure = Some(stage);
+                    break;
+                }
+            };
+            self.emit_stage(stage, StageStatus::Running, None, None);
+            if let Some(stderr) = child.stderr.take() {
+                readers.push(self.forward(stage, OutputStream::Stderr, stderr));
+            }
+            if let Some(stdout) = child.stdout.take() {
+                if stage == last {
+                    readers.push(self.forward(stage, OutputStream::Stdout, stdout));
+                } else {
+                    previous_stdout = Some(Stdio::from(stdout));
+                }
+            }
+            children.push(child);
+        }
+
+        let mut failed_stage = spawn_failure;
+        let mut exit_code = None;
+        if let Some(failed) = spawn_failure {
+            // Earlier stages would block on a pipe nobody reads
+            for child in children.iter_mut() {
+                let _ = child.kill();
+            }
+            for stage in failed + 1..self.steps.len() {
+                self.emit_stage(stage, StageStatus::Skipped, None, None);
+            }
+        }
+        for (stage, mut child) in children.into_iter().enumerate() {
+            match child.wait() {
+                Ok(status) => {
+                    let succeeded = spawn_failure.is_none() && stage_succeeded(&status);
+                    if !succeeded && failed_stage.is_none() {
+                        failed_stage = Some(stage);
+                    }
+                    if stage == last {
+                        exit_code = status.code();
+                    }
+                    let status_kind = if succeeded {
+                        StageStatus::Succeeded
+                    } else {
+                        StageStatus::Failed
+                    };
+                    self.emit_stage(stage, status_kind, status.code(), None);
+                }
+                Err(e) => {
+                    failed_stage.get_or_insert(stage);
+                    self.emit_stage(stage, StageStatus::Failed, None, Some(e.to_string()));
+                }
+            }
+        }
+        for reader in readers {
+            let _ = reader.join();
+        }
+
+        record_audit_event(
+            &self.app_handle,
+            &self.session_id,
+            &self.display,
+            &cwd,
+            false,
+            exit_code,
+        );
+        record_history_entry(
+            &self.app_handle,
+            &self.session_id,
+            &self.display,
+            &cwd,
+            exit_code,
+            Some(started_at.elapsed().as_millis() as u64),
+        );
+        let _ = self.app_handle.emit(
+            "pipeline_finished",
+            PipelineFinishedEvent {
+                pipeline_id: self.pipeline_id.clone(),
+                session_id: self.session_id.clone(),
+                success: failed_stage.is_none(),
+                failed_stage,
+                exit_code,
+            },
+        );
+    }
+}
+
+// Validates structured steps and runs them as `a | b | c` without a shell, so arguments are
+// never reinterpreted. Progress arrives as pipeline_stage / pipeline_output events and a final
+// pipeline_finished naming the stage that failed, if any. Like execute_command, pipelines are
+// audited, kept in history and go through the protected-session and cost gates; a held one
+// fails with the confirmation marker until it is built again with the token.
+#[command]
+pub fn build_pipeline<R: Runtime>(
+    session_id: String,
+    steps: Vec<PipelineStep>,
+    confirmation_token: Option<String>,
+    app_handle: AppHandle<R>,
+    command_manager: State<'_, CommandManager>,
+) -> Result<PipelineStarted, String> {
+    let cwd = {
+        let states = command_manager.commands.lock().map_err(|e| e.to_string())?;
+        match states.get(&session_id) {
+            Some(state) if state.is_ssh_session_active => {
+                return Err("Pipelines cannot be built in an SSH session".to_string())
+            }
+            Some(state) => state.current_dir.clone(),
+            None => std::env::current_dir()
+                .map_err(|e| e.to_string())?
+                .to_string_lossy()
+                .to_string(),
+        }
+    };
+
+    let mut env_map: HashMap<String, String> =
+        scrubbed_environment(&app_handle.state::<SettingsManager>().current());
+    if !env_map.contains_key("PATH") {
+        if let Some(path_value) = get_shell_path() {
+            env_map.insert("PATH".to_string(), path_value);
+        }
+    }
+    env_map.insert("PWD".to_string(), cwd.clone());
+    env_map.extend(session_env_overrides(&command_manager, &session_id));
+
+    let path_value = env_map.get("PATH").cloned().unwrap_or_default();
+    let programs = validate_steps(&steps, &cwd, &path_value)?;
+    let display = steps
+        .iter()
+        .map(display_step)
+        .collect::<Vec<_>>()
+        .join(" | ");
+    require_command_gates(
+        &app_handle,
+        &session_id,
+        &display,
+        confirmation_token.as_deref(),
+    )?;
+    app_handle
+        .state::<ScrollbackManager>()
+        .append(&session_id, OutputStream::Input, &display);
+
+    let pipeline_id = uuid::Uuid::new_v4().to_string();
+    let run = PipelineRun {
+        app_handle: app_handle.clone(),
+        pipeline_id: pipeline_id.clone(),
+        session_id,
+        steps,
+        display: display.clone(),
+    };
+    thread::spawn(move || run.run(programs, cwd, env_map));
+
+    Ok(PipelineStarted {
+        pipeline_id,
+        display,
+    })
+}
@@ -0,0 +1,270 @@
+use crate::command::constants::PORT_CHECK_TIMEOUT_MS;
+use crate::command::core::cost_warnings::is_command_prefix;
+use crate::command::windows::session_windows::emit_session_event;
+use crate::settings::types::settings_manager::SettingsManager;
+use serde::Serialize;
+use std::collections::HashMap;
+use std::net::{IpAddr, Ipv4Addr, Ipv6Addr, SocketAddr, TcpStream};
+use std::process::Command;
+use std::time::Duration;
+use tauri::{command, AppHandle, Runtime, State};
+
+pub const PORT_CONFLICT_EVENT: &str = "port_conflict";
+
+// A development server command and where it finds its port
+struct DevServer {
+    words: &'static [&'static str],
+    default_port: u16,
+    // Reads PORT from the environment when no flag is given
+    port_env: bool,
+    // Takes the port (or addr:port) as a plain argument, like `http.server 8080`
+    positional_port: bool,
+}
+
+const fn dev_server(words: &'static [&'static str], default_port: u16) -> DevServer {
+    DevServer {
+        words,
+        default_port,
+        port_env: false,
+        positional_port: false,
+    }
+}
+
+const fn port_env(server: DevServer) -> DevServer {
+    DevServer {
+        port_env: true,
+        ..server
+    }
+}
+
+const fn positional_port(server: DevServer) -> DevServer {
+    DevServer {
+        positional_port: true,
+        ..server
+    }
+}
+
+// More specific entries come first
+const DEV_SERVERS: &[DevServer] = &[
+    port_env(dev_server(&["rails", "server"], 3000)),
+    port_env(dev_server(&["rails", "s"], 3000)),
+    port_env(dev_server(&["next", "dev"], 3000)),
+    port_env(dev_server(&["next", "start"], 3000)),
+    port_env(dev_server(&["nuxt", "dev"], 3000)),
+    port_env(dev_server(&["remix", "dev"], 3000)),
+    port_env(dev_server(&["react-scripts", "start"], 3000)),
+    dev_server(&["vite", "preview"], 4173),
+    dev_server(&["vite"], 5173),
+    dev_server(&["astro", "dev"], 4321),
+    dev_server(&["ng", "serve"], 4200),
+    dev_server(&["webpack", "serve"], 8080),
+    dev_server(&["webpack-dev-server"], 8080),
+    port_env(dev_server(&["gatsby", "develop"], 8000)),
+    dev_server(&["jekyll", "serve"], 4000),
+    dev_server(&["hugo", "server"], 1313),
+    dev_server(&["flask", "run"], 5000),
+    dev_server(&["uvicorn"], 8000),
+    positional_port(dev_server(&["python", "-m", "http.server"], 8000)),
+    positional_port(dev_server(&["python3", "-m", "http.server"], 8000)),
+    positional_port(dev_server(&["python", "manage.py", "runserver"], 8000)),
+    positional_port(dev_server(&["python3", "manage.py", "runserver"], 8000)),
+    positional_port(dev_server(&["./manage.py", "runserver"], 8000)),
+    dev_server(&["php", "artisan", "serve"], 8000),
+    port_env(dev_server(&["mix", "phx.server"], 4000)),
+    dev_server(&["rackup"], 9292),
+];
+
+// Wrappers that run the dev server named after them
+const RUNNERS: &[&[&str]] = &[
+    &["npx"],
+    &["bunx"],
+    &["pnpx"],
+    &["yarn"],
+    &["pnpm", "exec"],
+    &["bundle", "exec"],
+    &["poetry", "run"],
+    &["pipenv", "run"],
+    &["uv", "run"],
+];
+
+#[derive(Serialize, Clone, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct PortConflict {
+    // The part of a chained command that starts the server
+    pub matched_command: String,
+    pub port: u16,
+    // Process listening on the port, when the OS tells us
+    pub pid: Option<u32>,
+    pub process: Option<String>,
+}
+
+#[derive(Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+struct PortConflictEvent {
+    session_id: String,
+    command: String,
+    conflict: PortConflict,
+}
+
+fn parse_port(value: &str) -> Option<u16> {
+    // addr:port forms, e.g. `runserver 0.0.0.0:8001`
+    let value = value.rsplit(':').next().unwrap_or(value);
+    value.parse::<u16>().ok().filter(|port| *port != 0)
+}
+
+fn long_port_flag(args: &[&str]) -> Option<u16> {
+    args.iter()
+        .enumerate()
+        .find_map(|(index, arg)| match arg.strip_prefix("--port") {
+            Some("") => args.get(index + 1).and_then(|value| parse_port(value)),
+            Some(value) => value.strip_prefix('=').and_then(parse_port),
+            None => None,
+        })
+}
+
+// Port given with --port/-p (or -P for jekyll), or positionally for servers that take it so
+fn flag_port(server: &DevServer, args: &[&str]) -> Option<u16> {
+    let mut iter = args.iter();
+    while let Some(arg) = iter.next() {
+        if let Some(value) = arg.strip_prefix("--port=") {
+            return parse_port(value);
+        }
+        if matches!(*arg, "--port" | "-p" | "-P") {
+            return iter.next().and_then(|value| parse_port(value));
+        }
+        if server.positional_port && !arg.starts_with('-') {
+            if let Some(port) = parse_port(arg) {
+                return Some(port);
+            }
+        }
+    }
+    None
+}
+
+// The port a command segment's dev server will try to bind, if it starts one
+fn segment_port(segment: &str, env_map: &HashMap<String, String>) -> Option<u16> {
+    let words: Vec<&str> = segment.split_whitespace().collect();
+    let program_at = words.iter().position(|word| !is_command_prefix(word))?;
+    let assigned_port = words[..program_at]
+        .iter()
+        .find_map(|word| word.strip_prefix("PORT="))
+        .and_then(parse_port);
+
+    let mut rest = &words[program_at..];
+    while let Some(runner) = RUNNERS.iter().find(|runner| rest.starts_with(runner)) {
+        rest = &rest[runner.len()..];
+    }
+    let Some(server) = DEV_SERVERS
+        .iter()
+        .find(|server| rest.starts_with(server.words))
+    else {
+        // Other servers count once given an explicit --port, e.g. `npm run dev -- --port 3000`
+        return long_port_flag(rest);
+    };
+    let env_port =
+        || assigned_port.or_else(|| env_map.get("PORT").and_then(|port| parse_port(port)));
+    flag_port(server, &rest[server.words.len()..])
+        .or_else(|| server.port_env.then(env_port).flatten())
+        .or(Some(server.default_port))
+}
+
+fn is_port_bound(port: u16) -> bool {
+    [
+        IpAddr::V4(Ipv4Addr::LOCALHOST),
+        IpAddr::V6(Ipv6Addr::LOCALHOST),
+    ]
+    .into_iter()
+    .any(|ip| {
+        TcpStream::connect_timeout(
+            &SocketAddr::new(ip, port),
+            Duration::from_millis(PORT_CHECK_TIMEOUT_MS),
+        )
+        .is_ok()
+    })
+}
+
+// Pid and command line of the process listening on a port, from lsof and ps
+fn port_owner(port: u16) -> Option<(u32, Option<String>)> {
+    if cfg!(windows) {
+        return None;
+    }
+    let output = Command::new("lsof")
+        .args(["-nP", &format!("-iTCP:{}", port), "-sTCP:LISTEN", "-Fp"])
+        .output()
+        .ok()?;
+    let pid = String::from_utf8_lossy(&output.stdout)
+        .lines()
+        .find_map(|line| {
+            line.strip_prefix('p')
+                .and_then(|pid| pid.parse::<u32>().ok())
+        })?;
+    let process = Command::new("ps")
+        .args(["-o", "args=", "-p", &pid.to_string()])
+        .output()
+        .ok()
+        .map(|output| String::from_utf8_lossy(&output.stdout).trim().to_string())
+        .filter(|args| !args.is_empty());
+    Some((pid, process))
+}
+
+// First dev server in a `;`, `&&`, `||` or `|` chain whose port is already taken
+pub fn find_port_conflict(
+    command: &str,
+    env_map: &HashMap<String, String>,
+) -> Option<PortConflict> {
+    command
+        .split([';', '&', '|', '\n'])
+        .map(str::trim)
+        .filter(|segment| !segment.is_empty())
+        .find_map(|segment| {
+            let port = segment_port(segment, env_map)?;
+            if !is_port_bound(port) {
+                return None;
+            }
+            let (pid, process) = match port_owner(port) {
+                Some((pid, process)) => (Some(pid), process),
+                None => (None, None),
+            };
+            Some(PortConflict {
+                matched_command: segment.to_string(),
+                port,
+                pid,
+                process,
+            })
+        })
+}
+
+// Emits port_conflict before a dev server starts on a port something else holds. The command
+// still runs; the warning saves reading "address already in use" from its output.
+pub fn warn_port_conflict<R: Runtime>(
+    app_handle: &AppHandle<R>,
+    session_id: &str,
+    command: &str,
+    env_map: &HashMap<String, String>,
+) {
+    if let Some(conflict) = find_port_conflict(command, env_map) {
+        emit_session_event(
+            app_handle,
+            session_id,
+            PORT_CONFLICT_EVENT,
+            PortConflictEvent {
+                session_id: session_id.to_string(),
+                command: command.to_string(),
+                conflict,
+            },
+        );
+    }
+}
+
+// Lets the input show the warning while the command is being typed
+#[command]
+pub fn check_port_conflict(
+    command: String,
+    settings_manager: State<'_, SettingsManager>,
+) -> Option<PortConflict> {
+    if !settings_manager.current().port_conflict_warnings_enabled {
+        return None;
+    }
+    let env_map: HashMap<String, String> = std::env::vars().collect();
+    find_port_conflict(&command, &env_map)
+}
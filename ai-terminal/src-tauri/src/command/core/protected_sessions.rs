@@ -0,0 +1,155 @@
+use crate::command::core::cost_warnings::{check_cost_gate, program_part};
+use crate::command::types::confirmation_manager::{
+    ConfirmationManager, CONFIRMATION_REQUIRED_EVENT, CONFIRMATION_REQUIRED_MARKER,
+};
+use crate::layout::types::layout_manager::LayoutManager;
+use crate::settings::types::app_settings::AppSettings;
+use crate::settings::types::settings_manager::SettingsManager;
+use regex::Regex;
+use tauri::{AppHandle, Emitter, Manager, Runtime};
+
+pub fn is_session_protected<R: Runtime>(app_handle: &AppHandle<R>, session_id: &str) -> bool {
+    app_handle
+        .state::<LayoutManager>()
+        .meta(session_id)
+        .protected
+}
+
+// The first command in a `;`, `&&`, `||` or `|` chain matching a danger pattern
+pub fn dangerous_command(settings: &AppSettings, command: &str) -> Option<String> {
+    let patterns: Vec<Regex> = settings
+        .danger_patterns
+        .iter()
+        .filter_map(|pattern| Regex::new(pattern).ok())
+        .collect();
+    command
+        .split([';', '&', '|', '\n'])
+        .map(program_part)
+        .filter(|segment| !segment.is_empty())
+        .find(|segment| patterns.iter().any(|regex| regex.is_match(segment)))
+        .map(str::to_string)
+}
+
+pub fn validate_danger_patterns(patterns: &[String]) -> Result<(), String> {
+    for pattern in patterns {
+        Regex::new(pattern).map_err(|e| format!("Invalid danger pattern '{}': {}", pattern, e))?;
+    }
+    Ok(())
+}
+
+// Consumes `confirmation_token` for `scope`. Without a valid one, a new request is emitted as
+// confirmation_required and false is returned; the caller must stop and return the marker.
+pub fn confirm_protected_action<R: Runtime>(
+    app_handle: &AppHandle<R>,
+    scope: &str,
+    title: &str,
+    detail: &str,
+    confirmation_token: Option<&str>,
+) -> Result<bool, String> {
+    let confirmation_manager = app_handle.state::<ConfirmationManager>();
+    let confirmed = confirmation_token
+        .map(|token| confirmation_manager.consume(token, scope))
+        .unwrap_or(false);
+    if confirmed {
+        return Ok(true);
+    }
+    let request = confirmation_manager.issue(scope, title, detail)?;
+    app_handle
+        .emit(CONFIRMATION_REQUIRED_EVENT, request)
+        .map_err(|e| e.to_string())?;
+    Ok(false)
+}
+
+// None when the session is not protected or the command is not dangerous; otherwise whether
+// the command was confirmed
+pub fn check_protected_command<R: Runtime>(
+    app_handle: &AppHandle<R>,
+    session_id: &str,
+    command: &str,
+    confirmation_token: Option<&str>,
+) -> Result<Option<bool>, String> {
+    if !is_session_protected(app_handle, session_id) {
+        return Ok(None);
+    }
+    let settings = app_handle.state::<SettingsManager>().current();
+    let Some(matched) = dangerous_command(&settings, command) else {
+        return Ok(None);
+    };
+    confirm_protected_action(
+        app_handle,
+        &format!("protected:command:{}:{}", session_id, command),
+        "Run a dangerous command in a protected session?",
+        &format!("{}\n\nMatched: {}", command, matched),
+        confirmation_token,
+    )
+    .map(Some)
+}
+
+// The gates every way of running a command goes through: protected sessions hold dangerous
+// commands until confirmed; other commands get cost warnings, and some cost rules hold the
+// command until confirmed too. False means the caller must stop and return the marker.
+pub fn check_command_gates<R: Runtime>(
+    app_handle: &AppHandle<R>,
+    session_id: &str,
+    command: &str,
+    confirmation_token: Option<&str>,
+) -> Result<bool, String> {
+    match check_protected_command(app_handle, session_id, command, confirmation_token)? {
+        Some(confirmed) => Ok(confirmed),
+        None => check_cost_gate(app_handle, session_id, command, confirmation_token),
+    }
+}
+
+// check_command_gates for commands that return data rather than a message
+pub fn require_command_gates<R: Runtime>(
+    app_handle: &AppHandle<R>,
+    session_id: &str,
+    command: &str,
+    confirmation_token: Option<&str>,
+) -> Result<(), String> {
+    if check_command_gates(app_handle, session_id, command, confirmation_token)? {
+        Ok(())
+    } else {
+        Err(CONFIRMATION_REQUIRED_MARKER.to_string())
+    }
+}
+
+// For gated commands that return data rather than a message: Err with the confirmation marker
+// until a dangerous command in a protected session is confirmed
+pub fn require_protected_confirmation<R: Runtime>(
+    app_handle: &AppHandle<R>,
+    session_id: &str,
+    command: &str,
+    confirmation_token: Option<&str>,
+) -> Result<(), String> {
+    match check_protected_command(app_handle, session_id, command, confirmation_token)? {
+        Some(false) => Err(CONFIRMATION_REQUIRED_MARKER.to_string()),
+        _ => Ok(()),
+    }
+}
+
+// Whether Enter may submit `line`, typed into a protected session. A line that could not be
+// followed keystroke by keystroke (None) always needs confirmation.
+pub fn confirm_typed_line<R: Runtime>(
+    app_handle: &AppHandle<R>,
+    session_id: &str,
+    line: Option<&str>,
+    confirmation_token: Option<&str>,
+) -> Result<bool, String> {
+    match line {
+        Some(line) => {
+            Ok(
+                check_protected_command(app_handle, session_id, line, confirmation_token)?
+                    .unwrap_or(true),
+            )
+        }
+        None => confirm_protected_action(
+            app_handle,
+            &format!("protected:input:{}", session_id),
+            "Run an edited command line in a protected session?",
+            "The line was recalled from history, completed or edited with the cursor keys, so \
+             it could not be checked against the danger patterns.",
+            confirmation_token,
+        ),
+    }
+}
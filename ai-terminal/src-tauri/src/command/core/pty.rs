@@ -1,11 +1,34 @@
-use crate::command::types::pty_manager::{PtyManager, PtySession};
+use crate::appearance::appearance_commands::apply_appearance_env;
+use crate::command::containers::kubernetes::reconnect_kubernetes_session;
+use crate::command::core::protected_sessions::{
+    confirm_protected_action, confirm_typed_line, is_session_protected,
+};
+use crate::command::environment::session_tmpdir::{existing_session_tmpdir, remove_session_tmpdir};
+use crate::command::output::bell::{forget_bell_session, track_bell};
+use crate::command::output::post_processors::process_output;
+use crate::command::output::progress::{finish_progress, track_progress};
+use crate::command::output::screen_state::{
+    forget_screen_session, is_alt_screen, reset_screen, resize_screen, track_screen,
+};
+use crate::command::types::command_manager::CommandManager;
+use crate::command::types::confirmation_manager::CONFIRMATION_REQUIRED_MARKER;
+use crate::command::types::macro_manager::MacroManager;
+use crate::command::types::pty_manager::{PtyManager, PtySession, SessionTarget};
+use crate::command::types::scrollback_manager::{OutputStream, ScrollbackManager};
+use crate::command::windows::session_windows::emit_session_event;
+use crate::settings::types::settings_manager::SettingsManager;
+use crate::utils::config_utils::unix_timestamp_millis;
+use crate::utils::env_scrubbing::scrubbed_environment;
 use portable_pty::{native_pty_system, CommandBuilder, PtySize};
 use serde::Serialize;
 use std::path::Path;
 use std::io::{Read, Write};
 use std::sync::{Arc, Mutex};
 use std::thread;
-use tauri::{command, AppHandle, Emitter, Manager, State};
+use tauri::{command, AppHandle, Manager, Runtime, State};
+
+// Returned by pty_close_session once the session is gone
+pub const PTY_SESSION_CLOSED_MESSAGE: &str = "PTY session closed.";
 
 #[derive(Serialize, Clone)]
 #[serde(rename_all = "camelCase")]
@@ -22,23 +45,13 @@ pub struct PtyExitEvent {
 }
 
 #[command]
-pub fn pty_create_session(
+pub fn pty_create_session<R: Runtime>(
     session_id: String,
     cols: u16,
     rows: u16,
-    app_handle: AppHandle,
+    app_handle: AppHandle<R>,
     pty_manager: State<'_, PtyManager>,
 ) -> Result<(), String> {
-    let pty_system = native_pty_system();
-    let pair = pty_system
-        .openpty(PtySize {
-            rows,
-            cols,
-            pixel_width: 0,
-            pixel_height: 0,
-        })
-        .map_err(|e| format!("Failed to open PTY: {e}"))?;
-
     // Prefer a clean bash session for embedded PTY stability.
     // This avoids shell theme artifacts and prompt control sequences.
     let preferred_bash = "/bin/bash";
@@ -48,6 +61,10 @@ pub fn pty_create_session(
         std::env::var("SHELL").unwrap_or_else(|_| "/bin/zsh".to_string())
     };
     let mut command = CommandBuilder::new(shell.clone());
+    command.env_clear();
+    for (name, value) in scrubbed_environment(&app_handle.state::<SettingsManager>().current()) {
+        command.env(name, value);
+    }
     if shell.ends_with("bash") {
         command.arg("--noprofile");
         command.arg("--norc");
@@ -64,10 +81,50 @@ pub fn pty_create_session(
     command.arg("-i");
     command.env("TERM", "xterm-256color");
     command.env("COLORTERM", "truecolor");
+    apply_appearance_env(&app_handle, &mut command);
+    // Only a dir requested before the shell starts; the shell's environment is fixed after that
+    if let Some(tmp_dir) =
+        existing_session_tmpdir(&app_handle.state::<CommandManager>(), &session_id)
+    {
+        for name in ["TMPDIR", "TMP", "TEMP"] {
+            command.env(name, &tmp_dir);
+        }
+    }
 
     let cwd = std::env::current_dir().map_err(|e| format!("Failed to get cwd: {e}"))?;
     command.cwd(cwd);
 
+    spawn_pty_session(
+        &app_handle,
+        &pty_manager,
+        session_id,
+        cols,
+        rows,
+        command,
+        None,
+    )
+}
+
+// Spawns `command` on a fresh PTY and streams its output as pty_output events until it exits
+pub fn spawn_pty_session<R: Runtime>(
+    app_handle: &AppHandle<R>,
+    pty_manager: &PtyManager,
+    session_id: String,
+    cols: u16,
+    rows: u16,
+    command: CommandBuilder,
+    target: Option<SessionTarget>,
+) -> Result<(), String> {
+    let pty_system = native_pty_system();
+    let pair = pty_system
+        .openpty(PtySize {
+            rows,
+            cols,
+            pixel_width: 0,
+            pixel_height: 0,
+        })
+        .map_err(|e| format!("Failed to open PTY: {e}"))?;
+
     let child = pair
         .slave
         .spawn_command(command)
@@ -97,10 +154,14 @@ pub fn pty_create_session(
                 master: pair.master,
                 writer: writer.clone(),
                 child: child.clone(),
+                target,
+                started_at: unix_timestamp_millis(),
+                input_line: Some(String::new()),
             },
         );
     }
 
+    reset_screen(app_handle, &session_id, rows, cols);
     let emit_handle = app_handle.clone();
     let session_id_for_reader = session_id.clone();
     thread::spawn(move || {
@@ -111,7 +172,24 @@ pub fn pty_create_session(
             if data.is_empty() {
                 return;
             }
-            let _ = emit_handle.emit(
+            // Before post-processing, which may rewrite escape sequences
+            track_bell(&emit_handle, &session_id_for_reader, &data);
+            let data = process_output(
+                &emit_handle,
+                &session_id_for_reader,
+                OutputStream::Pty,
+                data,
+            );
+            let retained = track_screen(&emit_handle, &session_id_for_reader, &data);
+            emit_handle.state::<ScrollbackManager>().append(
+                &session_id_for_reader,
+                OutputStream::Pty,
+                &retained,
+            );
+            track_progress(&emit_handle, &session_id_for_reader, &data);
+            emit_session_event(
+                &emit_handle,
+                &session_id_for_reader,
                 "pty_output",
                 PtyOutputEvent {
                     session_id: session_id_for_reader.clone(),
@@ -187,12 +265,32 @@ pub fn pty_create_session(
             .map(|status| status.success())
             .unwrap_or(false);
 
+        finish_progress(&wait_handle, &wait_session_id);
         let manager = wait_handle.state::<PtyManager>();
-        if let Ok(mut sessions) = manager.sessions.lock() {
-            sessions.remove(&wait_session_id);
+        let removed = manager
+            .sessions
+            .lock()
+            .ok()
+            .and_then(|mut sessions| sessions.remove(&wait_session_id));
+
+        // A session still in the map ended on its own (not via pty_close_session), so a
+        // Kubernetes exec may just have lost its pod to a restart
+        if let Some(session) = removed {
+            if let Some(target @ SessionTarget::Kubernetes { .. }) = session.target.clone() {
+                let size = session.master.get_size().ok();
+                drop(session);
+                if reconnect_kubernetes_session(&wait_handle, &wait_session_id, target, size) {
+                    return;
+                }
+            }
         }
 
-        let _ = wait_handle.emit(
+        forget_bell_session(&wait_handle, &wait_session_id);
+        forget_screen_session(&wait_handle, &wait_session_id);
+        remove_session_tmpdir(&wait_handle.state::<CommandManager>(), &wait_session_id);
+        emit_session_event(
+            &wait_handle,
+            &wait_session_id,
             "pty_exit",
             PtyExitEvent {
                 session_id: wait_session_id.clone(),
@@ -204,20 +302,50 @@ pub fn pty_create_session(
     Ok(())
 }
 
+// Container (or other remote target) the session's shell runs in, if any
 #[command]
-pub fn pty_write(
+pub fn get_pty_session_target(
     session_id: String,
-    data: String,
     pty_manager: State<'_, PtyManager>,
+) -> Result<Option<SessionTarget>, String> {
+    let sessions = pty_manager.sessions.lock().map_err(|e| e.to_string())?;
+    Ok(sessions
+        .get(&session_id)
+        .and_then(|session| session.target.clone()))
+}
+
+// Sends input to a PTY session as if it were typed
+pub fn write_pty_input(
+    pty_manager: &PtyManager,
+    session_id: &str,
+    data: &str,
+) -> Result<(), String> {
+    write_pty_bytes(pty_manager, session_id, data.as_bytes())?;
+    if let Some(session) = pty_manager
+        .sessions
+        .lock()
+        .map_err(|e| e.to_string())?
+        .get_mut(session_id)
+    {
+        track_typed_input(&mut session.input_line, data);
+    }
+    Ok(())
+}
+
+// Like write_pty_input, for input that is not UTF-8 such as legacy mouse reports
+pub fn write_pty_bytes(
+    pty_manager: &PtyManager,
+    session_id: &str,
+    data: &[u8],
 ) -> Result<(), String> {
     let sessions = pty_manager.sessions.lock().map_err(|e| e.to_string())?;
     let session = sessions
-        .get(&session_id)
+        .get(session_id)
         .ok_or_else(|| format!("PTY session '{}' not found", session_id))?;
 
     let mut writer = session.writer.lock().map_err(|e| e.to_string())?;
     writer
-        .write_all(data.as_bytes())
+        .write_all(data)
         .map_err(|e| format!("Failed to write PTY input: {e}"))?;
     writer
         .flush()
@@ -225,11 +353,130 @@ pub fn pty_write(
     Ok(())
 }
 
+// Follows typed input the way a line editor would, as far as keystrokes allow
+fn track_typed_input(line: &mut Option<String>, typed: &str) {
+    for c in typed.chars() {
+        match c {
+            '\x7f' | '\x08' => {
+                if let Some(line) = line {
+                    line.pop();
+                }
+            }
+            // Enter submits the line; Ctrl-C and Ctrl-U discard it
+            '\r' | '\n' | '\x03' | '\x15' => *line = Some(String::new()),
+            c if c.is_control() => *line = None,
+            c => {
+                if let Some(line) = line {
+                    line.push(c);
+                }
+            }
+        }
+    }
+}
+
+fn typed_line(pty_manager: &PtyManager, session_id: &str) -> Result<Option<String>, String> {
+    let sessions = pty_manager.sessions.lock().map_err(|e| e.to_string())?;
+    let session = sessions
+        .get(session_id)
+        .ok_or_else(|| format!("PTY session '{}' not found", session_id))?;
+    Ok(session.input_line.clone())
+}
+
+// Sends typed input. In a protected session the line is checked against the danger patterns
+// when Enter is pressed; a dangerous line stays at the prompt, without the Enter, and the
+// confirmation marker is returned until pty_write is called again with the token. Full-screen
+// apps get their input unchecked.
 #[command]
-pub fn pty_resize(
+pub fn pty_write<R: Runtime>(
+    session_id: String,
+    data: String,
+    confirmation_token: Option<String>,
+    app_handle: AppHandle<R>,
+    pty_manager: State<'_, PtyManager>,
+    macro_manager: State<'_, MacroManager>,
+) -> Result<String, String> {
+    let send = |input: &str| -> Result<(), String> {
+        if !input.is_empty() {
+            write_pty_input(&pty_manager, &session_id, input)?;
+            macro_manager.record_input(&session_id, input);
+        }
+        Ok(())
+    };
+    if !is_session_protected(&app_handle, &session_id) || is_alt_screen(&app_handle, &session_id) {
+        send(&data)?;
+        return Ok(String::new());
+    }
+
+    let mut rest = data.as_str();
+    while let Some(end) = rest.find(['\r', '\n']) {
+        let (typed, tail) = rest.split_at(end);
+        send(typed)?;
+        let line = typed_line(&pty_manager, &session_id)?;
+        if !confirm_typed_line(
+            &app_handle,
+            &session_id,
+            line.as_deref(),
+            confirmation_token.as_deref(),
+        )? {
+            return Ok(CONFIRMATION_REQUIRED_MARKER.to_string());
+        }
+        let (enter, tail) = tail.split_at(1);
+        send(enter)?;
+        rest = tail;
+    }
+    send(rest)?;
+    Ok(String::new())
+}
+
+// Writes the same input to several sessions, e.g. to run one command on a group of hosts.
+// Protected targets need confirmation first; nothing is written until it is given.
+#[command]
+pub fn pty_broadcast<R: Runtime>(
+    session_ids: Vec<String>,
+    data: String,
+    confirmation_token: Option<String>,
+    app_handle: AppHandle<R>,
+    pty_manager: State<'_, PtyManager>,
+    macro_manager: State<'_, MacroManager>,
+) -> Result<String, String> {
+    let mut protected: Vec<&str> = session_ids
+        .iter()
+        .map(String::as_str)
+        .filter(|session_id| is_session_protected(&app_handle, session_id))
+        .collect();
+    protected.sort_unstable();
+    protected.dedup();
+    if !protected.is_empty()
+        && !confirm_protected_action(
+            &app_handle,
+            &format!("protected:broadcast:{}:{}", protected.join(","), data),
+            "Send input to protected sessions?",
+            &format!("{}\n\nProtected: {}", data, protected.join(", ")),
+            confirmation_token.as_deref(),
+        )?
+    {
+        return Ok(CONFIRMATION_REQUIRED_MARKER.to_string());
+    }
+
+    let mut failed = Vec::new();
+    for session_id in &session_ids {
+        match write_pty_input(&pty_manager, session_id, &data) {
+            Ok(()) => macro_manager.record_input(session_id, &data),
+            Err(e) => failed.push(e),
+        }
+    }
+    if !failed.is_empty() {
+        return Err(failed.join("; "));
+    }
+    Ok(format!("Sent to {} sessions.", session_ids.len()))
+}
+
+#[command]
+pub fn pty_resize<R: Runtime>(
     session_id: String,
     cols: u16,
     rows: u16,
+    app_handle: AppHandle<R>,
     pty_manager: State<'_, PtyManager>,
 ) -> Result<(), String> {
     let mut sessions = pty_manager.sessions.lock().map_err(|e| e.to_string())?;
@@ -246,11 +493,31 @@ pub fn pty_resize(
             pixel_height: 0,
         })
         .map_err(|e| format!("Failed to resize PTY: {e}"))?;
+    resize_screen(&app_handle, &session_id, rows, cols);
     Ok(())
 }
 
+// Protected sessions are only closed with a confirmation token; the first call emits
+// confirmation_required and returns the confirmation marker
 #[command]
-pub fn pty_close_session(session_id: String, pty_manager: State<'_, PtyManager>) -> Result<(), String> {
+pub fn pty_close_session<R: Runtime>(
+    session_id: String,
+    confirmation_token: Option<String>,
+    app_handle: AppHandle<R>,
+    pty_manager: State<'_, PtyManager>,
+) -> Result<String, String> {
+    if is_session_protected(&app_handle, &session_id)
+        && !confirm_protected_action(
+            &app_handle,
+            &format!("protected:close:{}", session_id),
+            "Close protected session?",
+            &session_id,
+            confirmation_token.as_deref(),
+        )?
+    {
+        return Ok(CONFIRMATION_REQUIRED_MARKER.to_string());
+    }
+
     let session_opt = {
         let mut sessions = pty_manager.sessions.lock().map_err(|e| e.to_string())?;
         sessions.remove(&session_id)
@@ -266,8 +533,6 @@ pub fn pty_close_session(session_id: String, pty_manager: State<'_, PtyManager>)
                 // Tauri command thread; dropping the session still detaches the tab.
             }
         }
-        Ok(())
-    } else {
-        Ok(())
     }
+    Ok(PTY_SESSION_CLOSED_MESSAGE.to_string())
 }
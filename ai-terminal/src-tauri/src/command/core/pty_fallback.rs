@@ -0,0 +1,119 @@
+use crate::appearance::appearance_commands::apply_appearance_env;
+use crate::audit::audit_log::record_audit_event;
+use crate::command::constants::{PTY_FALLBACK_COLS, PTY_FALLBACK_ROWS};
+use crate::command::core::cost_warnings::program_part;
+use crate::command::core::pty::spawn_pty_session;
+use crate::command::timeline::session_timeline::record_timeline_event;
+use crate::command::types::pty_manager::{PtyManager, SessionTarget};
+use crate::command::types::timeline_manager::TimelineEventKind;
+use crate::command::windows::session_windows::emit_session_event;
+use crate::history::recorder::record_history_entry;
+use crate::settings::types::app_settings::AppSettings;
+use portable_pty::CommandBuilder;
+use regex::Regex;
+use serde::Serialize;
+use std::collections::HashMap;
+use tauri::{AppHandle, Manager, Runtime};
+
+// Returned by execute_command when the command went to a PTY; its output arrives as pty_output
+// for the session named in the pty_fallback event
+pub const PTY_FALLBACK_MARKER: &str = "PTY_FALLBACK_STARTED";
+
+#[derive(Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+struct PtyFallbackEvent {
+    session_id: String,
+    // PTY session running the command; it is removed (with pty_exit) when the command exits
+    pty_session_id: String,
+    command: String,
+    // tty_commands pattern that matched
+    pattern: String,
+}
+
+// tty_commands pattern matching any command in a `;`, `&&`, `||` or `|` chain
+pub fn tty_command_match(settings: &AppSettings, command: &str) -> Option<String> {
+    if !settings.pty_fallback_enabled {
+        return None;
+    }
+    let patterns: Vec<(&String, Regex)> = settings
+        .tty_commands
+        .iter()
+        .filter_map(|pattern| Regex::new(pattern).ok().map(|regex| (pattern, regex)))
+        .collect();
+    command
+        .split([';', '&', '|', '\n'])
+        .map(program_part)
+        .filter(|segment| !segment.is_empty())
+        .find_map(|segment| {
+            patterns
+                .iter()
+                .find(|(_, regex)| regex.is_match(segment))
+                .map(|(pattern, _)| (*pattern).clone())
+        })
+}
+
+pub fn validate_tty_commands(patterns: &[String]) -> Result<(), String> {
+    for pattern in patterns {
+        Regex::new(pattern)
+            .map_err(|e| format!("Invalid terminal command pattern '{}': {}", pattern, e))?;
+    }
+    Ok(())
+}
+
+// Starts `sh -c command` on a new PTY session with the environment execute_command built, and
+// tells the frontend to show that session until pty_exit
+pub fn start_pty_fallback<R: Runtime>(
+    app_handle: &AppHandle<R>,
+    session_id: &str,
+    command: &str,
+    cwd: &str,
+    env_map: &HashMap<String, String>,
+    pattern: String,
+) -> Result<String, String> {
+    let mut builder = CommandBuilder::new("sh");
+    builder.args(["-c", command]);
+    builder.env_clear();
+    for (name, value) in env_map {
+        builder.env(name, value);
+    }
+    builder.env("TERM", "xterm-256color");
+    builder.env("COLORTERM", "truecolor");
+    apply_appearance_env(app_handle, &mut builder);
+    builder.cwd(cwd);
+
+    let pty_session_id = format!("{}-tty-{}", session_id, uuid::Uuid::new_v4().simple());
+    spawn_pty_session(
+        app_handle,
+        &app_handle.state::<PtyManager>(),
+        pty_session_id.clone(),
+        PTY_FALLBACK_COLS,
+        PTY_FALLBACK_ROWS,
+        builder,
+        Some(SessionTarget::Command {
+            command: command.to_string(),
+        }),
+    )?;
+
+    // The exit status is not reported back from the PTY, so history has none
+    record_audit_event(app_handle, session_id, command, cwd, false, None);
+    record_history_entry(app_handle, session_id, command, cwd, None, None);
+    record_timeline_event(
+        app_handle,
+        session_id,
+        TimelineEventKind::CommandStarted,
+        command,
+        serde_json::json!({ "command": command, "cwd": cwd, "ptySessionId": pty_session_id }),
+    );
+    emit_session_event(
+        app_handle,
+        session_id,
+        "pty_fallback",
+        PtyFallbackEvent {
+            session_id: session_id.to_string(),
+            pty_session_id,
+            command: command.to_string(),
+            pattern,
+        },
+    );
+    Ok(PTY_FALLBACK_MARKER.to_string())
+}
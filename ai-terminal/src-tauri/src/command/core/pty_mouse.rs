@@ -0,0 +1,180 @@
+use crate::command::core::pty::write_pty_bytes;
+use crate::command::types::pty_manager::PtyManager;
+use crate::command::types::screen_model_manager::ScreenModelManager;
+use crate::utils::screen_model::{MouseEncoding, MouseMode, MouseTracking};
+use serde::Deserialize;
+use tauri::{command, State};
+
+// Largest coordinate the default encoding can carry in its single byte (255 - 32)
+const DEFAULT_ENCODING_MAX_COORDINATE: usize = 223;
+// Largest value a two-byte UTF-8 sequence can carry in the 1005 encoding
+const UTF8_ENCODING_MAX_VALUE: usize = 2047;
+
+#[derive(Deserialize, Clone, Copy, Debug, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub enum MouseEventKind {
+    Press,
+    Release,
+    // Motion; with a button it is a drag
+    Move,
+    WheelUp,
+    WheelDown,
+    WheelLeft,
+    WheelRight,
+}
+
+#[derive(Deserialize, Clone, Copy, Debug, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub enum MouseButton {
+    Left,
+    Middle,
+    Right,
+}
+
+#[derive(Deserialize, Clone, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct MouseEvent {
+    pub kind: MouseEventKind,
+    // The button pressed, released or held while moving
+    #[serde(default)]
+    pub button: Option<MouseButton>,
+    // 0-based cell of the terminal grid
+    pub row: usize,
+    pub col: usize,
+    #[serde(default)]
+    pub shift: bool,
+    #[serde(default)]
+    pub alt: bool,
+    #[serde(default)]
+    pub ctrl: bool,
+}
+
+fn button_code(button: Option<MouseButton>) -> usize {
+    match button {
+        Some(MouseButton::Left) => 0,
+        Some(MouseButton::Middle) => 1,
+        Some(MouseButton::Right) => 2,
+        // Release in the legacy encodings, and motion without a button
+        None => 3,
+    }
+}
+
+// Whether the app's tracking mode asks for this event
+fn is_reported(tracking: MouseTracking, event: &MouseEvent) -> bool {
+    match event.kind {
+        MouseEventKind::Move => match tracking {
+            MouseTracking::AnyEvent => true,
+            MouseTracking::ButtonEvent => event.button.is_some(),
+            _ => false,
+        },
+        MouseEventKind::Release => !matches!(tracking, MouseTracking::Off | MouseTracking::X10),
+        MouseEventKind::Press => event.button.is_some() && tracking != MouseTracking::Off,
+        _ => tracking != MouseTracking::Off,
+    }
+}
+
+fn push_utf8_value(report: &mut Vec<u8>, value: usize) {
+    let mut buffer = [0u8; 4];
+    if let Some(c) = char::from_u32(value as u32) {
+        report.extend_from_slice(c.encode_utf8(&mut buffer).as_bytes());
+    }
+}
+
+// The bytes xterm would send for `event` in `mode`, or None when the app did not ask for it or
+// the encoding cannot express its position
+pub fn encode_mouse_event(mode: MouseMode, event: &MouseEvent) -> Option<Vec<u8>> {
+    if !is_reported(mode.tracking, event) {
+        return None;
+    }
+    let mut code = match event.kind {
+        MouseEventKind::Press => button_code(event.button),
+        MouseEventKind::Release if mode.encoding == MouseEncoding::Sgr => button_code(event.button),
+        MouseEventKind::Release => 3,
+        MouseEventKind::Move => button_code(event.button) + 32,
+        MouseEventKind::WheelUp => 64,
+        MouseEventKind::WheelDown => 65,
+        MouseEventKind::WheelLeft => 66,
+        MouseEventKind::WheelRight => 67,
+    };
+    // X10 reports carry no modifiers
+    if mode.tracking != MouseTracking::X10 {
+        code += usize::from(event.shift) * 4
+            + usize::from(event.alt) * 8
+            + usize::from(event.ctrl) * 16;
+    }
+    let col = event.col + 1;
+    let row = event.row + 1;
+
+    match mode.encoding {
+        MouseEncoding::Sgr => {
+            let final_byte = if event.kind == MouseEventKind::Release {
+                'm'
+            } else {
+                'M'
+            };
+            Some(format!("\x1b[<{};{};{}{}", code, col, row, final_byte).into_bytes())
+        }
+        MouseEncoding::Urxvt => Some(format!("\x1b[{};{};{}M", code + 32, col, row).into_bytes()),
+        MouseEncoding::Utf8 => {
+            if [code, col, row]
+                .iter()
+                .any(|value| value + 32 > UTF8_ENCODING_MAX_VALUE)
+            {
+                return None;
+            }
+            let mut report = b"\x1b[M".to_vec();
+            for value in [code, col, row] {
+                push_utf8_value(&mut report, value + 32);
+            }
+            Some(report)
+        }
+        MouseEncoding::Default => {
+            if col > DEFAULT_ENCODING_MAX_COORDINATE || row > DEFAULT_ENCODING_MAX_COORDINATE {
+                return None;
+            }
+            let mut report = b"\x1b[M".to_vec();
+            report.extend([code, col, row].iter().map(|value| (value + 32) as u8));
+            Some(report)
+        }
+    }
+}
+
+fn session_mouse_mode(
+    screen_model_manager: &ScreenModelManager,
+    session_id: &str,
+) -> Result<MouseMode, String> {
+    screen_model_manager
+        .sessions
+        .lock()
+        .map_err(|e| e.to_string())?
+        .get(session_id)
+        .map(|model| model.mouse())
+        .ok_or_else(|| format!("PTY session '{}' not found", session_id))
+}
+
+// Mouse tracking the app in the session has turned on; while it is off the frontend keeps
+// handling the mouse itself
+#[command]
+pub fn pty_get_mouse_mode(
+    session_id: String,
+    screen_model_manager: State<'_, ScreenModelManager>,
+) -> Result<MouseMode, String> {
+    session_mouse_mode(&screen_model_manager, &session_id)
+}
+
+// Forwards a click, drag, move or wheel event to the app, encoded as it asked. Returns false,
+// without writing anything, when the app's mouse mode does not report this event.
+#[command]
+pub fn pty_send_mouse(
+    session_id: String,
+    event: MouseEvent,
+    pty_manager: State<'_, PtyManager>,
+    screen_model_manager: State<'_, ScreenModelManager>,
+) -> Result<bool, String> {
+    let mode = session_mouse_mode(&screen_model_manager, &session_id)?;
+    let Some(report) = encode_mouse_event(mode, &event) else {
+        return Ok(false);
+    };
+    write_pty_bytes(&pty_manager, &session_id, &report)?;
+    Ok(true)
+}
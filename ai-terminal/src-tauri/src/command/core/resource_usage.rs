@@ -0,0 +1,61 @@
+use serde::Serialize;
+use std::io;
+use std::process::{Child, ExitStatus};
+
+// What a finished command cost, including the children its shell waited for
+#[derive(Serialize, Clone, Debug, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct ResourceUsage {
+    pub peak_rss_bytes: u64,
+    pub user_cpu_ms: u64,
+    pub system_cpu_ms: u64,
+    // Filesystem blocks read and written (512-byte units on most systems)
+    pub block_reads: u64,
+    pub block_writes: u64,
+}
+
+// Child::wait that also collects the child's rusage via wait4. Once this returns the child is
+// reaped, so it must not be waited on or killed through `child` again.
+#[cfg(unix)]
+pub fn wait_with_usage(child: &mut Child) -> io::Result<(ExitStatus, Option<ResourceUsage>)> {
+    use std::os::unix::process::ExitStatusExt;
+
+    let pid = child.id() as libc::pid_t;
+    let mut status: libc::c_int = 0;
+    // SAFETY: rusage is plain data, and wait4 fully initializes it on success
+    let mut usage: libc::rusage = unsafe { std::mem::zeroed() };
+    loop {
+        // SAFETY: both pointers are valid for the duration of the call
+        let result = unsafe { libc::wait4(pid, &mut status, 0, &mut usage) };
+        if result == pid {
+            break;
+        }
+        let error = io::Error::last_os_error();
+        if error.kind() != io::ErrorKind::Interrupted {
+            return Err(error);
+        }
+    }
+
+    let millis = |time: libc::timeval| time.tv_sec as u64 * 1000 + time.tv_usec as u64 / 1000;
+    // Linux reports ru_maxrss in KiB, macOS in bytes
+    let peak_rss_bytes = if cfg!(target_os = "macos") {
+        usage.ru_maxrss as u64
+    } else {
+        usage.ru_maxrss as u64 * 1024
+    };
+    Ok((
+        ExitStatus::from_raw(status),
+        Some(ResourceUsage {
+            peak_rss_bytes,
+            user_cpu_ms: millis(usage.ru_utime),
+            system_cpu_ms: millis(usage.ru_stime),
+            block_reads: usage.ru_inblock as u64,
+            block_writes: usage.ru_oublock as u64,
+        }),
+    ))
+}
+
+#[cfg(not(unix))]
+pub fn wait_with_usage(child: &mut Child) -> io::Result<(ExitStatus, Option<ResourceUsage>)> {
+    child.wait().map(|status| (status, None))
+}
@@ -0,0 +1,308 @@
+use crate::audit::audit_log::record_audit_event;
+use crate::command::constants::{
+    DEFAULT_RETRY_ATTEMPTS, DEFAULT_RETRY_BACKOFF_MS, MAX_RETRY_ATTEMPTS, MAX_RETRY_BACKOFF_MS,
+};
+use crate::command::core::command_end::{emit_command_end, CommandEndEvent};
+use crate::command::core::command_output::{emit_command_error, emit_command_output};
+use crate::command::environment::directory_env::session_env_overrides;
+use crate::command::output::post_processors::process_output;
+use crate::command::output::progress::{finish_progress, track_progress};
+use crate::command::types::command_manager::CommandManager;
+use crate::command::types::command_state::{CommandState, RunningCommand};
+use crate::command::types::scrollback_manager::{OutputStream, ScrollbackManager};
+use crate::history::recorder::record_history_entry;
+use crate::settings::types::settings_manager::SettingsManager;
+use crate::utils::env_scrubbing::scrubbed_environment;
+use serde::Serialize;
+use std::io::{BufReader, Read};
+use std::os::unix::process::CommandExt;
+use std::process::{Command, Stdio};
+use std::thread::{self, JoinHandle};
+use std::time::{Duration, Instant};
+use tauri::{command, AppHandle, Emitter, Manager, Runtime, State};
+
+#[derive(Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct RetryAttempt {
+    pub attempt: u32,
+    pub exit_code: Option<i32>,
+    pub duration_ms: u64,
+}
+
+#[derive(Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct RetryAttemptEvent {
+    pub session_id: String,
+    pub command: String,
+    pub attempt: u32,
+    pub max_attempts: u32,
+    pub exit_code: Option<i32>,
+    // Delay before the next attempt; None when no further attempt follows
+    pub next_delay_ms: Option<u64>,
+}
+
+#[derive(Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct RetryResult {
+    pub session_id: String,
+    pub command: String,
+    pub succeeded: bool,
+    pub cancelled: bool,
+    pub attempts: Vec<RetryAttempt>,
+}
+
+fn stream_output<R: Runtime, T: Read + Send + 'static>(
+    app_handle: AppHandle<R>,
+    session_id: String,
+    reader: T,
+    stream: OutputStream,
+) -> JoinHandle<()> {
+    thread::spawn(move || {
+        let mut reader = BufReader::new(reader);
+        let mut buffer = [0; 2048];
+        loop {
+            match reader.read(&mut buffer) {
+                Ok(0) => break,
+                Ok(n) => {
+                    let chunk = process_output(
+                        &app_handle,
+                        &session_id,
+                        stream,
+                        String::from_utf8_lossy(&buffer[..n]).to_string(),
+                    );
+                    app_handle
+                        .state::<ScrollbackManager>()
+                        .append(&session_id, stream, &chunk);
+                    track_progress(&app_handle, &session_id, &chunk);
+                    if stream == OutputStream::Stderr {
+                        emit_command_error(&app_handle, &session_id, chunk);
+                    } else {
+                        emit_command_output(&app_handle, &session_id, chunk);
+                    }
+                }
+                Err(e) if e.kind() == std::io::ErrorKind::Interrupted => continue,
+                Err(_) => break,
+            }
+        }
+    })
+}
+
+// Runs one attempt to completion; None exit code means it was killed by a signal
+fn run_attempt<R: Runtime>(
+    app_handle: &AppHandle<R>,
+    session_id: &str,
+    command: &str,
+    cwd: &str,
+) -> Result<Option<i32>, String> {
+    let mut env_map = scrubbed_environment(&app_handle.state::<SettingsManager>().current());
+    env_map.insert("PWD".to_string(), cwd.to_string());
+    env_map.extend(session_env_overrides(
+        &app_handle.state::<CommandManager>(),
+        session_id,
+    ));
+
+    let mut shell_command = Command::new("sh");
+    shell_command
+        .arg("-c")
+        .arg(command)
+        .current_dir(cwd)
+        .env_clear()
+        .envs(&env_map)
+        .stdin(Stdio::null())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped());
+    // Own process group so terminate_command can stop the attempt like any other command
+    unsafe {
+        shell_command.pre_exec(|| {
+            nix::unistd::setsid()
+                .map(|_| ())
+                .map_err(std::io::Error::from)
+        });
+    }
+    let mut child = shell_command
+        .spawn()
+        .map_err(|e| format!("Failed to start command: {}", e))?;
+
+    {
+        let command_manager = app_handle.state::<CommandManager>();
+        let mut states = command_manager.commands.lock().map_err(|e| e.to_string())?;
+        let state = states
+            .entry(session_id.to_string())
+            .or_insert_with(|| CommandState::new(cwd.to_string()));
+        state.pid = Some(child.id());
+        state.running = Some(RunningCommand::new(child.id(), command));
+    }
+
+    let mut readers = Vec::new();
+    if let Some(stdout) = child.stdout.take() {
+        readers.push(stream_output(
+            app_handle.clone(),
+            session_id.to_string(),
+            stdout,
+            OutputStream::Stdout,
+        ));
+    }
+    if let Some(stderr) = child.stderr.take() {
+        readers.push(stream_output(
+            app_handle.clone(),
+            session_id.to_string(),
+            stderr,
+            OutputStream::Stderr,
+        ));
+    }
+
+    let status = child
+        .wait()
+        .map_err(|e| format!("Error waiting for command: {}", e))?;
+    for reader in readers {
+        let _ = reader.join();
+    }
+    Ok(status.code())
+}
+
+// Clears the finished attempt's pid. Returns true if terminate_command already cleared it,
+// which is how a user cancels the remaining attempts.
+fn take_attempt_pid_cancelled<R: Runtime>(app_handle: &AppHandle<R>, session_id: &str) -> bool {
+    let command_manager = app_handle.state::<CommandManager>();
+    let cancelled = match command_manager.commands.lock() {
+        Ok(mut states) => states
+            .get_mut(session_id)
+            .map(|state| state.pid.take().is_none())
+            .unwrap_or(false),
+        Err(_) => false,
+    };
+    cancelled
+}
+
+// Runs a command until it exits successfully or the attempts are used up, waiting
+// `backoff_ms`, then twice as long, and so on between attempts. Emits
+// `command_retry_attempt` after each failure and `command_retry_result` at the end.
+#[command]
+pub fn execute_with_retry<R: Runtime>(
+    command: String,
+    session_id: String,
+    attempts: Option<u32>,
+    backoff_ms: Option<u64>,
+    app_handle: AppHandle<R>,
+    command_manager: State<'_, CommandManager>,
+) -> Result<String, String> {
+    let command = command.trim().to_string();
+    if command.is_empty() {
+        return Err("Command cannot be empty".to_string());
+    }
+    let max_attempts = attempts
+        .unwrap_or(DEFAULT_RETRY_ATTEMPTS)
+        .clamp(1, MAX_RETRY_ATTEMPTS);
+    let base_delay_ms = backoff_ms
+        .unwrap_or(DEFAULT_RETRY_BACKOFF_MS)
+        .min(MAX_RETRY_BACKOFF_MS);
+
+    let cwd = {
+        let mut states = command_manager.commands.lock().map_err(|e| e.to_string())?;
+        let state = states.entry(session_id.clone()).or_insert_with(|| {
+            CommandState::new(
+                std::env::current_dir()
+                    .unwrap_or_default()
+                    .to_string_lossy()
+                    .to_string(),
+            )
+        });
+        if state.is_ssh_session_active {
+            return Err("Retries are not supported inside an SSH session".to_string());
+        }
+        state.current_dir.clone()
+    };
+
+    app_handle
+        .state::<ScrollbackManager>()
+        .append(&session_id, OutputStream::Input, &command);
+
+    thread::spawn(move || {
+        let mut results = Vec::new();
+        let mut cancelled = false;
+        for attempt in 1..=max_attempts {
+            let started_at = Instant::now();
+            let exit_code = match run_attempt(&app_handle, &session_id, &command, &cwd) {
+                Ok(exit_code) => exit_code,
+                Err(e) => {
+                    emit_command_error(&app_handle, &session_id, e);
+                    None
+                }
+            };
+            let duration_ms = started_at.elapsed().as_millis() as u64;
+            record_audit_event(&app_handle, &session_id, &command, &cwd, false, exit_code);
+            record_history_entry(
+                &app_handle,
+                &session_id,
+                &command,
+                &cwd,
+                exit_code,
+                Some(duration_ms),
+            );
+            results.push(RetryAttempt {
+                attempt,
+                exit_code,
+                duration_ms,
+            });
+
+            cancelled = take_attempt_pid_cancelled(&app_handle, &session_id) || exit_code.is_none();
+            if exit_code == Some(0) || cancelled {
+                break;
+            }
+
+            let next_delay_ms = (attempt < max_attempts).then(|| {
+                base_delay_ms
+                    .saturating_mul(1 << (attempt - 1).min(16))
+                    .min(MAX_RETRY_BACKOFF_MS)
+            });
+            let _ = app_handle.emit(
+                "command_retry_attempt",
+                RetryAttemptEvent {
+                    session_id: session_id.clone(),
+                    command: command.clone(),
+                    attempt,
+                    max_attempts,
+                    exit_code,
+                    next_delay_ms,
+                },
+            );
+            if let Some(delay) = next_delay_ms {
+                thread::sleep(Duration::from_millis(delay));
+            }
+        }
+
+        let exit_code = results.last().and_then(|result| result.exit_code);
+        app_handle
+            .state::<ScrollbackManager>()
+            .finish_run(&session_id, exit_code);
+        finish_progress(&app_handle, &session_id);
+        let succeeded = exit_code == Some(0);
+        let _ = app_handle.emit(
+            "command_retry_result",
+            RetryResult {
+                session_id: session_id.clone(),
+                command: command.clone(),
+                succeeded,
+                cancelled,
+                attempts: results,
+            },
+        );
+        emit_command_end(
+            &app_handle,
+            CommandEndEvent {
+                exit_code,
+                ..CommandEndEvent::new(
+                    &session_id,
+                    succeeded,
+                    if succeeded {
+                        "Command completed successfully."
+                    } else {
+                        "Command failed."
+                    },
+                )
+            },
+        );
+    });
+
+    Ok("Command started. Output will stream in real-time.".to_string())
+}
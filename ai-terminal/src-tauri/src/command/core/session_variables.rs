@@ -0,0 +1,109 @@
+use crate::command::constants::{MAX_SESSION_VARIABLES, MAX_SESSION_VARIABLE_BYTES};
+use crate::command::types::command_manager::CommandManager;
+use crate::command::types::command_state::CommandState;
+use std::collections::BTreeMap;
+use tauri::{command, State};
+
+pub fn validate_name(name: &str) -> Result<(), String> {
+    let is_valid = !name.is_empty()
+        && name
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || "_.-".contains(c));
+    if is_valid {
+        Ok(())
+    } else {
+        Err(format!(
+            "Invalid variable name '{}': use letters, digits, '_', '.' or '-'",
+            name
+        ))
+    }
+}
+
+// Sentence for the AI prompt listing the session's variables so the agent can refer to them
+// as {{var:name}}
+pub fn session_variables_context(
+    command_manager: &CommandManager,
+    session_id: &str,
+) -> Option<String> {
+    let states = command_manager.commands.lock().ok()?;
+    let variables = &states.get(session_id)?.variables;
+    if variables.is_empty() {
+        return None;
+    }
+    let listed: Vec<String> = variables
+        .iter()
+        .map(|(name, value)| format!("{} = {:?}", name, value))
+        .collect();
+    Some(format!(
+        "Session variables (usable in commands as {{{{var:name}}}}): {}.",
+        listed.join(", ")
+    ))
+}
+
+// Stores a value in the session's scratchpad; `value: None` removes the variable
+#[command]
+pub fn set_var(
+    session_id: String,
+    name: String,
+    value: Option<String>,
+    command_manager: State<'_, CommandManager>,
+) -> Result<(), String> {
+    let name = name.trim().to_string();
+    validate_name(&name)?;
+    let mut states = command_manager.commands.lock().map_err(|e| e.to_string())?;
+    let state = states.entry(session_id).or_insert_with(|| {
+        CommandState::new(
+            std::env::current_dir()
+                .unwrap_or_default()
+                .to_string_lossy()
+                .to_string(),
+        )
+    });
+    match value {
+        Some(value) => {
+            if value.len() > MAX_SESSION_VARIABLE_BYTES {
+                return Err(format!(
+                    "Value for '{}' is too large (limit {} bytes)",
+                    name, MAX_SESSION_VARIABLE_BYTES
+                ));
+            }
+            if !state.variables.contains_key(&name)
+                && state.variables.len() >= MAX_SESSION_VARIABLES
+            {
+                return Err(format!(
+                    "A session can hold at most {} variables",
+                    MAX_SESSION_VARIABLES
+                ));
+            }
+            state.variables.insert(name, value);
+        }
+        None => {
+            state.variables.remove(&name);
+        }
+    }
+    Ok(())
+}
+
+#[command]
+pub fn get_var(
+    session_id: String,
+    name: String,
+    command_manager: State<'_, CommandManager>,
+) -> Result<Option<String>, String> {
+    let states = command_manager.commands.lock().map_err(|e| e.to_string())?;
+    Ok(states
+        .get(&session_id)
+        .and_then(|state| state.variables.get(name.trim()).cloned()))
+}
+
+#[command]
+pub fn list_vars(
+    session_id: String,
+    command_manager: State<'_, CommandManager>,
+) -> Result<BTreeMap<String, String>, String> {
+    let states = command_manager.commands.lock().map_err(|e| e.to_string())?;
+    Ok(states
+        .get(&session_id)
+        .map(|state| state.variables.clone())
+        .unwrap_or_default())
+}
@@ -0,0 +1,113 @@
+use crate::command::types::scrollback_manager::{OutputStream, ScrollbackManager};
+use crate::command::windows::session_windows::emit_session_event;
+use serde::{Deserialize, Serialize};
+use tauri::{AppHandle, Manager, Runtime};
+
+// PS4 for traced commands. The record separator never appears in normal output, so trace lines
+// can be told apart from the command's own stderr; bash repeats it once per nesting level.
+pub const TRACE_PS4: &str = "\u{1e}+ ";
+const TRACE_MARKER: char = '\u{1e}';
+
+// Execution flags for one command run through `sh -c`
+#[derive(Deserialize, Clone, Copy, Debug, Default)]
+#[serde(rename_all = "camelCase", default)]
+pub struct ShellOptions {
+    // set -x: stream each command the shell runs as command_trace
+    pub trace: bool,
+    // set -eu plus pipefail where the shell supports it
+    pub strict: bool,
+}
+
+impl ShellOptions {
+    pub fn is_empty(&self) -> bool {
+        !self.trace && !self.strict
+    }
+
+    // Prefixes the script with the requested `set` lines. Tracing is turned on last so the
+    // prelude itself is not traced.
+    pub fn wrap(&self, command: &str) -> String {
+        let mut script = String::new();
+        if self.strict {
+            script.push_str("set -eu\n(set -o pipefail) 2>/dev/null && set -o pipefail\n");
+        }
+        if self.trace {
+            script.push_str("set -x\n");
+        }
+        script.push_str(command);
+        script
+    }
+}
+
+#[derive(Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+struct CommandTraceEvent {
+    session_id: String,
+    // Subshell / eval nesting level, 1 for the top-level script
+    depth: usize,
+    line: String,
+}
+
+// Pulls set -x lines out of a traced command's stderr as it streams
+#[derive(Default)]
+pub struct TraceSplitter {
+    // Unfinished trace line; anything else is passed through without waiting for a newline
+    partial: String,
+}
+
+impl TraceSplitter {
+    // Emits complete trace lines in `chunk` and returns the rest of the stderr text
+    pub fn split<R: Runtime>(
+        &mut self,
+        app_handle: &AppHandle<R>,
+        session_id: &str,
+        chunk: &str,
+    ) -> String {
+        self.partial.push_str(chunk);
+        let text = std::mem::take(&mut self.partial);
+        let mut rest = String::new();
+        let mut lines = text.split_inclusive('\n').peekable();
+        while let Some(line) = lines.next() {
+            let is_last = lines.peek().is_none();
+            if !line.starts_with(TRACE_MARKER) {
+                rest.push_str(line);
+            } else if is_last && !line.ends_with('\n') {
+                self.partial = line.to_string();
+            } else {
+                emit_trace_line(app_handle, session_id, line);
+            }
+        }
+        rest
+    }
+
+    // Emits a trace line left unfinished when stderr closed
+    pub fn finish<R: Runtime>(&mut self, app_handle: &AppHandle<R>, session_id: &str) {
+        let partial = std::mem::take(&mut self.partial);
+        if !partial.is_empty() {
+            emit_trace_line(app_handle, session_id, &partial);
+        }
+    }
+}
+
+fn emit_trace_line<R: Runtime>(app_handle: &AppHandle<R>, session_id: &str, line: &str) {
+    let depth = line.chars().take_while(|c| *c == TRACE_MARKER).count();
+    let traced = line
+        .trim_start_matches(TRACE_MARKER)
+        .trim_start_matches('+')
+        .trim_start()
+        .trim_end_matches(['\r', '\n']);
+    app_handle.state::<ScrollbackManager>().append(
+        session_id,
+        OutputStream::Trace,
+        &format!("{} {}\n", "+".repeat(depth), traced),
+    );
+    emit_session_event(
+        app_handle,
+        session_id,
+        "command_trace",
+        CommandTraceEvent {
+            session_id: session_id.to_string(),
+            depth,
+            line: traced.to_string(),
+        },
+    );
+}
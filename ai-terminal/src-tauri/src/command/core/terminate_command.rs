@@ -1,22 +1,24 @@
 use crate::command::types::command_manager::CommandManager;
+use crate::i18n::types::app_error::AppError;
+use crate::i18n::types::error_code::ErrorCode;
 use tauri::State;
 
 #[tauri::command]
 pub fn terminate_command(
     session_id: String,
     command_manager: State<'_, CommandManager>,
-) -> Result<(), String> {
+) -> Result<(), AppError> {
     let mut states = command_manager.commands.lock().map_err(|e| e.to_string())?;
     let key = session_id;
 
     let pid = if let Some(state) = states.get(&key) {
         state.pid.unwrap_or(0)
     } else {
-        return Err("No active process found".to_string());
+        return Err(AppError::new(ErrorCode::NoActiveProcess, &[]));
     };
 
     if pid == 0 {
-        return Err("No active process to terminate".to_string());
+        return Err(AppError::new(ErrorCode::NoActiveProcess, &[]));
     }
 
     #[cfg(unix)]
@@ -26,7 +28,10 @@ pub fn terminate_command(
 
         // Try to send SIGTERM first
         if let Err(err) = kill(Pid::from_raw(pid as i32), Signal::SIGTERM) {
-            return Err(format!("Failed to send SIGTERM: {}", err));
+            return Err(AppError::new(
+                ErrorCode::SignalFailed,
+                &[("signal", "SIGTERM"), ("reason", &err.to_string())],
+            ));
         }
 
         // Give the process a moment to terminate gracefully
@@ -34,7 +39,10 @@ pub fn terminate_command(
 
         // If it's still running, force kill with SIGKILL
         if let Err(err) = kill(Pid::from_raw(pid as i32), Signal::SIGKILL) {
-            return Err(format!("Failed to send SIGKILL: {}", err));
+            return Err(AppError::new(
+                ErrorCode::SignalFailed,
+                &[("signal", "SIGKILL"), ("reason", &err.to_string())],
+            ));
         }
     }
 
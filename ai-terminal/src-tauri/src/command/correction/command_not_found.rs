@@ -0,0 +1,113 @@
+use crate::command::constants::COMMON_COMMANDS;
+use crate::utils::path_executables::list_path_executables;
+use crate::utils::string_utils::closest_matches;
+use serde::Serialize;
+use std::path::Path;
+use std::process::Command;
+use tauri::{AppHandle, Emitter, Runtime};
+
+// Exit status used by POSIX shells when the command could not be found
+pub const COMMAND_NOT_FOUND_EXIT_CODE: i32 = 127;
+
+const MAX_SUGGESTIONS: usize = 5;
+
+// Ubuntu/Debian helper that maps missing commands to installable packages
+const COMMAND_NOT_FOUND_HELPERS: &[&str] = &[
+    "/usr/lib/command-not-found",
+    "/usr/share/command-not-found/command-not-found",
+];
+
+#[derive(Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct CommandSuggestion {
+    pub session_id: String,
+    pub command: String,
+    pub missing_program: String,
+    pub corrections: Vec<String>,
+    pub package_hints: Vec<String>,
+}
+
+// Index of the word naming the program, skipping leading VAR=value assignments
+fn program_word_index(words: &[&str]) -> Option<usize> {
+    words
+        .iter()
+        .position(|word| !(word.contains('=') && !word.starts_with('=') && !word.starts_with('-')))
+}
+
+// Program a command line runs, as the shell looks it up
+pub fn command_program(command: &str) -> Option<&str> {
+    let words: Vec<&str> = command.split_whitespace().collect();
+    program_word_index(&words).map(|index| words[index])
+}
+
+pub fn suggest_corrections(session_id: &str, command: &str) -> Option<CommandSuggestion> {
+    let words: Vec<&str> = command.split_whitespace().collect();
+    let index = program_word_index(&words)?;
+    let program = words[index];
+    // Paths like ./script.sh are not PATH lookups, so there is nothing to correct against
+    if program.contains('/') {
+        return None;
+    }
+
+    let mut candidates = list_path_executables();
+    // The program exists, so the 127 came from something it ran (e.g. a script calling a
+    // missing tool); suggesting replacements for it would be wrong
+    if candidates.iter().any(|candidate| candidate == program) {
+        return None;
+    }
+    candidates.extend(COMMON_COMMANDS.iter().map(|c| c.to_string()));
+    let max_distance = if program.chars().count() <= 3 { 1 } else { 2 };
+
+    let corrections = closest_matches(program, candidates.iter(), max_distance, MAX_SUGGESTIONS)
+        .into_iter()
+        .map(|replacement| {
+            let mut corrected = words.clone();
+            corrected[index] = replacement.as_str();
+            corrected.join(" ")
+        })
+        .collect();
+
+    Some(CommandSuggestion {
+        session_id: session_id.to_string(),
+        command: command.to_string(),
+        missing_program: program.to_string(),
+        corrections,
+        package_hints: package_hints(program),
+    })
+}
+
+fn package_hints(program: &str) -> Vec<String> {
+    let helper = match COMMAND_NOT_FOUND_HELPERS
+        .iter()
+        .find(|helper| Path::new(helper).exists())
+    {
+        Some(helper) => helper,
+        None => return Vec::new(),
+    };
+
+    let output = match Command::new(helper).arg("--").arg(program).output() {
+        Ok(output) => output,
+        Err(_) => return Vec::new(),
+    };
+
+    // The helper prints its hints on stderr, e.g. "sudo apt install ripgrep"
+    String::from_utf8_lossy(&output.stderr)
+        .lines()
+        .chain(String::from_utf8_lossy(&output.stdout).lines())
+        .map(str::trim)
+        .filter(|line| line.contains(" install "))
+        .map(String::from)
+        .collect()
+}
+
+pub fn emit_command_suggestion<R: Runtime>(
+    app_handle: &AppHandle<R>,
+    session_id: &str,
+    command: &str,
+) {
+    if let Some(suggestion) = suggest_corrections(session_id, command) {
+        if !suggestion.corrections.is_empty() || !suggestion.package_hints.is_empty() {
+            let _ = app_handle.emit("command_suggestion", suggestion);
+        }
+    }
+}
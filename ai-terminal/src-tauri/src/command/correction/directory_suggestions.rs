@@ -0,0 +1,119 @@
+use crate::utils::string_utils::edit_distance;
+use serde::Serialize;
+use std::fs;
+use std::path::{Component, Path, PathBuf};
+use tauri::{AppHandle, Emitter, Runtime};
+
+const MAX_SUGGESTIONS: usize = 5;
+const MAX_DIRECTORY_DISTANCE: usize = 2;
+
+#[derive(Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct DirectorySuggestion {
+    pub session_id: String,
+    pub requested: String,
+    // Corrected paths written the way the user typed theirs (relative, ~/ or absolute)
+    pub suggestions: Vec<String>,
+}
+
+// Subdirectories of `parent` matching `name` case-insensitively or within a small edit distance,
+// best match first
+fn similar_directories(parent: &Path, name: &str) -> Vec<String> {
+    let entries = match fs::read_dir(parent) {
+        Ok(entries) => entries,
+        Err(_) => return Vec::new(),
+    };
+    let lower = name.to_lowercase();
+    let mut scored: Vec<(usize, String)> = entries
+        .filter_map(Result::ok)
+        .filter(|entry| entry.path().is_dir())
+        .filter_map(|entry| entry.file_name().into_string().ok())
+        .filter_map(|candidate| {
+            let distance = if candidate.to_lowercase() == lower {
+                0
+            } else {
+                edit_distance(&lower, &candidate.to_lowercase()) + 1
+            };
+            (distance <= MAX_DIRECTORY_DISTANCE + 1).then_some((distance, candidate))
+        })
+        .collect();
+    scored.sort();
+    scored.into_iter().map(|(_, candidate)| candidate).collect()
+}
+
+// Greedily repairs the components after `base`, taking the best match for each one
+fn repair_rest(mut base: PathBuf, rest: &[String]) -> Option<PathBuf> {
+    for name in rest {
+        let candidate = base.join(name);
+        if candidate.is_dir() {
+            base = candidate;
+        } else {
+            base = base.join(similar_directories(&base, name).into_iter().next()?);
+        }
+    }
+    Some(base)
+}
+
+// Finds existing directories close to `target`, which does not exist
+pub fn suggest_directories(target: &Path) -> Vec<PathBuf> {
+    let mut existing = PathBuf::new();
+    let mut missing = Vec::new();
+    for component in target.components() {
+        match component {
+            Component::Normal(name) if !missing.is_empty() || !existing.join(name).is_dir() => {
+                missing.push(name.to_string_lossy().to_string())
+            }
+            other => existing.push(other),
+        }
+    }
+    if missing.is_empty() {
+        return Vec::new();
+    }
+
+    similar_directories(&existing, &missing[0])
+        .into_iter()
+        .filter_map(|first| repair_rest(existing.join(first), &missing[1..]))
+        .take(MAX_SUGGESTIONS)
+        .collect()
+}
+
+// Renders a suggestion relative to the cwd or home when the user typed it that way
+fn display_path(path: &Path, requested: &str, current_dir: &Path) -> String {
+    if requested.starts_with('~') {
+        if let Some(rest) =
+            dirs::home_dir().and_then(|home| path.strip_prefix(home).ok().map(Path::to_path_buf))
+        {
+            return format!("~/{}", rest.to_string_lossy());
+        }
+    } else if !requested.starts_with('/') {
+        if let Ok(rest) = path.strip_prefix(current_dir) {
+            return rest.to_string_lossy().to_string();
+        }
+    }
+    path.to_string_lossy().to_string()
+}
+
+// Emits `cd_suggestion` and returns the display paths so the error message can mention them
+pub fn emit_directory_suggestion<R: Runtime>(
+    app_handle: &AppHandle<R>,
+    session_id: &str,
+    requested: &str,
+    target: &Path,
+    current_dir: &Path,
+) -> Vec<String> {
+    let suggestions: Vec<String> = suggest_directories(target)
+        .iter()
+        .map(|path| display_path(path, requested, current_dir))
+        .collect();
+    if !suggestions.is_empty() {
+        let _ = app_handle.emit(
+            "cd_suggestion",
+            DirectorySuggestion {
+                session_id: session_id.to_string(),
+                requested: requested.to_string(),
+                suggestions: suggestions.clone(),
+            },
+        );
+    }
+    suggestions
+}
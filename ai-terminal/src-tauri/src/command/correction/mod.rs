@@ -0,0 +1,3 @@
+pub mod command_not_found;
+pub mod directory_suggestions;
+pub mod natural_language;
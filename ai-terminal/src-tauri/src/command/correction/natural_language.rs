@@ -0,0 +1,273 @@
+use crate::command::constants::COMMON_COMMANDS;
+use crate::command::types::command_manager::CommandManager;
+use crate::ollama::context_filter::{
+    filter_applies, filter_context, ContextPiece, ContextPieceKind,
+};
+use crate::ollama::mock_provider::mock_classify;
+use crate::ollama::types::ai_provider::AiProvider;
+use crate::ollama::types::ollama_request::OllamaRequest;
+use crate::ollama::types::ollama_response::OllamaResponse;
+use crate::settings::types::app_settings::AppSettings;
+use crate::settings::types::settings_manager::SettingsManager;
+use crate::utils::path_executables::find_executable;
+use crate::utils::string_utils::edit_distance;
+use serde::Serialize;
+use tauri::{command, State};
+
+// Words that open a question or request to the assistant
+const QUESTION_WORDS: &[&str] = &[
+    "how", "what", "why", "where", "when", "who", "which", "can", "could", "would", "should", "is",
+    "are", "does", "explain", "tell", "show", "please", "whats", "hows", "whys",
+];
+
+// Function words common in English sentences and rare as command arguments
+const STOPWORDS: &[&str] = &[
+    "a", "an", "the", "to", "of", "in", "on", "for", "with", "my", "this", "that", "these",
+    "those", "it", "its", "i", "me", "is", "are", "do", "does", "all", "from", "into", "and", "or",
+    "you", "your", "there", "be", "can", "how", "what", "some", "way", "about",
+];
+
+// Builtins that find_executable cannot see
+const SHELL_BUILTINS: &[&str] = &[
+    "alias", "bg", "cd", "eval", "exec", "export", "fg", "jobs", "set", "source", "type", "ulimit",
+    "umask", "unalias", "unset", "wait",
+];
+
+// Scores at or above this are treated as natural language
+const NATURAL_LANGUAGE_THRESHOLD: f64 = 0.5;
+
+// Heuristic scores this close to the threshold are sent to the model when it is enabled
+const MODEL_AMBIGUITY_MARGIN: f64 = 0.2;
+
+#[derive(Serialize, Clone, Copy, PartialEq, Eq, Debug)]
+#[serde(rename_all = "camelCase")]
+pub enum InputKind {
+    Command,
+    NaturalLanguage,
+}
+
+#[derive(Serialize, Clone, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct InputClassification {
+    pub kind: InputKind,
+    // 0.0 (certainly a command) to 1.0 (certainly natural language)
+    pub score: f64,
+    // The input with a misspelled question word fixed ("hwo do i" -> "how do i")
+    pub corrected: Option<String>,
+    pub reasons: Vec<String>,
+    // Text for the UI to offer when the input looks like natural language
+    pub hint: Option<String>,
+    pub used_model: bool,
+}
+
+// Levenshtein distance 1, or two adjacent letters swapped ("hwo" for "how")
+fn is_near_word(word: &str, target: &str) -> bool {
+    if word.len() < 3 || target.len() < 3 {
+        return false;
+    }
+    if edit_distance(word, target) <= 1 {
+        return true;
+    }
+    let a: Vec<char> = word.chars().collect();
+    let b: Vec<char> = target.chars().collect();
+    let differing: Vec<usize> = (0..a.len().min(b.len()))
+        .filter(|&i| a[i] != b[i])
+        .collect();
+    a.len() == b.len()
+        && differing.len() == 2
+        && differing[1] == differing[0] + 1
+        && a[differing[0]] == b[differing[1]]
+        && a[differing[1]] == b[differing[0]]
+}
+
+fn is_known_program(word: &str) -> bool {
+    word.contains('/')
+        || SHELL_BUILTINS.contains(&word)
+        || COMMON_COMMANDS.contains(&word)
+        || find_executable(word).is_some()
+}
+
+fn has_shell_syntax(input: &str) -> bool {
+    input.contains(['|', '>', '<', '$', '`', ';', '&', '=', '*', '"', '\''])
+        || input
+            .split_whitespace()
+            .skip(1)
+            .any(|word| word.starts_with('-') || word.contains('/') || word.starts_with('~'))
+}
+
+fn normalize_word(word: &str) -> String {
+    word.trim_matches(|c: char| !c.is_alphanumeric())
+        .replace('\'', "")
+        .to_lowercase()
+}
+
+// Heuristic estimate of whether the input is a sentence for the assistant rather than a
+// command line
+pub fn classify_input_heuristic(input: &str) -> InputClassification {
+    let trimmed = input.trim();
+    let raw_words: Vec<&str> = trimmed.split_whitespace().collect();
+    let words: Vec<String> = raw_words.iter().map(|word| normalize_word(word)).collect();
+    let mut reasons = Vec::new();
+    let mut corrected = None;
+    let mut score: f64 = 0.0;
+
+    let Some(first) = words.first() else {
+        return finish(0.0, None, reasons);
+    };
+    let first_is_program = is_known_program(raw_words[0]);
+
+    if QUESTION_WORDS.contains(&first.as_str()) {
+        // `which python` and `who` are commands too, so such words count for less
+        score += if first_is_program { 0.2 } else { 0.45 };
+        reasons.push(format!("starts with \"{}\"", first));
+    } else if !first_is_program {
+        if let Some(word) = QUESTION_WORDS.iter().find(|word| is_near_word(first, word)) {
+            score += 0.4;
+            reasons.push(format!("starts with \"{}\" (read as \"{}\")", first, word));
+            let mut fixed = vec![word.to_string()];
+            fixed.extend(raw_words[1..].iter().map(|word| word.to_string()));
+            corrected = Some(fixed.join(" "));
+        } else if words.len() >= 3 {
+            score += 0.2;
+            reasons.push(format!("\"{}\" is not a known command", raw_words[0]));
+        }
+    } else {
+        score -= 0.3;
+    }
+
+    if trimmed.ends_with('?') {
+        score += 0.25;
+        reasons.push("ends with a question mark".to_string());
+    }
+
+    if words.len() > 1 {
+        let stopwords = words[1..]
+            .iter()
+            .filter(|word| STOPWORDS.contains(&word.as_str()))
+            .count();
+        if stopwords > 0 {
+            score += 0.5 * stopwords as f64 / (words.len() - 1) as f64;
+            reasons.push(format!("{} common English words", stopwords));
+        }
+    }
+    if words.len() >= 4 {
+        score += 0.1;
+    }
+
+    // Flags, paths, pipes, redirects, variables and quoting are strong signs of a command
+    if has_shell_syntax(trimmed.trim_end_matches('?')) {
+        score -= 0.4;
+        reasons.push("uses shell syntax".to_string());
+    }
+    if words.len() == 1 && !trimmed.ends_with('?') {
+        score = score.min(0.3);
+    }
+
+    finish(score, corrected, reasons)
+}
+
+fn finish(score: f64, corrected: Option<String>, reasons: Vec<String>) -> InputClassification {
+    let score = score.clamp(0.0, 1.0);
+    let kind = if score >= NATURAL_LANGUAGE_THRESHOLD {
+        InputKind::NaturalLanguage
+    } else {
+        InputKind::Command
+    };
+    InputClassification {
+        kind,
+        score,
+        corrected,
+        reasons,
+        hint: (kind == InputKind::NaturalLanguage)
+            .then(|| "This looks like a question. Ask AI instead of executing?".to_string()),
+        used_model: false,
+    }
+}
+
+// Asks the configured Ollama model for a one-word verdict; None if it is unavailable or the
+// answer is unclear
+async fn classify_with_model(
+    input: &str,
+    command_manager: &CommandManager,
+    settings: &AppSettings,
+) -> Option<bool> {
+    let (model, api_host) = {
+        let ollama_state = command_manager.ollama.lock().ok()?;
+        if ollama_state.provider == AiProvider::Mock {
+            return Some(mock_classify(input));
+        }
+        (
+            ollama_state.current_model.clone(),
+            ollama_state.api_host.clone(),
+        )
+    };
+    // The input goes through the same content filter as ask_ai context
+    let input = filter_context(
+        settings,
+        filter_applies(settings, AiProvider::Ollama, &api_host),
+        None,
+        vec![ContextPiece {
+            kind: ContextPieceKind::Question,
+            source: None,
+            text: input.to_string(),
+        }],
+    )
+    .pop()
+    .map(|piece| piece.text)
+    .unwrap_or_default();
+    let prompt = format!(
+        "Is the following terminal input a shell command or a natural-language question or \
+         request? Reply with exactly one word, COMMAND or QUESTION.\n\nInput: {}",
+        input
+    );
+    let response: OllamaResponse = reqwest::Client::new()
+        .post(format!("{}/api/generate", api_host))
+        .json(&OllamaRequest {
+            model,
+            prompt,
+            stream: false,
+            options: None,
+        })
+        .send()
+        .await
+        .ok()?
+        .json()
+        .await
+        .ok()?;
+    let verdict = response.response.trim().to_uppercase();
+    if verdict.starts_with("QUESTION") {
+        Some(true)
+    } else if verdict.starts_with("COMMAND") {
+        Some(false)
+    } else {
+        None
+    }
+}
+
+// Classifies command-box input before it runs so the UI can offer "Ask AI instead?".
+// With use_model, inputs the heuristics cannot decide are checked with the local model.
+#[command]
+pub async fn classify_input(
+    input: String,
+    use_model: Option<bool>,
+    command_manager: State<'_, CommandManager>,
+    settings_manager: State<'_, SettingsManager>,
+) -> Result<InputClassification, String> {
+    let mut classification = classify_input_heuristic(&input);
+    let ambiguous =
+        (classification.score - NATURAL_LANGUAGE_THRESHOLD).abs() < MODEL_AMBIGUITY_MARGIN;
+    if use_model.unwrap_or(false) && ambiguous {
+        if let Some(is_question) =
+            classify_with_model(&input, &command_manager, &settings_manager.current()).await
+        {
+            let mut reasons = std::mem::take(&mut classification.reasons);
+            reasons.push("checked with the local model".to_string());
+            let score = if is_question { 0.9 } else { 0.1 };
+            classification = InputClassification {
+                used_model: true,
+                ..finish(score, classification.corrected, reasons)
+            };
+        }
+    }
+    Ok(classification)
+}
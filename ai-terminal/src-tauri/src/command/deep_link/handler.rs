@@ -0,0 +1,206 @@
+use crate::command::types::command_manager::CommandManager;
+use crate::command::types::command_state::CommandState;
+use crate::command::types::confirmation_manager::{
+    ConfirmationManager, ConfirmationRequest, CONFIRMATION_REQUIRED_MARKER,
+};
+use serde::Serialize;
+use std::path::PathBuf;
+use tauri::{command, AppHandle, Emitter, Manager, Runtime, State};
+use tauri_plugin_deep_link::DeepLinkExt;
+use url::Url;
+
+const DEEP_LINK_SCHEME: &str = "ai-terminal";
+const MAX_DEEP_LINK_COMMAND_LEN: usize = 4096;
+
+#[derive(Serialize, Clone, Debug)]
+#[serde(tag = "type", rename_all = "camelCase")]
+pub enum DeepLinkAction {
+    Run {
+        command: String,
+        cwd: Option<String>,
+    },
+    Ssh {
+        target: String,
+    },
+}
+
+#[derive(Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct DeepLinkRequestEvent {
+    pub url: String,
+    pub action: DeepLinkAction,
+    pub confirmation: ConfirmationRequest,
+}
+
+#[derive(Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct DeepLinkSessionEvent {
+    pub session_id: String,
+    pub cwd: String,
+    pub command: String,
+}
+
+// Called from main's setup hook: forwards URLs opened by the OS to the frontend for confirmation
+pub fn register_deep_link_handler<R: Runtime>(app_handle: &AppHandle<R>) {
+    let handle = app_handle.clone();
+    app_handle.deep_link().on_open_url(move |event| {
+        for url in event.urls() {
+            if let Err(e) = request_deep_link(&handle, url.as_str()) {
+                let _ = handle.emit("deep_link_error", e);
+            }
+        }
+    });
+
+    // URLs that launched the app before the handler was registered
+    if let Ok(Some(urls)) = app_handle.deep_link().get_current() {
+        for url in urls {
+            if let Err(e) = request_deep_link(app_handle, url.as_str()) {
+                let _ = app_handle.emit("deep_link_error", e);
+            }
+        }
+    }
+}
+
+pub fn parse_deep_link(raw_url: &str) -> Result<DeepLinkAction, String> {
+    let url = Url::parse(raw_url).map_err(|e| format!("Invalid deep link '{}': {}", raw_url, e))?;
+    if url.scheme() != DEEP_LINK_SCHEME {
+        return Err(format!("Unsupported deep link scheme: {}", url.scheme()));
+    }
+
+    match url.host_str() {
+        Some("run") => {
+            let mut command = None;
+            let mut cwd = None;
+            for (key, value) in url.query_pairs() {
+                match key.as_ref() {
+                    "cmd" => command = Some(value.to_string()),
+                    "cwd" => cwd = Some(value.to_string()),
+                    _ => {}
+                }
+            }
+            let command = command
+                .map(|c| c.trim().to_string())
+                .filter(|c| !c.is_empty())
+                .ok_or("Deep link is missing the 'cmd' parameter")?;
+            // A single visible line only, so nothing can hide below the first line of the dialog
+            if command.contains('\n') || command.contains('\r') {
+                return Err("Deep link commands must be a single line".to_string());
+            }
+            if command.len() > MAX_DEEP_LINK_COMMAND_LEN {
+                return Err("Deep link command is too long".to_string());
+            }
+            Ok(DeepLinkAction::Run { command, cwd })
+        }
+        Some("ssh") => {
+            let target = url.path().trim_matches('/').to_string();
+            let is_valid_target = !target.is_empty()
+                && !target.starts_with('-')
+                && target
+                    .chars()
+                    .all(|c| c.is_ascii_alphanumeric() || "@._:-".contains(c));
+            if !is_valid_target {
+                return Err(format!("Invalid SSH target in deep link: '{}'", target));
+            }
+            Ok(DeepLinkAction::Ssh { target })
+        }
+        other => Err(format!(
+            "Unknown deep link action: {}",
+            other.unwrap_or_default()
+        )),
+    }
+}
+
+fn describe(action: &DeepLinkAction) -> (String, String) {
+    match action {
+        DeepLinkAction::Run { command, cwd } => (
+            "Run command from link?".to_string(),
+            match cwd {
+                Some(cwd) => format!("{}\n(in {})", command, cwd),
+                None => command.clone(),
+            },
+        ),
+        DeepLinkAction::Ssh { target } => (
+            "Open SSH session from link?".to_string(),
+            format!("ssh {}", target),
+        ),
+    }
+}
+
+fn request_deep_link<R: Runtime>(app_handle: &AppHandle<R>, raw_url: &str) -> Result<(), String> {
+    let action = parse_deep_link(raw_url)?;
+    let (title, detail) = describe(&action);
+    let confirmation = app_handle.state::<ConfirmationManager>().issue(
+        &format!("deep_link:{}", raw_url),
+        &title,
+        &detail,
+    )?;
+    app_handle
+        .emit(
+            "deep_link_request",
+            DeepLinkRequestEvent {
+                url: raw_url.to_string(),
+                action,
+                confirmation,
+            },
+        )
+        .map_err(|e| e.to_string())
+}
+
+fn resolve_cwd(cwd: Option<&str>) -> Result<String, String> {
+    let path = match cwd {
+        Some(dir) if dir.starts_with('~') => {
+            let home = dirs::home_dir().ok_or("Could not determine home directory")?;
+            home.join(dir.trim_start_matches('~').trim_start_matches('/'))
+        }
+        Some(dir) => PathBuf::from(dir),
+        None => dirs::home_dir().ok_or("Could not determine home directory")?,
+    };
+    if !path.is_dir() {
+        return Err(format!("Directory not found: {}", path.display()));
+    }
+    Ok(path.to_string_lossy().to_string())
+}
+
+// Prepares a session for a confirmed deep link. Without a valid token the request is
+// (re)issued to the frontend and the confirmation marker is returned instead.
+#[command]
+pub fn open_deep_link<R: Runtime>(
+    url: String,
+    confirmation_token: Option<String>,
+    app_handle: AppHandle<R>,
+    command_manager: State<'_, CommandManager>,
+    confirmation_manager: State<'_, ConfirmationManager>,
+) -> Result<String, String> {
+    let action = parse_deep_link(&url)?;
+
+    let confirmed = confirmation_token
+        .map(|token| confirmation_manager.consume(&token, &format!("deep_link:{}", url)))
+        .unwrap_or(false);
+    if !confirmed {
+        request_deep_link(&app_handle, &url)?;
+        return Ok(CONFIRMATION_REQUIRED_MARKER.to_string());
+    }
+
+    let (cwd, command_line) = match action {
+        DeepLinkAction::Run { command, cwd } => (resolve_cwd(cwd.as_deref())?, command),
+        DeepLinkAction::Ssh { target } => (resolve_cwd(None)?, format!("ssh {}", target)),
+    };
+
+    let session_id = format!("deeplink-{}", uuid::Uuid::new_v4());
+    {
+        let mut states = command_manager.commands.lock().map_err(|e| e.to_string())?;
+        states.insert(session_id.clone(), CommandState::new(cwd.clone()));
+    }
+
+    app_handle
+        .emit(
+            "deep_link_open_session",
+            DeepLinkSessionEvent {
+                session_id: session_id.clone(),
+                cwd,
+                command: command_line,
+            },
+        )
+        .map_err(|e| e.to_string())?;
+    Ok(session_id)
+}
@@ -0,0 +1,310 @@
+use crate::command::autocomplete::tldr::{
+    placeholders_to_usage, tldr_commands, tldr_page, TldrExample, TldrPage,
+};
+use crate::command::constants::{
+    MAX_CHEATSHEET_RESULTS, TLDR_DOWNLOAD_TIMEOUT_MS, TLDR_INDEX_URL, TLDR_MAX_AGE_MS,
+    TLDR_PAGES_URL,
+};
+use crate::utils::config_utils::app_data_dir;
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::{Mutex, OnceLock};
+use std::time::{Duration, SystemTime};
+use tauri::{command, AppHandle, Runtime};
+
+// Entry of the tldr-pages index: a command and the platforms it has pages for
+#[derive(Deserialize, Clone, Debug)]
+struct IndexEntry {
+    name: String,
+    #[serde(default)]
+    platform: Vec<String>,
+}
+
+#[derive(Deserialize)]
+struct TldrIndex {
+    commands: Vec<IndexEntry>,
+}
+
+#[derive(Serialize, Clone, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct CheatsheetMatch {
+    pub command: String,
+    // Known without a download for bundled pages only
+    pub description: Option<String>,
+    pub platforms: Vec<String>,
+}
+
+// Downloaded index, loaded from the data directory on first use and replaced on refresh
+static INDEX_CACHE: OnceLock<Mutex<Option<Vec<IndexEntry>>>> = OnceLock::new();
+
+fn tldr_dir() -> Result<PathBuf, String> {
+    Ok(app_data_dir()?.join("tldr"))
+}
+
+fn index_path() -> Result<PathBuf, String> {
+    Ok(tldr_dir()?.join("index.json"))
+}
+
+fn page_path(platform: &str, command: &str) -> Result<PathBuf, String> {
+    Ok(tldr_dir()?
+        .join("pages")
+        .join(platform)
+        .join(format!("{}.md", command)))
+}
+
+fn is_fresh(path: &Path) -> bool {
+    fs::metadata(path)
+        .and_then(|metadata| metadata.modified())
+        .ok()
+        .and_then(|modified| SystemTime::now().duration_since(modified).ok())
+        .is_some_and(|age| age < Duration::from_millis(TLDR_MAX_AGE_MS))
+}
+
+// tldr page names are lowercase words such as `git-commit` or `7z`
+fn is_page_name(command: &str) -> bool {
+    !command.is_empty()
+        && command
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || matches!(c, '-' | '_' | '.' | '+'))
+        && !command.starts_with('.')
+}
+
+// Platform directories to look in, most specific first
+fn preferred_platforms() -> &'static [&'static str] {
+    if cfg!(target_os = "macos") {
+        &["osx", "common"]
+    } else if cfg!(windows) {
+        &["windows", "common"]
+    } else {
+        &["linux", "common"]
+    }
+}
+
+fn load_index() -> Option<Vec<IndexEntry>> {
+    let contents = fs::read_to_string(index_path().ok()?).ok()?;
+    serde_json::from_str::<TldrIndex>(&contents)
+        .ok()
+        .map(|index| index.commands)
+}
+
+fn with_index<T>(f: impl FnOnce(Option<&[IndexEntry]>) -> T) -> T {
+    let cache = INDEX_CACHE.get_or_init(|| Mutex::new(None));
+    match cache.lock() {
+        Ok(mut cache) => {
+            if cache.is_none() {
+                *cache = load_index();
+            }
+            f(cache.as_deref())
+        }
+        Err(_) => f(None),
+    }
+}
+
+async fn download(url: &str) -> Result<String, String> {
+    let response = reqwest::Client::new()
+        .get(url)
+        .timeout(Duration::from_millis(TLDR_DOWNLOAD_TIMEOUT_MS))
+        .send()
+        .await
+        .map_err(|e| format!("Failed to download {}: {}", url, e))?;
+    if !response.status().is_success() {
+        return Err(format!("Failed to download {}: {}", url, response.status()));
+    }
+    response
+        .text()
+        .await
+        .map_err(|e| format!("Failed to download {}: {}", url, e))
+}
+
+fn write_file(path: &Path, contents: &str) -> Result<(), String> {
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)
+            .map_err(|e| format!("Failed to create {}: {}", parent.display(), e))?;
+    }
+    fs::write(path, contents).map_err(|e| format!("Failed to write {}: {}", path.display(), e))
+}
+
+// Downloads the index unless the copy on disk is recent
+pub async fn refresh_tldr_index(force: bool) -> Result<(), String> {
+    let path = index_path()?;
+    if !force && is_fresh(&path) {
+        return Ok(());
+    }
+    let contents = download(TLDR_INDEX_URL).await?;
+    let index: TldrIndex = serde_json::from_str(&contents)
+        .map_err(|e| format!("Invalid tldr index from {}: {}", TLDR_INDEX_URL, e))?;
+    write_file(&path, &contents)?;
+    if let Ok(mut cache) = INDEX_CACHE.get_or_init(|| Mutex::new(None)).lock() {
+        *cache = Some(index.commands);
+    }
+    Ok(())
+}
+
+// Scheduler task: keeps the downloaded index from going stale
+pub fn refresh_cheatsheets<R: Runtime>(_app_handle: &AppHandle<R>) -> Result<(), String> {
+    tauri::async_runtime::block_on(refresh_tldr_index(false))
+}
+
+// A tldr page in its markdown form:
+//   # tar
+//   > Archiving utility.
+//   - Extract an archive:
+//   `tar xf {{path/to/source.tar}}`
+pub fn parse_tldr_markdown(command: &str, markdown: &str) -> Option<TldrPage> {
+    let mut description: Vec<&str> = Vec::new();
+    let mut examples = Vec::new();
+    let mut pending: Option<String> = None;
+    for line in markdown.lines().map(str::trim) {
+        if let Some(text) = line.strip_prefix('>') {
+            let text = text.trim();
+            if !text.starts_with("More information") {
+                description.push(text);
+            }
+        } else if let Some(text) = line.strip_prefix("- ") {
+            pending = Some(text.trim_end_matches(':').to_string());
+        } else if line.len() > 1 && line.starts_with('`') && line.ends_with('`') {
+            if let Some(example_description) = pending.take() {
+                examples.push(TldrExample {
+                    description: example_description,
+                    usage: placeholders_to_usage(&line[1..line.len() - 1]),
+                });
+            }
+        }
+    }
+    if examples.is_empty() {
+        return None;
+    }
+    Some(TldrPage {
+        command: command.to_string(),
+        description: description.join(" "),
+        examples,
+    })
+}
+
+fn cached_page(command: &str, fresh_only: bool) -> Option<TldrPage> {
+    preferred_platforms().iter().find_map(|platform| {
+        let path = page_path(platform, command).ok()?;
+        if fresh_only && !is_fresh(&path) {
+            return None;
+        }
+        parse_tldr_markdown(command, &fs::read_to_string(path).ok()?)
+    })
+}
+
+async fn download_page(command: &str) -> Option<TldrPage> {
+    let platforms: Vec<String> = with_index(|index| match index {
+        Some(index) => {
+            let entry = index.iter().find(|entry| entry.name == command)?;
+            let mut platforms: Vec<String> = preferred_platforms()
+                .iter()
+                .filter(|platform| entry.platform.iter().any(|p| p == *platform))
+                .map(|platform| platform.to_string())
+                .collect();
+            // A page only written for another platform is still better than nothing
+            platforms.extend(
+                entry
+                    .platform
+                    .iter()
+                    .filter(|platform| !platforms.contains(platform))
+                    .cloned()
+                    .collect::<Vec<_>>(),
+            );
+            Some(platforms)
+        }
+        None => Some(
+            preferred_platforms()
+                .iter()
+                .map(|platform| platform.to_string())
+                .collect(),
+        ),
+    })?;
+    for platform in platforms {
+        let url = format!("{}/{}/{}.md", TLDR_PAGES_URL, platform, command);
+        let Ok(markdown) = download(&url).await else {
+            continue;
+        };
+        let Some(page) = parse_tldr_markdown(command, &markdown) else {
+            continue;
+        };
+        if let Ok(path) = page_path(&platform, command) {
+            let _ = write_file(&path, &markdown);
+        }
+        return Some(page);
+    }
+    None
+}
+
+// Examples for a command: a recently downloaded page, a fresh download, an outdated copy when
+// offline, and finally the bundled page
+#[command]
+pub async fn get_cheatsheet(command: String) -> Result<Option<TldrPage>, String> {
+    let command = command.trim().to_lowercase().replace(' ', "-");
+    if !is_page_name(&command) {
+        return Err(format!("Invalid command name '{}'", command));
+    }
+    if let Some(page) = cached_page(&command, true) {
+        return Ok(Some(page));
+    }
+    if let Some(page) = download_page(&command).await {
+        return Ok(Some(page));
+    }
+    Ok(cached_page(&command, false).or_else(|| tldr_page(&command).cloned()))
+}
+
+// Commands with a page whose name (or, for bundled pages, description) contains the query;
+// exact and prefix matches first
+#[command]
+pub fn search_cheatsheets(query: String) -> Vec<CheatsheetMatch> {
+    let query = query.trim().to_lowercase();
+    if query.is_empty() {
+        return Vec::new();
+    }
+    let mut matches: Vec<(u8, CheatsheetMatch)> = with_index(|index| {
+        index
+            .unwrap_or_default()
+            .iter()
+            .map(|entry| CheatsheetMatch {
+                command: entry.name.clone(),
+                description: tldr_page(&entry.name).map(|page| page.description.clone()),
+                platforms: entry.platform.clone(),
+            })
+            .collect::<Vec<_>>()
+    })
+    .into_iter()
+    .chain(tldr_commands().map(|page| CheatsheetMatch {
+        command: page.command.clone(),
+        description: Some(page.description.clone()),
+        platforms: vec!["common".to_string()],
+    }))
+    .filter_map(|candidate| {
+        let name = candidate.command.to_lowercase();
+        let rank = if name == query {
+            0
+        } else if name.starts_with(&query) {
+            1
+        } else if name.contains(&query) {
+            2
+        } else if candidate
+            .description
+            .as_ref()
+            .is_some_and(|description| description.to_lowercase().contains(&query))
+        {
+            3
+        } else {
+            return None;
+        };
+        Some((rank, candidate))
+    })
+    .collect();
+    matches.sort_by(|(a_rank, a), (b_rank, b)| {
+        a_rank.cmp(b_rank).then_with(|| a.command.cmp(&b.command))
+    });
+    // Bundled pages are usually in the index too
+    matches.dedup_by(|(_, a), (_, b)| a.command == b.command);
+    matches
+        .into_iter()
+        .take(MAX_CHEATSHEET_RESULTS)
+        .map(|(_, candidate)| candidate)
+        .collect()
+}
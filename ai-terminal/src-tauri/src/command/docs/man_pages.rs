@@ -0,0 +1,1243 @@
+use serde::Serialize;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use tauri::command;
+
+// Searched when no section is requested, in the order man(1) uses
+const SECTION_ORDER: &[&str] = &["1", "8", "6", "2", "3", "5", "7", "4", "9"];
+
+// Used when neither MANPATH nor manpath(1) say where pages live
+const DEFAULT_MAN_DIRS: &[&str] = &[
+    "/usr/local/share/man",
+    "/usr/share/man",
+    "/usr/local/man",
+    "/opt/homebrew/share/man",
+    "/opt/local/share/man",
+];
+
+// Compressed pages are decompressed with the matching tool
+const DECOMPRESSORS: &[(&str, &str)] = &[
+    ("gz", "gzip"),
+    ("bz2", "bzip2"),
+    ("xz", "xz"),
+    ("zst", "zstd"),
+];
+
+// `.so` redirects followed before giving up
+const MAX_SO_DEPTH: usize = 3;
+
+#[derive(Serialize, Clone, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct ManPage {
+    pub command: String,
+    pub section: String,
+    pub path: String,
+    // From .TH / .Dt, e.g. "LS(1)"
+    pub title: String,
+    // Section headings in order, for a table of contents
+    pub headings: Vec<String>,
+    pub text: String,
+    pub html: String,
+}
+
+// Directories holding man1, man2, ... subdirectories
+fn man_dirs() -> Vec<PathBuf> {
+    let configured = std::env::var("MANPATH").ok().or_else(|| {
+        Command::new("manpath")
+            .output()
+            .ok()
+            .filter(|output| output.status.success())
+            .map(|output| String::from_utf8_lossy(&output.stdout).trim().to_string())
+    });
+    let mut dirs: Vec<PathBuf> = configured
+        .iter()
+        .flat_map(|value| std::env::split_paths(value).collect::<Vec<_>>())
+        .filter(|dir| !dir.as_os_str().is_empty())
+        .collect();
+    // man also looks next to each PATH entry, e.g. /opt/tool/bin -> /opt/tool/share/man
+    if let Ok(path) = std::env::var("PATH") {
+        for bin in std::env::split_paths(&path) {
+            if let Some(prefix) = bin.parent() {
+                dirs.push(prefix.join("share/man"));
+                dirs.push(prefix.join("man"));
+            }
+        }
+    }
+    dirs.extend(DEFAULT_MAN_DIRS.iter().map(PathBuf::from));
+    let mut unique = Vec::new();
+    for dir in dirs {
+        if dir.is_dir() && !unique.contains(&dir) {
+            unique.push(dir);
+        }
+    }
+    unique
+}
+
+fn strip_compression(file_name: &str) -> &str {
+    DECOMPRESSORS
+        .iter()
+        .find_map(|(extension, _)| file_name.strip_suffix(&format!(".{}", extension)))
+        .unwrap_or(file_name)
+}
+
+// `ls.1.gz` in man1 for section "1"; `printf.3p.gz` also matches a request for section "3"
+fn find_in_section(root: &Path, command: &str, section: &str) -> Option<(PathBuf, String)> {
+    let main_section = &section[..1];
+    let mut dirs: Vec<PathBuf> = fs::read_dir(root)
+        .ok()?
+        .flatten()
+        .map(|entry| entry.path())
+        .filter(|dir| {
+            dir.file_name()
+                .and_then(|name| name.to_str())
+                .and_then(|name| name.strip_prefix("man"))
+                .is_some_and(|dir_section| dir_section.starts_with(main_section))
+        })
+        .collect();
+    dirs.sort();
+    let prefix = format!("{}.", command);
+    dirs.iter().find_map(|dir| {
+        let mut candidates: Vec<(PathBuf, String)> = fs::read_dir(dir)
+            .ok()?
+            .flatten()
+            .filter_map(|entry| {
+                let file_name = entry.file_name().to_string_lossy().to_string();
+                let page_section = strip_compression(&file_name).strip_prefix(&prefix)?;
+                page_section
+                    .starts_with(section)
+                    .then(|| (entry.path(), page_section.to_string()))
+            })
+            .collect();
+        // Prefer the exact section over extensions such as 1ssl
+        candidates.sort_by_key(|(_, page_section)| (page_section != section, page_section.len()));
+        candidates.into_iter().next()
+    })
+}
+
+fn find_man_page(command: &str, section: Option<&str>) -> Result<(PathBuf, String), String> {
+    let sections: Vec<&str> = match section {
+        Some(section) => vec![section],
+        None => SECTION_ORDER.to_vec(),
+    };
+    let roots = man_dirs();
+    sections
+        .iter()
+        .find_map(|section| {
+            roots
+                .iter()
+                .find_map(|root| find_in_section(root, command, section))
+        })
+        .ok_or_else(|| match section {
+            Some(section) => format!("No manual entry for {} in section {}", command, section),
+            None => format!("No manual entry for {}", command),
+        })
+}
+
+fn read_page_source(path: &Path) -> Result<String, String> {
+    let file_name = path
+        .file_name()
+        .and_then(|name| name.to_str())
+        .unwrap_or("");
+    let decompressor = DECOMPRESSORS
+        .iter()
+        .find(|(extension, _)| file_name.ends_with(&format!(".{}", extension)));
+    let bytes = match decompressor {
+        Some((_, tool)) => {
+            let output = Command::new(tool)
+                .arg("-dc")
+                .arg(path)
+                .output()
+                .map_err(|e| format!("Failed to run {} for {}: {}", tool, path.display(), e))?;
+            if !output.status.success() {
+                return Err(format!(
+                    "Failed to decompress {}: {}",
+                    path.display(),
+                    String::from_utf8_lossy(&output.stderr).trim()
+                ));
+            }
+            output.stdout
+        }
+        None => fs::read(path).map_err(|e| format!("Failed to read {}: {}", path.display(), e))?,
+    };
+    Ok(String::from_utf8_lossy(&bytes).to_string())
+}
+
+// Pages that are only `.so man1/other.1` are read from the page they point to
+fn load_page(path: &Path, depth: usize) -> Result<String, String> {
+    let source = read_page_source(path)?;
+    let redirect = source
+        .lines()
+        .find(|line| !line.starts_with(".\\\"") && !line.trim().is_empty())
+        .and_then(|line| line.strip_prefix(".so "))
+        .map(str::trim);
+    let Some(target) = redirect.filter(|_| depth < MAX_SO_DEPTH) else {
+        return Ok(source);
+    };
+    // .so paths are relative to the directory holding man1, man2, ...
+    let root = path
+        .parent()
+        .and_then(Path::parent)
+        .ok_or_else(|| format!("Cannot resolve .so {} from {}", target, path.display()))?;
+    let target_path = root.join(target);
+    let candidates =
+        std::iter::once(target_path.clone()).chain(DECOMPRESSORS.iter().map(|(extension, _)| {
+            PathBuf::from(format!("{}.{}", target_path.display(), extension))
+        }));
+    for candidate in candidates {
+        if candidate.is_file() {
+            return load_page(&candidate, depth + 1);
+        }
+    }
+    Err(format!(
+        "Page {} points to missing {}",
+        path.display(),
+        target
+    ))
+}
+
+#[derive(Clone, Copy, PartialEq, Debug)]
+enum Font {
+    Roman,
+    Bold,
+    Italic,
+}
+
+#[derive(Clone, Debug)]
+struct Span {
+    font: Font,
+    text: String,
+}
+
+fn roman(text: &str) -> Span {
+    Span {
+        font: Font::Roman,
+        text: text.to_string(),
+    }
+}
+
+enum Block {
+    Heading {
+        level: u8,
+        text: String,
+    },
+    Paragraph {
+        indent: usize,
+        spans: Vec<Span>,
+    },
+    Tagged {
+        indent: usize,
+        tag: Vec<Span>,
+        body: Vec<Span>,
+    },
+    Preformatted {
+        indent: usize,
+        lines: Vec<Vec<Span>>,
+    },
+}
+
+// Kind of the innermost mdoc .Bl list and the number of its next .It for -enum lists
+enum ListKind {
+    Tag,
+    Bullet,
+    Dash,
+    Enum(usize),
+}
+
+// Turns man(7) and mdoc(7) source into blocks of styled text. Only what matters for reading
+// is interpreted; layout requests such as .ad, .ne or .in are ignored.
+struct Renderer {
+    blocks: Vec<Block>,
+    current: Vec<Span>,
+    tag: Option<Vec<Span>>,
+    // .TP: the next text line is the tag
+    awaiting_tag: bool,
+    // .SH / .SS without arguments: the next text line is the heading
+    awaiting_heading: Option<u8>,
+    preformatted: Option<Vec<Vec<Span>>>,
+    indent: usize,
+    lists: Vec<ListKind>,
+    font: Font,
+    previous_font: Font,
+    title: String,
+    // mdoc .Nm without arguments repeats the first name given
+    name: Option<String>,
+    // mdoc .Sm off: words are joined without spaces, except to what came before
+    no_spacing: bool,
+    spaced_once: bool,
+    // mdoc .Pp inside a list item, applied once more text follows
+    paragraph_break: bool,
+}
+
+fn split_args(text: &str) -> Vec<String> {
+    let mut args = Vec::new();
+    let mut chars = text.chars().peekable();
+    loop {
+        while chars.peek().is_some_and(|c| *c == ' ' || *c == '\t') {
+            chars.next();
+        }
+        let Some(&first) = chars.peek() else {
+            break;
+        };
+        let mut arg = String::new();
+        if first == '"' {
+            chars.next();
+            while let Some(c) = chars.next() {
+                if c == '"' {
+                    // "" inside a quoted argument is a literal quote
+                    if chars.peek() == Some(&'"') {
+                        chars.next();
+                        arg.push('"');
+                        continue;
+                    }
+                    break;
+                }
+                arg.push(c);
+            }
+        } else {
+            while let Some(&c) = chars.peek() {
+                if c == ' ' || c == '\t' {
+                    break;
+                }
+                arg.push(c);
+                chars.next();
+            }
+        }
+        args.push(arg);
+    }
+    args
+}
+
+fn special_character(name: &str) -> &'static str {
+    match name {
+        "em" => "\u{2014}",
+        "en" => "\u{2013}",
+        "hy" | "mi" | "-" => "-",
+        "bu" => "\u{2022}",
+        "co" => "\u{a9}",
+        "rg" => "\u{ae}",
+        "tm" => "\u{2122}",
+        "lq" | "rq" | "dq" | "Lq" | "Rq" => "\"",
+        "oq" | "cq" | "aq" => "'",
+        "ga" => "`",
+        "ti" | "ap" => "~",
+        "ha" | "a^" => "^",
+        "rs" => "\\",
+        "mu" => "\u{d7}",
+        "di" => "\u{f7}",
+        "<=" => "\u{2264}",
+        ">=" => "\u{2265}",
+        "!=" => "\u{2260}",
+        "->" | "rh" => "\u{2192}",
+        "<-" => "\u{2190}",
+        "de" => "\u{b0}",
+        "sc" => "\u{a7}",
+        "ba" | "or" => "|",
+        "lB" => "[",
+        "rB" => "]",
+        "lC" => "{",
+        "rC" => "}",
+        "pl" => "+",
+        "eq" => "=",
+        "sl" => "/",
+        "ss" => "\u{df}",
+        _ => "",
+    }
+}
+
+fn predefined_string(name: &str) -> &'static str {
+    match name {
+        "R" => "\u{ae}",
+        "Tm" => "\u{2122}",
+        "lq" | "rq" | "Lq" | "Rq" => "\"",
+        _ => "",
+    }
+}
+
+impl Renderer {
+    fn new() -> Self {
+        Renderer {
+            blocks: Vec::new(),
+            current: Vec::new(),
+            tag: None,
+            awaiting_tag: false,
+            awaiting_heading: None,
+            preformatted: None,
+            indent: 0,
+            lists: Vec::new(),
+            font: Font::Roman,
+            previous_font: Font::Roman,
+            title: String::new(),
+            name: None,
+            no_spacing: false,
+            spaced_once: false,
+            paragraph_break: false,
+        }
+    }
+
+    fn set_font(&mut self, font: Font) {
+        self.previous_font = self.font;
+        self.font = font;
+    }
+
+    // Reads a name after \( (two characters), \[ (up to ]) or a single character
+    fn escape_name(chars: &mut std::iter::Peekable<std::str::Chars<'_>>) -> String {
+        match chars.next() {
+            Some('(') => chars.by_ref().take(2).collect(),
+            Some('[') => chars.by_ref().take_while(|c| *c != ']').collect(),
+            Some(c) => c.to_string(),
+            None => String::new(),
+        }
+    }
+
+    // Text with escapes such as \fB, \-, \(em resolved into spans
+    fn inline(&mut self, text: &str) -> Vec<Span> {
+        let mut spans: Vec<Span> = Vec::new();
+        let mut buffer = String::new();
+        let mut chars = text.chars().peekable();
+        let flush = |spans: &mut Vec<Span>, buffer: &mut String, font: Font| {
+            if !buffer.is_empty() {
+                spans.push(Span {
+                    font,
+                    text: std::mem::take(buffer),
+                });
+            }
+        };
+        while let Some(c) = chars.next() {
+            if c != '\\' {
+                buffer.push(c);
+                continue;
+            }
+            let Some(escape) = chars.next() else {
+                break;
+            };
+            match escape {
+                'f' => {
+                    flush(&mut spans, &mut buffer, self.font);
+                    let font = Self::escape_name(&mut chars);
+                    match font.as_str() {
+                        "B" | "BI" | "CB" | "3" => self.set_font(Font::Bold),
+                        "I" | "CI" | "2" => self.set_font(Font::Italic),
+                        "P" => {
+                            let previous = self.previous_font;
+                            self.set_font(previous);
+                        }
+                        _ => self.set_font(Font::Roman),
+                    }
+                }
+                '(' | '[' => {
+                    let name: String = if escape == '(' {
+                        chars.by_ref().take(2).collect()
+                    } else {
+                        chars.by_ref().take_while(|c| *c != ']').collect()
+                    };
+                    buffer.push_str(special_character(&name));
+                }
+                '*' => buffer.push_str(predefined_string(&Self::escape_name(&mut chars))),
+                'n' | 'g' | 'k' | 'F' | 'm' | 'M' | 'V' | 'Y' => {
+                    Self::escape_name(&mut chars);
+                }
+                's' => {
+                    if matches!(chars.peek(), Some('+') | Some('-')) {
+                        chars.next();
+                    }
+                    while chars.peek().is_some_and(|c| c.is_ascii_digit()) {
+                        chars.next();
+                    }
+                }
+                'w' | 'h' | 'v' | 'X' | 'o' | 'b' | 'l' | 'L' | 'D' | 'A' | 'N' | 'Z' => {
+                    // Delimited argument, e.g. \h'2n'
+                    if let Some(delimiter) = chars.next() {
+                        for c in chars.by_ref() {
+                            if c == delimiter {
+                                break;
+                            }
+                        }
+                    }
+                }
+                '"' => break,
+                'e' | '\\' => buffer.push('\\'),
+                '-' => buffer.push('-'),
+                ' ' | '~' | '0' => buffer.push(' '),
+                '\'' => buffer.push('\''),
+                '`' => buffer.push('`'),
+                '.' => buffer.push('.'),
+                '&' | '|' | '^' | '/' | ',' | ':' | '%' | 'c' | 'd' | 'u' | 'p' | 'a' | 't'
+                | '{' | '}' | 'r' | 'z' => {}
+                other => buffer.push(other),
+            }
+        }
+        flush(&mut spans, &mut buffer, self.font);
+        spans
+    }
+
+    // Arguments in alternating fonts without spaces between them, as .BR and friends do
+    fn alternating(&mut self, args: &[String], fonts: [Font; 2]) -> Vec<Span> {
+        let mut spans = Vec::new();
+        for (index, arg) in args.iter().enumerate() {
+            let saved = (self.font, self.previous_font);
+            self.font = fonts[index % 2];
+            spans.extend(self.inline(arg));
+            (self.font, self.previous_font) = saved;
+        }
+        spans
+    }
+
+    fn in_font(&mut self, font: Font, text: &str) -> Vec<Span> {
+        let saved = (self.font, self.previous_font);
+        self.font = font;
+        let spans = self.inline(text);
+        (self.font, self.previous_font) = saved;
+        spans
+    }
+
+    fn flush(&mut self) {
+        self.paragraph_break = false;
+        let spans = std::mem::take(&mut self.current);
+        match self.tag.take() {
+            Some(tag) => self.blocks.push(Block::Tagged {
+                indent: self.indent,
+                tag,
+                body: spans,
+            }),
+            None if !spans.is_empty() => self.blocks.push(Block::Paragraph {
+                indent: self.indent,
+                spans,
+            }),
+            None => {}
+        }
+    }
+
+    fn push_spans(&mut self, spans: Vec<Span>) {
+        if let Some(level) = self.awaiting_heading.take() {
+            let text: String = spans.iter().map(|span| span.text.as_str()).collect();
+            self.heading(level, &text);
+            return;
+        }
+        if self.awaiting_tag {
+            self.awaiting_tag = false;
+            self.tag = Some(spans);
+            return;
+        }
+        if let Some(lines) = self.preformatted.as_mut() {
+            lines.push(spans);
+            return;
+        }
+        if spans.iter().all(|span| span.text.is_empty()) {
+            return;
+        }
+        let needs_space = self
+            .current
+            .last()
+            .is_some_and(|span| !span.text.ends_with([' ', '\n']));
+        if self.paragraph_break && !self.current.is_empty() {
+            self.current.push(roman("\n\n"));
+        } else if needs_space && (!self.no_spacing || !self.spaced_once) {
+            self.current.push(roman(" "));
+        }
+        self.paragraph_break = false;
+        self.spaced_once = true;
+        self.current.extend(spans);
+    }
+
+    fn heading(&mut self, level: u8, text: &str) {
+        self.flush();
+        self.indent = 0;
+        self.lists.clear();
+        self.blocks.push(Block::Heading {
+            level,
+            text: text.trim().to_string(),
+        });
+    }
+
+    fn text_line(&mut self, line: &str) {
+        if self.preformatted.is_none() && line.trim().is_empty() {
+            // A blank line in filled text separates paragraphs
+            self.flush();
+            return;
+        }
+        let spans = self.inline(line);
+        self.push_spans(spans);
+    }
+
+    fn start_preformatted(&mut self) {
+        self.flush();
+        if self.preformatted.is_none() {
+            self.preformatted = Some(Vec::new());
+        }
+    }
+
+    fn end_preformatted(&mut self) {
+        if let Some(lines) = self.preformatted.take() {
+            self.blocks.push(Block::Preformatted {
+                indent: self.indent,
+                lines,
+            });
+        }
+    }
+
+    // mdoc line whose words may be callable macros, e.g. `Op Fl a Ar file`
+    fn mdoc_spans(&mut self, args: &[String]) -> Vec<Span> {
+        const CLOSING: &[&str] = &[".", ",", ";", ":", "?", "!", ")", "]"];
+        const OPENING: &[&str] = &["(", "["];
+        let mut spans: Vec<Span> = Vec::new();
+        let mut closers: Vec<&str> = Vec::new();
+        let mut mode: Option<(Font, &str)> = None;
+        let mut no_space = true;
+        let spacing = !self.no_spacing;
+        let mut index = 0;
+        while index < args.len() {
+            let arg = args[index].as_str();
+            index += 1;
+            let mut word: Option<Span> = None;
+            match arg {
+                "Fl" => {
+                    mode = Some((Font::Bold, "-"));
+                    let takes_arg = args.get(index).is_some_and(|next| {
+                        !CLOSING.contains(&next.as_str()) && !is_mdoc_macro(next)
+                    });
+                    if !takes_arg {
+                        word = Some(Span {
+                            font: Font::Bold,
+                            text: "-".to_string(),
+                        });
+                    }
+                }
+                "Ar" => {
+                    mode = Some((Font::Italic, ""));
+                    let takes_arg = args.get(index).is_some_and(|next| {
+                        !CLOSING.contains(&next.as_str()) && !is_mdoc_macro(next)
+                    });
+                    if !takes_arg {
+                        word = Some(Span {
+                            font: Font::Italic,
+                            text: "file ...".to_string(),
+                        });
+                    }
+                }
+                "Cm" | "Sy" | "Ic" | "Li" | "Cd" | "Fn" | "Fd" | "In" => {
+                    mode = Some((Font::Bold, ""))
+                }
+                "Em" | "Pa" | "Va" | "Ev" | "Ad" | "Fa" | "Vt" | "Ft" | "Lk" | "Mt" => {
+                    mode = Some((Font::Italic, ""))
+                }
+                "Nm" => {
+                    let has_arg = args.get(index).is_some_and(|next| {
+                        !CLOSING.contains(&next.as_str()) && !is_mdoc_macro(next)
+                    });
+                    if has_arg {
+                        if self.name.is_none() {
+                            self.name = args.get(index).cloned();
+                        }
+                        mode = Some((Font::Bold, ""));
+                    } else if let Some(name) = self.name.clone() {
+                        mode = None;
+                        word = Some(Span {
+                            font: Font::Bold,
+                            text: name,
+                        });
+                    }
+                }
+                "Xr" => {
+                    let name = args.get(index).cloned().unwrap_or_default();
+                    let section = args.get(index + 1).cloned().unwrap_or_default();
+                    index += 2;
+                    mode = None;
+                    word = Some(Span {
+                        font: Font::Bold,
+                        text: format!("{}({})", name, section),
+                    });
+                }
+                "Op" | "Oo" | "Bq" | "Bo" => {
+                    mode = None;
+                    word = Some(roman("["));
+                    closers.push("]");
+                }
+                "Pq" | "Po" => {
+                    mode = None;
+                    word = Some(roman("("));
+                    closers.push(")");
+                }
+                "Dq" | "Do" => {
+                    mode = None;
+                    word = Some(roman("\u{201c}"));
+                    closers.push("\u{201d}");
+                }
+                "Sq" | "So" | "Ql" => {
+                    mode = None;
+                    word = Some(roman("\u{2018}"));
+                    closers.push("\u{2019}");
+                }
+                "Oc" | "Bc" | "Pc" | "Dc" | "Sc" => {
+                    mode = None;
+                    if let Some(closer) = closers.pop() {
+                        spans.push(roman(closer));
+                    }
+                    continue;
+                }
+                "Ns" => {
+                    no_space = true;
+                    continue;
+                }
+                "No" | "Pf" => mode = None,
+                "Ux" => word = Some(roman("UNIX")),
+                "Bx" => word = Some(roman("BSD")),
+                "Dx" | "Fx" | "Nx" | "Ox" => word = Some(roman(arg)),
+                "At" => word = Some(roman("AT&T UNIX")),
+                "St" | "Lb" => mode = None,
+                _ => {
+                    let text = if CLOSING.contains(&arg) || OPENING.contains(&arg) {
+                        arg.to_string()
+                    } else {
+                        match mode {
+                            Some((_, prefix)) => format!("{}{}", prefix, arg),
+                            None => arg.to_string(),
+                        }
+                    };
+                    let font = match mode {
+                        Some((font, _)) if !CLOSING.contains(&arg) => font,
+                        _ => Font::Roman,
+                    };
+                    let mut resolved = self.in_font(font, &text);
+                    let attach = CLOSING.contains(&arg);
+                    if spacing && !no_space && !attach && !resolved.is_empty() {
+                        spans.push(roman(" "));
+                    }
+                    no_space = OPENING.contains(&arg);
+                    spans.append(&mut resolved);
+                    continue;
+                }
+            }
+            if let Some(word) = word {
+                if spacing && !no_space {
+                    spans.push(roman(" "));
+                }
+                // Opening brackets and quotes hug what follows
+                no_space = matches!(word.text.as_str(), "[" | "(" | "\u{201c}" | "\u{2018}");
+                spans.push(word);
+            }
+        }
+        while let Some(closer) = closers.pop() {
+            spans.push(roman(closer));
+        }
+        spans
+    }
+
+    fn mdoc_list_item(&mut self, args: &[String]) {
+        self.flush();
+        let tag = match self.lists.last_mut() {
+            Some(ListKind::Bullet) => vec![roman("\u{2022}")],
+            Some(ListKind::Dash) => vec![roman("-")],
+            Some(ListKind::Enum(number)) => {
+                *number += 1;
+                vec![roman(&format!("{}.", number))]
+            }
+            Some(ListKind::Tag) | None => self.mdoc_spans(args),
+        };
+        self.tag = Some(tag);
+    }
+
+    fn request(&mut self, line: &str) {
+        let body = line[1..].trim_start();
+        let (name, rest) = match body.find([' ', '\t']) {
+            Some(pos) => (&body[..pos], body[pos..].trim_start()),
+            None => (body, ""),
+        };
+        let args = split_args(rest);
+        match name {
+            "" | "\\\"" => {}
+            // man(7)
+            "TH" => {
+                self.title = format!(
+                    "{}({})",
+                    args.first().cloned().unwrap_or_default(),
+                    args.get(1).cloned().unwrap_or_default()
+                );
+            }
+            "SH" | "SS" => {
+                let level = if name == "SH" { 2 } else { 3 };
+                self.end_preformatted();
+                if rest.is_empty() {
+                    self.flush();
+                    self.awaiting_heading = Some(level);
+                } else {
+                    let text: String = self
+                        .inline(&args.join(" "))
+                        .into_iter()
+                        .map(|span| span.text)
+                        .collect();
+                    self.heading(level, &text);
+                }
+            }
+            // A new paragraph inside an mdoc list item stays in the item's body
+            "Pp" | "Lp" if self.tag.is_some() && !self.lists.is_empty() => {
+                self.paragraph_break = true;
+            }
+            "PP" | "LP" | "P" | "HP" | "Pp" | "Lp" => self.flush(),
+            "Sm" => {
+                self.no_spacing = args.first().is_some_and(|arg| arg == "off");
+                self.spaced_once = false;
+            }
+            "TP" | "TQ" => {
+                self.flush();
+                self.awaiting_tag = true;
+            }
+            "IP" => {
+                self.flush();
+                let tag = args.first().map(|tag| self.inline(tag)).unwrap_or_default();
+                self.tag = Some(tag);
+            }
+            "RS" => {
+                self.flush();
+                self.indent += 1;
+            }
+            "RE" => {
+                self.flush();
+                self.indent = self.indent.saturating_sub(1);
+            }
+            "nf" | "EX" => self.start_preformatted(),
+            "fi" | "EE" => self.end_preformatted(),
+            "br" => {
+                if self.preformatted.is_none() && !self.current.is_empty() {
+                    self.current.push(roman("\n"));
+                }
+            }
+            "sp" => {
+                if let Some(lines) = self.preformatted.as_mut() {
+                    lines.push(Vec::new());
+                } else {
+                    self.flush();
+                }
+            }
+            "B" | "SB" => {
+                let spans = self.in_font(Font::Bold, &args.join(" "));
+                self.push_spans(spans);
+            }
+            "I" => {
+                let spans = self.in_font(Font::Italic, &args.join(" "));
+                self.push_spans(spans);
+            }
+            "SM" | "UR" | "MT" => {
+                let spans = self.inline(&args.join(" "));
+                self.push_spans(spans);
+            }
+            "BR" | "BI" | "IB" | "IR" | "RB" | "RI" => {
+                let font = |c: u8| match c {
+                    b'B' => Font::Bold,
+                    b'I' => Font::Italic,
+                    _ => Font::Roman,
+                };
+                let fonts = [font(name.as_bytes()[0]), font(name.as_bytes()[1])];
+                let spans = self.alternating(&args, fonts);
+                self.push_spans(spans);
+            }
+            // mdoc(7)
+            "Dt" => {
+                self.title = format!(
+                    "{}({})",
+                    args.first().cloned().unwrap_or_default(),
+                    args.get(1).cloned().unwrap_or_default()
+                );
+            }
+            "Sh" => {
+                self.end_preformatted();
+                self.heading(2, &args.join(" "));
+            }
+            "Ss" => {
+                self.end_preformatted();
+                self.heading(3, &args.join(" "));
+            }
+            "Nd" => {
+                let mut spans = vec![roman("\u{2014} ")];
+                spans.extend(self.inline(&args.join(" ")));
+                self.push_spans(spans);
+            }
+            "Bl" => {
+                self.flush();
+                let kind = if args.iter().any(|arg| arg == "-bullet") {
+                    ListKind::Bullet
+                } else if args.iter().any(|arg| arg == "-dash" || arg == "-hyphen") {
+                    ListKind::Dash
+                } else if args.iter().any(|arg| arg == "-enum") {
+                    ListKind::Enum(0)
+                } else {
+                    ListKind::Tag
+                };
+                self.lists.push(kind);
+                self.indent += 1;
+            }
+            "El" => {
+                self.flush();
+                self.lists.pop();
+                self.indent = self.indent.saturating_sub(1);
+            }
+            "It" => self.mdoc_list_item(&args),
+            "Bd" => {
+                self.flush();
+                if args
+                    .iter()
+                    .any(|arg| arg == "-literal" || arg == "-unfilled")
+                {
+                    self.start_preformatted();
+                }
+                self.indent += 1;
+            }
+            "Ed" => {
+                self.end_preformatted();
+                self.flush();
+                self.indent = self.indent.saturating_sub(1);
+            }
+            "D1" | "Dl" => {
+                self.flush();
+                self.indent += 1;
+                let spans = if name == "Dl" {
+                    self.inline(rest)
+                } else {
+                    self.mdoc_spans(&args)
+                };
+                self.push_spans(spans);
+                self.flush();
+                self.indent -= 1;
+            }
+            // Layout, prologue and reference requests with nothing to show
+            "Dd" | "Os" | "ad" | "na" | "ne" | "in" | "ti" | "hy" | "nh" | "ll" | "ft" | "ps"
+            | "vs" | "ds" | "de" | "ig" | "so" | "ie" | "el" | "if" | "nr" | "rm" | "tr" | "cs"
+            | "bp" | "PD" | "UC" | "DT" | "UE" | "ME" | "Rs" | "Re" | "%A" | "%T" | "%B" | "%J"
+            | "%N" | "%V" | "%D" | "%P" | "%O" | "%Q" | "%R" | "%I" | "%C" | "%U" => {}
+            _ if is_mdoc_macro(name) => {
+                let mut words = vec![name.to_string()];
+                words.extend(args);
+                let spans = self.mdoc_spans(&words);
+                self.push_spans(spans);
+            }
+            // Unknown requests: keep their text rather than lose it
+            _ => {
+                let spans = self.inline(rest);
+                self.push_spans(spans);
+            }
+        }
+    }
+
+    fn render(mut self, source: &str) -> (String, Vec<Block>) {
+        // Lines ending in a backslash continue on the next line
+        let joined = source.replace("\\\n", "");
+        for line in joined.lines() {
+            if line.starts_with('.') || line.starts_with('\'') {
+                self.request(line);
+            } else {
+                self.text_line(line);
+            }
+        }
+        self.end_preformatted();
+        self.flush();
+        (self.title, self.blocks)
+    }
+}
+
+fn is_mdoc_macro(word: &str) -> bool {
+    matches!(
+        word,
+        "Fl" | "Ar"
+            | "Cm"
+            | "Sy"
+            | "Ic"
+            | "Li"
+            | "Cd"
+            | "Fn"
+            | "Fd"
+            | "In"
+            | "Em"
+            | "Pa"
+            | "Va"
+            | "Ev"
+            | "Ad"
+            | "Fa"
+            | "Vt"
+            | "Ft"
+            | "Lk"
+            | "Mt"
+            | "Nm"
+            | "Xr"
+            | "Op"
+            | "Oo"
+            | "Oc"
+            | "Bq"
+            | "Bo"
+            | "Bc"
+            | "Pq"
+            | "Po"
+            | "Pc"
+            | "Dq"
+            | "Do"
+            | "Dc"
+            | "Sq"
+            | "So"
+            | "Sc"
+            | "Ql"
+            | "Ns"
+            | "No"
+            | "Pf"
+            | "Ux"
+            | "Bx"
+            | "Dx"
+            | "Fx"
+            | "Nx"
+            | "Ox"
+            | "At"
+            | "St"
+            | "Lb"
+    )
+}
+
+fn spans_text(spans: &[Span]) -> String {
+    spans.iter().map(|span| span.text.as_str()).collect()
+}
+
+fn escape_html(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+fn spans_html(spans: &[Span]) -> String {
+    spans
+        .iter()
+        .map(|span| {
+            let text = escape_html(&span.text).replace('\n', "<br>");
+            match span.font {
+                Font::Bold => format!("<b>{}</b>", text),
+                Font::Italic => format!("<i>{}</i>", text),
+                Font::Roman => text,
+            }
+        })
+        .collect()
+}
+
+// Plain text laid out like man(1) without line filling, which is left to the viewer
+fn blocks_text(blocks: &[Block]) -> String {
+    const BODY_INDENT: usize = 7;
+    const STEP: usize = 4;
+    let pad = |indent: usize| " ".repeat(BODY_INDENT + indent * STEP);
+    let indent_lines = |text: &str, prefix: &str| -> String {
+        text.lines()
+            .map(|line| {
+                if line.is_empty() {
+                    "\n".to_string()
+                } else {
+                    format!("{}{}\n", prefix, line)
+                }
+            })
+            .collect()
+    };
+    let mut text = String::new();
+    for block in blocks {
+        match block {
+            Block::Heading {
+                level,
+                text: heading,
+            } => {
+                let prefix = if *level == 2 { "" } else { "   " };
+                text.push_str(&format!("{}{}\n", prefix, heading));
+            }
+            Block::Paragraph { indent, spans } => {
+                text.push_str(&indent_lines(&spans_text(spans), &pad(*indent)));
+                text.push('\n');
+            }
+            Block::Tagged { indent, tag, body } => {
+                text.push_str(&indent_lines(&spans_text(tag), &pad(*indent)));
+                text.push_str(&indent_lines(&spans_text(body), &pad(*indent + 1)));
+                text.push('\n');
+            }
+            Block::Preformatted { indent, lines } => {
+                for line in lines {
+                    text.push_str(&format!("{}{}\n", pad(*indent), spans_text(line)));
+                }
+                text.push('\n');
+            }
+        }
+    }
+    text.trim_end().to_string() + "\n"
+}
+
+fn blocks_html(blocks: &[Block]) -> String {
+    let margin = |indent: usize| {
+        if indent == 0 {
+            String::new()
+        } else {
+            format!(" style=\"margin-left: {}em\"", indent * 2)
+        }
+    };
+    let mut html = String::new();
+    for block in blocks {
+        match block {
+            Block::Heading { level, text } => {
+                html.push_str(&format!("<h{0}>{1}</h{0}>\n", level, escape_html(text)));
+            }
+            Block::Paragraph { indent, spans } => {
+                html.push_str(&format!(
+                    "<p{}>{}</p>\n",
+                    margin(*indent),
+                    spans_html(spans)
+                ));
+            }
+            Block::Tagged { indent, tag, body } => {
+                html.push_str(&format!(
+                    "<dl{}><dt>{}</dt><dd>{}</dd></dl>\n",
+                    margin(*indent),
+                    spans_html(tag),
+                    spans_html(body)
+                ));
+            }
+            Block::Preformatted { indent, lines } => {
+                let lines: Vec<String> = lines.iter().map(|line| spans_html(line)).collect();
+                html.push_str(&format!(
+                    "<pre{}>{}</pre>\n",
+                    margin(*indent),
+                    lines.join("\n")
+                ));
+            }
+        }
+    }
+    html
+}
+
+// Renders man(7) or mdoc(7) source; returns the title, headings, plain text and HTML
+pub fn render_man_source(source: &str) -> (String, Vec<String>, String, String) {
+    let (title, blocks) = Renderer::new().render(source);
+    let headings = blocks
+        .iter()
+        .filter_map(|block| match block {
+            Block::Heading { level: 2, text } => Some(text.clone()),
+            _ => None,
+        })
+        .collect();
+    (title, headings, blocks_text(&blocks), blocks_html(&blocks))
+}
+
+// Finds a command's man page (in `section` if given), decompresses it and renders it to text
+// and HTML without needing man, groff or mandoc
+#[command]
+pub fn get_man_page(command: String, section: Option<String>) -> Result<ManPage, String> {
+    let command = command.trim();
+    if command.is_empty() || command.contains(['/', '\0']) || command.starts_with('.') {
+        return Err(format!("Invalid command name '{}'", command));
+    }
+    let section = section
+        .map(|section| section.trim().to_string())
+        .filter(|section| !section.is_empty());
+    if section
+        .as_deref()
+        .is_some_and(|section| !section.starts_with(|c: char| c.is_ascii_alphanumeric()))
+    {
+        return Err(format!(
+            "Invalid manual section '{}'",
+            section.unwrap_or_default()
+        ));
+    }
+
+    let (path, page_section) = find_man_page(command, section.as_deref())?;
+    let source = load_page(&path, 0)?;
+    let (title, headings, text, html) = render_man_source(&source);
+    Ok(ManPage {
+        command: command.to_string(),
+        section: page_section,
+        path: path.to_string_lossy().to_string(),
+        title,
+        headings,
+        text,
+        html,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn scratch_man_dir(name: &str) -> PathBuf {
+        let root = std::env::temp_dir().join(format!("man-pages-{}-{}", name, std::process::id()));
+        let _ = fs::remove_dir_all(&root);
+        for (dir, file) in [
+            ("man1", "ls.1.gz"),
+            ("man1", "openssl.1ssl"),
+            ("man3", "printf.3"),
+            ("man3", "printf.3p.gz"),
+            ("man3p", "exit.3p"),
+        ] {
+            fs::create_dir_all(root.join(dir)).unwrap();
+            fs::write(root.join(dir).join(file), "").unwrap();
+        }
+        root
+    }
+
+    fn found(root: &Path, command: &str, section: &str) -> Option<String> {
+        find_in_section(root, command, section).map(|(path, page_section)| {
+            let file = path.strip_prefix(root).unwrap().to_string_lossy();
+            format!("{} {}", file, page_section)
+        })
+    }
+
+    #[test]
+    fn pages_are_matched_by_section_prefix() {
+        let root = scratch_man_dir("sections");
+        assert_eq!(found(&root, "ls", "1").as_deref(), Some("man1/ls.1.gz 1"));
+        // The exact section wins over an extension of it
+        assert_eq!(
+            found(&root, "printf", "3").as_deref(),
+            Some("man3/printf.3 3")
+        );
+        assert_eq!(
+            found(&root, "printf", "3p").as_deref(),
+            Some("man3/printf.3p.gz 3p")
+        );
+        assert_eq!(
+            found(&root, "openssl", "1").as_deref(),
+            Some("man1/openssl.1ssl 1ssl")
+        );
+        // man3p is searched for section 3 too
+        assert_eq!(
+            found(&root, "exit", "3").as_deref(),
+            Some("man3p/exit.3p 3p")
+        );
+        assert_eq!(found(&root, "ls", "8"), None);
+        assert_eq!(found(&root, "printf", "3x"), None);
+        let _ = fs::remove_dir_all(&root);
+    }
+
+    #[test]
+    fn sections_not_starting_with_an_ascii_character_are_rejected() {
+        for section in ["é", "-1", "."] {
+            let error = get_man_page("ls".to_string(), Some(section.to_string())).unwrap_err();
+            assert!(error.starts_with("Invalid manual section"), "{}", error);
+        }
+        assert!(get_man_page("../ls".to_string(), None).is_err());
+    }
+
+    #[test]
+    fn so_redirects_are_followed() {
+        let root = scratch_man_dir("so");
+        fs::write(root.join("man1/ls.1"), ".TH LS 1\n").unwrap();
+        fs::write(root.join("man1/dir.1"), ".so man1/ls.1\n").unwrap();
+        assert_eq!(
+            load_page(&root.join("man1/dir.1"), 0).unwrap(),
+            ".TH LS 1\n"
+        );
+        fs::write(root.join("man1/loop.1"), ".so man1/loop.1\n").unwrap();
+        assert_eq!(
+            load_page(&root.join("man1/loop.1"), 0).unwrap(),
+            ".so man1/loop.1\n"
+        );
+        let _ = fs::remove_dir_all(&root);
+    }
+
+    #[test]
+    fn man_source_is_rendered() {
+        let source = ".TH LS 1\n.SH NAME\nls \\- list directory contents\n.SH OPTIONS\n.TP\n\
+                      .B \\-a\ndo not ignore entries starting with .\n";
+        let (title, headings, text, html) = render_man_source(source);
+        assert_eq!(title, "LS(1)");
+        assert_eq!(headings, vec!["NAME", "OPTIONS"]);
+        assert!(text.contains("ls - list directory contents"), "{}", text);
+        assert!(html.contains("<dt><b>-a</b></dt>"), "{}", html);
+    }
+}
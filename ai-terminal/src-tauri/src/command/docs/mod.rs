@@ -0,0 +1,2 @@
+pub mod cheatsheets;
+pub mod man_pages;
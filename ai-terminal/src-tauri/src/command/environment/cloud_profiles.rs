@@ -0,0 +1,328 @@
+use crate::command::environment::directory_env::{session_env_overrides, session_var};
+use crate::command::types::command_manager::CommandManager;
+use serde::Serialize;
+use std::collections::HashMap;
+use std::fs;
+use std::path::PathBuf;
+use tauri::{command, AppHandle, Emitter, Runtime, State};
+
+// Session variables owned by each provider; set_cloud_profile replaces all of them at once
+const AWS_VARS: &[&str] = &["AWS_PROFILE", "AWS_REGION", "AWS_DEFAULT_REGION"];
+const GCLOUD_VARS: &[&str] = &["CLOUDSDK_ACTIVE_CONFIG_NAME", "CLOUDSDK_CORE_PROJECT"];
+const AZURE_VARS: &[&str] = &["AZURE_SUBSCRIPTION_ID", "ARM_SUBSCRIPTION_ID"];
+
+#[derive(Serialize, Clone, Debug, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct CloudContext {
+    pub aws_profile: Option<String>,
+    pub aws_region: Option<String>,
+    pub gcloud_config: Option<String>,
+    pub gcloud_project: Option<String>,
+    pub gcloud_account: Option<String>,
+    pub azure_subscription: Option<String>,
+    pub azure_subscription_id: Option<String>,
+}
+
+#[derive(Serialize, Clone, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct AzureSubscription {
+    pub id: String,
+    pub name: String,
+    pub is_default: bool,
+}
+
+#[derive(Serialize, Clone, Debug, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct CloudProfiles {
+    pub aws: Vec<String>,
+    pub gcloud: Vec<String>,
+    pub azure: Vec<AzureSubscription>,
+}
+
+type IniSections = HashMap<String, HashMap<String, String>>;
+
+// Minimal INI reader for the AWS and gcloud config formats
+fn parse_ini(contents: &str) -> IniSections {
+    let mut sections: IniSections = HashMap::new();
+    let mut current = String::new();
+    for line in contents.lines() {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with(['#', ';']) {
+            continue;
+        }
+        if let Some(name) = line.strip_prefix('[').and_then(|l| l.strip_suffix(']')) {
+            current = name.trim().to_string();
+            sections.entry(current.clone()).or_default();
+        } else if let Some((key, value)) = line.split_once('=') {
+            sections
+                .entry(current.clone())
+                .or_default()
+                .insert(key.trim().to_string(), value.trim().to_string());
+        }
+    }
+    sections
+}
+
+fn read_ini(path: Option<PathBuf>) -> IniSections {
+    path.and_then(|path| fs::read_to_string(path).ok())
+        .map(|contents| parse_ini(&contents))
+        .unwrap_or_default()
+}
+
+fn config_path(
+    overrides: &HashMap<String, String>,
+    var: &str,
+    home_relative: &str,
+) -> Option<PathBuf> {
+    session_var(overrides, var)
+        .map(PathBuf::from)
+        .or_else(|| dirs::home_dir().map(|home| home.join(home_relative)))
+}
+
+// Profiles from ~/.aws/config (`[profile x]`) and ~/.aws/credentials (`[x]`)
+fn aws_profiles(overrides: &HashMap<String, String>) -> (Vec<String>, IniSections) {
+    let config = read_ini(config_path(overrides, "AWS_CONFIG_FILE", ".aws/config"));
+    let credentials = read_ini(config_path(
+        overrides,
+        "AWS_SHARED_CREDENTIALS_FILE",
+        ".aws/credentials",
+    ));
+    let mut names: Vec<String> = config
+        .keys()
+        .map(|section| {
+            section
+                .strip_prefix("profile ")
+                .unwrap_or(section)
+                .trim()
+                .to_string()
+        })
+        .filter(|name| !name.is_empty() && !name.starts_with("sso-session"))
+        .chain(credentials.keys().cloned())
+        .collect();
+    names.sort();
+    names.dedup();
+    (names, config)
+}
+
+fn gcloud_dir(overrides: &HashMap<String, String>) -> Option<PathBuf> {
+    config_path(overrides, "CLOUDSDK_CONFIG", ".config/gcloud")
+}
+
+fn gcloud_configs(overrides: &HashMap<String, String>) -> Vec<String> {
+    let Some(entries) =
+        gcloud_dir(overrides).and_then(|dir| fs::read_dir(dir.join("configurations")).ok())
+    else {
+        return Vec::new();
+    };
+    let mut names: Vec<String> = entries
+        .flatten()
+        .filter_map(|entry| {
+            entry
+                .file_name()
+                .to_string_lossy()
+                .strip_prefix("config_")
+                .map(str::to_string)
+        })
+        .collect();
+    names.sort();
+    names
+}
+
+fn azure_subscriptions(overrides: &HashMap<String, String>) -> Vec<AzureSubscription> {
+    let Some(contents) = config_path(overrides, "AZURE_CONFIG_DIR", ".azure")
+        .and_then(|dir| fs::read_to_string(dir.join("azureProfile.json")).ok())
+    else {
+        return Vec::new();
+    };
+    // az writes this file with a UTF-8 BOM
+    let Ok(profile) =
+        serde_json::from_str::<serde_json::Value>(contents.trim_start_matches('\u{feff}'))
+    else {
+        return Vec::new();
+    };
+    profile
+        .get("subscriptions")
+        .and_then(|subscriptions| subscriptions.as_array())
+        .map(|subscriptions| {
+            subscriptions
+                .iter()
+                .filter_map(|subscription| {
+                    Some(AzureSubscription {
+                        id: subscription.get("id")?.as_str()?.to_string(),
+                        name: subscription.get("name")?.as_str()?.to_string(),
+                        is_default: subscription
+                            .get("isDefault")
+                            .and_then(|value| value.as_bool())
+                            .unwrap_or(false),
+                    })
+                })
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+// What each cloud CLI would use if run in the session right now
+pub fn cloud_context(overrides: &HashMap<String, String>) -> CloudContext {
+    let (aws_profiles, aws_config) = aws_profiles(overrides);
+    let aws_profile = session_var(overrides, "AWS_PROFILE")
+        .or_else(|| session_var(overrides, "AWS_DEFAULT_PROFILE"))
+        .or_else(|| {
+            aws_profiles
+                .iter()
+                .any(|name| name == "default")
+                .then(|| "default".to_string())
+        });
+    let aws_region = session_var(overrides, "AWS_REGION")
+        .or_else(|| session_var(overrides, "AWS_DEFAULT_REGION"))
+        .or_else(|| {
+            let profile = aws_profile.as_deref()?;
+            let section = if profile == "default" {
+                "default".to_string()
+            } else {
+                format!("profile {}", profile)
+            };
+            aws_config.get(&section)?.get("region").cloned()
+        });
+
+    let gcloud_dir = gcloud_dir(overrides);
+    let gcloud_config = session_var(overrides, "CLOUDSDK_ACTIVE_CONFIG_NAME").or_else(|| {
+        let active = fs::read_to_string(gcloud_dir.as_ref()?.join("active_config")).ok()?;
+        Some(active.trim().to_string()).filter(|name| !name.is_empty())
+    });
+    let gcloud_core = gcloud_config
+        .as_ref()
+        .and_then(|config| {
+            let path = gcloud_dir
+                .as_ref()?
+                .join("configurations")
+                .join(format!("config_{}", config));
+            read_ini(Some(path)).remove("core")
+        })
+        .unwrap_or_default();
+    let gcloud_project = session_var(overrides, "CLOUDSDK_CORE_PROJECT")
+        .or_else(|| gcloud_core.get("project").cloned());
+
+    let subscriptions = azure_subscriptions(overrides);
+    let azure = match session_var(overrides, "AZURE_SUBSCRIPTION_ID") {
+        Some(id) => subscriptions
+            .iter()
+            .find(|subscription| subscription.id == id)
+            .map(|subscription| (subscription.name.clone(), id.clone()))
+            .or(Some((id.clone(), id))),
+        None => subscriptions
+            .iter()
+            .find(|subscription| subscription.is_default)
+            .map(|subscription| (subscription.name.clone(), subscription.id.clone())),
+    };
+
+    CloudContext {
+        aws_profile,
+        aws_region,
+        gcloud_config,
+        gcloud_project,
+        gcloud_account: gcloud_core.get("account").cloned(),
+        azure_subscription: azure.as_ref().map(|(name, _)| name.clone()),
+        azure_subscription_id: azure.map(|(_, id)| id),
+    }
+}
+
+#[command]
+pub fn get_cloud_context(
+    session_id: String,
+    command_manager: State<'_, CommandManager>,
+) -> Result<CloudContext, String> {
+    Ok(cloud_context(&session_env_overrides(
+        &command_manager,
+        &session_id,
+    )))
+}
+
+#[command]
+pub fn list_cloud_profiles(
+    session_id: String,
+    command_manager: State<'_, CommandManager>,
+) -> Result<CloudProfiles, String> {
+    let overrides = session_env_overrides(&command_manager, &session_id);
+    Ok(CloudProfiles {
+        aws: aws_profiles(&overrides).0,
+        gcloud: gcloud_configs(&overrides),
+        azure: azure_subscriptions(&overrides),
+    })
+}
+
+// Points one provider's CLI at a profile for this session only, by setting the env vars the
+// CLI reads. `profile: None` clears the session's selection for that provider.
+//   aws:    profile name (+ optional region)
+//   gcloud: configuration name, or a project id when no configuration has that name
+//   azure:  subscription name or id
+#[command]
+pub fn set_cloud_profile<R: Runtime>(
+    session_id: String,
+    provider: String,
+    profile: Option<String>,
+    region: Option<String>,
+    app_handle: AppHandle<R>,
+    command_manager: State<'_, CommandManager>,
+) -> Result<CloudContext, String> {
+    let overrides = session_env_overrides(&command_manager, &session_id);
+    let (owned_vars, assignments): (&[&str], Vec<(&str, String)>) = match provider.as_str() {
+        "aws" => {
+            let mut assignments = Vec::new();
+            if let Some(profile) = &profile {
+                if !aws_profiles(&overrides).0.contains(profile) {
+                    return Err(format!("Unknown AWS profile '{}'", profile));
+                }
+                assignments.push(("AWS_PROFILE", profile.clone()));
+                if let Some(region) = region {
+                    assignments.push(("AWS_REGION", region.clone()));
+                    assignments.push(("AWS_DEFAULT_REGION", region));
+                }
+            }
+            (AWS_VARS, assignments)
+        }
+        "gcloud" => {
+            let assignments = match &profile {
+                Some(name) if gcloud_configs(&overrides).contains(name) => {
+                    vec![("CLOUDSDK_ACTIVE_CONFIG_NAME", name.clone())]
+                }
+                Some(project) => vec![("CLOUDSDK_CORE_PROJECT", project.clone())],
+                None => Vec::new(),
+            };
+            (GCLOUD_VARS, assignments)
+        }
+        "azure" => {
+            let assignments = match &profile {
+                Some(profile) => {
+                    let subscription = azure_subscriptions(&overrides)
+                        .into_iter()
+                        .find(|s| &s.id == profile || &s.name == profile)
+                        .ok_or_else(|| format!("Unknown Azure subscription '{}'", profile))?;
+                    vec![
+                        ("AZURE_SUBSCRIPTION_ID", subscription.id.clone()),
+                        ("ARM_SUBSCRIPTION_ID", subscription.id),
+                    ]
+                }
+                None => Vec::new(),
+            };
+            (AZURE_VARS, assignments)
+        }
+        other => return Err(format!("Unknown cloud provider '{}'", other)),
+    };
+
+    {
+        let mut states = command_manager.commands.lock().map_err(|e| e.to_string())?;
+        let state = states
+            .get_mut(&session_id)
+            .ok_or_else(|| format!("Unknown session '{}'", session_id))?;
+        state
+            .cloud_env
+            .retain(|name, _| !owned_vars.contains(&name.as_str()));
+        for (name, value) in assignments {
+            state.cloud_env.insert(name.to_string(), value);
+        }
+    }
+
+    let context = cloud_context(&session_env_overrides(&command_manager, &session_id));
+    let _ = app_handle.emit("cloud_profile_changed", context.clone());
+    Ok(context)
+}
@@ -0,0 +1,330 @@
+use crate::command::core::command_output::emit_command_error;
+use crate::command::types::command_manager::CommandManager;
+use crate::command::types::command_state::DirectoryEnv;
+use crate::settings::types::settings_manager::SettingsManager;
+use crate::utils::config_utils::{load_json_config, save_json_config};
+use crate::utils::env_scrubbing::scrubbed_environment;
+use serde::Serialize;
+use sha2::{Digest, Sha256};
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use tauri::{command, AppHandle, Emitter, Manager, Runtime, State};
+
+// .envrc files are shell scripts, so each one must be approved (per content hash) before it runs
+pub const ENVRC_APPROVALS_FILE: &str = "envrc_approvals.json";
+const DOTENV_FILE: &str = ".env";
+const ENVRC_FILE: &str = ".envrc";
+
+#[derive(Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct DirectoryEnvEvent {
+    pub session_id: String,
+    pub dir: String,
+    pub file: String,
+    // Names only; values may be secrets
+    pub variables: Vec<String>,
+}
+
+fn content_hash(contents: &[u8]) -> String {
+    Sha256::digest(contents)
+        .iter()
+        .map(|byte| format!("{:02x}", byte))
+        .collect()
+}
+
+// Nearest .envrc or .env at or above `cwd`; .envrc wins when a directory has both
+fn find_env_file(cwd: &Path) -> Option<PathBuf> {
+    cwd.ancestors()
+        .flat_map(|dir| [dir.join(ENVRC_FILE), dir.join(DOTENV_FILE)])
+        .find(|file| file.is_file())
+}
+
+// KEY=VALUE lines with optional `export`, quotes and comments; nothing is evaluated
+pub fn parse_dotenv(contents: &str) -> HashMap<String, String> {
+    let mut variables = HashMap::new();
+    for line in contents.lines() {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+        let line = line.strip_prefix("export ").unwrap_or(line);
+        let Some((key, value)) = line.split_once('=') else {
+            continue;
+        };
+        let key = key.trim();
+        if key.is_empty() || !key.chars().all(|c| c.is_ascii_alphanumeric() || c == '_') {
+            continue;
+        }
+        let value = value.trim();
+        let value = if value.len() >= 2
+            && ((value.starts_with('"') && value.ends_with('"'))
+                || (value.starts_with('\'') && value.ends_with('\'')))
+        {
+            let inner = &value[1..value.len() - 1];
+            if value.starts_with('"') {
+                inner.replace("\\n", "\n").replace("\\\"", "\"")
+            } else {
+                inner.to_string()
+            }
+        } else {
+            // Strip trailing inline comments from unquoted values
+            value.split(" #").next().unwrap_or("").trim().to_string()
+        };
+        variables.insert(key.to_string(), value);
+    }
+    variables
+}
+
+// Sources the .envrc in bash and returns the variables it added or changed
+fn evaluate_envrc(
+    file: &Path,
+    base_env: &HashMap<String, String>,
+) -> Result<HashMap<String, String>, String> {
+    let dir = file.parent().ok_or("Invalid .envrc path")?;
+    let output = Command::new("bash")
+        .arg("-c")
+        .arg("set -a; source \"$1\" >/dev/null 2>&1; env -0")
+        .arg("envrc")
+        .arg(file)
+        .current_dir(dir)
+        .env_clear()
+        .envs(base_env)
+        .output()
+        .map_err(|e| format!("Failed to evaluate {}: {}", file.display(), e))?;
+    if !output.status.success() {
+        return Err(format!("{} exited with {}", file.display(), output.status));
+    }
+
+    Ok(String::from_utf8_lossy(&output.stdout)
+        .split('\0')
+        .filter_map(|pair| pair.split_once('='))
+        .filter(|(key, value)| {
+            !matches!(*key, "PWD" | "OLDPWD" | "SHLVL" | "_")
+                && base_env.get(*key).map(String::as_str) != Some(*value)
+        })
+        .map(|(key, value)| (key.to_string(), value.to_string()))
+        .collect())
+}
+
+fn is_envrc_approved(file: &Path, hash: &str) -> bool {
+    let approvals: HashMap<String, String> = load_json_config(ENVRC_APPROVALS_FILE);
+    approvals.get(&file.to_string_lossy().to_string()) == Some(&hash.to_string())
+}
+
+fn event_for(session_id: &str, env: &DirectoryEnv) -> DirectoryEnvEvent {
+    let mut variables: Vec<String> = env.variables.keys().cloned().collect();
+    variables.sort();
+    DirectoryEnvEvent {
+        session_id: session_id.to_string(),
+        dir: env.dir.clone(),
+        file: env.file.clone(),
+        variables,
+    }
+}
+
+fn load_directory_env<R: Runtime>(
+    app_handle: &AppHandle<R>,
+    session_id: &str,
+    file: &Path,
+) -> Option<DirectoryEnv> {
+    let contents = fs::read(file).ok()?;
+    let hash = content_hash(&contents);
+    let dir = file.parent()?.to_string_lossy().to_string();
+    let file_name = file.to_string_lossy().to_string();
+
+    let variables = if file.file_name().is_some_and(|name| name == ENVRC_FILE) {
+        if !is_envrc_approved(file, &hash) {
+            let _ = app_handle.emit(
+                "env_approval_required",
+                DirectoryEnvEvent {
+                    session_id: session_id.to_string(),
+                    dir,
+                    file: file_name,
+                    variables: Vec::new(),
+                },
+            );
+            return None;
+        }
+        let base_env = scrubbed_environment(&app_handle.state::<SettingsManager>().current());
+        match evaluate_envrc(file, &base_env) {
+            Ok(variables) => variables,
+            Err(e) => {
+                emit_command_error(app_handle, session_id, e);
+                return None;
+            }
+        }
+    } else {
+        parse_dotenv(&String::from_utf8_lossy(&contents))
+    };
+
+    Some(DirectoryEnv {
+        dir,
+        file: file_name,
+        content_hash: hash,
+        variables,
+    })
+}
+
+// Called after the session's directory changes: unloads the previous directory's variables
+// and loads the ones in scope for `cwd`, emitting env_unloaded / env_loaded
+pub fn refresh_directory_env<R: Runtime>(app_handle: &AppHandle<R>, session_id: &str, cwd: &str) {
+    let enabled = app_handle
+        .state::<SettingsManager>()
+        .current()
+        .directory_env_enabled;
+    let target = if enabled {
+        find_env_file(Path::new(cwd))
+    } else {
+        None
+    };
+    let target_hash = target
+        .as_ref()
+        .and_then(|file| fs::read(file).ok())
+        .map(|contents| content_hash(&contents));
+
+    let command_manager = app_handle.state::<CommandManager>();
+    let current = match command_manager.commands.lock() {
+        Ok(states) => states
+            .get(session_id)
+            .and_then(|state| state.directory_env.clone()),
+        Err(_) => return,
+    };
+    let unchanged = match (&current, &target) {
+        (Some(current), Some(file)) => {
+            current.file == file.to_string_lossy()
+                && Some(&current.content_hash) == target_hash.as_ref()
+        }
+        (None, None) => true,
+        _ => false,
+    };
+    if unchanged {
+        return;
+    }
+
+    let loaded = target
+        .as_ref()
+        .and_then(|file| load_directory_env(app_handle, session_id, file));
+    if let Ok(mut states) = command_manager.commands.lock() {
+        if let Some(state) = states.get_mut(session_id) {
+            state.directory_env = loaded.clone();
+        }
+    }
+
+    if let Some(previous) = current {
+        let _ = app_handle.emit("env_unloaded", event_for(session_id, &previous));
+    }
+    if let Some(loaded) = loaded {
+        let _ = app_handle.emit("env_loaded", event_for(session_id, &loaded));
+    }
+}
+
+// Session-scoped variables (e.g. from a directory .env) win over the app's environment
+pub fn session_var(overrides: &HashMap<String, String>, name: &str) -> Option<String> {
+    overrides
+        .get(name)
+        .cloned()
+        .or_else(|| std::env::var(name).ok())
+        .filter(|value| !value.is_empty())
+}
+
+// Variables to layer over the base environment for commands run in the session
+pub fn session_env_overrides(
+    command_manager: &CommandManager,
+    session_id: &str,
+) -> HashMap<String, String> {
+    let Ok(states) = command_manager.commands.lock() else {
+        return HashMap::new();
+    };
+    let Some(state) = states.get(session_id) else {
+        return HashMap::new();
+    };
+    let mut overrides = state
+        .directory_env
+        .as_ref()
+        .map(|env| env.variables.clone())
+        .unwrap_or_default();
+    overrides.extend(state.cloud_env.clone());
+    if let Some(python_env) = &state.python_env {
+        overrides.extend(python_env.variables.clone());
+    }
+    if let Some(tmp_dir) = &state.tmp_dir {
+        for name in ["TMPDIR", "TMP", "TEMP"] {
+            overrides.insert(name.to_string(), tmp_dir.clone());
+        }
+    }
+
+    // Activated toolchains go in front of whatever PATH the session would otherwise have
+    let bin_dirs: Vec<PathBuf> = [
+        state.node_version.as_ref().map(|node| &node.bin_dir),
+        state.python_env.as_ref().map(|env| &env.bin_dir),
+    ]
+    .into_iter()
+    .flatten()
+    .map(PathBuf::from)
+    .collect();
+    if !bin_dirs.is_empty() {
+        let base_path = overrides
+            .get("PATH")
+            .cloned()
+            .or_else(|| std::env::var("PATH").ok())
+            .unwrap_or_default();
+        if let Ok(path) = std::env::join_paths(
+            bin_dirs
+                .into_iter()
+                .chain(std::env::split_paths(&base_path)),
+        ) {
+            overrides.insert("PATH".to_string(), path.to_string_lossy().to_string());
+        }
+    }
+    overrides
+}
+
+// Approves the current contents of an .envrc and loads it into the session
+#[command]
+pub fn approve_envrc<R: Runtime>(
+    session_id: String,
+    path: String,
+    app_handle: AppHandle<R>,
+    command_manager: State<'_, CommandManager>,
+) -> Result<(), String> {
+    let file = PathBuf::from(&path);
+    if file.file_name().is_none_or(|name| name != ENVRC_FILE) {
+        return Err(format!("Not an .envrc file: {}", path));
+    }
+    let contents = fs::read(&file).map_err(|e| format!("Failed to read {}: {}", path, e))?;
+
+    let mut approvals: HashMap<String, String> = load_json_config(ENVRC_APPROVALS_FILE);
+    approvals.insert(path, content_hash(&contents));
+    save_json_config(ENVRC_APPROVALS_FILE, &approvals)?;
+
+    let cwd = {
+        let states = command_manager.commands.lock().map_err(|e| e.to_string())?;
+        states
+            .get(&session_id)
+            .map(|state| state.current_dir.clone())
+            .ok_or_else(|| format!("Unknown session '{}'", session_id))?
+    };
+    refresh_directory_env(&app_handle, &session_id, &cwd);
+    Ok(())
+}
+
+#[command]
+pub fn revoke_envrc(path: String) -> Result<(), String> {
+    let mut approvals: HashMap<String, String> = load_json_config(ENVRC_APPROVALS_FILE);
+    approvals.remove(&path);
+    save_json_config(ENVRC_APPROVALS_FILE, &approvals)
+}
+
+#[command]
+pub fn get_directory_env(
+    session_id: String,
+    command_manager: State<'_, CommandManager>,
+) -> Result<Option<DirectoryEnvEvent>, String> {
+    let states = command_manager.commands.lock().map_err(|e| e.to_string())?;
+    Ok(states
+        .get(&session_id)
+        .and_then(|state| state.directory_env.as_ref())
+        .map(|env| event_for(&session_id, env)))
+}
@@ -0,0 +1,5 @@
+pub mod cloud_profiles;
+pub mod directory_env;
+pub mod node_version;
+pub mod python_env;
+pub mod session_tmpdir;
@@ -0,0 +1,227 @@
+use crate::command::environment::directory_env::session_env_overrides;
+use crate::command::types::command_manager::CommandManager;
+use crate::command::types::command_state::ActivatedNodeVersion;
+use serde::Serialize;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use tauri::{command, AppHandle, Emitter, Runtime, State};
+
+const NODE_VERSION_FILES: &[&str] = &[".nvmrc", ".node-version"];
+
+#[derive(Serialize, Clone, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct InstalledNodeVersion {
+    pub version: String,
+    // nvm, fnm, n or asdf
+    pub manager: String,
+    pub bin_dir: String,
+}
+
+#[derive(Serialize, Clone, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct NodeVersionInfo {
+    // Spec from the nearest .nvmrc/.node-version, e.g. "18", "v20.11.0" or "lts/iron"
+    pub expected: Option<String>,
+    pub expected_source: Option<String>,
+    // `node --version` as the session would run it
+    pub active: Option<String>,
+    // Installed version that satisfies `expected`
+    pub resolved: Option<InstalledNodeVersion>,
+    pub matches: bool,
+    pub installed: Vec<InstalledNodeVersion>,
+}
+
+fn version_numbers(version: &str) -> Vec<u64> {
+    version
+        .trim()
+        .trim_start_matches('v')
+        .split('.')
+        .map_while(|part| part.parse().ok())
+        .collect()
+}
+
+// "18" matches 18.x.y, "18.17" matches 18.17.x, a full version matches exactly
+fn version_satisfies(installed: &str, spec: &str) -> bool {
+    let spec = version_numbers(spec);
+    !spec.is_empty() && version_numbers(installed).starts_with(&spec)
+}
+
+fn find_version_file(cwd: &Path) -> Option<(PathBuf, String)> {
+    cwd.ancestors()
+        .flat_map(|dir| NODE_VERSION_FILES.iter().map(move |file| dir.join(file)))
+        .find_map(|file| {
+            let spec = fs::read_to_string(&file).ok()?;
+            let spec = spec.lines().next()?.trim().to_string();
+            (!spec.is_empty()).then_some((file, spec))
+        })
+}
+
+fn env_or_home(var: &str, home_relative: &str) -> Option<PathBuf> {
+    std::env::var(var)
+        .ok()
+        .map(PathBuf::from)
+        .or_else(|| dirs::home_dir().map(|home| home.join(home_relative)))
+}
+
+// Version directories of every supported manager, newest first
+fn installed_versions() -> Vec<InstalledNodeVersion> {
+    let layouts: Vec<(&str, Option<PathBuf>, &str)> = vec![
+        (
+            "nvm",
+            env_or_home("NVM_DIR", ".nvm").map(|dir| dir.join("versions/node")),
+            "bin",
+        ),
+        (
+            "fnm",
+            env_or_home("FNM_DIR", ".local/share/fnm").map(|dir| dir.join("node-versions")),
+            "installation/bin",
+        ),
+        (
+            "n",
+            Some(PathBuf::from("/usr/local/n/versions/node")),
+            "bin",
+        ),
+        (
+            "asdf",
+            env_or_home("ASDF_DATA_DIR", ".asdf").map(|dir| dir.join("installs/nodejs")),
+            "bin",
+        ),
+    ];
+
+    let mut versions = Vec::new();
+    for (manager, root, bin) in layouts {
+        let Some(entries) = root.and_then(|root| fs::read_dir(root).ok()) else {
+            continue;
+        };
+        for entry in entries.flatten() {
+            let bin_dir = entry.path().join(bin);
+            if !bin_dir.join("node").exists() {
+                continue;
+            }
+            let name = entry.file_name().to_string_lossy().to_string();
+            versions.push(InstalledNodeVersion {
+                version: format!("v{}", name.trim_start_matches('v')),
+                manager: manager.to_string(),
+                bin_dir: bin_dir.to_string_lossy().to_string(),
+            });
+        }
+    }
+    versions.sort_by_key(|installed| std::cmp::Reverse(version_numbers(&installed.version)));
+    versions
+}
+
+// nvm aliases such as `lts/iron` or `default` are files holding a version (or another alias)
+fn resolve_nvm_alias(spec: &str) -> Option<String> {
+    let alias_dir = env_or_home("NVM_DIR", ".nvm")?.join("alias");
+    let mut spec = spec.to_string();
+    for _ in 0..5 {
+        if !version_numbers(&spec).is_empty() {
+            return Some(spec);
+        }
+        spec = fs::read_to_string(alias_dir.join(&spec))
+            .ok()?
+            .trim()
+            .to_string();
+    }
+    None
+}
+
+fn resolve_version(spec: &str, installed: &[InstalledNodeVersion]) -> Option<InstalledNodeVersion> {
+    // `node` / `latest` mean the newest installed version
+    if matches!(spec, "node" | "latest" | "current") {
+        return installed.first().cloned();
+    }
+    let spec = resolve_nvm_alias(spec).unwrap_or_else(|| spec.to_string());
+    installed
+        .iter()
+        .find(|candidate| version_satisfies(&candidate.version, &spec))
+        .cloned()
+}
+
+fn active_node_version(command_manager: &CommandManager, session_id: &str) -> Option<String> {
+    let overrides = session_env_overrides(command_manager, session_id);
+    let mut command = Command::new("node");
+    command.arg("--version");
+    if let Some(path) = overrides.get("PATH") {
+        command.env("PATH", path);
+    }
+    let output = command.output().ok()?;
+    output
+        .status
+        .success()
+        .then(|| String::from_utf8_lossy(&output.stdout).trim().to_string())
+}
+
+fn session_cwd(command_manager: &CommandManager, session_id: &str) -> Result<PathBuf, String> {
+    let states = command_manager.commands.lock().map_err(|e| e.to_string())?;
+    match states.get(session_id) {
+        Some(state) => Ok(PathBuf::from(&state.current_dir)),
+        None => std::env::current_dir().map_err(|e| e.to_string()),
+    }
+}
+
+// Expected (from .nvmrc/.node-version) vs active node version for the session
+#[command]
+pub fn get_node_version_info(
+    session_id: String,
+    command_manager: State<'_, CommandManager>,
+) -> Result<NodeVersionInfo, String> {
+    let cwd = session_cwd(&command_manager, &session_id)?;
+    let expected = find_version_file(&cwd);
+    let installed = installed_versions();
+    let resolved = expected
+        .as_ref()
+        .and_then(|(_, spec)| resolve_version(spec, &installed));
+    let active = active_node_version(&command_manager, &session_id);
+    let matches = match (&active, &resolved) {
+        (Some(active), Some(resolved)) => {
+            version_numbers(active) == version_numbers(&resolved.version)
+        }
+        // No version file means nothing to mismatch
+        _ => expected.is_none(),
+    };
+
+    Ok(NodeVersionInfo {
+        expected_source: expected
+            .as_ref()
+            .map(|(file, _)| file.to_string_lossy().to_string()),
+        expected: expected.map(|(_, spec)| spec),
+        active,
+        resolved,
+        matches,
+        installed,
+    })
+}
+
+// Puts the node version requested by the nearest .nvmrc/.node-version first on the session PATH
+#[command]
+pub fn use_node_version<R: Runtime>(
+    session_id: String,
+    app_handle: AppHandle<R>,
+    command_manager: State<'_, CommandManager>,
+) -> Result<InstalledNodeVersion, String> {
+    let cwd = session_cwd(&command_manager, &session_id)?;
+    let (file, spec) = find_version_file(&cwd)
+        .ok_or_else(|| "No .nvmrc or .node-version found for this directory".to_string())?;
+    let resolved = resolve_version(&spec, &installed_versions()).ok_or_else(|| {
+        format!(
+            "Node {} (from {}) is not installed; install it with your version manager first",
+            spec,
+            file.display()
+        )
+    })?;
+
+    let mut states = command_manager.commands.lock().map_err(|e| e.to_string())?;
+    let state = states
+        .get_mut(&session_id)
+        .ok_or_else(|| format!("Unknown session '{}'", session_id))?;
+    state.node_version = Some(ActivatedNodeVersion {
+        version: resolved.version.clone(),
+        bin_dir: resolved.bin_dir.clone(),
+    });
+    drop(states);
+
+    let _ = app_handle.emit("node_version_changed", resolved.clone());
+    Ok(resolved)
+}
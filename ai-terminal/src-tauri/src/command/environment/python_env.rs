@@ -0,0 +1,246 @@
+use crate::command::types::command_manager::CommandManager;
+use crate::command::types::command_state::ActivatedPythonEnv;
+use serde::Serialize;
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use tauri::{command, AppHandle, Emitter, Runtime, State};
+
+// Directory names conventionally used for a project's virtualenv
+const VENV_DIR_NAMES: &[&str] = &[".venv", "venv", "env", ".env"];
+
+#[derive(Serialize, Clone, Copy, PartialEq, Eq, Debug)]
+#[serde(rename_all = "lowercase")]
+pub enum PythonEnvKind {
+    Venv,
+    Conda,
+}
+
+#[derive(Serialize, Clone, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct PythonEnvironment {
+    pub name: String,
+    pub kind: PythonEnvKind,
+    pub path: String,
+    pub python_version: Option<String>,
+    // Found next to the cwd rather than in the global conda list
+    pub local: bool,
+    pub active: bool,
+}
+
+#[cfg(windows)]
+const ENV_BIN_DIR: &str = "Scripts";
+#[cfg(not(windows))]
+const ENV_BIN_DIR: &str = "bin";
+
+// `version = 3.11.4` (or `version_info` from newer tools) in pyvenv.cfg
+fn venv_python_version(venv: &Path) -> Option<String> {
+    let config = fs::read_to_string(venv.join("pyvenv.cfg")).ok()?;
+    config.lines().find_map(|line| {
+        let (key, value) = line.split_once('=')?;
+        matches!(key.trim(), "version" | "version_info").then(|| value.trim().to_string())
+    })
+}
+
+// The nearest virtualenvs at or above `cwd`
+fn local_venvs(cwd: &Path) -> Vec<PythonEnvironment> {
+    // Standing inside a venv counts as well
+    let candidates = |dir: &Path| -> Vec<PathBuf> {
+        let mut found: Vec<PathBuf> = VENV_DIR_NAMES
+            .iter()
+            .map(|name| dir.join(name))
+            .filter(|path| path.join("pyvenv.cfg").is_file())
+            .collect();
+        if dir.join("pyvenv.cfg").is_file() {
+            found.push(dir.to_path_buf());
+        }
+        found
+    };
+    let Some(venvs) = cwd
+        .ancestors()
+        .map(candidates)
+        .find(|venvs| !venvs.is_empty())
+    else {
+        return Vec::new();
+    };
+
+    venvs
+        .into_iter()
+        .map(|venv| {
+            let dir_name = venv
+                .file_name()
+                .map(|name| name.to_string_lossy().to_string())
+                .unwrap_or_default();
+            // `.venv` says little on its own, so qualify generic names with the project folder
+            let name = match venv.parent().and_then(|parent| parent.file_name()) {
+                Some(project) if VENV_DIR_NAMES.contains(&dir_name.as_str()) => {
+                    format!("{}/{}", project.to_string_lossy(), dir_name)
+                }
+                _ => dir_name,
+            };
+            PythonEnvironment {
+                name,
+                kind: PythonEnvKind::Venv,
+                python_version: venv_python_version(&venv),
+                path: venv.to_string_lossy().to_string(),
+                local: true,
+                active: false,
+            }
+        })
+        .collect()
+}
+
+// Environments conda knows about, from ~/.conda/environments.txt (no conda process needed)
+fn conda_envs() -> Vec<PythonEnvironment> {
+    let Some(registry) = dirs::home_dir().map(|home| home.join(".conda/environments.txt")) else {
+        return Vec::new();
+    };
+    let contents = fs::read_to_string(registry).unwrap_or_default();
+    let mut envs: Vec<PythonEnvironment> = Vec::new();
+    for line in contents
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty())
+    {
+        let prefix = Path::new(line);
+        if !prefix.join("conda-meta").is_dir() || envs.iter().any(|env| env.path == line) {
+            continue;
+        }
+        let in_envs_dir = prefix
+            .parent()
+            .and_then(|parent| parent.file_name())
+            .is_some_and(|name| name == "envs");
+        let name = match prefix.file_name() {
+            Some(name) if in_envs_dir => name.to_string_lossy().to_string(),
+            _ => "base".to_string(),
+        };
+        envs.push(PythonEnvironment {
+            name,
+            kind: PythonEnvKind::Conda,
+            path: line.to_string(),
+            python_version: None,
+            local: false,
+            active: false,
+        });
+    }
+    envs
+}
+
+// Local virtualenvs first, then conda environments, with the session's active one flagged
+pub fn detect_python_environments(
+    cwd: &Path,
+    active_prefix: Option<&str>,
+) -> Vec<PythonEnvironment> {
+    let mut envs = local_venvs(cwd);
+    envs.extend(conda_envs());
+    for env in &mut envs {
+        env.active = active_prefix == Some(env.path.as_str());
+    }
+    envs
+}
+
+// Variables `activate` would set, apart from PATH which session_env_overrides assembles
+fn activation_variables(env: &PythonEnvironment) -> HashMap<String, String> {
+    match env.kind {
+        PythonEnvKind::Venv => HashMap::from([
+            ("VIRTUAL_ENV".to_string(), env.path.clone()),
+            ("VIRTUAL_ENV_PROMPT".to_string(), env.name.clone()),
+        ]),
+        PythonEnvKind::Conda => HashMap::from([
+            ("CONDA_PREFIX".to_string(), env.path.clone()),
+            ("CONDA_DEFAULT_ENV".to_string(), env.name.clone()),
+            ("CONDA_SHLVL".to_string(), "1".to_string()),
+        ]),
+    }
+}
+
+fn session_dir_and_active(
+    command_manager: &CommandManager,
+    session_id: &str,
+) -> Result<(String, Option<String>), String> {
+    let states = command_manager.commands.lock().map_err(|e| e.to_string())?;
+    Ok(match states.get(session_id) {
+        Some(state) => (
+            state.current_dir.clone(),
+            state.python_env.as_ref().map(|env| env.prefix.clone()),
+        ),
+        None => (
+            std::env::current_dir()
+                .map(|dir| dir.to_string_lossy().to_string())
+                .map_err(|e| e.to_string())?,
+            None,
+        ),
+    })
+}
+
+// Sentence for the AI prompt naming the session's active Python environment
+pub fn python_env_context(command_manager: &CommandManager, session_id: &str) -> Option<String> {
+    let states = command_manager.commands.lock().ok()?;
+    let env = states.get(session_id)?.python_env.as_ref()?;
+    Some(format!(
+        "The Python environment \"{}\" ({}) is active, so `python` and `pip` refer to it.",
+        env.name, env.prefix
+    ))
+}
+
+#[command]
+pub fn list_python_environments(
+    session_id: String,
+    command_manager: State<'_, CommandManager>,
+) -> Result<Vec<PythonEnvironment>, String> {
+    let (cwd, active) = session_dir_and_active(&command_manager, &session_id)?;
+    Ok(detect_python_environments(
+        Path::new(&cwd),
+        active.as_deref(),
+    ))
+}
+
+// Activates a virtualenv or conda environment (by name or path) for commands run in the
+// session, the way `source bin/activate` / `conda activate` would
+#[command]
+pub fn activate_environment<R: Runtime>(
+    session_id: String,
+    name: String,
+    app_handle: AppHandle<R>,
+    command_manager: State<'_, CommandManager>,
+) -> Result<PythonEnvironment, String> {
+    let (cwd, _) = session_dir_and_active(&command_manager, &session_id)?;
+    let mut env = detect_python_environments(Path::new(&cwd), None)
+        .into_iter()
+        .find(|env| env.name == name || env.path == name)
+        .ok_or_else(|| format!("No Python environment named '{}'", name))?;
+
+    let mut states = command_manager.commands.lock().map_err(|e| e.to_string())?;
+    let state = states
+        .get_mut(&session_id)
+        .ok_or_else(|| format!("Unknown session '{}'", session_id))?;
+    state.python_env = Some(ActivatedPythonEnv {
+        name: env.name.clone(),
+        prefix: env.path.clone(),
+        bin_dir: Path::new(&env.path)
+            .join(ENV_BIN_DIR)
+            .to_string_lossy()
+            .to_string(),
+        variables: activation_variables(&env),
+    });
+    drop(states);
+
+    env.active = true;
+    let _ = app_handle.emit("python_env_activated", env.clone());
+    Ok(env)
+}
+
+#[command]
+pub fn deactivate_environment<R: Runtime>(
+    session_id: String,
+    app_handle: AppHandle<R>,
+    command_manager: State<'_, CommandManager>,
+) -> Result<(), String> {
+    let mut states = command_manager.commands.lock().map_err(|e| e.to_string())?;
+    if let Some(state) = states.get_mut(&session_id) {
+        if state.python_env.take().is_some() {
+            let _ = app_handle.emit("python_env_deactivated", session_id.clone());
+        }
+    }
+    Ok(())
+}
@@ -0,0 +1,188 @@
+use crate::command::constants::SESSION_TMPDIR_ROOT;
+use crate::command::types::command_manager::CommandManager;
+use crate::command::types::command_state::CommandState;
+use std::fs;
+use std::path::{Path, PathBuf};
+use tauri::{command, State};
+
+// Under the user's runtime dir (XDG_RUNTIME_DIR) where there is one, else their cache dir.
+// Never the shared temp dir, where another user could create the directories first.
+fn sessions_root() -> Result<PathBuf, String> {
+    dirs::runtime_dir()
+        .or_else(dirs::cache_dir)
+        .map(|base| base.join(SESSION_TMPDIR_ROOT))
+        .ok_or_else(|| "Could not determine a per-user directory for temp dirs".to_string())
+}
+
+// Temp dirs of this app instance; the pid in the path lets a later run sweep what a crashed
+// one left behind
+fn instance_root() -> Result<PathBuf, String> {
+    Ok(sessions_root()?.join(std::process::id().to_string()))
+}
+
+fn dir_name(session_id: &str) -> String {
+    let safe: String = session_id
+        .chars()
+        .take(64)
+        .map(|c| {
+            if c.is_ascii_alphanumeric() || c == '-' || c == '_' {
+                c
+            } else {
+                '_'
+            }
+        })
+        .collect();
+    // Suffixed so ids that only differ in replaced characters do not share a directory
+    format!(
+        "{}-{}",
+        safe,
+        &uuid::Uuid::new_v4().simple().to_string()[..8]
+    )
+}
+
+fn is_process_alive(pid: u32) -> bool {
+    #[cfg(unix)]
+    {
+        use nix::sys::signal::kill;
+        use nix::unistd::Pid;
+        kill(Pid::from_raw(pid as i32), None).is_ok()
+    }
+    #[cfg(not(unix))]
+    {
+        let _ = pid;
+        true
+    }
+}
+
+// A real directory (not a symlink) owned by the current user
+fn check_own_dir(dir: &Path) -> Result<(), String> {
+    let metadata = fs::symlink_metadata(dir)
+        .map_err(|e| format!("Failed to inspect {}: {}", dir.display(), e))?;
+    if !metadata.is_dir() {
+        return Err(format!("{} is not a directory", dir.display()));
+    }
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::MetadataExt;
+        // getuid cannot fail
+        if metadata.uid() != unsafe { libc::getuid() } {
+            return Err(format!("{} belongs to another user", dir.display()));
+        }
+    }
+    Ok(())
+}
+
+// Creates `dir` and any missing parents up to the sessions root with mode 0700, then checks
+// every level is the user's own directory before anything is put in it
+fn create_private_dir(dir: &Path) -> Result<(), String> {
+    let root = sessions_root()?;
+    let mut builder = fs::DirBuilder::new();
+    builder.recursive(true);
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::DirBuilderExt;
+        builder.mode(0o700);
+    }
+    builder
+        .create(dir)
+        .map_err(|e| format!("Failed to create {}: {}", dir.display(), e))?;
+    for level in dir.ancestors().take_while(|level| level.starts_with(&root)) {
+        check_own_dir(level)?;
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            fs::set_permissions(level, fs::Permissions::from_mode(0o700))
+                .map_err(|e| format!("Failed to restrict {}: {}", level.display(), e))?;
+        }
+    }
+    Ok(())
+}
+
+// The session's temp dir, created on first use. Commands the session runs afterwards get it
+// as TMPDIR (and TMP/TEMP), as do PTYs started with its id.
+pub fn ensure_session_tmpdir(
+    command_manager: &CommandManager,
+    session_id: &str,
+) -> Result<PathBuf, String> {
+    let mut states = command_manager.commands.lock().map_err(|e| e.to_string())?;
+    let state = states.entry(session_id.to_string()).or_insert_with(|| {
+        CommandState::new(
+            std::env::current_dir()
+                .unwrap_or_default()
+                .to_string_lossy()
+                .to_string(),
+        )
+    });
+    // Recreated if something (e.g. a tmp cleaner) removed it meanwhile
+    let dir = state
+        .tmp_dir
+        .as_ref()
+        .map(PathBuf::from)
+        .map(Ok)
+        .unwrap_or_else(|| instance_root().map(|root| root.join(dir_name(session_id))))?;
+    create_private_dir(&dir)?;
+    state.tmp_dir = Some(dir.to_string_lossy().to_string());
+    Ok(dir)
+}
+
+// The session's temp dir if it has one, without creating it
+pub fn existing_session_tmpdir(
+    command_manager: &CommandManager,
+    session_id: &str,
+) -> Option<String> {
+    command_manager
+        .commands
+        .lock()
+        .ok()?
+        .get(session_id)?
+        .tmp_dir
+        .clone()
+}
+
+// Deletes the session's temp dir and everything in it
+pub fn remove_session_tmpdir(command_manager: &CommandManager, session_id: &str) {
+    let tmp_dir = command_manager
+        .commands
+        .lock()
+        .ok()
+        .and_then(|mut states| states.get_mut(session_id)?.tmp_dir.take());
+    if let Some(tmp_dir) = tmp_dir {
+        let _ = fs::remove_dir_all(tmp_dir);
+    }
+}
+
+// On exit: the temp dirs of every session of this instance
+pub fn remove_all_session_tmpdirs() {
+    if let Ok(root) = instance_root() {
+        let _ = fs::remove_dir_all(root);
+    }
+}
+
+// Temp dirs of instances that are no longer running, e.g. after a crash
+pub fn sweep_stale_session_tmpdirs() {
+    let Ok(root) = sessions_root() else {
+        return;
+    };
+    if check_own_dir(&root).is_err() {
+        return;
+    }
+    let Ok(entries) = fs::read_dir(&root) else {
+        return;
+    };
+    for entry in entries.flatten() {
+        let pid = entry.file_name().to_string_lossy().parse::<u32>().ok();
+        if pid.is_some_and(|pid| pid != std::process::id() && !is_process_alive(pid)) {
+            let _ = fs::remove_dir_all(entry.path());
+        }
+    }
+}
+
+// Private scratch directory for the session (AI runs, previews), removed when it closes
+#[command]
+pub fn get_session_tmpdir(
+    session_id: String,
+    command_manager: State<'_, CommandManager>,
+) -> Result<String, String> {
+    ensure_session_tmpdir(&command_manager, &session_id)
+        .map(|dir| dir.to_string_lossy().to_string())
+}
@@ -0,0 +1,306 @@
+use crate::command::types::command_manager::CommandManager;
+use crate::utils::file_system_utils::resolve_session_path;
+use regex::Regex;
+use serde::Serialize;
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use tauri::{command, State};
+
+#[derive(Serialize, Clone, Copy, Debug, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum RenameStatus {
+    Ok,
+    // The new name is the old one, or `find` did not match
+    Unchanged,
+    // Another file already has, or would get, the new name
+    Conflict,
+    Invalid,
+}
+
+#[derive(Serialize, Clone, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct RenamePlan {
+    pub from: String,
+    pub to: String,
+    pub status: RenameStatus,
+    pub reason: Option<String>,
+}
+
+#[derive(Serialize, Clone, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct BulkRenameResult {
+    pub preview: bool,
+    pub renamed: usize,
+    pub entries: Vec<RenamePlan>,
+}
+
+// Expands the placeholders of `pattern` for one file:
+// {name} file name without extension, {ext} extension, {n} / {n:3} sequence number (zero
+// padded to 3 digits), {1}..{9} groups captured by `find`, {{ and }} literal braces
+fn expand_pattern(
+    pattern: &str,
+    file_name: &str,
+    sequence: u64,
+    captures: Option<&regex::Captures>,
+) -> Result<String, String> {
+    let path = Path::new(file_name);
+    let stem = path
+        .file_stem()
+        .map(|stem| stem.to_string_lossy().to_string())
+        .unwrap_or_default();
+    let extension = path
+        .extension()
+        .map(|extension| extension.to_string_lossy().to_string())
+        .unwrap_or_default();
+
+    let mut expanded = String::new();
+    let mut rest = pattern;
+    while let Some(index) = rest.find(['{', '}']) {
+        expanded.push_str(&rest[..index]);
+        let tail = &rest[index..];
+        if tail.starts_with("{{") || tail.starts_with("}}") {
+            expanded.push_str(&tail[..1]);
+            rest = &tail[2..];
+            continue;
+        }
+        if tail.starts_with('}') {
+            return Err("Unmatched '}' in pattern; write }} for a literal brace".to_string());
+        }
+        let end = tail
+            .find('}')
+            .ok_or("Unclosed '{' in pattern; write {{ for a literal brace")?;
+        let placeholder = &tail[1..end];
+        match placeholder {
+            "name" => expanded.push_str(&stem),
+            "ext" => expanded.push_str(&extension),
+            "n" => expanded.push_str(&sequence.to_string()),
+            _ => {
+                if let Some(width) = placeholder.strip_prefix("n:") {
+                    let width: usize = width
+                        .parse()
+                        .map_err(|_| format!("Invalid sequence width in {{{}}}", placeholder))?;
+                    expanded.push_str(&format!("{:0width$}", sequence, width = width));
+                } else if let Ok(group) = placeholder.parse::<usize>() {
+                    let captures = captures
+                        .ok_or_else(|| format!("{{{}}} needs a find expression", placeholder))?;
+                    if group >= captures.len() {
+                        return Err(format!(
+                            "{{{}}} refers to a group the find expression does not have",
+                            placeholder
+                        ));
+                    }
+                    expanded.push_str(captures.get(group).map_or("", |group| group.as_str()));
+                } else {
+                    return Err(format!("Unknown placeholder {{{}}}", placeholder));
+                }
+            }
+        }
+        rest = &tail[end + 1..];
+    }
+    expanded.push_str(rest);
+    Ok(expanded)
+}
+
+fn invalid_name(name: &str) -> Option<&'static str> {
+    if name.is_empty() {
+        Some("The new name is empty")
+    } else if name == "." || name == ".." {
+        Some("The new name is reserved")
+    } else if name.contains(['/', '\0']) || (cfg!(windows) && name.contains('\\')) {
+        Some("The new name contains a path separator")
+    } else {
+        None
+    }
+}
+
+fn same_file(a: &Path, b: &Path) -> bool {
+    match (fs::canonicalize(a), fs::canonicalize(b)) {
+        (Ok(a), Ok(b)) => a == b,
+        _ => false,
+    }
+}
+
+// Source, target, status and the reason it cannot be applied
+type PlannedRename = (PathBuf, PathBuf, RenameStatus, Option<String>);
+
+// New names for every path, with the ones that cannot be applied marked and explained
+fn plan_renames(
+    sources: &[PathBuf],
+    pattern: &str,
+    find: Option<&Regex>,
+    start: u64,
+) -> Result<Vec<PlannedRename>, String> {
+    let mut plans = Vec::new();
+    // Files `find` skips take no sequence number
+    let mut sequence = start;
+    for source in sources {
+        let file_name = source
+            .file_name()
+            .map(|name| name.to_string_lossy().to_string())
+            .ok_or_else(|| format!("{} has no file name", source.display()))?;
+        if fs::symlink_metadata(source).is_err() {
+            plans.push((
+                source.clone(),
+                source.clone(),
+                RenameStatus::Invalid,
+                Some("The file does not exist".to_string()),
+            ));
+            continue;
+        }
+        let captures = find.map(|find| find.captures(&file_name));
+        if let Some(None) = captures {
+            plans.push((
+                source.clone(),
+                source.clone(),
+                RenameStatus::Unchanged,
+                None,
+            ));
+            continue;
+        }
+        let new_name = expand_pattern(pattern, &file_name, sequence, captures.flatten().as_ref())?;
+        sequence += 1;
+        let target = source.with_file_name(&new_name);
+        let (status, reason) = match invalid_name(&new_name) {
+            Some(reason) => (RenameStatus::Invalid, Some(reason.to_string())),
+            None if new_name == file_name => (RenameStatus::Unchanged, None),
+            None => (RenameStatus::Ok, None),
+        };
+        plans.push((source.clone(), target, status, reason));
+    }
+
+    // Two renames onto one name, or onto a file that stays where it is
+    let mut claimed: HashMap<PathBuf, usize> = HashMap::new();
+    for (source, target, status, _) in &plans {
+        if *status != RenameStatus::Invalid {
+            let key = if *status == RenameStatus::Ok {
+                target
+            } else {
+                source
+            };
+            *claimed.entry(key.clone()).or_default() += 1;
+        }
+    }
+    for (source, target, status, reason) in plans.iter_mut() {
+        if *status != RenameStatus::Ok {
+            continue;
+        }
+        if claimed.get(target).copied().unwrap_or(0) > 1 {
+            *status = RenameStatus::Conflict;
+            *reason = Some("Another file in the batch gets the same name".to_string());
+        } else if fs::symlink_metadata(&*target).is_ok()
+            && !sources.contains(target)
+            && !same_file(source, target)
+        {
+            *status = RenameStatus::Conflict;
+            *reason = Some(format!("{} already exists", target.display()));
+        }
+    }
+    Ok(plans)
+}
+
+// Renames in two steps through hidden temporary names, so swaps and chains (a->b, b->c)
+// work. Renames already done are undone when one fails.
+fn apply_renames(renames: &[(&PathBuf, &PathBuf)]) -> Result<(), String> {
+    let batch = uuid::Uuid::new_v4().simple().to_string();
+    let temporary: Vec<PathBuf> = renames
+        .iter()
+        .enumerate()
+        .map(|(index, (source, _))| {
+            source.with_file_name(format!(".rename-{}-{}", &batch[..8], index))
+        })
+        .collect();
+
+    for (index, (source, _)) in renames.iter().enumerate() {
+        if let Err(e) = fs::rename(source, &temporary[index]) {
+            for undo in (0..index).rev() {
+                let _ = fs::rename(&temporary[undo], renames[undo].0);
+            }
+            return Err(format!("Failed to rename {}: {}", source.display(), e));
+        }
+    }
+    for (index, (_, target)) in renames.iter().enumerate() {
+        if let Err(e) = fs::rename(&temporary[index], target) {
+            for undo in (0..index).rev() {
+                let _ = fs::rename(renames[undo].1, &temporary[undo]);
+            }
+            for undo in 0..renames.len() {
+                let _ = fs::rename(&temporary[undo], renames[undo].0);
+            }
+            return Err(format!("Failed to rename to {}: {}", target.display(), e));
+        }
+    }
+    Ok(())
+}
+
+// Renames files by a pattern such as `photo-{n:3}.{ext}`, numbering them in the order given.
+// With `find`, a regular expression on the file name, only matching files are renamed and
+// its groups are available as {1}, {2}, ... With `preview` nothing is touched; otherwise the
+// batch is refused if any entry is a conflict or invalid.
+#[command]
+pub fn bulk_rename(
+    paths: Vec<String>,
+    pattern: String,
+    preview: bool,
+    find: Option<String>,
+    start: Option<u64>,
+    session_id: Option<String>,
+    command_manager: State<'_, CommandManager>,
+) -> Result<BulkRenameResult, String> {
+    let find = find
+        .filter(|find| !find.is_empty())
+        .map(|find| Regex::new(&find).map_err(|e| format!("Invalid find expression: {}", e)))
+        .transpose()?;
+    let sources = paths
+        .iter()
+        .map(|path| resolve_session_path(&command_manager, session_id.as_deref(), path))
+        .collect::<Result<Vec<PathBuf>, String>>()?;
+    for (index, source) in sources.iter().enumerate() {
+        if sources[..index].contains(source) {
+            return Err(format!("{} is listed twice", source.display()));
+        }
+    }
+
+    let plans = plan_renames(&sources, &pattern, find.as_ref(), start.unwrap_or(1))?;
+    let entries: Vec<RenamePlan> = plans
+        .iter()
+        .map(|(source, target, status, reason)| RenamePlan {
+            from: source.to_string_lossy().to_string(),
+            to: target.to_string_lossy().to_string(),
+            status: *status,
+            reason: reason.clone(),
+        })
+        .collect();
+    if preview {
+        return Ok(BulkRenameResult {
+            preview,
+            renamed: 0,
+            entries,
+        });
+    }
+
+    let blocked = plans
+        .iter()
+        .filter(|(_, _, status, _)| {
+            matches!(status, RenameStatus::Conflict | RenameStatus::Invalid)
+        })
+        .count();
+    if blocked > 0 {
+        return Err(format!(
+            "{} of {} files cannot be renamed; preview the batch for details",
+            blocked,
+            plans.len()
+        ));
+    }
+    let renames: Vec<(&PathBuf, &PathBuf)> = plans
+        .iter()
+        .filter(|(_, _, status, _)| *status == RenameStatus::Ok)
+        .map(|(source, target, _, _)| (source, target))
+        .collect();
+    apply_renames(&renames)?;
+    Ok(BulkRenameResult {
+        preview,
+        renamed: renames.len(),
+        entries,
+    })
+}
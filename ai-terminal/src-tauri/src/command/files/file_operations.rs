@@ -0,0 +1,716 @@
+use crate::command::constants::{FS_COPY_BUFFER_BYTES, FS_PROGRESS_INTERVAL_MS};
+use crate::command::types::command_manager::CommandManager;
+use crate::command::types::file_operation_manager::FileOperationManager;
+use crate::utils::file_system_utils::resolve_session_path;
+use serde::{Deserialize, Serialize};
+use std::fs::{self, File};
+use std::io::{Read, Write};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::{Duration, Instant};
+use tauri::{command, AppHandle, Emitter, Manager, Runtime, State};
+
+// What to do when the destination of a file already exists
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum OverwritePolicy {
+    // Nothing is written if anything would be replaced
+    #[default]
+    Fail,
+    Skip,
+    // Files only; a directory is never replaced by a file
+    Overwrite,
+    // Keep both, as `name (1).ext`
+    Rename,
+}
+
+#[derive(Serialize, Clone, Copy, Debug, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum FileOperationKind {
+    Copy,
+    Move,
+    Delete,
+}
+
+#[derive(Serialize, Clone, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct FileOperationResult {
+    pub operation_id: String,
+    pub kind: FileOperationKind,
+    pub source: String,
+    pub destination: Option<String>,
+    pub total_files: usize,
+    pub files_done: usize,
+    pub total_bytes: u64,
+    pub bytes_done: u64,
+    // Destinations left alone under the skip policy
+    pub skipped: Vec<String>,
+    // Destinations chosen under the rename policy
+    pub renamed: Vec<String>,
+    pub trashed: bool,
+    pub cancelled: bool,
+    pub duration_ms: u64,
+}
+
+#[derive(Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+struct FileOperationProgress {
+    operation_id: String,
+    kind: FileOperationKind,
+    current_path: String,
+    files_done: usize,
+    total_files: usize,
+    bytes_done: u64,
+    total_bytes: u64,
+    percent: f64,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum EntryKind {
+    Directory,
+    File,
+    Symlink,
+}
+
+// One source entry and where it goes, decided before anything is written
+#[derive(Debug)]
+struct PlannedEntry {
+    source: PathBuf,
+    target: PathBuf,
+    kind: EntryKind,
+    size: u64,
+    // Something is in the way and goes first (overwrite policy)
+    replace: bool,
+}
+
+#[derive(Default)]
+struct Plan {
+    // Directories come before their contents
+    entries: Vec<PlannedEntry>,
+    conflicts: Vec<PathBuf>,
+    skipped: Vec<String>,
+    renamed: Vec<String>,
+}
+
+fn entry_kind(metadata: &fs::Metadata) -> EntryKind {
+    if metadata.file_type().is_symlink() {
+        EntryKind::Symlink
+    } else if metadata.is_dir() {
+        EntryKind::Directory
+    } else {
+        EntryKind::File
+    }
+}
+
+// `report.pdf` -> `report (1).pdf`, the first name not taken
+fn unique_name(target: &Path) -> PathBuf {
+    let parent = target.parent().unwrap_or(Path::new(""));
+    let stem = target
+        .file_stem()
+        .map(|stem| stem.to_string_lossy().to_string())
+        .unwrap_or_default();
+    let extension = target
+        .extension()
+        .map(|extension| format!(".{}", extension.to_string_lossy()))
+        .unwrap_or_default();
+    (1..)
+        .map(|n| parent.join(format!("{} ({}){}", stem, n, extension)))
+        .find(|candidate| fs::symlink_metadata(candidate).is_err())
+        .unwrap_or_else(|| target.to_path_buf())
+}
+
+fn plan_entry(
+    source: &Path,
+    target: PathBuf,
+    policy: OverwritePolicy,
+    plan: &mut Plan,
+) -> Result<(), String> {
+    let metadata = fs::symlink_metadata(source)
+        .map_err(|e| format!("Failed to read {}: {}", source.display(), e))?;
+    let kind = entry_kind(&metadata);
+    let existing = fs::symlink_metadata(&target).ok();
+    let mut replace = false;
+    let target = match existing {
+        None => target,
+        // Directories merge into directories
+        Some(existing) if kind == EntryKind::Directory && existing.is_dir() => target,
+        Some(existing) => match policy {
+            OverwritePolicy::Skip => {
+                plan.skipped.push(target.to_string_lossy().to_string());
+                return Ok(());
+            }
+            OverwritePolicy::Rename => {
+                let renamed = unique_name(&target);
+                plan.renamed.push(renamed.to_string_lossy().to_string());
+                renamed
+            }
+            OverwritePolicy::Overwrite if !existing.is_dir() => {
+                replace = true;
+                target
+            }
+            _ => {
+                plan.conflicts.push(target);
+                return Ok(());
+            }
+        },
+    };
+
+    plan.entries.push(PlannedEntry {
+        source: source.to_path_buf(),
+        target: target.clone(),
+        kind,
+        size: if kind == EntryKind::File {
+            metadata.len()
+        } else {
+            0
+        },
+        replace,
+    });
+    if kind == EntryKind::Directory {
+        let mut children: Vec<PathBuf> = fs::read_dir(source)
+            .map_err(|e| format!("Failed to read {}: {}", source.display(), e))?
+            .filter_map(|child| child.ok().map(|child| child.path()))
+            .collect();
+        children.sort();
+        for child in children {
+            if let Some(name) = child.file_name() {
+                plan_entry(&child, target.join(name), policy, plan)?;
+            }
+        }
+    }
+    Ok(())
+}
+
+// Where `source` ends up: inside `destination` when that is an existing directory (as with
+// cp and mv), otherwise at `destination` itself
+fn destination_path(source: &Path, destination: &Path) -> Result<PathBuf, String> {
+    if !destination.is_dir() {
+        return Ok(destination.to_path_buf());
+    }
+    let name = source
+        .file_name()
+        .ok_or_else(|| format!("{} has no file name", source.display()))?;
+    Ok(destination.join(name))
+}
+
+// The path with its parent resolved, leaving a final symlink itself rather than its target
+fn canonical_location(path: &Path) -> Option<PathBuf> {
+    let parent = match path.parent() {
+        Some(parent) if !parent.as_os_str().is_empty() => parent,
+        _ => Path::new("."),
+    };
+    Some(fs::canonicalize(parent).ok()?.join(path.file_name()?))
+}
+
+fn plan_transfer(source: &Path, target: &Path, policy: OverwritePolicy) -> Result<Plan, String> {
+    if fs::symlink_metadata(source).is_err() {
+        return Err(format!("{} does not exist", source.display()));
+    }
+    let mut plan = Plan::default();
+    let mut target = target.to_path_buf();
+    if let (Some(canonical_source), Some(canonical_target)) =
+        (canonical_location(source), canonical_location(&target))
+    {
+        if canonical_target == canonical_source {
+            if policy != OverwritePolicy::Rename {
+                return Err(format!(
+                    "{} and its destination are the same",
+                    source.display()
+                ));
+            }
+            // Duplicating in place: the copy gets a name of its own
+            target = unique_name(&target);
+            plan.renamed.push(target.to_string_lossy().to_string());
+        } else if canonical_target.starts_with(&canonical_source) {
+            return Err(format!("Cannot put {} inside itself", source.display()));
+        }
+    }
+    plan_entry(source, target, policy, &mut plan)?;
+    if !plan.conflicts.is_empty() {
+        let shown: Vec<String> = plan
+            .conflicts
+            .iter()
+            .take(5)
+            .map(|path| path.display().to_string())
+            .collect();
+        return Err(format!(
+            "{} destination{} already exist{}: {}{}",
+            plan.conflicts.len(),
+            if plan.conflicts.len() == 1 { "" } else { "s" },
+            if plan.conflicts.len() == 1 { "s" } else { "" },
+            shown.join(", "),
+            if plan.conflicts.len() > shown.len() {
+                ", …"
+            } else {
+                ""
+            }
+        ));
+    }
+    Ok(plan)
+}
+
+// Counts what is done and emits file_operation_progress at most every FS_PROGRESS_INTERVAL_MS
+struct Progress<'a, R: Runtime> {
+    app_handle: &'a AppHandle<R>,
+    operation_id: String,
+    kind: FileOperationKind,
+    cancelled: &'a AtomicBool,
+    total_files: usize,
+    total_bytes: u64,
+    files_done: usize,
+    bytes_done: u64,
+    reported_at: Instant,
+}
+
+impl<R: Runtime> Progress<'_, R> {
+    fn is_cancelled(&self) -> bool {
+        self.cancelled.load(Ordering::Relaxed)
+    }
+
+    fn add_bytes(&mut self, bytes: u64, path: &Path) {
+        self.bytes_done += bytes;
+        self.report(path, false);
+    }
+
+    fn file_done(&mut self, path: &Path) {
+        self.files_done += 1;
+        self.report(path, false);
+    }
+
+    fn report(&mut self, path: &Path, force: bool) {
+        if !force && self.reported_at.elapsed() < Duration::from_millis(FS_PROGRESS_INTERVAL_MS) {
+            return;
+        }
+        self.reported_at = Instant::now();
+        let percent = if self.total_bytes > 0 {
+            self.bytes_done as f64 / self.total_bytes as f64
+        } else if self.total_files > 0 {
+            self.files_done as f64 / self.total_files as f64
+        } else {
+            1.0
+        };
+        let _ = self.app_handle.emit(
+            "file_operation_progress",
+            FileOperationProgress {
+                operation_id: self.operation_id.clone(),
+                kind: self.kind,
+                current_path: path.to_string_lossy().to_string(),
+                files_done: self.files_done,
+                total_files: self.total_files,
+                bytes_done: self.bytes_done,
+                total_bytes: self.total_bytes,
+                percent: (percent * 1000.0).round() / 10.0,
+            },
+        );
+    }
+}
+
+// Hidden sibling the copy is written to, so a cancelled or failed copy never leaves half a
+// file at (or in place of) the destination
+fn partial_path(target: &Path) -> PathBuf {
+    let name = target
+        .file_name()
+        .map(|name| name.to_string_lossy().to_string())
+        .unwrap_or_default();
+    target.with_file_name(format!(".{}.partial", name))
+}
+
+// Ok(false) when cancelled part way
+fn copy_file<R: Runtime>(
+    source: &Path,
+    target: &Path,
+    progress: &mut Progress<'_, R>,
+) -> Result<bool, String> {
+    let partial = partial_path(target);
+    let result = (|| {
+        let mut reader = File::open(source)
+            .map_err(|e| format!("Failed to open {}: {}", source.display(), e))?;
+        let mut writer = File::create(&partial)
+            .map_err(|e| format!("Failed to create {}: {}", target.display(), e))?;
+        let mut buffer = vec![0; FS_COPY_BUFFER_BYTES];
+        loop {
+            if progress.is_cancelled() {
+                return Ok(false);
+            }
+            let read = match reader.read(&mut buffer) {
+                Ok(0) => break,
+                Ok(read) => read,
+                Err(e) if e.kind() == std::io::ErrorKind::Interrupted => continue,
+                Err(e) => return Err(format!("Failed to read {}: {}", source.display(), e)),
+            };
+            writer
+                .write_all(&buffer[..read])
+                .map_err(|e| format!("Failed to write {}: {}", target.display(), e))?;
+            progress.add_bytes(read as u64, source);
+        }
+        if let Ok(metadata) = fs::metadata(source) {
+            let _ = fs::set_permissions(&partial, metadata.permissions());
+        }
+        fs::rename(&partial, target)
+            .map_err(|e| format!("Failed to write {}: {}", target.display(), e))?;
+        Ok(true)
+    })();
+    if !matches!(result, Ok(true)) {
+        let _ = fs::remove_file(&partial);
+    }
+    result
+}
+
+fn copy_symlink(source: &Path, target: &Path) -> Result<(), String> {
+    #[cfg(unix)]
+    {
+        let link = fs::read_link(source)
+            .map_err(|e| format!("Failed to read link {}: {}", source.display(), e))?;
+        std::os::unix::fs::symlink(link, target)
+            .map_err(|e| format!("Failed to create link {}: {}", target.display(), e))
+    }
+    #[cfg(not(unix))]
+    {
+        fs::copy(source, target)
+            .map(|_| ())
+            .map_err(|e| format!("Failed to copy {}: {}", source.display(), e))
+    }
+}
+
+// Carries out the plan; a move renames each file where it can and copies it over otherwise.
+// Ok(false) when cancelled; what was done by then stays done.
+fn execute_plan<R: Runtime>(
+    plan: &Plan,
+    moving: bool,
+    progress: &mut Progress<'_, R>,
+) -> Result<bool, String> {
+    for entry in &plan.entries {
+        if progress.is_cancelled() {
+            return Ok(false);
+        }
+        if entry.kind == EntryKind::Directory {
+            if entry.replace {
+                fs::remove_file(&entry.target)
+                    .map_err(|e| format!("Failed to replace {}: {}", entry.target.display(), e))?;
+            }
+            fs::create_dir_all(&entry.target)
+                .map_err(|e| format!("Failed to create {}: {}", entry.target.display(), e))?;
+            continue;
+        }
+        if moving && fs::rename(&entry.source, &entry.target).is_ok() {
+            progress.add_bytes(entry.size, &entry.source);
+            progress.file_done(&entry.source);
+            continue;
+        }
+        if entry.kind == EntryKind::Symlink {
+            if entry.replace {
+                let _ = fs::remove_file(&entry.target);
+            }
+            copy_symlink(&entry.source, &entry.target)?;
+        } else if !copy_file(&entry.source, &entry.target, progress)? {
+            return Ok(false);
+        }
+        if moving {
+            fs::remove_file(&entry.source)
+                .map_err(|e| format!("Failed to remove {}: {}", entry.source.display(), e))?;
+        }
+        progress.file_done(&entry.source);
+    }
+    if moving {
+        // Deepest first; directories still holding skipped files stay
+        for entry in plan.entries.iter().rev() {
+            if entry.kind == EntryKind::Directory {
+                let _ = fs::remove_dir(&entry.source);
+            }
+        }
+    }
+    Ok(true)
+}
+
+fn transfer<R: Runtime>(
+    app_handle: &AppHandle<R>,
+    operation_id: &str,
+    cancelled: &AtomicBool,
+    kind: FileOperationKind,
+    source: &Path,
+    destination: &Path,
+    policy: OverwritePolicy,
+) -> Result<FileOperationResult, String> {
+    let started_at = Instant::now();
+    let target = destination_path(source, destination)?;
+    let plan = plan_transfer(source, &target, policy)?;
+    let files: Vec<&PlannedEntry> = plan
+        .entries
+        .iter()
+        .filter(|entry| entry.kind != EntryKind::Directory)
+        .collect();
+    let mut progress = Progress {
+        app_handle,
+        operation_id: operation_id.to_string(),
+        kind,
+        cancelled,
+        total_files: files.len(),
+        total_bytes: files.iter().map(|entry| entry.size).sum(),
+        files_done: 0,
+        bytes_done: 0,
+        reported_at: Instant::now(),
+    };
+
+    let moving = kind == FileOperationKind::Move;
+    // Same filesystem and nothing in the way: one rename moves the whole tree
+    let renamed_whole = moving
+        && fs::symlink_metadata(&target).is_err()
+        && plan
+            .entries
+            .first()
+            .is_some_and(|entry| entry.target == target)
+        && fs::rename(source, &target).is_ok();
+    let completed = if renamed_whole {
+        progress.files_done = progress.total_files;
+        progress.bytes_done = progress.total_bytes;
+        true
+    } else {
+        execute_plan(&plan, moving, &mut progress)?
+    };
+    progress.report(source, true);
+
+    Ok(FileOperationResult {
+        operation_id: operation_id.to_string(),
+        kind,
+        source: source.to_string_lossy().to_string(),
+        destination: Some(target.to_string_lossy().to_string()),
+        total_files: progress.total_files,
+        files_done: progress.files_done,
+        total_bytes: progress.total_bytes,
+        bytes_done: progress.bytes_done,
+        skipped: plan.skipped,
+        renamed: plan.renamed,
+        trashed: false,
+        cancelled: !completed,
+        duration_ms: started_at.elapsed().as_millis() as u64,
+    })
+}
+
+fn delete<R: Runtime>(
+    app_handle: &AppHandle<R>,
+    operation_id: &str,
+    cancelled: &AtomicBool,
+    path: &Path,
+    to_trash: bool,
+) -> Result<FileOperationResult, String> {
+    let started_at = Instant::now();
+    let metadata =
+        fs::symlink_metadata(path).map_err(|_| format!("{} does not exist", path.display()))?;
+    let canonical = fs::canonicalize(path).unwrap_or_else(|_| path.to_path_buf());
+    if canonical.parent().is_none() || dirs::home_dir().is_some_and(|home| canonical == home) {
+        return Err(format!("Refusing to delete {}", path.display()));
+    }
+
+    let mut plan = Plan::default();
+    if metadata.is_dir() && !to_trash {
+        plan_entry(
+            path,
+            path.to_path_buf(),
+            OverwritePolicy::Overwrite,
+            &mut plan,
+        )?;
+    } else {
+        plan.entries.push(PlannedEntry {
+            source: path.to_path_buf(),
+            target: path.to_path_buf(),
+            kind: entry_kind(&metadata),
+            size: if metadata.is_file() {
+                metadata.len()
+            } else {
+                0
+            },
+            replace: false,
+        });
+    }
+    let files: Vec<&PlannedEntry> = plan
+        .entries
+        .iter()
+        .filter(|entry| entry.kind != EntryKind::Directory)
+        .collect();
+    let mut progress = Progress {
+        app_handle,
+        operation_id: operation_id.to_string(),
+        kind: FileOperationKind::Delete,
+        cancelled,
+        total_files: files.len(),
+        total_bytes: files.iter().map(|entry| entry.size).sum(),
+        files_done: 0,
+        bytes_done: 0,
+        reported_at: Instant::now(),
+    };
+
+    let mut completed = true;
+    if to_trash {
+        trash::delete(path)
+            .map_err(|e| format!("Failed to move {} to the trash: {}", path.display(), e))?;
+        progress.files_done = progress.total_files;
+        progress.bytes_done = progress.total_bytes;
+    } else {
+        for entry in &files {
+            if progress.is_cancelled() {
+                completed = false;
+                break;
+            }
+            fs::remove_file(&entry.source)
+                .map_err(|e| format!("Failed to delete {}: {}", entry.source.display(), e))?;
+            progress.add_bytes(entry.size, &entry.source);
+            progress.file_done(&entry.source);
+        }
+        if completed {
+            for entry in plan.entries.iter().rev() {
+                if entry.kind == EntryKind::Directory {
+                    fs::remove_dir(&entry.source).map_err(|e| {
+                        format!("Failed to delete {}: {}", entry.source.display(), e)
+                    })?;
+                }
+            }
+        }
+    }
+    progress.report(path, true);
+
+    Ok(FileOperationResult {
+        operation_id: operation_id.to_string(),
+        kind: FileOperationKind::Delete,
+        source: path.to_string_lossy().to_string(),
+        destination: None,
+        total_files: progress.total_files,
+        files_done: progress.files_done,
+        total_bytes: progress.total_bytes,
+        bytes_done: progress.bytes_done,
+        skipped: Vec::new(),
+        renamed: Vec::new(),
+        trashed: to_trash,
+        cancelled: !completed,
+        duration_ms: started_at.elapsed().as_millis() as u64,
+    })
+}
+
+// Registers the operation for cancel_file_operation and runs it off the async runtime
+async fn run_operation<R: Runtime>(
+    app_handle: AppHandle<R>,
+    operation_id: Option<String>,
+    operation: impl FnOnce(&AppHandle<R>, &str, &AtomicBool) -> Result<FileOperationResult, String>
+        + Send
+        + 'static,
+) -> Result<FileOperationResult, String> {
+    let operation_id = operation_id.unwrap_or_else(|| uuid::Uuid::new_v4().to_string());
+    let cancelled = app_handle
+        .state::<FileOperationManager>()
+        .start(&operation_id)?;
+    tauri::async_runtime::spawn_blocking(move || {
+        let result = operation(&app_handle, &operation_id, &cancelled);
+        app_handle
+            .state::<FileOperationManager>()
+            .finish(&operation_id);
+        result
+    })
+    .await
+    .map_err(|e| e.to_string())?
+}
+
+fn resolve_paths<R: Runtime>(
+    app_handle: &AppHandle<R>,
+    session_id: Option<&str>,
+    paths: [&str; 2],
+) -> Result<(PathBuf, PathBuf), String> {
+    let command_manager = app_handle.state::<CommandManager>();
+    Ok((
+        resolve_session_path(&command_manager, session_id, paths[0])?,
+        resolve_session_path(&command_manager, session_id, paths[1])?,
+    ))
+}
+
+// Copies a file or directory tree. Into `dst` if it is a directory, otherwise to `dst`. The
+// caller may pick `operation_id` up front to cancel with cancel_file_operation; progress
+// arrives as file_operation_progress.
+#[command]
+pub async fn fs_copy<R: Runtime>(
+    src: String,
+    dst: String,
+    overwrite: Option<OverwritePolicy>,
+    operation_id: Option<String>,
+    session_id: Option<String>,
+    app_handle: AppHandle<R>,
+) -> Result<FileOperationResult, String> {
+    let (source, destination) = resolve_paths(&app_handle, session_id.as_deref(), [&src, &dst])?;
+    let policy = overwrite.unwrap_or_default();
+    run_operation(
+        app_handle,
+        operation_id,
+        move |app_handle, id, cancelled| {
+            transfer(
+                app_handle,
+                id,
+                cancelled,
+                FileOperationKind::Copy,
+                &source,
+                &destination,
+                policy,
+            )
+        },
+    )
+    .await
+}
+
+// Moves a file or directory tree like fs_copy, renaming where possible and copying across
+// filesystems
+#[command]
+pub async fn fs_move<R: Runtime>(
+    src: String,
+    dst: String,
+    overwrite: Option<OverwritePolicy>,
+    operation_id: Option<String>,
+    session_id: Option<String>,
+    app_handle: AppHandle<R>,
+) -> Result<FileOperationResult, String> {
+    let (source, destination) = resolve_paths(&app_handle, session_id.as_deref(), [&src, &dst])?;
+    let policy = overwrite.unwrap_or_default();
+    run_operation(
+        app_handle,
+        operation_id,
+        move |app_handle, id, cancelled| {
+            transfer(
+                app_handle,
+                id,
+                cancelled,
+                FileOperationKind::Move,
+                &source,
+                &destination,
+                policy,
+            )
+        },
+    )
+    .await
+}
+
+// Moves `path` to the system trash, or deletes it for good (file by file, cancellable) when
+// `to_trash` is false
+#[command]
+pub async fn fs_delete<R: Runtime>(
+    path: String,
+    to_trash: bool,
+    operation_id: Option<String>,
+    session_id: Option<String>,
+    app_handle: AppHandle<R>,
+) -> Result<FileOperationResult, String> {
+    let path = resolve_session_path(
+        &app_handle.state::<CommandManager>(),
+        session_id.as_deref(),
+        &path,
+    )?;
+    run_operation(
+        app_handle,
+        operation_id,
+        move |app_handle, id, cancelled| delete(app_handle, id, cancelled, &path, to_trash),
+    )
+    .await
+}
+
+// Stops a copy, move or delete after the file in progress; a partly copied file is removed
+#[command]
+pub fn cancel_file_operation(
+    operation_id: String,
+    file_operation_manager: State<'_, FileOperationManager>,
+) -> Result<(), String> {
+    file_operation_manager.cancel(&operation_id)
+}
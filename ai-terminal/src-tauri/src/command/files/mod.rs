@@ -0,0 +1,2 @@
+pub mod bulk_rename;
+pub mod file_operations;
@@ -0,0 +1,118 @@
+use crate::command::constants::{GIT_FETCH_MIN_INTERVAL_MS, GIT_FETCH_TIMEOUT_MS};
+use crate::command::git_commands::git::{new_git_command, read_git_status, GitStatus};
+use crate::command::types::command_manager::CommandManager;
+use crate::settings::types::settings_manager::SettingsManager;
+use crate::utils::config_utils::unix_timestamp_millis;
+use serde::Serialize;
+use std::collections::{BTreeSet, HashMap};
+use std::process::Stdio;
+use std::sync::{Mutex, OnceLock};
+use std::time::{Duration, Instant};
+use tauri::{AppHandle, Emitter, Manager, Runtime};
+
+// Last fetch time per repository root, shared by every session in that repository
+static LAST_FETCH: OnceLock<Mutex<HashMap<String, u64>>> = OnceLock::new();
+
+#[derive(Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+struct GitRemoteStatus {
+    repository: String,
+    status: GitStatus,
+}
+
+fn repository_root(dir: &str) -> Option<String> {
+    let output = new_git_command()
+        .args(["rev-parse", "--show-toplevel"])
+        .current_dir(dir)
+        .output()
+        .ok()?;
+    output
+        .status
+        .success()
+        .then(|| String::from_utf8_lossy(&output.stdout).trim().to_string())
+        .filter(|root| !root.is_empty())
+}
+
+// Non-interactive fetch: credential or host-key prompts fail instead of hanging
+fn fetch_repository(root: &str) -> Result<(), String> {
+    let mut child = new_git_command()
+        .args(["fetch", "--quiet", "--no-tags"])
+        .current_dir(root)
+        .env("GIT_TERMINAL_PROMPT", "0")
+        .env("GIT_SSH_COMMAND", "ssh -o BatchMode=yes")
+        .stdin(Stdio::null())
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .spawn()
+        .map_err(|e| format!("Failed to run git fetch in {}: {}", root, e))?;
+    let deadline = Instant::now() + Duration::from_millis(GIT_FETCH_TIMEOUT_MS);
+    loop {
+        match child.try_wait().map_err(|e| e.to_string())? {
+            Some(status) if status.success() => return Ok(()),
+            Some(_) => return Err(format!("git fetch failed in {}", root)),
+            None if Instant::now() >= deadline => {
+                let _ = child.kill();
+                let _ = child.wait();
+                return Err(format!("git fetch timed out in {}", root));
+            }
+            None => std::thread::sleep(Duration::from_millis(200)),
+        }
+    }
+}
+
+// Scheduler task: fetches each repository a local session is in (at most once per
+// GIT_FETCH_MIN_INTERVAL_MS) and emits git_remote_status with the refreshed counts
+pub fn fetch_session_repositories<R: Runtime>(app_handle: &AppHandle<R>) -> Result<(), String> {
+    if !app_handle
+        .state::<SettingsManager>()
+        .current()
+        .background_git_fetch
+    {
+        return Ok(());
+    }
+    let dirs: BTreeSet<String> = {
+        let command_manager = app_handle.state::<CommandManager>();
+        let states = command_manager.commands.lock().map_err(|e| e.to_string())?;
+        states
+            .values()
+            .filter(|state| !state.is_ssh_session_active)
+            .map(|state| state.current_dir.clone())
+            .collect()
+    };
+    let roots: BTreeSet<String> = dirs.iter().filter_map(|dir| repository_root(dir)).collect();
+
+    let last_fetch = LAST_FETCH.get_or_init(|| Mutex::new(HashMap::new()));
+    let mut errors = Vec::new();
+    for root in roots {
+        let now = unix_timestamp_millis();
+        {
+            let mut last_fetch = last_fetch.lock().map_err(|e| e.to_string())?;
+            let recent = last_fetch
+                .get(&root)
+                .is_some_and(|fetched| now.saturating_sub(*fetched) < GIT_FETCH_MIN_INTERVAL_MS);
+            if recent {
+                continue;
+            }
+            // Recorded up front so a failing remote is not retried on every run
+            last_fetch.insert(root.clone(), now);
+        }
+        if let Err(error) = fetch_repository(&root) {
+            errors.push(error);
+            continue;
+        }
+        if let Ok(Some(status)) = read_git_status(&root) {
+            let _ = app_handle.emit(
+                "git_remote_status",
+                GitRemoteStatus {
+                    repository: root,
+                    status,
+                },
+            );
+        }
+    }
+    if errors.is_empty() {
+        Ok(())
+    } else {
+        Err(errors.join("; "))
+    }
+}
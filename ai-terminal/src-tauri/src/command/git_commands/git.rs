@@ -1,5 +1,6 @@
 use crate::command::types::command_manager::CommandManager;
 use crate::utils::file_system_utils::get_shell_path;
+use serde::Serialize;
 use std::process::Command;
 use tauri::{command, State};
 
@@ -32,19 +33,123 @@ pub fn get_git_branch(
             .map_err(|e| e.to_string())?
     };
 
-    // Get current branch
+    Ok(read_git_branch(&current_dir)?.unwrap_or_default())
+}
+
+// Current branch of the repository containing `dir`, or None outside a repository
+pub fn read_git_branch(dir: &str) -> Result<Option<String>, String> {
     let mut cmd = new_git_command();
     cmd.arg("rev-parse")
         .arg("--abbrev-ref")
         .arg("HEAD")
-        .current_dir(&current_dir);
+        .current_dir(dir);
 
     let output = cmd.output().map_err(|e| e.to_string())?;
 
     if output.status.success() {
         let branch = String::from_utf8_lossy(&output.stdout).trim().to_string();
-        Ok(branch)
+        Ok(Some(branch))
     } else {
-        Ok("".to_string())
+        Ok(None)
+    }
+}
+
+#[derive(Serialize, Clone, Debug, Default, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct GitStatus {
+    pub branch: Option<String>,
+    pub upstream: Option<String>,
+    pub ahead: u32,
+    pub behind: u32,
+    pub staged: u32,
+    pub modified: u32,
+    pub untracked: u32,
+    pub conflicted: u32,
+}
+
+// `## main...origin/main [ahead 1, behind 2]`, `## No commits yet on main`, `## HEAD (no branch)`
+fn parse_branch_header(header: &str, status: &mut GitStatus) {
+    let header = header.trim();
+    if let Some(branch) = header.strip_prefix("No commits yet on ") {
+        status.branch = Some(branch.to_string());
+        return;
+    }
+    if header.starts_with("HEAD (no branch)") {
+        return;
+    }
+    let (refs, counts) = match header.split_once(" [") {
+        Some((refs, counts)) => (refs, counts.trim_end_matches(']')),
+        None => (header, ""),
+    };
+    match refs.split_once("...") {
+        Some((branch, upstream)) => {
+            status.branch = Some(branch.to_string());
+            status.upstream = Some(upstream.to_string());
+        }
+        None => status.branch = Some(refs.to_string()),
+    }
+    for count in counts.split(", ") {
+        if let Some(n) = count.strip_prefix("ahead ") {
+            status.ahead = n.parse().unwrap_or(0);
+        } else if let Some(n) = count.strip_prefix("behind ") {
+            status.behind = n.parse().unwrap_or(0);
+        }
+    }
+}
+
+pub fn parse_git_status(porcelain: &str) -> GitStatus {
+    let mut status = GitStatus::default();
+    for line in porcelain.lines() {
+        if let Some(header) = line.strip_prefix("## ") {
+            parse_branch_header(header, &mut status);
+            continue;
+        }
+        let mut codes = line.chars();
+        let (Some(index), Some(worktree)) = (codes.next(), codes.next()) else {
+            continue;
+        };
+        match (index, worktree) {
+            ('?', '?') => status.untracked += 1,
+            ('U', _) | (_, 'U') | ('A', 'A') | ('D', 'D') => status.conflicted += 1,
+            _ => {
+                if index != ' ' {
+                    status.staged += 1;
+                }
+                if worktree != ' ' {
+                    status.modified += 1;
+                }
+            }
+        }
+    }
+    status
+}
+
+// Branch, upstream divergence and change counts for the repository containing `dir`,
+// or None outside a repository
+pub fn read_git_status(dir: &str) -> Result<Option<GitStatus>, String> {
+    read_git_status_in(dir, false)
+}
+
+// Like read_git_status, with the change counts limited to `dir` and below when `subtree` is
+// set (the branch line still describes the whole repository). Uses the untracked cache when
+// the index has one, and never takes the index lock, so it can run beside the user's git.
+pub fn read_git_status_in(dir: &str, subtree: bool) -> Result<Option<GitStatus>, String> {
+    let mut cmd = new_git_command();
+    cmd.args(["-c", "core.untrackedCache=true"])
+        .arg("status")
+        .arg("--porcelain=v1")
+        .arg("--branch")
+        .env("GIT_OPTIONAL_LOCKS", "0")
+        .current_dir(dir);
+    if subtree {
+        cmd.args(["--", "."]);
+    }
+
+    let output = cmd.output().map_err(|e| e.to_string())?;
+    if !output.status.success() {
+        return Ok(None);
     }
+    Ok(Some(parse_git_status(&String::from_utf8_lossy(
+        &output.stdout,
+    ))))
 }
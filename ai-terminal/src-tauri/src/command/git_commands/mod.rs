@@ -1 +1,3 @@
+pub mod background_fetch;
 pub mod git;
+pub mod status_cache;
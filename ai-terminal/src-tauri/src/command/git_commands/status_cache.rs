@@ -0,0 +1,149 @@
+use crate::command::constants::{
+    GIT_STATUS_CACHE_ENTRIES, GIT_STATUS_SUBTREE_MS, GIT_STATUS_THROTTLE_FACTOR, GIT_STATUS_WAIT_MS,
+};
+use crate::command::git_commands::git::{read_git_status_in, GitStatus};
+use crate::command::types::git_status_cache::{CachedGitStatus, GitStatusCache};
+use crate::utils::config_utils::unix_timestamp_millis;
+use serde::Serialize;
+use std::collections::HashMap;
+use std::sync::mpsc;
+use std::time::{Duration, Instant};
+use tauri::{AppHandle, Emitter, Manager, Runtime};
+
+#[derive(Serialize, Clone, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct PromptGitStatus {
+    #[serde(flatten)]
+    pub status: GitStatus,
+    // Scanned before this request; git_status_updated follows with a newer scan
+    pub stale: bool,
+    pub checked_at: u64,
+    // Change counts only cover the working directory's subtree
+    pub subtree: bool,
+}
+
+#[derive(Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+struct GitStatusUpdate {
+    cwd: String,
+    git: Option<PromptGitStatus>,
+}
+
+fn prompt_status(entry: &CachedGitStatus, requested_at: u64) -> Option<PromptGitStatus> {
+    entry.status.clone().map(|status| PromptGitStatus {
+        status,
+        stale: entry.checked_at < requested_at,
+        checked_at: entry.checked_at,
+        subtree: entry.subtree,
+    })
+}
+
+// Drops the directories scanned longest ago once the cache is full
+fn evict(entries: &mut HashMap<String, CachedGitStatus>) {
+    while entries.len() > GIT_STATUS_CACHE_ENTRIES {
+        let oldest = entries
+            .iter()
+            .filter(|(_, entry)| !entry.refreshing)
+            .min_by_key(|(_, entry)| entry.checked_at)
+            .map(|(cwd, _)| cwd.clone());
+        match oldest {
+            Some(cwd) => entries.remove(&cwd),
+            None => break,
+        };
+    }
+}
+
+// Starts a scan of `cwd` after `delay_ms`. Signals `done` once the cache holds the result, or
+// announces it with git_status_updated when nobody is waiting any more.
+fn spawn_scan<R: Runtime>(
+    app_handle: &AppHandle<R>,
+    cwd: &str,
+    subtree: bool,
+    delay_ms: u64,
+    done: mpsc::Sender<()>,
+) {
+    let app_handle = app_handle.clone();
+    let cwd = cwd.to_string();
+    std::thread::spawn(move || {
+        std::thread::sleep(Duration::from_millis(delay_ms));
+        let started = Instant::now();
+        let status = read_git_status_in(&cwd, subtree).ok().flatten();
+        let duration_ms = started.elapsed().as_millis() as u64;
+        let entry = CachedGitStatus {
+            status,
+            checked_at: unix_timestamp_millis(),
+            duration_ms,
+            // Once a full scan is slow, later scans stay limited to the subtree
+            subtree: subtree || duration_ms >= GIT_STATUS_SUBTREE_MS,
+            refreshing: false,
+            announce: false,
+        };
+        let cache = app_handle.state::<GitStatusCache>();
+        let announce = match cache.entries.lock() {
+            Ok(mut entries) => entries
+                .insert(cwd.clone(), entry.clone())
+                .is_some_and(|previous| previous.announce),
+            Err(_) => false,
+        };
+        if done.send(()).is_err() || announce {
+            let _ = app_handle.emit(
+                "git_status_updated",
+                GitStatusUpdate {
+                    git: prompt_status(&entry, 0),
+                    cwd,
+                },
+            );
+        }
+    });
+}
+
+// Git status for the prompt without blocking on slow repositories. A due scan gets
+// GIT_STATUS_WAIT_MS to finish; otherwise the last result comes back marked stale and
+// git_status_updated follows. Scans of one directory are spaced GIT_STATUS_THROTTLE_FACTOR
+// times their duration apart, so a prompt refresh never keeps git busy.
+pub fn prompt_git_status<R: Runtime>(
+    app_handle: &AppHandle<R>,
+    cwd: &str,
+) -> Option<PromptGitStatus> {
+    let cache = app_handle.state::<GitStatusCache>();
+    let requested_at = unix_timestamp_millis();
+    let scan = {
+        let mut entries = cache.entries.lock().ok()?;
+        let entry = entries
+            .entry(cwd.to_string())
+            .or_insert_with(|| CachedGitStatus {
+                status: None,
+                checked_at: 0,
+                duration_ms: 0,
+                subtree: false,
+                refreshing: false,
+                announce: false,
+            });
+        let scan = if entry.refreshing {
+            // Someone else started the scan and may be the one waiting for it
+            entry.announce = true;
+            None
+        } else {
+            let ready_at = entry.checked_at + entry.duration_ms * GIT_STATUS_THROTTLE_FACTOR;
+            entry.refreshing = true;
+            Some((entry.subtree, ready_at.saturating_sub(requested_at)))
+        };
+        evict(&mut entries);
+        scan
+    };
+
+    if let Some((subtree, delay_ms)) = scan {
+        let (done, finished) = mpsc::channel();
+        spawn_scan(app_handle, cwd, subtree, delay_ms, done);
+        if delay_ms == 0 {
+            let _ = finished.recv_timeout(Duration::from_millis(GIT_STATUS_WAIT_MS));
+        }
+        // From here on a finishing scan announces itself instead
+        drop(finished);
+    }
+
+    let entries = cache.entries.lock().ok()?;
+    entries
+        .get(cwd)
+        .and_then(|entry| prompt_status(entry, requested_at))
+}
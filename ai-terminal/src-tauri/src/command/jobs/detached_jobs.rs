@@ -0,0 +1,245 @@
+use crate::audit::audit_log::record_audit_event;
+use crate::command::constants::{DETACHED_LOG_TAIL_BYTES, DETACHED_LOG_TAIL_LINES};
+use crate::command::core::command_templates::{expand_templates, session_template_context};
+use crate::command::core::protected_sessions::require_command_gates;
+use crate::command::environment::directory_env::session_env_overrides;
+use crate::command::types::command_manager::CommandManager;
+use crate::history::recorder::record_history_entry;
+use crate::settings::types::settings_manager::SettingsManager;
+use crate::utils::config_utils::{app_data_dir, unix_timestamp_millis};
+use crate::utils::env_scrubbing::scrubbed_environment;
+use crate::utils::file_system_utils::get_shell_path;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fs::{self, File, OpenOptions};
+use std::io::{Read, Seek, SeekFrom};
+use std::path::PathBuf;
+use std::process::{Command, Stdio};
+use std::sync::Mutex;
+use tauri::{command, AppHandle, Manager, Runtime, State};
+
+const DETACHED_JOBS_FILE: &str = "detached_jobs.json";
+const DETACHED_LOGS_DIR: &str = "detached_logs";
+
+// Serializes read-modify-write cycles on the registry file
+static REGISTRY_LOCK: Mutex<()> = Mutex::new(());
+
+// A process started with detach_command. The registry lives in the data directory so jobs
+// can still be found after the app restarts.
+#[derive(Serialize, Deserialize, Clone, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct DetachedJob {
+    pub id: String,
+    pub session_id: String,
+    pub command: String,
+    pub cwd: String,
+    pub pid: u32,
+    pub log_path: String,
+    pub started_at: u64,
+    // Only known when the job ended while the app that started it was still running
+    pub finished_at: Option<u64>,
+    pub exit_code: Option<i32>,
+    // Filled in by list_detached_jobs
+    #[serde(default)]
+    pub running: bool,
+}
+
+fn registry_path() -> Result<PathBuf, String> {
+    Ok(app_data_dir()?.join(DETACHED_JOBS_FILE))
+}
+
+fn load_jobs() -> Vec<DetachedJob> {
+    registry_path()
+        .ok()
+        .and_then(|path| fs::read_to_string(path).ok())
+        .and_then(|contents| serde_json::from_str(&contents).ok())
+        .unwrap_or_default()
+}
+
+fn update_jobs(update: impl FnOnce(&mut Vec<DetachedJob>)) -> Result<(), String> {
+    let _guard = REGISTRY_LOCK.lock().map_err(|e| e.to_string())?;
+    let mut jobs = load_jobs();
+    update(&mut jobs);
+    let path = registry_path()?;
+    let contents = serde_json::to_string_pretty(&jobs).map_err(|e| e.to_string())?;
+    fs::write(&path, contents).map_err(|e| format!("Failed to write {}: {}", path.display(), e))
+}
+
+fn is_running(job: &DetachedJob) -> bool {
+    if job.finished_at.is_some() {
+        return false;
+    }
+    #[cfg(unix)]
+    {
+        use nix::sys::signal::kill;
+        use nix::unistd::Pid;
+        kill(Pid::from_raw(job.pid as i32), None).is_ok()
+    }
+    #[cfg(not(unix))]
+    {
+        true
+    }
+}
+
+// Starts the command in its own session with output going to a log file, so it keeps running
+// when the app quits (like `nohup cmd &> log & disown`). It goes through the same gates as
+// execute_command; a held command fails with the confirmation marker until it is detached
+// again with the token. The audit log and history get it when it starts, without an exit code.
+#[command]
+pub fn detach_command<R: Runtime>(
+    command: String,
+    session_id: String,
+    confirmation_token: Option<String>,
+    app_handle: AppHandle<R>,
+    command_manager: State<'_, CommandManager>,
+) -> Result<DetachedJob, String> {
+    let context = session_template_context(&command_manager, &session_id)?;
+    if context.is_remote {
+        return Err("Detached jobs can only be started in a local session".to_string());
+    }
+    let command = expand_templates(command.trim(), &context)?;
+    if command.is_empty() {
+        return Err("No command to detach".to_string());
+    }
+    require_command_gates(
+        &app_handle,
+        &session_id,
+        &command,
+        confirmation_token.as_deref(),
+    )?;
+    let cwd = context.cwd;
+
+    let id = uuid::Uuid::new_v4().to_string();
+    let log_dir = app_data_dir()?.join(DETACHED_LOGS_DIR);
+    fs::create_dir_all(&log_dir)
+        .map_err(|e| format!("Failed to create {}: {}", log_dir.display(), e))?;
+    let log_path = log_dir.join(format!("{}.log", id));
+    let log = File::create(&log_path)
+        .map_err(|e| format!("Failed to create {}: {}", log_path.display(), e))?;
+    let log_for_stderr = log.try_clone().map_err(|e| e.to_string())?;
+
+    let mut env_map: HashMap<String, String> =
+        scrubbed_environment(&app_handle.state::<SettingsManager>().current());
+    if !env_map.contains_key("PATH") {
+        if let Some(path_value) = get_shell_path() {
+            env_map.insert("PATH".to_string(), path_value);
+        }
+    }
+    env_map.insert("PWD".to_string(), cwd.clone());
+    env_map.extend(session_env_overrides(&command_manager, &session_id));
+
+    let mut process = Command::new("sh");
+    process
+        .arg("-c")
+        .arg(&command)
+        .current_dir(&cwd)
+        .env_clear()
+        .envs(&env_map)
+        .stdin(Stdio::null())
+        .stdout(Stdio::from(log))
+        .stderr(Stdio::from(log_for_stderr));
+    // A new session detaches from the app's process group and controlling terminal, so the
+    // job neither receives the app's signals nor SIGHUP when it exits
+    #[cfg(unix)]
+    unsafe {
+        use std::os::unix::process::CommandExt;
+        process.pre_exec(|| match nix::unistd::setsid() {
+            Ok(_) => Ok(()),
+            Err(e) => Err(std::io::Error::other(e)),
+        });
+    }
+    let mut child = process
+        .spawn()
+        .map_err(|e| format!("Failed to start detached command: {}", e))?;
+
+    let job = DetachedJob {
+        id: id.clone(),
+        session_id,
+        command,
+        cwd,
+        pid: child.id(),
+        log_path: log_path.to_string_lossy().to_string(),
+        started_at: unix_timestamp_millis(),
+        finished_at: None,
+        exit_code: None,
+        running: true,
+    };
+    update_jobs(|jobs| jobs.push(job.clone()))?;
+    record_audit_event(
+        &app_handle,
+        &job.session_id,
+        &job.command,
+        &job.cwd,
+        false,
+        None,
+    );
+    record_history_entry(
+        &app_handle,
+        &job.session_id,
+        &job.command,
+        &job.cwd,
+        None,
+        None,
+    );
+
+    // Reap the child while the app runs so it does not linger as a zombie, recording how it ended
+    std::thread::spawn(move || {
+        let exit_code = child.wait().ok().and_then(|status| status.code());
+        let _ = update_jobs(|jobs| {
+            if let Some(job) = jobs.iter_mut().find(|job| job.id == id) {
+                job.finished_at = Some(unix_timestamp_millis());
+                job.exit_code = exit_code;
+            }
+        });
+    });
+
+    Ok(job)
+}
+
+// Jobs started with detach_command, newest first, including ones from earlier app runs
+#[command]
+pub fn list_detached_jobs() -> Result<Vec<DetachedJob>, String> {
+    let _guard = REGISTRY_LOCK.lock().map_err(|e| e.to_string())?;
+    let mut jobs = load_jobs();
+    for job in jobs.iter_mut() {
+        job.running = is_running(job);
+    }
+    jobs.reverse();
+    Ok(jobs)
+}
+
+// Last `lines` lines of a job's log (like `tail -n`)
+#[command]
+pub fn tail_detached_log(job_id: String, lines: Option<usize>) -> Result<String, String> {
+    let job = {
+        let _guard = REGISTRY_LOCK.lock().map_err(|e| e.to_string())?;
+        load_jobs()
+            .into_iter()
+            .find(|job| job.id == job_id)
+            .ok_or_else(|| format!("No detached job '{}'", job_id))?
+    };
+    let mut file = OpenOptions::new()
+        .read(true)
+        .open(&job.log_path)
+        .map_err(|e| format!("Failed to open {}: {}", job.log_path, e))?;
+    let length = file.metadata().map_err(|e| e.to_string())?.len();
+    let start = length.saturating_sub(DETACHED_LOG_TAIL_BYTES);
+    file.seek(SeekFrom::Start(start))
+        .map_err(|e| e.to_string())?;
+    let mut bytes = Vec::new();
+    file.read_to_end(&mut bytes).map_err(|e| e.to_string())?;
+    let contents = String::from_utf8_lossy(&bytes);
+
+    // Drop the partial first line when reading from the middle of the file
+    let contents = if start > 0 {
+        contents
+            .split_once('\n')
+            .map(|(_, rest)| rest)
+            .unwrap_or("")
+    } else {
+        &contents
+    };
+    let wanted = lines.unwrap_or(DETACHED_LOG_TAIL_LINES);
+    let all: Vec<&str> = contents.lines().collect();
+    Ok(all[all.len().saturating_sub(wanted)..].join("\n"))
+}
@@ -0,0 +1 @@
+pub mod detached_jobs;
@@ -0,0 +1,398 @@
+use crate::command::constants::{KILL_RING_SIZE, LINE_EDITOR_UNDO_LIMIT};
+use serde::{Deserialize, Serialize};
+use std::collections::VecDeque;
+
+// Editing operations, named after their readline counterparts (default Emacs bindings in
+// the comments)
+#[derive(Deserialize, Clone, Debug)]
+#[serde(tag = "type", rename_all = "camelCase")]
+pub enum EditAction {
+    // Typed or pasted text at the cursor
+    Insert { text: String },
+    // Replaces the whole line, e.g. when recalling history; `cursor` defaults to the end
+    SetLine { text: String, cursor: Option<usize> },
+    BackwardChar,       // C-b
+    ForwardChar,        // C-f
+    BeginningOfLine,    // C-a
+    EndOfLine,          // C-e
+    BackwardWord,       // M-b
+    ForwardWord,        // M-f
+    DeleteChar,         // C-d
+    BackwardDeleteChar, // Backspace
+    KillLine,           // C-k
+    UnixLineDiscard,    // C-u
+    KillWord,           // M-d
+    BackwardKillWord,   // M-Backspace
+    UnixWordRubout,     // C-w
+    Yank,               // C-y
+    YankPop,            // M-y
+    TransposeChars,     // C-t
+    UpcaseWord,         // M-u
+    DowncaseWord,       // M-l
+    CapitalizeWord,     // M-c
+    Undo,               // C-_
+}
+
+#[derive(Serialize, Clone, Debug, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct LineState {
+    pub text: String,
+    // In characters, not bytes
+    pub cursor: usize,
+}
+
+#[derive(Clone, Copy, PartialEq)]
+enum KillDirection {
+    Forward,
+    Backward,
+}
+
+#[derive(Default)]
+pub struct LineEditor {
+    line: Vec<char>,
+    cursor: usize,
+    kill_ring: VecDeque<String>,
+    undo_stack: Vec<(Vec<char>, usize)>,
+    // Consecutive kills are appended to the same kill-ring entry, as in readline
+    last_kill: Option<KillDirection>,
+    // Range of the text inserted by the last yank and its kill-ring index, for yank-pop
+    last_yank: Option<(usize, usize, usize)>,
+}
+
+fn is_word_char(c: char) -> bool {
+    c.is_alphanumeric()
+}
+
+impl LineEditor {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn state(&self) -> LineState {
+        LineState {
+            text: self.line.iter().collect(),
+            cursor: self.cursor,
+        }
+    }
+
+    // Empties the line for the next command, keeping the kill ring
+    pub fn accept_line(&mut self) {
+        self.line.clear();
+        self.cursor = 0;
+        self.undo_stack.clear();
+        self.last_kill = None;
+        self.last_yank = None;
+    }
+
+    pub fn apply(&mut self, action: EditAction) -> LineState {
+        let is_kill = matches!(
+            action,
+            EditAction::KillLine
+                | EditAction::UnixLineDiscard
+                | EditAction::KillWord
+                | EditAction::BackwardKillWord
+                | EditAction::UnixWordRubout
+        );
+        let is_yank = matches!(action, EditAction::Yank | EditAction::YankPop);
+        let before = (self.line.clone(), self.cursor);
+
+        match action {
+            EditAction::Insert { text } => {
+                let chars: Vec<char> = text.chars().collect();
+                let count = chars.len();
+                self.line.splice(self.cursor..self.cursor, chars);
+                self.cursor += count;
+            }
+            EditAction::SetLine { text, cursor } => {
+                self.line = text.chars().collect();
+                self.cursor = cursor.unwrap_or(self.line.len()).min(self.line.len());
+            }
+            EditAction::BackwardChar => self.cursor = self.cursor.saturating_sub(1),
+            EditAction::ForwardChar => self.cursor = (self.cursor + 1).min(self.line.len()),
+            EditAction::BeginningOfLine => self.cursor = 0,
+            EditAction::EndOfLine => self.cursor = self.line.len(),
+            EditAction::BackwardWord => self.cursor = self.word_start_before(self.cursor),
+            EditAction::ForwardWord => self.cursor = self.word_end_after(self.cursor),
+            EditAction::DeleteChar => {
+                if self.cursor < self.line.len() {
+                    self.line.remove(self.cursor);
+                }
+            }
+            EditAction::BackwardDeleteChar => {
+                if self.cursor > 0 {
+                    self.cursor -= 1;
+                    self.line.remove(self.cursor);
+                }
+            }
+            EditAction::KillLine => self.kill(self.cursor, self.line.len(), KillDirection::Forward),
+            EditAction::UnixLineDiscard => self.kill(0, self.cursor, KillDirection::Backward),
+            EditAction::KillWord => {
+                let end = self.word_end_after(self.cursor);
+                self.kill(self.cursor, end, KillDirection::Forward);
+            }
+            EditAction::BackwardKillWord => {
+                let start = self.word_start_before(self.cursor);
+                self.kill(start, self.cursor, KillDirection::Backward);
+            }
+            EditAction::UnixWordRubout => {
+                let mut start = self.cursor;
+                while start > 0 && self.line[start - 1].is_whitespace() {
+                    start -= 1;
+                }
+                while start > 0 && !self.line[start - 1].is_whitespace() {
+                    start -= 1;
+                }
+                self.kill(start, self.cursor, KillDirection::Backward);
+            }
+            EditAction::Yank => self.yank(0),
+            EditAction::YankPop => {
+                // Only valid straight after a yank: replaces it with the previous kill
+                if let Some((start, end, index)) = self.last_yank {
+                    self.line.drain(start..end);
+                    self.cursor = start;
+                    self.yank(index + 1);
+                }
+            }
+            EditAction::TransposeChars => self.transpose_chars(),
+            EditAction::UpcaseWord => self.map_word(|word| word.to_uppercase()),
+            EditAction::DowncaseWord => self.map_word(|word| word.to_lowercase()),
+            EditAction::CapitalizeWord => self.map_word(|word| {
+                // The range starts with any separators before the word
+                let mut seen_word = false;
+                word.chars()
+                    .flat_map(|c| {
+                        let first = !seen_word && is_word_char(c);
+                        seen_word |= is_word_char(c);
+                        if first {
+                            c.to_uppercase().collect::<Vec<_>>()
+                        } else {
+                            c.to_lowercase().collect()
+                        }
+                    })
+                    .collect()
+            }),
+            EditAction::Undo => {
+                if let Some((line, cursor)) = self.undo_stack.pop() {
+                    self.line = line;
+                    self.cursor = cursor;
+                }
+                self.last_kill = None;
+                self.last_yank = None;
+                return self.state();
+            }
+        }
+
+        if !is_kill {
+            self.last_kill = None;
+        }
+        if !is_yank {
+            self.last_yank = None;
+        }
+        // Cursor motion alone is not an undoable change
+        if self.line != before.0 {
+            self.undo_stack.push(before);
+            if self.undo_stack.len() > LINE_EDITOR_UNDO_LIMIT {
+                self.undo_stack.remove(0);
+            }
+        }
+        self.state()
+    }
+
+    // Start of the word at or before `position` (readline's backward-word)
+    fn word_start_before(&self, position: usize) -> usize {
+        let mut index = position;
+        while index > 0 && !is_word_char(self.line[index - 1]) {
+            index -= 1;
+        }
+        while index > 0 && is_word_char(self.line[index - 1]) {
+            index -= 1;
+        }
+        index
+    }
+
+    // End of the word at or after `position` (readline's forward-word)
+    fn word_end_after(&self, position: usize) -> usize {
+        let mut index = position;
+        while index < self.line.len() && !is_word_char(self.line[index]) {
+            index += 1;
+        }
+        while index < self.line.len() && is_word_char(self.line[index]) {
+            index += 1;
+        }
+        index
+    }
+
+    fn kill(&mut self, start: usize, end: usize, direction: KillDirection) {
+        if start >= end {
+            return;
+        }
+        let killed: String = self.line.drain(start..end).collect();
+        self.cursor = start;
+        match (self.last_kill, self.kill_ring.front_mut()) {
+            (Some(_), Some(entry)) => match direction {
+                KillDirection::Forward => entry.push_str(&killed),
+                KillDirection::Backward => entry.insert_str(0, &killed),
+            },
+            _ => {
+                self.kill_ring.push_front(killed);
+                self.kill_ring.truncate(KILL_RING_SIZE);
+            }
+        }
+        self.last_kill = Some(direction);
+    }
+
+    fn yank(&mut self, index: usize) {
+        if self.kill_ring.is_empty() {
+            return;
+        }
+        let index = index % self.kill_ring.len();
+        let text: Vec<char> = self.kill_ring[index].chars().collect();
+        let start = self.cursor;
+        let count = text.len();
+        self.line.splice(start..start, text);
+        self.cursor = start + count;
+        self.last_yank = Some((start, self.cursor, index));
+    }
+
+    // Swaps the characters around the cursor; at the end of the line, the last two
+    fn transpose_chars(&mut self) {
+        if self.line.len() < 2 || self.cursor == 0 {
+            return;
+        }
+        let position = self.cursor.min(self.line.len() - 1);
+        self.line.swap(position - 1, position);
+        self.cursor = position + 1;
+    }
+
+    // Rewrites from the cursor to the end of the next word and moves past it
+    fn map_word(&mut self, transform: impl Fn(&str) -> String) {
+        let end = self.word_end_after(self.cursor);
+        let word: String = self.line[self.cursor..end].iter().collect();
+        let replaced: Vec<char> = transform(&word).chars().collect();
+        let count = replaced.len();
+        self.line.splice(self.cursor..end, replaced);
+        self.cursor += count;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn line(text: &str, cursor: usize) -> LineEditor {
+        let mut editor = LineEditor::new();
+        editor.apply(EditAction::SetLine {
+            text: text.to_string(),
+            cursor: Some(cursor),
+        });
+        editor
+    }
+
+    fn state(text: &str, cursor: usize) -> LineState {
+        LineState {
+            text: text.to_string(),
+            cursor,
+        }
+    }
+
+    #[test]
+    fn word_motions() {
+        let mut editor = line("git commit --amend", 0);
+        assert_eq!(editor.apply(EditAction::ForwardWord).cursor, 3);
+        assert_eq!(editor.apply(EditAction::ForwardWord).cursor, 10);
+        assert_eq!(editor.apply(EditAction::BackwardWord).cursor, 4);
+        editor.apply(EditAction::EndOfLine);
+        assert_eq!(editor.apply(EditAction::BackwardWord).cursor, 13);
+        assert_eq!(editor.apply(EditAction::BeginningOfLine).cursor, 0);
+        assert_eq!(editor.apply(EditAction::BackwardChar).cursor, 0);
+    }
+
+    #[test]
+    fn consecutive_kills_share_a_kill_ring_entry() {
+        let mut editor = line("echo foo bar", 12);
+        editor.apply(EditAction::BackwardKillWord);
+        assert_eq!(
+            editor.apply(EditAction::BackwardKillWord),
+            state("echo ", 5)
+        );
+        assert_eq!(editor.apply(EditAction::Yank), state("echo foo bar", 12));
+
+        let mut editor = line("ls -la /tmp", 11);
+        assert_eq!(
+            editor.apply(EditAction::UnixWordRubout),
+            state("ls -la ", 7)
+        );
+        editor.apply(EditAction::UnixWordRubout);
+        assert_eq!(editor.apply(EditAction::Yank), state("ls -la /tmp", 11));
+    }
+
+    #[test]
+    fn yank_pop_cycles_through_earlier_kills() {
+        let mut editor = line("one two", 3);
+        editor.apply(EditAction::KillLine);
+        // Moving ends the kill sequence, so the next kill gets its own entry
+        editor.apply(EditAction::BeginningOfLine);
+        assert_eq!(editor.apply(EditAction::KillLine), state("", 0));
+
+        assert_eq!(editor.apply(EditAction::Yank), state("one", 3));
+        assert_eq!(editor.apply(EditAction::YankPop), state(" two", 4));
+        assert_eq!(editor.apply(EditAction::YankPop), state("one", 3));
+        // Not straight after a yank, so nothing happens
+        editor.apply(EditAction::BackwardChar);
+        assert_eq!(editor.apply(EditAction::YankPop), state("one", 2));
+    }
+
+    #[test]
+    fn undo_restores_earlier_lines_but_not_motions() {
+        let mut editor = LineEditor::new();
+        editor.apply(EditAction::Insert {
+            text: "ab".to_string(),
+        });
+        editor.apply(EditAction::Insert {
+            text: "c".to_string(),
+        });
+        editor.apply(EditAction::BackwardChar);
+        assert_eq!(editor.apply(EditAction::BackwardDeleteChar), state("ac", 1));
+
+        assert_eq!(editor.apply(EditAction::Undo), state("abc", 2));
+        assert_eq!(editor.apply(EditAction::Undo), state("ab", 2));
+        assert_eq!(editor.apply(EditAction::Undo), state("", 0));
+        assert_eq!(editor.apply(EditAction::Undo), state("", 0));
+    }
+
+    #[test]
+    fn accepting_a_line_keeps_the_kill_ring() {
+        let mut editor = line("secret", 0);
+        editor.apply(EditAction::KillLine);
+        editor.accept_line();
+        assert_eq!(editor.apply(EditAction::Undo), state("", 0));
+        assert_eq!(editor.apply(EditAction::Yank), state("secret", 6));
+    }
+
+    #[test]
+    fn transpose_and_case_changes() {
+        let mut editor = line("ab", 2);
+        assert_eq!(editor.apply(EditAction::TransposeChars), state("ba", 2));
+
+        let mut editor = line("hello WORLD", 0);
+        assert_eq!(
+            editor.apply(EditAction::CapitalizeWord),
+            state("Hello WORLD", 5)
+        );
+        assert_eq!(
+            editor.apply(EditAction::DowncaseWord),
+            state("Hello world", 11)
+        );
+    }
+
+    #[test]
+    fn cursor_counts_characters() {
+        let mut editor = LineEditor::new();
+        editor.apply(EditAction::Insert {
+            text: "héllo".to_string(),
+        });
+        editor.apply(EditAction::BackwardWord);
+        assert_eq!(editor.apply(EditAction::DeleteChar), state("éllo", 0));
+        assert_eq!(editor.apply(EditAction::UpcaseWord), state("ÉLLO", 4));
+    }
+}
@@ -0,0 +1,54 @@
+use crate::command::line_editor::editor::{EditAction, LineEditor, LineState};
+use crate::command::types::line_editor_manager::LineEditorManager;
+use tauri::{command, State};
+
+// Applies one editing action to the session's input line and returns the resulting line
+#[command]
+pub fn line_editor_apply(
+    session_id: String,
+    action: EditAction,
+    line_editor_manager: State<'_, LineEditorManager>,
+) -> Result<LineState, String> {
+    let mut editors = line_editor_manager
+        .editors
+        .lock()
+        .map_err(|e| e.to_string())?;
+    Ok(editors
+        .entry(session_id)
+        .or_insert_with(LineEditor::new)
+        .apply(action))
+}
+
+#[command]
+pub fn line_editor_state(
+    session_id: String,
+    line_editor_manager: State<'_, LineEditorManager>,
+) -> Result<LineState, String> {
+    let editors = line_editor_manager
+        .editors
+        .lock()
+        .map_err(|e| e.to_string())?;
+    Ok(editors
+        .get(&session_id)
+        .map(LineEditor::state)
+        .unwrap_or(LineState {
+            text: String::new(),
+            cursor: 0,
+        }))
+}
+
+// Clears the line after it is submitted; the kill ring survives, undo history does not
+#[command]
+pub fn line_editor_accept(
+    session_id: String,
+    line_editor_manager: State<'_, LineEditorManager>,
+) -> Result<LineState, String> {
+    let mut editors = line_editor_manager
+        .editors
+        .lock()
+        .map_err(|e| e.to_string())?;
+    let editor = editors.entry(session_id).or_insert_with(LineEditor::new);
+    let submitted = editor.state();
+    editor.accept_line();
+    Ok(submitted)
+}
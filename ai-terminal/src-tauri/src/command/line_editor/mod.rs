@@ -0,0 +1,2 @@
+pub mod editor;
+pub mod line_editor_commands;
@@ -0,0 +1,229 @@
+use crate::command::core::protected_sessions::{confirm_protected_action, is_session_protected};
+use crate::command::core::pty::write_pty_input;
+use crate::command::types::confirmation_manager::CONFIRMATION_REQUIRED_MARKER;
+use crate::command::types::macro_manager::{InputMacro, MacroManager, MacroRecording};
+use crate::command::types::pty_manager::PtyManager;
+use crate::command::windows::session_windows::emit_session_event;
+use crate::utils::config_utils::unix_timestamp_millis;
+use serde::Serialize;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::thread;
+use std::time::{Duration, Instant};
+use tauri::{command, AppHandle, Manager, Runtime, State};
+
+// Longest sleep between checks for a cancelled playback
+const PLAYBACK_POLL_MS: u64 = 50;
+
+#[derive(Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct MacroPlaybackEvent {
+    pub session_id: String,
+    pub name: String,
+    pub steps_played: usize,
+    pub total_steps: usize,
+    pub cancelled: bool,
+    pub error: Option<String>,
+}
+
+// Sleeps for `duration` unless the playback is cancelled first
+fn wait(duration: Duration, cancelled: &AtomicBool) -> bool {
+    let until = Instant::now() + duration;
+    loop {
+        if cancelled.load(Ordering::Relaxed) {
+            return false;
+        }
+        let left = until.saturating_duration_since(Instant::now());
+        if left.is_zero() {
+            return true;
+        }
+        thread::sleep(left.min(Duration::from_millis(PLAYBACK_POLL_MS)));
+    }
+}
+
+// Starts capturing what pty_write sends to the session, with the time between inputs
+#[command]
+pub fn start_macro_recording(
+    session_id: String,
+    macro_manager: State<'_, MacroManager>,
+    pty_manager: State<'_, PtyManager>,
+) -> Result<(), String> {
+    let exists = pty_manager
+        .sessions
+        .lock()
+        .map_err(|e| e.to_string())?
+        .contains_key(&session_id);
+    if !exists {
+        return Err(format!("PTY session '{}' not found", session_id));
+    }
+    let mut recordings = macro_manager.recordings.lock().map_err(|e| e.to_string())?;
+    if recordings.contains_key(&session_id) {
+        return Err(format!(
+            "Session '{}' is already being recorded",
+            session_id
+        ));
+    }
+    recordings.insert(
+        session_id,
+        MacroRecording {
+            steps: Vec::new(),
+            last_input_at: Instant::now(),
+        },
+    );
+    Ok(())
+}
+
+// Ends a recording and saves it as `name`, replacing a macro of that name; without a name
+// the recording is discarded
+#[command]
+pub fn stop_macro_recording(
+    session_id: String,
+    name: Option<String>,
+    macro_manager: State<'_, MacroManager>,
+) -> Result<Option<InputMacro>, String> {
+    let recording = macro_manager
+        .recordings
+        .lock()
+        .map_err(|e| e.to_string())?
+        .remove(&session_id)
+        .ok_or_else(|| format!("Session '{}' is not being recorded", session_id))?;
+    let Some(name) = name else {
+        return Ok(None);
+    };
+    let name = name.trim().to_string();
+    if name.is_empty() {
+        return Err("Macro name cannot be empty".to_string());
+    }
+    if recording.steps.is_empty() {
+        return Err("Nothing was typed while recording".to_string());
+    }
+
+    let input_macro = InputMacro {
+        name: name.clone(),
+        steps: recording.steps,
+        recorded_at: unix_timestamp_millis(),
+    };
+    let mut macros = macro_manager.macros.lock().map_err(|e| e.to_string())?;
+    macros.insert(name, input_macro.clone());
+    macro_manager.persist(&macros)?;
+    Ok(Some(input_macro))
+}
+
+#[command]
+pub fn list_macros(macro_manager: State<'_, MacroManager>) -> Result<Vec<InputMacro>, String> {
+    let macros = macro_manager.macros.lock().map_err(|e| e.to_string())?;
+    let mut macros: Vec<InputMacro> = macros.values().cloned().collect();
+    macros.sort_by(|a, b| a.name.cmp(&b.name));
+    Ok(macros)
+}
+
+#[command]
+pub fn delete_macro(name: String, macro_manager: State<'_, MacroManager>) -> Result<(), String> {
+    let mut macros = macro_manager.macros.lock().map_err(|e| e.to_string())?;
+    if macros.remove(&name).is_none() {
+        return Err(format!("Macro '{}' not found", name));
+    }
+    macro_manager.persist(&macros)
+}
+
+// Replays a macro into a session with its recorded timing, divided by `speed` (default 1; 0
+// sends every step at once). Returns immediately; macro_playback_finished reports the end.
+// Playing into a protected session fails with the confirmation marker until it is confirmed.
+#[command]
+pub fn play_macro<R: Runtime>(
+    name: String,
+    session_id: String,
+    speed: Option<f64>,
+    confirmation_token: Option<String>,
+    app_handle: AppHandle<R>,
+    macro_manager: State<'_, MacroManager>,
+) -> Result<(), String> {
+    let speed = speed.unwrap_or(1.0);
+    if !speed.is_finite() || speed < 0.0 {
+        return Err("Playback speed must be a positive number, or 0".to_string());
+    }
+    let input_macro = macro_manager
+        .macros
+        .lock()
+        .map_err(|e| e.to_string())?
+        .get(&name)
+        .cloned()
+        .ok_or_else(|| format!("Macro '{}' not found", name))?;
+    // Its keystrokes are not checked line by line like typed input, so any macro needs consent
+    if is_session_protected(&app_handle, &session_id)
+        && !confirm_protected_action(
+            &app_handle,
+            &format!("protected:macro:{}:{}", session_id, name),
+            "Play a macro into a protected session?",
+            &format!("Macro '{}' into {}", name, session_id),
+            confirmation_token.as_deref(),
+        )?
+    {
+        return Err(CONFIRMATION_REQUIRED_MARKER.to_string());
+    }
+    let cancelled = Arc::new(AtomicBool::new(false));
+    {
+        let mut playbacks = macro_manager.playbacks.lock().map_err(|e| e.to_string())?;
+        if playbacks.contains_key(&session_id) {
+            return Err(format!(
+                "A macro is already playing in session '{}'",
+                session_id
+            ));
+        }
+        playbacks.insert(session_id.clone(), cancelled.clone());
+    }
+
+    thread::spawn(move || {
+        let total_steps = input_macro.steps.len();
+        let mut steps_played = 0;
+        let mut error = None;
+        for step in &input_macro.steps {
+            let delay = if speed == 0.0 {
+                Duration::ZERO
+            } else {
+                Duration::from_secs_f64(step.delay_ms as f64 / 1000.0 / speed)
+            };
+            if !wait(delay, &cancelled) {
+                break;
+            }
+            if let Err(e) =
+                write_pty_input(&app_handle.state::<PtyManager>(), &session_id, &step.data)
+            {
+                error = Some(e);
+                break;
+            }
+            steps_played += 1;
+        }
+
+        if let Ok(mut playbacks) = app_handle.state::<MacroManager>().playbacks.lock() {
+            playbacks.remove(&session_id);
+        }
+        emit_session_event(
+            &app_handle,
+            &session_id,
+            "macro_playback_finished",
+            MacroPlaybackEvent {
+                session_id: session_id.clone(),
+                name: input_macro.name.clone(),
+                steps_played,
+                total_steps,
+                cancelled: cancelled.load(Ordering::Relaxed),
+                error,
+            },
+        );
+    });
+    Ok(())
+}
+
+#[command]
+pub fn stop_macro_playback(
+    session_id: String,
+    macro_manager: State<'_, MacroManager>,
+) -> Result<(), String> {
+    let playbacks = macro_manager.playbacks.lock().map_err(|e| e.to_string())?;
+    let cancelled = playbacks
+        .get(&session_id)
+        .ok_or_else(|| format!("No macro is playing in session '{}'", session_id))?;
+    cancelled.store(true, Ordering::Relaxed);
+    Ok(())
+}
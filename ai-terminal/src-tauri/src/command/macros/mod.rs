@@ -0,0 +1 @@
+pub mod macro_commands;
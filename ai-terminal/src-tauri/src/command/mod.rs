@@ -1,5 +1,28 @@
 pub mod autocomplete;
+pub mod checksums;
 pub mod constants;
+pub mod containers;
 pub mod core;
+pub mod correction;
+pub mod deep_link;
+pub mod docs;
+pub mod environment;
+pub mod files;
 pub mod git_commands;
+pub mod jobs;
+pub mod line_editor;
+pub mod macros;
+pub mod network;
+pub mod output;
+pub mod project;
+pub mod prompt;
+pub mod recovery;
+pub mod repl;
+pub mod repro;
+pub mod sharing;
+pub mod shell_integration;
+pub mod ssh;
+pub mod timeline;
+pub mod transcript;
 pub mod types;
+pub mod windows;
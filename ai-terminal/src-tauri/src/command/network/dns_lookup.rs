@@ -0,0 +1,198 @@
+use crate::command::constants::DNS_LOOKUP_TIMEOUT_MS;
+use crate::utils::process_output::{output_with_timeout, ToolError};
+use serde::Serialize;
+use std::net::{IpAddr, ToSocketAddrs};
+use std::process::Command;
+use std::time::{Duration, Instant};
+use tauri::command;
+
+const RECORD_TYPES: &[&str] = &[
+    "A", "AAAA", "CNAME", "MX", "TXT", "NS", "SOA", "SRV", "CAA", "PTR",
+];
+
+#[derive(Serialize, Clone, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct DnsRecord {
+    pub name: String,
+    pub record_type: String,
+    // Unknown when the system resolver answered
+    pub ttl: Option<u32>,
+    pub value: String,
+}
+
+#[derive(Serialize, Clone, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct DnsLookup {
+    pub host: String,
+    pub record_type: String,
+    // Name server asked, when not the system default
+    pub server: Option<String>,
+    // "dig", or "system" when dig is not installed
+    pub resolver: String,
+    // Includes any CNAME records followed on the way to the answer
+    pub records: Vec<DnsRecord>,
+    pub duration_ms: u64,
+}
+
+fn parse_record_type(record_type: Option<&str>) -> Result<String, String> {
+    let record_type = record_type.unwrap_or("A").trim().to_ascii_uppercase();
+    if RECORD_TYPES.contains(&record_type.as_str()) {
+        Ok(record_type)
+    } else {
+        Err(format!(
+            "Unsupported record type '{}'. Supported: {}",
+            record_type,
+            RECORD_TYPES.join(", ")
+        ))
+    }
+}
+
+// Hostnames (with `_service._proto` labels for SRV) and IP addresses only, so nothing reaches dig
+// as an option such as `-f file` or a query flag such as `+tcp`
+fn is_valid_host(host: &str) -> bool {
+    host.parse::<IpAddr>().is_ok()
+        || (host.len() <= 253
+            && !host.starts_with(['-', '+', '.'])
+            && host
+                .chars()
+                .all(|c| c.is_ascii_alphanumeric() || matches!(c, '-' | '.' | '_')))
+}
+
+// `example.com. 300 IN A 93.184.216.34` lines from `dig +noall +answer`
+fn parse_dig_answer(stdout: &str) -> Vec<DnsRecord> {
+    stdout
+        .lines()
+        .filter(|line| !line.trim().is_empty() && !line.starts_with(';'))
+        .filter_map(|line| {
+            let fields: Vec<&str> = line.split_whitespace().collect();
+            if fields.len() < 5 {
+                return None;
+            }
+            Some(DnsRecord {
+                name: fields[0].trim_end_matches('.').to_string(),
+                ttl: fields[1].parse().ok(),
+                record_type: fields[3].to_string(),
+                value: fields[4..].join(" "),
+            })
+        })
+        .collect()
+}
+
+fn dig_lookup(
+    host: &str,
+    record_type: &str,
+    server: Option<&str>,
+) -> Result<Vec<DnsRecord>, ToolError> {
+    let mut dig = Command::new("dig");
+    dig.args(["+noall", "+answer", "+time=3", "+tries=2"]);
+    if let Some(server) = server {
+        dig.arg(format!("@{}", server));
+    }
+    // Reverse lookups take the address rather than its in-addr.arpa name
+    if record_type == "PTR" && host.parse::<IpAddr>().is_ok() {
+        dig.args(["-x", host]);
+    } else {
+        dig.args([host, record_type]);
+    }
+
+    let output = output_with_timeout(&mut dig, None, Duration::from_millis(DNS_LOOKUP_TIMEOUT_MS))?;
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        // dig reports "no servers could be reached" and the like as ;; comments
+        let reason = stderr
+            .lines()
+            .chain(stdout.lines())
+            .map(|line| line.trim_start_matches(";;").trim())
+            .find(|line| !line.is_empty())
+            .unwrap_or("no answer")
+            .to_string();
+        return Err(ToolError::Failed(format!(
+            "DNS lookup for {} failed: {}",
+            host, reason
+        )));
+    }
+    Ok(parse_dig_answer(&stdout))
+}
+
+// Without dig only address records can be looked up, through the system resolver
+fn system_lookup(host: &str, record_type: &str) -> Result<Vec<DnsRecord>, String> {
+    let addresses = (host, 0)
+        .to_socket_addrs()
+        .map_err(|e| format!("DNS lookup for {} failed: {}", host, e))?;
+    let mut records: Vec<DnsRecord> = Vec::new();
+    for address in addresses {
+        let matches = match address.ip() {
+            IpAddr::V4(_) => record_type == "A",
+            IpAddr::V6(_) => record_type == "AAAA",
+        };
+        let value = address.ip().to_string();
+        if matches && !records.iter().any(|record| record.value == value) {
+            records.push(DnsRecord {
+                name: host.to_string(),
+                record_type: record_type.to_string(),
+                ttl: None,
+                value,
+            });
+        }
+    }
+    Ok(records)
+}
+
+fn lookup(
+    host: &str,
+    record_type: Option<&str>,
+    server: Option<&str>,
+) -> Result<DnsLookup, String> {
+    let host = host.trim().trim_end_matches('.');
+    if host.is_empty() {
+        return Err("Host is required".to_string());
+    }
+    if !is_valid_host(host) {
+        return Err(format!("'{}' is not a hostname or IP address", host));
+    }
+    let record_type = parse_record_type(record_type)?;
+    let server = server.map(str::trim).filter(|server| !server.is_empty());
+    if let Some(server) = server.filter(|server| !is_valid_host(server)) {
+        return Err(format!("'{}' is not a name server address", server));
+    }
+    let started = Instant::now();
+
+    let (resolver, records) = match dig_lookup(host, &record_type, server) {
+        Ok(records) => ("dig", records),
+        Err(ToolError::Failed(error)) => return Err(error),
+        Err(ToolError::NotFound) => {
+            if server.is_some() || !matches!(record_type.as_str(), "A" | "AAAA") {
+                return Err(
+                    "dig is not installed; without it only A and AAAA records can be \
+                            looked up, through the system resolver"
+                        .to_string(),
+                );
+            }
+            ("system", system_lookup(host, &record_type)?)
+        }
+    };
+    Ok(DnsLookup {
+        host: host.to_string(),
+        record_type,
+        server: server.map(str::to_string),
+        resolver: resolver.to_string(),
+        records,
+        duration_ms: started.elapsed().as_millis() as u64,
+    })
+}
+
+// Looks up A/AAAA/CNAME/MX/TXT/NS/SOA/SRV/CAA/PTR records (A by default). Passing a `server`
+// such as 8.8.8.8 asks that name server directly, to see whether a change has propagated.
+#[command]
+pub async fn dns_lookup(
+    host: String,
+    record_type: Option<String>,
+    server: Option<String>,
+) -> Result<DnsLookup, String> {
+    tauri::async_runtime::spawn_blocking(move || {
+        lookup(&host, record_type.as_deref(), server.as_deref())
+    })
+    .await
+    .map_err(|e| e.to_string())?
+}
@@ -0,0 +1,2 @@
+pub mod dns_lookup;
+pub mod tls_inspection;
@@ -0,0 +1,279 @@
+use crate::command::constants::{TLS_EXPIRY_WARNING_DAYS, TLS_INSPECT_TIMEOUT_MS};
+use crate::utils::process_output::{output_with_timeout, ToolError};
+use chrono::{NaiveDateTime, Utc};
+use serde::Serialize;
+use std::net::IpAddr;
+use std::process::Command;
+use std::time::{Duration, Instant};
+use tauri::command;
+
+const PEM_BEGIN: &str = "-----BEGIN CERTIFICATE-----";
+const PEM_END: &str = "-----END CERTIFICATE-----";
+
+#[derive(Serialize, Clone, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct CertificateInfo {
+    pub subject: String,
+    pub issuer: String,
+    pub serial: Option<String>,
+    pub subject_alt_names: Vec<String>,
+    pub not_before: Option<String>,
+    pub not_after: Option<String>,
+    // Negative once expired
+    pub days_remaining: Option<i64>,
+    pub expired: bool,
+    pub fingerprint_sha256: Option<String>,
+}
+
+#[derive(Serialize, Clone, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct TlsInspection {
+    pub host: String,
+    pub port: u16,
+    pub protocol: Option<String>,
+    pub cipher: Option<String>,
+    // Chain and hostname checked against the system trust store
+    pub verified: bool,
+    pub verify_result: Option<String>,
+    // Leaf first, as the server sent it
+    pub chain: Vec<CertificateInfo>,
+    // Leaf certificate expires within TLS_EXPIRY_WARNING_DAYS
+    pub expires_soon: bool,
+    pub duration_ms: u64,
+}
+
+fn openssl_missing() -> String {
+    "openssl is not installed; it is needed to inspect TLS certificates".to_string()
+}
+
+fn tool_error(error: ToolError) -> String {
+    match error {
+        ToolError::NotFound => openssl_missing(),
+        ToolError::Failed(error) => error,
+    }
+}
+
+fn pem_blocks(text: &str) -> Vec<String> {
+    let mut blocks = Vec::new();
+    let mut rest = text;
+    while let Some(start) = rest.find(PEM_BEGIN) {
+        let Some(end) = rest[start..].find(PEM_END) else {
+            break;
+        };
+        let end = start + end + PEM_END.len();
+        blocks.push(format!("{}\n", &rest[start..end]));
+        rest = &rest[end..];
+    }
+    blocks
+}
+
+// `notAfter=Nov 16 04:13:20 2026 GMT`
+fn parse_openssl_date(value: &str) -> Option<NaiveDateTime> {
+    let normalized = value
+        .trim()
+        .trim_end_matches("GMT")
+        .split_whitespace()
+        .collect::<Vec<_>>()
+        .join(" ");
+    NaiveDateTime::parse_from_str(&normalized, "%b %d %H:%M:%S %Y").ok()
+}
+
+fn describe_certificate(pem: &str) -> Result<CertificateInfo, String> {
+    let fields = [
+        "-subject",
+        "-issuer",
+        "-startdate",
+        "-enddate",
+        "-serial",
+        "-fingerprint",
+        "-sha256",
+    ];
+    let run = |with_alt_names: bool| {
+        let mut x509 = Command::new("openssl");
+        x509.args(["x509", "-noout"]).args(fields);
+        if with_alt_names {
+            x509.args(["-ext", "subjectAltName"]);
+        }
+        output_with_timeout(
+            &mut x509,
+            Some(pem.as_bytes()),
+            Duration::from_millis(TLS_INSPECT_TIMEOUT_MS),
+        )
+        .map_err(tool_error)
+    };
+    // -ext needs OpenSSL 1.1.1; older versions still give everything else
+    let mut output = run(true)?;
+    if !output.status.success() {
+        output = run(false)?;
+    }
+    if !output.status.success() {
+        return Err(format!(
+            "openssl could not read the certificate: {}",
+            String::from_utf8_lossy(&output.stderr).trim()
+        ));
+    }
+
+    let text = String::from_utf8_lossy(&output.stdout);
+    let mut info = CertificateInfo {
+        subject: String::new(),
+        issuer: String::new(),
+        serial: None,
+        subject_alt_names: Vec::new(),
+        not_before: None,
+        not_after: None,
+        days_remaining: None,
+        expired: false,
+        fingerprint_sha256: None,
+    };
+    let mut in_alt_names = false;
+    for line in text.lines() {
+        if in_alt_names && line.starts_with(char::is_whitespace) {
+            info.subject_alt_names.extend(
+                line.split(',')
+                    .map(|name| name.trim().to_string())
+                    .filter(|name| !name.is_empty()),
+            );
+            continue;
+        }
+        in_alt_names = line.starts_with("X509v3 Subject Alternative Name");
+        let Some((key, value)) = line.split_once('=') else {
+            continue;
+        };
+        let value = value.trim().to_string();
+        match key.trim().to_ascii_lowercase().as_str() {
+            "subject" => info.subject = value,
+            "issuer" => info.issuer = value,
+            "serial" => info.serial = Some(value),
+            "notbefore" => info.not_before = Some(value),
+            "notafter" => info.not_after = Some(value),
+            "sha256 fingerprint" => info.fingerprint_sha256 = Some(value),
+            _ => {}
+        }
+    }
+
+    if let Some(not_after) = info.not_after.as_deref().and_then(parse_openssl_date) {
+        let remaining = not_after.and_utc() - Utc::now();
+        info.days_remaining = Some(remaining.num_days());
+        info.expired = remaining.num_seconds() < 0;
+        info.not_after = Some(not_after.and_utc().to_rfc3339());
+    }
+    if let Some(not_before) = info.not_before.as_deref().and_then(parse_openssl_date) {
+        info.not_before = Some(not_before.and_utc().to_rfc3339());
+    }
+    Ok(info)
+}
+
+// The reason in `40474DE8F07F0000:error:8000006F:system library:BIO_connect:Connection refused:...`
+fn openssl_reason(line: &str) -> &str {
+    let fields: Vec<&str> = line.split(':').collect();
+    if fields.get(1) == Some(&"error") && fields.len() > 5 {
+        fields[5]
+    } else {
+        line
+    }
+}
+
+// `New, TLSv1.3, Cipher is TLS_AES_256_GCM_SHA384`, or the SSL-Session block on older versions
+fn negotiated(text: &str) -> (Option<String>, Option<String>) {
+    let mut protocol = None;
+    let mut cipher = None;
+    for line in text.lines().map(str::trim) {
+        if let Some(rest) = line.strip_prefix("New, ") {
+            if let Some((version, suite)) = rest.split_once(", Cipher is ") {
+                protocol.get_or_insert_with(|| version.trim().to_string());
+                cipher.get_or_insert_with(|| suite.trim().to_string());
+            }
+        } else if let Some((key, value)) = line.split_once(':') {
+            match key.trim() {
+                "Protocol" => {
+                    protocol.get_or_insert_with(|| value.trim().to_string());
+                }
+                "Cipher" => {
+                    cipher.get_or_insert_with(|| value.trim().to_string());
+                }
+                _ => {}
+            }
+        }
+    }
+    (protocol, cipher)
+}
+
+fn inspect(host: &str, port: u16) -> Result<TlsInspection, String> {
+    let host = host.trim();
+    if host.is_empty() {
+        return Err("Host is required".to_string());
+    }
+    let started = Instant::now();
+    let ip = host.trim_matches(['[', ']']).parse::<IpAddr>().ok();
+    let address = match ip {
+        Some(IpAddr::V6(ip)) => format!("[{}]:{}", ip, port),
+        _ => format!("{}:{}", host, port),
+    };
+
+    let mut s_client = Command::new("openssl");
+    s_client.args(["s_client", "-connect", &address, "-showcerts"]);
+    match ip {
+        Some(ip) => {
+            s_client.args(["-verify_ip", &ip.to_string()]);
+        }
+        None => {
+            s_client.args(["-servername", host, "-verify_hostname", host]);
+        }
+    }
+    // With stdin closed s_client exits right after the handshake
+    let output = output_with_timeout(
+        &mut s_client,
+        None,
+        Duration::from_millis(TLS_INSPECT_TIMEOUT_MS),
+    )
+    .map_err(tool_error)?;
+    let stdout = String::from_utf8_lossy(&output.stdout);
+
+    let blocks = pem_blocks(&stdout);
+    if blocks.is_empty() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        let reason = stderr
+            .lines()
+            .map(str::trim)
+            .find(|line| !line.is_empty() && !line.starts_with("Connecting to") && *line != "---")
+            .map(openssl_reason)
+            .unwrap_or("no certificate received");
+        return Err(format!("TLS handshake with {} failed: {}", address, reason));
+    }
+    let chain = blocks
+        .iter()
+        .map(|pem| describe_certificate(pem))
+        .collect::<Result<Vec<_>, String>>()?;
+
+    let verify_result = stdout
+        .lines()
+        .find_map(|line| line.trim().strip_prefix("Verify return code:"))
+        .map(|result| result.trim().to_string());
+    let (protocol, cipher) = negotiated(&stdout);
+    let expires_soon = chain
+        .first()
+        .and_then(|leaf| leaf.days_remaining)
+        .is_some_and(|days| days < TLS_EXPIRY_WARNING_DAYS);
+    Ok(TlsInspection {
+        host: host.to_string(),
+        port,
+        protocol,
+        cipher,
+        verified: verify_result
+            .as_deref()
+            .is_some_and(|result| result.starts_with("0 ")),
+        verify_result,
+        chain,
+        expires_soon,
+        duration_ms: started.elapsed().as_millis() as u64,
+    })
+}
+
+// Connects with openssl and reports the negotiated protocol, whether the chain verifies, and each
+// certificate's names and expiry
+#[command]
+pub async fn inspect_tls(host: String, port: Option<u16>) -> Result<TlsInspection, String> {
+    tauri::async_runtime::spawn_blocking(move || inspect(&host, port.unwrap_or(443)))
+        .await
+        .map_err(|e| e.to_string())?
+}
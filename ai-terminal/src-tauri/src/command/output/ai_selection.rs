@@ -0,0 +1,151 @@
+use crate::command::constants::MAX_AI_SELECTION_BYTES;
+use crate::command::types::command_manager::CommandManager;
+use crate::command::types::command_state::CommandState;
+use crate::command::types::scrollback_manager::{OutputStream, ScrollbackManager};
+use crate::utils::ansi::strip_ansi;
+use serde::{Deserialize, Serialize};
+use tauri::{command, State};
+
+// Selected part of the scrollback: absolute entry positions (as used by output marks), end
+// exclusive, with optional char offsets into the plain text of the first and last entries
+#[derive(Deserialize, Clone, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct TextRange {
+    pub start: usize,
+    pub end: usize,
+    pub start_offset: Option<usize>,
+    pub end_offset: Option<usize>,
+}
+
+#[derive(Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct AiSelection {
+    pub text: String,
+    pub lines: usize,
+    // The selection was longer than MAX_AI_SELECTION_BYTES and its beginning was dropped
+    pub truncated: bool,
+}
+
+fn entry_text(stream: OutputStream, data: &str) -> String {
+    match stream {
+        OutputStream::Input => format!("$ {}\n", data.trim_end()),
+        _ => strip_ansi(data).replace("\r\n", "\n"),
+    }
+}
+
+fn char_boundary_after(text: &str, chars: usize) -> usize {
+    text.char_indices()
+        .nth(chars)
+        .map(|(index, _)| index)
+        .unwrap_or(text.len())
+}
+
+// Keeps the last MAX_AI_SELECTION_BYTES, where errors and summaries usually are
+fn truncate_selection(text: &str) -> (String, bool) {
+    if text.len() <= MAX_AI_SELECTION_BYTES {
+        return (text.to_string(), false);
+    }
+    let mut start = text.len() - MAX_AI_SELECTION_BYTES;
+    while !text.is_char_boundary(start) {
+        start += 1;
+    }
+    (text[start..].to_string(), true)
+}
+
+fn quote_selection(selection: &str) -> String {
+    let quoted: Vec<String> = selection
+        .lines()
+        .map(|line| format!("> {}", line))
+        .collect();
+    format!(
+        "The user selected this terminal output:\n{}",
+        quoted.join("\n")
+    )
+}
+
+// Quoted block prepended to the next question, consuming the selection
+pub fn take_ai_selection_context(
+    command_manager: &CommandManager,
+    session_id: &str,
+) -> Option<String> {
+    let mut states = command_manager.commands.lock().ok()?;
+    let selection = states.get_mut(session_id)?.ai_selection.take()?;
+    Some(quote_selection(&selection))
+}
+
+// The same block without consuming the selection, for previews
+pub fn peek_ai_selection_context(
+    command_manager: &CommandManager,
+    session_id: &str,
+) -> Option<String> {
+    let states = command_manager.commands.lock().ok()?;
+    let selection = states.get(session_id)?.ai_selection.as_ref()?;
+    Some(quote_selection(selection))
+}
+
+// Registers a scrollback selection so the next ask_ai call for the session quotes it
+#[command]
+pub fn set_ai_selection(
+    session_id: String,
+    text_range: TextRange,
+    command_manager: State<'_, CommandManager>,
+    scrollback_manager: State<'_, ScrollbackManager>,
+) -> Result<AiSelection, String> {
+    if text_range.end <= text_range.start {
+        return Err("The selection is empty".to_string());
+    }
+    let entries =
+        scrollback_manager.entries_between(&session_id, text_range.start, Some(text_range.end))?;
+    if entries.is_empty() {
+        return Err("The selected output is no longer retained".to_string());
+    }
+    let last = entries.len() - 1;
+    let mut text = String::new();
+    for (index, entry) in entries.iter().enumerate() {
+        let mut part = entry_text(entry.stream, &entry.data);
+        if index == last {
+            if let Some(offset) = text_range.end_offset {
+                part.truncate(char_boundary_after(&part, offset));
+            }
+        }
+        if index == 0 {
+            if let Some(offset) = text_range.start_offset {
+                part.drain(..char_boundary_after(&part, offset));
+            }
+        }
+        text.push_str(&part);
+    }
+    let text = text.trim_matches('\n');
+    if text.trim().is_empty() {
+        return Err("The selection is empty".to_string());
+    }
+    let (text, truncated) = truncate_selection(text);
+
+    let mut states = command_manager.commands.lock().map_err(|e| e.to_string())?;
+    let state = states.entry(session_id).or_insert_with(|| {
+        CommandState::new(
+            std::env::current_dir()
+                .unwrap_or_default()
+                .to_string_lossy()
+                .to_string(),
+        )
+    });
+    state.ai_selection = Some(text.clone());
+    Ok(AiSelection {
+        lines: text.lines().count(),
+        text,
+        truncated,
+    })
+}
+
+#[command]
+pub fn clear_ai_selection(
+    session_id: String,
+    command_manager: State<'_, CommandManager>,
+) -> Result<(), String> {
+    let mut states = command_manager.commands.lock().map_err(|e| e.to_string())?;
+    if let Some(state) = states.get_mut(&session_id) {
+        state.ai_selection = None;
+    }
+    Ok(())
+}
@@ -0,0 +1,195 @@
+use crate::command::constants::BELL_MIN_INTERVAL_MS;
+use crate::command::types::bell_manager::{BellManager, BellScanState};
+use crate::command::types::window_router::WindowRouter;
+use crate::command::windows::session_windows::emit_session_event;
+use crate::notifications::notification_commands::notification_action;
+use crate::notifications::types::notification::NotificationAction;
+use crate::settings::types::app_settings::BellAction;
+use crate::settings::types::settings_manager::SettingsManager;
+use serde::Serialize;
+use std::process::{Command, Stdio};
+use std::thread;
+use std::time::{Duration, Instant};
+use tauri::{command, AppHandle, Manager, Runtime, State, UserAttentionType};
+
+#[derive(Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct VisualBellEvent {
+    pub session_id: String,
+}
+
+// Counts the bells in `data`. BEL also ends OSC strings (window titles, progress reports,
+// hyperlinks), so it only rings outside escape sequences.
+pub fn scan_bells(state: &mut BellScanState, data: &str) -> usize {
+    use BellScanState::{Escape, Ground, Osc, OscEscape};
+    let mut bells = 0;
+    for c in data.chars() {
+        *state = match (*state, c) {
+            // CAN and SUB abort any sequence
+            (_, '\x18' | '\x1a') => Ground,
+            (Ground, '\x07') => {
+                bells += 1;
+                Ground
+            }
+            (Ground, '\x1b') => Escape,
+            // 8-bit OSC, DCS, SOS, PM and APC
+            (Ground, '\u{90}' | '\u{98}' | '\u{9d}' | '\u{9e}' | '\u{9f}') => Osc,
+            (Ground, _) => Ground,
+            (Osc, '\x07' | '\u{9c}') => Ground,
+            (Osc, '\x1b') => OscEscape,
+            (Osc, _) => Osc,
+            (OscEscape, '\\') => Ground,
+            // Any other ESC ends the string and starts a new sequence
+            (Escape | OscEscape, ']' | 'P' | 'X' | '^' | '_') => Osc,
+            (Escape | OscEscape, '\x1b') => Escape,
+            (Escape | OscEscape, _) => Ground,
+        };
+    }
+    bells
+}
+
+fn bell_action<R: Runtime>(app_handle: &AppHandle<R>, session_id: &str) -> BellAction {
+    app_handle
+        .state::<BellManager>()
+        .overrides
+        .lock()
+        .ok()
+        .and_then(|overrides| overrides.get(session_id).copied())
+        .unwrap_or_else(|| app_handle.state::<SettingsManager>().current().bell_action)
+}
+
+// Starts the desktop's alert sound without waiting for it; false when there is no player
+fn play_system_sound() -> bool {
+    #[cfg(target_os = "macos")]
+    let mut command = {
+        let mut command = Command::new("osascript");
+        command.args(["-e", "beep"]);
+        command
+    };
+    #[cfg(target_os = "windows")]
+    let mut command = {
+        let mut command = Command::new("rundll32");
+        command.arg("user32.dll,MessageBeep");
+        command
+    };
+    #[cfg(not(any(target_os = "macos", target_os = "windows")))]
+    let mut command = {
+        let mut command = Command::new("canberra-gtk-play");
+        command.args(["--id", "bell"]);
+        command
+    };
+    let spawned = command
+        .stdin(Stdio::null())
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .spawn();
+    match spawned {
+        Ok(mut child) => {
+            thread::spawn(move || {
+                let _ = child.wait();
+            });
+            true
+        }
+        Err(_) => false,
+    }
+}
+
+// Bounces the dock icon (macOS) or flashes the taskbar button of the session's window
+fn flash_window<R: Runtime>(app_handle: &AppHandle<R>, session_id: &str) -> bool {
+    let label = app_handle
+        .state::<WindowRouter>()
+        .owner(session_id)
+        .unwrap_or_else(|| "main".to_string());
+    app_handle.get_webview_window(&label).is_some_and(|window| {
+        window
+            .request_user_attention(Some(UserAttentionType::Informational))
+            .is_ok()
+    })
+}
+
+fn ring_bell<R: Runtime>(app_handle: &AppHandle<R>, session_id: &str) {
+    // Sound and flashing follow do-not-disturb and the session's notification override;
+    // the pane flashes instead
+    let alert = notification_action(app_handle, session_id) == NotificationAction::Deliver;
+    let alerted = match bell_action(app_handle, session_id) {
+        BellAction::Off => return,
+        BellAction::Sound if alert => play_system_sound(),
+        BellAction::Flash if alert => flash_window(app_handle, session_id),
+        BellAction::Sound | BellAction::Flash | BellAction::Visual => false,
+    };
+    if !alerted {
+        emit_session_event(
+            app_handle,
+            session_id,
+            "visual_bell",
+            VisualBellEvent {
+                session_id: session_id.to_string(),
+            },
+        );
+    }
+}
+
+// Feeds raw PTY output for a session and rings the bell for each BEL outside escape
+// sequences, at most once every BELL_MIN_INTERVAL_MS
+pub fn track_bell<R: Runtime>(app_handle: &AppHandle<R>, session_id: &str, data: &str) {
+    let manager = app_handle.state::<BellManager>();
+    let due = {
+        let Ok(mut sessions) = manager.sessions.lock() else {
+            return;
+        };
+        let session = sessions.entry(session_id.to_string()).or_default();
+        if scan_bells(&mut session.scan, data) == 0 {
+            return;
+        }
+        let interval = Duration::from_millis(BELL_MIN_INTERVAL_MS);
+        let due = session
+            .rang_at
+            .is_none_or(|rang_at| rang_at.elapsed() >= interval);
+        if due {
+            session.rang_at = Some(Instant::now());
+        }
+        due
+    };
+    if due {
+        ring_bell(app_handle, session_id);
+    }
+}
+
+// Drops the scanner state and override of a session that has ended
+pub fn forget_bell_session<R: Runtime>(app_handle: &AppHandle<R>, session_id: &str) {
+    let manager = app_handle.state::<BellManager>();
+    if let Ok(mut sessions) = manager.sessions.lock() {
+        sessions.remove(session_id);
+    }
+    if let Ok(mut overrides) = manager.overrides.lock() {
+        overrides.remove(session_id);
+    };
+}
+
+// The bell action of a session, or the bellAction setting without one
+#[command]
+pub fn get_bell_action(
+    session_id: Option<String>,
+    bell_manager: State<'_, BellManager>,
+    settings_manager: State<'_, SettingsManager>,
+) -> Result<BellAction, String> {
+    let overrides = bell_manager.overrides.lock().map_err(|e| e.to_string())?;
+    Ok(session_id
+        .and_then(|session_id| overrides.get(&session_id).copied())
+        .unwrap_or_else(|| settings_manager.current().bell_action))
+}
+
+// Overrides the bellAction setting for one session; None goes back to the setting
+#[command]
+pub fn set_bell_action(
+    session_id: String,
+    action: Option<BellAction>,
+    bell_manager: State<'_, BellManager>,
+) -> Result<(), String> {
+    let mut overrides = bell_manager.overrides.lock().map_err(|e| e.to_string())?;
+    match action {
+        Some(action) => overrides.insert(session_id, action),
+        None => overrides.remove(&session_id),
+    };
+    Ok(())
+}
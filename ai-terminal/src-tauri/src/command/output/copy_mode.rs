@@ -0,0 +1,558 @@
+use crate::command::constants::COPY_MODE_DEFAULT_ROWS;
+use crate::command::types::copy_mode_manager::{
+    CopyModeManager, CopyModeState, CopyPosition, SelectionMode,
+};
+use crate::command::types::scrollback_manager::ScrollbackManager;
+use serde::{Deserialize, Serialize};
+use tauri::{command, State};
+
+#[derive(Deserialize, Clone, Copy, Debug, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub enum CopyMotion {
+    Left,
+    Right,
+    Up,
+    Down,
+    LineStart,
+    LineEnd,
+    // Words are runs of non-whitespace, like W and B in vi
+    WordForward,
+    WordBackward,
+    PageUp,
+    PageDown,
+    HalfPageUp,
+    HalfPageDown,
+    Top,
+    Bottom,
+    // Next or previous occurrence of the query
+    SearchForward,
+    SearchBackward,
+}
+
+// What the frontend draws: only the lines of the viewport, however long the scrollback is
+#[derive(Serialize, Clone, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct CopyModeView {
+    pub cursor: CopyPosition,
+    pub anchor: Option<CopyPosition>,
+    // None while nothing is selected
+    pub mode: Option<SelectionMode>,
+    pub top: usize,
+    pub lines: Vec<String>,
+    pub total_lines: usize,
+}
+
+fn line_chars(state: &CopyModeState, line: usize) -> Vec<char> {
+    state
+        .lines
+        .get(line)
+        .map(|text| text.chars().collect())
+        .unwrap_or_default()
+}
+
+fn last_column(state: &CopyModeState, line: usize) -> usize {
+    line_chars(state, line).len().saturating_sub(1)
+}
+
+fn position(line: usize, column: usize) -> CopyPosition {
+    CopyPosition { line, column }
+}
+
+fn word_forward(state: &CopyModeState, from: CopyPosition) -> CopyPosition {
+    let mut line_index = from.line;
+    let mut line = line_chars(state, line_index);
+    let mut column = from.column;
+    while column < line.len() && !line[column].is_whitespace() {
+        column += 1;
+    }
+    loop {
+        while column < line.len() && line[column].is_whitespace() {
+            column += 1;
+        }
+        if column < line.len() {
+            return position(line_index, column);
+        }
+        if line_index + 1 >= state.lines.len() {
+            return position(line_index, last_column(state, line_index));
+        }
+        line_index += 1;
+        line = line_chars(state, line_index);
+        column = 0;
+        // An empty line is a stop of its own
+        if line.is_empty() {
+            return position(line_index, 0);
+        }
+    }
+}
+
+fn word_backward(state: &CopyModeState, from: CopyPosition) -> CopyPosition {
+    let mut line_index = from.line;
+    let mut line = line_chars(state, line_index);
+    let mut column = from.column.min(line.len());
+    loop {
+        while column > 0 && line[column - 1].is_whitespace() {
+            column -= 1;
+        }
+        if column > 0 {
+            break;
+        }
+        if line_index == 0 {
+            return position(0, 0);
+        }
+        line_index -= 1;
+        line = line_chars(state, line_index);
+        column = line.len();
+        if line.is_empty() {
+            return position(line_index, 0);
+        }
+    }
+    while column > 0 && !line[column - 1].is_whitespace() {
+        column -= 1;
+    }
+    position(line_index, column)
+}
+
+fn char_column(line: &str, byte: usize) -> usize {
+    line[..byte].chars().count()
+}
+
+fn byte_offset(line: &str, column: usize) -> usize {
+    line.char_indices()
+        .nth(column)
+        .map(|(index, _)| index)
+        .unwrap_or(line.len())
+}
+
+fn search_forward(state: &CopyModeState, from: CopyPosition, query: &str) -> Option<CopyPosition> {
+    let first = &state.lines[from.line];
+    let start = byte_offset(first, from.column + 1);
+    if let Some(found) = first[start..].find(query) {
+        return Some(position(from.line, char_column(first, start + found)));
+    }
+    (from.line + 1..state.lines.len()).find_map(|line_index| {
+        let line = &state.lines[line_index];
+        line.find(query)
+            .map(|found| position(line_index, char_column(line, found)))
+    })
+}
+
+fn search_backward(state: &CopyModeState, from: CopyPosition, query: &str) -> Option<CopyPosition> {
+    let first = &state.lines[from.line];
+    let end = byte_offset(first, from.column);
+    if let Some(found) = first[..end].rfind(query) {
+        return Some(position(from.line, char_column(first, found)));
+    }
+    (0..from.line).rev().find_map(|line_index| {
+        let line = &state.lines[line_index];
+        line.rfind(query)
+            .map(|found| position(line_index, char_column(line, found)))
+    })
+}
+
+fn move_cursor(
+    state: &mut CopyModeState,
+    motion: CopyMotion,
+    count: usize,
+    query: Option<&str>,
+) -> Result<(), String> {
+    let cursor = state.cursor;
+    let last_line = state.lines.len() - 1;
+    let vertical = |lines: isize| {
+        let line = (cursor.line as isize + lines).clamp(0, last_line as isize) as usize;
+        position(line, state.preferred_column.min(last_column(state, line)))
+    };
+    let page = state.rows.max(1) as isize * count as isize;
+    let target = match motion {
+        CopyMotion::Left => position(cursor.line, cursor.column.saturating_sub(count)),
+        CopyMotion::Right => position(
+            cursor.line,
+            (cursor.column + count).min(last_column(state, cursor.line)),
+        ),
+        CopyMotion::Up => vertical(-(count as isize)),
+        CopyMotion::Down => vertical(count as isize),
+        CopyMotion::LineStart => position(cursor.line, 0),
+        CopyMotion::LineEnd => position(cursor.line, last_column(state, cursor.line)),
+        CopyMotion::WordForward => (0..count).fold(cursor, |from, _| word_forward(state, from)),
+        CopyMotion::WordBackward => (0..count).fold(cursor, |from, _| word_backward(state, from)),
+        CopyMotion::PageUp => vertical(-page),
+        CopyMotion::PageDown => vertical(page),
+        CopyMotion::HalfPageUp => vertical(-page / 2),
+        CopyMotion::HalfPageDown => vertical(page / 2),
+        CopyMotion::Top => position(0, 0),
+        CopyMotion::Bottom => position(last_line, 0),
+        CopyMotion::SearchForward | CopyMotion::SearchBackward => {
+            let query = query
+                .filter(|query| !query.is_empty())
+                .ok_or("Searching needs a query")?;
+            let mut found = cursor;
+            for _ in 0..count {
+                let next = if motion == CopyMotion::SearchForward {
+                    search_forward(state, found, query)
+                } else {
+                    search_backward(state, found, query)
+                };
+                found = next.ok_or_else(|| format!("'{}' not found", query))?;
+            }
+            found
+        }
+    };
+
+    let keeps_column = matches!(
+        motion,
+        CopyMotion::Up
+            | CopyMotion::Down
+            | CopyMotion::PageUp
+            | CopyMotion::PageDown
+            | CopyMotion::HalfPageUp
+            | CopyMotion::HalfPageDown
+    );
+    if !keeps_column {
+        state.preferred_column = target.column;
+    }
+    state.cursor = target;
+    // Scroll just enough to keep the cursor in the viewport
+    if target.line < state.top {
+        state.top = target.line;
+    } else if target.line >= state.top + state.rows {
+        state.top = target.line + 1 - state.rows;
+    }
+    Ok(())
+}
+
+fn selected_text(state: &CopyModeState) -> Option<String> {
+    let anchor = state.anchor?;
+    let start = anchor.min(state.cursor);
+    let end = anchor.max(state.cursor);
+    let lines = &state.lines[start.line..=end.line];
+    let text: Vec<String> = match state.mode {
+        SelectionMode::Line => lines.to_vec(),
+        SelectionMode::Char => lines
+            .iter()
+            .enumerate()
+            .map(|(index, line)| {
+                let chars: Vec<char> = line.chars().collect();
+                let from = if index == 0 { start.column } else { 0 };
+                let to = if index == lines.len() - 1 {
+                    end.column + 1
+                } else {
+                    chars.len()
+                };
+                chars[from.min(chars.len())..to.min(chars.len())]
+                    .iter()
+                    .collect()
+            })
+            .collect(),
+        SelectionMode::Block => {
+            let left = anchor.column.min(state.cursor.column);
+            let right = anchor.column.max(state.cursor.column) + 1;
+            lines
+                .iter()
+                .map(|line| {
+                    let chars: Vec<char> = line.chars().collect();
+                    chars[left.min(chars.len())..right.min(chars.len())]
+                        .iter()
+                        .collect::<String>()
+                        .trim_end()
+                        .to_string()
+                })
+                .collect()
+        }
+    };
+    Some(text.join("\n"))
+}
+
+fn view(state: &CopyModeState) -> CopyModeView {
+    let bottom = (state.top + state.rows).min(state.lines.len());
+    CopyModeView {
+        cursor: state.cursor,
+        anchor: state.anchor,
+        mode: state.anchor.map(|_| state.mode),
+        top: state.top,
+        lines: state.lines[state.top..bottom].to_vec(),
+        total_lines: state.lines.len(),
+    }
+}
+
+fn with_copy_mode<T>(
+    copy_mode_manager: &CopyModeManager,
+    session_id: &str,
+    update: impl FnOnce(&mut CopyModeState) -> Result<T, String>,
+) -> Result<T, String> {
+    let mut sessions = copy_mode_manager
+        .sessions
+        .lock()
+        .map_err(|e| e.to_string())?;
+    let state = sessions
+        .get_mut(session_id)
+        .ok_or_else(|| format!("Session '{}' is not in copy mode", session_id))?;
+    update(state)
+}
+
+// Freezes the session's scrollback for keyboard selection, with the cursor on the last line.
+// `rows` is the viewport height.
+#[command]
+pub fn enter_copy_mode(
+    session_id: String,
+    rows: Option<usize>,
+    copy_mode_manager: State<'_, CopyModeManager>,
+    scrollback_manager: State<'_, ScrollbackManager>,
+) -> Result<CopyModeView, String> {
+    let mut lines = scrollback_manager.plain_text_lines(&session_id)?;
+    if lines.is_empty() {
+        lines.push(String::new());
+    }
+    let rows = rows.unwrap_or(COPY_MODE_DEFAULT_ROWS).max(1);
+    let last_line = lines.len() - 1;
+    let state = CopyModeState {
+        top: lines.len().saturating_sub(rows),
+        lines,
+        cursor: position(last_line, 0),
+        anchor: None,
+        mode: SelectionMode::default(),
+        rows,
+        preferred_column: 0,
+    };
+    let view = view(&state);
+    copy_mode_manager
+        .sessions
+        .lock()
+        .map_err(|e| e.to_string())?
+        .insert(session_id, state);
+    Ok(view)
+}
+
+// Moves the cursor `count` times (default 1); the search motions look for `query`
+#[command]
+pub fn copy_mode_move(
+    session_id: String,
+    motion: CopyMotion,
+    count: Option<usize>,
+    query: Option<String>,
+    copy_mode_manager: State<'_, CopyModeManager>,
+) -> Result<CopyModeView, String> {
+    with_copy_mode(&copy_mode_manager, &session_id, |state| {
+        move_cursor(state, motion, count.unwrap_or(1).max(1), query.as_deref())?;
+        Ok(view(state))
+    })
+}
+
+// Starts a selection at the cursor, or switches the mode of the current one; None clears it
+#[command]
+pub fn copy_mode_select(
+    session_id: String,
+    mode: Option<SelectionMode>,
+    copy_mode_manager: State<'_, CopyModeManager>,
+) -> Result<CopyModeView, String> {
+    with_copy_mode(&copy_mode_manager, &session_id, |state| {
+        match mode {
+            Some(mode) => {
+                state.anchor.get_or_insert(state.cursor);
+                state.mode = mode;
+            }
+            None => state.anchor = None,
+        }
+        Ok(view(state))
+    })
+}
+
+// The selected text, for the clipboard. Leaves copy mode unless `exit` is false.
+#[command]
+pub fn copy_mode_yank(
+    session_id: String,
+    exit: Option<bool>,
+    copy_mode_manager: State<'_, CopyModeManager>,
+) -> Result<String, String> {
+    let text = with_copy_mode(&copy_mode_manager, &session_id, |state| {
+        selected_text(state).ok_or_else(|| "Nothing is selected".to_string())
+    })?;
+    if exit.unwrap_or(true) {
+        exit_copy_mode(session_id, copy_mode_manager)?;
+    }
+    Ok(text)
+}
+
+#[command]
+pub fn exit_copy_mode(
+    session_id: String,
+    copy_mode_manager: State<'_, CopyModeManager>,
+) -> Result<(), String> {
+    copy_mode_manager
+        .sessions
+        .lock()
+        .map_err(|e| e.to_string())?
+        .remove(&session_id);
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn copy_mode(lines: &[&str], rows: usize) -> CopyModeState {
+        CopyModeState {
+            lines: lines.iter().map(|line| line.to_string()).collect(),
+            cursor: position(0, 0),
+            anchor: None,
+            mode: SelectionMode::Char,
+            top: 0,
+            rows,
+            preferred_column: 0,
+        }
+    }
+
+    fn select(
+        state: &mut CopyModeState,
+        mode: SelectionMode,
+        anchor: CopyPosition,
+        cursor: CopyPosition,
+    ) -> Option<String> {
+        state.mode = mode;
+        state.anchor = Some(anchor);
+        state.cursor = cursor;
+        selected_text(state)
+    }
+
+    fn moved(state: &mut CopyModeState, motion: CopyMotion, count: usize) -> CopyPosition {
+        move_cursor(state, motion, count, None).unwrap();
+        state.cursor
+    }
+
+    #[test]
+    fn selections() {
+        let mut state = copy_mode(&["hello world", "second line", "third"], 10);
+        assert_eq!(selected_text(&state), None);
+        assert_eq!(
+            select(
+                &mut state,
+                SelectionMode::Char,
+                position(0, 6),
+                position(1, 6)
+            )
+            .unwrap(),
+            "world\nsecond "
+        );
+        // The anchor may come after the cursor
+        assert_eq!(
+            select(
+                &mut state,
+                SelectionMode::Char,
+                position(1, 2),
+                position(0, 1)
+            )
+            .unwrap(),
+            "ello world\nsec"
+        );
+        assert_eq!(
+            select(
+                &mut state,
+                SelectionMode::Line,
+                position(2, 3),
+                position(1, 0)
+            )
+            .unwrap(),
+            "second line\nthird"
+        );
+        assert_eq!(
+            select(
+                &mut state,
+                SelectionMode::Block,
+                position(0, 1),
+                position(2, 3)
+            )
+            .unwrap(),
+            "ell\neco\nhir"
+        );
+    }
+
+    #[test]
+    fn block_selection_over_short_lines() {
+        let mut state = copy_mode(&["abcdef", "ab", "abcd  x"], 10);
+        assert_eq!(
+            select(
+                &mut state,
+                SelectionMode::Block,
+                position(2, 5),
+                position(0, 3)
+            )
+            .unwrap(),
+            "def\n\nd"
+        );
+    }
+
+    #[test]
+    fn word_motions_cross_lines() {
+        let mut state = copy_mode(&["ls -la  /tmp", "", "  next"], 10);
+        assert_eq!(
+            moved(&mut state, CopyMotion::WordForward, 1),
+            position(0, 3)
+        );
+        assert_eq!(
+            moved(&mut state, CopyMotion::WordForward, 2),
+            position(1, 0)
+        );
+        assert_eq!(
+            moved(&mut state, CopyMotion::WordForward, 1),
+            position(2, 2)
+        );
+        assert_eq!(
+            moved(&mut state, CopyMotion::WordForward, 1),
+            position(2, 5)
+        );
+        assert_eq!(
+            moved(&mut state, CopyMotion::WordBackward, 1),
+            position(2, 2)
+        );
+        assert_eq!(
+            moved(&mut state, CopyMotion::WordBackward, 1),
+            position(1, 0)
+        );
+        assert_eq!(
+            moved(&mut state, CopyMotion::WordBackward, 2),
+            position(0, 3)
+        );
+    }
+
+    #[test]
+    fn vertical_moves_return_to_the_preferred_column() {
+        let mut state = copy_mode(&["long line here", "ab", "another long"], 10);
+        moved(&mut state, CopyMotion::Right, 10);
+        assert_eq!(moved(&mut state, CopyMotion::Down, 1), position(1, 1));
+        assert_eq!(moved(&mut state, CopyMotion::Down, 1), position(2, 10));
+        assert_eq!(moved(&mut state, CopyMotion::Down, 5), position(2, 10));
+        assert_eq!(moved(&mut state, CopyMotion::LineEnd, 1), position(2, 11));
+        assert_eq!(moved(&mut state, CopyMotion::Up, 2), position(0, 11));
+        assert_eq!(moved(&mut state, CopyMotion::Left, 20), position(0, 0));
+    }
+
+    #[test]
+    fn search_works_in_characters() {
+        let mut state = copy_mode(&["héllo x", "x é", "none"], 10);
+        let mut search =
+            |motion, count| move_cursor(&mut state, motion, count, Some("x")).map(|_| state.cursor);
+        assert_eq!(search(CopyMotion::SearchForward, 1), Ok(position(0, 6)));
+        assert_eq!(search(CopyMotion::SearchForward, 1), Ok(position(1, 0)));
+        assert_eq!(search(CopyMotion::SearchBackward, 1), Ok(position(0, 6)));
+        assert_eq!(
+            search(CopyMotion::SearchForward, 3),
+            Err("'x' not found".to_string())
+        );
+        assert!(move_cursor(&mut state, CopyMotion::SearchForward, 1, None).is_err());
+    }
+
+    #[test]
+    fn the_viewport_follows_the_cursor() {
+        let lines: Vec<String> = (0..10).map(|line| line.to_string()).collect();
+        let lines: Vec<&str> = lines.iter().map(String::as_str).collect();
+        let mut state = copy_mode(&lines, 3);
+        state.cursor = position(9, 0);
+        state.top = 7;
+        moved(&mut state, CopyMotion::Top, 1);
+        assert_eq!(state.top, 0);
+        assert_eq!(moved(&mut state, CopyMotion::PageDown, 1), position(3, 0));
+        assert_eq!(state.top, 1);
+        assert_eq!(view(&state).lines, vec!["1", "2", "3"]);
+        assert_eq!(moved(&mut state, CopyMotion::Bottom, 1), position(9, 0));
+        assert_eq!(state.top, 7);
+        assert_eq!(moved(&mut state, CopyMotion::HalfPageUp, 1), position(8, 0));
+    }
+}
@@ -0,0 +1,291 @@
+use crate::command::constants::{DIAGNOSTICS_SCAN_BYTES, MAX_DIAGNOSTICS};
+use crate::command::types::command_manager::CommandManager;
+use crate::command::types::scrollback_manager::ScrollbackManager;
+use crate::utils::ansi::strip_ansi;
+use regex::Regex;
+use serde::Serialize;
+use std::path::Path;
+use std::sync::OnceLock;
+use tauri::{command, State};
+
+#[derive(Serialize, Clone, Copy, PartialEq, Eq, Debug)]
+#[serde(rename_all = "lowercase")]
+pub enum Severity {
+    Error,
+    Warning,
+    Note,
+    Info,
+}
+
+#[derive(Serialize, Clone, Debug, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct Diagnostic {
+    pub severity: Severity,
+    pub message: String,
+    pub file: Option<String>,
+    pub line: Option<u32>,
+    pub column: Option<u32>,
+    // Error code or exception type, e.g. E0308, TS2322, ValueError
+    pub code: Option<String>,
+    // Name of the rule that produced it
+    pub rule: &'static str,
+}
+
+#[derive(Serialize, Clone, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct DiagnosticsReport {
+    pub command: Option<String>,
+    pub exit_code: Option<i32>,
+    pub diagnostics: Vec<Diagnostic>,
+}
+
+#[derive(Clone, Copy)]
+enum RuleKind {
+    // File, position and message on one line (gcc, tsc)
+    Complete,
+    // Message without a position; the next location line supplies it (rustc, JS/Java errors)
+    Header,
+    // Position only. `trailing_header` locations (Python frames) are kept until the exception
+    // line that follows them; others attach to a pending header and are ignored otherwise.
+    Location { trailing_header: bool },
+}
+
+struct Rule {
+    name: &'static str,
+    kind: RuleKind,
+    pattern: &'static str,
+    severity: Severity,
+}
+
+// Checked in order; the first matching rule claims the line
+const RULES: &[Rule] = &[
+    Rule {
+        name: "rustc",
+        kind: RuleKind::Header,
+        pattern: r"^(?P<severity>error|warning)(?:\[(?P<code>[A-Z]\d+)\])?: (?P<message>.+)$",
+        severity: Severity::Error,
+    },
+    Rule {
+        name: "rustc",
+        kind: RuleKind::Location {
+            trailing_header: false,
+        },
+        pattern: r"^\s*--> (?P<file>[^\s:]+):(?P<line>\d+):(?P<col>\d+)",
+        severity: Severity::Error,
+    },
+    Rule {
+        name: "tsc",
+        kind: RuleKind::Complete,
+        pattern: r"^(?P<file>[^\s(]+)\((?P<line>\d+),(?P<col>\d+)\): (?P<severity>error|warning) (?P<code>TS\d+): (?P<message>.+)$",
+        severity: Severity::Error,
+    },
+    Rule {
+        name: "python",
+        kind: RuleKind::Location {
+            trailing_header: true,
+        },
+        pattern: r#"^\s*File "(?P<file>[^"]+)", line (?P<line>\d+)"#,
+        severity: Severity::Error,
+    },
+    Rule {
+        name: "java",
+        kind: RuleKind::Header,
+        pattern: r#"^Exception in thread "[^"]*" (?P<code>[\w.$]+)(?:: (?P<message>.*))?$"#,
+        severity: Severity::Error,
+    },
+    Rule {
+        name: "java",
+        kind: RuleKind::Location {
+            trailing_header: false,
+        },
+        pattern: r"^\s+at [\w.$<>]+\((?P<file>[\w$]+\.(?:java|kt|scala)):(?P<line>\d+)\)$",
+        severity: Severity::Error,
+    },
+    Rule {
+        name: "exception",
+        kind: RuleKind::Header,
+        pattern: r"^(?:Uncaught )?(?P<code>[A-Za-z_][\w.]*(?:Error|Exception|Exit|Interrupt))(?:: (?P<message>.*))?$",
+        severity: Severity::Error,
+    },
+    Rule {
+        name: "javascript",
+        kind: RuleKind::Location {
+            trailing_header: false,
+        },
+        pattern: r"^\s+at (?:.+? \()?(?:file://)?(?P<file>[^\s()]+?):(?P<line>\d+):(?P<col>\d+)\)?$",
+        severity: Severity::Error,
+    },
+    Rule {
+        name: "gcc",
+        kind: RuleKind::Complete,
+        pattern: r"^(?P<file>[^\s:]+\.[A-Za-z0-9]+):(?P<line>\d+):(?:(?P<col>\d+):)? (?:(?P<severity>fatal error|error|warning|note):\s*)(?P<message>.+)$",
+        severity: Severity::Error,
+    },
+    Rule {
+        name: "reference",
+        kind: RuleKind::Complete,
+        pattern: r"(?P<file>[\w./-]+\.[A-Za-z][A-Za-z0-9]{0,4}):(?P<line>\d+)(?::(?P<col>\d+))?",
+        severity: Severity::Info,
+    },
+];
+
+fn compiled_rules() -> &'static Vec<(&'static Rule, Regex)> {
+    static COMPILED: OnceLock<Vec<(&'static Rule, Regex)>> = OnceLock::new();
+    COMPILED.get_or_init(|| {
+        RULES
+            .iter()
+            .map(|rule| {
+                (
+                    rule,
+                    Regex::new(rule.pattern).expect("valid diagnostic rule"),
+                )
+            })
+            .collect()
+    })
+}
+
+fn severity_from(text: Option<&str>, default: Severity) -> Severity {
+    match text {
+        Some("error") | Some("fatal error") => Severity::Error,
+        Some("warning") => Severity::Warning,
+        Some("note") => Severity::Note,
+        _ => default,
+    }
+}
+
+fn diagnostic_from(rule: &'static Rule, captures: &regex::Captures, message: String) -> Diagnostic {
+    let number = |name: &str| captures.name(name).and_then(|m| m.as_str().parse().ok());
+    Diagnostic {
+        severity: severity_from(captures.name("severity").map(|m| m.as_str()), rule.severity),
+        message,
+        file: captures.name("file").map(|m| m.as_str().to_string()),
+        line: number("line"),
+        column: number("col"),
+        code: captures.name("code").map(|m| m.as_str().to_string()),
+        rule: rule.name,
+    }
+}
+
+// Structured errors, warnings and file:line references found in command output
+pub fn extract_diagnostics(output: &str) -> Vec<Diagnostic> {
+    let rules = compiled_rules();
+    let mut diagnostics: Vec<Diagnostic> = Vec::new();
+    let mut pending_header: Option<Diagnostic> = None;
+    let mut pending_location: Option<Diagnostic> = None;
+
+    for line in strip_ansi(output).lines() {
+        let line = line.trim_end();
+        let Some((rule, captures)) = rules
+            .iter()
+            .find_map(|(rule, regex)| regex.captures(line).map(|captures| (*rule, captures)))
+        else {
+            continue;
+        };
+        let message = captures
+            .name("message")
+            .map(|m| m.as_str().trim().to_string())
+            // Bare file:line references keep the whole line as context
+            .unwrap_or_else(|| line.trim().to_string());
+
+        match rule.kind {
+            RuleKind::Complete => diagnostics.push(diagnostic_from(rule, &captures, message)),
+            RuleKind::Header => {
+                let mut header = diagnostic_from(rule, &captures, message);
+                if captures.name("message").is_none() {
+                    header.message = header.code.clone().unwrap_or_default();
+                }
+                // A traceback ends in its exception line: attach it to the innermost frame
+                if let Some(location) = pending_location.take() {
+                    diagnostics.push(Diagnostic {
+                        file: location.file,
+                        line: location.line,
+                        column: location.column,
+                        ..header
+                    });
+                } else if let Some(previous) = pending_header.replace(header) {
+                    diagnostics.push(previous);
+                }
+            }
+            RuleKind::Location { trailing_header } => {
+                let location = diagnostic_from(rule, &captures, message);
+                if let Some(header) = pending_header.take() {
+                    diagnostics.push(Diagnostic {
+                        file: location.file,
+                        line: location.line,
+                        column: location.column,
+                        ..header
+                    });
+                } else if trailing_header {
+                    pending_location = Some(location);
+                }
+            }
+        }
+        if diagnostics.len() >= MAX_DIAGNOSTICS {
+            break;
+        }
+    }
+    diagnostics.extend(pending_header);
+
+    let mut unique: Vec<Diagnostic> = Vec::new();
+    for diagnostic in diagnostics {
+        if !unique.contains(&diagnostic) {
+            unique.push(diagnostic);
+        }
+    }
+    unique.truncate(MAX_DIAGNOSTICS);
+    unique
+}
+
+// Makes relative paths absolute against the session's cwd when the file exists there, so the
+// UI can open it directly
+fn resolve_paths(diagnostics: &mut [Diagnostic], cwd: &str) {
+    for diagnostic in diagnostics {
+        if let Some(file) = &diagnostic.file {
+            let candidate = Path::new(cwd).join(file);
+            if Path::new(file).is_relative() && candidate.is_file() {
+                diagnostic.file = Some(candidate.to_string_lossy().to_string());
+            }
+        }
+    }
+}
+
+// Diagnostics from the most recent command in the session (or the recent PTY output when the
+// session has no piped runs)
+#[command]
+pub fn get_last_diagnostics(
+    session_id: String,
+    scrollback_manager: State<'_, ScrollbackManager>,
+    command_manager: State<'_, CommandManager>,
+) -> Result<DiagnosticsReport, String> {
+    let (command, exit_code, output) = match scrollback_manager.runs(&session_id)?.pop() {
+        Some(run) => (Some(run.command), run.exit_code, run.output),
+        None => {
+            let entries = scrollback_manager.snapshot(&session_id)?;
+            let mut output = String::new();
+            for entry in entries.iter().rev() {
+                if output.len() >= DIAGNOSTICS_SCAN_BYTES {
+                    break;
+                }
+                output.insert_str(0, &entry.data);
+            }
+            (None, None, output)
+        }
+    };
+
+    let mut diagnostics = extract_diagnostics(&output);
+    let cwd = command_manager
+        .commands
+        .lock()
+        .map_err(|e| e.to_string())?
+        .get(&session_id)
+        .map(|state| state.current_dir.clone());
+    if let Some(cwd) = cwd {
+        resolve_paths(&mut diagnostics, &cwd);
+    }
+
+    Ok(DiagnosticsReport {
+        command,
+        exit_code,
+        diagnostics,
+    })
+}
@@ -0,0 +1,11 @@
+pub mod ai_selection;
+pub mod bell;
+pub mod copy_mode;
+pub mod diagnostics;
+pub mod output_diff;
+pub mod output_marks;
+pub mod plain_text;
+pub mod post_processors;
+pub mod progress;
+pub mod screen_state;
+pub mod structured_output;
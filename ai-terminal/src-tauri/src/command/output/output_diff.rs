@@ -0,0 +1,80 @@
+use crate::command::types::scrollback_manager::{CommandRun, ScrollbackManager};
+use crate::utils::ansi::strip_ansi;
+use serde::Serialize;
+use similar::{ChangeTag, TextDiff};
+use tauri::{command, State};
+
+#[derive(Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct DiffLine {
+    // 1-based line number in the run the line belongs to
+    pub line_number: usize,
+    pub text: String,
+}
+
+#[derive(Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct OutputDiff {
+    pub command: String,
+    pub previous_run_at: u64,
+    pub latest_run_at: u64,
+    pub added: Vec<DiffLine>,
+    pub removed: Vec<DiffLine>,
+    pub unchanged_count: usize,
+}
+
+pub fn diff_runs(previous: &CommandRun, latest: &CommandRun) -> OutputDiff {
+    let old_text = strip_ansi(&previous.output).replace("\r\n", "\n");
+    let new_text = strip_ansi(&latest.output).replace("\r\n", "\n");
+    let diff = TextDiff::from_lines(&old_text, &new_text);
+
+    let mut added = Vec::new();
+    let mut removed = Vec::new();
+    let mut unchanged_count = 0;
+    for change in diff.iter_all_changes() {
+        let text = change.value().trim_end_matches('\n').to_string();
+        match change.tag() {
+            ChangeTag::Insert => added.push(DiffLine {
+                line_number: change.new_index().map(|i| i + 1).unwrap_or(0),
+                text,
+            }),
+            ChangeTag::Delete => removed.push(DiffLine {
+                line_number: change.old_index().map(|i| i + 1).unwrap_or(0),
+                text,
+            }),
+            ChangeTag::Equal => unchanged_count += 1,
+        }
+    }
+
+    OutputDiff {
+        command: latest.command.clone(),
+        previous_run_at: previous.started_at,
+        latest_run_at: latest.started_at,
+        added,
+        removed,
+        unchanged_count,
+    }
+}
+
+// Diffs the outputs of the last two completed runs of `command` in the session
+#[command]
+pub fn diff_last_outputs(
+    session_id: String,
+    command: String,
+    scrollback_manager: State<'_, ScrollbackManager>,
+) -> Result<OutputDiff, String> {
+    let command = command.trim();
+    let runs = scrollback_manager.runs(&session_id)?;
+    let matching: Vec<&CommandRun> = runs
+        .iter()
+        .filter(|run| run.finished && run.command == command)
+        .collect();
+
+    match matching.as_slice() {
+        [.., previous, latest] => Ok(diff_runs(previous, latest)),
+        _ => Err(format!(
+            "Need at least two completed runs of '{}' to compare",
+            command
+        )),
+    }
+}
@@ -0,0 +1,80 @@
+use crate::command::types::scrollback_manager::{OutputMark, ScrollbackEntry, ScrollbackManager};
+use serde::Serialize;
+use tauri::{command, State};
+
+#[derive(Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct MarkJump {
+    pub mark: OutputMark,
+    pub previous_mark_id: Option<String>,
+    pub next_mark_id: Option<String>,
+    // Output from the mark up to the next mark (or the end of the session)
+    pub entries: Vec<ScrollbackEntry>,
+}
+
+#[command]
+pub fn add_output_mark(
+    session_id: String,
+    label: Option<String>,
+    scrollback_manager: State<'_, ScrollbackManager>,
+) -> Result<OutputMark, String> {
+    let label = label
+        .map(|l| l.trim().to_string())
+        .filter(|l| !l.is_empty())
+        .unwrap_or_else(|| "Mark".to_string());
+    scrollback_manager.add_mark(&session_id, &label)
+}
+
+#[command]
+pub fn remove_output_mark(
+    session_id: String,
+    mark_id: String,
+    scrollback_manager: State<'_, ScrollbackManager>,
+) -> Result<bool, String> {
+    scrollback_manager.remove_mark(&session_id, &mark_id)
+}
+
+// Command boundaries and user marks, ordered by position in the session output
+fn sorted_marks(
+    scrollback_manager: &ScrollbackManager,
+    session_id: &str,
+) -> Result<Vec<OutputMark>, String> {
+    let mut marks = scrollback_manager.marks(session_id)?;
+    marks.sort_by_key(|mark| (mark.position, mark.timestamp));
+    Ok(marks)
+}
+
+#[command]
+pub fn list_marks(
+    session_id: String,
+    scrollback_manager: State<'_, ScrollbackManager>,
+) -> Result<Vec<OutputMark>, String> {
+    sorted_marks(&scrollback_manager, &session_id)
+}
+
+#[command]
+pub fn jump_to_mark(
+    session_id: String,
+    mark_id: String,
+    scrollback_manager: State<'_, ScrollbackManager>,
+) -> Result<MarkJump, String> {
+    let marks = sorted_marks(&scrollback_manager, &session_id)?;
+    let index = marks
+        .iter()
+        .position(|mark| mark.id == mark_id)
+        .ok_or_else(|| format!("Mark '{}' not found in session '{}'", mark_id, session_id))?;
+
+    let mark = marks[index].clone();
+    let next = marks[index + 1..]
+        .iter()
+        .find(|other| other.position > mark.position);
+    let entries =
+        scrollback_manager.entries_between(&session_id, mark.position, next.map(|m| m.position))?;
+
+    Ok(MarkJump {
+        previous_mark_id: index.checked_sub(1).map(|i| marks[i].id.clone()),
+        next_mark_id: marks.get(index + 1).map(|m| m.id.clone()),
+        mark,
+        entries,
+    })
+}
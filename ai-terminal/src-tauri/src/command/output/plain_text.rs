@@ -0,0 +1,41 @@
+use crate::command::types::scrollback_manager::ScrollbackManager;
+use crate::utils::ansi::render_plain_text;
+use tauri::{command, State};
+
+// Removes control sequences and resolves cursor movement (\r progress bars, backspaces, erases)
+#[command]
+pub fn strip_ansi(text: String) -> Result<String, String> {
+    Ok(render_plain_text(&text))
+}
+
+// Enables a parallel plain-text copy of the session output for screen readers, search and AI context
+#[command]
+pub fn set_plain_text_retention(
+    session_id: String,
+    enabled: bool,
+    scrollback_manager: State<'_, ScrollbackManager>,
+) -> Result<(), String> {
+    scrollback_manager.set_plain_text_retention(&session_id, enabled)
+}
+
+#[command]
+pub fn get_plain_text_output(
+    session_id: String,
+    max_lines: Option<usize>,
+    scrollback_manager: State<'_, ScrollbackManager>,
+) -> Result<String, String> {
+    let text = scrollback_manager.plain_text(&session_id)?.ok_or_else(|| {
+        format!(
+            "Plain-text retention is not enabled for session '{}'",
+            session_id
+        )
+    })?;
+
+    Ok(match max_lines {
+        Some(limit) => {
+            let lines: Vec<&str> = text.lines().collect();
+            lines[lines.len().saturating_sub(limit)..].join("\n")
+        }
+        None => text,
+    })
+}
@@ -0,0 +1,294 @@
+use crate::command::output::diagnostics::{extract_diagnostics, Diagnostic};
+use crate::command::types::output_processor_manager::OutputProcessorManager;
+use crate::command::types::scrollback_manager::OutputStream;
+use crate::command::windows::session_windows::emit_session_event;
+use crate::utils::ansi::normalize_ansi;
+use regex::Regex;
+use serde::Serialize;
+use std::sync::OnceLock;
+use tauri::{command, AppHandle, Manager, Runtime, State};
+
+// Something a processor found in the output, reported alongside it in output_annotations
+#[derive(Serialize, Clone, Debug)]
+#[serde(tag = "kind", rename_all = "camelCase")]
+pub enum OutputAnnotation {
+    Redaction { rule: &'static str, count: usize },
+    Url { url: String },
+    Diagnostic { diagnostic: Diagnostic },
+}
+
+pub struct ProcessedOutput {
+    pub text: String,
+    pub annotations: Vec<OutputAnnotation>,
+}
+
+struct OutputProcessor {
+    name: &'static str,
+    description: &'static str,
+    enabled_by_default: bool,
+    // PTY output feeds a terminal emulator, which needs every control sequence intact
+    applies_to_pty: bool,
+    process: fn(&mut ProcessedOutput),
+}
+
+// Run in order on each chunk before it is stored and emitted: normalization first so escape
+// codes cannot split a secret, redaction before anything that reports text back
+const PROCESSORS: &[OutputProcessor] = &[
+    OutputProcessor {
+        name: "ansi_normalization",
+        description: "Drop cursor movement, erases and title changes; keep colors",
+        enabled_by_default: true,
+        applies_to_pty: false,
+        process: normalize,
+    },
+    OutputProcessor {
+        name: "secret_redaction",
+        description: "Replace API keys, tokens and passwords with [REDACTED]",
+        enabled_by_default: true,
+        applies_to_pty: true,
+        process: redact_secrets,
+    },
+    OutputProcessor {
+        name: "url_extraction",
+        description: "Report http(s) links found in the output",
+        enabled_by_default: true,
+        applies_to_pty: true,
+        process: extract_urls,
+    },
+    // Off by default: get_last_diagnostics already parses whole runs on demand
+    OutputProcessor {
+        name: "diagnostics",
+        description: "Report compiler errors and stack trace locations as they stream",
+        enabled_by_default: false,
+        applies_to_pty: true,
+        process: stream_diagnostics,
+    },
+];
+
+// (rule, pattern, replacement); `key` groups are kept so the output still says what was hidden
+const SECRET_RULES: &[(&str, &str, &str)] = &[
+    (
+        "private_key",
+        r"-----BEGIN [A-Z ]*PRIVATE KEY-----[\s\S]*?(?:-----END [A-Z ]*PRIVATE KEY-----|$)",
+        "[REDACTED PRIVATE KEY]",
+    ),
+    (
+        "aws_access_key",
+        r"\b(?:AKIA|ASIA)[0-9A-Z]{16}\b",
+        "[REDACTED]",
+    ),
+    (
+        "github_token",
+        r"\bgh[pousr]_[A-Za-z0-9]{36,}\b",
+        "[REDACTED]",
+    ),
+    (
+        "github_token",
+        r"\bgithub_pat_[A-Za-z0-9_]{22,}\b",
+        "[REDACTED]",
+    ),
+    (
+        "slack_token",
+        r"\bxox[abprs]-[A-Za-z0-9-]{10,}\b",
+        "[REDACTED]",
+    ),
+    ("openai_key", r"\bsk-[A-Za-z0-9_-]{20,}\b", "[REDACTED]"),
+    (
+        "bearer_token",
+        r"(?P<key>\b[Bb]earer\s+)[A-Za-z0-9._~+/-]{16,}=*",
+        "${key}[REDACTED]",
+    ),
+    (
+        "assignment",
+        r#"(?i)(?P<key>\b[\w.-]*(?:password|passwd|secret|token|api[_-]?key)\s*[=:]\s*)["']?[^\s"']{4,}["']?"#,
+        "${key}[REDACTED]",
+    ),
+];
+
+fn secret_rules() -> &'static Vec<(&'static str, Regex, &'static str)> {
+    static COMPILED: OnceLock<Vec<(&'static str, Regex, &'static str)>> = OnceLock::new();
+    COMPILED.get_or_init(|| {
+        SECRET_RULES
+            .iter()
+            .map(|(rule, pattern, replacement)| {
+                (
+                    *rule,
+                    Regex::new(pattern).expect("valid secret rule"),
+                    *replacement,
+                )
+            })
+            .collect()
+    })
+}
+
+// Secret redaction for text that is exported rather than streamed, e.g. repro bundles
+pub fn redact_text(text: &str) -> String {
+    redact_text_counted(text).0
+}
+
+// Redacted text and the number of secrets replaced
+pub fn redact_text_counted(text: &str) -> (String, usize) {
+    secret_rules().iter().fold(
+        (text.to_string(), 0),
+        |(text, count), (_, regex, replacement)| {
+            let found = regex.find_iter(&text).count();
+            if found == 0 {
+                return (text, count);
+            }
+            (
+                regex.replace_all(&text, *replacement).to_string(),
+                count + found,
+            )
+        },
+    )
+}
+
+fn url_pattern() -> &'static Regex {
+    static PATTERN: OnceLock<Regex> = OnceLock::new();
+    PATTERN.get_or_init(|| Regex::new(r#"https?://[^\s"'<>`\x1b]+"#).expect("valid url pattern"))
+}
+
+fn normalize(output: &mut ProcessedOutput) {
+    output.text = normalize_ansi(&output.text);
+}
+
+fn redact_secrets(output: &mut ProcessedOutput) {
+    for (rule, regex, replacement) in secret_rules() {
+        let count = regex.find_iter(&output.text).count();
+        if count == 0 {
+            continue;
+        }
+        output.text = regex.replace_all(&output.text, *replacement).to_string();
+        output
+            .annotations
+            .push(OutputAnnotation::Redaction { rule, count });
+    }
+}
+
+fn extract_urls(output: &mut ProcessedOutput) {
+    for found in url_pattern().find_iter(&output.text) {
+        let url = found
+            .as_str()
+            .trim_end_matches(['.', ',', ';', ':', ')', ']', '}', '!', '?']);
+        output.annotations.push(OutputAnnotation::Url {
+            url: url.to_string(),
+        });
+    }
+}
+
+// Per chunk, so only diagnostics that fit in one read are seen; bare file:line references
+// are left to get_last_diagnostics
+fn stream_diagnostics(output: &mut ProcessedOutput) {
+    let diagnostics = extract_diagnostics(&output.text)
+        .into_iter()
+        .filter(|diagnostic| diagnostic.rule != "reference")
+        .map(|diagnostic| OutputAnnotation::Diagnostic { diagnostic });
+    output.annotations.extend(diagnostics);
+}
+
+#[derive(Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+struct OutputAnnotationsEvent {
+    session_id: String,
+    stream: OutputStream,
+    annotations: Vec<OutputAnnotation>,
+}
+
+#[derive(Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct OutputProcessorInfo {
+    pub name: &'static str,
+    pub description: &'static str,
+    pub enabled: bool,
+    pub applies_to_pty: bool,
+}
+
+fn is_enabled(
+    manager: &OutputProcessorManager,
+    session_id: &str,
+    processor: &OutputProcessor,
+) -> bool {
+    manager
+        .overrides
+        .lock()
+        .ok()
+        .and_then(|overrides| {
+            overrides
+                .get(session_id)
+                .and_then(|session| session.get(processor.name).copied())
+        })
+        .unwrap_or(processor.enabled_by_default)
+}
+
+// Runs the session's enabled processors over a chunk of output. Callers store and emit the
+// returned text; annotations go out as one output_annotations event per chunk.
+pub fn process_output<R: Runtime>(
+    app_handle: &AppHandle<R>,
+    session_id: &str,
+    stream: OutputStream,
+    text: String,
+) -> String {
+    let manager = app_handle.state::<OutputProcessorManager>();
+    let mut output = ProcessedOutput {
+        text,
+        annotations: Vec::new(),
+    };
+    for processor in PROCESSORS {
+        if (stream == OutputStream::Pty && !processor.applies_to_pty)
+            || !is_enabled(&manager, session_id, processor)
+        {
+            continue;
+        }
+        (processor.process)(&mut output);
+    }
+
+    if !output.annotations.is_empty() {
+        emit_session_event(
+            app_handle,
+            session_id,
+            "output_annotations",
+            OutputAnnotationsEvent {
+                session_id: session_id.to_string(),
+                stream,
+                annotations: output.annotations,
+            },
+        );
+    }
+    output.text
+}
+
+#[command]
+pub fn list_output_processors(
+    session_id: String,
+    output_processor_manager: State<'_, OutputProcessorManager>,
+) -> Result<Vec<OutputProcessorInfo>, String> {
+    Ok(PROCESSORS
+        .iter()
+        .map(|processor| OutputProcessorInfo {
+            name: processor.name,
+            description: processor.description,
+            enabled: is_enabled(&output_processor_manager, &session_id, processor),
+            applies_to_pty: processor.applies_to_pty,
+        })
+        .collect())
+}
+
+#[command]
+pub fn set_output_processor_enabled(
+    session_id: String,
+    name: String,
+    enabled: bool,
+    output_processor_manager: State<'_, OutputProcessorManager>,
+) -> Result<(), String> {
+    if !PROCESSORS.iter().any(|processor| processor.name == name) {
+        return Err(format!("Unknown output processor '{}'", name));
+    }
+    output_processor_manager
+        .overrides
+        .lock()
+        .map_err(|e| e.to_string())?
+        .entry(session_id)
+        .or_default()
+        .insert(name, enabled);
+    Ok(())
+}
@@ -0,0 +1,207 @@
+use crate::command::constants::{PROGRESS_MIN_INTERVAL_MS, PROGRESS_MIN_STEP_PERCENT};
+use crate::command::types::progress_tracker::{ProgressState, ProgressTracker};
+use crate::command::windows::session_windows::emit_session_event;
+use crate::utils::ansi::render_plain_text;
+use regex::Regex;
+use serde::Serialize;
+use std::sync::OnceLock;
+use std::time::{Duration, Instant};
+use tauri::{AppHandle, Manager, Runtime};
+
+// Longest partial line kept between reads; anything longer is not a progress bar
+const MAX_PARTIAL_LINE: usize = 1024;
+
+#[derive(Serialize, Clone, Debug, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct ProgressEvent {
+    pub session_id: String,
+    pub percent: Option<f64>,
+    pub current: Option<f64>,
+    pub total: Option<f64>,
+    pub unit: Option<String>,
+    // The line the progress was read from, trimmed
+    pub label: Option<String>,
+    // false once the command has finished
+    pub active: bool,
+}
+
+#[derive(Debug, PartialEq)]
+pub struct ParsedProgress {
+    pub percent: f64,
+    pub current: Option<f64>,
+    pub total: Option<f64>,
+    pub unit: Option<String>,
+}
+
+struct ProgressPatterns {
+    // docker `12.5MB/45.2MB`, pip `12.3/45.6 MB`
+    sizes: Regex,
+    // cargo `[=====>   ] 120/250`, `(3/10)`, `Step 3/10`
+    fraction: Regex,
+    // `45%`, `[ 45%]`, rsync `45%  1.23MB/s`
+    percent: Regex,
+}
+
+fn patterns() -> &'static ProgressPatterns {
+    static PATTERNS: OnceLock<ProgressPatterns> = OnceLock::new();
+    PATTERNS.get_or_init(|| ProgressPatterns {
+        sizes: Regex::new(
+            r"(\d+(?:\.\d+)?)\s*([kKMGT]?i?B)?\s*/\s*(\d+(?:\.\d+)?)\s*([kKMGT]?i?B)\b",
+        )
+        .expect("valid sizes regex"),
+        fraction: Regex::new(r"(?:^|[\s(\[])(\d+)\s*/\s*(\d+)(?:$|[\s):\]])")
+            .expect("valid fraction regex"),
+        percent: Regex::new(r"(\d{1,3}(?:\.\d+)?)\s?%").expect("valid percent regex"),
+    })
+}
+
+fn unit_factor(unit: &str) -> f64 {
+    let binary = unit.contains('i');
+    let base: f64 = if binary { 1024.0 } else { 1000.0 };
+    match unit.chars().next() {
+        Some('k') | Some('K') => base,
+        Some('M') => base.powi(2),
+        Some('G') => base.powi(3),
+        Some('T') => base.powi(4),
+        _ => 1.0,
+    }
+}
+
+// Progress shown on a single line of output, if it looks like there is any
+pub fn parse_progress(line: &str) -> Option<ParsedProgress> {
+    let patterns = patterns();
+
+    // An explicit percentage is the most reliable signal
+    if let Some(captures) = patterns.percent.captures_iter(line).last() {
+        let percent: f64 = captures[1].parse().ok()?;
+        if percent <= 100.0 {
+            return Some(ParsedProgress {
+                percent,
+                current: None,
+                total: None,
+                unit: None,
+            });
+        }
+    }
+
+    if let Some(captures) = patterns.sizes.captures_iter(line).last() {
+        let total_unit = captures.get(4).map(|unit| unit.as_str()).unwrap_or("B");
+        // pip leaves the unit off the first number
+        let current_unit = captures
+            .get(2)
+            .map(|unit| unit.as_str())
+            .unwrap_or(total_unit);
+        let current: f64 = captures[1].parse().ok()?;
+        let total: f64 = captures[3].parse().ok()?;
+        let current_bytes = current * unit_factor(current_unit);
+        let total_bytes = total * unit_factor(total_unit);
+        if total_bytes > 0.0 && current_bytes <= total_bytes {
+            return Some(ParsedProgress {
+                percent: current_bytes / total_bytes * 100.0,
+                current: Some(current),
+                total: Some(total),
+                unit: Some(total_unit.to_string()),
+            });
+        }
+    }
+
+    if let Some(captures) = patterns.fraction.captures_iter(line).last() {
+        let current: f64 = captures[1].parse().ok()?;
+        let total: f64 = captures[2].parse().ok()?;
+        if total >= 2.0 && current <= total {
+            return Some(ParsedProgress {
+                percent: current / total * 100.0,
+                current: Some(current),
+                total: Some(total),
+                unit: None,
+            });
+        }
+    }
+    None
+}
+
+// Feeds streamed output for a session and emits command_progress when a progress indicator
+// is found. Progress bars redraw with \r, so only the newest segment of each line counts.
+pub fn track_progress<R: Runtime>(app_handle: &AppHandle<R>, session_id: &str, data: &str) {
+    let tracker = app_handle.state::<ProgressTracker>();
+    let Ok(mut sessions) = tracker.sessions.lock() else {
+        return;
+    };
+    let state = sessions
+        .entry(session_id.to_string())
+        .or_insert_with(|| ProgressState {
+            percent: -1.0,
+            emitted_at: Instant::now() - Duration::from_millis(PROGRESS_MIN_INTERVAL_MS),
+            partial_line: String::new(),
+        });
+
+    state.partial_line.push_str(data);
+    let text = std::mem::take(&mut state.partial_line);
+    let mut segments: Vec<&str> = text.split(['\n', '\r']).collect();
+    let unfinished = segments.pop().unwrap_or("");
+    if unfinished.len() <= MAX_PARTIAL_LINE {
+        state.partial_line = unfinished.to_string();
+    }
+
+    // The unfinished segment is the bar currently on screen; otherwise the newest full line
+    let latest = std::iter::once(unfinished)
+        .chain(segments.into_iter().rev())
+        .map(|segment| render_plain_text(segment).trim().to_string())
+        .filter(|segment| !segment.is_empty())
+        .find_map(|segment| parse_progress(&segment).map(|progress| (segment, progress)));
+    let Some((label, progress)) = latest else {
+        return;
+    };
+
+    let moved = (progress.percent - state.percent).abs() >= PROGRESS_MIN_STEP_PERCENT;
+    let due = state.emitted_at.elapsed() >= Duration::from_millis(PROGRESS_MIN_INTERVAL_MS);
+    let finished = progress.percent >= 100.0 && state.percent < 100.0;
+    if !(finished || (moved && due)) {
+        return;
+    }
+    state.percent = progress.percent;
+    state.emitted_at = Instant::now();
+    drop(sessions);
+
+    emit_session_event(
+        app_handle,
+        session_id,
+        "command_progress",
+        ProgressEvent {
+            session_id: session_id.to_string(),
+            percent: Some((progress.percent * 10.0).round() / 10.0),
+            current: progress.current,
+            total: progress.total,
+            unit: progress.unit,
+            label: Some(label.chars().take(120).collect()),
+            active: true,
+        },
+    );
+}
+
+// Clears the indicator when a command ends
+pub fn finish_progress<R: Runtime>(app_handle: &AppHandle<R>, session_id: &str) {
+    let had_progress = app_handle
+        .state::<ProgressTracker>()
+        .sessions
+        .lock()
+        .ok()
+        .and_then(|mut sessions| sessions.remove(session_id))
+        .is_some_and(|state| state.percent >= 0.0);
+    if had_progress {
+        emit_session_event(
+            app_handle,
+            session_id,
+            "command_progress",
+            ProgressEvent {
+                session_id: session_id.to_string(),
+                percent: None,
+                current: None,
+                total: None,
+                unit: None,
+                label: None,
+                active: false,
+            },
+        );
+    }
+}
@@ -0,0 +1,110 @@
+use crate::command::types::screen_model_manager::ScreenModelManager;
+use crate::command::windows::session_windows::emit_session_event;
+use crate::settings::types::settings_manager::SettingsManager;
+use crate::utils::screen_model::{ScreenModel, ScreenState};
+use serde::Serialize;
+use tauri::{command, AppHandle, Manager, Runtime, State};
+
+#[derive(Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct AltScreenEvent {
+    pub session_id: String,
+    pub active: bool,
+}
+
+// Starts tracking a new PTY, or a reconnected one, from a blank screen
+pub fn reset_screen<R: Runtime>(app_handle: &AppHandle<R>, session_id: &str, rows: u16, cols: u16) {
+    if let Ok(mut sessions) = app_handle.state::<ScreenModelManager>().sessions.lock() {
+        sessions.insert(
+            session_id.to_string(),
+            ScreenModel::new(rows as usize, cols as usize),
+        );
+    }
+}
+
+pub fn resize_screen<R: Runtime>(
+    app_handle: &AppHandle<R>,
+    session_id: &str,
+    rows: u16,
+    cols: u16,
+) {
+    if let Ok(mut sessions) = app_handle.state::<ScreenModelManager>().sessions.lock() {
+        if let Some(model) = sessions.get_mut(session_id) {
+            model.resize(rows as usize, cols as usize);
+        }
+    }
+}
+
+// Feeds PTY output to the session's screen model and returns what the scrollback should keep:
+// everything with altScreenScrollback on, otherwise only main-screen output, so the redraws
+// of vim or htop stay out of the scrollback, copy mode and exported transcripts. Emits
+// alt_screen_changed when a full-screen app starts or exits.
+pub fn track_screen<R: Runtime>(app_handle: &AppHandle<R>, session_id: &str, data: &str) -> String {
+    let (main_output, changed) = {
+        let manager = app_handle.state::<ScreenModelManager>();
+        let Ok(mut sessions) = manager.sessions.lock() else {
+            return data.to_string();
+        };
+        let Some(model) = sessions.get_mut(session_id) else {
+            return data.to_string();
+        };
+        let was_alt = model.alt_screen();
+        let main_output = model.feed(data);
+        let changed = (was_alt != model.alt_screen()).then_some(model.alt_screen());
+        (main_output, changed)
+    };
+    if let Some(active) = changed {
+        emit_session_event(
+            app_handle,
+            session_id,
+            "alt_screen_changed",
+            AltScreenEvent {
+                session_id: session_id.to_string(),
+                active,
+            },
+        );
+    }
+    if app_handle
+        .state::<SettingsManager>()
+        .current()
+        .alt_screen_scrollback
+    {
+        data.to_string()
+    } else {
+        main_output
+    }
+}
+
+// Whether a full-screen app (vim, less, top) has the session's alternate screen
+pub fn is_alt_screen<R: Runtime>(app_handle: &AppHandle<R>, session_id: &str) -> bool {
+    app_handle
+        .state::<ScreenModelManager>()
+        .sessions
+        .lock()
+        .ok()
+        .and_then(|sessions| sessions.get(session_id).map(|model| model.alt_screen()))
+        .unwrap_or(false)
+}
+
+pub fn forget_screen_session<R: Runtime>(app_handle: &AppHandle<R>, session_id: &str) {
+    if let Ok(mut sessions) = app_handle.state::<ScreenModelManager>().sessions.lock() {
+        sessions.remove(session_id);
+    }
+}
+
+// Cursor, scroll region and modes of a PTY session, e.g. to tell whether a full-screen app
+// is running
+#[command]
+pub fn get_screen_state(
+    session_id: String,
+    screen_model_manager: State<'_, ScreenModelManager>,
+) -> Result<ScreenState, String> {
+    let sessions = screen_model_manager
+        .sessions
+        .lock()
+        .map_err(|e| e.to_string())?;
+    sessions
+        .get(&session_id)
+        .map(ScreenModel::state)
+        .ok_or_else(|| format!("PTY session '{}' not found", session_id))
+}
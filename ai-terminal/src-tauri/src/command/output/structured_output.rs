@@ -0,0 +1,208 @@
+use crate::command::constants::MAX_STRUCTURED_OUTPUT_BYTES;
+use crate::command::types::scrollback_manager::{CommandRun, ScrollbackManager};
+use crate::command::windows::session_windows::emit_session_event;
+use crate::utils::ansi::render_plain_text;
+use crate::utils::json_query::JsonQuery;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use tauri::{command, AppHandle, Manager, Runtime, State};
+
+pub const STRUCTURED_OUTPUT_EVENT: &str = "structured_output";
+
+#[derive(Serialize, Clone, Copy, Debug, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum StructuredFormat {
+    Json,
+    // One JSON value per line, e.g. `docker inspect --format '{{json .}}'` or log streams
+    JsonLines,
+    Yaml,
+}
+
+#[derive(Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+struct StructuredOutputEvent {
+    session_id: String,
+    command: String,
+    format: StructuredFormat,
+}
+
+#[derive(Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct FormattedOutput {
+    pub command: String,
+    pub format: StructuredFormat,
+    pub query: Option<String>,
+    // Pretty-printed results in the format the command produced
+    pub text: String,
+    pub results: Vec<Value>,
+}
+
+// `curl -i` puts the status line and headers (and sometimes a 100 Continue block) before the body
+fn skip_http_headers(text: &str) -> &str {
+    let mut body = text;
+    while body.starts_with("HTTP/") {
+        match body
+            .find("\r\n\r\n")
+            .map(|at| at + 4)
+            .or_else(|| body.find("\n\n").map(|at| at + 2))
+        {
+            Some(end) => body = &body[end..],
+            None => return "",
+        }
+    }
+    body
+}
+
+fn parse_json_lines(text: &str) -> Option<Vec<Value>> {
+    let values = text
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty())
+        .map(|line| {
+            serde_json::from_str::<Value>(line)
+                .ok()
+                .filter(|value| value.is_object() || value.is_array())
+        })
+        .collect::<Option<Vec<Value>>>()?;
+    (values.len() > 1).then_some(values)
+}
+
+// YAML accepts almost any text as a scalar, so only mappings and sequences of at least two
+// lines count, and every line has to look like YAML
+fn parse_yaml(text: &str) -> Option<Vec<Value>> {
+    let lines: Vec<&str> = text
+        .lines()
+        .filter(|line| !line.trim().is_empty() && !line.trim_start().starts_with('#'))
+        .collect();
+    if lines.len() < 2 {
+        return None;
+    }
+    let looks_like_yaml = lines.iter().all(|line| {
+        line.starts_with(char::is_whitespace)
+            || line.starts_with("- ")
+            || line.starts_with("---")
+            || line.contains(": ")
+            || line.trim_end().ends_with(':')
+    });
+    if !looks_like_yaml {
+        return None;
+    }
+    let documents = serde_yaml::Deserializer::from_str(text)
+        .map(|document| Value::deserialize(document).ok())
+        .collect::<Option<Vec<Value>>>()?;
+    let documents: Vec<Value> = documents
+        .into_iter()
+        .filter(|document| !document.is_null())
+        .collect();
+    (!documents.is_empty()
+        && documents
+            .iter()
+            .all(|document| document.is_object() || document.is_array()))
+    .then_some(documents)
+}
+
+// The JSON or YAML documents in a command's output, if that is what it printed
+pub fn detect_structured_output(output: &str) -> Option<(StructuredFormat, Vec<Value>)> {
+    if output.len() > MAX_STRUCTURED_OUTPUT_BYTES {
+        return None;
+    }
+    let plain = render_plain_text(output);
+    let body = skip_http_headers(plain.trim_start()).trim();
+    if body.is_empty() {
+        return None;
+    }
+    if body.starts_with('{') || body.starts_with('[') {
+        if let Ok(value) = serde_json::from_str::<Value>(body) {
+            return Some((StructuredFormat::Json, vec![value]));
+        }
+        return parse_json_lines(body).map(|values| (StructuredFormat::JsonLines, values));
+    }
+    parse_yaml(body).map(|documents| (StructuredFormat::Yaml, documents))
+}
+
+fn render(format: StructuredFormat, results: &[Value]) -> Result<String, String> {
+    match format {
+        StructuredFormat::Json | StructuredFormat::JsonLines => results
+            .iter()
+            .map(|value| serde_json::to_string_pretty(value).map_err(|e| e.to_string()))
+            .collect::<Result<Vec<String>, String>>()
+            .map(|parts| parts.join("\n")),
+        StructuredFormat::Yaml => results
+            .iter()
+            .map(|value| serde_yaml::to_string(value).map_err(|e| e.to_string()))
+            .collect::<Result<Vec<String>, String>>()
+            .map(|parts| parts.join("---\n")),
+    }
+}
+
+fn last_output_run(runs: Vec<CommandRun>) -> Option<CommandRun> {
+    runs.into_iter()
+        .rev()
+        .find(|run| !run.output.trim().is_empty())
+}
+
+// Tells the frontend the command that just finished printed JSON or YAML, so it can offer
+// format_last_output
+pub fn announce_structured_output<R: Runtime>(app_handle: &AppHandle<R>, session_id: &str) {
+    let Some(run) = app_handle
+        .state::<ScrollbackManager>()
+        .runs(session_id)
+        .ok()
+        .and_then(|runs| runs.into_iter().last())
+    else {
+        return;
+    };
+    if let Some((format, _)) = detect_structured_output(&run.output) {
+        emit_session_event(
+            app_handle,
+            session_id,
+            STRUCTURED_OUTPUT_EVENT,
+            StructuredOutputEvent {
+                session_id: session_id.to_string(),
+                command: run.command,
+                format,
+            },
+        );
+    }
+}
+
+// Pretty-prints the most recent JSON/YAML output of a session, optionally through a jq-style
+// query such as `.items[] | select(.status.phase != "Running") | .metadata.name`
+#[command]
+pub fn format_last_output(
+    session_id: String,
+    query: Option<String>,
+    scrollback_manager: State<'_, ScrollbackManager>,
+) -> Result<FormattedOutput, String> {
+    let run = last_output_run(scrollback_manager.runs(&session_id)?)
+        .ok_or_else(|| format!("Session '{}' has no command output yet", session_id))?;
+    let (format, documents) = detect_structured_output(&run.output)
+        .ok_or_else(|| format!("The output of '{}' is not JSON or YAML", run.command.trim()))?;
+
+    let query = query
+        .map(|query| query.trim().to_string())
+        .filter(|query| !query.is_empty());
+    let results = match &query {
+        Some(text) => {
+            let parsed = JsonQuery::parse(text).map_err(|e| format!("Invalid query: {}", e))?;
+            let mut results = Vec::new();
+            for document in &documents {
+                results.extend(
+                    parsed
+                        .run(document)
+                        .map_err(|e| format!("Query failed: {}", e))?,
+                );
+            }
+            results
+        }
+        None => documents,
+    };
+
+    Ok(FormattedOutput {
+        command: run.command,
+        format,
+        query,
+        text: render(format, &results)?,
+        results,
+    })
+}
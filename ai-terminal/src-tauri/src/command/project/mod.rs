@@ -0,0 +1,2 @@
+pub mod project_detection;
+pub mod project_tasks;
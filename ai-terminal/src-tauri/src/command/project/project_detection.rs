@@ -0,0 +1,261 @@
+use crate::command::types::command_manager::CommandManager;
+use serde::Serialize;
+use std::fs;
+use std::path::{Path, PathBuf};
+use tauri::{command, State};
+
+#[derive(Serialize, Clone, Copy, PartialEq, Eq, Debug)]
+#[serde(rename_all = "lowercase")]
+pub enum ProjectType {
+    Rust,
+    Node,
+    Go,
+    Docker,
+    Git,
+}
+
+#[derive(Serialize, Clone, Debug, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct SuggestedCommands {
+    pub build: Option<String>,
+    pub test: Option<String>,
+    pub run: Option<String>,
+}
+
+#[derive(Serialize, Clone, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct ProjectInfo {
+    pub project_type: ProjectType,
+    pub name: String,
+    pub root: String,
+    // Every marker file found in the project root, e.g. a Rust project with a Dockerfile
+    pub markers: Vec<String>,
+    pub commands: SuggestedCommands,
+}
+
+// Checked in priority order; the first one present decides the project type
+const PROJECT_MARKERS: &[(&str, ProjectType)] = &[
+    ("Cargo.toml", ProjectType::Rust),
+    ("package.json", ProjectType::Node),
+    ("go.mod", ProjectType::Go),
+    ("Dockerfile", ProjectType::Docker),
+    (".git", ProjectType::Git),
+];
+
+fn markers_in(dir: &Path) -> Vec<(&'static str, ProjectType)> {
+    PROJECT_MARKERS
+        .iter()
+        .filter(|(marker, _)| dir.join(marker).exists())
+        .copied()
+        .collect()
+}
+
+// `name = "..."` from the [package] table; avoids pulling in a TOML parser for one key
+fn cargo_package_name(manifest: &str) -> Option<String> {
+    let mut in_package = false;
+    for line in manifest.lines() {
+        let line = line.trim();
+        if line.starts_with('[') {
+            in_package = line == "[package]";
+            continue;
+        }
+        if !in_package {
+            continue;
+        }
+        if let Some((key, value)) = line.split_once('=') {
+            if key.trim() == "name" {
+                return Some(value.trim().trim_matches('"').to_string());
+            }
+        }
+    }
+    None
+}
+
+fn go_module_name(go_mod: &str) -> Option<String> {
+    go_mod
+        .lines()
+        .find_map(|line| line.trim().strip_prefix("module "))
+        .map(|module| module.trim().to_string())
+}
+
+// npm, pnpm, yarn or bun, judged by the lockfile next to package.json
+pub fn node_package_manager(root: &Path) -> &'static str {
+    if root.join("pnpm-lock.yaml").exists() {
+        "pnpm"
+    } else if root.join("yarn.lock").exists() {
+        "yarn"
+    } else if root.join("bun.lockb").exists() || root.join("bun.lock").exists() {
+        "bun"
+    } else {
+        "npm"
+    }
+}
+
+pub fn package_json(root: &Path) -> Option<serde_json::Value> {
+    let contents = fs::read_to_string(root.join("package.json")).ok()?;
+    serde_json::from_str(&contents).ok()
+}
+
+fn node_commands(root: &Path, manifest: Option<&serde_json::Value>) -> SuggestedCommands {
+    let manager = node_package_manager(root);
+    let has_script = |script: &str| {
+        manifest
+            .and_then(|manifest| manifest.get("scripts"))
+            .and_then(|scripts| scripts.get(script))
+            .is_some()
+    };
+    let run_script = |script: &str| format!("{} run {}", manager, script);
+
+    SuggestedCommands {
+        build: has_script("build").then(|| run_script("build")),
+        test: has_script("test").then(|| format!("{} test", manager)),
+        run: if has_script("dev") {
+            Some(run_script("dev"))
+        } else if has_script("start") {
+            Some(format!("{} start", manager))
+        } else {
+            None
+        },
+    }
+}
+
+fn docker_tag(name: &str) -> String {
+    name.rsplit('/')
+        .next()
+        .unwrap_or(name)
+        .to_lowercase()
+        .chars()
+        .map(|c| {
+            if c.is_ascii_alphanumeric() || c == '-' || c == '_' || c == '.' {
+                c
+            } else {
+                '-'
+            }
+        })
+        .collect()
+}
+
+// Describes the project at `root`, or None if it has no recognised markers
+pub fn inspect_project(root: &Path) -> Option<ProjectInfo> {
+    let markers = markers_in(root);
+    let (_, project_type) = *markers.first()?;
+    let dir_name = root
+        .file_name()
+        .map(|name| name.to_string_lossy().to_string())
+        .unwrap_or_else(|| root.to_string_lossy().to_string());
+
+    let (name, commands) = match project_type {
+        ProjectType::Rust => {
+            let name = fs::read_to_string(root.join("Cargo.toml"))
+                .ok()
+                .and_then(|manifest| cargo_package_name(&manifest));
+            (
+                name,
+                SuggestedCommands {
+                    build: Some("cargo build".to_string()),
+                    test: Some("cargo test".to_string()),
+                    run: Some("cargo run".to_string()),
+                },
+            )
+        }
+        ProjectType::Node => {
+            let manifest = package_json(root);
+            let name = manifest
+                .as_ref()
+                .and_then(|manifest| manifest.get("name"))
+                .and_then(|name| name.as_str())
+                .map(str::to_string);
+            (name, node_commands(root, manifest.as_ref()))
+        }
+        ProjectType::Go => {
+            let name = fs::read_to_string(root.join("go.mod"))
+                .ok()
+                .and_then(|go_mod| go_module_name(&go_mod));
+            (
+                name,
+                SuggestedCommands {
+                    build: Some("go build ./...".to_string()),
+                    test: Some("go test ./...".to_string()),
+                    run: Some("go run .".to_string()),
+                },
+            )
+        }
+        ProjectType::Docker => {
+            let tag = docker_tag(&dir_name);
+            (
+                None,
+                SuggestedCommands {
+                    build: Some(format!("docker build -t {} .", tag)),
+                    test: None,
+                    run: Some(format!("docker run --rm -it {}", tag)),
+                },
+            )
+        }
+        ProjectType::Git => (None, SuggestedCommands::default()),
+    };
+
+    Some(ProjectInfo {
+        project_type,
+        name: name.unwrap_or(dir_name),
+        root: root.to_string_lossy().to_string(),
+        markers: markers
+            .iter()
+            .map(|(marker, _)| marker.to_string())
+            .collect(),
+        commands,
+    })
+}
+
+// Walks up from `cwd` to the nearest directory that looks like a project root
+pub fn find_project(cwd: &Path) -> Option<ProjectInfo> {
+    cwd.ancestors().find_map(inspect_project)
+}
+
+// One-paragraph description of the project for the AI prompt
+pub fn project_context_summary(project: &ProjectInfo) -> String {
+    let mut summary = format!(
+        "The user is working in a {:?} project named \"{}\" at {}",
+        project.project_type, project.name, project.root
+    );
+    let commands: Vec<String> = [
+        ("build", &project.commands.build),
+        ("test", &project.commands.test),
+        ("run", &project.commands.run),
+    ]
+    .iter()
+    .filter_map(|(label, command)| {
+        command
+            .as_ref()
+            .map(|command| format!("{}: `{}`", label, command))
+    })
+    .collect();
+    if !commands.is_empty() {
+        summary.push_str(&format!(" ({})", commands.join(", ")));
+    }
+    summary.push('.');
+    summary
+}
+
+// Local working directory of the session; project detection can't look inside remote hosts
+pub fn session_project_dir(
+    command_manager: &CommandManager,
+    session_id: &str,
+) -> Result<PathBuf, String> {
+    let states = command_manager.commands.lock().map_err(|e| e.to_string())?;
+    match states.get(session_id) {
+        Some(state) if state.is_ssh_session_active => {
+            Err("Project detection is not available in remote sessions".to_string())
+        }
+        Some(state) => Ok(PathBuf::from(&state.current_dir)),
+        None => std::env::current_dir().map_err(|e| e.to_string()),
+    }
+}
+
+#[command]
+pub fn detect_project(
+    session_id: String,
+    command_manager: State<'_, CommandManager>,
+) -> Result<Option<ProjectInfo>, String> {
+    let cwd = session_project_dir(&command_manager, &session_id)?;
+    Ok(find_project(&cwd))
+}
@@ -0,0 +1,255 @@
+use crate::command::core::execute_command::execute_command;
+use crate::command::project::project_detection::{
+    find_project, node_package_manager, package_json, session_project_dir, ProjectType,
+};
+use crate::command::types::command_manager::CommandManager;
+use serde::Serialize;
+use std::fs;
+use std::path::Path;
+use tauri::{command, AppHandle, Runtime, State};
+
+#[derive(Serialize, Clone, Copy, PartialEq, Eq, Debug)]
+#[serde(rename_all = "lowercase")]
+pub enum TaskSource {
+    Cargo,
+    Npm,
+    Make,
+    Just,
+}
+
+impl TaskSource {
+    fn prefix(self) -> &'static str {
+        match self {
+            TaskSource::Cargo => "cargo",
+            TaskSource::Npm => "npm",
+            TaskSource::Make => "make",
+            TaskSource::Just => "just",
+        }
+    }
+}
+
+#[derive(Serialize, Clone, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct ProjectTask {
+    // `<source>:<name>`, unique across sources (e.g. both make and npm may define "build")
+    pub id: String,
+    pub name: String,
+    pub source: TaskSource,
+    pub command: String,
+    pub description: Option<String>,
+}
+
+const CARGO_TASKS: &[(&str, &str)] = &[
+    ("build", "Compile the package"),
+    ("check", "Type-check without producing binaries"),
+    ("test", "Run the test suite"),
+    ("run", "Build and run the main binary"),
+    ("clippy", "Run the linter"),
+    ("fmt", "Format the sources"),
+];
+
+const MAKEFILES: &[&str] = &["GNUmakefile", "makefile", "Makefile"];
+const JUSTFILES: &[&str] = &["justfile", "Justfile", ".justfile"];
+
+// Task name and optional description, as read from a Makefile or justfile
+type TaskDefinition = (String, Option<String>);
+
+fn task(
+    source: TaskSource,
+    name: &str,
+    command: String,
+    description: Option<String>,
+) -> ProjectTask {
+    ProjectTask {
+        id: format!("{}:{}", source.prefix(), name),
+        name: name.to_string(),
+        source,
+        command,
+        description,
+    }
+}
+
+fn cargo_tasks() -> Vec<ProjectTask> {
+    CARGO_TASKS
+        .iter()
+        .map(|(name, description)| {
+            task(
+                TaskSource::Cargo,
+                name,
+                format!("cargo {}", name),
+                Some(description.to_string()),
+            )
+        })
+        .collect()
+}
+
+fn npm_tasks(root: &Path) -> Vec<ProjectTask> {
+    let manager = node_package_manager(root);
+    let Some(scripts) = package_json(root)
+        .and_then(|manifest| manifest.get("scripts").and_then(|s| s.as_object().cloned()))
+    else {
+        return Vec::new();
+    };
+    scripts
+        .iter()
+        .map(|(name, script)| {
+            task(
+                TaskSource::Npm,
+                name,
+                format!("{} run {}", manager, name),
+                script.as_str().map(str::to_string),
+            )
+        })
+        .collect()
+}
+
+// Explicit targets only: skips special targets (.PHONY), pattern rules and variable assignments.
+// A `## text` comment on the target line becomes its description.
+pub fn parse_make_targets(makefile: &str) -> Vec<TaskDefinition> {
+    let mut targets: Vec<TaskDefinition> = Vec::new();
+    for line in makefile.lines() {
+        if line.starts_with(['\t', ' ', '#', '.']) {
+            continue;
+        }
+        let Some((names, rest)) = line.split_once(':') else {
+            continue;
+        };
+        // `VAR := value`, `VAR ::= value` and `name: VAR = value` are assignments, not rules
+        let (prerequisites, comment) = rest.split_once("##").unwrap_or((rest, ""));
+        if rest.starts_with('=') || names.contains('=') || prerequisites.contains('=') {
+            continue;
+        }
+        let description = Some(comment.trim().to_string()).filter(|comment| !comment.is_empty());
+        for name in names.split_whitespace() {
+            if name.contains(['%', '$']) || targets.iter().any(|(existing, _)| existing == name) {
+                continue;
+            }
+            targets.push((name.to_string(), description.clone()));
+        }
+    }
+    targets
+}
+
+// Recipe names from a justfile; a `#` comment directly above a recipe becomes its description
+pub fn parse_just_recipes(justfile: &str) -> Vec<TaskDefinition> {
+    const KEYWORDS: &[&str] = &["set", "alias", "export", "import", "mod"];
+    let mut recipes = Vec::new();
+    let mut comment: Option<String> = None;
+    for line in justfile.lines() {
+        if line.starts_with([' ', '\t']) || line.trim().is_empty() {
+            comment = None;
+            continue;
+        }
+        if let Some(text) = line.strip_prefix('#') {
+            comment = Some(text.trim().to_string());
+            continue;
+        }
+        let description = comment.take();
+        if line.starts_with('[') || line.contains(":=") {
+            continue;
+        }
+        let Some((head, _)) = line.split_once(':') else {
+            continue;
+        };
+        let mut words = head.split_whitespace();
+        let Some(name) = words.next().map(|name| name.trim_start_matches('@')) else {
+            continue;
+        };
+        if KEYWORDS.contains(&name) || name.starts_with('_') {
+            continue;
+        }
+        recipes.push((name.to_string(), description));
+    }
+    recipes
+}
+
+fn file_tasks(
+    root: &Path,
+    candidates: &[&str],
+    source: TaskSource,
+    parse: fn(&str) -> Vec<TaskDefinition>,
+) -> Vec<ProjectTask> {
+    let Some(contents) = candidates
+        .iter()
+        .find_map(|file| fs::read_to_string(root.join(file)).ok())
+    else {
+        return Vec::new();
+    };
+    parse(&contents)
+        .into_iter()
+        .map(|(name, description)| {
+            let command = format!("{} {}", source.prefix(), name);
+            task(source, &name, command, description)
+        })
+        .collect()
+}
+
+// Tasks defined at the project root containing `cwd` (or `cwd` itself outside a project)
+pub fn collect_project_tasks(cwd: &Path) -> Vec<ProjectTask> {
+    let project = find_project(cwd);
+    let root = project
+        .as_ref()
+        .map(|project| Path::new(&project.root).to_path_buf())
+        .unwrap_or_else(|| cwd.to_path_buf());
+    let markers = project
+        .as_ref()
+        .map(|project| project.markers.clone())
+        .unwrap_or_default();
+
+    let mut tasks = Vec::new();
+    if project.as_ref().map(|project| project.project_type) == Some(ProjectType::Rust) {
+        tasks.extend(cargo_tasks());
+    }
+    if markers.iter().any(|marker| marker == "package.json") {
+        tasks.extend(npm_tasks(&root));
+    }
+    tasks.extend(file_tasks(
+        &root,
+        MAKEFILES,
+        TaskSource::Make,
+        parse_make_targets,
+    ));
+    tasks.extend(file_tasks(
+        &root,
+        JUSTFILES,
+        TaskSource::Just,
+        parse_just_recipes,
+    ));
+    tasks
+}
+
+#[command]
+pub fn list_project_tasks(
+    session_id: String,
+    command_manager: State<'_, CommandManager>,
+) -> Result<Vec<ProjectTask>, String> {
+    let cwd = session_project_dir(&command_manager, &session_id)?;
+    Ok(collect_project_tasks(&cwd))
+}
+
+// Runs a task by id (`make:build`) or bare name (first match) through the normal command pipeline
+#[command]
+pub fn run_project_task<R: Runtime>(
+    session_id: String,
+    name: String,
+    app_handle: AppHandle<R>,
+    command_manager: State<'_, CommandManager>,
+) -> Result<String, String> {
+    let cwd = session_project_dir(&command_manager, &session_id)?;
+    let tasks = collect_project_tasks(&cwd);
+    let task = tasks
+        .iter()
+        .find(|task| task.id == name)
+        .or_else(|| tasks.iter().find(|task| task.name == name))
+        .ok_or_else(|| format!("No project task named '{}'", name))?;
+    execute_command(
+        task.command.clone(),
+        session_id,
+        None,
+        None,
+        None,
+        app_handle,
+        command_manager,
+    )
+    .map_err(String::from)
+}
@@ -0,0 +1 @@
+pub mod prompt_segments;
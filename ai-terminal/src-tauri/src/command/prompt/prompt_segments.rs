@@ -0,0 +1,150 @@
+use crate::command::environment::cloud_profiles::{cloud_context, CloudContext};
+use crate::command::environment::directory_env::{session_env_overrides, session_var};
+use crate::command::environment::python_env::detect_python_environments;
+use crate::command::git_commands::status_cache::{prompt_git_status, PromptGitStatus};
+use crate::command::types::command_manager::CommandManager;
+use crate::history::types::history_store::HistoryStore;
+use serde::Serialize;
+use std::collections::HashMap;
+use std::fs;
+use std::path::Path;
+use tauri::{command, AppHandle, Runtime, State};
+
+// Directories shown in full at the end of an abbreviated cwd
+const PROMPT_CWD_COMPONENTS: usize = 3;
+
+// Everything the prompt line shows, gathered in one call
+#[derive(Serialize, Clone, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct PromptSegments {
+    pub cwd: String,
+    pub cwd_full: String,
+    pub remote: bool,
+    pub git: Option<PromptGitStatus>,
+    pub python_env: Option<String>,
+    // Virtualenvs next to the cwd that could be activated
+    pub available_python_envs: Vec<String>,
+    pub kube_context: Option<String>,
+    // Active AWS profile/region, gcloud project and az subscription
+    pub cloud: CloudContext,
+    pub last_exit_code: Option<i32>,
+    pub last_duration_ms: Option<u64>,
+}
+
+// `/home/me/src/org/project/crate` -> `~/…/project/crate`-style shortening
+pub fn abbreviate_path(path: &str) -> String {
+    let home = dirs::home_dir().map(|home| home.to_string_lossy().to_string());
+    let (prefix, rest) = match home {
+        Some(home) if path == home => return "~".to_string(),
+        Some(home) if path.starts_with(&format!("{}/", home)) => ("~", &path[home.len()..]),
+        _ => ("", path),
+    };
+    let components: Vec<&str> = rest.split('/').filter(|part| !part.is_empty()).collect();
+    if components.len() <= PROMPT_CWD_COMPONENTS {
+        return format!("{}{}", prefix, rest);
+    }
+    format!(
+        "{}/…/{}",
+        prefix,
+        components[components.len() - PROMPT_CWD_COMPONENTS..].join("/")
+    )
+}
+
+fn python_env_name(overrides: &HashMap<String, String>) -> Option<String> {
+    session_var(overrides, "VIRTUAL_ENV")
+        .and_then(|venv| {
+            Path::new(&venv)
+                .file_name()
+                .map(|name| name.to_string_lossy().to_string())
+        })
+        .or_else(|| session_var(overrides, "CONDA_DEFAULT_ENV"))
+}
+
+// current-context from the first kubeconfig file, read without a YAML parser
+fn kube_context(overrides: &HashMap<String, String>) -> Option<String> {
+    let config = match session_var(overrides, "KUBECONFIG") {
+        Some(paths) => std::env::split_paths(&paths).find(|path| path.is_file())?,
+        None => dirs::home_dir()?.join(".kube").join("config"),
+    };
+    let contents = fs::read_to_string(config).ok()?;
+    contents
+        .lines()
+        .find_map(|line| line.strip_prefix("current-context:"))
+        .map(|context| context.trim().trim_matches(['"', '\'']).to_string())
+        .filter(|context| !context.is_empty())
+}
+
+#[command]
+pub fn get_prompt_segments<R: Runtime>(
+    session_id: String,
+    app_handle: AppHandle<R>,
+    command_manager: State<'_, CommandManager>,
+    history_store: State<'_, HistoryStore>,
+) -> Result<PromptSegments, String> {
+    let (cwd, remote) = {
+        let states = command_manager.commands.lock().map_err(|e| e.to_string())?;
+        match states.get(&session_id) {
+            Some(state) if state.is_ssh_session_active => (
+                state
+                    .remote_current_dir
+                    .clone()
+                    .unwrap_or_else(|| "~".to_string()),
+                true,
+            ),
+            Some(state) => (state.current_dir.clone(), false),
+            None => (
+                std::env::current_dir()
+                    .map(|dir| dir.to_string_lossy().to_string())
+                    .map_err(|e| e.to_string())?,
+                false,
+            ),
+        }
+    };
+    let overrides = session_env_overrides(&command_manager, &session_id);
+
+    let (last_exit_code, last_duration_ms) = history_store
+        .entries()?
+        .as_ref()
+        .and_then(|entries| {
+            entries
+                .iter()
+                .rev()
+                .find(|entry| entry.session_id == session_id)
+        })
+        .map(|entry| (entry.exit_code, entry.duration_ms))
+        .unwrap_or_default();
+
+    Ok(PromptSegments {
+        cwd: if remote {
+            cwd.clone()
+        } else {
+            abbreviate_path(&cwd)
+        },
+        cwd_full: cwd.clone(),
+        // Local-only segments describe this machine, so they're left out over SSH
+        git: if remote {
+            None
+        } else {
+            prompt_git_status(&app_handle, &cwd)
+        },
+        python_env: python_env_name(&overrides).filter(|_| !remote),
+        available_python_envs: if remote {
+            Vec::new()
+        } else {
+            detect_python_environments(Path::new(&cwd), None)
+                .into_iter()
+                .filter(|env| env.local)
+                .map(|env| env.name)
+                .collect()
+        },
+        kube_context: kube_context(&overrides).filter(|_| !remote),
+        cloud: if remote {
+            CloudContext::default()
+        } else {
+            cloud_context(&overrides)
+        },
+        remote,
+        last_exit_code,
+        last_duration_ms,
+    })
+}
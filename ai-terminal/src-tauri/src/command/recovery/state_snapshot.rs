@@ -0,0 +1,193 @@
+use crate::command::constants::STATE_SNAPSHOT_FILE;
+use crate::command::types::command_manager::CommandManager;
+use crate::command::types::command_state::CommandState;
+use crate::command::types::pty_manager::PtyManager;
+use crate::command::types::recovery_manager::{
+    RecoveryManager, RunningCommand, SessionSnapshot, StateSnapshot,
+};
+use crate::command::types::scrollback_manager::ScrollbackManager;
+use crate::utils::config_utils::{app_data_dir, unix_timestamp_millis};
+use serde::Serialize;
+use std::collections::{BTreeSet, HashMap};
+use std::fs;
+use std::path::{Path, PathBuf};
+use tauri::{command, AppHandle, Manager, Runtime, State};
+
+#[derive(Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct InterruptedCommand {
+    pub session_id: String,
+    pub command: String,
+    pub started_at: u64,
+}
+
+#[derive(Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct RecoveryOffer {
+    pub saved_at: u64,
+    pub sessions: Vec<SessionSnapshot>,
+    // Commands that were running when the app went down and did not finish
+    pub interrupted: Vec<InterruptedCommand>,
+}
+
+fn snapshot_path() -> Result<PathBuf, String> {
+    Ok(app_data_dir()?.join(STATE_SNAPSHOT_FILE))
+}
+
+fn capture_snapshot<R: Runtime>(app_handle: &AppHandle<R>) -> Result<StateSnapshot, String> {
+    let targets: HashMap<String, _> = {
+        let pty_manager = app_handle.state::<PtyManager>();
+        let sessions = pty_manager.sessions.lock().map_err(|e| e.to_string())?;
+        sessions
+            .iter()
+            .map(|(id, session)| (id.clone(), session.target.clone()))
+            .collect()
+    };
+    let command_manager = app_handle.state::<CommandManager>();
+    let states = command_manager.commands.lock().map_err(|e| e.to_string())?;
+    let scrollback_manager = app_handle.state::<ScrollbackManager>();
+
+    let session_ids: BTreeSet<&String> = states.keys().chain(targets.keys()).collect();
+    let mut sessions = Vec::new();
+    for session_id in session_ids {
+        let state = states.get(session_id);
+        let runs = scrollback_manager.runs(session_id).unwrap_or_default();
+        let unfinished = runs.iter().rev().find(|run| !run.finished);
+        let is_ssh = state.is_some_and(|state| state.is_ssh_session_active);
+        let running_command = state
+            .and_then(|state| state.pid)
+            .map(|pid| RunningCommand {
+                command: unfinished
+                    .map(|run| run.command.clone())
+                    .unwrap_or_default(),
+                started_at: unfinished.map(|run| run.started_at).unwrap_or(0),
+                pid: Some(pid),
+            })
+            // An SSH connection is restored by re-running it, not reported as interrupted
+            .filter(|_| !is_ssh);
+        sessions.push(SessionSnapshot {
+            session_id: session_id.clone(),
+            cwd: state
+                .map(|state| state.current_dir.clone())
+                .unwrap_or_default(),
+            remote_current_dir: state
+                .filter(|_| is_ssh)
+                .and_then(|state| state.remote_current_dir.clone()),
+            ssh_command: unfinished.filter(|_| is_ssh).map(|run| run.command.clone()),
+            target: targets.get(session_id).cloned().flatten(),
+            variables: state
+                .map(|state| state.variables.clone())
+                .unwrap_or_default(),
+            cloud_env: state
+                .map(|state| state.cloud_env.clone())
+                .unwrap_or_default(),
+            python_env: state
+                .and_then(|state| state.python_env.as_ref())
+                .map(|env| env.prefix.clone()),
+            node_version: state
+                .and_then(|state| state.node_version.as_ref())
+                .map(|node| node.version.clone()),
+            running_command,
+        });
+    }
+    Ok(StateSnapshot {
+        saved_at: unix_timestamp_millis(),
+        sessions,
+    })
+}
+
+// Scheduler task: writes the current session metadata, replacing the file atomically so a
+// crash mid-write cannot leave it truncated
+pub fn write_state_snapshot<R: Runtime>(app_handle: &AppHandle<R>) -> Result<(), String> {
+    let snapshot = capture_snapshot(app_handle)?;
+    let path = snapshot_path()?;
+    let temp_path = path.with_extension("json.tmp");
+    let contents = serde_json::to_string_pretty(&snapshot).map_err(|e| e.to_string())?;
+    fs::write(&temp_path, contents)
+        .map_err(|e| format!("Failed to write {}: {}", temp_path.display(), e))?;
+    fs::rename(&temp_path, &path)
+        .map_err(|e| format!("Failed to replace {}: {}", path.display(), e))
+}
+
+// Called on a clean shutdown; a snapshot found at startup therefore means a crash
+pub fn clear_state_snapshot() {
+    if let Ok(path) = snapshot_path() {
+        let _ = fs::remove_file(path);
+    }
+}
+
+// Picks up the snapshot a crashed run left behind so the UI can offer to restore it
+pub fn init_crash_recovery<R: Runtime>(app_handle: &AppHandle<R>) {
+    let snapshot = snapshot_path()
+        .ok()
+        .and_then(|path| fs::read_to_string(path).ok())
+        .and_then(|contents| serde_json::from_str::<StateSnapshot>(&contents).ok())
+        .filter(|snapshot| !snapshot.sessions.is_empty());
+    if let Ok(mut pending) = app_handle.state::<RecoveryManager>().pending.lock() {
+        *pending = snapshot;
+    }
+}
+
+#[command]
+pub fn get_recovery_state(
+    recovery_manager: State<'_, RecoveryManager>,
+) -> Result<Option<RecoveryOffer>, String> {
+    let pending = recovery_manager.pending.lock().map_err(|e| e.to_string())?;
+    Ok(pending.as_ref().map(|snapshot| RecoveryOffer {
+        saved_at: snapshot.saved_at,
+        sessions: snapshot.sessions.clone(),
+        interrupted: snapshot
+            .sessions
+            .iter()
+            .filter_map(|session| {
+                let running = session.running_command.as_ref()?;
+                Some(InterruptedCommand {
+                    session_id: session.session_id.clone(),
+                    command: running.command.clone(),
+                    started_at: running.started_at,
+                })
+            })
+            .collect(),
+    }))
+}
+
+// Recreates each session's state (directory, variables, cloud profiles) and returns the
+// snapshots so the UI can reopen the terminals, reconnect SSH/container targets and
+// re-activate environments. Directories that no longer exist fall back to the home folder.
+#[command]
+pub fn restore_previous_state(
+    recovery_manager: State<'_, RecoveryManager>,
+    command_manager: State<'_, CommandManager>,
+) -> Result<Vec<SessionSnapshot>, String> {
+    let snapshot = recovery_manager
+        .pending
+        .lock()
+        .map_err(|e| e.to_string())?
+        .take()
+        .ok_or_else(|| "There is no previous state to restore".to_string())?;
+    let home = dirs::home_dir()
+        .map(|home| home.to_string_lossy().to_string())
+        .unwrap_or_else(|| "/".to_string());
+
+    let mut states = command_manager.commands.lock().map_err(|e| e.to_string())?;
+    let mut restored = Vec::new();
+    for mut session in snapshot.sessions {
+        if session.cwd.is_empty() || !Path::new(&session.cwd).is_dir() {
+            session.cwd = home.clone();
+        }
+        let state = states
+            .entry(session.session_id.clone())
+            .or_insert_with(|| CommandState::new(session.cwd.clone()));
+        state.current_dir = session.cwd.clone();
+        state.variables = session.variables.clone();
+        state.cloud_env = session.cloud_env.clone();
+        restored.push(session);
+    }
+    Ok(restored)
+}
+
+#[command]
+pub fn discard_recovery_state(recovery_manager: State<'_, RecoveryManager>) -> Result<(), String> {
+    *recovery_manager.pending.lock().map_err(|e| e.to_string())? = None;
+    Ok(())
+}
@@ -0,0 +1,237 @@
+use crate::command::constants::{CODE_BLOCK_DIR, CODE_BLOCK_RETENTION_MS};
+use crate::command::core::execute_command::execute_command;
+use crate::command::environment::session_tmpdir::ensure_session_tmpdir;
+use crate::command::types::command_manager::CommandManager;
+use crate::command::types::confirmation_manager::{
+    ConfirmationManager, CONFIRMATION_REQUIRED_EVENT, CONFIRMATION_REQUIRED_MARKER,
+};
+use crate::utils::path_executables::find_executable;
+use sha2::{Digest, Sha256};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime};
+use tauri::{command, AppHandle, Emitter, Runtime, State};
+
+struct CodeBlockLanguage {
+    name: &'static str,
+    // Fence tags accepted besides the name
+    aliases: &'static [&'static str],
+    extension: &'static str,
+    // Tried in order; the first one on PATH wins. Each is the program plus its arguments
+    // before the script path.
+    interpreters: &'static [&'static [&'static str]],
+}
+
+const CODE_BLOCK_LANGUAGES: &[CodeBlockLanguage] = &[
+    CodeBlockLanguage {
+        name: "python",
+        aliases: &["py", "python3"],
+        extension: "py",
+        interpreters: &[&["python3"], &["python"]],
+    },
+    CodeBlockLanguage {
+        name: "javascript",
+        aliases: &["js", "node", "nodejs", "mjs"],
+        extension: "js",
+        interpreters: &[&["node"], &["deno", "run"], &["bun", "run"]],
+    },
+    CodeBlockLanguage {
+        name: "typescript",
+        aliases: &["ts"],
+        extension: "ts",
+        interpreters: &[&["tsx"], &["ts-node"], &["deno", "run"], &["bun", "run"]],
+    },
+    CodeBlockLanguage {
+        name: "bash",
+        aliases: &["sh", "shell", "console"],
+        extension: "sh",
+        interpreters: &[&["bash"], &["sh"]],
+    },
+    CodeBlockLanguage {
+        name: "zsh",
+        aliases: &[],
+        extension: "zsh",
+        interpreters: &[&["zsh"]],
+    },
+    CodeBlockLanguage {
+        name: "fish",
+        aliases: &[],
+        extension: "fish",
+        interpreters: &[&["fish"]],
+    },
+    CodeBlockLanguage {
+        name: "ruby",
+        aliases: &["rb"],
+        extension: "rb",
+        interpreters: &[&["ruby"]],
+    },
+    CodeBlockLanguage {
+        name: "perl",
+        aliases: &["pl"],
+        extension: "pl",
+        interpreters: &[&["perl"]],
+    },
+    CodeBlockLanguage {
+        name: "php",
+        aliases: &[],
+        extension: "php",
+        interpreters: &[&["php"]],
+    },
+    CodeBlockLanguage {
+        name: "lua",
+        aliases: &[],
+        extension: "lua",
+        interpreters: &[&["lua"], &["luajit"]],
+    },
+    CodeBlockLanguage {
+        name: "powershell",
+        aliases: &["ps1", "pwsh", "ps"],
+        extension: "ps1",
+        interpreters: &[&["pwsh", "-NoProfile", "-File"]],
+    },
+    CodeBlockLanguage {
+        name: "go",
+        aliases: &["golang"],
+        extension: "go",
+        interpreters: &[&["go", "run"]],
+    },
+];
+
+// Accepts the fence tag as written ("Python", "py", "```js" info strings like "js title=x")
+fn resolve_language(language: &str) -> Option<&'static CodeBlockLanguage> {
+    let tag = language
+        .split_whitespace()
+        .next()
+        .unwrap_or_default()
+        .trim_start_matches('.')
+        .to_lowercase();
+    CODE_BLOCK_LANGUAGES
+        .iter()
+        .find(|lang| lang.name == tag || lang.aliases.contains(&tag.as_str()))
+}
+
+fn find_interpreter(language: &CodeBlockLanguage) -> Option<Vec<String>> {
+    language.interpreters.iter().find_map(|interpreter| {
+        find_executable(interpreter[0]).map(|program| {
+            let mut command = vec![program.to_string_lossy().to_string()];
+            command.extend(interpreter[1..].iter().map(|arg| arg.to_string()));
+            command
+        })
+    })
+}
+
+fn single_quote(value: &str) -> String {
+    format!("'{}'", value.replace('\'', "'\\''"))
+}
+
+// Removes blocks left over from earlier runs; the running one is still read by its
+// interpreter, so files are only deleted once they are old
+fn prune_code_blocks(dir: &Path) {
+    let Ok(entries) = fs::read_dir(dir) else {
+        return;
+    };
+    let cutoff = SystemTime::now() - Duration::from_millis(CODE_BLOCK_RETENTION_MS);
+    for entry in entries.flatten() {
+        let modified = entry.metadata().and_then(|metadata| metadata.modified());
+        if matches!(modified, Ok(modified) if modified < cutoff) {
+            let _ = fs::remove_file(entry.path());
+        }
+    }
+}
+
+fn write_code_block(
+    session_tmpdir: &Path,
+    language: &CodeBlockLanguage,
+    code: &str,
+) -> Result<PathBuf, String> {
+    let dir = session_tmpdir.join(CODE_BLOCK_DIR);
+    fs::create_dir_all(&dir).map_err(|e| format!("Failed to create {}: {}", dir.display(), e))?;
+    prune_code_blocks(&dir);
+
+    let path = dir.join(format!("{}.{}", uuid::Uuid::new_v4(), language.extension));
+    let mut contents = code.to_string();
+    if !contents.ends_with('\n') {
+        contents.push('\n');
+    }
+    fs::write(&path, contents).map_err(|e| format!("Failed to write {}: {}", path.display(), e))?;
+    Ok(path)
+}
+
+// The confirmation is bound to the exact code, so an edited block needs a new one
+fn confirmation_scope(session_id: &str, language: &str, code: &str) -> String {
+    let digest = Sha256::digest(format!("{}\0{}", language, code).as_bytes());
+    let hash: String = digest.iter().map(|byte| format!("{:02x}", byte)).collect();
+    format!("code_block:{}:{}", session_id, hash)
+}
+
+// Runs a fenced code block from an AI answer as a script in the session's directory. The
+// first call emits confirmation_required and returns the confirmation marker; once the user
+// accepts, the frontend calls again with the token and the script starts like any command.
+#[command]
+pub fn run_code_block<R: Runtime>(
+    session_id: String,
+    language: String,
+    code: String,
+    confirmation_token: Option<String>,
+    app_handle: AppHandle<R>,
+    command_manager: State<'_, CommandManager>,
+    confirmation_manager: State<'_, ConfirmationManager>,
+) -> Result<String, String> {
+    let lang = resolve_language(&language)
+        .ok_or_else(|| format!("Running '{}' code blocks is not supported", language))?;
+    if code.trim().is_empty() {
+        return Err("The code block is empty".to_string());
+    }
+    let is_remote = command_manager
+        .commands
+        .lock()
+        .map_err(|e| e.to_string())?
+        .get(&session_id)
+        .map(|state| state.is_ssh_session_active)
+        .unwrap_or(false);
+    if is_remote {
+        return Err("Code blocks cannot be run in an SSH session".to_string());
+    }
+    let interpreter = find_interpreter(lang).ok_or_else(|| {
+        format!(
+            "No {} interpreter found on PATH (tried {})",
+            lang.name,
+            lang.interpreters
+                .iter()
+                .map(|interpreter| interpreter[0])
+                .collect::<Vec<_>>()
+                .join(", ")
+        )
+    })?;
+
+    let scope = confirmation_scope(&session_id, lang.name, &code);
+    let confirmed = confirmation_token
+        .map(|token| confirmation_manager.consume(&token, &scope))
+        .unwrap_or(false);
+    if !confirmed {
+        let request = confirmation_manager.issue(
+            &scope,
+            &format!("Run this {} code block?", lang.name),
+            &code,
+        )?;
+        app_handle
+            .emit(CONFIRMATION_REQUIRED_EVENT, request)
+            .map_err(|e| e.to_string())?;
+        return Ok(CONFIRMATION_REQUIRED_MARKER.to_string());
+    }
+
+    let session_tmpdir = ensure_session_tmpdir(&command_manager, &session_id)?;
+    let path = write_code_block(&session_tmpdir, lang, &code)?;
+    let mut command_line: Vec<String> = interpreter.iter().map(|arg| single_quote(arg)).collect();
+    command_line.push(single_quote(&path.to_string_lossy()));
+    execute_command(
+        command_line.join(" "),
+        session_id,
+        None,
+        None,
+        None,
+        app_handle,
+        command_manager,
+    )
+    .map_err(String::from)
+}
@@ -0,0 +1,2 @@
+pub mod code_block_runner;
+pub mod repl_launcher;
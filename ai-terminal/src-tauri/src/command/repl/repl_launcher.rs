@@ -0,0 +1,192 @@
+use crate::appearance::appearance_commands::apply_appearance_env;
+use crate::command::core::protected_sessions::require_protected_confirmation;
+use crate::command::core::pty::spawn_pty_session;
+use crate::command::types::pty_manager::{PtyManager, SessionTarget};
+use crate::settings::types::settings_manager::SettingsManager;
+use crate::utils::env_scrubbing::scrubbed_environment;
+use crate::utils::path_executables::find_executable;
+use portable_pty::CommandBuilder;
+use serde::Serialize;
+use std::io::Write;
+use tauri::{command, AppHandle, Manager, Runtime, State};
+
+struct ReplLanguage {
+    name: &'static str,
+    aliases: &'static [&'static str],
+    // Tried in order; the first one on PATH wins
+    interpreters: &'static [&'static str],
+    args: &'static [&'static str],
+}
+
+const REPL_LANGUAGES: &[ReplLanguage] = &[
+    ReplLanguage {
+        name: "python",
+        aliases: &["py", "python3"],
+        interpreters: &["python3", "python"],
+        args: &["-q"],
+    },
+    ReplLanguage {
+        name: "javascript",
+        aliases: &["js", "node", "nodejs"],
+        interpreters: &["node"],
+        args: &[],
+    },
+    ReplLanguage {
+        name: "ruby",
+        aliases: &["rb", "irb"],
+        interpreters: &["irb"],
+        args: &[],
+    },
+    ReplLanguage {
+        name: "haskell",
+        aliases: &["hs", "ghci"],
+        interpreters: &["ghci"],
+        args: &[],
+    },
+];
+
+#[derive(Serialize, Clone, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct ReplSession {
+    pub session_id: String,
+    pub language: String,
+    pub interpreter: String,
+}
+
+// Accepts names, common aliases and file extensions ("Python", "py", ".py")
+fn resolve_language(language: &str) -> Option<&'static ReplLanguage> {
+    let language = language.trim().trim_start_matches('.').to_lowercase();
+    REPL_LANGUAGES
+        .iter()
+        .find(|repl| repl.name == language || repl.aliases.contains(&language.as_str()))
+}
+
+// The interactive Python prompt ends a block at the first blank line and rejects a dedented
+// statement straight after an indented one, so blank lines inside blocks are dropped and one
+// is inserted wherever the code returns to the top level
+fn prepare_python(code: &str) -> String {
+    let mut prepared = String::new();
+    let mut in_block = false;
+    for line in code.lines().filter(|line| !line.trim().is_empty()) {
+        let indented = line.starts_with([' ', '\t']);
+        if in_block && !indented {
+            prepared.push('\r');
+        }
+        prepared.push_str(line);
+        prepared.push('\r');
+        in_block = indented;
+    }
+    if in_block {
+        prepared.push('\r');
+    }
+    prepared
+}
+
+// Shapes a snippet so the interpreter evaluates it as if it had been typed in
+fn prepare_snippet(language: &str, code: &str) -> String {
+    match language {
+        "python" => prepare_python(code),
+        // GHCi needs multi-line input wrapped in :{ ... :}
+        "haskell" if code.trim().contains('\n') => {
+            format!(":{{\r{}\r:}}\r", code.trim().replace('\n', "\r"))
+        }
+        _ => format!("{}\r", code.trim_end().replace('\n', "\r")),
+    }
+}
+
+// Starts the interpreter for `language` in a new PTY session tagged as a REPL
+#[command]
+pub fn start_repl<R: Runtime>(
+    language: String,
+    cols: u16,
+    rows: u16,
+    app_handle: AppHandle<R>,
+    pty_manager: State<'_, PtyManager>,
+) -> Result<ReplSession, String> {
+    let repl = resolve_language(&language).ok_or_else(|| {
+        let supported: Vec<&str> = REPL_LANGUAGES.iter().map(|repl| repl.name).collect();
+        format!(
+            "No REPL for '{}'. Supported: {}",
+            language,
+            supported.join(", ")
+        )
+    })?;
+    let (interpreter, path) = repl
+        .interpreters
+        .iter()
+        .find_map(|name| find_executable(name).map(|path| (*name, path)))
+        .ok_or_else(|| {
+            format!(
+                "No {} interpreter found on PATH (looked for {})",
+                repl.name,
+                repl.interpreters.join(", ")
+            )
+        })?;
+
+    let mut command = CommandBuilder::new(path);
+    command.args(repl.args);
+    command.env_clear();
+    for (name, value) in scrubbed_environment(&app_handle.state::<SettingsManager>().current()) {
+        command.env(name, value);
+    }
+    command.env("TERM", "xterm-256color");
+    apply_appearance_env(&app_handle, &mut command);
+    if let Ok(cwd) = std::env::current_dir() {
+        command.cwd(cwd);
+    }
+
+    let session_id = format!("repl-{}", uuid::Uuid::new_v4());
+    spawn_pty_session(
+        &app_handle,
+        &pty_manager,
+        session_id.clone(),
+        cols,
+        rows,
+        command,
+        Some(SessionTarget::Repl {
+            language: repl.name.to_string(),
+            interpreter: interpreter.to_string(),
+        }),
+    )?;
+
+    Ok(ReplSession {
+        session_id,
+        language: repl.name.to_string(),
+        interpreter: interpreter.to_string(),
+    })
+}
+
+// Types a code snippet (e.g. one suggested by the AI) into a REPL session for evaluation. In a
+// protected session, code matching a danger pattern (e.g. DROP TABLE) fails with the
+// confirmation marker until it is sent again with the token.
+#[command]
+pub fn send_to_repl<R: Runtime>(
+    session_id: String,
+    code: String,
+    confirmation_token: Option<String>,
+    app_handle: AppHandle<R>,
+    pty_manager: State<'_, PtyManager>,
+) -> Result<(), String> {
+    require_protected_confirmation(
+        &app_handle,
+        &session_id,
+        &code,
+        confirmation_token.as_deref(),
+    )?;
+    let sessions = pty_manager.sessions.lock().map_err(|e| e.to_string())?;
+    let session = sessions
+        .get(&session_id)
+        .ok_or_else(|| format!("PTY session '{}' not found", session_id))?;
+    let Some(SessionTarget::Repl { language, .. }) = &session.target else {
+        return Err(format!("Session '{}' is not a REPL", session_id));
+    };
+
+    let input = prepare_snippet(language, &code);
+    let mut writer = session.writer.lock().map_err(|e| e.to_string())?;
+    writer
+        .write_all(input.as_bytes())
+        .map_err(|e| format!("Failed to write REPL input: {e}"))?;
+    writer
+        .flush()
+        .map_err(|e| format!("Failed to flush REPL input: {e}"))
+}
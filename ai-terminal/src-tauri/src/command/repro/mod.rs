@@ -0,0 +1 @@
+pub mod repro_bundle;
@@ -0,0 +1,382 @@
+use crate::command::environment::directory_env::session_env_overrides;
+use crate::command::output::post_processors::redact_text;
+use crate::command::types::command_manager::CommandManager;
+use crate::command::types::scrollback_manager::ScrollbackManager;
+use crate::history::types::history_store::HistoryStore;
+use crate::settings::types::app_settings::DEFAULT_ENV_DENYLIST;
+use crate::settings::types::settings_manager::SettingsManager;
+use crate::utils::ansi::strip_ansi;
+use crate::utils::config_utils::app_data_dir;
+use crate::utils::env_scrubbing::{env_pattern_matches, scrubbed_environment};
+use crate::utils::file_system_utils::{expand_home, get_shell_path};
+use serde::Serialize;
+use std::collections::{BTreeMap, HashMap};
+use std::fs;
+use std::path::Path;
+use std::process::{Command, Stdio};
+use tauri::{command, AppHandle, Manager, Runtime};
+
+// Tools whose versions usually matter in a bug report, with the arguments that print them
+const REPRO_TOOLS: &[(&str, &[&str])] = &[
+    ("node", &["--version"]),
+    ("npm", &["--version"]),
+    ("python3", &["--version"]),
+    ("python", &["--version"]),
+    ("cargo", &["--version"]),
+    ("rustc", &["--version"]),
+    ("go", &["version"]),
+    ("git", &["--version"]),
+];
+
+// Variable names whose values are withheld even when env scrubbing lets them through
+const SENSITIVE_NAME_PARTS: &[&str] = &[
+    "TOKEN",
+    "SECRET",
+    "PASSWORD",
+    "PASSWD",
+    "CREDENTIAL",
+    "PRIVATE",
+    "API_KEY",
+    "APIKEY",
+    "AUTH",
+    "COOKIE",
+];
+
+#[derive(Serialize, Clone, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct ToolVersion {
+    pub name: String,
+    // None when the tool is not installed
+    pub version: Option<String>,
+}
+
+#[derive(Serialize, Clone, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct ReproBundle {
+    // The .tar.gz written
+    pub path: String,
+    pub command: String,
+    pub cwd: String,
+    pub exit_code: Option<i32>,
+    pub duration_ms: Option<u64>,
+    // Unix milliseconds the command started
+    pub ran_at: u64,
+    pub os: String,
+    pub tools: Vec<ToolVersion>,
+    // Values replaced with [REDACTED]; names are kept
+    pub redacted_variables: Vec<String>,
+    // Output was not in the scrollback anymore (or the command ran in a PTY)
+    pub output_missing: bool,
+}
+
+// The failed command a bundle is about
+struct FailedRun {
+    command: String,
+    cwd: Option<String>,
+    exit_code: Option<i32>,
+    duration_ms: Option<u64>,
+    ran_at: u64,
+    output: Option<String>,
+}
+
+// Most recent failure in the session: from the scrollback when its output is still there,
+// with directory and duration from history
+fn last_failed_run<R: Runtime>(
+    app_handle: &AppHandle<R>,
+    session_id: &str,
+) -> Result<FailedRun, String> {
+    let failed_history = {
+        let history = app_handle.state::<HistoryStore>();
+        let guard = history.entries()?;
+        guard
+            .as_deref()
+            .unwrap_or_default()
+            .iter()
+            .rev()
+            .find(|entry| {
+                entry.session_id == session_id && entry.exit_code.is_some_and(|code| code != 0)
+            })
+            .cloned()
+    };
+    let failed_output = app_handle
+        .state::<ScrollbackManager>()
+        .runs(session_id)?
+        .into_iter()
+        .rev()
+        .find(|run| run.finished && run.exit_code != Some(0));
+
+    match (failed_output, failed_history) {
+        (Some(run), history) => {
+            let history = history
+                .filter(|entry| entry.command == run.command && entry.exit_code == run.exit_code);
+            Ok(FailedRun {
+                cwd: history.as_ref().map(|entry| entry.cwd.clone()),
+                duration_ms: history.as_ref().and_then(|entry| entry.duration_ms),
+                command: run.command,
+                exit_code: run.exit_code,
+                ran_at: run.started_at,
+                output: Some(run.output),
+            })
+        }
+        (None, Some(entry)) => Ok(FailedRun {
+            command: entry.command,
+            cwd: Some(entry.cwd),
+            exit_code: entry.exit_code,
+            duration_ms: entry.duration_ms,
+            ran_at: entry.timestamp,
+            output: None,
+        }),
+        (None, None) => Err(format!(
+            "No failed command recorded in session {}",
+            session_id
+        )),
+    }
+}
+
+fn is_sensitive_name(denylist: &[String], name: &str) -> bool {
+    let upper = name.to_uppercase();
+    SENSITIVE_NAME_PARTS.iter().any(|part| upper.contains(part))
+        || DEFAULT_ENV_DENYLIST
+            .iter()
+            .any(|pattern| env_pattern_matches(pattern, name))
+        || denylist
+            .iter()
+            .any(|pattern| env_pattern_matches(pattern, name))
+}
+
+// First line a tool prints for its version, run where the command ran so version managers
+// (nvm, pyenv, rustup overrides) pick the same one
+fn tool_version(
+    name: &str,
+    args: &[&str],
+    cwd: &str,
+    env_map: &HashMap<String, String>,
+) -> Option<String> {
+    let output = Command::new(name)
+        .args(args)
+        .current_dir(cwd)
+        .env_clear()
+        .envs(env_map)
+        .stdin(Stdio::null())
+        .output()
+        .ok()?;
+    if !output.status.success() {
+        return None;
+    }
+    // python2 prints its version on stderr
+    let text = if output.stdout.is_empty() {
+        output.stderr
+    } else {
+        output.stdout
+    };
+    String::from_utf8_lossy(&text)
+        .lines()
+        .next()
+        .map(|line| line.trim().to_string())
+        .filter(|line| !line.is_empty())
+}
+
+fn os_description() -> String {
+    let uname = Command::new("uname")
+        .arg("-srm")
+        .output()
+        .ok()
+        .filter(|output| output.status.success())
+        .map(|output| String::from_utf8_lossy(&output.stdout).trim().to_string());
+    uname.unwrap_or_else(|| format!("{} {}", std::env::consts::OS, std::env::consts::ARCH))
+}
+
+fn render_readme(bundle: &ReproBundle, output: Option<&str>) -> String {
+    let mut readme = format!(
+        "# Reproduction bundle\n\n```console\n$ {}\n```\n\n",
+        bundle.command
+    );
+    readme.push_str(&format!("- Directory: `{}`\n", bundle.cwd));
+    readme.push_str(&format!(
+        "- Exit code: {}\n",
+        bundle
+            .exit_code
+            .map(|code| code.to_string())
+            .unwrap_or_else(|| "none (killed by a signal)".to_string())
+    ));
+    if let Some(duration_ms) = bundle.duration_ms {
+        readme.push_str(&format!("- Duration: {} ms\n", duration_ms));
+    }
+    if let Some(time) = chrono::DateTime::from_timestamp_millis(bundle.ran_at as i64) {
+        readme.push_str(&format!("- Ran at: {}\n", time.to_rfc3339()));
+    }
+    readme.push_str(&format!("- OS: {}\n\n## Tools\n\n", bundle.os));
+    for tool in &bundle.tools {
+        readme.push_str(&format!(
+            "- {}: {}\n",
+            tool.name,
+            tool.version.as_deref().unwrap_or("not found")
+        ));
+    }
+    readme.push_str("\n## Output\n\n");
+    match output {
+        Some(output) if !output.trim().is_empty() => {
+            let fence = if output.contains("```") {
+                "````"
+            } else {
+                "```"
+            };
+            readme.push_str(&format!("{}\n{}\n{}\n", fence, output.trim_end(), fence));
+        }
+        Some(_) => readme.push_str("The command printed nothing.\n"),
+        None => readme.push_str("The output is no longer available.\n"),
+    }
+    readme.push_str(
+        "\nThe environment is in environment.txt; secret values are replaced with [REDACTED].\n",
+    );
+    readme
+}
+
+fn write_archive(staging: &Path, target: &Path) -> Result<(), String> {
+    let (Some(parent), Some(name)) = (staging.parent(), staging.file_name()) else {
+        return Err(format!("Invalid bundle directory {}", staging.display()));
+    };
+    let output = Command::new("tar")
+        .arg("-czf")
+        .arg(target)
+        .arg("-C")
+        .arg(parent)
+        .arg(name)
+        .output()
+        .map_err(|e| format!("Failed to run tar: {}", e))?;
+    if !output.status.success() {
+        return Err(format!(
+            "Failed to write {}: {}",
+            target.display(),
+            String::from_utf8_lossy(&output.stderr).trim()
+        ));
+    }
+    Ok(())
+}
+
+fn build_repro_bundle<R: Runtime>(
+    app_handle: &AppHandle<R>,
+    session_id: &str,
+    path: Option<String>,
+) -> Result<ReproBundle, String> {
+    let run = last_failed_run(app_handle, session_id)?;
+    let settings = app_handle.state::<SettingsManager>().current();
+    let command_manager = app_handle.state::<CommandManager>();
+    let cwd = match run.cwd {
+        Some(cwd) => cwd,
+        None => command_manager
+            .commands
+            .lock()
+            .map_err(|e| e.to_string())?
+            .get(session_id)
+            .map(|state| state.current_dir.clone())
+            .unwrap_or_default(),
+    };
+
+    // The environment the session's commands get
+    let mut env_map = scrubbed_environment(&settings);
+    if !env_map.contains_key("PATH") {
+        if let Some(path_value) = get_shell_path() {
+            env_map.insert("PATH".to_string(), path_value);
+        }
+    }
+    env_map.insert("PWD".to_string(), cwd.clone());
+    env_map.extend(session_env_overrides(&command_manager, session_id));
+
+    let tools: Vec<ToolVersion> = REPRO_TOOLS
+        .iter()
+        .map(|(name, args)| ToolVersion {
+            name: name.to_string(),
+            version: tool_version(name, args, &cwd, &env_map),
+        })
+        .collect();
+
+    let mut redacted_variables = Vec::new();
+    let environment: BTreeMap<String, String> = env_map
+        .into_iter()
+        .map(|(name, value)| {
+            if is_sensitive_name(&settings.env_denylist, &name) {
+                redacted_variables.push(name.clone());
+                (name, "[REDACTED]".to_string())
+            } else {
+                let value = redact_text(&value);
+                (name, value)
+            }
+        })
+        .collect();
+    redacted_variables.sort();
+    let output = run
+        .output
+        .as_deref()
+        .map(|output| redact_text(&strip_ansi(output)));
+
+    let name = format!("repro-{}", chrono::Local::now().format("%Y%m%d-%H%M%S"));
+    let target = match path {
+        Some(path) => expand_home(&path)?,
+        None => app_data_dir()?
+            .join("repro")
+            .join(format!("{}.tar.gz", name)),
+    };
+    let bundle = ReproBundle {
+        path: target.to_string_lossy().to_string(),
+        command: redact_text(&run.command),
+        cwd,
+        exit_code: run.exit_code,
+        duration_ms: run.duration_ms,
+        ran_at: run.ran_at,
+        os: os_description(),
+        tools,
+        redacted_variables,
+        output_missing: output.is_none(),
+    };
+
+    let staging = std::env::temp_dir()
+        .join(format!("ai-terminal-{}-{}", name, uuid::Uuid::new_v4()))
+        .join(&name);
+    let write = |file: &str, contents: &str| {
+        fs::write(staging.join(file), contents)
+            .map_err(|e| format!("Failed to write {}: {}", file, e))
+    };
+    let result = fs::create_dir_all(&staging)
+        .map_err(|e| format!("Failed to create {}: {}", staging.display(), e))
+        .and_then(|_| write("README.md", &render_readme(&bundle, output.as_deref())))
+        .and_then(|_| {
+            write(
+                "bundle.json",
+                &serde_json::to_string_pretty(&bundle).map_err(|e| e.to_string())?,
+            )
+        })
+        .and_then(|_| {
+            let lines: Vec<String> = environment
+                .iter()
+                .map(|(name, value)| format!("{}={}", name, value))
+                .collect();
+            write("environment.txt", &(lines.join("\n") + "\n"))
+        })
+        .and_then(|_| write("output.txt", output.as_deref().unwrap_or_default()))
+        .and_then(|_| {
+            if let Some(parent) = target.parent() {
+                fs::create_dir_all(parent)
+                    .map_err(|e| format!("Failed to create {}: {}", parent.display(), e))?;
+            }
+            write_archive(&staging, &target)
+        });
+    if let Some(staging_root) = staging.parent() {
+        let _ = fs::remove_dir_all(staging_root);
+    }
+    result.map(|_| bundle)
+}
+
+// Packs the session's last failed command with its directory, redacted environment, tool
+// versions and output into a .tar.gz (under the data directory unless `path` is given) that
+// can be attached to an issue
+#[command]
+pub async fn capture_repro_bundle<R: Runtime>(
+    session_id: String,
+    path: Option<String>,
+    app_handle: AppHandle<R>,
+) -> Result<ReproBundle, String> {
+    tauri::async_runtime::spawn_blocking(move || build_repro_bundle(&app_handle, &session_id, path))
+        .await
+        .map_err(|e| e.to_string())?
+}
@@ -0,0 +1,279 @@
+use crate::command::types::scrollback_manager::ScrollbackManager;
+use crate::command::types::share_manager::{ShareManager, SharedSession};
+use serde::Serialize;
+use std::io::Write;
+use std::net::{TcpListener, TcpStream};
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::sync::Arc;
+use std::thread;
+use std::time::Duration;
+use tauri::{command, AppHandle, Emitter, Manager, Runtime, State};
+use tungstenite::Message;
+
+const VIEWER_HTML: &str = r#"<!DOCTYPE html>
+<html>
+<head>
+<meta charset="utf-8">
+<title>AI Terminal - shared session</title>
+<style>
+body { background: #1e1e1e; color: #d4d4d4; font-family: Menlo, Consolas, monospace; margin: 1em; }
+pre { white-space: pre-wrap; }
+.input { color: #569cd6; font-weight: bold; }
+.stderr { color: #f14c4c; }
+</style>
+</head>
+<body>
+<pre id="out"></pre>
+<script>
+const out = document.getElementById('out');
+// The token comes in the fragment, which browsers never send; it is dropped from the address
+// bar and passed as the first WebSocket message
+const token = decodeURIComponent(location.hash.slice(1));
+history.replaceState(null, '', location.pathname);
+const ws = new WebSocket('ws://' + location.host + '/ws');
+ws.onopen = () => ws.send(token);
+const strip = (s) => s
+  .replace(/\x1b\][^\x07\x1b]*(\x07|\x1b\\)/g, '')
+  .replace(/\x1b\[[0-9;?]*[ -\/]*[@-~]/g, '')
+  .replace(/\r(?!\n)/g, '');
+ws.onmessage = (e) => {
+  const entry = JSON.parse(e.data);
+  const span = document.createElement('span');
+  span.className = entry.stream;
+  span.textContent = entry.stream === 'input' ? '$ ' + entry.data + '\n' : strip(entry.data);
+  out.appendChild(span);
+  window.scrollTo(0, document.body.scrollHeight);
+};
+ws.onclose = () => out.appendChild(document.createTextNode('\n[sharing ended]\n'));
+</script>
+</body>
+</html>
+"#;
+
+#[derive(Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct ShareInfo {
+    pub session_id: String,
+    pub viewer_url: String,
+    pub websocket_url: String,
+    pub token: String,
+    pub viewers: usize,
+}
+
+// The viewer page gets the token in its fragment; WebSocket clients send it as their first
+// message. It is never part of a request line, so it does not end up in logs or proxies.
+fn share_info(session_id: &str, share: &SharedSession) -> ShareInfo {
+    ShareInfo {
+        session_id: session_id.to_string(),
+        viewer_url: format!("http://{}/#{}", share.address, share.token),
+        websocket_url: format!("ws://{}/ws", share.address),
+        token: share.token.clone(),
+        viewers: share.viewers.load(Ordering::SeqCst),
+    }
+}
+
+// Takes as long wherever the first difference is, so the token cannot be guessed byte by byte
+// from response times
+fn tokens_match(given: &[u8], expected: &[u8]) -> bool {
+    let difference = given
+        .iter()
+        .zip(expected)
+        .fold(0u8, |difference, (a, b)| difference | (a ^ b));
+    given.len() == expected.len() && std::hint::black_box(difference) == 0
+}
+
+// Streams a session's output read-only to token-authenticated viewers. The server only listens
+// on 127.0.0.1: the stream is plain HTTP/WebSocket, so viewers on other machines should come in
+// through an SSH tunnel (`ssh -L <port>:127.0.0.1:<port>`).
+#[command]
+pub fn start_sharing<R: Runtime>(
+    session_id: String,
+    port: Option<u16>,
+    app_handle: AppHandle<R>,
+    share_manager: State<'_, ShareManager>,
+) -> Result<ShareInfo, String> {
+    let mut shares = share_manager.shares.lock().map_err(|e| e.to_string())?;
+    if let Some(existing) = shares.get(&session_id) {
+        return Ok(share_info(&session_id, existing));
+    }
+
+    let listener = TcpListener::bind(("127.0.0.1", port.unwrap_or(0)))
+        .map_err(|e| format!("Failed to start sharing server: {}", e))?;
+    listener
+        .set_nonblocking(true)
+        .map_err(|e| format!("Failed to configure sharing server: {}", e))?;
+    let address = listener.local_addr().map_err(|e| e.to_string())?;
+
+    let share = SharedSession {
+        token: uuid::Uuid::new_v4().simple().to_string(),
+        address,
+        stop: Arc::new(AtomicBool::new(false)),
+        viewers: Arc::new(AtomicUsize::new(0)),
+    };
+
+    let token = share.token.clone();
+    let stop = share.stop.clone();
+    let viewers = share.viewers.clone();
+    let session_id_for_listener = session_id.clone();
+    thread::spawn(move || {
+        while !stop.load(Ordering::SeqCst) {
+            match listener.accept() {
+                Ok((stream, _)) => {
+                    let app_handle = app_handle.clone();
+                    let token = token.clone();
+                    let stop = stop.clone();
+                    let viewers = viewers.clone();
+                    let session_id = session_id_for_listener.clone();
+                    thread::spawn(move || {
+                        handle_viewer(stream, &token, &session_id, &app_handle, &stop, &viewers)
+                    });
+                }
+                Err(_) => thread::sleep(Duration::from_millis(100)),
+            }
+        }
+    });
+
+    let info = share_info(&session_id, &share);
+    shares.insert(session_id, share);
+    Ok(info)
+}
+
+#[command]
+pub fn stop_sharing(
+    session_id: String,
+    share_manager: State<'_, ShareManager>,
+) -> Result<(), String> {
+    let mut shares = share_manager.shares.lock().map_err(|e| e.to_string())?;
+    let share = shares
+        .remove(&session_id)
+        .ok_or_else(|| format!("Session '{}' is not being shared", session_id))?;
+    share.stop.store(true, Ordering::SeqCst);
+    Ok(())
+}
+
+#[command]
+pub fn list_shared_sessions(
+    share_manager: State<'_, ShareManager>,
+) -> Result<Vec<ShareInfo>, String> {
+    let shares = share_manager.shares.lock().map_err(|e| e.to_string())?;
+    Ok(shares
+        .iter()
+        .map(|(session_id, share)| share_info(session_id, share))
+        .collect())
+}
+
+fn handle_viewer<R: Runtime>(
+    mut stream: TcpStream,
+    token: &str,
+    session_id: &str,
+    app_handle: &AppHandle<R>,
+    stop: &AtomicBool,
+    viewers: &AtomicUsize,
+) {
+    // Accepted sockets inherit non-blocking mode on some platforms
+    let _ = stream.set_nonblocking(false);
+    let _ = stream.set_read_timeout(Some(Duration::from_secs(5)));
+
+    // Peek at the request head so the WebSocket handshake can still consume it
+    let mut head = [0u8; 4096];
+    let read = match stream.peek(&mut head) {
+        Ok(read) => read,
+        Err(_) => return,
+    };
+    let request = String::from_utf8_lossy(&head[..read]).to_string();
+    let request_line = request.lines().next().unwrap_or_default();
+    let target = request_line.split_whitespace().nth(1).unwrap_or_default();
+    let path = target.split('?').next().unwrap_or_default();
+
+    let is_websocket = request
+        .to_lowercase()
+        .lines()
+        .any(|line| line.starts_with("upgrade:") && line.contains("websocket"));
+    if !is_websocket {
+        // The page holds no session data; it only connects with the token from its fragment
+        if path != "/" {
+            let _ = stream.write_all(
+                b"HTTP/1.1 404 Not Found\r\nContent-Length: 0\r\nConnection: close\r\n\r\n",
+            );
+            return;
+        }
+        let response = format!(
+            "HTTP/1.1 200 OK\r\nContent-Type: text/html; charset=utf-8\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+            VIEWER_HTML.len(),
+            VIEWER_HTML
+        );
+        let _ = stream.write_all(response.as_bytes());
+        return;
+    }
+
+    let mut socket = match tungstenite::accept(stream) {
+        Ok(socket) => socket,
+        Err(_) => return,
+    };
+    // Nothing is sent until the first message, within the read timeout, is the token
+    let authenticated = matches!(
+        socket.read(),
+        Ok(Message::Text(given)) if tokens_match(given.as_bytes(), token.as_bytes())
+    );
+    if !authenticated {
+        let _ = socket.close(None);
+        let _ = socket.flush();
+        return;
+    }
+
+    let scrollback = app_handle.state::<ScrollbackManager>();
+    let backlog = scrollback.snapshot(session_id).unwrap_or_default();
+    let receiver = match scrollback.subscribe(session_id) {
+        Ok(receiver) => receiver,
+        Err(_) => return,
+    };
+
+    let viewer_count = viewers.fetch_add(1, Ordering::SeqCst) + 1;
+    let _ = app_handle.emit(
+        "share_viewer_changed",
+        serde_json::json!({ "sessionId": session_id, "viewers": viewer_count }),
+    );
+
+    let mut connected = backlog.iter().all(|entry| {
+        serde_json::to_string(entry)
+            .map(|json| socket.send(Message::Text(json)).is_ok())
+            .unwrap_or(true)
+    });
+
+    let _ = socket
+        .get_ref()
+        .set_read_timeout(Some(Duration::from_millis(100)));
+    while connected {
+        if stop.load(Ordering::SeqCst) {
+            let _ = socket.close(None);
+            let _ = socket.flush();
+            break;
+        }
+
+        // Viewers are read-only: anything they send besides close frames is ignored
+        match socket.read() {
+            Ok(Message::Close(_)) => break,
+            Ok(_) => {}
+            Err(tungstenite::Error::Io(e))
+                if e.kind() == std::io::ErrorKind::WouldBlock
+                    || e.kind() == std::io::ErrorKind::TimedOut => {}
+            Err(_) => break,
+        }
+
+        while let Ok(entry) = receiver.try_recv() {
+            let sent = serde_json::to_string(&entry)
+                .map(|json| socket.send(Message::Text(json)).is_ok())
+                .unwrap_or(true);
+            if !sent {
+                connected = false;
+                break;
+            }
+        }
+    }
+
+    let viewer_count = viewers.fetch_sub(1, Ordering::SeqCst).saturating_sub(1);
+    let _ = app_handle.emit(
+        "share_viewer_changed",
+        serde_json::json!({ "sessionId": session_id, "viewers": viewer_count }),
+    );
+}
@@ -0,0 +1,229 @@
+use serde::Serialize;
+use std::fs;
+use std::path::{Path, PathBuf};
+use tauri::command;
+
+const BLOCK_START: &str = "# >>> ai-terminal shell integration >>>";
+const BLOCK_END: &str = "# <<< ai-terminal shell integration <<<";
+
+// Reports the working directory (OSC 7), appends/reloads history after each
+// prompt, and emits OSC 133 prompt/command marks (A: prompt, B: input, C: output, D: exit).
+const BASH_SNIPPET: &str = r#"if [[ $- == *i* ]] && [[ -z "$__AI_TERMINAL_INTEGRATION" ]]; then
+  __AI_TERMINAL_INTEGRATION=1
+  __ai_terminal_precmd() {
+    local ret=$?
+    printf '\033]133;D;%s\007' "$ret"
+    printf '\033]7;file://%s%s\007' "$HOSTNAME" "$PWD"
+    history -a
+    history -n
+    printf '\033]133;A\007'
+    return $ret
+  }
+  PROMPT_COMMAND="__ai_terminal_precmd${PROMPT_COMMAND:+;$PROMPT_COMMAND}"
+  PS1="${PS1}\[\e]133;B\a\]"
+  PS0="${PS0}\e]133;C\a"
+fi"#;
+
+const ZSH_SNIPPET: &str = r#"if [[ -o interactive ]] && [[ -z "$__AI_TERMINAL_INTEGRATION" ]]; then
+  __AI_TERMINAL_INTEGRATION=1
+  setopt INC_APPEND_HISTORY SHARE_HISTORY
+  __ai_terminal_precmd() {
+    local ret=$?
+    printf '\033]133;D;%s\007' "$ret"
+    printf '\033]7;file://%s%s\007' "$HOST" "$PWD"
+    printf '\033]133;A\007'
+  }
+  __ai_terminal_preexec() {
+    printf '\033]133;C\007'
+  }
+  autoload -Uz add-zsh-hook
+  add-zsh-hook precmd __ai_terminal_precmd
+  add-zsh-hook preexec __ai_terminal_preexec
+  PS1="${PS1}%{$(printf '\033]133;B\007')%}"
+fi"#;
+
+const FISH_SNIPPET: &str = r#"if status is-interactive; and not set -q __AI_TERMINAL_INTEGRATION
+  set -g __AI_TERMINAL_INTEGRATION 1
+  function __ai_terminal_prompt --on-event fish_prompt
+    set -l ret $status
+    printf '\e]133;D;%s\a' $ret
+    printf '\e]7;file://%s%s\a' (hostname) $PWD
+    history merge
+    printf '\e]133;A\a'
+  end
+  function __ai_terminal_preexec --on-event fish_preexec
+    printf '\e]133;C\a'
+  end
+end"#;
+
+#[derive(Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct ShellIntegrationStatus {
+    pub shell: String,
+    pub config_path: String,
+    pub installed: bool,
+}
+
+#[derive(Clone, Copy)]
+enum SupportedShell {
+    Bash,
+    Zsh,
+    Fish,
+}
+
+impl SupportedShell {
+    fn parse(name: &str) -> Result<Self, String> {
+        // Accept both plain names ("zsh") and paths ("/bin/zsh")
+        let base = name.rsplit('/').next().unwrap_or(name).trim();
+        match base {
+            "bash" => Ok(SupportedShell::Bash),
+            "zsh" => Ok(SupportedShell::Zsh),
+            "fish" => Ok(SupportedShell::Fish),
+            other => Err(format!("Unsupported shell for integration: {}", other)),
+        }
+    }
+
+    fn name(&self) -> &'static str {
+        match self {
+            SupportedShell::Bash => "bash",
+            SupportedShell::Zsh => "zsh",
+            SupportedShell::Fish => "fish",
+        }
+    }
+
+    fn snippet(&self) -> &'static str {
+        match self {
+            SupportedShell::Bash => BASH_SNIPPET,
+            SupportedShell::Zsh => ZSH_SNIPPET,
+            SupportedShell::Fish => FISH_SNIPPET,
+        }
+    }
+
+    fn config_path(&self) -> Result<PathBuf, String> {
+        let home = dirs::home_dir().ok_or("Could not determine home directory")?;
+        Ok(match self {
+            SupportedShell::Bash => home.join(".bashrc"),
+            SupportedShell::Zsh => std::env::var("ZDOTDIR")
+                .map(PathBuf::from)
+                .unwrap_or(home)
+                .join(".zshrc"),
+            SupportedShell::Fish => home.join(".config").join("fish").join("config.fish"),
+        })
+    }
+}
+
+// Resolve the requested shell, falling back to the user's login shell
+fn resolve_shell(shell: Option<String>) -> Result<SupportedShell, String> {
+    match shell {
+        Some(name) if !name.trim().is_empty() => SupportedShell::parse(&name),
+        _ => {
+            let login_shell = std::env::var("SHELL").unwrap_or_else(|_| "/bin/bash".to_string());
+            SupportedShell::parse(&login_shell)
+        }
+    }
+}
+
+fn render_block(shell: SupportedShell) -> String {
+    format!("{}\n{}\n{}\n", BLOCK_START, shell.snippet(), BLOCK_END)
+}
+
+// Returns the config contents with any existing integration block removed. A start marker
+// without an end marker after it is an error: everything below it would be lost, so the file
+// has to be fixed by hand.
+fn strip_block(contents: &str, path: &Path) -> Result<String, String> {
+    let lines: Vec<&str> = contents.lines().collect();
+    let mut result = String::with_capacity(contents.len());
+    let mut index = 0;
+    while index < lines.len() {
+        if lines[index].trim() == BLOCK_START {
+            let end = lines[index + 1..]
+                .iter()
+                .position(|line| line.trim() == BLOCK_END)
+                .ok_or_else(|| {
+                    format!(
+                        "{} has a '{}' line without a matching '{}'; remove the partial block \
+                         by hand and try again",
+                        path.display(),
+                        BLOCK_START,
+                        BLOCK_END
+                    )
+                })?;
+            index += end + 2;
+            continue;
+        }
+        result.push_str(lines[index]);
+        result.push('\n');
+        index += 1;
+    }
+    Ok(result)
+}
+
+fn status_for(shell: SupportedShell) -> Result<ShellIntegrationStatus, String> {
+    let path = shell.config_path()?;
+    let installed = fs::read_to_string(&path)
+        .map(|contents| contents.lines().any(|line| line.trim() == BLOCK_START))
+        .unwrap_or(false);
+    Ok(ShellIntegrationStatus {
+        shell: shell.name().to_string(),
+        config_path: path.to_string_lossy().to_string(),
+        installed,
+    })
+}
+
+#[command]
+pub fn detect_shell_integration(shell: Option<String>) -> Result<ShellIntegrationStatus, String> {
+    status_for(resolve_shell(shell)?)
+}
+
+#[command]
+pub fn install_shell_integration(shell: Option<String>) -> Result<ShellIntegrationStatus, String> {
+    let shell = resolve_shell(shell)?;
+    let path = shell.config_path()?;
+
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)
+            .map_err(|e| format!("Failed to create {}: {}", parent.display(), e))?;
+    }
+
+    let existing = if path.exists() {
+        fs::read_to_string(&path)
+            .map_err(|e| format!("Failed to read {}: {}", path.display(), e))?
+    } else {
+        String::new()
+    };
+
+    // Re-installing replaces the previous block so snippet updates are picked up
+    let mut contents = strip_block(&existing, &path)?;
+    if !contents.is_empty() && !contents.ends_with("\n\n") {
+        contents.push('\n');
+    }
+    contents.push_str(&render_block(shell));
+
+    fs::write(&path, contents).map_err(|e| format!("Failed to write {}: {}", path.display(), e))?;
+    status_for(shell)
+}
+
+#[command]
+pub fn uninstall_shell_integration(
+    shell: Option<String>,
+) -> Result<ShellIntegrationStatus, String> {
+    let shell = resolve_shell(shell)?;
+    let path = shell.config_path()?;
+
+    if path.exists() {
+        let existing = fs::read_to_string(&path)
+            .map_err(|e| format!("Failed to read {}: {}", path.display(), e))?;
+        if existing.lines().any(|line| line.trim() == BLOCK_START) {
+            let stripped = strip_block(&existing, &path)?;
+            let cleaned = if stripped.trim().is_empty() {
+                String::new()
+            } else {
+                format!("{}\n", stripped.trim_end())
+            };
+            fs::write(&path, cleaned)
+                .map_err(|e| format!("Failed to write {}: {}", path.display(), e))?;
+        }
+    }
+
+    status_for(shell)
+}
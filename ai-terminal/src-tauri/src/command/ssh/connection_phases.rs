@@ -0,0 +1,193 @@
+use crate::command::windows::session_windows::emit_session_event;
+use serde::Serialize;
+use std::sync::{Arc, Mutex};
+use std::time::Instant;
+use tauri::{AppHandle, Runtime};
+
+// Steps of an SSH connection, in the order ssh goes through them
+#[derive(Serialize, Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+#[serde(rename_all = "snake_case")]
+pub enum SshConnectionPhase {
+    Resolving,
+    TcpConnected,
+    Authenticating,
+    ChannelOpen,
+    Ready,
+}
+
+#[derive(Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct SshConnectionPhaseEvent {
+    pub session_id: String,
+    pub pid: u32,
+    pub phase: SshConnectionPhase,
+    // Since ssh was spawned, and since the previous phase
+    pub elapsed_ms: u64,
+    pub phase_ms: u64,
+    // Address connected to, or the authentication method that succeeded
+    pub detail: Option<String>,
+}
+
+// What `ssh -v` prints that only the phase tracker is interested in
+const VERBOSE_PREFIXES: &[&str] = &[
+    "debug1:",
+    "OpenSSH_",
+    "Authenticated to ",
+    "Transferred: sent",
+    "Bytes per second:",
+];
+
+fn is_verbose_line(line: &str) -> bool {
+    VERBOSE_PREFIXES
+        .iter()
+        .any(|prefix| line.starts_with(prefix))
+}
+
+// An unterminated line that may still turn out to be verbose output
+fn may_be_verbose_line(partial: &str) -> bool {
+    VERBOSE_PREFIXES
+        .iter()
+        .any(|prefix| prefix.starts_with(partial) || partial.starts_with(prefix))
+}
+
+// Text between the first pair of quotes, e.g. the method in `using "publickey".`
+fn quoted(line: &str) -> Option<String> {
+    let start = line.find('"')? + 1;
+    let end = start + line[start..].find('"')?;
+    Some(line[start..end].to_string())
+}
+
+// "Connecting to host [address] port 22." - resolution is done, the TCP connect follows
+fn connecting_address(message: &str) -> Option<String> {
+    let rest = message.strip_prefix("Connecting to ")?;
+    let (_, rest) = rest.split_once('[')?;
+    Some(rest.split_once(']')?.0.to_string())
+}
+
+// The phase a verbose line reports reaching. A session multiplexed over an existing control
+// master goes straight to ready.
+fn phase_of_line(message: &str) -> Option<SshConnectionPhase> {
+    if message.starts_with("Connection established") {
+        Some(SshConnectionPhase::TcpConnected)
+    } else if message.starts_with("Authenticating to ")
+        || message.starts_with("SSH2_MSG_SERVICE_ACCEPT")
+    {
+        Some(SshConnectionPhase::Authenticating)
+    } else if message.starts_with("channel 0: new") {
+        Some(SshConnectionPhase::ChannelOpen)
+    } else if message.starts_with("Entering interactive session")
+        || message.starts_with("mux_client_request_session: master session id")
+    {
+        Some(SshConnectionPhase::Ready)
+    } else {
+        None
+    }
+}
+
+// Follows `ssh -v` stderr, emitting ssh_connection_phase as the connection progresses and
+// keeping the verbose lines out of the session's output
+pub struct SshPhaseTracker {
+    session_id: String,
+    pid: u32,
+    started_at: Instant,
+    phase_started_at: Instant,
+    reached: Arc<Mutex<Option<SshConnectionPhase>>>,
+    // Verbose lines the user asked for with their own -v are shown as well
+    show_verbose: bool,
+    pending: String,
+    address: Option<String>,
+    auth_method: Option<String>,
+}
+
+impl SshPhaseTracker {
+    pub fn new(session_id: &str, pid: u32, started_at: Instant, show_verbose: bool) -> Self {
+        Self {
+            session_id: session_id.to_string(),
+            pid,
+            started_at,
+            phase_started_at: started_at,
+            reached: Arc::new(Mutex::new(None)),
+            show_verbose,
+            pending: String::new(),
+            address: None,
+            auth_method: None,
+        }
+    }
+
+    // Shared with the wait thread, which reports how far a failed connection got
+    pub fn reached(&self) -> Arc<Mutex<Option<SshConnectionPhase>>> {
+        self.reached.clone()
+    }
+
+    pub fn start<R: Runtime>(&mut self, app_handle: &AppHandle<R>) {
+        self.advance(app_handle, SshConnectionPhase::Resolving, None);
+    }
+
+    fn advance<R: Runtime>(
+        &mut self,
+        app_handle: &AppHandle<R>,
+        phase: SshConnectionPhase,
+        detail: Option<String>,
+    ) {
+        {
+            let Ok(mut reached) = self.reached.lock() else {
+                return;
+            };
+            // Phases only move forward; ssh repeats some messages (e.g. per address tried)
+            if reached.is_some_and(|reached| reached >= phase) {
+                return;
+            }
+            *reached = Some(phase);
+        }
+        let now = Instant::now();
+        emit_session_event(
+            app_handle,
+            &self.session_id,
+            "ssh_connection_phase",
+            SshConnectionPhaseEvent {
+                session_id: self.session_id.clone(),
+                pid: self.pid,
+                phase,
+                elapsed_ms: now.duration_since(self.started_at).as_millis() as u64,
+                phase_ms: now.duration_since(self.phase_started_at).as_millis() as u64,
+                detail,
+            },
+        );
+        self.phase_started_at = now;
+    }
+
+    // Returns the part of a stderr chunk that belongs in the session's output
+    pub fn observe<R: Runtime>(&mut self, app_handle: &AppHandle<R>, chunk: &str) -> String {
+        self.pending.push_str(chunk);
+        let mut shown = String::new();
+        while let Some(newline) = self.pending.find('\n') {
+            let line: String = self.pending.drain(..=newline).collect();
+            let trimmed = line.trim_end_matches(['\r', '\n']);
+            if !is_verbose_line(trimmed) {
+                shown.push_str(&line);
+                continue;
+            }
+            let message = trimmed.strip_prefix("debug1: ").unwrap_or(trimmed);
+            if let Some(address) = connecting_address(message) {
+                self.address = Some(address);
+            } else if message.starts_with("Authenticated to ") {
+                self.auth_method = quoted(message);
+            } else if let Some(phase) = phase_of_line(message) {
+                let detail = match phase {
+                    SshConnectionPhase::TcpConnected => self.address.take(),
+                    SshConnectionPhase::ChannelOpen => self.auth_method.take(),
+                    _ => None,
+                };
+                self.advance(app_handle, phase, detail);
+            }
+            if self.show_verbose {
+                shown.push_str(&line);
+            }
+        }
+        // Prompts without a newline must not wait for one
+        if !self.pending.is_empty() && !may_be_verbose_line(&self.pending) {
+            shown.push_str(&std::mem::take(&mut self.pending));
+        }
+        shown
+    }
+}
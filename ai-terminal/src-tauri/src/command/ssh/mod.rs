@@ -0,0 +1,2 @@
+pub mod connection_phases;
+pub mod remote_exec;
@@ -0,0 +1,225 @@
+use crate::audit::audit_log::record_audit_event;
+use crate::command::constants::{
+    MAX_SSH_EXEC_OUTPUT_BYTES, SSH_CONTROL_PATH, SSH_EXEC_CONNECT_TIMEOUT_SECS, SSH_EXEC_TIMEOUT_MS,
+};
+use crate::command::core::protected_sessions::{is_session_protected, require_command_gates};
+use crate::command::types::command_manager::CommandManager;
+use crate::history::host_history::ssh_host;
+use crate::i18n::types::app_error::AppError;
+use crate::settings::types::settings_manager::SettingsManager;
+use crate::utils::env_scrubbing::scrubbed_environment;
+use serde::Serialize;
+use std::collections::HashMap;
+use std::io::Read;
+use std::process::{Command, Stdio};
+use std::thread;
+use std::time::{Duration, Instant};
+use tauri::{command, AppHandle, Manager, Runtime};
+
+#[derive(Serialize, Clone, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct SshExecResult {
+    pub stdout: String,
+    pub stderr: String,
+    // None when the remote command was killed by a signal or timed out
+    pub exit_code: Option<i32>,
+    pub duration_ms: u64,
+    // Output beyond MAX_SSH_EXEC_OUTPUT_BYTES was dropped
+    pub truncated: bool,
+}
+
+// ssh options and destination for a connection: the session id of an interactive SSH session,
+// or a `[user@]host` destination
+fn ssh_target(command_manager: &CommandManager, connection: &str) -> Result<Vec<String>, String> {
+    let states = command_manager.commands.lock().map_err(|e| e.to_string())?;
+    if let Some(state) = states.get(connection) {
+        return match &state.ssh_target {
+            Some(target) if state.is_ssh_session_active => Ok(target.clone()),
+            _ => Err(format!(
+                "Session {} has no interactive SSH session",
+                connection
+            )),
+        };
+    }
+    let destination = connection.trim();
+    if destination.is_empty()
+        || destination.starts_with('-')
+        || destination.contains(char::is_whitespace)
+    {
+        return Err(format!("'{}' is not an SSH destination", connection));
+    }
+    Ok(vec![destination.to_string()])
+}
+
+// The session whose gates apply to a command run on `host`: a protected session with an
+// interactive SSH session to the host, so naming the host instead of the session does not get
+// around its protection; otherwise `connection` itself
+fn gating_session<R: Runtime>(
+    app_handle: &AppHandle<R>,
+    connection: &str,
+    host: Option<&str>,
+) -> Result<String, String> {
+    if is_session_protected(app_handle, connection) {
+        return Ok(connection.to_string());
+    }
+    let Some(host) = host else {
+        return Ok(connection.to_string());
+    };
+    let mut sessions_on_host: Vec<String> = {
+        let command_manager = app_handle.state::<CommandManager>();
+        let states = command_manager.commands.lock().map_err(|e| e.to_string())?;
+        states
+            .iter()
+            .filter(|(_, state)| {
+                state.is_ssh_session_active
+                    && state.ssh_target.as_deref().and_then(ssh_host).as_deref() == Some(host)
+            })
+            .map(|(session_id, _)| session_id.clone())
+            .collect()
+    };
+    sessions_on_host.sort();
+    Ok(sessions_on_host
+        .into_iter()
+        .find(|session_id| is_session_protected(app_handle, session_id))
+        .unwrap_or_else(|| connection.to_string()))
+}
+
+// Reads a pipe to the end, keeping at most MAX_SSH_EXEC_OUTPUT_BYTES
+fn read_capped(mut pipe: impl Read) -> (Vec<u8>, bool) {
+    let mut kept = Vec::new();
+    let mut truncated = false;
+    let mut buffer = [0; 8192];
+    while let Ok(n) = pipe.read(&mut buffer) {
+        if n == 0 {
+            break;
+        }
+        let room = MAX_SSH_EXEC_OUTPUT_BYTES.saturating_sub(kept.len());
+        truncated |= n > room;
+        kept.extend_from_slice(&buffer[..n.min(room)]);
+    }
+    (kept, truncated)
+}
+
+// Runs a command over its own exec channel, without a TTY or stdin. With an interactive session
+// to the same host the channel goes through its control master, so no new login is needed;
+// the user's remote shell never sees the command or its output.
+pub fn run_ssh_exec(
+    target: &[String],
+    command: &str,
+    env_map: &HashMap<String, String>,
+    timeout: Duration,
+) -> Result<SshExecResult, AppError> {
+    let started_at = Instant::now();
+    let mut child = Command::new("ssh")
+        .args(["-T", "-o", "BatchMode=yes", "-o", "ControlMaster=no"])
+        .arg("-o")
+        .arg(format!("ControlPath={}", SSH_CONTROL_PATH))
+        .arg("-o")
+        .arg(format!("ConnectTimeout={}", SSH_EXEC_CONNECT_TIMEOUT_SECS))
+        .args(target)
+        .arg(command)
+        .env_clear()
+        .envs(env_map)
+        .stdin(Stdio::null())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .map_err(|e| format!("Failed to run ssh: {}", e))?;
+
+    let stdout_reader = child
+        .stdout
+        .take()
+        .map(|pipe| thread::spawn(move || read_capped(pipe)));
+    let stderr_reader = child
+        .stderr
+        .take()
+        .map(|pipe| thread::spawn(move || read_capped(pipe)));
+    let deadline = started_at + timeout;
+    let status = loop {
+        match child.try_wait().map_err(|e| e.to_string())? {
+            Some(status) => break Some(status),
+            None if Instant::now() >= deadline => {
+                let _ = child.kill();
+                let _ = child.wait();
+                break None;
+            }
+            None => thread::sleep(Duration::from_millis(20)),
+        }
+    };
+    let collect = |reader: Option<thread::JoinHandle<(Vec<u8>, bool)>>| {
+        reader
+            .and_then(|reader| reader.join().ok())
+            .map(|(bytes, truncated)| (String::from_utf8_lossy(&bytes).to_string(), truncated))
+            .unwrap_or_default()
+    };
+    let (stdout, stdout_truncated) = collect(stdout_reader);
+    let (stderr, stderr_truncated) = collect(stderr_reader);
+
+    let Some(status) = status else {
+        return Err(format!(
+            "'{}' did not finish within {} ms",
+            command,
+            timeout.as_millis()
+        )
+        .into());
+    };
+    // 255 is ssh's own failure; remote commands can exit with it too, so only known causes count
+    if status.code() == Some(255) {
+        if let Some(error) = AppError::from_ssh_output(&stderr) {
+            return Err(error);
+        }
+    }
+    Ok(SshExecResult {
+        stdout,
+        stderr,
+        exit_code: status.code(),
+        duration_ms: started_at.elapsed().as_millis() as u64,
+        truncated: stdout_truncated || stderr_truncated,
+    })
+}
+
+// Runs `command` on the host of `connection` (a session id or `[user@]host`) and returns its
+// output, for remote completion, git status or file checks that must not touch the session.
+// Gated like local commands: a dangerous command on the host of a protected session, or one a
+// cost rule holds, fails with the confirmation marker until it is run again with the token.
+#[command]
+pub async fn ssh_exec<R: Runtime>(
+    connection: String,
+    command: String,
+    timeout_ms: Option<u64>,
+    confirmation_token: Option<String>,
+    app_handle: AppHandle<R>,
+) -> Result<SshExecResult, AppError> {
+    let command = command.trim().to_string();
+    if command.is_empty() {
+        return Err("No command to run".into());
+    }
+    let target = ssh_target(&app_handle.state::<CommandManager>(), &connection)?;
+    let target_host = ssh_host(&target);
+    let gating_session = gating_session(&app_handle, &connection, target_host.as_deref())?;
+    require_command_gates(
+        &app_handle,
+        &gating_session,
+        &command,
+        confirmation_token.as_deref(),
+    )?;
+    let env_map = scrubbed_environment(&app_handle.state::<SettingsManager>().current());
+    let timeout = Duration::from_millis(timeout_ms.unwrap_or(SSH_EXEC_TIMEOUT_MS));
+    let host = target_host.unwrap_or_else(|| connection.clone());
+    let audited_command = command.clone();
+    let result = tauri::async_runtime::spawn_blocking(move || {
+        run_ssh_exec(&target, &command, &env_map, timeout)
+    })
+    .await
+    .map_err(|e| e.to_string())?;
+    // Audited like commands sent to an SSH session, with the host in place of the directory
+    record_audit_event(
+        &app_handle,
+        &connection,
+        &audited_command,
+        &host,
+        true,
+        result.as_ref().ok().and_then(|result| result.exit_code),
+    );
+    result
+}
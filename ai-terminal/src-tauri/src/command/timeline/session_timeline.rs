@@ -0,0 +1,84 @@
+use crate::command::types::timeline_manager::{TimelineEvent, TimelineEventKind, TimelineManager};
+use crate::utils::file_system_utils::expand_home;
+use serde_json::Value;
+use std::fs;
+use tauri::{command, AppHandle, Manager, Runtime, State};
+
+// Convenience for call sites that only hold an AppHandle (worker threads, async commands)
+pub fn record_timeline_event<R: Runtime>(
+    app_handle: &AppHandle<R>,
+    session_id: &str,
+    kind: TimelineEventKind,
+    summary: &str,
+    details: Value,
+) {
+    app_handle
+        .state::<TimelineManager>()
+        .record(session_id, kind, summary, details);
+}
+
+#[command]
+pub fn get_session_timeline(
+    session_id: String,
+    since: Option<u64>,
+    timeline_manager: State<'_, TimelineManager>,
+) -> Result<Vec<TimelineEvent>, String> {
+    timeline_manager.events(&session_id, since)
+}
+
+#[command]
+pub fn export_session_timeline(
+    session_id: String,
+    format: String,
+    path: String,
+    since: Option<u64>,
+    timeline_manager: State<'_, TimelineManager>,
+) -> Result<String, String> {
+    let events = timeline_manager.events(&session_id, since)?;
+    if events.is_empty() {
+        return Err(format!("No activity recorded for session '{}'", session_id));
+    }
+
+    let document = match format.to_lowercase().as_str() {
+        "json" => serde_json::to_string_pretty(&events).map_err(|e| e.to_string())?,
+        "jsonl" => events
+            .iter()
+            .map(|event| serde_json::to_string(event).map_err(|e| e.to_string()))
+            .collect::<Result<Vec<_>, _>>()?
+            .join("\n"),
+        "markdown" | "md" => render_markdown(&session_id, &events),
+        other => return Err(format!("Unsupported timeline format: {}", other)),
+    };
+
+    let target = expand_home(&path)?;
+    if let Some(parent) = target.parent() {
+        fs::create_dir_all(parent)
+            .map_err(|e| format!("Failed to create {}: {}", parent.display(), e))?;
+    }
+    fs::write(&target, document)
+        .map_err(|e| format!("Failed to write timeline to {}: {}", target.display(), e))?;
+    Ok(target.to_string_lossy().to_string())
+}
+
+fn render_markdown(session_id: &str, events: &[TimelineEvent]) -> String {
+    let mut document = format!(
+        "# AI Terminal activity - {}\n\n| Time (UTC) | Event | Summary |\n|---|---|---|\n",
+        session_id
+    );
+    for event in events {
+        let time = chrono::DateTime::from_timestamp_millis(event.timestamp as i64)
+            .map(|t| t.format("%Y-%m-%d %H:%M:%S").to_string())
+            .unwrap_or_default();
+        let kind = serde_json::to_value(event.kind)
+            .ok()
+            .and_then(|v| v.as_str().map(String::from))
+            .unwrap_or_default();
+        document.push_str(&format!(
+            "| {} | {} | {} |\n",
+            time,
+            kind,
+            event.summary.replace('|', "\\|").replace('\n', " ")
+        ));
+    }
+    document
+}
@@ -0,0 +1,115 @@
+use crate::command::constants::TRANSCRIPT_TAB_WIDTH;
+use crate::command::types::scrollback_manager::{OutputStream, ScrollbackEntry, ScrollbackManager};
+use crate::utils::ansi::{ansi_to_html, escape_html, strip_ansi};
+use crate::utils::cell_width::expand_tabs;
+use crate::utils::file_system_utils::expand_home;
+use std::fs;
+use tauri::{command, State};
+
+#[command]
+pub fn export_transcript(
+    session_id: String,
+    format: String,
+    path: String,
+    scrollback_manager: State<'_, ScrollbackManager>,
+) -> Result<String, String> {
+    let entries = scrollback_manager.snapshot(&session_id)?;
+    if entries.is_empty() {
+        return Err(format!("No output recorded for session '{}'", session_id));
+    }
+
+    let document = match format.to_lowercase().as_str() {
+        "html" => render_html(&session_id, &entries),
+        "markdown" | "md" => render_markdown(&session_id, &entries),
+        other => return Err(format!("Unsupported transcript format: {}", other)),
+    };
+
+    let target = expand_home(&path)?;
+    if let Some(parent) = target.parent() {
+        fs::create_dir_all(parent)
+            .map_err(|e| format!("Failed to create {}: {}", parent.display(), e))?;
+    }
+    fs::write(&target, document)
+        .map_err(|e| format!("Failed to write transcript to {}: {}", target.display(), e))?;
+
+    Ok(target.to_string_lossy().to_string())
+}
+
+fn render_html(session_id: &str, entries: &[ScrollbackEntry]) -> String {
+    let mut body = String::new();
+    for entry in entries {
+        match entry.stream {
+            OutputStream::Input => body.push_str(&format!(
+                "<span class=\"prompt\">$ {}</span>\n",
+                escape_html(entry.data.trim_end())
+            )),
+            OutputStream::Stderr => body.push_str(&format!(
+                "<span class=\"stderr\">{}</span>",
+                ansi_to_html(&entry.data)
+            )),
+            OutputStream::Trace => body.push_str(&format!(
+                "<span class=\"trace\">{}</span>",
+                escape_html(&entry.data)
+            )),
+            OutputStream::Stdout | OutputStream::Pty => body.push_str(&ansi_to_html(&entry.data)),
+        }
+    }
+
+    format!(
+        "<!DOCTYPE html>\n<html>\n<head>\n<meta charset=\"utf-8\">\n<title>AI Terminal transcript - {}</title>\n<style>\n\
+         body {{ background: #1e1e1e; color: #d4d4d4; margin: 2em; }}\n\
+         pre {{ font-family: Menlo, Consolas, monospace; font-size: 13px; white-space: pre-wrap; }}\n\
+         .prompt {{ color: #569cd6; font-weight: bold; }}\n\
+         .stderr {{ color: #f14c4c; }}\n\
+         .trace {{ color: #808080; }}\n\
+         </style>\n</head>\n<body>\n<pre>\n{}</pre>\n</body>\n</html>\n",
+        escape_html(session_id),
+        body
+    )
+}
+
+fn render_markdown(session_id: &str, entries: &[ScrollbackEntry]) -> String {
+    let mut document = format!("# AI Terminal transcript - {}\n\n", session_id);
+    let mut block = String::new();
+
+    let close_block = |document: &mut String, block: &mut String| {
+        let text = block.replace("\r\n", "\n");
+        if !text.trim().is_empty() {
+            // Use a fence longer than any backtick run in the output
+            let fence = "`".repeat(longest_backtick_run(&text).max(2) + 1);
+            document.push_str(&format!(
+                "{}console\n{}\n{}\n\n",
+                fence,
+                text.trim_end(),
+                fence
+            ));
+        }
+        block.clear();
+    };
+
+    for entry in entries {
+        match entry.stream {
+            OutputStream::Input => {
+                close_block(&mut document, &mut block);
+                block.push_str(&format!("$ {}\n", entry.data.trim_end()));
+            }
+            _ => block.push_str(&expand_tabs(&strip_ansi(&entry.data), TRANSCRIPT_TAB_WIDTH)),
+        }
+    }
+    close_block(&mut document, &mut block);
+    document
+}
+
+fn longest_backtick_run(text: &str) -> usize {
+    let mut longest = 0;
+    let mut current = 0;
+    for c in text.chars() {
+        if c == '`' {
+            current += 1;
+            longest = longest.max(current);
+        } else {
+            current = 0;
+        }
+    }
+    longest
+}
@@ -0,0 +1,45 @@
+use crate::settings::types::app_settings::BellAction;
+use std::collections::HashMap;
+use std::sync::Mutex;
+use std::time::Instant;
+
+// Where the BEL scanner is in the output, kept across reads so a sequence split between
+// two reads is still recognised
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum BellScanState {
+    #[default]
+    Ground,
+    // After ESC
+    Escape,
+    // Inside an OSC (or DCS, APC, PM, SOS) string, where BEL is a terminator and not a bell
+    Osc,
+    // ESC inside such a string, possibly the start of its ST terminator
+    OscEscape,
+}
+
+#[derive(Default)]
+pub struct SessionBell {
+    pub scan: BellScanState,
+    pub rang_at: Option<Instant>,
+}
+
+// Per-session bell overrides of the bellAction setting, and the scanner state of PTY output
+pub struct BellManager {
+    pub overrides: Mutex<HashMap<String, BellAction>>,
+    pub sessions: Mutex<HashMap<String, SessionBell>>,
+}
+
+impl BellManager {
+    pub fn new() -> Self {
+        Self {
+            overrides: Mutex::new(HashMap::new()),
+            sessions: Mutex::new(HashMap::new()),
+        }
+    }
+}
+
+impl Default for BellManager {
+    fn default() -> Self {
+        Self::new()
+    }
+}
@@ -1,6 +1,62 @@
-use std::process::Child;
+use crate::command::core::input_prompts::PromptWatch;
+use crate::ollama::context_filter::ContextPieceKind;
+use crate::utils::config_utils::unix_timestamp_millis;
+use std::collections::{BTreeMap, HashMap};
+use std::process::{Child, ChildStdin};
 use std::sync::{Arc, Mutex};
 
+// Variables loaded from a directory's .env/.envrc while the session is inside it
+#[derive(Clone)]
+pub struct DirectoryEnv {
+    pub dir: String,
+    pub file: String,
+    pub content_hash: String,
+    pub variables: HashMap<String, String>,
+}
+
+// A Python virtualenv or conda environment activated for the session by activate_environment
+#[derive(Clone)]
+pub struct ActivatedPythonEnv {
+    pub name: String,
+    pub prefix: String,
+    // Prepended to PATH by session_env_overrides
+    pub bin_dir: String,
+    pub variables: HashMap<String, String>,
+}
+
+// A Node.js version selected for the session by use_node_version
+#[derive(Clone)]
+pub struct ActivatedNodeVersion {
+    pub version: String,
+    pub bin_dir: String,
+}
+
+// Stdin of a running local command, kept open so prompts can be answered with send_command_input
+#[derive(Clone)]
+pub struct LocalInput {
+    pub stdin: Arc<Mutex<ChildStdin>>,
+    pub prompts: Arc<PromptWatch>,
+}
+
+// What the session's pid belongs to; stale once pid no longer matches
+#[derive(Clone)]
+pub struct RunningCommand {
+    pub pid: u32,
+    pub command: String,
+    // Unix milliseconds
+    pub started_at: u64,
+}
+
+impl RunningCommand {
+    pub fn new(pid: u32, command: &str) -> Self {
+        RunningCommand {
+            pid,
+            command: command.to_string(),
+            started_at: unix_timestamp_millis(),
+        }
+    }
+}
+
 // Store the current working directory for each command
 #[derive(Clone)]
 pub struct CommandState {
@@ -8,6 +64,50 @@ pub struct CommandState {
     pub child_wait_handle: Option<Arc<Mutex<Child>>>, // For wait() and kill()
     pub child_stdin: Option<Arc<Mutex<std::process::ChildStdin>>>, // For writing
     pub pid: Option<u32>,
+    pub running: Option<RunningCommand>,
+    pub input: Option<LocalInput>,
     pub is_ssh_session_active: bool, // Added for persistent SSH
     pub remote_current_dir: Option<String>, // New field for remote SSH path
+    // Options and destination of an interactive SSH session, for ssh_exec
+    pub ssh_target: Option<Vec<String>>,
+    pub directory_env: Option<DirectoryEnv>,
+    pub python_env: Option<ActivatedPythonEnv>,
+    pub node_version: Option<ActivatedNodeVersion>,
+    // Cloud CLI selections made with set_cloud_profile (AWS_PROFILE, CLOUDSDK_CORE_PROJECT, ...)
+    pub cloud_env: HashMap<String, String>,
+    // Scratchpad shared with the AI (set_var/get_var), interpolated as {{var:name}}
+    pub variables: BTreeMap<String, String>,
+    // Stdout of commands run with capture_output, interpolated as {{capture:name}}
+    pub captures: BTreeMap<String, String>,
+    // Scrollback text selected with set_ai_selection, quoted into the next ask_ai call
+    pub ai_selection: Option<String>,
+    // Context pieces left out of this session's AI requests (set_ai_context_omissions)
+    pub ai_context_omitted: Vec<ContextPieceKind>,
+    // Private temp dir from get_session_tmpdir, exported as TMPDIR; removed with the session
+    pub tmp_dir: Option<String>,
+}
+
+impl CommandState {
+    pub fn new(current_dir: String) -> Self {
+        CommandState {
+            current_dir,
+            child_wait_handle: None,
+            child_stdin: None,
+            pid: None,
+            running: None,
+            input: None,
+            is_ssh_session_active: false,
+            remote_current_dir: None,
+            ssh_target: None,
+            directory_env: None,
+            python_env: None,
+            node_version: None,
+            cloud_env: HashMap::new(),
+            variables: BTreeMap::new(),
+            captures: BTreeMap::new(),
+            ai_selection: None,
+            ai_context_omitted: Vec::new(),
+            tmp_dir: None,
+        }
+    }
 }
@@ -0,0 +1,82 @@
+use crate::command::constants::CONFIRMATION_TTL_MS;
+use crate::utils::config_utils::unix_timestamp_millis;
+use serde::Serialize;
+use std::collections::HashMap;
+use std::sync::Mutex;
+
+// Returned by gated commands until the frontend re-invokes them with a valid token
+pub const CONFIRMATION_REQUIRED_MARKER: &str = "CONFIRMATION_REQUIRED";
+pub const CONFIRMATION_REQUIRED_EVENT: &str = "confirmation_required";
+
+#[derive(Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct ConfirmationRequest {
+    pub token: String,
+    pub scope: String,
+    pub title: String,
+    pub detail: String,
+}
+
+struct PendingConfirmation {
+    scope: String,
+    issued_at: u64,
+}
+
+// One-shot tokens binding a user confirmation to a specific action (the "scope")
+pub struct ConfirmationManager {
+    pending: Mutex<HashMap<String, PendingConfirmation>>,
+}
+
+impl ConfirmationManager {
+    pub fn new() -> Self {
+        Self {
+            pending: Mutex::new(HashMap::new()),
+        }
+    }
+
+    pub fn issue(
+        &self,
+        scope: &str,
+        title: &str,
+        detail: &str,
+    ) -> Result<ConfirmationRequest, String> {
+        let token = uuid::Uuid::new_v4().to_string();
+        let now = unix_timestamp_millis();
+        let mut pending = self.pending.lock().map_err(|e| e.to_string())?;
+        pending.retain(|_, p| now.saturating_sub(p.issued_at) < CONFIRMATION_TTL_MS);
+        pending.insert(
+            token.clone(),
+            PendingConfirmation {
+                scope: scope.to_string(),
+                issued_at: now,
+            },
+        );
+        Ok(ConfirmationRequest {
+            token,
+            scope: scope.to_string(),
+            title: title.to_string(),
+            detail: detail.to_string(),
+        })
+    }
+
+    // Consumes the token; only succeeds once, before expiry, and for the same scope
+    pub fn consume(&self, token: &str, scope: &str) -> bool {
+        let mut pending = match self.pending.lock() {
+            Ok(pending) => pending,
+            Err(_) => return false,
+        };
+        match pending.remove(token) {
+            Some(p) => {
+                p.scope == scope
+                    && unix_timestamp_millis().saturating_sub(p.issued_at) < CONFIRMATION_TTL_MS
+            }
+            None => false,
+        }
+    }
+}
+
+impl Default for ConfirmationManager {
+    fn default() -> Self {
+        Self::new()
+    }
+}
@@ -0,0 +1,56 @@
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::sync::Mutex;
+
+// Line and char column in the copy mode snapshot
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, Default, PartialEq, Eq, PartialOrd, Ord)]
+#[serde(rename_all = "camelCase")]
+pub struct CopyPosition {
+    pub line: usize,
+    pub column: usize,
+}
+
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum SelectionMode {
+    // From the anchor to the cursor, like v in vi
+    #[default]
+    Char,
+    // Whole lines, like V
+    Line,
+    // The rectangle between anchor and cursor, like Ctrl-V
+    Block,
+}
+
+// Copy mode of one session: a snapshot of its scrollback as plain text, so output arriving
+// meanwhile does not move the selection
+pub struct CopyModeState {
+    pub lines: Vec<String>,
+    pub cursor: CopyPosition,
+    // Where the selection started; None while only moving
+    pub anchor: Option<CopyPosition>,
+    pub mode: SelectionMode,
+    // First line of the viewport and its height
+    pub top: usize,
+    pub rows: usize,
+    // Column vertical moves try to return to, as in vi
+    pub preferred_column: usize,
+}
+
+pub struct CopyModeManager {
+    pub sessions: Mutex<HashMap<String, CopyModeState>>,
+}
+
+impl CopyModeManager {
+    pub fn new() -> Self {
+        Self {
+            sessions: Mutex::new(HashMap::new()),
+        }
+    }
+}
+
+impl Default for CopyModeManager {
+    fn default() -> Self {
+        Self::new()
+    }
+}
@@ -0,0 +1,50 @@
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+
+// Cancellation flags of the file operations in progress, by operation id
+pub struct FileOperationManager {
+    operations: Mutex<HashMap<String, Arc<AtomicBool>>>,
+}
+
+impl FileOperationManager {
+    pub fn new() -> Self {
+        Self {
+            operations: Mutex::new(HashMap::new()),
+        }
+    }
+
+    pub fn start(&self, operation_id: &str) -> Result<Arc<AtomicBool>, String> {
+        let mut operations = self.operations.lock().map_err(|e| e.to_string())?;
+        if operations.contains_key(operation_id) {
+            return Err(format!(
+                "A file operation with id '{}' is already running",
+                operation_id
+            ));
+        }
+        let cancelled = Arc::new(AtomicBool::new(false));
+        operations.insert(operation_id.to_string(), cancelled.clone());
+        Ok(cancelled)
+    }
+
+    pub fn cancel(&self, operation_id: &str) -> Result<(), String> {
+        let operations = self.operations.lock().map_err(|e| e.to_string())?;
+        let cancelled = operations
+            .get(operation_id)
+            .ok_or_else(|| format!("No file operation with id '{}'", operation_id))?;
+        cancelled.store(true, Ordering::Relaxed);
+        Ok(())
+    }
+
+    pub fn finish(&self, operation_id: &str) {
+        if let Ok(mut operations) = self.operations.lock() {
+            operations.remove(operation_id);
+        }
+    }
+}
+
+impl Default for FileOperationManager {
+    fn default() -> Self {
+        Self::new()
+    }
+}
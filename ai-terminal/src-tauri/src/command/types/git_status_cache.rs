@@ -0,0 +1,39 @@
+use crate::command::git_commands::git::GitStatus;
+use std::collections::HashMap;
+use std::sync::Mutex;
+
+// Last prompt status scan of one directory
+#[derive(Clone, Debug)]
+pub struct CachedGitStatus {
+    // None outside a repository
+    pub status: Option<GitStatus>,
+    // Unix millis when the scan finished; 0 before the first one
+    pub checked_at: u64,
+    pub duration_ms: u64,
+    // Counts cover the directory's subtree only, since a full scan was too slow
+    pub subtree: bool,
+    // A scan is running or scheduled
+    pub refreshing: bool,
+    // A stale result was handed out while that scan ran, so its result must be announced
+    pub announce: bool,
+}
+
+// Prompt git status per working directory, so slow repositories are scanned in the
+// background and at most every GIT_STATUS_THROTTLE_FACTOR scan durations
+pub struct GitStatusCache {
+    pub entries: Mutex<HashMap<String, CachedGitStatus>>,
+}
+
+impl GitStatusCache {
+    pub fn new() -> Self {
+        Self {
+            entries: Mutex::new(HashMap::new()),
+        }
+    }
+}
+
+impl Default for GitStatusCache {
+    fn default() -> Self {
+        Self::new()
+    }
+}
@@ -0,0 +1,37 @@
+use crate::utils::config_utils::{load_json_config, save_json_config};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeSet;
+use std::sync::Mutex;
+
+pub const LEARNED_COMMANDS_FILE: &str = "learned_commands.json";
+
+#[derive(Serialize, Deserialize, Clone, Debug, Default)]
+#[serde(default, rename_all = "camelCase")]
+pub struct LearnedCommands {
+    // Programs picked up from history, offered by first-word autocompletion
+    pub learned: BTreeSet<String>,
+    // Programs the user removed; they are not learned again
+    pub forgotten: BTreeSet<String>,
+}
+
+pub struct LearnedCommandStore {
+    pub commands: Mutex<LearnedCommands>,
+}
+
+impl LearnedCommandStore {
+    pub fn new() -> Self {
+        Self {
+            commands: Mutex::new(load_json_config(LEARNED_COMMANDS_FILE)),
+        }
+    }
+
+    pub fn persist(&self, commands: &LearnedCommands) -> Result<(), String> {
+        save_json_config(LEARNED_COMMANDS_FILE, commands)
+    }
+}
+
+impl Default for LearnedCommandStore {
+    fn default() -> Self {
+        Self::new()
+    }
+}
@@ -0,0 +1,22 @@
+use crate::command::line_editor::editor::LineEditor;
+use std::collections::HashMap;
+use std::sync::Mutex;
+
+// Input-box editing state per session
+pub struct LineEditorManager {
+    pub editors: Mutex<HashMap<String, LineEditor>>,
+}
+
+impl LineEditorManager {
+    pub fn new() -> Self {
+        Self {
+            editors: Mutex::new(HashMap::new()),
+        }
+    }
+}
+
+impl Default for LineEditorManager {
+    fn default() -> Self {
+        Self::new()
+    }
+}
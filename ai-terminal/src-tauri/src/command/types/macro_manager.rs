@@ -0,0 +1,79 @@
+use crate::command::constants::{MACRO_MAX_STEPS, MACRO_MAX_STEP_DELAY_MS};
+use crate::utils::config_utils::{load_json_config, save_json_config};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::sync::atomic::AtomicBool;
+use std::sync::{Arc, Mutex};
+use std::time::Instant;
+
+pub const MACROS_CONFIG_FILE: &str = "macros.json";
+
+// One pty_write of a recorded macro
+#[derive(Serialize, Deserialize, Clone, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct MacroStep {
+    // Wait since the previous step, capped at MACRO_MAX_STEP_DELAY_MS
+    pub delay_ms: u64,
+    pub data: String,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct InputMacro {
+    pub name: String,
+    pub steps: Vec<MacroStep>,
+    pub recorded_at: u64,
+}
+
+// Input captured so far for a session being recorded
+pub struct MacroRecording {
+    pub steps: Vec<MacroStep>,
+    // When recording started, then when the last input was written
+    pub last_input_at: Instant,
+}
+
+// Saved macros by name, the sessions being recorded, and cancellation flags of playbacks
+pub struct MacroManager {
+    pub macros: Mutex<HashMap<String, InputMacro>>,
+    pub recordings: Mutex<HashMap<String, MacroRecording>>,
+    pub playbacks: Mutex<HashMap<String, Arc<AtomicBool>>>,
+}
+
+impl MacroManager {
+    pub fn new() -> Self {
+        Self {
+            macros: Mutex::new(load_json_config(MACROS_CONFIG_FILE)),
+            recordings: Mutex::new(HashMap::new()),
+            playbacks: Mutex::new(HashMap::new()),
+        }
+    }
+
+    pub fn persist(&self, macros: &HashMap<String, InputMacro>) -> Result<(), String> {
+        save_json_config(MACROS_CONFIG_FILE, macros)
+    }
+
+    // Adds input written to a session to its recording, if it is being recorded
+    pub fn record_input(&self, session_id: &str, data: &str) {
+        let Ok(mut recordings) = self.recordings.lock() else {
+            return;
+        };
+        let Some(recording) = recordings.get_mut(session_id) else {
+            return;
+        };
+        if recording.steps.len() >= MACRO_MAX_STEPS {
+            return;
+        }
+        let delay_ms = recording.last_input_at.elapsed().as_millis() as u64;
+        recording.last_input_at = Instant::now();
+        recording.steps.push(MacroStep {
+            delay_ms: delay_ms.min(MACRO_MAX_STEP_DELAY_MS),
+            data: data.to_string(),
+        });
+    }
+}
+
+impl Default for MacroManager {
+    fn default() -> Self {
+        Self::new()
+    }
+}
@@ -1,3 +1,19 @@
+pub mod bell_manager;
 pub mod command_manager;
 pub mod command_state;
+pub mod confirmation_manager;
+pub mod copy_mode_manager;
+pub mod file_operation_manager;
+pub mod git_status_cache;
+pub mod learned_command_store;
+pub mod line_editor_manager;
+pub mod macro_manager;
+pub mod output_processor_manager;
+pub mod progress_tracker;
 pub mod pty_manager;
+pub mod recovery_manager;
+pub mod screen_model_manager;
+pub mod scrollback_manager;
+pub mod share_manager;
+pub mod timeline_manager;
+pub mod window_router;
@@ -0,0 +1,21 @@
+use std::collections::HashMap;
+use std::sync::Mutex;
+
+pub struct OutputProcessorManager {
+    // session id → processor name → enabled, for sessions that changed the defaults
+    pub overrides: Mutex<HashMap<String, HashMap<String, bool>>>,
+}
+
+impl OutputProcessorManager {
+    pub fn new() -> Self {
+        Self {
+            overrides: Mutex::new(HashMap::new()),
+        }
+    }
+}
+
+impl Default for OutputProcessorManager {
+    fn default() -> Self {
+        Self::new()
+    }
+}
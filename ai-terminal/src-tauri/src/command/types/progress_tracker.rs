@@ -0,0 +1,29 @@
+use std::collections::HashMap;
+use std::sync::Mutex;
+use std::time::Instant;
+
+// Last progress reported for a session, used to throttle command_progress events
+pub struct ProgressState {
+    pub percent: f64,
+    pub emitted_at: Instant,
+    // Text after the last line break, so a progress line split across reads is seen whole
+    pub partial_line: String,
+}
+
+pub struct ProgressTracker {
+    pub sessions: Mutex<HashMap<String, ProgressState>>,
+}
+
+impl ProgressTracker {
+    pub fn new() -> Self {
+        Self {
+            sessions: Mutex::new(HashMap::new()),
+        }
+    }
+}
+
+impl Default for ProgressTracker {
+    fn default() -> Self {
+        Self::new()
+    }
+}
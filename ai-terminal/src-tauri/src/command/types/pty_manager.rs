@@ -1,12 +1,103 @@
 use portable_pty::{Child, MasterPty};
+use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 use std::io::Write;
 use std::sync::{Arc, Mutex};
 
+// Where a PTY session's shell is running, when it isn't the local machine
+#[derive(Serialize, Deserialize, Clone, Debug)]
+#[serde(tag = "kind", rename_all = "camelCase")]
+pub enum SessionTarget {
+    #[serde(rename_all = "camelCase")]
+    Container {
+        id: String,
+        name: String,
+        image: String,
+        // PRETTY_NAME from the container's /etc/os-release
+        os: Option<String>,
+    },
+    #[serde(rename_all = "camelCase")]
+    Kubernetes {
+        context: Option<String>,
+        namespace: String,
+        pod: String,
+        container: Option<String>,
+        // Container restart count when the exec started; a change means the pod restarted
+        restart_count: u32,
+    },
+    #[serde(rename_all = "camelCase")]
+    Wsl { distro: String },
+    // Not a shell at all: an interpreter started by start_repl
+    #[serde(rename_all = "camelCase")]
+    Repl {
+        language: String,
+        interpreter: String,
+    },
+    // Not a shell either: one terminal-only command moved off execute_command
+    #[serde(rename_all = "camelCase")]
+    Command { command: String },
+}
+
+impl SessionTarget {
+    // Sentence prepended to AI prompts so answers fit the environment
+    pub fn ai_context(&self) -> String {
+        match self {
+            SessionTarget::Container {
+                name, image, os, ..
+            } => format!(
+                "You are inside the Docker container \"{}\" (image {}, OS {}); prefer commands available in that image.",
+                name,
+                image,
+                os.as_deref().unwrap_or("unknown")
+            ),
+            SessionTarget::Kubernetes {
+                context,
+                namespace,
+                pod,
+                container,
+                ..
+            } => format!(
+                "You are inside Kubernetes pod \"{}\"{} in namespace \"{}\"{}; the filesystem and tools are the container's, not the user's machine.",
+                pod,
+                container
+                    .as_deref()
+                    .map(|container| format!(" (container \"{}\")", container))
+                    .unwrap_or_default(),
+                namespace,
+                context
+                    .as_deref()
+                    .map(|context| format!(", kubectl context \"{}\"", context))
+                    .unwrap_or_default()
+            ),
+            SessionTarget::Wsl { distro } => format!(
+                "You are inside the WSL distribution \"{}\" on Windows; Windows drives are mounted under /mnt.",
+                distro
+            ),
+            SessionTarget::Repl {
+                language,
+                interpreter,
+            } => format!(
+                "The session is a {} REPL ({}); reply with code that can be evaluated in it directly.",
+                language, interpreter
+            ),
+            SessionTarget::Command { command } => format!(
+                "The session is running the interactive command `{}`; it exits when that command does.",
+                command
+            ),
+        }
+    }
+}
+
 pub struct PtySession {
     pub master: Box<dyn MasterPty + Send>,
     pub writer: Arc<Mutex<Box<dyn Write + Send>>>,
     pub child: Arc<Mutex<Box<dyn Child + Send + Sync>>>,
+    pub target: Option<SessionTarget>,
+    // Unix milliseconds
+    pub started_at: u64,
+    // Input typed since the last Enter, checked when the session is protected. None once it
+    // was edited in ways keystrokes do not reveal (history recall, completion, cursor keys).
+    pub input_line: Option<String>,
 }
 
 pub struct PtyManager {
@@ -0,0 +1,57 @@
+use crate::command::types::pty_manager::SessionTarget;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
+use std::sync::Mutex;
+
+// A command that was still running when the snapshot was taken
+#[derive(Serialize, Deserialize, Clone, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct RunningCommand {
+    pub command: String,
+    pub started_at: u64,
+    pub pid: Option<u32>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct SessionSnapshot {
+    pub session_id: String,
+    pub cwd: String,
+    // Set while an SSH session is active; ssh_command is what started it
+    pub remote_current_dir: Option<String>,
+    pub ssh_command: Option<String>,
+    pub target: Option<SessionTarget>,
+    pub variables: BTreeMap<String, String>,
+    pub cloud_env: HashMap<String, String>,
+    // Prefix of the activated Python environment and the selected Node.js version
+    pub python_env: Option<String>,
+    pub node_version: Option<String>,
+    pub running_command: Option<RunningCommand>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, Default)]
+#[serde(default, rename_all = "camelCase")]
+pub struct StateSnapshot {
+    pub saved_at: u64,
+    pub sessions: Vec<SessionSnapshot>,
+}
+
+// Snapshot left behind by a run that did not shut down cleanly, until the user restores or
+// discards it
+pub struct RecoveryManager {
+    pub pending: Mutex<Option<StateSnapshot>>,
+}
+
+impl RecoveryManager {
+    pub fn new() -> Self {
+        Self {
+            pending: Mutex::new(None),
+        }
+    }
+}
+
+impl Default for RecoveryManager {
+    fn default() -> Self {
+        Self::new()
+    }
+}
@@ -0,0 +1,22 @@
+use crate::utils::screen_model::ScreenModel;
+use std::collections::HashMap;
+use std::sync::Mutex;
+
+// Screen model of each PTY session, fed by its output
+pub struct ScreenModelManager {
+    pub sessions: Mutex<HashMap<String, ScreenModel>>,
+}
+
+impl ScreenModelManager {
+    pub fn new() -> Self {
+        Self {
+            sessions: Mutex::new(HashMap::new()),
+        }
+    }
+}
+
+impl Default for ScreenModelManager {
+    fn default() -> Self {
+        Self::new()
+    }
+}
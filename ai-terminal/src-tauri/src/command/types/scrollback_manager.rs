@@ -0,0 +1,352 @@
+use crate::command::constants::{
+    MAX_PLAIN_TEXT_LINES, MAX_RECORDED_RUNS, MAX_RUN_OUTPUT_BYTES, MAX_SCROLLBACK_BYTES,
+};
+use crate::utils::ansi::PlainTextRenderer;
+use crate::utils::config_utils::unix_timestamp_millis;
+use serde::Serialize;
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::sync::mpsc::{channel, Receiver, Sender};
+use std::sync::Mutex;
+
+#[derive(Serialize, Clone, Copy, PartialEq, Eq, Debug)]
+#[serde(rename_all = "lowercase")]
+pub enum OutputStream {
+    Input,
+    Stdout,
+    Stderr,
+    Pty,
+    // set -x lines of a traced command, kept apart from its stderr
+    Trace,
+}
+
+#[derive(Serialize, Clone, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct ScrollbackEntry {
+    pub timestamp: u64,
+    pub stream: OutputStream,
+    pub data: String,
+}
+
+// Output captured for one invocation through the piped executor
+#[derive(Serialize, Clone, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct CommandRun {
+    pub command: String,
+    pub started_at: u64,
+    pub output: String,
+    pub exit_code: Option<i32>,
+    pub finished: bool,
+}
+
+#[derive(Serialize, Clone, Copy, PartialEq, Eq, Debug)]
+#[serde(rename_all = "lowercase")]
+pub enum MarkKind {
+    Command,
+    User,
+}
+
+// A navigable position in the scrollback; `position` is an absolute entry index that
+// stays stable when older entries are trimmed
+#[derive(Serialize, Clone, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct OutputMark {
+    pub id: String,
+    pub label: String,
+    pub kind: MarkKind,
+    pub position: usize,
+    pub timestamp: u64,
+}
+
+// Retained output for a single session, trimmed from the front once it grows past the limit
+#[derive(Default)]
+pub struct Scrollback {
+    pub entries: VecDeque<ScrollbackEntry>,
+    pub total_bytes: usize,
+    pub runs: VecDeque<CommandRun>,
+    // Number of entries trimmed from the front so far
+    pub dropped_entries: usize,
+    pub marks: Vec<OutputMark>,
+    // Parallel plain-text rendering, only maintained when retention is enabled for the session
+    pub plain_text: Option<PlainTextRenderer>,
+}
+
+impl Scrollback {
+    fn push(&mut self, entry: ScrollbackEntry) {
+        self.render_plain_text(&entry);
+
+        match entry.stream {
+            OutputStream::Input => {
+                self.marks.push(OutputMark {
+                    id: uuid::Uuid::new_v4().to_string(),
+                    label: entry.data.trim().to_string(),
+                    kind: MarkKind::Command,
+                    position: self.next_position(),
+                    timestamp: entry.timestamp,
+                });
+                self.runs.push_back(CommandRun {
+                    command: entry.data.trim().to_string(),
+                    started_at: entry.timestamp,
+                    output: String::new(),
+                    exit_code: None,
+                    finished: false,
+                });
+                if self.runs.len() > MAX_RECORDED_RUNS {
+                    self.runs.pop_front();
+                }
+            }
+            OutputStream::Stdout | OutputStream::Stderr => {
+                if let Some(run) = self.runs.back_mut() {
+                    if run.output.len() < MAX_RUN_OUTPUT_BYTES {
+                        run.output.push_str(&entry.data);
+                    }
+                }
+            }
+            OutputStream::Pty | OutputStream::Trace => {}
+        }
+
+        self.total_bytes += entry.data.len();
+        self.entries.push_back(entry);
+        while self.total_bytes > MAX_SCROLLBACK_BYTES {
+            match self.entries.pop_front() {
+                Some(removed) => {
+                    self.total_bytes -= removed.data.len();
+                    self.dropped_entries += 1;
+                }
+                None => break,
+            }
+        }
+        // Marks pointing at trimmed output can no longer be jumped to
+        let first_position = self.dropped_entries;
+        self.marks.retain(|mark| mark.position >= first_position);
+    }
+
+    fn next_position(&self) -> usize {
+        self.dropped_entries + self.entries.len()
+    }
+
+    fn render_plain_text(&mut self, entry: &ScrollbackEntry) {
+        if let Some(renderer) = self.plain_text.as_mut() {
+            feed_plain_text(renderer, entry);
+        }
+    }
+}
+
+fn feed_plain_text(renderer: &mut PlainTextRenderer, entry: &ScrollbackEntry) {
+    match entry.stream {
+        OutputStream::Input => renderer.feed(&format!("$ {}\n", entry.data.trim_end())),
+        _ => renderer.feed(&entry.data),
+    }
+}
+
+pub struct ScrollbackManager {
+    pub sessions: Mutex<HashMap<String, Scrollback>>,
+    // Live listeners (e.g. shared-session viewers) receiving every new entry
+    subscribers: Mutex<HashMap<String, Vec<Sender<ScrollbackEntry>>>>,
+    // Sessions of each locked window: their output is still delivered live but not retained
+    retention_paused: Mutex<HashMap<String, HashSet<String>>>,
+}
+
+impl ScrollbackManager {
+    pub fn new() -> Self {
+        Self {
+            sessions: Mutex::new(HashMap::new()),
+            subscribers: Mutex::new(HashMap::new()),
+            retention_paused: Mutex::new(HashMap::new()),
+        }
+    }
+
+    pub fn pause_retention(&self, window: &str, session_ids: HashSet<String>) {
+        if let Ok(mut paused) = self.retention_paused.lock() {
+            paused.insert(window.to_string(), session_ids);
+        }
+    }
+
+    pub fn resume_retention(&self, window: &str) {
+        if let Ok(mut paused) = self.retention_paused.lock() {
+            paused.remove(window);
+        }
+    }
+
+    fn is_retention_paused(&self, session_id: &str) -> bool {
+        self.retention_paused
+            .lock()
+            .map(|paused| {
+                paused
+                    .values()
+                    .any(|sessions| sessions.contains(session_id))
+            })
+            .unwrap_or(false)
+    }
+
+    pub fn append(&self, session_id: &str, stream: OutputStream, data: &str) {
+        if data.is_empty() {
+            return;
+        }
+        let entry = ScrollbackEntry {
+            timestamp: unix_timestamp_millis(),
+            stream,
+            data: data.to_string(),
+        };
+
+        if let Ok(mut subscribers) = self.subscribers.lock() {
+            if let Some(senders) = subscribers.get_mut(session_id) {
+                // Drop listeners whose receiving end has gone away
+                senders.retain(|sender| sender.send(entry.clone()).is_ok());
+            }
+        }
+
+        if self.is_retention_paused(session_id) {
+            return;
+        }
+        if let Ok(mut sessions) = self.sessions.lock() {
+            sessions
+                .entry(session_id.to_string())
+                .or_default()
+                .push(entry);
+        }
+    }
+
+    pub fn subscribe(&self, session_id: &str) -> Result<Receiver<ScrollbackEntry>, String> {
+        let (sender, receiver) = channel();
+        let mut subscribers = self.subscribers.lock().map_err(|e| e.to_string())?;
+        subscribers
+            .entry(session_id.to_string())
+            .or_default()
+            .push(sender);
+        Ok(receiver)
+    }
+
+    // Records the exit status of the most recent unfinished run
+    pub fn finish_run(&self, session_id: &str, exit_code: Option<i32>) {
+        if let Ok(mut sessions) = self.sessions.lock() {
+            if let Some(run) = sessions
+                .get_mut(session_id)
+                .and_then(|scrollback| scrollback.runs.iter_mut().rev().find(|run| !run.finished))
+            {
+                run.exit_code = exit_code;
+                run.finished = true;
+            }
+        }
+    }
+
+    pub fn set_plain_text_retention(&self, session_id: &str, enabled: bool) -> Result<(), String> {
+        let mut sessions = self.sessions.lock().map_err(|e| e.to_string())?;
+        let scrollback = sessions.entry(session_id.to_string()).or_default();
+        if !enabled {
+            scrollback.plain_text = None;
+        } else if scrollback.plain_text.is_none() {
+            // Seed from what is already retained so the plain view starts complete
+            scrollback.plain_text = Some(PlainTextRenderer::new(MAX_PLAIN_TEXT_LINES));
+            let entries: Vec<ScrollbackEntry> = scrollback.entries.iter().cloned().collect();
+            for entry in &entries {
+                scrollback.render_plain_text(entry);
+            }
+        }
+        Ok(())
+    }
+
+    pub fn plain_text(&self, session_id: &str) -> Result<Option<String>, String> {
+        let sessions = self.sessions.lock().map_err(|e| e.to_string())?;
+        Ok(sessions
+            .get(session_id)
+            .and_then(|scrollback| scrollback.plain_text.as_ref())
+            .map(|renderer| renderer.text()))
+    }
+
+    // Lines of the retained output as plain text, rendered on the spot when the session does
+    // not keep a plain-text copy
+    pub fn plain_text_lines(&self, session_id: &str) -> Result<Vec<String>, String> {
+        let sessions = self.sessions.lock().map_err(|e| e.to_string())?;
+        let Some(scrollback) = sessions.get(session_id) else {
+            return Ok(Vec::new());
+        };
+        let text = match &scrollback.plain_text {
+            Some(renderer) => renderer.text(),
+            None => {
+                let mut renderer = PlainTextRenderer::new(MAX_PLAIN_TEXT_LINES);
+                for entry in &scrollback.entries {
+                    feed_plain_text(&mut renderer, entry);
+                }
+                renderer.text()
+            }
+        };
+        Ok(text.lines().map(|line| line.to_string()).collect())
+    }
+
+    // Marks the current end of the session output with a user-defined label
+    pub fn add_mark(&self, session_id: &str, label: &str) -> Result<OutputMark, String> {
+        let mut sessions = self.sessions.lock().map_err(|e| e.to_string())?;
+        let scrollback = sessions.entry(session_id.to_string()).or_default();
+        let mark = OutputMark {
+            id: uuid::Uuid::new_v4().to_string(),
+            label: label.to_string(),
+            kind: MarkKind::User,
+            position: scrollback.next_position(),
+            timestamp: unix_timestamp_millis(),
+        };
+        scrollback.marks.push(mark.clone());
+        Ok(mark)
+    }
+
+    pub fn remove_mark(&self, session_id: &str, mark_id: &str) -> Result<bool, String> {
+        let mut sessions = self.sessions.lock().map_err(|e| e.to_string())?;
+        Ok(match sessions.get_mut(session_id) {
+            Some(scrollback) => {
+                let before = scrollback.marks.len();
+                scrollback.marks.retain(|mark| mark.id != mark_id);
+                scrollback.marks.len() != before
+            }
+            None => false,
+        })
+    }
+
+    pub fn marks(&self, session_id: &str) -> Result<Vec<OutputMark>, String> {
+        let sessions = self.sessions.lock().map_err(|e| e.to_string())?;
+        Ok(sessions
+            .get(session_id)
+            .map(|scrollback| scrollback.marks.clone())
+            .unwrap_or_default())
+    }
+
+    // Entries between two absolute positions (end exclusive), clamped to what is still retained
+    pub fn entries_between(
+        &self,
+        session_id: &str,
+        start: usize,
+        end: Option<usize>,
+    ) -> Result<Vec<ScrollbackEntry>, String> {
+        let sessions = self.sessions.lock().map_err(|e| e.to_string())?;
+        let Some(scrollback) = sessions.get(session_id) else {
+            return Ok(Vec::new());
+        };
+        let offset = scrollback.dropped_entries;
+        let from = start.saturating_sub(offset).min(scrollback.entries.len());
+        let to = end
+            .map(|end| end.saturating_sub(offset))
+            .unwrap_or(scrollback.entries.len())
+            .clamp(from, scrollback.entries.len());
+        Ok(scrollback.entries.range(from..to).cloned().collect())
+    }
+
+    pub fn runs(&self, session_id: &str) -> Result<Vec<CommandRun>, String> {
+        let sessions = self.sessions.lock().map_err(|e| e.to_string())?;
+        Ok(sessions
+            .get(session_id)
+            .map(|scrollback| scrollback.runs.iter().cloned().collect())
+            .unwrap_or_default())
+    }
+
+    pub fn snapshot(&self, session_id: &str) -> Result<Vec<ScrollbackEntry>, String> {
+        let sessions = self.sessions.lock().map_err(|e| e.to_string())?;
+        Ok(sessions
+            .get(session_id)
+            .map(|scrollback| scrollback.entries.iter().cloned().collect())
+            .unwrap_or_default())
+    }
+}
+
+impl Default for ScrollbackManager {
+    fn default() -> Self {
+        Self::new()
+    }
+}
@@ -0,0 +1,29 @@
+use std::collections::HashMap;
+use std::net::SocketAddr;
+use std::sync::atomic::{AtomicBool, AtomicUsize};
+use std::sync::{Arc, Mutex};
+
+pub struct SharedSession {
+    pub token: String,
+    pub address: SocketAddr,
+    pub stop: Arc<AtomicBool>,
+    pub viewers: Arc<AtomicUsize>,
+}
+
+pub struct ShareManager {
+    pub shares: Mutex<HashMap<String, SharedSession>>,
+}
+
+impl ShareManager {
+    pub fn new() -> Self {
+        Self {
+            shares: Mutex::new(HashMap::new()),
+        }
+    }
+}
+
+impl Default for ShareManager {
+    fn default() -> Self {
+        Self::new()
+    }
+}
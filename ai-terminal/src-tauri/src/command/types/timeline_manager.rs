@@ -0,0 +1,79 @@
+use crate::command::constants::MAX_TIMELINE_EVENTS;
+use crate::utils::config_utils::unix_timestamp_millis;
+use serde::Serialize;
+use serde_json::Value;
+use std::collections::{HashMap, VecDeque};
+use std::sync::Mutex;
+
+#[derive(Serialize, Clone, Copy, PartialEq, Eq, Debug)]
+#[serde(rename_all = "snake_case")]
+pub enum TimelineEventKind {
+    CommandStarted,
+    CommandFinished,
+    DirectoryChanged,
+    SshConnected,
+    SshDisconnected,
+    AiInteraction,
+}
+
+#[derive(Serialize, Clone, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct TimelineEvent {
+    pub timestamp: u64,
+    pub kind: TimelineEventKind,
+    // One-line human readable description, e.g. the command or the new directory
+    pub summary: String,
+    pub details: Value,
+}
+
+// Structured per-session record of what happened, independent of the raw output
+pub struct TimelineManager {
+    sessions: Mutex<HashMap<String, VecDeque<TimelineEvent>>>,
+}
+
+impl TimelineManager {
+    pub fn new() -> Self {
+        Self {
+            sessions: Mutex::new(HashMap::new()),
+        }
+    }
+
+    pub fn record(&self, session_id: &str, kind: TimelineEventKind, summary: &str, details: Value) {
+        if let Ok(mut sessions) = self.sessions.lock() {
+            let events = sessions.entry(session_id.to_string()).or_default();
+            events.push_back(TimelineEvent {
+                timestamp: unix_timestamp_millis(),
+                kind,
+                summary: summary.to_string(),
+                details,
+            });
+            if events.len() > MAX_TIMELINE_EVENTS {
+                events.pop_front();
+            }
+        }
+    }
+
+    pub fn events(
+        &self,
+        session_id: &str,
+        since: Option<u64>,
+    ) -> Result<Vec<TimelineEvent>, String> {
+        let sessions = self.sessions.lock().map_err(|e| e.to_string())?;
+        Ok(sessions
+            .get(session_id)
+            .map(|events| {
+                events
+                    .iter()
+                    .filter(|event| since.is_none_or(|since| event.timestamp >= since))
+                    .cloned()
+                    .collect()
+            })
+            .unwrap_or_default())
+    }
+}
+
+impl Default for TimelineManager {
+    fn default() -> Self {
+        Self::new()
+    }
+}
@@ -0,0 +1,32 @@
+use std::collections::HashMap;
+use std::sync::Mutex;
+
+// Which window shows each session. Sessions live in the shared managers regardless of window,
+// so moving one only changes where its events are delivered.
+pub struct WindowRouter {
+    pub owners: Mutex<HashMap<String, String>>,
+}
+
+impl WindowRouter {
+    pub fn new() -> Self {
+        Self {
+            owners: Mutex::new(HashMap::new()),
+        }
+    }
+
+    pub fn owner(&self, session_id: &str) -> Option<String> {
+        self.owners.lock().ok()?.get(session_id).cloned()
+    }
+
+    // Returns the window that showed the session before, if any
+    pub fn assign(&self, session_id: &str, window: &str) -> Result<Option<String>, String> {
+        let mut owners = self.owners.lock().map_err(|e| e.to_string())?;
+        Ok(owners.insert(session_id.to_string(), window.to_string()))
+    }
+}
+
+impl Default for WindowRouter {
+    fn default() -> Self {
+        Self::new()
+    }
+}
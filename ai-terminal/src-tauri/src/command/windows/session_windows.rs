@@ -0,0 +1,121 @@
+use crate::command::types::window_router::WindowRouter;
+use serde::Serialize;
+use std::collections::HashMap;
+use tauri::{
+    command, AppHandle, Emitter, Manager, Runtime, State, WebviewUrl, WebviewWindowBuilder, Window,
+};
+
+// Labels of windows opened for sessions; the capability file grants them the main window's
+// permissions by this prefix
+const SESSION_WINDOW_PREFIX: &str = "session-";
+
+#[derive(Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+struct SessionMoveEvent {
+    session_id: String,
+    from_window: Option<String>,
+    to_window: String,
+}
+
+// Sends a session's event only to the window showing it. Sessions that were never attached,
+// or whose window has been closed, fall back to a broadcast.
+pub fn emit_session_event<R: Runtime, S: Serialize + Clone>(
+    app_handle: &AppHandle<R>,
+    session_id: &str,
+    event: &str,
+    payload: S,
+) {
+    let owner = app_handle
+        .state::<WindowRouter>()
+        .owner(session_id)
+        .filter(|label| app_handle.get_webview_window(label).is_some());
+    let _ = match owner {
+        Some(label) => app_handle.emit_to(label.as_str(), event, payload),
+        None => app_handle.emit(event, payload),
+    };
+}
+
+fn move_session<R: Runtime>(
+    app_handle: &AppHandle<R>,
+    session_id: &str,
+    window: &str,
+) -> Result<(), String> {
+    let previous = app_handle
+        .state::<WindowRouter>()
+        .assign(session_id, window)?;
+    if previous.as_deref() == Some(window) {
+        return Ok(());
+    }
+    let event = SessionMoveEvent {
+        session_id: session_id.to_string(),
+        from_window: previous.clone(),
+        to_window: window.to_string(),
+    };
+    // The old window drops its tab without killing the process; the new one adopts it and
+    // can restore the output through the scrollback commands
+    if let Some(previous) = previous {
+        let _ = app_handle.emit_to(previous.as_str(), "session_detached", event.clone());
+    }
+    app_handle
+        .emit_to(window, "session_attached", event)
+        .map_err(|e| e.to_string())
+}
+
+// Called by a window when it creates or restores a session
+#[command]
+pub fn attach_session_to_window<R: Runtime>(
+    session_id: String,
+    window: Window<R>,
+    window_router: State<'_, WindowRouter>,
+) -> Result<(), String> {
+    window_router.assign(&session_id, window.label())?;
+    Ok(())
+}
+
+#[command]
+pub fn move_session_to_window<R: Runtime>(
+    session_id: String,
+    window: String,
+    app_handle: AppHandle<R>,
+) -> Result<(), String> {
+    if app_handle.get_webview_window(&window).is_none() {
+        return Err(format!("No window '{}'", window));
+    }
+    move_session(&app_handle, &session_id, &window)
+}
+
+// Opens a new window for a session dragged out of its current one and moves it there.
+// Returns the new window's label. The window may finish loading after session_attached is
+// sent, so on startup it should look its sessions up with list_session_windows.
+#[command]
+pub fn open_session_window<R: Runtime>(
+    session_id: String,
+    title: Option<String>,
+    app_handle: AppHandle<R>,
+) -> Result<String, String> {
+    let label = format!("{}{}", SESSION_WINDOW_PREFIX, uuid::Uuid::new_v4().simple());
+    WebviewWindowBuilder::new(
+        &app_handle,
+        label.clone(),
+        WebviewUrl::App("index.html".into()),
+    )
+    .title(title.unwrap_or_else(|| "AI Terminal".to_string()))
+    .inner_size(1024.0, 768.0)
+    .min_inner_size(800.0, 600.0)
+    .build()
+    .map_err(|e| format!("Failed to open window: {}", e))?;
+    move_session(&app_handle, &session_id, &label)?;
+    Ok(label)
+}
+
+// Session id -> window label for every attached session
+#[command]
+pub fn list_session_windows(
+    window_router: State<'_, WindowRouter>,
+) -> Result<HashMap<String, String>, String> {
+    Ok(window_router
+        .owners
+        .lock()
+        .map_err(|e| e.to_string())?
+        .clone())
+}
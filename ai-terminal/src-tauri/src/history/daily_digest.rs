@@ -0,0 +1,361 @@
+use crate::command::constants::{
+    AI_RESPONSE_TOKEN_RESERVE, DAILY_DIGEST_COMMANDS_PER_PROJECT, DAILY_DIGEST_FAILURES_PER_PROJECT,
+};
+use crate::command::output::post_processors::redact_text;
+use crate::command::project::project_detection::inspect_project;
+use crate::command::types::command_manager::CommandManager;
+use crate::history::directory_history::project_root;
+use crate::history::types::history_entry::HistoryEntry;
+use crate::history::types::history_store::HistoryStore;
+use crate::ollama::context_filter::is_hosted;
+use crate::ollama::model_request::request::generate;
+use crate::settings::types::settings_manager::SettingsManager;
+use crate::utils::config_utils::{app_data_dir, unix_timestamp_millis};
+use crate::utils::token_estimate::estimate_tokens;
+use chrono::{DateTime, Local, NaiveDate, NaiveTime};
+use serde::{Deserialize, Serialize};
+use std::cmp::Reverse;
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use tauri::{command, AppHandle, Emitter, Manager, Runtime};
+
+const DATE_FORMAT: &str = "%Y-%m-%d";
+const DIGEST_QUESTION: &str = "Summarize the day's terminal work";
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct CommandCount {
+    pub command: String,
+    pub runs: usize,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct FailedCommand {
+    pub command: String,
+    pub exit_code: i32,
+    pub timestamp: u64,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct ProjectActivity {
+    // None for commands run outside any repository or project
+    pub root: Option<String>,
+    pub name: String,
+    pub commands: usize,
+    pub failures: usize,
+    pub first_at: u64,
+    pub last_at: u64,
+    pub total_duration_ms: u64,
+    // Most frequent first, at most DAILY_DIGEST_COMMANDS_PER_PROJECT
+    pub top_commands: Vec<CommandCount>,
+    // Latest last, at most DAILY_DIGEST_FAILURES_PER_PROJECT
+    pub failed_commands: Vec<FailedCommand>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct DailyDigest {
+    // Local date, YYYY-MM-DD
+    pub date: String,
+    pub generated_at: u64,
+    pub model: String,
+    pub total_commands: usize,
+    pub failed_commands: usize,
+    // Busiest first
+    pub projects: Vec<ProjectActivity>,
+    pub summary: String,
+}
+
+fn digest_path(date: NaiveDate) -> Result<PathBuf, String> {
+    let dir = app_data_dir()?.join("digests");
+    fs::create_dir_all(&dir).map_err(|e| format!("Failed to create {}: {}", dir.display(), e))?;
+    Ok(dir.join(format!("{}.json", date.format(DATE_FORMAT))))
+}
+
+pub fn validate_digest_time(time: &str) -> Result<(), String> {
+    parse_digest_time(time).map(|_| ())
+}
+
+fn parse_digest_time(time: &str) -> Result<NaiveTime, String> {
+    NaiveTime::parse_from_str(time.trim(), "%H:%M")
+        .map_err(|_| format!("Invalid daily digest time '{}'; expected HH:MM", time))
+}
+
+fn parse_date(date: Option<&str>) -> Result<NaiveDate, String> {
+    match date.map(str::trim).filter(|date| !date.is_empty()) {
+        Some(date) => NaiveDate::parse_from_str(date, DATE_FORMAT)
+            .map_err(|_| format!("Invalid date '{}'; expected YYYY-MM-DD", date)),
+        None => Ok(Local::now().date_naive()),
+    }
+}
+
+fn local_date(timestamp: u64) -> Option<NaiveDate> {
+    DateTime::from_timestamp_millis(timestamp as i64)
+        .map(|time| time.with_timezone(&Local).date_naive())
+}
+
+fn local_clock(timestamp: u64) -> String {
+    DateTime::from_timestamp_millis(timestamp as i64)
+        .map(|time| time.with_timezone(&Local).format("%H:%M").to_string())
+        .unwrap_or_default()
+}
+
+// The day's commands on this device grouped by project. Synced entries from other machines
+// are left out since their directories mean nothing here. Commands are redacted as the
+// digest is written to disk and meant to be pasted elsewhere.
+pub fn collect_activity(entries: &[HistoryEntry], date: NaiveDate) -> Vec<ProjectActivity> {
+    let mut roots: HashMap<String, Option<String>> = HashMap::new();
+    let mut groups: HashMap<Option<String>, Vec<&HistoryEntry>> = HashMap::new();
+    for entry in entries
+        .iter()
+        .filter(|entry| entry.device_id.is_none() && local_date(entry.timestamp) == Some(date))
+    {
+        let root = roots
+            .entry(entry.cwd.clone())
+            .or_insert_with(|| project_root(Path::new(&entry.cwd)))
+            .clone();
+        groups.entry(root).or_default().push(entry);
+    }
+
+    let mut projects: Vec<ProjectActivity> = groups
+        .into_iter()
+        .map(|(root, entries)| {
+            let name = match &root {
+                Some(root) => inspect_project(Path::new(root))
+                    .map(|project| project.name)
+                    .or_else(|| {
+                        Path::new(root)
+                            .file_name()
+                            .map(|name| name.to_string_lossy().to_string())
+                    })
+                    .unwrap_or_else(|| root.clone()),
+                None => "Other directories".to_string(),
+            };
+            let mut counts: Vec<CommandCount> = Vec::new();
+            for entry in &entries {
+                let command = redact_text(entry.command.trim());
+                match counts.iter_mut().find(|count| count.command == command) {
+                    Some(count) => count.runs += 1,
+                    None => counts.push(CommandCount { command, runs: 1 }),
+                }
+            }
+            // Stable sort keeps first-run order among equally frequent commands
+            counts.sort_by_key(|count| Reverse(count.runs));
+            counts.truncate(DAILY_DIGEST_COMMANDS_PER_PROJECT);
+            let mut failed: Vec<FailedCommand> = entries
+                .iter()
+                .filter_map(|entry| {
+                    entry
+                        .exit_code
+                        .filter(|code| *code != 0)
+                        .map(|exit_code| FailedCommand {
+                            command: redact_text(entry.command.trim()),
+                            exit_code,
+                            timestamp: entry.timestamp,
+                        })
+                })
+                .collect();
+            let failures = failed.len();
+            failed.drain(..failures.saturating_sub(DAILY_DIGEST_FAILURES_PER_PROJECT));
+            ProjectActivity {
+                root,
+                name,
+                commands: entries.len(),
+                failures,
+                first_at: entries
+                    .iter()
+                    .map(|entry| entry.timestamp)
+                    .min()
+                    .unwrap_or(0),
+                last_at: entries
+                    .iter()
+                    .map(|entry| entry.timestamp)
+                    .max()
+                    .unwrap_or(0),
+                total_duration_ms: entries.iter().filter_map(|entry| entry.duration_ms).sum(),
+                top_commands: counts,
+                failed_commands: failed,
+            }
+        })
+        .collect();
+    projects.sort_by(|a, b| {
+        b.commands
+            .cmp(&a.commands)
+            .then_with(|| a.name.cmp(&b.name))
+    });
+    projects
+}
+
+fn project_section(project: &ProjectActivity) -> String {
+    let mut section = format!(
+        "Project {}{}: {} commands, {} failed, {}-{}, {} min running",
+        project.name,
+        project
+            .root
+            .as_ref()
+            .map(|root| format!(" ({})", root))
+            .unwrap_or_default(),
+        project.commands,
+        project.failures,
+        local_clock(project.first_at),
+        local_clock(project.last_at),
+        project.total_duration_ms / 60_000
+    );
+    section.push_str("\nCommands:");
+    for count in &project.top_commands {
+        section.push_str(&format!("\n- {} (x{})", count.command, count.runs));
+    }
+    if !project.failed_commands.is_empty() {
+        section.push_str("\nFailed:");
+        for failed in &project.failed_commands {
+            section.push_str(&format!(
+                "\n- {} (exit {}) at {}",
+                failed.command,
+                failed.exit_code,
+                local_clock(failed.timestamp)
+            ));
+        }
+    }
+    section
+}
+
+// Busiest projects first; quieter ones are only counted once the context window is full
+fn digest_prompt(date: NaiveDate, projects: &[ProjectActivity], context_window: usize) -> String {
+    let mut prompt = format!(
+        "Write short standup notes for {} from the terminal activity below. For each project, \
+         list in a few bullets what was worked on, judging by the commands, and point out \
+         failures that may need follow-up. Do not invent work that is not in the activity.",
+        date.format(DATE_FORMAT)
+    );
+    let available = context_window.saturating_sub(AI_RESPONSE_TOKEN_RESERVE);
+    let mut used = estimate_tokens(&prompt);
+    let mut omitted = 0;
+    for project in projects {
+        let section = project_section(project);
+        let cost = estimate_tokens(&section) + 1;
+        if omitted > 0 || used + cost > available {
+            omitted += 1;
+            continue;
+        }
+        used += cost;
+        prompt.push_str("\n\n");
+        prompt.push_str(&section);
+    }
+    if omitted > 0 {
+        prompt.push_str(&format!(
+            "\n\n[{} quieter projects omitted to fit the context window]",
+            omitted
+        ));
+    }
+    prompt
+}
+
+// Summarizes the day with the local model and stores the digest, replacing an earlier one.
+// None when nothing was run that day.
+async fn create_digest<R: Runtime>(
+    app_handle: &AppHandle<R>,
+    date: NaiveDate,
+) -> Result<Option<DailyDigest>, String> {
+    let projects = {
+        let history_store = app_handle.state::<HistoryStore>();
+        let entries = history_store.entries()?;
+        collect_activity(entries.as_deref().unwrap_or_default(), date)
+    };
+    if projects.is_empty() {
+        return Ok(None);
+    }
+    let (provider, api_host, model) = {
+        let command_manager = app_handle.state::<CommandManager>();
+        let ollama_state = command_manager.ollama.lock().map_err(|e| e.to_string())?;
+        (
+            ollama_state.provider,
+            ollama_state.api_host.clone(),
+            ollama_state.current_model.clone(),
+        )
+    };
+    // A day of commands stays on this machine
+    if is_hosted(provider, &api_host) {
+        return Err(format!(
+            "Daily digests only use a local model; {} is not on this machine",
+            api_host
+        ));
+    }
+    let context_window = app_handle
+        .state::<SettingsManager>()
+        .current()
+        .ai_context_window_tokens;
+    let prompt = digest_prompt(date, &projects, context_window);
+    let summary = generate(
+        provider,
+        &api_host,
+        model.clone(),
+        DIGEST_QUESTION,
+        prompt,
+        context_window,
+    )
+    .await?;
+
+    let digest = DailyDigest {
+        date: date.format(DATE_FORMAT).to_string(),
+        generated_at: unix_timestamp_millis(),
+        model,
+        total_commands: projects.iter().map(|project| project.commands).sum(),
+        failed_commands: projects.iter().map(|project| project.failures).sum(),
+        projects,
+        summary: summary.trim().to_string(),
+    };
+    let path = digest_path(date)?;
+    let contents = serde_json::to_string_pretty(&digest).map_err(|e| e.to_string())?;
+    fs::write(&path, contents).map_err(|e| format!("Failed to write {}: {}", path.display(), e))?;
+    Ok(Some(digest))
+}
+
+// Scheduler task: once daily_digest_time has passed, writes today's digest (if there was any
+// activity) and emits daily_digest. Commands run after that are left to generate_daily_digest.
+pub fn run_daily_digest<R: Runtime>(app_handle: &AppHandle<R>) -> Result<(), String> {
+    let settings = app_handle.state::<SettingsManager>().current();
+    if !settings.daily_digest_enabled {
+        return Ok(());
+    }
+    let now = Local::now();
+    if now.time() < parse_digest_time(&settings.daily_digest_time)? {
+        return Ok(());
+    }
+    let date = now.date_naive();
+    if digest_path(date)?.exists() {
+        return Ok(());
+    }
+    if let Some(digest) = tauri::async_runtime::block_on(create_digest(app_handle, date))? {
+        let _ = app_handle.emit("daily_digest", digest);
+    }
+    Ok(())
+}
+
+// The stored digest for `date` (YYYY-MM-DD, today by default), or None if none was written
+#[command]
+pub fn get_daily_digest(date: Option<String>) -> Result<Option<DailyDigest>, String> {
+    let path = digest_path(parse_date(date.as_deref())?)?;
+    if !path.exists() {
+        return Ok(None);
+    }
+    let contents = fs::read_to_string(&path)
+        .map_err(|e| format!("Failed to read {}: {}", path.display(), e))?;
+    serde_json::from_str(&contents)
+        .map(Some)
+        .map_err(|e| format!("Failed to parse {}: {}", path.display(), e))
+}
+
+// Writes (or rewrites) the digest for `date` now, whether or not scheduled digests are on
+#[command]
+pub async fn generate_daily_digest<R: Runtime>(
+    date: Option<String>,
+    app_handle: AppHandle<R>,
+) -> Result<DailyDigest, String> {
+    let date = parse_date(date.as_deref())?;
+    create_digest(&app_handle, date)
+        .await?
+        .ok_or_else(|| format!("No commands were recorded on {}", date.format(DATE_FORMAT)))
+}
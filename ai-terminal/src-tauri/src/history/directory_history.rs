@@ -0,0 +1,86 @@
+use crate::command::constants::DEFAULT_RECENT_DIRECTORIES;
+use crate::command::project::project_detection::{find_project, inspect_project};
+use crate::history::types::directory_visit::{DirectoryVisit, RecentDirectory, RecentProject};
+use crate::history::types::history_store::HistoryStore;
+use crate::utils::config_utils::unix_timestamp_millis;
+use std::collections::HashMap;
+use std::path::Path;
+use tauri::{command, AppHandle, Manager, Runtime, State};
+
+// Adds the session's new working directory to the directory history
+pub fn record_directory_visit<R: Runtime>(app_handle: &AppHandle<R>, session_id: &str, path: &str) {
+    let visit = DirectoryVisit {
+        path: path.to_string(),
+        session_id: session_id.to_string(),
+        timestamp: unix_timestamp_millis(),
+    };
+    if let Err(e) = app_handle.state::<HistoryStore>().record_directory(visit) {
+        eprintln!("[history] Failed to record directory: {}", e);
+    }
+}
+
+// Repository root containing `path`, or the nearest project root for projects outside git
+pub fn project_root(path: &Path) -> Option<String> {
+    path.ancestors()
+        .find(|ancestor| ancestor.join(".git").exists())
+        .map(|root| root.to_string_lossy().to_string())
+        .or_else(|| find_project(path).map(|project| project.root))
+}
+
+// Directories deleted since they were visited are left out
+#[command]
+pub fn get_recent_directories(
+    limit: Option<usize>,
+    history_store: State<'_, HistoryStore>,
+) -> Result<Vec<RecentDirectory>, String> {
+    Ok(history_store
+        .recent_directories()?
+        .into_iter()
+        .filter(|directory| Path::new(&directory.path).is_dir())
+        .take(limit.unwrap_or(DEFAULT_RECENT_DIRECTORIES))
+        .collect())
+}
+
+// Projects the recent directories belong to, one per repository root (or project root for
+// projects outside git), most recent first
+#[command]
+pub fn get_recent_projects(
+    limit: Option<usize>,
+    history_store: State<'_, HistoryStore>,
+) -> Result<Vec<RecentProject>, String> {
+    let limit = limit.unwrap_or(DEFAULT_RECENT_DIRECTORIES);
+    let mut projects: Vec<RecentProject> = Vec::new();
+    let mut positions: HashMap<String, usize> = HashMap::new();
+    // Many visits share a project; resolve each directory only once
+    let mut roots: HashMap<String, Option<String>> = HashMap::new();
+
+    for directory in history_store.recent_directories()? {
+        let root = roots
+            .entry(directory.path.clone())
+            .or_insert_with(|| project_root(Path::new(&directory.path)))
+            .clone();
+        let Some(root) = root else {
+            continue;
+        };
+        if let Some(&index) = positions.get(&root) {
+            projects[index].visits += directory.visits;
+            continue;
+        }
+        if projects.len() >= limit {
+            continue;
+        }
+        let Some(project) = inspect_project(Path::new(&root)) else {
+            continue;
+        };
+        positions.insert(root.clone(), projects.len());
+        projects.push(RecentProject {
+            root,
+            name: project.name,
+            project_type: project.project_type,
+            last_directory: directory.path,
+            last_visited: directory.last_visited,
+            visits: directory.visits,
+        });
+    }
+    Ok(projects)
+}
@@ -0,0 +1,142 @@
+use crate::command::constants::{DEFAULT_DIRECTORY_SUGGESTIONS, DIRECTORY_SUGGESTION_HALF_LIFE_MS};
+use crate::command::correction::command_not_found::COMMAND_NOT_FOUND_EXIT_CODE;
+use crate::command::project::project_detection::{find_project, session_project_dir};
+use crate::command::types::command_manager::CommandManager;
+use crate::history::types::history_store::HistoryStore;
+use crate::utils::config_utils::unix_timestamp_millis;
+use serde::Serialize;
+use std::collections::HashMap;
+use std::path::Path;
+use tauri::{command, State};
+
+// Navigation and housekeeping that say nothing about the project
+const IGNORED_PROGRAMS: &[&str] = &["cd", "ls", "ll", "la", "pwd", "clear", "exit", "history"];
+
+// Commands run in the directory itself count double against ones run elsewhere in the project
+const SAME_DIRECTORY_WEIGHT: f64 = 2.0;
+const PROJECT_WEIGHT: f64 = 1.0;
+
+#[derive(Serialize, Clone, Copy, Debug, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum SuggestionSource {
+    History,
+    // Build/test/run command detected for a project with no matching history
+    Project,
+}
+
+#[derive(Serialize, Clone, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct DirectorySuggestion {
+    pub command: String,
+    pub source: SuggestionSource,
+    pub runs: usize,
+    pub failures: usize,
+    pub last_run: Option<u64>,
+}
+
+#[derive(Default)]
+struct Tally {
+    score: f64,
+    runs: usize,
+    failures: usize,
+    last_run: u64,
+}
+
+fn is_ignored(command: &str) -> bool {
+    command
+        .split_whitespace()
+        .next()
+        .is_none_or(|program| IGNORED_PROGRAMS.contains(&program))
+}
+
+// Recent runs count more; a run loses half its weight every half-life
+fn recency(now: u64, timestamp: u64) -> f64 {
+    let age = now.saturating_sub(timestamp) as f64;
+    0.5f64.powf(age / DIRECTORY_SUGGESTION_HALF_LIFE_MS as f64)
+}
+
+// Commands most often run in the session's directory and the project around it, best first,
+// topped up with the project's detected build/test/run commands
+#[command]
+pub fn get_directory_suggestions(
+    session_id: String,
+    limit: Option<usize>,
+    command_manager: State<'_, CommandManager>,
+    history_store: State<'_, HistoryStore>,
+) -> Result<Vec<DirectorySuggestion>, String> {
+    let limit = limit.unwrap_or(DEFAULT_DIRECTORY_SUGGESTIONS);
+    let cwd = session_project_dir(&command_manager, &session_id)?;
+    let project = find_project(&cwd);
+    let project_root = project.as_ref().map(|project| Path::new(&project.root));
+    let now = unix_timestamp_millis();
+
+    let mut tallies: HashMap<String, Tally> = HashMap::new();
+    {
+        let entries = history_store.entries()?;
+        for entry in entries.iter().flatten() {
+            if entry.exit_code == Some(COMMAND_NOT_FOUND_EXIT_CODE) || is_ignored(&entry.command) {
+                continue;
+            }
+            let entry_dir = Path::new(&entry.cwd);
+            let weight = if entry_dir == cwd {
+                SAME_DIRECTORY_WEIGHT
+            } else if project_root.is_some_and(|root| entry_dir.starts_with(root)) {
+                PROJECT_WEIGHT
+            } else {
+                continue;
+            };
+            let tally = tallies.entry(entry.command.clone()).or_default();
+            tally.score += weight * recency(now, entry.timestamp);
+            tally.runs += 1;
+            if entry.exit_code.is_some_and(|code| code != 0) {
+                tally.failures += 1;
+            }
+            tally.last_run = tally.last_run.max(entry.timestamp);
+        }
+    }
+
+    let mut ranked: Vec<(String, Tally)> = tallies.into_iter().collect();
+    ranked.sort_by(|(a_command, a), (b_command, b)| {
+        b.score
+            .total_cmp(&a.score)
+            .then_with(|| a_command.cmp(b_command))
+    });
+    let mut suggestions: Vec<DirectorySuggestion> = ranked
+        .into_iter()
+        .take(limit)
+        .map(|(command, tally)| DirectorySuggestion {
+            command,
+            source: SuggestionSource::History,
+            runs: tally.runs,
+            failures: tally.failures,
+            last_run: Some(tally.last_run),
+        })
+        .collect();
+
+    if let Some(project) = project {
+        let detected = [
+            project.commands.test,
+            project.commands.build,
+            project.commands.run,
+        ];
+        for command in detected.into_iter().flatten() {
+            if suggestions.len() >= limit {
+                break;
+            }
+            if suggestions
+                .iter()
+                .any(|suggestion| suggestion.command == command)
+            {
+                continue;
+            }
+            suggestions.push(DirectorySuggestion {
+                command,
+                source: SuggestionSource::Project,
+                runs: 0,
+                failures: 0,
+                last_run: None,
+            });
+        }
+    }
+    Ok(suggestions)
+}
@@ -0,0 +1,53 @@
+use crate::history::types::history_entry::HistoryHit;
+use crate::history::types::history_search::{SearchMode, SearchResult};
+use crate::history::types::history_store::HistoryStore;
+use tauri::{command, State};
+
+// Up arrow: the next older command starting with `prefix` (an empty prefix matches everything)
+#[command]
+pub fn history_prev(
+    session_id: String,
+    cursor: Option<usize>,
+    prefix: Option<String>,
+    history_store: State<'_, HistoryStore>,
+) -> Result<Option<HistoryHit>, String> {
+    history_store.previous(&session_id, cursor, prefix.as_deref().unwrap_or(""))
+}
+
+// Down arrow: the newer command, or None to return to the line being edited
+#[command]
+pub fn history_next(
+    session_id: String,
+    cursor: Option<usize>,
+    prefix: Option<String>,
+    history_store: State<'_, HistoryStore>,
+) -> Result<Option<HistoryHit>, String> {
+    history_store.next(&session_id, cursor, prefix.as_deref().unwrap_or(""))
+}
+
+// Ctrl-R: call on every keystroke with the current query, and with `older` set when Ctrl-R is
+// pressed again. Returns None when nothing (further) matches.
+#[command]
+pub fn history_search(
+    session_id: String,
+    query: String,
+    mode: Option<SearchMode>,
+    older: Option<bool>,
+    history_store: State<'_, HistoryStore>,
+) -> Result<Option<SearchResult>, String> {
+    history_store.search(
+        &session_id,
+        &query,
+        mode.unwrap_or_default(),
+        older.unwrap_or(false),
+    )
+}
+
+// Called when the search overlay closes (accepted or cancelled)
+#[command]
+pub fn history_search_reset(
+    session_id: String,
+    history_store: State<'_, HistoryStore>,
+) -> Result<(), String> {
+    history_store.reset_search(&session_id)
+}
@@ -0,0 +1,63 @@
+use crate::command::constants::HOST_HISTORY_LIMIT;
+use crate::history::types::history_entry::HistoryHit;
+use crate::history::types::history_store::HistoryStore;
+use tauri::{command, State};
+
+// Host name of an SSH destination (`user@host`, `ssh://user@host:port`), lowercased so
+// `Web1` and `deploy@web1` share one history
+pub fn normalize_host(destination: &str) -> Option<String> {
+    let destination = destination.trim();
+    let (destination, is_uri) = match destination.strip_prefix("ssh://") {
+        Some(rest) => (rest.trim_end_matches('/'), true),
+        None => (destination, false),
+    };
+    let host = destination
+        .rsplit_once('@')
+        .map_or(destination, |(_, host)| host);
+    let host = if let Some(bracketed) = host.strip_prefix('[') {
+        bracketed.split(']').next().unwrap_or_default()
+    } else if is_uri {
+        host.split(':').next().unwrap_or_default()
+    } else {
+        host
+    };
+    if host.is_empty() {
+        None
+    } else {
+        Some(host.to_lowercase())
+    }
+}
+
+// Host of an interactive SSH session from its options and destination (the destination is last)
+pub fn ssh_host(target: &[String]) -> Option<String> {
+    target
+        .last()
+        .filter(|destination| !destination.starts_with('-'))
+        .and_then(|destination| normalize_host(destination))
+}
+
+// Commands sent to `host` in earlier and current SSH sessions, most recent first
+#[command]
+pub fn get_host_history(
+    host: String,
+    limit: Option<usize>,
+    history_store: State<'_, HistoryStore>,
+) -> Result<Vec<HistoryHit>, String> {
+    let host = normalize_host(&host).ok_or("Host is empty")?;
+    let entries = history_store.entries()?;
+    Ok(entries
+        .as_deref()
+        .unwrap_or_default()
+        .iter()
+        .enumerate()
+        .rev()
+        .filter(|(_, entry)| entry.host.as_deref() == Some(host.as_str()))
+        .take(limit.unwrap_or(HOST_HISTORY_LIMIT))
+        .map(|(cursor, entry)| HistoryHit {
+            cursor,
+            command: entry.command.clone(),
+            cwd: entry.cwd.clone(),
+            timestamp: entry.timestamp,
+        })
+        .collect())
+}
@@ -0,0 +1,10 @@
+pub mod daily_digest;
+pub mod directory_history;
+pub mod directory_suggestions;
+pub mod history_commands;
+pub mod host_history;
+pub mod recorder;
+pub mod shell_import;
+pub mod sync;
+pub mod types;
+pub mod usage_stats;
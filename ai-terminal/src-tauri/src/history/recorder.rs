@@ -0,0 +1,66 @@
+use crate::command::autocomplete::command_names::learn_command;
+use crate::history::types::history_entry::HistoryEntry;
+use crate::history::types::history_store::HistoryStore;
+use crate::utils::config_utils::unix_timestamp_millis;
+use tauri::{AppHandle, Manager, Runtime};
+
+// Adds an executed command to the shared history; blank commands are ignored
+pub fn record_history_entry<R: Runtime>(
+    app_handle: &AppHandle<R>,
+    session_id: &str,
+    command: &str,
+    cwd: &str,
+    exit_code: Option<i32>,
+    duration_ms: Option<u64>,
+) {
+    record_entry(
+        app_handle,
+        session_id,
+        command,
+        cwd,
+        exit_code,
+        duration_ms,
+        None,
+    );
+}
+
+// Adds a command sent to an SSH session, tagged with the remote host when it is known
+pub fn record_remote_history_entry<R: Runtime>(
+    app_handle: &AppHandle<R>,
+    session_id: &str,
+    command: &str,
+    cwd: &str,
+    host: Option<String>,
+) {
+    record_entry(app_handle, session_id, command, cwd, None, None, host);
+}
+
+fn record_entry<R: Runtime>(
+    app_handle: &AppHandle<R>,
+    session_id: &str,
+    command: &str,
+    cwd: &str,
+    exit_code: Option<i32>,
+    duration_ms: Option<u64>,
+    host: Option<String>,
+) {
+    let command = command.trim();
+    if command.is_empty() {
+        return;
+    }
+    let entry = HistoryEntry {
+        command: command.to_string(),
+        session_id: session_id.to_string(),
+        cwd: cwd.to_string(),
+        timestamp: unix_timestamp_millis(),
+        exit_code,
+        duration_ms,
+        device_id: None,
+        host,
+    };
+    if let Err(e) = app_handle.state::<HistoryStore>().append(entry) {
+        eprintln!("[history] Failed to record command: {}", e);
+        return;
+    }
+    learn_command(app_handle, command, exit_code);
+}
@@ -0,0 +1,228 @@
+use crate::command::constants::MAX_HISTORY_ENTRIES;
+use crate::history::types::history_entry::HistoryEntry;
+use crate::history::types::history_store::HistoryStore;
+use serde::Serialize;
+use std::fs;
+use std::path::PathBuf;
+use std::time::UNIX_EPOCH;
+use tauri::{command, State};
+
+// zsh stores bytes >= 0x83 as this marker followed by the byte XOR 0x20
+const ZSH_META: u8 = 0x83;
+
+// A command read from a shell history file; `timestamp` is in unix seconds when recorded
+#[derive(Debug, PartialEq)]
+pub struct ImportedCommand {
+    pub command: String,
+    pub timestamp: Option<u64>,
+}
+
+#[derive(Serialize, Clone, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct ImportReport {
+    pub shell: String,
+    pub path: String,
+    pub imported: usize,
+}
+
+// Plain lines, optionally preceded by `#<epoch>` lines when HISTTIMEFORMAT was set
+pub fn parse_bash_history(contents: &str) -> Vec<ImportedCommand> {
+    let mut commands = Vec::new();
+    let mut timestamp = None;
+    for line in contents.lines() {
+        if let Some(epoch) = line
+            .strip_prefix('#')
+            .and_then(|rest| rest.parse::<u64>().ok())
+        {
+            timestamp = Some(epoch);
+            continue;
+        }
+        if !line.trim().is_empty() {
+            commands.push(ImportedCommand {
+                command: line.to_string(),
+                timestamp: timestamp.take(),
+            });
+        }
+    }
+    commands
+}
+
+fn unmetafy(bytes: &[u8]) -> Vec<u8> {
+    let mut output = Vec::with_capacity(bytes.len());
+    let mut iter = bytes.iter();
+    while let Some(&byte) = iter.next() {
+        if byte == ZSH_META {
+            if let Some(&next) = iter.next() {
+                output.push(next ^ 0x20);
+            }
+        } else {
+            output.push(byte);
+        }
+    }
+    output
+}
+
+// Plain or EXTENDED_HISTORY (`: <start>:<elapsed>;command`) entries; multi-line commands
+// continue on lines ending in a backslash
+pub fn parse_zsh_history(bytes: &[u8]) -> Vec<ImportedCommand> {
+    let contents = String::from_utf8_lossy(&unmetafy(bytes)).to_string();
+    let mut commands = Vec::new();
+    let mut lines = contents.lines();
+    while let Some(line) = lines.next() {
+        let mut entry = line.to_string();
+        while entry.ends_with('\\') {
+            let Some(next) = lines.next() else {
+                break;
+            };
+            entry.pop();
+            entry.push('\n');
+            entry.push_str(next);
+        }
+
+        let extended = entry.strip_prefix(": ").and_then(|rest| {
+            let (header, command) = rest.split_once(';')?;
+            let start = header.split(':').next()?.trim().parse::<u64>().ok()?;
+            Some((start, command.to_string()))
+        });
+        let (timestamp, command) = match extended {
+            Some((start, command)) => (Some(start), command),
+            None => (None, entry),
+        };
+        if !command.trim().is_empty() {
+            commands.push(ImportedCommand { command, timestamp });
+        }
+    }
+    commands
+}
+
+fn unescape_fish(value: &str) -> String {
+    let mut output = String::with_capacity(value.len());
+    let mut chars = value.chars();
+    while let Some(c) = chars.next() {
+        if c != '\\' {
+            output.push(c);
+            continue;
+        }
+        match chars.next() {
+            Some('n') => output.push('\n'),
+            Some('\\') => output.push('\\'),
+            Some(other) => {
+                output.push('\\');
+                output.push(other);
+            }
+            None => output.push('\\'),
+        }
+    }
+    output
+}
+
+// fish's YAML-like format: `- cmd: ...` followed by `  when: <epoch>` (and `  paths:`)
+pub fn parse_fish_history(contents: &str) -> Vec<ImportedCommand> {
+    let mut commands: Vec<ImportedCommand> = Vec::new();
+    for line in contents.lines() {
+        if let Some(command) = line.strip_prefix("- cmd: ") {
+            commands.push(ImportedCommand {
+                command: unescape_fish(command),
+                timestamp: None,
+            });
+        } else if let Some(when) = line.trim_start().strip_prefix("when: ") {
+            if let Some(last) = commands.last_mut() {
+                last.timestamp = when.trim().parse().ok();
+            }
+        }
+    }
+    commands.retain(|command| !command.command.trim().is_empty());
+    commands
+}
+
+fn default_history_path(shell: &str) -> Result<PathBuf, String> {
+    let home = dirs::home_dir().ok_or("Could not determine home directory")?;
+    Ok(match shell {
+        "bash" => home.join(".bash_history"),
+        "zsh" => home.join(".zsh_history"),
+        "fish" => dirs::data_dir()
+            .unwrap_or_else(|| home.join(".local/share"))
+            .join("fish")
+            .join("fish_history"),
+        other => {
+            return Err(format!(
+                "Unsupported shell '{}': use bash, zsh or fish",
+                other
+            ))
+        }
+    })
+}
+
+// Imports a shell's history file into the app history. Commands without a recorded time get
+// times just before the file's modification time so their order is kept. Importing the same
+// shell again replaces the earlier import instead of duplicating it.
+#[command]
+pub fn import_shell_history(
+    shell: String,
+    path: Option<String>,
+    history_store: State<'_, HistoryStore>,
+) -> Result<ImportReport, String> {
+    let shell = shell.trim().to_lowercase();
+    let path = match path {
+        Some(path) => PathBuf::from(path),
+        None => default_history_path(&shell)?,
+    };
+    let bytes = fs::read(&path).map_err(|e| format!("Failed to read {}: {}", path.display(), e))?;
+    let commands = match shell.as_str() {
+        "bash" => parse_bash_history(&String::from_utf8_lossy(&bytes)),
+        "zsh" => parse_zsh_history(&bytes),
+        "fish" => parse_fish_history(&String::from_utf8_lossy(&bytes)),
+        other => {
+            return Err(format!(
+                "Unsupported shell '{}': use bash, zsh or fish",
+                other
+            ))
+        }
+    };
+
+    let modified_ms = fs::metadata(&path)
+        .and_then(|metadata| metadata.modified())
+        .ok()
+        .and_then(|modified| modified.duration_since(UNIX_EPOCH).ok())
+        .map(|duration| duration.as_millis() as u64)
+        .unwrap_or(0);
+    let source = format!("import:{}", shell);
+    let cwd = dirs::home_dir()
+        .map(|home| home.to_string_lossy().to_string())
+        .unwrap_or_default();
+    let count = commands.len() as u64;
+    let imported: Vec<HistoryEntry> = commands
+        .into_iter()
+        .enumerate()
+        .map(|(index, command)| HistoryEntry {
+            command: command.command,
+            session_id: source.clone(),
+            cwd: cwd.clone(),
+            timestamp: command
+                .timestamp
+                .map(|seconds| seconds * 1000)
+                .unwrap_or_else(|| modified_ms.saturating_sub(count - index as u64)),
+            exit_code: None,
+            duration_ms: None,
+            device_id: None,
+            host: None,
+        })
+        .collect();
+    let imported_count = imported.len();
+
+    history_store.replace_with(|entries| {
+        entries.retain(|entry| entry.session_id != source);
+        entries.extend(imported);
+        // Stable, so entries recorded with equal times keep their relative order
+        entries.sort_by_key(|entry| entry.timestamp);
+        if entries.len() > MAX_HISTORY_ENTRIES {
+            entries.drain(..entries.len() - MAX_HISTORY_ENTRIES);
+        }
+    })?;
+
+    Ok(ImportReport {
+        shell,
+        path: path.to_string_lossy().to_string(),
+        imported: imported_count,
+    })
+}
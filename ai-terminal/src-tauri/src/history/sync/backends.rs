@@ -0,0 +1,267 @@
+use crate::command::git_commands::git::new_git_command;
+use crate::history::types::sync_config::SyncBackend;
+use crate::utils::config_utils::app_data_dir;
+use reqwest::StatusCode;
+use std::fs;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
+
+pub const SYNC_FILE_NAME: &str = "ai-terminal-history.sync";
+const GIT_CHECKOUT_DIR: &str = "history_sync_git";
+const DEFAULT_GIT_BRANCH: &str = "main";
+
+fn webdav_request(
+    method: reqwest::Method,
+    url: &str,
+    username: Option<&str>,
+    password: Option<&str>,
+) -> reqwest::RequestBuilder {
+    let request = reqwest::Client::new().request(
+        method,
+        format!("{}/{}", url.trim_end_matches('/'), SYNC_FILE_NAME),
+    );
+    match username {
+        Some(username) => request.basic_auth(username, password),
+        None => request,
+    }
+}
+
+fn s3_object_uri(uri: &str) -> Result<String, String> {
+    if !uri.starts_with("s3://") {
+        return Err(format!(
+            "S3 location must look like s3://bucket/prefix, got '{}'",
+            uri
+        ));
+    }
+    Ok(format!("{}/{}", uri.trim_end_matches('/'), SYNC_FILE_NAME))
+}
+
+fn aws_command(profile: Option<&str>) -> Command {
+    let mut command = Command::new("aws");
+    if let Some(profile) = profile {
+        command.args(["--profile", profile]);
+    }
+    command
+}
+
+fn s3_download(uri: &str, profile: Option<&str>) -> Result<Option<Vec<u8>>, String> {
+    let output = aws_command(profile)
+        .args(["s3", "cp", &s3_object_uri(uri)?, "-"])
+        .output()
+        .map_err(|e| format!("Failed to run the aws CLI: {}", e))?;
+    if output.status.success() {
+        return Ok(Some(output.stdout));
+    }
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    if stderr.contains("(404)") || stderr.contains("NoSuchKey") {
+        Ok(None)
+    } else {
+        Err(format!("aws s3 cp failed: {}", stderr.trim()))
+    }
+}
+
+fn s3_upload(uri: &str, profile: Option<&str>, data: &[u8]) -> Result<(), String> {
+    let mut child = aws_command(profile)
+        .args(["s3", "cp", "-", &s3_object_uri(uri)?])
+        .stdin(Stdio::piped())
+        .stdout(Stdio::null())
+        .stderr(Stdio::piped())
+        .spawn()
+        .map_err(|e| format!("Failed to run the aws CLI: {}", e))?;
+    if let Some(mut stdin) = child.stdin.take() {
+        stdin
+            .write_all(data)
+            .map_err(|e| format!("Failed to send history to the aws CLI: {}", e))?;
+    }
+    let output = child.wait_with_output().map_err(|e| e.to_string())?;
+    if output.status.success() {
+        Ok(())
+    } else {
+        Err(format!(
+            "aws s3 cp failed: {}",
+            String::from_utf8_lossy(&output.stderr).trim()
+        ))
+    }
+}
+
+fn git(checkout: &Path, args: &[&str]) -> Result<String, String> {
+    let output = new_git_command()
+        .arg("-C")
+        .arg(checkout)
+        .args(args)
+        .output()
+        .map_err(|e| format!("Failed to run git: {}", e))?;
+    if output.status.success() {
+        Ok(String::from_utf8_lossy(&output.stdout).to_string())
+    } else {
+        Err(format!(
+            "git {} failed: {}",
+            args.first().unwrap_or(&""),
+            String::from_utf8_lossy(&output.stderr).trim()
+        ))
+    }
+}
+
+// Brings the app's private checkout to the remote branch tip, creating it on first use
+fn prepare_git_checkout(repository: &str, branch: &str) -> Result<PathBuf, String> {
+    let checkout = app_data_dir()?.join(GIT_CHECKOUT_DIR);
+    if !checkout.join(".git").is_dir() {
+        fs::create_dir_all(&checkout)
+            .map_err(|e| format!("Failed to create {}: {}", checkout.display(), e))?;
+        git(&checkout, &["init", "--quiet"])?;
+        git(&checkout, &["remote", "add", "origin", repository])?;
+    } else {
+        git(&checkout, &["remote", "set-url", "origin", repository])?;
+    }
+    git(&checkout, &["fetch", "--quiet", "origin"])?;
+    let remote_branch = format!("origin/{}", branch);
+    if git(
+        &checkout,
+        &["rev-parse", "--verify", "--quiet", &remote_branch],
+    )
+    .is_ok()
+    {
+        git(
+            &checkout,
+            &[
+                "checkout",
+                "--quiet",
+                "--force",
+                "-B",
+                branch,
+                &remote_branch,
+            ],
+        )?;
+    } else {
+        // First sync into an empty repository or a new branch
+        git(&checkout, &["checkout", "--quiet", "--force", "-B", branch])?;
+    }
+    Ok(checkout)
+}
+
+fn git_download(repository: &str, branch: &str) -> Result<Option<Vec<u8>>, String> {
+    let checkout = prepare_git_checkout(repository, branch)?;
+    match fs::read(checkout.join(SYNC_FILE_NAME)) {
+        Ok(data) => Ok(Some(data)),
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(None),
+        Err(e) => Err(e.to_string()),
+    }
+}
+
+// Commits on top of the checkout prepared by git_download; a rejected push (another machine
+// synced in between) surfaces as an error and the next sync merges both
+fn git_upload(repository: &str, branch: &str, data: &[u8]) -> Result<(), String> {
+    let checkout = app_data_dir()?.join(GIT_CHECKOUT_DIR);
+    if !checkout.join(".git").is_dir() {
+        prepare_git_checkout(repository, branch)?;
+    }
+    let path = checkout.join(SYNC_FILE_NAME);
+    fs::write(&path, data).map_err(|e| format!("Failed to write {}: {}", path.display(), e))?;
+    git(&checkout, &["add", SYNC_FILE_NAME])?;
+    if git(&checkout, &["status", "--porcelain"])?
+        .trim()
+        .is_empty()
+    {
+        return Ok(());
+    }
+    git(
+        &checkout,
+        &[
+            "-c",
+            "user.name=AI Terminal",
+            "-c",
+            "user.email=ai-terminal@localhost",
+            "commit",
+            "--quiet",
+            "-m",
+            "Sync command history",
+        ],
+    )?;
+    git(
+        &checkout,
+        &["push", "--quiet", "origin", &format!("HEAD:{}", branch)],
+    )?;
+    Ok(())
+}
+
+// Runs a blocking CLI-based transfer off the async runtime's worker threads
+async fn blocking<T: Send + 'static>(
+    task: impl FnOnce() -> Result<T, String> + Send + 'static,
+) -> Result<T, String> {
+    tauri::async_runtime::spawn_blocking(task)
+        .await
+        .map_err(|e| e.to_string())?
+}
+
+// Fetches the stored bundle; None when nothing has been synced there yet. `password` is only
+// used by WebDAV.
+pub async fn download(
+    backend: &SyncBackend,
+    password: Option<&str>,
+) -> Result<Option<Vec<u8>>, String> {
+    match backend.clone() {
+        SyncBackend::WebDav { url, username } => {
+            let response =
+                webdav_request(reqwest::Method::GET, &url, username.as_deref(), password)
+                    .send()
+                    .await
+                    .map_err(|e| format!("WebDAV request failed: {}", e))?;
+            match response.status() {
+                StatusCode::NOT_FOUND => Ok(None),
+                status if status.is_success() => Ok(Some(
+                    response
+                        .bytes()
+                        .await
+                        .map_err(|e| format!("WebDAV download failed: {}", e))?
+                        .to_vec(),
+                )),
+                status => Err(format!("WebDAV server returned {}", status)),
+            }
+        }
+        SyncBackend::S3 { uri, profile } => {
+            blocking(move || s3_download(&uri, profile.as_deref())).await
+        }
+        SyncBackend::Git { repository, branch } => {
+            blocking(move || {
+                git_download(&repository, branch.as_deref().unwrap_or(DEFAULT_GIT_BRANCH))
+            })
+            .await
+        }
+    }
+}
+
+pub async fn upload(
+    backend: &SyncBackend,
+    password: Option<&str>,
+    data: Vec<u8>,
+) -> Result<(), String> {
+    match backend.clone() {
+        SyncBackend::WebDav { url, username } => {
+            let response =
+                webdav_request(reqwest::Method::PUT, &url, username.as_deref(), password)
+                    .body(data)
+                    .send()
+                    .await
+                    .map_err(|e| format!("WebDAV request failed: {}", e))?;
+            if response.status().is_success() {
+                Ok(())
+            } else {
+                Err(format!("WebDAV server returned {}", response.status()))
+            }
+        }
+        SyncBackend::S3 { uri, profile } => {
+            blocking(move || s3_upload(&uri, profile.as_deref(), &data)).await
+        }
+        SyncBackend::Git { repository, branch } => {
+            blocking(move || {
+                git_upload(
+                    &repository,
+                    branch.as_deref().unwrap_or(DEFAULT_GIT_BRANCH),
+                    &data,
+                )
+            })
+            .await
+        }
+    }
+}
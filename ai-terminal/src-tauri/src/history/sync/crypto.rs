@@ -0,0 +1,34 @@
+use crate::utils::crypto::{open, seal, Cipher, SealedData};
+use serde::{Deserialize, Serialize};
+
+const BUNDLE_VERSION: u32 = 1;
+const BUNDLE_NAME: &str = "synced history";
+
+// What is stored remotely, encrypted with the user's passphrase. The storage provider never
+// sees the key or the history.
+#[derive(Serialize, Deserialize)]
+struct EncryptedBundle {
+    version: u32,
+    #[serde(flatten)]
+    sealed: SealedData,
+}
+
+pub fn encrypt_bundle(passphrase: &str, plaintext: &[u8]) -> Result<Vec<u8>, String> {
+    serde_json::to_vec(&EncryptedBundle {
+        version: BUNDLE_VERSION,
+        sealed: seal(Cipher::Aes256Gcm, passphrase, plaintext, BUNDLE_NAME)?,
+    })
+    .map_err(|e| e.to_string())
+}
+
+pub fn decrypt_bundle(passphrase: &str, data: &[u8]) -> Result<Vec<u8>, String> {
+    let bundle: EncryptedBundle = serde_json::from_slice(data)
+        .map_err(|e| format!("The synced history is not a valid bundle: {}", e))?;
+    if bundle.version != BUNDLE_VERSION {
+        return Err(format!(
+            "The synced history uses format version {}, which this version cannot read",
+            bundle.version
+        ));
+    }
+    open(passphrase, &bundle.sealed, BUNDLE_NAME)
+}
@@ -0,0 +1,3 @@
+pub mod backends;
+pub mod crypto;
+pub mod sync_commands;
@@ -0,0 +1,161 @@
+use crate::command::constants::MAX_HISTORY_ENTRIES;
+use crate::history::sync::backends::{download, upload};
+use crate::history::sync::crypto::{decrypt_bundle, encrypt_bundle};
+use crate::history::types::history_entry::HistoryEntry;
+use crate::history::types::history_store::HistoryStore;
+use crate::history::types::sync_config::{
+    HistorySyncConfig, SyncBackend, HISTORY_SYNC_CONFIG_FILE,
+};
+use crate::utils::config_utils::{load_json_config, save_json_config, unix_timestamp_millis};
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+use tauri::{command, AppHandle, Manager, Runtime};
+
+// Decrypted contents of the remote bundle
+#[derive(Serialize, Deserialize, Default)]
+#[serde(default, rename_all = "camelCase")]
+struct SyncPayload {
+    entries: Vec<HistoryEntry>,
+    updated_at: u64,
+    updated_by: String,
+}
+
+#[derive(Serialize, Clone, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct SyncReport {
+    // Entries from other machines that were new here
+    pub downloaded: usize,
+    // Entries from this machine that were new remotely
+    pub uploaded: usize,
+    pub total: usize,
+    pub synced_at: u64,
+}
+
+type EntryKey = (u64, Option<String>, String, String);
+
+fn entry_key(entry: &HistoryEntry) -> EntryKey {
+    (
+        entry.timestamp,
+        entry.device_id.clone(),
+        entry.session_id.clone(),
+        entry.command.clone(),
+    )
+}
+
+// Union of both histories. Entries are identified by device and timestamp, so the same command
+// run on two machines is kept twice, and ordered by timestamp with the device id breaking ties
+// so every machine ends up with the same list.
+pub fn merge_histories(local: &[HistoryEntry], remote: &[HistoryEntry]) -> Vec<HistoryEntry> {
+    let mut seen: HashSet<EntryKey> = HashSet::new();
+    let mut merged: Vec<HistoryEntry> = local
+        .iter()
+        .chain(remote)
+        .filter(|entry| seen.insert(entry_key(entry)))
+        .cloned()
+        .collect();
+    merged.sort_by(|a, b| {
+        a.timestamp
+            .cmp(&b.timestamp)
+            .then_with(|| a.device_id.cmp(&b.device_id))
+    });
+    if merged.len() > MAX_HISTORY_ENTRIES {
+        merged.drain(..merged.len() - MAX_HISTORY_ENTRIES);
+    }
+    merged
+}
+
+fn load_sync_config() -> Result<HistorySyncConfig, String> {
+    let mut config: HistorySyncConfig = load_json_config(HISTORY_SYNC_CONFIG_FILE);
+    // Older versions stored the WebDAV password; saving drops it from the file
+    let stored: serde_json::Value = load_json_config(HISTORY_SYNC_CONFIG_FILE);
+    if config.device_id.is_none() || stored["backend"].get("password").is_some() {
+        // Kept when only the password goes: it keys this machine's entries in the synced history
+        config
+            .device_id
+            .get_or_insert_with(|| uuid::Uuid::new_v4().to_string());
+        save_json_config(HISTORY_SYNC_CONFIG_FILE, &config)?;
+    }
+    Ok(config)
+}
+
+#[command]
+pub fn get_history_sync_config() -> Result<HistorySyncConfig, String> {
+    load_sync_config()
+}
+
+// Sets (or with None, removes) the storage history is synced to
+#[command]
+pub fn configure_history_sync(backend: Option<SyncBackend>) -> Result<HistorySyncConfig, String> {
+    let mut config = load_sync_config()?;
+    config.backend = backend;
+    config.last_synced_at = None;
+    save_json_config(HISTORY_SYNC_CONFIG_FILE, &config)?;
+    Ok(config)
+}
+
+// Downloads the remote history, merges it into the local one and uploads the result. The
+// passphrase encrypts the bundle end to end; like the WebDAV password, it is never stored.
+#[command]
+pub async fn sync_history<R: Runtime>(
+    passphrase: String,
+    password: Option<String>,
+    app_handle: AppHandle<R>,
+) -> Result<SyncReport, String> {
+    if passphrase.is_empty() {
+        return Err("A passphrase is required to encrypt synced history".to_string());
+    }
+    let mut config = load_sync_config()?;
+    let backend = config
+        .backend
+        .clone()
+        .ok_or("History sync is not configured")?;
+    let device_id = config.device_id.clone().unwrap_or_default();
+
+    let remote: SyncPayload = match download(&backend, password.as_deref()).await? {
+        Some(data) => serde_json::from_slice(&decrypt_bundle(&passphrase, &data)?)
+            .map_err(|e| format!("The synced history could not be read: {}", e))?,
+        None => SyncPayload::default(),
+    };
+
+    let remote_keys: HashSet<EntryKey> = remote.entries.iter().map(entry_key).collect();
+    let mut local_keys: HashSet<EntryKey> = HashSet::new();
+    let merged = app_handle.state::<HistoryStore>().replace_with(|entries| {
+        // Entries recorded before sync was set up belong to this machine
+        for entry in entries.iter_mut() {
+            entry.device_id.get_or_insert_with(|| device_id.clone());
+        }
+        local_keys = entries.iter().map(entry_key).collect();
+        *entries = merge_histories(entries, &remote.entries);
+    })?;
+
+    let synced_at = unix_timestamp_millis();
+    let payload = SyncPayload {
+        entries: merged,
+        updated_at: synced_at,
+        updated_by: device_id,
+    };
+    let plaintext = serde_json::to_vec(&payload).map_err(|e| e.to_string())?;
+    upload(
+        &backend,
+        password.as_deref(),
+        encrypt_bundle(&passphrase, &plaintext)?,
+    )
+    .await?;
+
+    config.last_synced_at = Some(synced_at);
+    save_json_config(HISTORY_SYNC_CONFIG_FILE, &config)?;
+    Ok(SyncReport {
+        downloaded: payload
+            .entries
+            .iter()
+            .filter(|entry| !local_keys.contains(&entry_key(entry)))
+            .count(),
+        uploaded: payload
+            .entries
+            .iter()
+            .filter(|entry| !remote_keys.contains(&entry_key(entry)))
+            .count(),
+        total: payload.entries.len(),
+        synced_at,
+    })
+}
@@ -0,0 +1,31 @@
+use crate::command::project::project_detection::ProjectType;
+use serde::{Deserialize, Serialize};
+
+// A session changing into a directory, persisted next to the command history
+#[derive(Serialize, Deserialize, Clone, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct DirectoryVisit {
+    pub path: String,
+    pub session_id: String,
+    pub timestamp: u64,
+}
+
+#[derive(Serialize, Clone, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct RecentDirectory {
+    pub path: String,
+    pub last_visited: u64,
+    pub visits: usize,
+}
+
+#[derive(Serialize, Clone, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct RecentProject {
+    pub root: String,
+    pub name: String,
+    pub project_type: ProjectType,
+    // Most recently visited directory inside the project
+    pub last_directory: String,
+    pub last_visited: u64,
+    pub visits: usize,
+}
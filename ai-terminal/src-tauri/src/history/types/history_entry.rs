@@ -0,0 +1,29 @@
+use serde::{Deserialize, Serialize};
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct HistoryEntry {
+    pub command: String,
+    pub session_id: String,
+    pub cwd: String,
+    // Unix millis when the command finished (or was sent, for remote commands)
+    pub timestamp: u64,
+    pub exit_code: Option<i32>,
+    pub duration_ms: Option<u64>,
+    // Machine that ran the command; set when history is first synced (None means this device)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub device_id: Option<String>,
+    // Remote host the command was sent to through an SSH session
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub host: Option<String>,
+}
+
+// An entry returned to the frontend together with its position in the store
+#[derive(Serialize, Clone, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct HistoryHit {
+    pub cursor: usize,
+    pub command: String,
+    pub cwd: String,
+    pub timestamp: u64,
+}
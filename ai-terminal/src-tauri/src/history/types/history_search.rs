@@ -0,0 +1,31 @@
+use crate::history::types::history_entry::HistoryHit;
+use serde::{Deserialize, Serialize};
+
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum SearchMode {
+    #[default]
+    Substring,
+    Regex,
+    Fuzzy,
+}
+
+// Incremental Ctrl-R state for one session
+pub struct SearchState {
+    pub query: String,
+    pub mode: SearchMode,
+    // Positions of matches already shown, the current one last
+    pub shown: Vec<usize>,
+}
+
+#[derive(Serialize, Clone, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct SearchResult {
+    pub hit: HistoryHit,
+    // Char indices of the matched characters in the command, for highlighting
+    pub matched_indices: Vec<usize>,
+    pub total_matches: usize,
+    // Commands run just before and after the match in the same session
+    pub context_before: Vec<HistoryHit>,
+    pub context_after: Vec<HistoryHit>,
+}
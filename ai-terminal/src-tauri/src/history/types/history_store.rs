@@ -0,0 +1,439 @@
+use crate::command::constants::{MAX_DIRECTORY_VISITS, MAX_HISTORY_ENTRIES};
+use crate::history::types::directory_visit::{DirectoryVisit, RecentDirectory};
+use crate::history::types::history_entry::{HistoryEntry, HistoryHit};
+use crate::history::types::history_search::{SearchMode, SearchResult, SearchState};
+use crate::utils::config_utils::app_data_dir;
+use crate::utils::string_utils::fuzzy_match;
+use regex::{Regex, RegexBuilder};
+use std::collections::{HashMap, HashSet};
+use std::fs::{self, OpenOptions};
+use std::io::Write;
+use std::path::PathBuf;
+use std::sync::{Mutex, MutexGuard};
+
+pub const HISTORY_FILE: &str = "history.jsonl";
+pub const DIRECTORY_HISTORY_FILE: &str = "directory_history.jsonl";
+const SEARCH_CONTEXT_ENTRIES: usize = 2;
+
+// Up/down navigation in progress for one session: the prefix being matched and the
+// positions already shown, most recent last
+struct Navigation {
+    prefix: String,
+    shown: Vec<usize>,
+}
+
+// Command history shared by all sessions and persisted as JSONL in the data directory
+pub struct HistoryStore {
+    entries: Mutex<Option<Vec<HistoryEntry>>>,
+    navigation: Mutex<HashMap<String, Navigation>>,
+    searches: Mutex<HashMap<String, SearchState>>,
+    directories: Mutex<Option<Vec<DirectoryVisit>>>,
+}
+
+fn history_path() -> Result<PathBuf, String> {
+    Ok(app_data_dir()?.join(HISTORY_FILE))
+}
+
+fn load_entries() -> Vec<HistoryEntry> {
+    let path = match history_path() {
+        Ok(path) => path,
+        Err(_) => return Vec::new(),
+    };
+    let contents = fs::read_to_string(&path).unwrap_or_default();
+    let mut entries: Vec<HistoryEntry> = contents
+        .lines()
+        .filter_map(|line| serde_json::from_str(line).ok())
+        .collect();
+
+    // Compact the file once it has grown well past the limit
+    if entries.len() > MAX_HISTORY_ENTRIES {
+        entries.drain(..entries.len() - MAX_HISTORY_ENTRIES);
+        let compacted: Vec<String> = entries
+            .iter()
+            .filter_map(|entry| serde_json::to_string(entry).ok())
+            .collect();
+        let _ = fs::write(&path, compacted.join("\n") + "\n");
+    }
+    entries
+}
+
+impl HistoryStore {
+    pub fn new() -> Self {
+        Self {
+            entries: Mutex::new(None),
+            navigation: Mutex::new(HashMap::new()),
+            searches: Mutex::new(HashMap::new()),
+            directories: Mutex::new(None),
+        }
+    }
+
+    // Loads the history file on first use so startup does not pay for it
+    pub fn entries(&self) -> Result<MutexGuard<'_, Option<Vec<HistoryEntry>>>, String> {
+        let mut entries = self.entries.lock().map_err(|e| e.to_string())?;
+        if entries.is_none() {
+            *entries = Some(load_entries());
+        }
+        Ok(entries)
+    }
+
+    pub fn append(&self, entry: HistoryEntry) -> Result<(), String> {
+        let line = serde_json::to_string(&entry).map_err(|e| e.to_string())?;
+        let path = history_path()?;
+        let mut guard = self.entries()?;
+        let entries = guard.get_or_insert_with(Vec::new);
+
+        let mut file = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&path)
+            .map_err(|e| format!("Failed to open {}: {}", path.display(), e))?;
+        writeln!(file, "{}", line)
+            .map_err(|e| format!("Failed to write {}: {}", path.display(), e))?;
+
+        entries.push(entry);
+        Ok(())
+    }
+
+    fn hit(entries: &[HistoryEntry], cursor: usize) -> HistoryHit {
+        let entry = &entries[cursor];
+        HistoryHit {
+            cursor,
+            command: entry.command.clone(),
+            cwd: entry.cwd.clone(),
+            timestamp: entry.timestamp,
+        }
+    }
+
+    // Older entry starting with `prefix`, skipping commands already shown in this navigation.
+    // `cursor` is None when navigation starts from the input line. Returns None when there is
+    // nothing older, in which case the current entry stays on screen.
+    pub fn previous(
+        &self,
+        session_id: &str,
+        cursor: Option<usize>,
+        prefix: &str,
+    ) -> Result<Option<HistoryHit>, String> {
+        let guard = self.entries()?;
+        let entries = guard.as_deref().unwrap_or_default();
+        let mut navigation = self.navigation.lock().map_err(|e| e.to_string())?;
+
+        let state = navigation
+            .entry(session_id.to_string())
+            .or_insert_with(|| Navigation {
+                prefix: prefix.to_string(),
+                shown: Vec::new(),
+            });
+        if cursor.is_none() || state.shown.last().copied() != cursor || state.prefix != prefix {
+            state.prefix = prefix.to_string();
+            state.shown.clear();
+        }
+
+        let start = cursor.unwrap_or(entries.len()).min(entries.len());
+        let current = cursor
+            .and_then(|c| entries.get(c))
+            .map(|e| e.command.as_str());
+        let found = (0..start).rev().find(|&i| {
+            let command = entries[i].command.as_str();
+            command.starts_with(prefix)
+                && current != Some(command)
+                && !state
+                    .shown
+                    .iter()
+                    .any(|&shown| entries[shown].command == command)
+        });
+
+        Ok(found.map(|index| {
+            state.shown.push(index);
+            Self::hit(entries, index)
+        }))
+    }
+
+    // Steps back toward the input line. Returns None once the newest match has been passed,
+    // meaning the frontend should restore the user's draft.
+    pub fn next(
+        &self,
+        session_id: &str,
+        cursor: Option<usize>,
+        prefix: &str,
+    ) -> Result<Option<HistoryHit>, String> {
+        let guard = self.entries()?;
+        let entries = guard.as_deref().unwrap_or_default();
+        let mut navigation = self.navigation.lock().map_err(|e| e.to_string())?;
+
+        let Some(state) = navigation.get_mut(session_id) else {
+            return Ok(None);
+        };
+        if cursor.is_none() || state.shown.last().copied() != cursor || state.prefix != prefix {
+            state.shown.clear();
+            return Ok(None);
+        }
+
+        state.shown.pop();
+        Ok(state
+            .shown
+            .last()
+            .filter(|&&index| index < entries.len())
+            .map(|&index| Self::hit(entries, index)))
+    }
+}
+
+// Scores a command against the query (higher is better) and returns the matched char indices
+type Matcher = Box<dyn Fn(&str) -> Option<(i64, Vec<usize>)>>;
+
+fn build_matcher(query: &str, mode: SearchMode) -> Result<Matcher, String> {
+    let regex = match mode {
+        // Smart case: case-sensitive only when the query has uppercase letters. Matched as a
+        // regex rather than on a lowercased copy, whose characters need not line up with the
+        // command's (e.g. İ lowercases to two).
+        SearchMode::Substring => RegexBuilder::new(&regex::escape(query))
+            .case_insensitive(!query.chars().any(char::is_uppercase))
+            .build()
+            .map_err(|e| format!("Invalid search: {}", e))?,
+        SearchMode::Regex => Regex::new(query).map_err(|e| format!("Invalid regex: {}", e))?,
+        SearchMode::Fuzzy => {
+            let pattern = query.to_string();
+            return Ok(Box::new(move |command: &str| {
+                fuzzy_match(&pattern, command)
+            }));
+        }
+    };
+    Ok(Box::new(move |command: &str| {
+        let found = regex.find(command)?;
+        let first = command[..found.start()].chars().count();
+        Some((0, (first..first + found.as_str().chars().count()).collect()))
+    }))
+}
+
+impl HistoryStore {
+    // Incremental reverse search. Typing keeps the current match while it still matches;
+    // `older` (pressing Ctrl-R again) moves to the next match. Substring and regex matches are
+    // ordered by recency, fuzzy matches by score.
+    pub fn search(
+        &self,
+        session_id: &str,
+        query: &str,
+        mode: SearchMode,
+        older: bool,
+    ) -> Result<Option<SearchResult>, String> {
+        let guard = self.entries()?;
+        let entries = guard.as_deref().unwrap_or_default();
+        let mut searches = self.searches.lock().map_err(|e| e.to_string())?;
+        let state = searches
+            .entry(session_id.to_string())
+            .or_insert_with(|| SearchState {
+                query: String::new(),
+                mode,
+                shown: Vec::new(),
+            });
+        if query.is_empty() {
+            state.shown.clear();
+            state.query.clear();
+            return Ok(None);
+        }
+
+        let matcher = build_matcher(query, mode)?;
+        let mut ranked: Vec<(usize, i64, Vec<usize>)> = Vec::new();
+        let mut seen = HashSet::new();
+        for (index, entry) in entries.iter().enumerate().rev() {
+            if seen.contains(entry.command.as_str()) {
+                continue;
+            }
+            if let Some((score, indices)) = matcher(&entry.command) {
+                seen.insert(entry.command.as_str());
+                ranked.push((index, score, indices));
+            }
+        }
+        if mode == SearchMode::Fuzzy {
+            // Stable sort keeps recency order among equal scores
+            ranked.sort_by_key(|(_, score, _)| std::cmp::Reverse(*score));
+        }
+
+        let same_search = state.mode == mode && query.starts_with(state.query.as_str());
+        if !same_search {
+            state.shown.clear();
+        }
+        state.query = query.to_string();
+        state.mode = mode;
+
+        let current = state
+            .shown
+            .last()
+            .and_then(|&shown| ranked.iter().position(|(index, _, _)| *index == shown));
+        let position = match (older, current) {
+            (true, Some(current)) => current + 1,
+            // Fuzzy rankings change with every keystroke, so always show the best match
+            (false, Some(current)) if mode != SearchMode::Fuzzy => current,
+            _ => 0,
+        };
+        let Some((index, _, matched_indices)) = ranked.get(position).cloned() else {
+            // No further match: stay on the current one, as shells do
+            return Ok(None);
+        };
+        if state.shown.last() != Some(&index) {
+            state.shown.push(index);
+        }
+
+        let session = &entries[index].session_id;
+        let neighbours = |range: Box<dyn Iterator<Item = usize>>| -> Vec<HistoryHit> {
+            range
+                .filter(|&i| entries[i].session_id == *session)
+                .take(SEARCH_CONTEXT_ENTRIES)
+                .map(|i| Self::hit(entries, i))
+                .collect()
+        };
+        let mut context_before = neighbours(Box::new((0..index).rev()));
+        context_before.reverse();
+        let context_after = neighbours(Box::new(index + 1..entries.len()));
+
+        Ok(Some(SearchResult {
+            hit: Self::hit(entries, index),
+            matched_indices,
+            total_matches: ranked.len(),
+            context_before,
+            context_after,
+        }))
+    }
+
+    pub fn reset_search(&self, session_id: &str) -> Result<(), String> {
+        let mut searches = self.searches.lock().map_err(|e| e.to_string())?;
+        searches.remove(session_id);
+        Ok(())
+    }
+}
+
+impl HistoryStore {
+    // Rewrites the whole history (used when merging synced history). Navigation and search
+    // positions refer to indices in the old list, so they are reset.
+    pub fn replace_with(
+        &self,
+        update: impl FnOnce(&mut Vec<HistoryEntry>),
+    ) -> Result<Vec<HistoryEntry>, String> {
+        let path = history_path()?;
+        let mut guard = self.entries()?;
+        let entries = guard.get_or_insert_with(Vec::new);
+        update(entries);
+        if entries.len() > MAX_HISTORY_ENTRIES {
+            entries.drain(..entries.len() - MAX_HISTORY_ENTRIES);
+        }
+
+        let lines: Vec<String> = entries
+            .iter()
+            .filter_map(|entry| serde_json::to_string(entry).ok())
+            .collect();
+        let temp_path = path.with_extension("jsonl.tmp");
+        fs::write(&temp_path, lines.join("\n") + "\n")
+            .map_err(|e| format!("Failed to write {}: {}", temp_path.display(), e))?;
+        fs::rename(&temp_path, &path)
+            .map_err(|e| format!("Failed to replace {}: {}", path.display(), e))?;
+
+        self.navigation.lock().map_err(|e| e.to_string())?.clear();
+        self.searches.lock().map_err(|e| e.to_string())?.clear();
+        Ok(entries.clone())
+    }
+
+    // Forgets the loaded history so the next use reads the files again (after a restore)
+    pub fn reload(&self) -> Result<(), String> {
+        *self.entries.lock().map_err(|e| e.to_string())? = None;
+        *self.directories.lock().map_err(|e| e.to_string())? = None;
+        self.navigation.lock().map_err(|e| e.to_string())?.clear();
+        self.searches.lock().map_err(|e| e.to_string())?.clear();
+        Ok(())
+    }
+}
+
+fn load_directory_visits() -> Vec<DirectoryVisit> {
+    let path = match app_data_dir() {
+        Ok(dir) => dir.join(DIRECTORY_HISTORY_FILE),
+        Err(_) => return Vec::new(),
+    };
+    let contents = fs::read_to_string(&path).unwrap_or_default();
+    let mut visits: Vec<DirectoryVisit> = contents
+        .lines()
+        .filter_map(|line| serde_json::from_str(line).ok())
+        .collect();
+
+    if visits.len() > MAX_DIRECTORY_VISITS {
+        visits.drain(..visits.len() - MAX_DIRECTORY_VISITS);
+        let compacted: Vec<String> = visits
+            .iter()
+            .filter_map(|visit| serde_json::to_string(visit).ok())
+            .collect();
+        let _ = fs::write(&path, compacted.join("\n") + "\n");
+    }
+    visits
+}
+
+impl HistoryStore {
+    // Records a directory change; staying in the same directory is not a new visit
+    pub fn record_directory(&self, visit: DirectoryVisit) -> Result<(), String> {
+        let mut guard = self.directories.lock().map_err(|e| e.to_string())?;
+        let visits = guard.get_or_insert_with(load_directory_visits);
+        let repeated = visits
+            .iter()
+            .rev()
+            .find(|previous| previous.session_id == visit.session_id)
+            .is_some_and(|previous| previous.path == visit.path);
+        if repeated {
+            return Ok(());
+        }
+
+        let path = app_data_dir()?.join(DIRECTORY_HISTORY_FILE);
+        let line = serde_json::to_string(&visit).map_err(|e| e.to_string())?;
+        let mut file = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&path)
+            .map_err(|e| format!("Failed to open {}: {}", path.display(), e))?;
+        writeln!(file, "{}", line)
+            .map_err(|e| format!("Failed to write {}: {}", path.display(), e))?;
+        visits.push(visit);
+        Ok(())
+    }
+
+    // Visited directories across all sessions, most recent first, one row per path
+    pub fn recent_directories(&self) -> Result<Vec<RecentDirectory>, String> {
+        let mut guard = self.directories.lock().map_err(|e| e.to_string())?;
+        let visits = guard.get_or_insert_with(load_directory_visits);
+        let mut recent: Vec<RecentDirectory> = Vec::new();
+        let mut positions: HashMap<&str, usize> = HashMap::new();
+        for visit in visits.iter().rev() {
+            match positions.get(visit.path.as_str()) {
+                Some(&index) => recent[index].visits += 1,
+                None => {
+                    positions.insert(&visit.path, recent.len());
+                    recent.push(RecentDirectory {
+                        path: visit.path.clone(),
+                        last_visited: visit.timestamp,
+                        visits: 1,
+                    });
+                }
+            }
+        }
+        Ok(recent)
+    }
+}
+
+impl Default for HistoryStore {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn indices(query: &str, command: &str) -> Option<Vec<usize>> {
+        build_matcher(query, SearchMode::Substring).unwrap()(command).map(|(_, indices)| indices)
+    }
+
+    #[test]
+    fn substring_matches_index_the_original_command() {
+        // İ lowercases to two characters, which must not shift the match
+        assert_eq!(indices("cd", "echo İ; cd /tmp"), Some(vec![8, 9]));
+        assert_eq!(indices("make", "İİ MAKE"), Some(vec![3, 4, 5, 6]));
+        // Smart case
+        assert_eq!(indices("Make", "make"), None);
+        assert_eq!(indices("a.b", "axb"), None);
+    }
+}
@@ -0,0 +1,5 @@
+pub mod directory_visit;
+pub mod history_entry;
+pub mod history_search;
+pub mod history_store;
+pub mod sync_config;
@@ -0,0 +1,38 @@
+use serde::{Deserialize, Serialize};
+
+pub const HISTORY_SYNC_CONFIG_FILE: &str = "history_sync.json";
+
+// Where the encrypted history bundle is stored. The app only ever writes one file there.
+#[derive(Serialize, Deserialize, Clone, Debug)]
+#[serde(tag = "kind", rename_all = "camelCase")]
+pub enum SyncBackend {
+    // Directory URL on a WebDAV server (Nextcloud, ownCloud, ...). The password is passed to
+    // each sync and never stored.
+    #[serde(rename_all = "camelCase")]
+    WebDav {
+        url: String,
+        username: Option<String>,
+    },
+    // s3://bucket/prefix, accessed through the aws CLI and its credentials
+    #[serde(rename_all = "camelCase")]
+    S3 {
+        uri: String,
+        profile: Option<String>,
+    },
+    // Any repository the local git can push to
+    #[serde(rename_all = "camelCase")]
+    Git {
+        repository: String,
+        branch: Option<String>,
+    },
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, Default)]
+#[serde(default, rename_all = "camelCase")]
+pub struct HistorySyncConfig {
+    // None disables sync
+    pub backend: Option<SyncBackend>,
+    // Identifies this machine in synced entries; generated on first use
+    pub device_id: Option<String>,
+    pub last_synced_at: Option<u64>,
+}
@@ -0,0 +1,173 @@
+use crate::history::types::history_entry::HistoryEntry;
+use crate::history::types::history_store::HistoryStore;
+use crate::utils::config_utils::unix_timestamp_millis;
+use serde::Serialize;
+use std::collections::HashMap;
+use tauri::{command, State};
+
+const TOP_LIMIT: usize = 10;
+// Runs needed before a command is considered for failure rates and alias suggestions
+const MIN_RUNS_FOR_INSIGHT: usize = 3;
+
+#[derive(Serialize, Clone, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct CommandStats {
+    pub command: String,
+    pub runs: usize,
+    pub failures: usize,
+    pub failure_rate: f64,
+    pub average_duration_ms: Option<u64>,
+    pub total_duration_ms: u64,
+}
+
+#[derive(Serialize, Clone, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct DirectoryStats {
+    pub cwd: String,
+    pub runs: usize,
+}
+
+#[derive(Serialize, Clone, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct UsageStats {
+    pub range: String,
+    pub since: Option<u64>,
+    pub total_commands: usize,
+    pub failed_commands: usize,
+    // Grouped by program name (first word), e.g. all `git ...` invocations together
+    pub top_programs: Vec<CommandStats>,
+    // Grouped by the full command line
+    pub top_commands: Vec<CommandStats>,
+    pub most_failing: Vec<CommandStats>,
+    pub slowest: Vec<CommandStats>,
+    pub busiest_directories: Vec<DirectoryStats>,
+    // Frequently-run full commands ranked by the total time spent in them
+    pub alias_candidates: Vec<CommandStats>,
+}
+
+fn range_start(range: &str) -> Result<Option<u64>, String> {
+    const HOUR: u64 = 60 * 60 * 1000;
+    let span = match range {
+        "hour" => HOUR,
+        "day" => 24 * HOUR,
+        "week" => 7 * 24 * HOUR,
+        "month" => 30 * 24 * HOUR,
+        "all" => return Ok(None),
+        other => return Err(format!("Unsupported range: {}", other)),
+    };
+    Ok(Some(unix_timestamp_millis().saturating_sub(span)))
+}
+
+fn program_name(command: &str) -> String {
+    command
+        .split_whitespace()
+        .find(|word| !word.contains('=') && *word != "sudo")
+        .unwrap_or(command)
+        .to_string()
+}
+
+fn aggregate<'a>(
+    entries: impl Iterator<Item = &'a HistoryEntry>,
+    key: impl Fn(&HistoryEntry) -> String,
+) -> Vec<CommandStats> {
+    let mut groups: HashMap<String, (usize, usize, u64, usize)> = HashMap::new();
+    for entry in entries {
+        let group = groups.entry(key(entry)).or_default();
+        group.0 += 1;
+        if entry.exit_code.is_some_and(|code| code != 0) {
+            group.1 += 1;
+        }
+        if let Some(duration) = entry.duration_ms {
+            group.2 += duration;
+            group.3 += 1;
+        }
+    }
+    groups
+        .into_iter()
+        .map(
+            |(command, (runs, failures, total_duration_ms, timed_runs))| CommandStats {
+                command,
+                runs,
+                failures,
+                failure_rate: failures as f64 / runs as f64,
+                average_duration_ms: (timed_runs > 0)
+                    .then(|| total_duration_ms / timed_runs as u64),
+                total_duration_ms,
+            },
+        )
+        .collect()
+}
+
+fn top_by<K: Ord>(stats: &[CommandStats], key: impl Fn(&CommandStats) -> K) -> Vec<CommandStats> {
+    let mut sorted = stats.to_vec();
+    sorted.sort_by(|a, b| key(b).cmp(&key(a)).then_with(|| a.command.cmp(&b.command)));
+    sorted.truncate(TOP_LIMIT);
+    sorted
+}
+
+pub fn compute_usage_stats(entries: &[HistoryEntry], range: &str) -> Result<UsageStats, String> {
+    let since = range_start(range)?;
+    let in_range: Vec<&HistoryEntry> = entries
+        .iter()
+        .filter(|entry| since.is_none_or(|since| entry.timestamp >= since))
+        .collect();
+
+    let programs = aggregate(in_range.iter().copied(), |e| program_name(&e.command));
+    let commands = aggregate(in_range.iter().copied(), |e| e.command.clone());
+    let frequent: Vec<CommandStats> = commands
+        .iter()
+        .filter(|stats| stats.runs >= MIN_RUNS_FOR_INSIGHT)
+        .cloned()
+        .collect();
+
+    let mut directories: HashMap<&str, usize> = HashMap::new();
+    for entry in &in_range {
+        *directories.entry(entry.cwd.as_str()).or_default() += 1;
+    }
+    let mut busiest_directories: Vec<DirectoryStats> = directories
+        .into_iter()
+        .map(|(cwd, runs)| DirectoryStats {
+            cwd: cwd.to_string(),
+            runs,
+        })
+        .collect();
+    busiest_directories.sort_by(|a, b| b.runs.cmp(&a.runs).then_with(|| a.cwd.cmp(&b.cwd)));
+    busiest_directories.truncate(TOP_LIMIT);
+
+    let programs_with_failures: Vec<CommandStats> = programs
+        .iter()
+        .filter(|stats| stats.runs >= MIN_RUNS_FOR_INSIGHT && stats.failures > 0)
+        .cloned()
+        .collect();
+
+    Ok(UsageStats {
+        range: range.to_string(),
+        since,
+        total_commands: in_range.len(),
+        failed_commands: in_range
+            .iter()
+            .filter(|entry| entry.exit_code.is_some_and(|code| code != 0))
+            .count(),
+        top_programs: top_by(&programs, |stats| stats.runs),
+        top_commands: top_by(&commands, |stats| stats.runs),
+        most_failing: top_by(&programs_with_failures, |stats| {
+            (stats.failure_rate * 1000.0) as u64
+        }),
+        slowest: top_by(&commands, |stats| stats.average_duration_ms.unwrap_or(0)),
+        busiest_directories,
+        alias_candidates: top_by(&frequent, |stats| stats.total_duration_ms),
+    })
+}
+
+// `range` is one of hour, day, week, month or all (the default)
+#[command]
+pub fn get_usage_stats(
+    range: Option<String>,
+    history_store: State<'_, HistoryStore>,
+) -> Result<UsageStats, String> {
+    let guard = history_store.entries()?;
+    compute_usage_stats(
+        guard.as_deref().unwrap_or_default(),
+        range.as_deref().unwrap_or("all"),
+    )
+}
@@ -0,0 +1,137 @@
+use crate::hooks::types::hook::{Hook, HookEvent, HookKind};
+use crate::hooks::types::hook_manager::HookManager;
+use crate::utils::config_utils::unix_timestamp_millis;
+use serde_json::{json, Value};
+use std::io::Write;
+use std::process::{Command, Stdio};
+use std::thread;
+use tauri::{AppHandle, Emitter, Manager, Runtime};
+
+// Threshold used by long_command_finished hooks that don't set their own
+pub const DEFAULT_LONG_COMMAND_SECS: u64 = 30;
+
+// Invoke every enabled hook registered for `event` with the given payload
+pub fn fire_hooks<R: Runtime>(app_handle: &AppHandle<R>, event: HookEvent, payload: Value) {
+    let hooks: Vec<Hook> = {
+        let manager = app_handle.state::<HookManager>();
+        let hooks = match manager.hooks.lock() {
+            Ok(hooks) => hooks,
+            Err(_) => return,
+        };
+        hooks
+            .iter()
+            .filter(|hook| hook.enabled && hook.event == event)
+            .cloned()
+            .collect()
+    };
+
+    for hook in hooks {
+        if event == HookEvent::LongCommandFinished {
+            let threshold_ms = hook.min_duration_secs.unwrap_or(DEFAULT_LONG_COMMAND_SECS) * 1000;
+            let duration_ms = payload["durationMs"].as_u64().unwrap_or(0);
+            if duration_ms < threshold_ms {
+                continue;
+            }
+        }
+
+        let mut body = payload.clone();
+        body["event"] = json!(event.as_str());
+        body["hookId"] = json!(hook.id);
+        body["timestamp"] = json!(unix_timestamp_millis());
+        run_hook(app_handle, hook, body);
+    }
+}
+
+// Fire command_failed / long_command_finished hooks once a local command exits
+pub fn fire_command_finished_hooks<R: Runtime>(
+    app_handle: &AppHandle<R>,
+    session_id: &str,
+    command: &str,
+    cwd: &str,
+    exit_code: Option<i32>,
+    duration_ms: u64,
+) {
+    let payload = json!({
+        "sessionId": session_id,
+        "command": command,
+        "cwd": cwd,
+        "exitCode": exit_code,
+        "durationMs": duration_ms,
+    });
+
+    if exit_code != Some(0) {
+        fire_hooks(app_handle, HookEvent::CommandFailed, payload.clone());
+    }
+    fire_hooks(app_handle, HookEvent::LongCommandFinished, payload);
+}
+
+pub fn run_hook<R: Runtime>(app_handle: &AppHandle<R>, hook: Hook, payload: Value) {
+    let app_handle = app_handle.clone();
+    match hook.kind {
+        HookKind::Url => {
+            tauri::async_runtime::spawn(async move {
+                let result = reqwest::Client::new()
+                    .post(&hook.target)
+                    .json(&payload)
+                    .send()
+                    .await;
+                let error = match result {
+                    Ok(res) if res.status().is_success() => None,
+                    Ok(res) => Some(format!("Webhook returned {}", res.status())),
+                    Err(e) => Some(format!("Failed to call webhook: {}", e)),
+                };
+                if let Some(error) = error {
+                    report_hook_failure(&app_handle, &hook, error);
+                }
+            });
+        }
+        HookKind::Script => {
+            thread::spawn(move || {
+                let spawned = Command::new(&hook.target)
+                    .env("AI_TERMINAL_EVENT", payload["event"].as_str().unwrap_or(""))
+                    .stdin(Stdio::piped())
+                    .stdout(Stdio::null())
+                    .stderr(Stdio::null())
+                    .spawn();
+
+                let mut child = match spawned {
+                    Ok(child) => child,
+                    Err(e) => {
+                        report_hook_failure(
+                            &app_handle,
+                            &hook,
+                            format!("Failed to start hook script: {}", e),
+                        );
+                        return;
+                    }
+                };
+
+                if let Some(mut stdin) = child.stdin.take() {
+                    let _ = stdin.write_all(payload.to_string().as_bytes());
+                }
+
+                match child.wait() {
+                    Ok(status) if status.success() => {}
+                    Ok(status) => report_hook_failure(
+                        &app_handle,
+                        &hook,
+                        format!("Hook script exited with {}", status),
+                    ),
+                    Err(e) => report_hook_failure(
+                        &app_handle,
+                        &hook,
+                        format!("Failed to wait for hook script: {}", e),
+                    ),
+                }
+            });
+        }
+    }
+}
+
+fn report_hook_failure<R: Runtime>(app_handle: &AppHandle<R>, hook: &Hook, error: String) {
+    eprintln!("[Rust HOOK] Hook {} failed: {}", hook.id, error);
+    let _ = app_handle.emit(
+        "hook_failed",
+        json!({ "hookId": hook.id, "event": hook.event.as_str(), "error": error }),
+    );
+}
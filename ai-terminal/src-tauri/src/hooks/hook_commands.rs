@@ -0,0 +1,95 @@
+use crate::hooks::dispatch::run_hook;
+use crate::hooks::types::hook::{Hook, HookEvent, HookKind};
+use crate::hooks::types::hook_manager::HookManager;
+use crate::utils::config_utils::unix_timestamp_millis;
+use serde_json::json;
+use tauri::{command, AppHandle, Runtime, State};
+
+#[command]
+pub fn list_hooks(hook_manager: State<'_, HookManager>) -> Result<Vec<Hook>, String> {
+    let hooks = hook_manager.hooks.lock().map_err(|e| e.to_string())?;
+    Ok(hooks.clone())
+}
+
+#[command]
+pub fn add_hook(
+    event: HookEvent,
+    kind: HookKind,
+    target: String,
+    min_duration_secs: Option<u64>,
+    hook_manager: State<'_, HookManager>,
+) -> Result<Hook, String> {
+    let target = target.trim().to_string();
+    if target.is_empty() {
+        return Err("Hook target cannot be empty".to_string());
+    }
+    if kind == HookKind::Url && !(target.starts_with("http://") || target.starts_with("https://")) {
+        return Err(format!("Invalid webhook URL: {}", target));
+    }
+
+    let hook = Hook {
+        id: format!("hook-{}", unix_timestamp_millis()),
+        event,
+        kind,
+        target,
+        enabled: true,
+        min_duration_secs,
+    };
+
+    let mut hooks = hook_manager.hooks.lock().map_err(|e| e.to_string())?;
+    hooks.push(hook.clone());
+    hook_manager.persist(&hooks)?;
+    Ok(hook)
+}
+
+#[command]
+pub fn remove_hook(id: String, hook_manager: State<'_, HookManager>) -> Result<(), String> {
+    let mut hooks = hook_manager.hooks.lock().map_err(|e| e.to_string())?;
+    let before = hooks.len();
+    hooks.retain(|hook| hook.id != id);
+    if hooks.len() == before {
+        return Err(format!("Hook '{}' not found", id));
+    }
+    hook_manager.persist(&hooks)
+}
+
+#[command]
+pub fn set_hook_enabled(
+    id: String,
+    enabled: bool,
+    hook_manager: State<'_, HookManager>,
+) -> Result<(), String> {
+    let mut hooks = hook_manager.hooks.lock().map_err(|e| e.to_string())?;
+    let hook = hooks
+        .iter_mut()
+        .find(|hook| hook.id == id)
+        .ok_or_else(|| format!("Hook '{}' not found", id))?;
+    hook.enabled = enabled;
+    hook_manager.persist(&hooks)
+}
+
+// Send a sample payload so users can check their endpoint or script
+#[command]
+pub fn test_hook<R: Runtime>(
+    id: String,
+    app_handle: AppHandle<R>,
+    hook_manager: State<'_, HookManager>,
+) -> Result<(), String> {
+    let hook = {
+        let hooks = hook_manager.hooks.lock().map_err(|e| e.to_string())?;
+        hooks
+            .iter()
+            .find(|hook| hook.id == id)
+            .cloned()
+            .ok_or_else(|| format!("Hook '{}' not found", id))?
+    };
+
+    let payload = json!({
+        "event": hook.event.as_str(),
+        "hookId": hook.id,
+        "timestamp": unix_timestamp_millis(),
+        "test": true,
+    });
+    run_hook(&app_handle, hook, payload);
+    Ok(())
+}
@@ -0,0 +1,3 @@
+pub mod dispatch;
+pub mod hook_commands;
+pub mod types;
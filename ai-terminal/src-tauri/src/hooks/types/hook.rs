@@ -0,0 +1,42 @@
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum HookEvent {
+    CommandFailed,
+    LongCommandFinished,
+    SshConnected,
+    SshDisconnected,
+}
+
+impl HookEvent {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            HookEvent::CommandFailed => "command_failed",
+            HookEvent::LongCommandFinished => "long_command_finished",
+            HookEvent::SshConnected => "ssh_connected",
+            HookEvent::SshDisconnected => "ssh_disconnected",
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum HookKind {
+    // POST the JSON payload to a URL (e.g. a Slack incoming webhook relay)
+    Url,
+    // Run a local executable with the JSON payload on stdin
+    Script,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct Hook {
+    pub id: String,
+    pub event: HookEvent,
+    pub kind: HookKind,
+    pub target: String,
+    pub enabled: bool,
+    // Only used by long_command_finished hooks
+    pub min_duration_secs: Option<u64>,
+}
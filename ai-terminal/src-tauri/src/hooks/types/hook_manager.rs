@@ -0,0 +1,27 @@
+use crate::hooks::types::hook::Hook;
+use crate::utils::config_utils::{load_json_config, save_json_config};
+use std::sync::Mutex;
+
+pub const HOOKS_CONFIG_FILE: &str = "hooks.json";
+
+pub struct HookManager {
+    pub hooks: Mutex<Vec<Hook>>,
+}
+
+impl HookManager {
+    pub fn new() -> Self {
+        Self {
+            hooks: Mutex::new(load_json_config(HOOKS_CONFIG_FILE)),
+        }
+    }
+
+    pub fn persist(&self, hooks: &[Hook]) -> Result<(), String> {
+        save_json_config(HOOKS_CONFIG_FILE, &hooks)
+    }
+}
+
+impl Default for HookManager {
+    fn default() -> Self {
+        Self::new()
+    }
+}
@@ -0,0 +1,2 @@
+pub mod hook;
+pub mod hook_manager;
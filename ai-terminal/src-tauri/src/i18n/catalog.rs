@@ -0,0 +1,119 @@
+use crate::i18n::types::error_code::ErrorCode;
+use crate::utils::config_utils::app_config_dir;
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::PathBuf;
+
+pub const DEFAULT_LOCALE: &str = "en";
+
+// Message templates for the default locale; `{name}` is replaced with the error's param
+pub fn english_template(code: ErrorCode) -> &'static str {
+    match code {
+        ErrorCode::Internal => "{message}",
+        ErrorCode::DirectoryNotFound => "Directory not found: {path}",
+        ErrorCode::DirectoryNotFoundWithSuggestions => {
+            "Directory not found: {path}. Did you mean {suggestions}?"
+        }
+        ErrorCode::AlreadyAtRoot => "Already at root directory",
+        ErrorCode::HomeDirectoryUnknown => "Could not determine home directory",
+        ErrorCode::SpawnFailed => "Failed to start {program}: {reason}",
+        ErrorCode::CommandNotFound => "Command not found: {program}",
+        ErrorCode::NoActiveProcess => "No active process to terminate",
+        ErrorCode::SignalFailed => "Failed to send {signal}: {reason}",
+        ErrorCode::SshSessionConflict => "SSH session conflict: active but no stdin. Please retry.",
+        ErrorCode::SshAuthFailed => "SSH authentication failed: {detail}",
+        ErrorCode::SshHostUnresolved => "Could not resolve SSH host: {detail}",
+        ErrorCode::SshHostUnreachable => "SSH host is unreachable: {detail}",
+        ErrorCode::SshConnectionRefused => "SSH connection refused: {detail}",
+        ErrorCode::SshHostKeyChanged => "SSH host key verification failed: {detail}",
+    }
+}
+
+// Single pass, so values containing braces are not expanded again. Unknown placeholders are
+// left in place so a broken translation stays readable.
+pub fn render(template: &str, params: &BTreeMap<String, String>) -> String {
+    let mut message = String::with_capacity(template.len());
+    let mut rest = template;
+    while let Some(open) = rest.find('{') {
+        message.push_str(&rest[..open]);
+        let after = &rest[open + 1..];
+        match after
+            .find('}')
+            .and_then(|close| params.get(&after[..close]).map(|value| (close, value)))
+        {
+            Some((close, value)) => {
+                message.push_str(value);
+                rest = &after[close + 1..];
+            }
+            None => {
+                message.push('{');
+                rest = after;
+            }
+        }
+    }
+    message.push_str(rest);
+    message
+}
+
+// Translations live in <config dir>/locales/<locale>.json as { "<code>": "<template>" }
+fn locales_dir() -> Result<PathBuf, String> {
+    Ok(app_config_dir()?.join("locales"))
+}
+
+fn is_valid_locale(locale: &str) -> bool {
+    !locale.is_empty()
+        && locale
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_')
+}
+
+// Templates for every code: the locale's translations over the English defaults
+pub fn load_catalog(locale: &str) -> Result<BTreeMap<String, String>, String> {
+    let mut catalog: BTreeMap<String, String> = ErrorCode::ALL
+        .iter()
+        .map(|code| {
+            (
+                code.as_str().to_string(),
+                english_template(*code).to_string(),
+            )
+        })
+        .collect();
+    if locale == DEFAULT_LOCALE {
+        return Ok(catalog);
+    }
+    if !is_valid_locale(locale) {
+        return Err(format!("Invalid locale '{}'", locale));
+    }
+
+    let path = locales_dir()?.join(format!("{}.json", locale));
+    let Ok(contents) = fs::read_to_string(&path) else {
+        return Ok(catalog);
+    };
+    let translations: BTreeMap<String, String> = serde_json::from_str(&contents)
+        .map_err(|e| format!("Invalid locale file {}: {}", path.display(), e))?;
+    for (code, template) in translations {
+        if let Some(entry) = catalog.get_mut(&code) {
+            *entry = template;
+        }
+    }
+    Ok(catalog)
+}
+
+// The default locale plus every locale file that has been installed
+pub fn available_locales() -> Vec<String> {
+    let mut locales = vec![DEFAULT_LOCALE.to_string()];
+    if let Ok(entries) = locales_dir().and_then(|dir| fs::read_dir(dir).map_err(|e| e.to_string()))
+    {
+        locales.extend(entries.flatten().filter_map(|entry| {
+            let path = entry.path();
+            if path.extension()? != "json" {
+                return None;
+            }
+            let locale = path.file_stem()?.to_str()?.to_string();
+            (is_valid_locale(&locale) && locale != DEFAULT_LOCALE).then_some(locale)
+        }));
+    }
+    locales.sort();
+    locales.dedup();
+    locales
+}
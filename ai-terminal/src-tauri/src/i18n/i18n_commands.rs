@@ -0,0 +1,27 @@
+use crate::i18n::catalog::{available_locales, load_catalog, DEFAULT_LOCALE};
+use serde::Serialize;
+use std::collections::BTreeMap;
+use tauri::command;
+
+#[derive(Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct ErrorCatalog {
+    pub locale: String,
+    // Error code -> message template with {param} placeholders
+    pub messages: BTreeMap<String, String>,
+}
+
+// Message templates the UI renders errors with, keyed by AppError code
+#[command]
+pub fn get_error_catalog(locale: Option<String>) -> Result<ErrorCatalog, String> {
+    let locale = locale.unwrap_or_else(|| DEFAULT_LOCALE.to_string());
+    Ok(ErrorCatalog {
+        messages: load_catalog(&locale)?,
+        locale,
+    })
+}
+
+#[command]
+pub fn list_locales() -> Vec<String> {
+    available_locales()
+}
@@ -0,0 +1,3 @@
+pub mod catalog;
+pub mod i18n_commands;
+pub mod types;
@@ -0,0 +1,68 @@
+use crate::i18n::catalog::{english_template, render};
+use crate::i18n::types::error_code::ErrorCode;
+use serde::Serialize;
+use std::collections::BTreeMap;
+use std::fmt;
+
+// Error returned to the frontend as { code, message, params }. `message` is the English text;
+// a localized UI renders the catalog entry for `code` with `params` instead.
+#[derive(Serialize, Clone, Debug, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct AppError {
+    pub code: ErrorCode,
+    pub message: String,
+    pub params: BTreeMap<String, String>,
+}
+
+impl AppError {
+    pub fn new(code: ErrorCode, params: &[(&str, &str)]) -> Self {
+        let params: BTreeMap<String, String> = params
+            .iter()
+            .map(|(name, value)| (name.to_string(), value.to_string()))
+            .collect();
+        AppError {
+            code,
+            message: render(english_template(code), &params),
+            params,
+        }
+    }
+
+    // Why ssh failed, from the last stderr line naming a known cause
+    pub fn from_ssh_output(stderr: &str) -> Option<Self> {
+        stderr.lines().rev().find_map(|line| {
+            let line = line.trim();
+            ErrorCode::from_ssh_output(line).map(|code| AppError::new(code, &[("detail", line)]))
+        })
+    }
+}
+
+impl fmt::Display for AppError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.message)
+    }
+}
+
+impl std::error::Error for AppError {}
+
+// Errors from code that still reports plain strings
+impl From<String> for AppError {
+    fn from(message: String) -> Self {
+        AppError {
+            code: ErrorCode::Internal,
+            params: BTreeMap::from([("message".to_string(), message.clone())]),
+            message,
+        }
+    }
+}
+
+impl From<&str> for AppError {
+    fn from(message: &str) -> Self {
+        AppError::from(message.to_string())
+    }
+}
+
+impl From<AppError> for String {
+    fn from(error: AppError) -> Self {
+        error.message
+    }
+}
@@ -0,0 +1,88 @@
+use serde::{Deserialize, Serialize};
+
+// Stable identifiers for failures the frontend may localize or react to. Codes are part of the
+// frontend contract: add new ones, never rename or reuse them.
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
+#[serde(rename_all = "snake_case")]
+pub enum ErrorCode {
+    // Not classified yet; the message is the only information
+    Internal,
+    DirectoryNotFound,
+    DirectoryNotFoundWithSuggestions,
+    AlreadyAtRoot,
+    HomeDirectoryUnknown,
+    SpawnFailed,
+    CommandNotFound,
+    NoActiveProcess,
+    SignalFailed,
+    SshSessionConflict,
+    SshAuthFailed,
+    SshHostUnresolved,
+    SshHostUnreachable,
+    SshConnectionRefused,
+    SshHostKeyChanged,
+}
+
+impl ErrorCode {
+    pub const ALL: &'static [ErrorCode] = &[
+        ErrorCode::Internal,
+        ErrorCode::DirectoryNotFound,
+        ErrorCode::DirectoryNotFoundWithSuggestions,
+        ErrorCode::AlreadyAtRoot,
+        ErrorCode::HomeDirectoryUnknown,
+        ErrorCode::SpawnFailed,
+        ErrorCode::CommandNotFound,
+        ErrorCode::NoActiveProcess,
+        ErrorCode::SignalFailed,
+        ErrorCode::SshSessionConflict,
+        ErrorCode::SshAuthFailed,
+        ErrorCode::SshHostUnresolved,
+        ErrorCode::SshHostUnreachable,
+        ErrorCode::SshConnectionRefused,
+        ErrorCode::SshHostKeyChanged,
+    ];
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ErrorCode::Internal => "internal",
+            ErrorCode::DirectoryNotFound => "directory_not_found",
+            ErrorCode::DirectoryNotFoundWithSuggestions => "directory_not_found_with_suggestions",
+            ErrorCode::AlreadyAtRoot => "already_at_root",
+            ErrorCode::HomeDirectoryUnknown => "home_directory_unknown",
+            ErrorCode::SpawnFailed => "spawn_failed",
+            ErrorCode::CommandNotFound => "command_not_found",
+            ErrorCode::NoActiveProcess => "no_active_process",
+            ErrorCode::SignalFailed => "signal_failed",
+            ErrorCode::SshSessionConflict => "ssh_session_conflict",
+            ErrorCode::SshAuthFailed => "ssh_auth_failed",
+            ErrorCode::SshHostUnresolved => "ssh_host_unresolved",
+            ErrorCode::SshHostUnreachable => "ssh_host_unreachable",
+            ErrorCode::SshConnectionRefused => "ssh_connection_refused",
+            ErrorCode::SshHostKeyChanged => "ssh_host_key_changed",
+        }
+    }
+
+    // Why an ssh (or sshpass) process failed, from what it printed on stderr
+    pub fn from_ssh_output(stderr: &str) -> Option<ErrorCode> {
+        const RULES: &[(&str, ErrorCode)] = &[
+            (
+                "REMOTE HOST IDENTIFICATION HAS CHANGED",
+                ErrorCode::SshHostKeyChanged,
+            ),
+            ("Host key verification failed", ErrorCode::SshHostKeyChanged),
+            ("Permission denied", ErrorCode::SshAuthFailed),
+            ("Too many authentication failures", ErrorCode::SshAuthFailed),
+            ("Could not resolve hostname", ErrorCode::SshHostUnresolved),
+            ("Name or service not known", ErrorCode::SshHostUnresolved),
+            ("Connection refused", ErrorCode::SshConnectionRefused),
+            ("Connection timed out", ErrorCode::SshHostUnreachable),
+            ("Operation timed out", ErrorCode::SshHostUnreachable),
+            ("No route to host", ErrorCode::SshHostUnreachable),
+            ("Network is unreachable", ErrorCode::SshHostUnreachable),
+        ];
+        RULES
+            .iter()
+            .find(|(needle, _)| stderr.contains(needle))
+            .map(|(_, code)| *code)
+    }
+}
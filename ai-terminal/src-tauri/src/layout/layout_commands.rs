@@ -0,0 +1,415 @@
+use crate::command::types::command_manager::CommandManager;
+use crate::command::types::pty_manager::PtyManager;
+use crate::command::types::window_router::WindowRouter;
+use crate::layout::types::layout_manager::LayoutManager;
+use crate::layout::types::pane_layout::{
+    new_node_id, PaneNode, SessionGroup, SessionMeta, SplitDirection, Workspace, MIN_SPLIT_RATIO,
+};
+use serde::Serialize;
+use std::collections::BTreeSet;
+use tauri::{command, AppHandle, Emitter, Runtime, State};
+
+#[derive(Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct SessionMetaEvent {
+    pub session_id: String,
+    pub meta: SessionMeta,
+}
+
+#[derive(Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct SessionSummary {
+    pub session_id: String,
+    // Whether it is a PTY session rather than one driven by execute_command
+    pub pty: bool,
+    // Label of the window showing it, once attached
+    pub window: Option<String>,
+    #[serde(flatten)]
+    pub meta: SessionMeta,
+}
+
+fn clamp_ratio(ratio: f64) -> f64 {
+    ratio.clamp(MIN_SPLIT_RATIO, 1.0 - MIN_SPLIT_RATIO)
+}
+
+// Applies a change to one workspace (created on first use), persists all layouts and emits
+// layout_changed so every window, and scripted callers, see the same layout
+fn update_workspace<R: Runtime, T>(
+    app_handle: &AppHandle<R>,
+    layout_manager: &LayoutManager,
+    workspace: &str,
+    update: impl FnOnce(&mut Workspace) -> Result<T, String>,
+) -> Result<T, String> {
+    let workspace_name = workspace.trim();
+    if workspace_name.is_empty() {
+        return Err("Workspace name cannot be empty".to_string());
+    }
+    let mut workspaces = layout_manager
+        .workspaces
+        .lock()
+        .map_err(|e| e.to_string())?;
+    let entry = workspaces
+        .entry(workspace_name.to_string())
+        .or_insert_with(|| Workspace {
+            name: workspace_name.to_string(),
+            ..Workspace::default()
+        });
+    let result = update(entry)?;
+    let snapshot = entry.clone();
+    layout_manager.persist(&workspaces)?;
+    let _ = app_handle.emit("layout_changed", snapshot);
+    Ok(result)
+}
+
+fn find_group<'a>(
+    workspace: &'a mut Workspace,
+    group_id: &str,
+) -> Result<&'a mut SessionGroup, String> {
+    workspace
+        .groups
+        .iter_mut()
+        .find(|group| group.id == group_id)
+        .ok_or_else(|| format!("No session group '{}'", group_id))
+}
+
+#[command]
+pub fn list_workspaces(layout_manager: State<'_, LayoutManager>) -> Result<Vec<String>, String> {
+    let workspaces = layout_manager
+        .workspaces
+        .lock()
+        .map_err(|e| e.to_string())?;
+    let mut names: Vec<String> = workspaces.keys().cloned().collect();
+    names.sort();
+    Ok(names)
+}
+
+#[command]
+pub fn get_layout(
+    workspace: String,
+    layout_manager: State<'_, LayoutManager>,
+) -> Result<Workspace, String> {
+    let workspaces = layout_manager
+        .workspaces
+        .lock()
+        .map_err(|e| e.to_string())?;
+    Ok(workspaces
+        .get(workspace.trim())
+        .cloned()
+        .unwrap_or(Workspace {
+            name: workspace.trim().to_string(),
+            ..Workspace::default()
+        }))
+}
+
+#[command]
+pub fn delete_workspace(
+    workspace: String,
+    layout_manager: State<'_, LayoutManager>,
+) -> Result<(), String> {
+    let mut workspaces = layout_manager
+        .workspaces
+        .lock()
+        .map_err(|e| e.to_string())?;
+    workspaces.remove(workspace.trim());
+    layout_manager.persist(&workspaces)
+}
+
+// New group (tab) with a single pane, made the active group
+#[command]
+pub fn create_session_group<R: Runtime>(
+    workspace: String,
+    name: String,
+    session_id: Option<String>,
+    app_handle: AppHandle<R>,
+    layout_manager: State<'_, LayoutManager>,
+) -> Result<SessionGroup, String> {
+    update_workspace(&app_handle, &layout_manager, &workspace, |workspace| {
+        let root = PaneNode::new_pane(session_id);
+        let group = SessionGroup {
+            id: new_node_id("group"),
+            name,
+            focused_pane: root.id().to_string(),
+            root,
+        };
+        workspace.active_group = Some(group.id.clone());
+        workspace.groups.push(group.clone());
+        Ok(group)
+    })
+}
+
+#[command]
+pub fn close_session_group<R: Runtime>(
+    workspace: String,
+    group_id: String,
+    app_handle: AppHandle<R>,
+    layout_manager: State<'_, LayoutManager>,
+) -> Result<(), String> {
+    update_workspace(&app_handle, &layout_manager, &workspace, |workspace| {
+        let index = workspace
+            .groups
+            .iter()
+            .position(|group| group.id == group_id)
+            .ok_or_else(|| format!("No session group '{}'", group_id))?;
+        workspace.groups.remove(index);
+        if workspace.active_group.as_deref() == Some(group_id.as_str()) {
+            workspace.active_group = workspace
+                .groups
+                .get(index.saturating_sub(1))
+                .map(|group| group.id.clone());
+        }
+        Ok(())
+    })
+}
+
+// Splits a pane in two; the existing pane keeps the first half and the new pane (focused)
+// gets the second. Returns the new pane's id.
+#[command]
+#[allow(clippy::too_many_arguments)]
+pub fn split_pane<R: Runtime>(
+    workspace: String,
+    group_id: String,
+    pane_id: String,
+    direction: SplitDirection,
+    session_id: Option<String>,
+    ratio: Option<f64>,
+    app_handle: AppHandle<R>,
+    layout_manager: State<'_, LayoutManager>,
+) -> Result<String, String> {
+    update_workspace(&app_handle, &layout_manager, &workspace, |workspace| {
+        let group = find_group(workspace, &group_id)?;
+        let node = group
+            .root
+            .find_mut(&pane_id)
+            .filter(|node| matches!(node, PaneNode::Pane { .. }))
+            .ok_or_else(|| format!("No pane '{}' in this group", pane_id))?;
+        let new_pane = PaneNode::new_pane(session_id);
+        let new_pane_id = new_pane.id().to_string();
+        let existing = node.clone();
+        *node = PaneNode::Split {
+            id: new_node_id("split"),
+            direction,
+            ratio: clamp_ratio(ratio.unwrap_or(0.5)),
+            first: Box::new(existing),
+            second: Box::new(new_pane),
+        };
+        group.focused_pane = new_pane_id.clone();
+        Ok(new_pane_id)
+    })
+}
+
+// Closes a pane (not the session running in it). Closing the last pane closes the group.
+#[command]
+pub fn close_pane<R: Runtime>(
+    workspace: String,
+    group_id: String,
+    pane_id: String,
+    app_handle: AppHandle<R>,
+    layout_manager: State<'_, LayoutManager>,
+) -> Result<(), String> {
+    update_workspace(&app_handle, &layout_manager, &workspace, |workspace| {
+        let group = find_group(workspace, &group_id)?;
+        if group.root.id() == pane_id {
+            workspace.groups.retain(|group| group.id != group_id);
+            if workspace.active_group.as_deref() == Some(group_id.as_str()) {
+                workspace.active_group = workspace.groups.last().map(|group| group.id.clone());
+            }
+            return Ok(());
+        }
+        if !group.root.remove_pane(&pane_id) {
+            return Err(format!("No pane '{}' in this group", pane_id));
+        }
+        if group.focused_pane == pane_id {
+            group.focused_pane = group.root.pane_ids().first().cloned().unwrap_or_default();
+        }
+        Ok(())
+    })
+}
+
+// Sets the share of a split given to its first child
+#[command]
+pub fn resize_pane<R: Runtime>(
+    workspace: String,
+    group_id: String,
+    split_id: String,
+    ratio: f64,
+    app_handle: AppHandle<R>,
+    layout_manager: State<'_, LayoutManager>,
+) -> Result<(), String> {
+    if !ratio.is_finite() {
+        return Err("Invalid split ratio".to_string());
+    }
+    update_workspace(&app_handle, &layout_manager, &workspace, |workspace| {
+        let group = find_group(workspace, &group_id)?;
+        match group.root.find_mut(&split_id) {
+            Some(PaneNode::Split { ratio: current, .. }) => {
+                *current = clamp_ratio(ratio);
+                Ok(())
+            }
+            _ => Err(format!("No split '{}' in this group", split_id)),
+        }
+    })
+}
+
+// Shows a session in a pane (None leaves the pane empty) and focuses it
+#[command]
+pub fn assign_pane_session<R: Runtime>(
+    workspace: String,
+    group_id: String,
+    pane_id: String,
+    session_id: Option<String>,
+    app_handle: AppHandle<R>,
+    layout_manager: State<'_, LayoutManager>,
+) -> Result<(), String> {
+    update_workspace(&app_handle, &layout_manager, &workspace, |workspace| {
+        let group = find_group(workspace, &group_id)?;
+        match group.root.find_mut(&pane_id) {
+            Some(PaneNode::Pane {
+                session_id: current,
+                ..
+            }) => *current = session_id,
+            _ => return Err(format!("No pane '{}' in this group", pane_id)),
+        }
+        group.focused_pane = pane_id;
+        workspace.active_group = Some(group_id.clone());
+        Ok(())
+    })
+}
+
+fn normalize_color(color: &str) -> Result<String, String> {
+    let color = color.trim().to_ascii_lowercase();
+    let digits = color.strip_prefix('#').unwrap_or_default();
+    if !matches!(digits.len(), 3 | 6) || !digits.chars().all(|c| c.is_ascii_hexdigit()) {
+        return Err(format!(
+            "Invalid color '{}', expected #rgb or #rrggbb",
+            color
+        ));
+    }
+    Ok(color)
+}
+
+// Trimmed, lowercase and without duplicates, keeping the order they were given in
+fn normalize_tags(tags: Vec<String>) -> Vec<String> {
+    let mut normalized: Vec<String> = Vec::new();
+    for tag in tags {
+        let tag = tag.trim().to_lowercase();
+        if !tag.is_empty() && !normalized.contains(&tag) {
+            normalized.push(tag);
+        }
+    }
+    normalized
+}
+
+// Saves a session's labels and tells every window, dropping the entry once nothing is set
+fn store_session_meta<R: Runtime>(
+    app_handle: &AppHandle<R>,
+    layout_manager: &LayoutManager,
+    session_id: String,
+    update: impl FnOnce(&mut SessionMeta),
+) -> Result<SessionMeta, String> {
+    let mut session_meta = layout_manager
+        .session_meta
+        .lock()
+        .map_err(|e| e.to_string())?;
+    let mut meta = session_meta.get(&session_id).cloned().unwrap_or_default();
+    update(&mut meta);
+    if meta.is_empty() {
+        session_meta.remove(&session_id);
+    } else {
+        session_meta.insert(session_id.clone(), meta.clone());
+    }
+    layout_manager.persist_session_meta(&session_meta)?;
+    let _ = app_handle.emit(
+        "session_meta_changed",
+        SessionMetaEvent {
+            session_id,
+            meta: meta.clone(),
+        },
+    );
+    Ok(meta)
+}
+
+// Replaces a session's name, color and tags; None and empty values clear them
+#[command]
+pub fn set_session_meta<R: Runtime>(
+    session_id: String,
+    name: Option<String>,
+    color: Option<String>,
+    tags: Option<Vec<String>>,
+    app_handle: AppHandle<R>,
+    layout_manager: State<'_, LayoutManager>,
+) -> Result<SessionMeta, String> {
+    let name = name
+        .map(|name| name.trim().to_string())
+        .filter(|name| !name.is_empty());
+    let color = color
+        .filter(|color| !color.trim().is_empty())
+        .map(|color| normalize_color(&color))
+        .transpose()?;
+    let tags = normalize_tags(tags.unwrap_or_default());
+    store_session_meta(&app_handle, &layout_manager, session_id, |meta| {
+        meta.name = name;
+        meta.color = color;
+        meta.tags = tags;
+    })
+}
+
+// Protected sessions ask for confirmation before they are closed, receive broadcast input,
+// or run a command matching the dangerPatterns setting
+#[command]
+pub fn set_session_protected<R: Runtime>(
+    session_id: String,
+    protected: bool,
+    app_handle: AppHandle<R>,
+    layout_manager: State<'_, LayoutManager>,
+) -> Result<SessionMeta, String> {
+    store_session_meta(&app_handle, &layout_manager, session_id, |meta| {
+        meta.protected = protected
+    })
+}
+
+// Open sessions with their labels, optionally only those carrying `tag`
+#[command]
+pub fn list_sessions(
+    tag: Option<String>,
+    layout_manager: State<'_, LayoutManager>,
+    pty_manager: State<'_, PtyManager>,
+    command_manager: State<'_, CommandManager>,
+    window_router: State<'_, WindowRouter>,
+) -> Result<Vec<SessionSummary>, String> {
+    let pty_sessions: BTreeSet<String> = pty_manager
+        .sessions
+        .lock()
+        .map_err(|e| e.to_string())?
+        .keys()
+        .cloned()
+        .collect();
+    let mut session_ids = pty_sessions.clone();
+    session_ids.extend(
+        command_manager
+            .commands
+            .lock()
+            .map_err(|e| e.to_string())?
+            .keys()
+            .filter(|id| id.as_str() != "default_state")
+            .cloned(),
+    );
+    let tag = tag.map(|tag| tag.trim().to_lowercase());
+    let session_meta = layout_manager
+        .session_meta
+        .lock()
+        .map_err(|e| e.to_string())?;
+
+    Ok(session_ids
+        .into_iter()
+        .map(|session_id| SessionSummary {
+            pty: pty_sessions.contains(&session_id),
+            window: window_router.owner(&session_id),
+            meta: session_meta.get(&session_id).cloned().unwrap_or_default(),
+            session_id,
+        })
+        .filter(|session| {
+            tag.as_ref()
+                .is_none_or(|tag| session.meta.tags.contains(tag))
+        })
+        .collect())
+}
@@ -0,0 +1,2 @@
+pub mod layout_commands;
+pub mod types;
@@ -0,0 +1,44 @@
+use crate::layout::types::pane_layout::{SessionMeta, Workspace};
+use crate::utils::config_utils::{load_json_config, save_json_config};
+use std::collections::HashMap;
+use std::sync::Mutex;
+
+pub const LAYOUTS_CONFIG_FILE: &str = "layouts.json";
+pub const SESSION_META_CONFIG_FILE: &str = "session_meta.json";
+
+pub struct LayoutManager {
+    pub workspaces: Mutex<HashMap<String, Workspace>>,
+    // By session id, kept next to the layouts whose panes refer to those sessions
+    pub session_meta: Mutex<HashMap<String, SessionMeta>>,
+}
+
+impl LayoutManager {
+    pub fn new() -> Self {
+        Self {
+            workspaces: Mutex::new(load_json_config(LAYOUTS_CONFIG_FILE)),
+            session_meta: Mutex::new(load_json_config(SESSION_META_CONFIG_FILE)),
+        }
+    }
+
+    pub fn persist(&self, workspaces: &HashMap<String, Workspace>) -> Result<(), String> {
+        save_json_config(LAYOUTS_CONFIG_FILE, workspaces)
+    }
+
+    pub fn persist_session_meta(&self, meta: &HashMap<String, SessionMeta>) -> Result<(), String> {
+        save_json_config(SESSION_META_CONFIG_FILE, meta)
+    }
+
+    pub fn meta(&self, session_id: &str) -> SessionMeta {
+        self.session_meta
+            .lock()
+            .ok()
+            .and_then(|meta| meta.get(session_id).cloned())
+            .unwrap_or_default()
+    }
+}
+
+impl Default for LayoutManager {
+    fn default() -> Self {
+        Self::new()
+    }
+}
@@ -0,0 +1,2 @@
+pub mod layout_manager;
+pub mod pane_layout;
@@ -0,0 +1,130 @@
+use serde::{Deserialize, Serialize};
+
+// Smallest share of a split either side can be resized to
+pub const MIN_SPLIT_RATIO: f64 = 0.05;
+
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum SplitDirection {
+    // Side by side
+    Horizontal,
+    // One above the other
+    Vertical,
+}
+
+// A binary tree of panes: leaves show a session, splits divide their area between two children
+#[derive(Serialize, Deserialize, Clone, Debug)]
+#[serde(tag = "kind", rename_all = "camelCase")]
+pub enum PaneNode {
+    #[serde(rename_all = "camelCase")]
+    Pane {
+        id: String,
+        session_id: Option<String>,
+    },
+    #[serde(rename_all = "camelCase")]
+    Split {
+        id: String,
+        direction: SplitDirection,
+        // Share of the area given to `first`
+        ratio: f64,
+        first: Box<PaneNode>,
+        second: Box<PaneNode>,
+    },
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct SessionGroup {
+    pub id: String,
+    pub name: String,
+    pub root: PaneNode,
+    pub focused_pane: String,
+}
+
+// Named set of session groups (tabs), persisted so layouts survive restarts
+#[derive(Serialize, Deserialize, Clone, Debug, Default)]
+#[serde(default, rename_all = "camelCase")]
+pub struct Workspace {
+    pub name: String,
+    pub groups: Vec<SessionGroup>,
+    pub active_group: Option<String>,
+}
+
+// Label a session's tab is shown with, so e.g. prod and staging sessions stand apart
+#[derive(Serialize, Deserialize, Clone, Debug, Default, PartialEq, Eq)]
+#[serde(default, rename_all = "camelCase")]
+pub struct SessionMeta {
+    pub name: Option<String>,
+    // #rgb or #rrggbb
+    pub color: Option<String>,
+    pub tags: Vec<String>,
+    // Closing the session, broadcasting to it, or running a dangerPatterns command in it
+    // needs confirmation
+    pub protected: bool,
+}
+
+impl SessionMeta {
+    pub fn is_empty(&self) -> bool {
+        self.name.is_none() && self.color.is_none() && self.tags.is_empty() && !self.protected
+    }
+}
+
+pub fn new_node_id(prefix: &str) -> String {
+    format!("{}-{}", prefix, uuid::Uuid::new_v4().simple())
+}
+
+impl PaneNode {
+    pub fn new_pane(session_id: Option<String>) -> Self {
+        PaneNode::Pane {
+            id: new_node_id("pane"),
+            session_id,
+        }
+    }
+
+    pub fn id(&self) -> &str {
+        match self {
+            PaneNode::Pane { id, .. } | PaneNode::Split { id, .. } => id,
+        }
+    }
+
+    pub fn find_mut(&mut self, target: &str) -> Option<&mut PaneNode> {
+        if self.id() == target {
+            return Some(self);
+        }
+        match self {
+            PaneNode::Pane { .. } => None,
+            PaneNode::Split { first, second, .. } => {
+                first.find_mut(target).or_else(|| second.find_mut(target))
+            }
+        }
+    }
+
+    // Ids of the leaf panes, in layout order
+    pub fn pane_ids(&self) -> Vec<String> {
+        match self {
+            PaneNode::Pane { id, .. } => vec![id.clone()],
+            PaneNode::Split { first, second, .. } => {
+                let mut ids = first.pane_ids();
+                ids.extend(second.pane_ids());
+                ids
+            }
+        }
+    }
+
+    // Removes a leaf pane; its sibling takes the parent split's place. Returns false if the
+    // pane was not found below this node.
+    pub fn remove_pane(&mut self, target: &str) -> bool {
+        let PaneNode::Split { first, second, .. } = self else {
+            return false;
+        };
+        let survivor = if first.id() == target && matches!(**first, PaneNode::Pane { .. }) {
+            (**second).clone()
+        } else if second.id() == target && matches!(**second, PaneNode::Pane { .. }) {
+            (**first).clone()
+        } else {
+            return first.remove_pane(target) || second.remove_pane(target);
+        };
+        *self = survivor;
+        true
+    }
+}
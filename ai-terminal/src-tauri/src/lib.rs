@@ -1,3 +1,17 @@
+pub mod appearance;
+pub mod audit;
 pub mod command;
+pub mod history;
+pub mod hooks;
+pub mod i18n;
+pub mod layout;
+pub mod notifications;
 pub mod ollama;
+pub mod privacy;
+pub mod receipts;
+pub mod scheduler;
+pub mod serial;
+pub mod settings;
+#[cfg(feature = "test-harness")]
+pub mod testing;
 pub mod utils;
@@ -1,41 +1,380 @@
 extern crate fix_path_env;
 
+use ai_terminal_lib::appearance::types::appearance_manager::AppearanceManager;
+use ai_terminal_lib::audit::audit_log::AuditLog;
+use ai_terminal_lib::command::types::bell_manager::BellManager;
 use ai_terminal_lib::command::types::command_manager::CommandManager;
+use ai_terminal_lib::command::types::confirmation_manager::ConfirmationManager;
+use ai_terminal_lib::command::types::copy_mode_manager::CopyModeManager;
+use ai_terminal_lib::command::types::file_operation_manager::FileOperationManager;
+use ai_terminal_lib::command::types::git_status_cache::GitStatusCache;
+use ai_terminal_lib::command::types::learned_command_store::LearnedCommandStore;
+use ai_terminal_lib::command::types::line_editor_manager::LineEditorManager;
+use ai_terminal_lib::command::types::macro_manager::MacroManager;
+use ai_terminal_lib::command::types::output_processor_manager::OutputProcessorManager;
+use ai_terminal_lib::command::types::progress_tracker::ProgressTracker;
 use ai_terminal_lib::command::types::pty_manager::PtyManager;
-use ai_terminal_lib::{command, ollama, utils};
+use ai_terminal_lib::command::types::recovery_manager::RecoveryManager;
+use ai_terminal_lib::command::types::screen_model_manager::ScreenModelManager;
+use ai_terminal_lib::command::types::scrollback_manager::ScrollbackManager;
+use ai_terminal_lib::command::types::share_manager::ShareManager;
+use ai_terminal_lib::command::types::timeline_manager::TimelineManager;
+use ai_terminal_lib::command::types::window_router::WindowRouter;
+use ai_terminal_lib::history::types::history_store::HistoryStore;
+use ai_terminal_lib::hooks::types::hook_manager::HookManager;
+use ai_terminal_lib::layout::types::layout_manager::LayoutManager;
+use ai_terminal_lib::notifications::types::notification_manager::NotificationManager;
+use ai_terminal_lib::ollama::types::chat_store::ChatStore;
+use ai_terminal_lib::privacy::types::idle_lock_manager::IdleLockManager;
+use ai_terminal_lib::receipts::types::agent_changes::AgentChangeStore;
+use ai_terminal_lib::receipts::types::agent_session_pool::AgentSessionPool;
+use ai_terminal_lib::receipts::types::receipt_store::ReceiptStore;
+use ai_terminal_lib::scheduler::types::startup_profiler::StartupProfiler;
+use ai_terminal_lib::scheduler::types::task_scheduler::BackgroundScheduler;
+use ai_terminal_lib::serial::types::serial_manager::SerialManager;
+use ai_terminal_lib::settings::types::settings_manager::SettingsManager;
+use ai_terminal_lib::{
+    appearance, audit, command, history, hooks, i18n, layout, notifications, ollama, privacy,
+    receipts, scheduler, serial, settings, utils,
+};
 use std::env;
+use tauri::Manager;
 
 fn main() {
-    let _ = fix_path_env::fix();
+    let startup_profiler = StartupProfiler::new();
+    // Runs the login shell to pick up the user's PATH; commands need it, so this stays blocking
+    let _ = startup_profiler.measure("path_probe", fix_path_env::fix);
 
-    let command_manager = CommandManager::new();
-    let pty_manager = PtyManager::new();
+    let command_manager = startup_profiler.measure("CommandManager", CommandManager::new);
+    let pty_manager = startup_profiler.measure("PtyManager", PtyManager::new);
+    let scrollback_manager = startup_profiler.measure("ScrollbackManager", ScrollbackManager::new);
+    let hook_manager = startup_profiler.measure("HookManager", HookManager::new);
+    let confirmation_manager =
+        startup_profiler.measure("ConfirmationManager", ConfirmationManager::new);
+    let share_manager = startup_profiler.measure("ShareManager", ShareManager::new);
+    let timeline_manager = startup_profiler.measure("TimelineManager", TimelineManager::new);
+    let settings_manager = startup_profiler.measure("SettingsManager", SettingsManager::new);
+    let audit_log = startup_profiler.measure("AuditLog", AuditLog::new);
+    let history_store = startup_profiler.measure("HistoryStore", HistoryStore::new);
+    let serial_manager = startup_profiler.measure("SerialManager", SerialManager::new);
+    let receipt_store = startup_profiler.measure("ReceiptStore", ReceiptStore::new);
+    let agent_change_store = startup_profiler.measure("AgentChangeStore", AgentChangeStore::new);
+    let agent_session_pool = startup_profiler.measure("AgentSessionPool", AgentSessionPool::new);
+    let progress_tracker = startup_profiler.measure("ProgressTracker", ProgressTracker::new);
+    let bell_manager = startup_profiler.measure("BellManager", BellManager::new);
+    let idle_lock_manager = startup_profiler.measure("IdleLockManager", IdleLockManager::new);
+    let line_editor_manager = startup_profiler.measure("LineEditorManager", LineEditorManager::new);
+    let copy_mode_manager = startup_profiler.measure("CopyModeManager", CopyModeManager::new);
+    let screen_model_manager =
+        startup_profiler.measure("ScreenModelManager", ScreenModelManager::new);
+    let macro_manager = startup_profiler.measure("MacroManager", MacroManager::new);
+    let window_router = startup_profiler.measure("WindowRouter", WindowRouter::new);
+    let layout_manager = startup_profiler.measure("LayoutManager", LayoutManager::new);
+    let notification_manager =
+        startup_profiler.measure("NotificationManager", NotificationManager::new);
+    let appearance_manager = startup_profiler.measure("AppearanceManager", AppearanceManager::new);
+    let background_scheduler: BackgroundScheduler =
+        startup_profiler.measure("BackgroundScheduler", BackgroundScheduler::new);
+    let recovery_manager = startup_profiler.measure("RecoveryManager", RecoveryManager::new);
+    let output_processor_manager =
+        startup_profiler.measure("OutputProcessorManager", OutputProcessorManager::new);
+    let learned_command_store =
+        startup_profiler.measure("LearnedCommandStore", LearnedCommandStore::new);
+    let chat_store = startup_profiler.measure("ChatStore", ChatStore::new);
+    let git_status_cache = startup_profiler.measure("GitStatusCache", GitStatusCache::new);
+    let file_operation_manager =
+        startup_profiler.measure("FileOperationManager", FileOperationManager::new);
 
     tauri::Builder::default()
         .plugin(tauri_plugin_shell::init())
-        .setup(|_app| Ok(()))
+        .plugin(tauri_plugin_deep_link::init())
+        .setup(|app| {
+            // Linux and Windows dev builds need the ai-terminal:// scheme registered at runtime
+            #[cfg(any(target_os = "linux", all(debug_assertions, windows)))]
+            {
+                use tauri_plugin_deep_link::DeepLinkExt;
+                app.deep_link().register_all()?;
+            }
+            let handle = app.handle();
+            let profiler = handle.state::<StartupProfiler>();
+            profiler.measure("deep_link_handler", || {
+                command::deep_link::handler::register_deep_link_handler(handle)
+            });
+            profiler.measure("crash_recovery", || {
+                command::recovery::state_snapshot::init_crash_recovery(handle)
+            });
+            profiler.measure("background_scheduler", || {
+                scheduler::background_scheduler::start_background_scheduler(handle)
+            });
+            profiler.measure("appearance", || {
+                appearance::appearance_commands::init_appearance(handle)
+            });
+            profiler.mark_ready();
+            scheduler::startup::start_startup_warmup(handle);
+            Ok(())
+        })
+        .on_window_event(|window, event| {
+            match event {
+                tauri::WindowEvent::ThemeChanged(theme) => {
+                    appearance::appearance_commands::handle_theme_changed(
+                        window.app_handle(),
+                        *theme,
+                    );
+                }
+                // Closing the main window quits the app normally
+                tauri::WindowEvent::Destroyed if window.label() == "main" => {
+                    command::recovery::state_snapshot::clear_state_snapshot();
+                    command::environment::session_tmpdir::remove_all_session_tmpdirs();
+                }
+                _ => {}
+            }
+        })
         .manage(command_manager)
         .manage(pty_manager)
+        .manage(scrollback_manager)
+        .manage(hook_manager)
+        .manage(confirmation_manager)
+        .manage(share_manager)
+        .manage(timeline_manager)
+        .manage(settings_manager)
+        .manage(audit_log)
+        .manage(history_store)
+        .manage(serial_manager)
+        .manage(receipt_store)
+        .manage(agent_change_store)
+        .manage(agent_session_pool)
+        .manage(progress_tracker)
+        .manage(bell_manager)
+        .manage(idle_lock_manager)
+        .manage(line_editor_manager)
+        .manage(copy_mode_manager)
+        .manage(screen_model_manager)
+        .manage(macro_manager)
+        .manage(window_router)
+        .manage(layout_manager)
+        .manage(notification_manager)
+        .manage(appearance_manager)
+        .manage(background_scheduler)
+        .manage(recovery_manager)
+        .manage(output_processor_manager)
+        .manage(learned_command_store)
+        .manage(chat_store)
+        .manage(git_status_cache)
+        .manage(file_operation_manager)
+        .manage(startup_profiler)
         .plugin(tauri_plugin_opener::init())
         .invoke_handler(tauri::generate_handler![
             command::core::execute_command::execute_command,
             command::core::execute_command::execute_sudo_command,
+            command::core::retry_command::execute_with_retry,
+            command::core::command_templates::expand_command_template,
+            command::core::command_templates::list_template_variables,
+            command::core::cost_warnings::check_command_cost,
+            command::core::port_conflicts::check_port_conflict,
+            command::core::pipeline_builder::build_pipeline,
+            command::jobs::detached_jobs::detach_command,
+            command::jobs::detached_jobs::list_detached_jobs,
+            command::jobs::detached_jobs::tail_detached_log,
+            command::core::session_variables::set_var,
+            command::core::session_variables::get_var,
+            command::core::session_variables::list_vars,
+            command::core::active_commands::get_active_commands,
+            command::core::active_commands::kill_active_command,
+            command::core::input_prompts::send_command_input,
+            command::core::input_prompts::close_command_input,
+            command::core::output_capture::capture_output,
+            command::core::output_capture::list_captures,
+            command::core::output_capture::clear_capture,
+            command::line_editor::line_editor_commands::line_editor_apply,
+            command::line_editor::line_editor_commands::line_editor_state,
+            command::line_editor::line_editor_commands::line_editor_accept,
+            command::windows::session_windows::attach_session_to_window,
+            command::windows::session_windows::move_session_to_window,
+            command::windows::session_windows::open_session_window,
+            command::windows::session_windows::list_session_windows,
+            layout::layout_commands::list_workspaces,
+            layout::layout_commands::get_layout,
+            layout::layout_commands::delete_workspace,
+            layout::layout_commands::create_session_group,
+            layout::layout_commands::close_session_group,
+            layout::layout_commands::split_pane,
+            layout::layout_commands::close_pane,
+            layout::layout_commands::resize_pane,
+            layout::layout_commands::assign_pane_session,
+            layout::layout_commands::set_session_meta,
+            layout::layout_commands::set_session_protected,
+            layout::layout_commands::list_sessions,
+            notifications::notification_commands::get_notification_policy,
+            notifications::notification_commands::set_notification_override,
+            notifications::notification_commands::route_notification,
+            command::output::bell::get_bell_action,
+            command::output::bell::set_bell_action,
+            appearance::appearance_commands::get_appearance,
+            i18n::i18n_commands::get_error_catalog,
+            i18n::i18n_commands::list_locales,
+            utils::cell_width::measure_cells,
+            command::correction::natural_language::classify_input,
+            command::output::ai_selection::set_ai_selection,
+            command::output::ai_selection::clear_ai_selection,
+            scheduler::background_scheduler::list_background_tasks,
+            scheduler::background_scheduler::run_background_task,
+            scheduler::startup::get_startup_profile,
+            command::recovery::state_snapshot::get_recovery_state,
+            command::recovery::state_snapshot::restore_previous_state,
+            command::recovery::state_snapshot::discard_recovery_state,
+            command::environment::directory_env::approve_envrc,
+            command::environment::directory_env::revoke_envrc,
+            command::environment::directory_env::get_directory_env,
+            command::environment::python_env::list_python_environments,
+            command::environment::python_env::activate_environment,
+            command::environment::python_env::deactivate_environment,
+            command::environment::node_version::get_node_version_info,
+            command::environment::node_version::use_node_version,
+            command::environment::cloud_profiles::get_cloud_context,
+            command::environment::cloud_profiles::list_cloud_profiles,
+            command::environment::cloud_profiles::set_cloud_profile,
             command::core::terminate_command::terminate_command,
             command::core::pty::pty_create_session,
             command::core::pty::pty_write,
+            command::core::pty::pty_broadcast,
+            command::macros::macro_commands::start_macro_recording,
+            command::macros::macro_commands::stop_macro_recording,
+            command::macros::macro_commands::play_macro,
+            command::macros::macro_commands::stop_macro_playback,
+            command::macros::macro_commands::list_macros,
+            command::macros::macro_commands::delete_macro,
             command::core::pty::pty_resize,
+            command::core::pty_mouse::pty_get_mouse_mode,
+            command::core::pty_mouse::pty_send_mouse,
             command::core::pty::pty_close_session,
+            command::core::pty::get_pty_session_target,
+            command::containers::docker::list_containers,
+            command::containers::docker::create_container_session,
+            command::containers::kubernetes::create_k8s_session,
+            command::containers::wsl::list_wsl_distros,
+            command::containers::wsl::create_wsl_session,
+            command::containers::wsl::translate_wsl_path,
+            command::ssh::remote_exec::ssh_exec,
+            command::repl::code_block_runner::run_code_block,
+            command::environment::session_tmpdir::get_session_tmpdir,
+            command::repl::repl_launcher::start_repl,
+            command::repl::repl_launcher::send_to_repl,
+            serial::serial_commands::list_serial_ports,
+            serial::serial_commands::open_serial_session,
+            serial::serial_commands::serial_write,
+            serial::serial_commands::set_serial_signals,
+            serial::serial_commands::close_serial_session,
+            receipts::receipt_commands::execute_agent_command,
+            receipts::receipt_commands::list_command_receipts,
+            receipts::receipt_commands::replay_command_receipt,
+            receipts::agent_changes::get_agent_changes,
+            receipts::agent_changes::clear_agent_changes,
+            receipts::agent_undo::undo_agent_run,
+            receipts::agent_sessions::open_agent_session,
+            receipts::agent_sessions::list_agent_sessions,
+            receipts::agent_sessions::run_in_agent_session,
+            receipts::agent_sessions::read_agent_session_output,
+            receipts::agent_sessions::close_agent_session,
             utils::operating_system_utils::get_current_pid,
             command::autocomplete::autocomplete_command::autocomplete,
+            command::autocomplete::command_names::get_known_commands,
+            command::autocomplete::command_names::forget_learned_command,
+            command::autocomplete::tldr::get_tldr,
+            command::docs::cheatsheets::get_cheatsheet,
+            command::docs::cheatsheets::search_cheatsheets,
+            command::docs::man_pages::get_man_page,
             utils::file_system_utils::get_working_directory,
             utils::file_system_utils::get_home_directory,
             ollama::model_request::request::ask_ai,
+            ollama::model_request::request::preview_ai_request,
+            ollama::model_request::request::preview_ai_context,
+            ollama::context_budget::set_ai_context_omissions,
+            ollama::conversation::get_conversation,
+            ollama::conversation::regenerate_last_answer,
+            ollama::conversation::branch_conversation,
+            ollama::conversation::list_conversation_branches,
+            ollama::conversation::switch_conversation_branch,
             ollama::model_request::request::get_models,
             ollama::model_request::request::switch_model,
             ollama::model_request::request::get_host,
             ollama::model_request::request::set_host,
+            ollama::model_request::request::set_provider,
+            ollama::model_request::request::get_provider,
             command::git_commands::git::get_git_branch,
+            command::prompt::prompt_segments::get_prompt_segments,
             utils::operating_system_utils::get_system_environment_variables,
+            command::shell_integration::installer::detect_shell_integration,
+            command::shell_integration::installer::install_shell_integration,
+            command::shell_integration::installer::uninstall_shell_integration,
+            command::transcript::export::export_transcript,
+            command::repro::repro_bundle::capture_repro_bundle,
+            command::checksums::file_hashing::hash_file,
+            command::checksums::file_hashing::verify_checksum,
+            command::files::file_operations::fs_copy,
+            command::files::file_operations::fs_move,
+            command::files::file_operations::fs_delete,
+            command::files::file_operations::cancel_file_operation,
+            command::files::bulk_rename::bulk_rename,
+            command::network::dns_lookup::dns_lookup,
+            command::network::tls_inspection::inspect_tls,
+            command::deep_link::handler::open_deep_link,
+            command::sharing::session_sharing::start_sharing,
+            command::sharing::session_sharing::stop_sharing,
+            command::sharing::session_sharing::list_shared_sessions,
+            command::output::output_diff::diff_last_outputs,
+            command::output::diagnostics::get_last_diagnostics,
+            command::output::plain_text::strip_ansi,
+            command::output::plain_text::set_plain_text_retention,
+            command::output::plain_text::get_plain_text_output,
+            command::output::copy_mode::enter_copy_mode,
+            command::output::copy_mode::copy_mode_move,
+            command::output::copy_mode::copy_mode_select,
+            command::output::copy_mode::copy_mode_yank,
+            command::output::copy_mode::exit_copy_mode,
+            command::output::screen_state::get_screen_state,
+            command::output::structured_output::format_last_output,
+            command::output::post_processors::list_output_processors,
+            command::output::post_processors::set_output_processor_enabled,
+            command::output::output_marks::add_output_mark,
+            command::output::output_marks::remove_output_mark,
+            command::output::output_marks::list_marks,
+            command::output::output_marks::jump_to_mark,
+            command::timeline::session_timeline::get_session_timeline,
+            command::timeline::session_timeline::export_session_timeline,
+            command::project::project_detection::detect_project,
+            command::project::project_tasks::list_project_tasks,
+            command::project::project_tasks::run_project_task,
+            settings::settings_commands::get_settings,
+            settings::settings_commands::update_settings,
+            settings::settings_commands::preview_environment_scrubbing,
+            audit::audit_commands::verify_audit_log,
+            audit::audit_commands::export_audit_log,
+            history::history_commands::history_prev,
+            history::history_commands::history_next,
+            history::history_commands::history_search,
+            history::history_commands::history_search_reset,
+            history::host_history::get_host_history,
+            history::shell_import::import_shell_history,
+            history::sync::sync_commands::get_history_sync_config,
+            history::sync::sync_commands::configure_history_sync,
+            history::sync::sync_commands::sync_history,
+            settings::backup::backup_app_data,
+            settings::backup::restore_app_data,
+            history::usage_stats::get_usage_stats,
+            history::daily_digest::get_daily_digest,
+            history::daily_digest::generate_daily_digest,
+            history::directory_history::get_recent_directories,
+            history::directory_history::get_recent_projects,
+            history::directory_suggestions::get_directory_suggestions,
+            privacy::idle_lock::report_activity,
+            privacy::idle_lock::lock_window,
+            privacy::idle_lock::unlock_window,
+            privacy::idle_lock::is_window_locked,
+            hooks::hook_commands::list_hooks,
+            hooks::hook_commands::add_hook,
+            hooks::hook_commands::remove_hook,
+            hooks::hook_commands::set_hook_enabled,
+            hooks::hook_commands::test_hook,
         ])
         .run(tauri::generate_context!())
         .expect("Error launcing AI Terminal");
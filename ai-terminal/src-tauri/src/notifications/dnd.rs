@@ -0,0 +1,48 @@
+#[cfg(any(target_os = "macos", target_os = "linux"))]
+use std::process::Command;
+
+// Whether the OS is currently in do-not-disturb / Focus. Systems without a way to tell
+// report false so notifications are never lost.
+#[cfg(target_os = "macos")]
+pub fn is_dnd_active() -> bool {
+    // Monterey and later record active Focus modes as assertions
+    let assertions = dirs::home_dir()
+        .map(|home| home.join("Library/DoNotDisturb/DB/Assertions.json"))
+        .and_then(|path| std::fs::read_to_string(path).ok())
+        .and_then(|contents| serde_json::from_str::<serde_json::Value>(&contents).ok());
+    if let Some(assertions) = assertions {
+        return assertions["data"].as_array().is_some_and(|stores| {
+            stores.iter().any(|store| {
+                store["storeAssertionRecords"]
+                    .as_array()
+                    .is_some_and(|records| !records.is_empty())
+            })
+        });
+    }
+    // Big Sur and earlier keep a single flag in the notification center preferences
+    Command::new("defaults")
+        .args([
+            "-currentHost",
+            "read",
+            "com.apple.notificationcenterui",
+            "doNotDisturb",
+        ])
+        .output()
+        .is_ok_and(|output| String::from_utf8_lossy(&output.stdout).trim() == "1")
+}
+
+#[cfg(target_os = "linux")]
+pub fn is_dnd_active() -> bool {
+    // GNOME's "Do Not Disturb" toggle turns banners off
+    Command::new("gsettings")
+        .args(["get", "org.gnome.desktop.notifications", "show-banners"])
+        .output()
+        .is_ok_and(|output| {
+            output.status.success() && String::from_utf8_lossy(&output.stdout).trim() == "false"
+        })
+}
+
+#[cfg(not(any(target_os = "macos", target_os = "linux")))]
+pub fn is_dnd_active() -> bool {
+    false
+}
@@ -0,0 +1,3 @@
+pub mod dnd;
+pub mod notification_commands;
+pub mod types;
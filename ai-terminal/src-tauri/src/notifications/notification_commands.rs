@@ -0,0 +1,133 @@
+use crate::command::windows::session_windows::emit_session_event;
+use crate::notifications::dnd::is_dnd_active;
+use crate::notifications::types::notification::{
+    NotificationAction, NotificationKind, NotificationOverride, NotificationPolicy,
+    TerminalNotification,
+};
+use crate::notifications::types::notification_manager::NotificationManager;
+use crate::settings::types::app_settings::DndBehavior;
+use crate::settings::types::settings_manager::SettingsManager;
+use crate::utils::config_utils::unix_timestamp_millis;
+use tauri::{command, AppHandle, Emitter, Manager, Runtime, State};
+
+fn resolve_action(
+    dnd_active: bool,
+    session_override: NotificationOverride,
+    behavior: DndBehavior,
+) -> NotificationAction {
+    match session_override {
+        NotificationOverride::Mute => NotificationAction::Suppress,
+        NotificationOverride::AlwaysNotify => NotificationAction::Deliver,
+        NotificationOverride::FollowSystem if !dnd_active => NotificationAction::Deliver,
+        NotificationOverride::FollowSystem => match behavior {
+            DndBehavior::Queue => NotificationAction::Queue,
+            DndBehavior::Suppress => NotificationAction::Suppress,
+        },
+    }
+}
+
+// What a notification for the session raised now would do
+pub fn notification_action<R: Runtime>(
+    app_handle: &AppHandle<R>,
+    session_id: &str,
+) -> NotificationAction {
+    let notification_manager = app_handle.state::<NotificationManager>();
+    resolve_action(
+        notification_manager.dnd_active(),
+        notification_manager.session_override(session_id),
+        app_handle.state::<SettingsManager>().current().dnd_behavior,
+    )
+}
+
+// Scheduler task: tracks do-not-disturb and, when it ends, delivers what was queued
+pub fn poll_dnd<R: Runtime>(app_handle: &AppHandle<R>) -> Result<(), String> {
+    let active = is_dnd_active();
+    let notification_manager = app_handle.state::<NotificationManager>();
+    if notification_manager.set_dnd_active(active) != active {
+        let _ = app_handle.emit("dnd_changed", active);
+    }
+    if !active {
+        for notification in notification_manager.take_queued() {
+            let session_id = notification.session_id.clone();
+            emit_session_event(app_handle, &session_id, "notification", notification);
+        }
+    }
+    Ok(())
+}
+
+#[command]
+pub fn get_notification_policy(
+    session_id: Option<String>,
+    notification_manager: State<'_, NotificationManager>,
+    settings_manager: State<'_, SettingsManager>,
+) -> Result<NotificationPolicy, String> {
+    let dnd_active = notification_manager.dnd_active();
+    let session_override = session_id
+        .map(|session_id| notification_manager.session_override(&session_id))
+        .unwrap_or_default();
+    Ok(NotificationPolicy {
+        dnd_active,
+        session_override,
+        action: resolve_action(
+            dnd_active,
+            session_override,
+            settings_manager.current().dnd_behavior,
+        ),
+        queued: notification_manager.queued_count(),
+    })
+}
+
+#[command]
+pub fn set_notification_override(
+    session_id: String,
+    mode: NotificationOverride,
+    notification_manager: State<'_, NotificationManager>,
+) -> Result<(), String> {
+    let mut overrides = notification_manager
+        .overrides
+        .lock()
+        .map_err(|e| e.to_string())?;
+    match mode {
+        NotificationOverride::FollowSystem => overrides.remove(&session_id),
+        mode => overrides.insert(session_id, mode),
+    };
+    Ok(())
+}
+
+// Routes a completion notification or bell through the policy. Delivered notifications are
+// emitted as `notification` to the session's window, which shows them (or rings the bell).
+#[command]
+pub fn route_notification<R: Runtime>(
+    session_id: String,
+    kind: NotificationKind,
+    title: String,
+    body: String,
+    app_handle: AppHandle<R>,
+    notification_manager: State<'_, NotificationManager>,
+    settings_manager: State<'_, SettingsManager>,
+) -> Result<NotificationAction, String> {
+    let action = resolve_action(
+        notification_manager.dnd_active(),
+        notification_manager.session_override(&session_id),
+        settings_manager.current().dnd_behavior,
+    );
+    let notification = TerminalNotification {
+        session_id: session_id.clone(),
+        kind,
+        title,
+        body,
+        timestamp: unix_timestamp_millis(),
+    };
+    match action {
+        NotificationAction::Deliver => {
+            emit_session_event(&app_handle, &session_id, "notification", notification)
+        }
+        // A bell heard minutes later means nothing, so bells are never queued
+        NotificationAction::Queue if kind == NotificationKind::Bell => {
+            return Ok(NotificationAction::Suppress)
+        }
+        NotificationAction::Queue => notification_manager.enqueue(notification),
+        NotificationAction::Suppress => {}
+    }
+    Ok(action)
+}
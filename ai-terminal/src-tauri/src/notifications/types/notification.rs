@@ -0,0 +1,51 @@
+use serde::{Deserialize, Serialize};
+
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum NotificationKind {
+    // A long-running command finished
+    Completion,
+    // The program in the session rang the terminal bell
+    Bell,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct TerminalNotification {
+    pub session_id: String,
+    pub kind: NotificationKind,
+    pub title: String,
+    pub body: String,
+    pub timestamp: u64,
+}
+
+// Per-session choice that takes precedence over do-not-disturb
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub enum NotificationOverride {
+    // Follow do-not-disturb and the dndBehavior setting
+    #[default]
+    FollowSystem,
+    // Notify even while do-not-disturb is on (e.g. a deploy being watched)
+    AlwaysNotify,
+    // Never notify for this session
+    Mute,
+}
+
+#[derive(Serialize, Clone, Copy, Debug, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum NotificationAction {
+    Deliver,
+    Queue,
+    Suppress,
+}
+
+#[derive(Serialize, Clone, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct NotificationPolicy {
+    pub dnd_active: bool,
+    pub session_override: NotificationOverride,
+    // What a notification raised now would do
+    pub action: NotificationAction,
+    pub queued: usize,
+}
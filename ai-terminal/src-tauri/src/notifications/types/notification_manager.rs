@@ -0,0 +1,66 @@
+use crate::command::constants::MAX_QUEUED_NOTIFICATIONS;
+use crate::notifications::types::notification::{NotificationOverride, TerminalNotification};
+use std::collections::{HashMap, VecDeque};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Mutex;
+
+// Last observed do-not-disturb state, session overrides, and notifications held back
+pub struct NotificationManager {
+    dnd_active: AtomicBool,
+    pub overrides: Mutex<HashMap<String, NotificationOverride>>,
+    queued: Mutex<VecDeque<TerminalNotification>>,
+}
+
+impl NotificationManager {
+    pub fn new() -> Self {
+        Self {
+            dnd_active: AtomicBool::new(false),
+            overrides: Mutex::new(HashMap::new()),
+            queued: Mutex::new(VecDeque::new()),
+        }
+    }
+
+    pub fn dnd_active(&self) -> bool {
+        self.dnd_active.load(Ordering::Relaxed)
+    }
+
+    // Returns the previous state
+    pub fn set_dnd_active(&self, active: bool) -> bool {
+        self.dnd_active.swap(active, Ordering::Relaxed)
+    }
+
+    pub fn session_override(&self, session_id: &str) -> NotificationOverride {
+        self.overrides
+            .lock()
+            .ok()
+            .and_then(|overrides| overrides.get(session_id).copied())
+            .unwrap_or_default()
+    }
+
+    // Oldest notifications are dropped once the queue is full
+    pub fn enqueue(&self, notification: TerminalNotification) {
+        if let Ok(mut queued) = self.queued.lock() {
+            if queued.len() >= MAX_QUEUED_NOTIFICATIONS {
+                queued.pop_front();
+            }
+            queued.push_back(notification);
+        }
+    }
+
+    pub fn queued_count(&self) -> usize {
+        self.queued.lock().map(|queued| queued.len()).unwrap_or(0)
+    }
+
+    pub fn take_queued(&self) -> Vec<TerminalNotification> {
+        self.queued
+            .lock()
+            .map(|mut queued| queued.drain(..).collect())
+            .unwrap_or_default()
+    }
+}
+
+impl Default for NotificationManager {
+    fn default() -> Self {
+        Self::new()
+    }
+}
@@ -0,0 +1,261 @@
+use crate::command::constants::AI_RESPONSE_TOKEN_RESERVE;
+use crate::command::types::command_manager::CommandManager;
+use crate::command::types::command_state::CommandState;
+use crate::ollama::context_filter::{ContextPieceKind, FilteredContextPiece};
+use crate::utils::token_estimate::estimate_tokens;
+use serde::Serialize;
+use tauri::{command, State};
+
+// Most important first: what was asked and pointed at, then why it failed and what was
+// already discussed, then the surroundings, then what scrolled by
+const PRIORITY: &[ContextPieceKind] = &[
+    ContextPieceKind::Question,
+    ContextPieceKind::Selection,
+    ContextPieceKind::Error,
+    ContextPieceKind::Conversation,
+    ContextPieceKind::Target,
+    ContextPieceKind::Project,
+    ContextPieceKind::PythonEnv,
+    ContextPieceKind::Variables,
+    ContextPieceKind::RecentOutput,
+    ContextPieceKind::History,
+];
+
+// Prompt order: session context joined with spaces, then the conversation, history, output
+// and selection blocks, and the question last
+const CONTEXT_LINE: &[ContextPieceKind] = &[
+    ContextPieceKind::Target,
+    ContextPieceKind::Project,
+    ContextPieceKind::PythonEnv,
+    ContextPieceKind::Variables,
+];
+const BLOCKS: &[ContextPieceKind] = &[
+    ContextPieceKind::Conversation,
+    ContextPieceKind::History,
+    ContextPieceKind::RecentOutput,
+    ContextPieceKind::Error,
+    ContextPieceKind::Selection,
+    ContextPieceKind::Question,
+];
+
+#[derive(Serialize, Clone, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct BudgetedContextPiece {
+    #[serde(flatten)]
+    pub piece: FilteredContextPiece,
+    // Estimated tokens as sent, and before truncation
+    pub tokens: usize,
+    pub original_tokens: usize,
+    // Lost its oldest lines to fit the budget
+    pub truncated: bool,
+    // Did not fit at all
+    pub dropped: bool,
+    // Turned off for the session with set_ai_context_omissions
+    pub omitted: bool,
+}
+
+impl BudgetedContextPiece {
+    fn is_sent(&self) -> bool {
+        !self.piece.excluded && !self.dropped && !self.omitted && !self.piece.text.is_empty()
+    }
+}
+
+#[derive(Serialize, Clone, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct ContextBudget {
+    pub context_window: usize,
+    pub reserved_for_response: usize,
+    // What the prompt may use
+    pub available: usize,
+    pub used: usize,
+    // The question alone is larger than the budget; it is sent anyway
+    pub over_budget: bool,
+}
+
+pub fn validate_context_window(tokens: usize) -> Result<(), String> {
+    if tokens <= AI_RESPONSE_TOKEN_RESERVE {
+        return Err(format!(
+            "The AI context window must be larger than the {} tokens reserved for the answer",
+            AI_RESPONSE_TOKEN_RESERVE
+        ));
+    }
+    Ok(())
+}
+
+fn priority(kind: ContextPieceKind) -> usize {
+    PRIORITY
+        .iter()
+        .position(|candidate| *candidate == kind)
+        .unwrap_or(PRIORITY.len())
+}
+
+// Output-like pieces can lose their oldest lines; the rest are kept whole or not at all
+fn is_truncatable(kind: ContextPieceKind) -> bool {
+    matches!(
+        kind,
+        ContextPieceKind::Selection
+            | ContextPieceKind::Error
+            | ContextPieceKind::Conversation
+            | ContextPieceKind::RecentOutput
+            | ContextPieceKind::History
+    )
+}
+
+// The heading line plus as many of the last lines as fit, where errors and summaries are
+fn keep_tail(text: &str, budget: usize) -> Option<String> {
+    let lines: Vec<&str> = text.lines().collect();
+    let (heading, body) = lines.split_first()?;
+    let mut used =
+        estimate_tokens(heading) + estimate_tokens("[... 9999 earlier lines omitted]") + 2;
+    let mut kept = 0;
+    for line in body.iter().rev() {
+        let cost = estimate_tokens(line) + 1;
+        if used + cost > budget {
+            break;
+        }
+        used += cost;
+        kept += 1;
+    }
+    if kept == 0 {
+        return None;
+    }
+    let omitted = body.len() - kept;
+    Some(format!(
+        "{}\n[... {} earlier lines omitted]\n{}",
+        heading,
+        omitted,
+        body[omitted..].join("\n")
+    ))
+}
+
+// Fits the pieces into `available` tokens by priority, truncating or dropping the least
+// important. The question is always kept.
+pub fn fit_to_budget(
+    pieces: Vec<FilteredContextPiece>,
+    omitted: &[ContextPieceKind],
+    context_window: usize,
+) -> (Vec<BudgetedContextPiece>, ContextBudget) {
+    let available = context_window.saturating_sub(AI_RESPONSE_TOKEN_RESERVE);
+    let mut budgeted: Vec<BudgetedContextPiece> = pieces
+        .into_iter()
+        .map(|piece| {
+            let tokens = if piece.excluded {
+                0
+            } else {
+                estimate_tokens(&piece.text)
+            };
+            BudgetedContextPiece {
+                omitted: piece.kind != ContextPieceKind::Question && omitted.contains(&piece.kind),
+                piece,
+                tokens,
+                original_tokens: tokens,
+                truncated: false,
+                dropped: false,
+            }
+        })
+        .collect();
+
+    let mut order: Vec<usize> = (0..budgeted.len()).collect();
+    order.sort_by_key(|index| priority(budgeted[*index].piece.kind));
+    let mut used = 0;
+    for index in order {
+        let piece = &mut budgeted[index];
+        if !piece.is_sent() {
+            piece.tokens = 0;
+            continue;
+        }
+        // Blocks are separated by a blank line
+        let cost = piece.tokens + 1;
+        let remaining = available.saturating_sub(used);
+        if cost <= remaining || piece.piece.kind == ContextPieceKind::Question {
+            used += cost;
+            continue;
+        }
+        let truncated = is_truncatable(piece.piece.kind)
+            .then(|| keep_tail(&piece.piece.text, remaining.saturating_sub(1)))
+            .flatten();
+        match truncated {
+            Some(text) => {
+                piece.tokens = estimate_tokens(&text);
+                piece.piece.text = text;
+                piece.truncated = true;
+                used += piece.tokens + 1;
+            }
+            None => {
+                piece.tokens = 0;
+                piece.dropped = true;
+            }
+        }
+    }
+
+    let budget = ContextBudget {
+        context_window,
+        reserved_for_response: AI_RESPONSE_TOKEN_RESERVE,
+        available,
+        used,
+        over_budget: used > available,
+    };
+    (budgeted, budget)
+}
+
+pub fn assemble_prompt(pieces: &[BudgetedContextPiece]) -> String {
+    let sent = |kind: ContextPieceKind| {
+        pieces
+            .iter()
+            .filter(move |piece| piece.piece.kind == kind && piece.is_sent())
+            .map(|piece| piece.piece.text.as_str())
+    };
+    let context = CONTEXT_LINE
+        .iter()
+        .flat_map(|kind| sent(*kind))
+        .collect::<Vec<_>>()
+        .join(" ");
+    std::iter::once(context.as_str())
+        .chain(BLOCKS.iter().flat_map(|kind| sent(*kind)))
+        .filter(|part| !part.is_empty())
+        .collect::<Vec<_>>()
+        .join("\n\n")
+}
+
+// Leaves the given kinds of context out of the session's AI requests, e.g. history that
+// only distracts the model. An empty list restores everything; the question is always sent.
+#[command]
+pub fn set_ai_context_omissions(
+    session_id: String,
+    omitted: Vec<ContextPieceKind>,
+    command_manager: State<'_, CommandManager>,
+) -> Result<Vec<ContextPieceKind>, String> {
+    let mut omitted: Vec<ContextPieceKind> = omitted
+        .into_iter()
+        .filter(|kind| *kind != ContextPieceKind::Question)
+        .collect();
+    omitted.sort_by_key(|kind| priority(*kind));
+    omitted.dedup();
+    let mut states = command_manager.commands.lock().map_err(|e| e.to_string())?;
+    let state = states.entry(session_id).or_insert_with(|| {
+        CommandState::new(
+            std::env::current_dir()
+                .unwrap_or_default()
+                .to_string_lossy()
+                .to_string(),
+        )
+    });
+    state.ai_context_omitted = omitted.clone();
+    Ok(omitted)
+}
+
+pub fn session_context_omissions(
+    command_manager: &CommandManager,
+    session_id: &str,
+) -> Vec<ContextPieceKind> {
+    command_manager
+        .commands
+        .lock()
+        .ok()
+        .and_then(|states| {
+            states
+                .get(session_id)
+                .map(|state| state.ai_context_omitted.clone())
+        })
+        .unwrap_or_default()
+}
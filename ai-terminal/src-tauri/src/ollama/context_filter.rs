@@ -0,0 +1,280 @@
+use crate::command::output::post_processors::redact_text_counted;
+use crate::ollama::types::ai_provider::AiProvider;
+use crate::settings::types::app_settings::{AiContextFilterMode, AppSettings};
+use crate::utils::file_system_utils::expand_home;
+use serde::{Deserialize, Serialize};
+use std::net::IpAddr;
+use std::path::{Component, Path};
+
+const OMITTED_LINE: &str = "[line omitted: mentions an excluded path]";
+
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum ContextPieceKind {
+    // Container, SSH host or other target a PTY session runs in
+    Target,
+    Project,
+    PythonEnv,
+    Variables,
+    // Earlier commands in the session
+    History,
+    // Output of the last command, when it succeeded or is still running
+    RecentOutput,
+    // Output of the last command, when it failed
+    Error,
+    // Earlier questions and answers on the active chat branch
+    Conversation,
+    Selection,
+    Question,
+}
+
+// One part of an AI prompt, with the local directory it was gathered from
+#[derive(Clone, Debug)]
+pub struct ContextPiece {
+    pub kind: ContextPieceKind,
+    pub source: Option<String>,
+    pub text: String,
+}
+
+#[derive(Serialize, Clone, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct FilteredContextPiece {
+    pub kind: ContextPieceKind,
+    // Exactly what is sent; empty when the piece is excluded
+    pub text: String,
+    pub excluded: bool,
+    pub reason: Option<String>,
+    pub redactions: usize,
+    pub omitted_lines: usize,
+}
+
+// Ollama on a loopback address (or the mock provider) keeps everything on this machine
+pub fn is_hosted(provider: AiProvider, api_host: &str) -> bool {
+    if provider == AiProvider::Mock {
+        return false;
+    }
+    let host = url::Url::parse(api_host.trim())
+        .ok()
+        .and_then(|url| url.host_str().map(|host| host.to_string()));
+    match host {
+        Some(host) => {
+            let host = host.trim_matches(['[', ']']);
+            !(host.eq_ignore_ascii_case("localhost")
+                || host
+                    .parse::<IpAddr>()
+                    .is_ok_and(|ip| ip.is_loopback() || ip.is_unspecified()))
+        }
+        // Assume the worst about a host that cannot be parsed
+        None => true,
+    }
+}
+
+pub fn filter_applies(settings: &AppSettings, provider: AiProvider, api_host: &str) -> bool {
+    match settings.ai_context_filter {
+        AiContextFilterMode::Off => false,
+        AiContextFilterMode::Always => true,
+        AiContextFilterMode::Hosted => is_hosted(provider, api_host),
+    }
+}
+
+// `*` and `?` within one path component
+fn component_matches(pattern: &[char], name: &[char]) -> bool {
+    match pattern.split_first() {
+        None => name.is_empty(),
+        Some(('*', rest)) => (0..=name.len()).any(|skip| component_matches(rest, &name[skip..])),
+        Some((first, rest)) => name.split_first().is_some_and(|(character, name_rest)| {
+            (*first == '?' || first == character) && component_matches(rest, name_rest)
+        }),
+    }
+}
+
+fn segment_matches(pattern: &str, name: &str) -> bool {
+    let pattern: Vec<char> = pattern.chars().collect();
+    let name: Vec<char> = name.chars().collect();
+    component_matches(&pattern, &name)
+}
+
+// True when the pattern matches the path or one of its ancestors, so a directory pattern
+// covers everything below it
+fn components_match(pattern: &[String], path: &[String]) -> bool {
+    match pattern.split_first() {
+        None => true,
+        Some((first, rest)) if first == "**" => {
+            (0..=path.len()).any(|skip| components_match(rest, &path[skip..]))
+        }
+        Some((first, rest)) => path.split_first().is_some_and(|(name, path_rest)| {
+            segment_matches(first, name) && components_match(rest, path_rest)
+        }),
+    }
+}
+
+// Normal components with `.` and `..` resolved lexically
+fn components(path: &Path) -> Vec<String> {
+    let mut components = Vec::new();
+    for component in path.components() {
+        match component {
+            Component::Normal(name) => components.push(name.to_string_lossy().to_string()),
+            Component::ParentDir => {
+                components.pop();
+            }
+            _ => {}
+        }
+    }
+    components
+}
+
+fn pattern_covers(pattern: &str, path_components: &[String]) -> bool {
+    if !pattern.contains('/') {
+        return path_components
+            .iter()
+            .any(|name| segment_matches(pattern, name));
+    }
+    let Ok(expanded) = expand_home(pattern) else {
+        return false;
+    };
+    let mut pattern_components = components(&expanded);
+    // Relative patterns such as `secrets/*.txt` may start anywhere
+    if !expanded.is_absolute() {
+        pattern_components.insert(0, "**".to_string());
+    }
+    components_match(&pattern_components, path_components)
+}
+
+// The exclusion pattern covering `path` or one of its parents, if any
+pub fn excluding_pattern<'a>(patterns: &'a [String], path: &Path) -> Option<&'a str> {
+    let path_components = components(path);
+    patterns
+        .iter()
+        .map(|pattern| pattern.trim())
+        .find(|pattern| !pattern.is_empty() && pattern_covers(pattern, &path_components))
+}
+
+// Pattern excluding a path mentioned in the line. Bare words are only checked against
+// component patterns such as `*.pem`; anything with a `/` or `~` is resolved against `cwd`.
+fn line_mentions_excluded<'a>(
+    patterns: &'a [String],
+    cwd: Option<&Path>,
+    line: &str,
+) -> Option<&'a str> {
+    line.split(|c: char| {
+        c.is_whitespace()
+            || matches!(
+                c,
+                '"' | '\''
+                    | '`'
+                    | '('
+                    | ')'
+                    | '['
+                    | ']'
+                    | '{'
+                    | '}'
+                    | '<'
+                    | '>'
+                    | ','
+                    | ';'
+                    | '='
+                    | ':'
+                    | '|'
+            )
+    })
+    .map(|token| token.trim_end_matches('.'))
+    .filter(|token| !token.is_empty())
+    .find_map(|token| {
+        if token.contains('/') || token.starts_with('~') {
+            let path = expand_home(token).ok()?;
+            let path = match cwd {
+                Some(cwd) if path.is_relative() => cwd.join(path),
+                _ => path,
+            };
+            excluding_pattern(patterns, &path)
+        } else {
+            patterns
+                .iter()
+                .map(|pattern| pattern.trim())
+                .find(|pattern| {
+                    !pattern.is_empty() && !pattern.contains('/') && segment_matches(pattern, token)
+                })
+        }
+    })
+}
+
+fn filter_piece(
+    settings: &AppSettings,
+    cwd: Option<&Path>,
+    piece: ContextPiece,
+) -> FilteredContextPiece {
+    let patterns = &settings.ai_context_exclusions;
+    if let Some(source) = &piece.source {
+        if let Some(pattern) = excluding_pattern(patterns, Path::new(source)) {
+            return FilteredContextPiece {
+                kind: piece.kind,
+                text: String::new(),
+                excluded: true,
+                reason: Some(format!("{} is excluded by '{}'", source, pattern)),
+                redactions: 0,
+                omitted_lines: 0,
+            };
+        }
+    }
+
+    let mut omitted_lines = 0;
+    let mut reason = None;
+    let lines: Vec<&str> = piece
+        .text
+        .lines()
+        .map(|line| match line_mentions_excluded(patterns, cwd, line) {
+            Some(pattern) => {
+                omitted_lines += 1;
+                reason.get_or_insert_with(|| format!("mentions a path excluded by '{}'", pattern));
+                OMITTED_LINE
+            }
+            None => line,
+        })
+        .collect();
+    let text = if omitted_lines == 0 {
+        piece.text
+    } else {
+        lines.join("\n")
+    };
+    let (text, redactions) = if settings.ai_context_redaction {
+        redact_text_counted(&text)
+    } else {
+        (text, 0)
+    };
+    FilteredContextPiece {
+        kind: piece.kind,
+        text,
+        excluded: false,
+        reason,
+        redactions,
+        omitted_lines,
+    }
+}
+
+// Runs the pieces through exclusions and redaction when the settings call for it for this
+// provider; otherwise they pass unchanged
+pub fn filter_context(
+    settings: &AppSettings,
+    apply: bool,
+    cwd: Option<&str>,
+    pieces: Vec<ContextPiece>,
+) -> Vec<FilteredContextPiece> {
+    let cwd = cwd.map(Path::new);
+    pieces
+        .into_iter()
+        .map(|piece| {
+            if apply {
+                filter_piece(settings, cwd, piece)
+            } else {
+                FilteredContextPiece {
+                    kind: piece.kind,
+                    text: piece.text,
+                    excluded: false,
+                    reason: None,
+                    redactions: 0,
+                    omitted_lines: 0,
+                }
+            }
+        })
+        .collect()
+}
@@ -0,0 +1,74 @@
+use crate::command::types::command_manager::CommandManager;
+use crate::ollama::model_request::request::{prepare_request, send_request};
+use crate::ollama::types::chat_store::{ChatBranchSummary, ChatStore, ChatTurn, Conversation};
+use tauri::{command, AppHandle, Manager, Runtime, State};
+
+#[command]
+pub fn get_conversation(
+    session_id: String,
+    chat_store: State<'_, ChatStore>,
+) -> Result<Conversation, String> {
+    chat_store.conversation(&session_id)
+}
+
+// Asks the last question of the active branch again, with the same earlier turns, and keeps
+// the replaced answer in previousAnswers
+#[command]
+pub async fn regenerate_last_answer<R: Runtime>(
+    session_id: String,
+    model_override: Option<String>,
+    app_handle: AppHandle<R>,
+    command_manager: State<'_, CommandManager>,
+) -> Result<ChatTurn, String> {
+    let conversation = app_handle.state::<ChatStore>().conversation(&session_id)?;
+    let Some((last, earlier)) = conversation.turns.split_last() else {
+        return Err("There is no answer to regenerate; ask a question first".to_string());
+    };
+    let request = prepare_request(
+        &app_handle,
+        &command_manager,
+        model_override,
+        Some(&session_id),
+        &last.question,
+        earlier,
+        false,
+    )?;
+    let model = request.model.clone();
+    let answer = send_request(request, &last.question).await?;
+    app_handle.state::<ChatStore>().replace_last_answer(
+        &session_id,
+        &conversation.branch_id,
+        conversation.turns.len(),
+        &answer,
+        &model,
+    )
+}
+
+// Forks the active branch after its first `from_turn` turns (0 starts over) and makes the
+// fork active, so the discussion can take another direction without losing the original
+#[command]
+pub fn branch_conversation(
+    session_id: String,
+    from_turn: usize,
+    chat_store: State<'_, ChatStore>,
+) -> Result<ChatBranchSummary, String> {
+    chat_store.branch(&session_id, from_turn)
+}
+
+#[command]
+pub fn list_conversation_branches(
+    session_id: String,
+    chat_store: State<'_, ChatStore>,
+) -> Result<Vec<ChatBranchSummary>, String> {
+    chat_store.branches(&session_id)
+}
+
+// Makes another branch the one ask_ai continues
+#[command]
+pub fn switch_conversation_branch(
+    session_id: String,
+    branch_id: String,
+    chat_store: State<'_, ChatStore>,
+) -> Result<Conversation, String> {
+    chat_store.switch(&session_id, &branch_id)
+}
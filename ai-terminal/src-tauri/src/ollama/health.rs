@@ -0,0 +1,59 @@
+use crate::command::types::command_manager::CommandManager;
+use crate::ollama::types::ai_provider::AiProvider;
+use serde::Serialize;
+use std::sync::atomic::{AtomicU8, Ordering};
+use std::time::Duration;
+use tauri::{AppHandle, Emitter, Manager, Runtime};
+
+const HEALTH_UNKNOWN: u8 = 0;
+const HEALTH_REACHABLE: u8 = 1;
+const HEALTH_UNREACHABLE: u8 = 2;
+
+static LAST_HEALTH: AtomicU8 = AtomicU8::new(HEALTH_UNKNOWN);
+
+#[derive(Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+struct OllamaHealth {
+    host: String,
+    reachable: bool,
+}
+
+// Scheduler task: probes the Ollama API and emits ollama_health when reachability changes
+pub fn check_ollama_health<R: Runtime>(app_handle: &AppHandle<R>) -> Result<(), String> {
+    let api_host = {
+        let command_manager = app_handle.state::<CommandManager>();
+        let ollama_state = command_manager.ollama.lock().map_err(|e| e.to_string())?;
+        // Nothing to probe while the mock provider answers
+        if ollama_state.provider == AiProvider::Mock {
+            return Ok(());
+        }
+        ollama_state.api_host.clone()
+    };
+    let reachable = tauri::async_runtime::block_on(async {
+        reqwest::Client::new()
+            .get(format!("{}/api/tags", api_host))
+            .timeout(Duration::from_secs(5))
+            .send()
+            .await
+            .is_ok_and(|res| res.status().is_success())
+    });
+    let health = if reachable {
+        HEALTH_REACHABLE
+    } else {
+        HEALTH_UNREACHABLE
+    };
+    if LAST_HEALTH.swap(health, Ordering::Relaxed) != health {
+        let _ = app_handle.emit(
+            "ollama_health",
+            OllamaHealth {
+                host: api_host.clone(),
+                reachable,
+            },
+        );
+    }
+    if reachable {
+        Ok(())
+    } else {
+        Err(format!("Ollama is not reachable at {}", api_host))
+    }
+}
@@ -0,0 +1,77 @@
+use sha2::{Digest, Sha256};
+
+// Models the mock provider reports, with made-up sizes in bytes
+const MOCK_MODELS: &[(&str, u64)] = &[("mock-small", 1_000_000), ("mock-large", 8_000_000)];
+
+// Keyword → canned answer; the first keyword found in the question wins
+const CANNED_ANSWERS: &[(&[&str], &str)] = &[
+    (
+        &["list files", "show files", "directory contents"],
+        "To list the files in the current directory, including hidden ones:\n\n```bash\nls -la\n```",
+    ),
+    (
+        &["disk", "space", "storage"],
+        "To see free disk space per filesystem:\n\n```bash\ndf -h\n```\n\nFor the size of the current directory, use `du -sh .`.",
+    ),
+    (
+        &["untar", "extract", "tar"],
+        "To extract a gzipped tarball into the current directory:\n\n```bash\ntar -xzf archive.tar.gz\n```",
+    ),
+    (
+        &["port", "listening"],
+        "To see which process is listening on a port:\n\n```bash\nlsof -i :8080\n```",
+    ),
+    (
+        &["git", "branch", "commit"],
+        "To see the current branch and uncommitted changes:\n\n```bash\ngit status\n```",
+    ),
+    (
+        &["error", "fail", "wrong", "why"],
+        "The command failed. Check the first error line in the output. It usually names the missing file, package or permission. Run it again with verbose output to see more.",
+    ),
+];
+
+const DEFAULT_ANSWER: &str = "This is a canned answer from the mock AI provider. Switch back with /provider ollama to get real answers.";
+
+// Deterministic stand-in for /api/generate: a canned answer picked by keyword, followed by
+// a line describing the prompt so the UI can show what would have been sent
+pub fn mock_generate(model: &str, question: &str, prompt: &str) -> String {
+    let lowered = question.to_lowercase();
+    let answer = CANNED_ANSWERS
+        .iter()
+        .find(|(keywords, _)| keywords.iter().any(|keyword| lowered.contains(keyword)))
+        .map(|(_, answer)| *answer)
+        .unwrap_or(DEFAULT_ANSWER);
+    let digest: String = Sha256::digest(prompt.as_bytes())
+        .iter()
+        .take(4)
+        .map(|byte| format!("{:02x}", byte))
+        .collect();
+    format!(
+        "{}\n\n[mock provider · model {} · prompt {} chars, {} lines, {} context · sha256 {}]",
+        answer,
+        model,
+        prompt.chars().count(),
+        prompt.lines().count(),
+        if prompt.len() > question.len() {
+            "with"
+        } else {
+            "no"
+        },
+        digest
+    )
+}
+
+// Mock verdict for the command/question classifier: questions end with '?'
+pub fn mock_classify(input: &str) -> bool {
+    input.trim_end().ends_with('?')
+}
+
+// Same format get_models returns for Ollama
+pub fn mock_models_listing() -> String {
+    let mut result = String::from("Available models:\n");
+    for (name, size) in MOCK_MODELS {
+        result.push_str(&format!("- {} ({} bytes)\n", name, size));
+    }
+    result
+}
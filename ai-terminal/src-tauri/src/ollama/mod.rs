@@ -1,2 +1,7 @@
+pub mod context_budget;
+pub mod context_filter;
+pub mod conversation;
+pub mod health;
+pub mod mock_provider;
 pub mod model_request;
 pub mod types;
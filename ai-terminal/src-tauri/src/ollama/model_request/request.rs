@@ -1,32 +1,263 @@
+use crate::command::constants::AI_CONTEXT_HISTORY_COMMANDS;
+use crate::command::core::command_templates::expand_prompt_captures;
+use crate::command::core::output_capture::session_captures;
+use crate::command::core::session_variables::session_variables_context;
+use crate::command::environment::python_env::python_env_context;
+use crate::command::output::ai_selection::{peek_ai_selection_context, take_ai_selection_context};
+use crate::command::project::project_detection::{
+    find_project, project_context_summary, session_project_dir,
+};
+use crate::command::timeline::session_timeline::record_timeline_event;
 use crate::command::types::command_manager::CommandManager;
+use crate::command::types::pty_manager::PtyManager;
+use crate::command::types::scrollback_manager::ScrollbackManager;
+use crate::command::types::timeline_manager::TimelineEventKind;
+use crate::ollama::context_budget::{
+    assemble_prompt, fit_to_budget, session_context_omissions, BudgetedContextPiece, ContextBudget,
+};
+use crate::ollama::context_filter::{
+    filter_applies, filter_context, is_hosted, ContextPiece, ContextPieceKind,
+};
+use crate::ollama::mock_provider::{mock_generate, mock_models_listing};
+use crate::ollama::types::ai_provider::AiProvider;
+use crate::ollama::types::chat_store::{ChatStore, ChatTurn};
 use crate::ollama::types::ollama_model_list::OllamaModelList;
-use crate::ollama::types::ollama_request::OllamaRequest;
+use crate::ollama::types::ollama_request::{OllamaOptions, OllamaRequest};
 use crate::ollama::types::ollama_response::OllamaResponse;
+use crate::settings::types::settings_manager::SettingsManager;
+use crate::utils::ansi::render_plain_text;
 use crate::utils::command::handle_special_command;
-use tauri::{command, State};
+use serde::Serialize;
+use tauri::{command, AppHandle, Manager, Runtime, State};
+
+// The last command's output (as an error when it failed) and the commands before it
+fn run_context<R: Runtime>(
+    app_handle: &AppHandle<R>,
+    session_id: &str,
+) -> Vec<(ContextPieceKind, String)> {
+    let runs = app_handle
+        .state::<ScrollbackManager>()
+        .runs(session_id)
+        .unwrap_or_default();
+    let Some((last, earlier)) = runs.split_last() else {
+        return Vec::new();
+    };
+    let mut pieces = Vec::new();
+    let skip = earlier.len().saturating_sub(AI_CONTEXT_HISTORY_COMMANDS);
+    let history: Vec<String> = earlier[skip..]
+        .iter()
+        .map(|run| match run.exit_code {
+            Some(code) => format!("$ {}  (exit {})", run.command, code),
+            None => format!("$ {}", run.command),
+        })
+        .collect();
+    if !history.is_empty() {
+        pieces.push((
+            ContextPieceKind::History,
+            format!(
+                "Commands run earlier in this session:\n{}",
+                history.join("\n")
+            ),
+        ));
+    }
+    let output = render_plain_text(&last.output);
+    let output = output.trim_end();
+    match last.exit_code {
+        Some(code) if last.finished && code != 0 => pieces.push((
+            ContextPieceKind::Error,
+            format!(
+                "The last command `{}` failed with exit code {}. Its output:\n{}",
+                last.command, code, output
+            ),
+        )),
+        _ if !output.trim().is_empty() => pieces.push((
+            ContextPieceKind::RecentOutput,
+            format!(
+                "Output of the last command `{}`{}:\n{}",
+                last.command,
+                if last.finished {
+                    ""
+                } else {
+                    " (still running)"
+                },
+                output
+            ),
+        )),
+        _ => {}
+    }
+    pieces
+}
+
+// Ground the answer in where the session is running: a container, or a local project. The
+// selection is only consumed for a real request, not a preview.
+fn gather_context_pieces<R: Runtime>(
+    app_handle: &AppHandle<R>,
+    command_manager: &CommandManager,
+    session_id: Option<&str>,
+    question: &str,
+    conversation: &[ChatTurn],
+    consume_selection: bool,
+) -> (Option<String>, Vec<ContextPiece>) {
+    let question = ContextPiece {
+        kind: ContextPieceKind::Question,
+        source: None,
+        text: question.to_string(),
+    };
+    let Some(session_id) = session_id else {
+        return (None, vec![question]);
+    };
+    // None in SSH sessions, whose files are not on this machine
+    let cwd = session_project_dir(command_manager, session_id)
+        .ok()
+        .map(|cwd| cwd.to_string_lossy().to_string());
+    let piece = |kind: ContextPieceKind, text: String| ContextPiece {
+        kind,
+        source: cwd.clone(),
+        text,
+    };
+
+    let mut pieces = Vec::new();
+    let target = app_handle
+        .state::<PtyManager>()
+        .sessions
+        .lock()
+        .ok()
+        .and_then(|sessions| sessions.get(session_id).and_then(|s| s.target.clone()));
+    match target {
+        Some(target) => pieces.push(ContextPiece {
+            kind: ContextPieceKind::Target,
+            source: None,
+            text: target.ai_context(),
+        }),
+        None => {
+            let project = cwd
+                .as_deref()
+                .and_then(|cwd| find_project(std::path::Path::new(cwd)))
+                .map(|project| project_context_summary(&project));
+            pieces.extend(project.map(|text| piece(ContextPieceKind::Project, text)));
+            pieces.extend(
+                python_env_context(command_manager, session_id)
+                    .map(|text| piece(ContextPieceKind::PythonEnv, text)),
+            );
+        }
+    }
+    pieces.extend(
+        session_variables_context(command_manager, session_id)
+            .map(|text| piece(ContextPieceKind::Variables, text)),
+    );
+    pieces.extend(
+        run_context(app_handle, session_id)
+            .into_iter()
+            .map(|(kind, text)| piece(kind, text)),
+    );
+    // Output the user selected for this question is quoted once, then cleared
+    let selection = if consume_selection {
+        take_ai_selection_context(command_manager, session_id)
+    } else {
+        peek_ai_selection_context(command_manager, session_id)
+    };
+    pieces.extend(selection.map(|text| piece(ContextPieceKind::Selection, text)));
+    if !conversation.is_empty() {
+        let turns: Vec<String> = conversation
+            .iter()
+            .map(|turn| format!("User: {}\nAssistant: {}", turn.question, turn.answer))
+            .collect();
+        pieces.push(ContextPiece {
+            kind: ContextPieceKind::Conversation,
+            source: None,
+            text: format!("Earlier in this conversation:\n{}", turns.join("\n")),
+        });
+    }
+    pieces.push(question);
+    (cwd, pieces)
+}
 
 #[command]
-pub async fn ask_ai(
+pub async fn ask_ai<R: Runtime>(
     question: String,
     model_override: Option<String>,
+    session_id: Option<String>,
+    app_handle: AppHandle<R>,
     command_manager: State<'_, CommandManager>,
 ) -> Result<String, String> {
     // Check if this is a special command
     if question.starts_with('/') {
-        return handle_special_command(question, command_manager).await;
+        return handle_special_command(question, session_id, &app_handle, command_manager).await;
     }
 
-    // Regular message to Ollama
-    let model;
-    let api_host;
+    // {{capture:name}} in the question becomes the output stored by capture_output
+    let question = expand_question_captures(&command_manager, session_id.as_deref(), question);
+    let conversation = session_conversation(&app_handle, session_id.as_deref());
+    let request = prepare_request(
+        &app_handle,
+        &command_manager,
+        model_override,
+        session_id.as_deref(),
+        &question,
+        &conversation,
+        true,
+    )?;
+    let model = request.model.clone();
 
-    // Scope the mutex lock to drop it before any async operations
-    {
-        let ollama_state = command_manager.ollama.lock().map_err(|e| e.to_string())?;
-        // Use the model_override if provided, otherwise use the default
-        model = model_override.unwrap_or_else(|| ollama_state.current_model.clone());
-        api_host = ollama_state.api_host.clone();
-        // MutexGuard is dropped here at the end of scope
+    if let Some(session_id) = &session_id {
+        record_timeline_event(
+            &app_handle,
+            session_id,
+            TimelineEventKind::AiInteraction,
+            &question,
+            serde_json::json!({ "model": model, "question": question }),
+        );
+    }
+
+    let answer = send_request(request, &question).await?;
+    if let Some(session_id) = &session_id {
+        app_handle
+            .state::<ChatStore>()
+            .record(session_id, &question, &answer, &model)?;
+    }
+    Ok(answer)
+}
+
+// Turns of the session's active chat branch, which ask_ai continues
+pub fn session_conversation<R: Runtime>(
+    app_handle: &AppHandle<R>,
+    session_id: Option<&str>,
+) -> Vec<ChatTurn> {
+    session_id
+        .and_then(|session_id| {
+            app_handle
+                .state::<ChatStore>()
+                .conversation(session_id)
+                .ok()
+        })
+        .map(|conversation| conversation.turns)
+        .unwrap_or_default()
+}
+
+// Sends a prepared prompt to the provider and returns the answer
+pub async fn send_request(request: AiRequestPreview, question: &str) -> Result<String, String> {
+    generate(
+        request.provider,
+        &request.api_host,
+        request.model,
+        question,
+        request.prompt,
+        request.budget.context_window,
+    )
+    .await
+}
+
+// One non-streaming /api/generate call with the given context window
+pub async fn generate(
+    provider: AiProvider,
+    api_host: &str,
+    model: String,
+    question: &str,
+    prompt: String,
+    num_ctx: usize,
+) -> Result<String, String> {
+    if provider == AiProvider::Mock {
+        return Ok(mock_generate(&model, question, &prompt));
     }
 
     let client = reqwest::Client::new();
@@ -34,8 +265,9 @@ pub async fn ask_ai(
         .post(format!("{}/api/generate", api_host))
         .json(&OllamaRequest {
             model,
-            prompt: question,
+            prompt,
             stream: false,
+            options: Some(OllamaOptions { num_ctx }),
         })
         .send()
         .await
@@ -53,6 +285,134 @@ pub async fn ask_ai(
     Ok(response.response)
 }
 
+#[derive(Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct AiRequestPreview {
+    pub provider: AiProvider,
+    pub api_host: String,
+    pub model: String,
+    // The provider is not on this machine
+    pub hosted: bool,
+    // Exclusions and redaction were applied (see the ai_context_filter setting)
+    pub filtered: bool,
+    pub budget: ContextBudget,
+    pub pieces: Vec<BudgetedContextPiece>,
+    // Exactly the prompt ask_ai would send
+    pub prompt: String,
+}
+
+pub fn expand_question_captures(
+    command_manager: &CommandManager,
+    session_id: Option<&str>,
+    question: String,
+) -> String {
+    match session_id {
+        Some(session_id) => {
+            expand_prompt_captures(&question, &session_captures(command_manager, session_id))
+        }
+        None => question,
+    }
+}
+
+// Gathers the context for a question, runs it through the content filter and fits it into
+// the model's context window
+pub fn prepare_request<R: Runtime>(
+    app_handle: &AppHandle<R>,
+    command_manager: &CommandManager,
+    model_override: Option<String>,
+    session_id: Option<&str>,
+    question: &str,
+    conversation: &[ChatTurn],
+    consume_selection: bool,
+) -> Result<AiRequestPreview, String> {
+    // Scope the mutex lock to drop it before any async operations
+    let (model, api_host, provider) = {
+        let ollama_state = command_manager.ollama.lock().map_err(|e| e.to_string())?;
+        // Use the model_override if provided, otherwise use the default
+        (
+            model_override.unwrap_or_else(|| ollama_state.current_model.clone()),
+            ollama_state.api_host.clone(),
+            ollama_state.provider,
+        )
+    };
+    let (cwd, pieces) = gather_context_pieces(
+        app_handle,
+        command_manager,
+        session_id,
+        question,
+        conversation,
+        consume_selection,
+    );
+    let settings = app_handle.state::<SettingsManager>().current();
+    let filtered = filter_applies(&settings, provider, &api_host);
+    let pieces = filter_context(&settings, filtered, cwd.as_deref(), pieces);
+    let omitted = session_id
+        .map(|session_id| session_context_omissions(command_manager, session_id))
+        .unwrap_or_default();
+    let (pieces, budget) = fit_to_budget(pieces, &omitted, settings.ai_context_window_tokens);
+    Ok(AiRequestPreview {
+        provider,
+        hosted: is_hosted(provider, &api_host),
+        api_host,
+        model,
+        filtered,
+        budget,
+        prompt: assemble_prompt(&pieces),
+        pieces,
+    })
+}
+
+// Dry run of ask_ai: the context it would gather for the session, what the content filter
+// drops or redacts from it, and the resulting prompt. Nothing is sent and the selection stays.
+#[command]
+pub fn preview_ai_request<R: Runtime>(
+    question: String,
+    model_override: Option<String>,
+    session_id: Option<String>,
+    app_handle: AppHandle<R>,
+    command_manager: State<'_, CommandManager>,
+) -> Result<AiRequestPreview, String> {
+    if question.starts_with('/') {
+        return Err("Special commands are handled locally; nothing is sent to a model".to_string());
+    }
+    let question = expand_question_captures(&command_manager, session_id.as_deref(), question);
+    prepare_request(
+        &app_handle,
+        &command_manager,
+        model_override,
+        session_id.as_deref(),
+        &question,
+        &session_conversation(&app_handle, session_id.as_deref()),
+        false,
+    )
+}
+
+// The session context the model would receive with the next question, with each piece's
+// token estimate and what was truncated or dropped to fit the context window. Pieces can be
+// left out with set_ai_context_omissions.
+#[command]
+pub fn preview_ai_context<R: Runtime>(
+    session_id: String,
+    question: Option<String>,
+    app_handle: AppHandle<R>,
+    command_manager: State<'_, CommandManager>,
+) -> Result<AiRequestPreview, String> {
+    let question = expand_question_captures(
+        &command_manager,
+        Some(&session_id),
+        question.unwrap_or_default(),
+    );
+    prepare_request(
+        &app_handle,
+        &command_manager,
+        None,
+        Some(&session_id),
+        &question,
+        &session_conversation(&app_handle, Some(&session_id)),
+        false,
+    )
+}
+
 // Add function to get models from Ollama API
 #[command]
 pub async fn get_models(command_manager: State<'_, CommandManager>) -> Result<String, String> {
@@ -60,6 +420,9 @@ pub async fn get_models(command_manager: State<'_, CommandManager>) -> Result<St
     let api_host;
     {
         let ollama_state = command_manager.ollama.lock().map_err(|e| e.to_string())?;
+        if ollama_state.provider == AiProvider::Mock {
+            return Ok(mock_models_listing());
+        }
         api_host = ollama_state.api_host.clone();
     }
 
@@ -120,3 +483,21 @@ pub fn set_host(
     ollama_state.api_host = host.clone();
     Ok(format!("Changed Ollama API host to: {}", host))
 }
+
+// Switches the backend for AI requests: "ollama", or "mock" for offline UI work and demos
+#[command]
+pub fn set_provider(
+    provider: String,
+    command_manager: State<'_, CommandManager>,
+) -> Result<String, String> {
+    let provider = AiProvider::parse(&provider)?;
+    let mut ollama_state = command_manager.ollama.lock().map_err(|e| e.to_string())?;
+    ollama_state.provider = provider;
+    Ok(format!("Switched AI provider to: {}", provider.as_str()))
+}
+
+#[command]
+pub fn get_provider(command_manager: State<'_, CommandManager>) -> Result<AiProvider, String> {
+    let ollama_state = command_manager.ollama.lock().map_err(|e| e.to_string())?;
+    Ok(ollama_state.provider)
+}
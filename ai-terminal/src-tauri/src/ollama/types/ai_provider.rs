@@ -0,0 +1,30 @@
+use serde::{Deserialize, Serialize};
+
+// Backend that answers ask_ai and the other model requests
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum AiProvider {
+    Ollama,
+    // Deterministic canned answers for UI development, tests and demos without Ollama
+    Mock,
+}
+
+impl AiProvider {
+    pub fn parse(name: &str) -> Result<Self, String> {
+        match name.trim().to_lowercase().as_str() {
+            "ollama" => Ok(AiProvider::Ollama),
+            "mock" => Ok(AiProvider::Mock),
+            other => Err(format!(
+                "Unknown AI provider '{}'. Available providers: ollama, mock",
+                other
+            )),
+        }
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            AiProvider::Ollama => "ollama",
+            AiProvider::Mock => "mock",
+        }
+    }
+}
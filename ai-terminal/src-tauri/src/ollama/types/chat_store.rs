@@ -0,0 +1,222 @@
+use crate::utils::config_utils::unix_timestamp_millis;
+use serde::Serialize;
+use std::collections::HashMap;
+use std::sync::Mutex;
+
+#[derive(Serialize, Clone, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct ChatTurn {
+    pub question: String,
+    pub answer: String,
+    pub model: String,
+    pub asked_at: u64,
+    // Answers replaced by regenerate_last_answer, oldest first
+    pub previous_answers: Vec<String>,
+}
+
+// One line of discussion. A fork copies the turns it shares with its parent, so branches
+// never change under each other.
+#[derive(Clone, Debug)]
+pub struct ChatBranch {
+    pub id: String,
+    pub parent_id: Option<String>,
+    // Number of turns taken over from the parent
+    pub forked_at: usize,
+    pub created_at: u64,
+    pub turns: Vec<ChatTurn>,
+}
+
+#[derive(Serialize, Clone, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct ChatBranchSummary {
+    pub id: String,
+    pub parent_id: Option<String>,
+    pub forked_at: usize,
+    pub created_at: u64,
+    pub turn_count: usize,
+    pub last_question: Option<String>,
+    pub active: bool,
+}
+
+#[derive(Serialize, Clone, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct Conversation {
+    pub session_id: String,
+    pub branch_id: String,
+    pub turns: Vec<ChatTurn>,
+}
+
+#[derive(Default)]
+struct SessionChat {
+    branches: Vec<ChatBranch>,
+    active: usize,
+}
+
+impl SessionChat {
+    fn active_branch(&mut self) -> &mut ChatBranch {
+        if self.branches.is_empty() {
+            self.branches.push(new_branch(None, Vec::new()));
+            self.active = 0;
+        }
+        &mut self.branches[self.active]
+    }
+}
+
+fn new_branch(parent_id: Option<String>, turns: Vec<ChatTurn>) -> ChatBranch {
+    ChatBranch {
+        id: uuid::Uuid::new_v4().to_string(),
+        parent_id,
+        forked_at: turns.len(),
+        created_at: unix_timestamp_millis(),
+        turns,
+    }
+}
+
+// ask_ai questions and answers per session, with branches for retried and forked discussions
+pub struct ChatStore {
+    sessions: Mutex<HashMap<String, SessionChat>>,
+}
+
+impl ChatStore {
+    pub fn new() -> Self {
+        Self {
+            sessions: Mutex::new(HashMap::new()),
+        }
+    }
+
+    pub fn record(
+        &self,
+        session_id: &str,
+        question: &str,
+        answer: &str,
+        model: &str,
+    ) -> Result<(), String> {
+        let mut sessions = self.sessions.lock().map_err(|e| e.to_string())?;
+        let branch = sessions
+            .entry(session_id.to_string())
+            .or_default()
+            .active_branch();
+        branch.turns.push(ChatTurn {
+            question: question.to_string(),
+            answer: answer.to_string(),
+            model: model.to_string(),
+            asked_at: unix_timestamp_millis(),
+            previous_answers: Vec::new(),
+        });
+        Ok(())
+    }
+
+    pub fn conversation(&self, session_id: &str) -> Result<Conversation, String> {
+        let mut sessions = self.sessions.lock().map_err(|e| e.to_string())?;
+        let branch = sessions
+            .entry(session_id.to_string())
+            .or_default()
+            .active_branch();
+        Ok(Conversation {
+            session_id: session_id.to_string(),
+            branch_id: branch.id.clone(),
+            turns: branch.turns.clone(),
+        })
+    }
+
+    // Swaps in a new answer for the last turn of `branch_id`, keeping the old one. Fails if
+    // the branch moved on while the answer was generated.
+    pub fn replace_last_answer(
+        &self,
+        session_id: &str,
+        branch_id: &str,
+        turn_count: usize,
+        answer: &str,
+        model: &str,
+    ) -> Result<ChatTurn, String> {
+        let mut sessions = self.sessions.lock().map_err(|e| e.to_string())?;
+        let branch = sessions
+            .get_mut(session_id)
+            .and_then(|chat| {
+                chat.branches
+                    .iter_mut()
+                    .find(|branch| branch.id == branch_id)
+            })
+            .filter(|branch| branch.turns.len() == turn_count)
+            .ok_or("The conversation changed while the answer was being regenerated")?;
+        let turn = branch
+            .turns
+            .last_mut()
+            .ok_or("There is no answer to regenerate")?;
+        let previous = std::mem::replace(&mut turn.answer, answer.to_string());
+        turn.previous_answers.push(previous);
+        turn.model = model.to_string();
+        turn.asked_at = unix_timestamp_millis();
+        Ok(turn.clone())
+    }
+
+    // Starts a new active branch holding the active branch's first `from_turn` turns
+    pub fn branch(&self, session_id: &str, from_turn: usize) -> Result<ChatBranchSummary, String> {
+        let mut sessions = self.sessions.lock().map_err(|e| e.to_string())?;
+        let chat = sessions.entry(session_id.to_string()).or_default();
+        let parent = chat.active_branch();
+        if from_turn > parent.turns.len() {
+            return Err(format!(
+                "Turn {} is out of range; the conversation has {} turns",
+                from_turn,
+                parent.turns.len()
+            ));
+        }
+        let branch = new_branch(Some(parent.id.clone()), parent.turns[..from_turn].to_vec());
+        chat.branches.push(branch);
+        chat.active = chat.branches.len() - 1;
+        Ok(summarize(&chat.branches[chat.active], true))
+    }
+
+    pub fn switch(&self, session_id: &str, branch_id: &str) -> Result<Conversation, String> {
+        let mut sessions = self.sessions.lock().map_err(|e| e.to_string())?;
+        let chat = sessions
+            .get_mut(session_id)
+            .ok_or_else(|| format!("No conversation in session '{}'", session_id))?;
+        let index = chat
+            .branches
+            .iter()
+            .position(|branch| branch.id == branch_id)
+            .ok_or_else(|| format!("No conversation branch '{}'", branch_id))?;
+        chat.active = index;
+        let branch = &chat.branches[index];
+        Ok(Conversation {
+            session_id: session_id.to_string(),
+            branch_id: branch.id.clone(),
+            turns: branch.turns.clone(),
+        })
+    }
+
+    // Oldest first
+    pub fn branches(&self, session_id: &str) -> Result<Vec<ChatBranchSummary>, String> {
+        let sessions = self.sessions.lock().map_err(|e| e.to_string())?;
+        Ok(sessions
+            .get(session_id)
+            .map(|chat| {
+                chat.branches
+                    .iter()
+                    .enumerate()
+                    .map(|(index, branch)| summarize(branch, index == chat.active))
+                    .collect()
+            })
+            .unwrap_or_default())
+    }
+}
+
+fn summarize(branch: &ChatBranch, active: bool) -> ChatBranchSummary {
+    ChatBranchSummary {
+        id: branch.id.clone(),
+        parent_id: branch.parent_id.clone(),
+        forked_at: branch.forked_at,
+        created_at: branch.created_at,
+        turn_count: branch.turns.len(),
+        last_question: branch.turns.last().map(|turn| turn.question.clone()),
+        active,
+    }
+}
+
+impl Default for ChatStore {
+    fn default() -> Self {
+        Self::new()
+    }
+}
@@ -1,3 +1,5 @@
+pub mod ai_provider;
+pub mod chat_store;
 pub mod ollama_model;
 pub mod ollama_model_list;
 pub mod ollama_request;
@@ -5,4 +5,12 @@ pub struct OllamaRequest {
     pub model: String,
     pub prompt: String,
     pub stream: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub options: Option<OllamaOptions>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct OllamaOptions {
+    // Context window in tokens; Ollama silently drops the start of longer prompts
+    pub num_ctx: usize,
 }
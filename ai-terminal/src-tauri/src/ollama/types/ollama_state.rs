@@ -1,4 +1,7 @@
+use crate::ollama::types::ai_provider::AiProvider;
+
 pub struct OllamaState {
     pub current_model: String,
     pub api_host: String,
+    pub provider: AiProvider,
 }
@@ -0,0 +1,150 @@
+use crate::command::types::command_manager::CommandManager;
+use crate::command::types::pty_manager::PtyManager;
+use crate::command::types::scrollback_manager::ScrollbackManager;
+use crate::command::types::window_router::WindowRouter;
+use crate::privacy::types::idle_lock_manager::IdleLockManager;
+use crate::settings::types::settings_manager::SettingsManager;
+use crate::utils::os_auth::authenticate_user;
+use serde::Serialize;
+use std::collections::HashSet;
+use tauri::{command, AppHandle, Emitter, Manager, Runtime, State, Window};
+
+#[derive(Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+struct LockEvent {
+    window: String,
+    // Why the window was locked: "idle" or "manual"
+    reason: &'static str,
+}
+
+// Sessions shown in `window`. Sessions never attached to a window are the main window's.
+fn window_sessions<R: Runtime>(app_handle: &AppHandle<R>, window: &str) -> HashSet<String> {
+    let owners = app_handle
+        .state::<WindowRouter>()
+        .owners
+        .lock()
+        .map(|owners| owners.clone())
+        .unwrap_or_default();
+    let mut sessions: HashSet<String> = owners
+        .iter()
+        .filter(|(_, owner)| owner.as_str() == window)
+        .map(|(session_id, _)| session_id.clone())
+        .collect();
+    if window == "main" {
+        let mut known: Vec<String> = Vec::new();
+        if let Ok(states) = app_handle.state::<CommandManager>().commands.lock() {
+            known.extend(states.keys().cloned());
+        }
+        if let Ok(ptys) = app_handle.state::<PtyManager>().sessions.lock() {
+            known.extend(ptys.keys().cloned());
+        }
+        if let Ok(scrollbacks) = app_handle.state::<ScrollbackManager>().sessions.lock() {
+            known.extend(scrollbacks.keys().cloned());
+        }
+        sessions.extend(
+            known
+                .into_iter()
+                .filter(|session_id| !owners.contains_key(session_id)),
+        );
+    }
+    sessions
+}
+
+fn apply_lock<R: Runtime>(app_handle: &AppHandle<R>, window: &str, reason: &'static str) {
+    // Output keeps streaming to the (blurred) window but is not retained while locked; sessions
+    // in other windows are unaffected
+    app_handle
+        .state::<ScrollbackManager>()
+        .pause_retention(window, window_sessions(app_handle, window));
+    let _ = app_handle.emit_to(
+        window,
+        "session_lock",
+        LockEvent {
+            window: window.to_string(),
+            reason,
+        },
+    );
+}
+
+// Scheduler task: locks windows once they have been idle for the configured time
+pub fn lock_idle_windows<R: Runtime>(app_handle: &AppHandle<R>) -> Result<(), String> {
+    let minutes = app_handle
+        .state::<SettingsManager>()
+        .current()
+        .idle_lock_minutes;
+    if minutes == 0 {
+        return Ok(());
+    }
+    let idle = app_handle
+        .state::<IdleLockManager>()
+        .lock_idle(u64::from(minutes) * 60 * 1000);
+    for window in idle {
+        apply_lock(app_handle, &window, "idle");
+    }
+    Ok(())
+}
+
+// Called by the frontend on input (throttled). Returns whether the window is locked.
+#[command]
+pub fn report_activity<R: Runtime>(
+    window: Window<R>,
+    idle_lock_manager: State<'_, IdleLockManager>,
+) -> Result<bool, String> {
+    idle_lock_manager.touch(window.label())
+}
+
+#[command]
+pub fn lock_window<R: Runtime>(
+    window: Window<R>,
+    app_handle: AppHandle<R>,
+    idle_lock_manager: State<'_, IdleLockManager>,
+) -> Result<(), String> {
+    if idle_lock_manager.set_locked(window.label(), true)? {
+        apply_lock(&app_handle, window.label(), "manual");
+    }
+    Ok(())
+}
+
+// Unlocks the calling window, first showing the OS authentication prompt when the settings
+// require it. Returns false if authentication was cancelled or failed.
+#[command]
+pub async fn unlock_window<R: Runtime>(
+    window: Window<R>,
+    app_handle: AppHandle<R>,
+) -> Result<bool, String> {
+    let label = window.label().to_string();
+    if !app_handle.state::<IdleLockManager>().is_locked(&label) {
+        return Ok(true);
+    }
+    if app_handle
+        .state::<SettingsManager>()
+        .current()
+        .idle_lock_requires_os_auth
+    {
+        let authenticated = tauri::async_runtime::spawn_blocking(|| {
+            authenticate_user("AI Terminal is locked. Authenticate to unlock it.")
+        })
+        .await
+        .map_err(|e| e.to_string())??;
+        if !authenticated {
+            return Ok(false);
+        }
+    }
+
+    app_handle
+        .state::<IdleLockManager>()
+        .set_locked(&label, false)?;
+    app_handle
+        .state::<ScrollbackManager>()
+        .resume_retention(&label);
+    let _ = app_handle.emit_to(label.as_str(), "session_unlock", label.clone());
+    Ok(true)
+}
+
+#[command]
+pub fn is_window_locked<R: Runtime>(
+    window: Window<R>,
+    idle_lock_manager: State<'_, IdleLockManager>,
+) -> bool {
+    idle_lock_manager.is_locked(window.label())
+}
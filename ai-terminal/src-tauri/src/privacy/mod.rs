@@ -0,0 +1,2 @@
+pub mod idle_lock;
+pub mod types;
@@ -0,0 +1,78 @@
+use crate::utils::config_utils::unix_timestamp_millis;
+use std::collections::HashMap;
+use std::sync::Mutex;
+
+struct WindowActivity {
+    last_activity: u64,
+    locked: bool,
+}
+
+// Last input time and lock state per window, keyed by window label
+pub struct IdleLockManager {
+    windows: Mutex<HashMap<String, WindowActivity>>,
+}
+
+impl IdleLockManager {
+    pub fn new() -> Self {
+        Self {
+            windows: Mutex::new(HashMap::new()),
+        }
+    }
+
+    // Records input in the window; ignored while it is locked so only unlocking resumes it
+    pub fn touch(&self, window: &str) -> Result<bool, String> {
+        let mut windows = self.windows.lock().map_err(|e| e.to_string())?;
+        let activity = windows.entry(window.to_string()).or_insert(WindowActivity {
+            last_activity: 0,
+            locked: false,
+        });
+        if !activity.locked {
+            activity.last_activity = unix_timestamp_millis();
+        }
+        Ok(activity.locked)
+    }
+
+    // Returns false if the window was already locked
+    pub fn set_locked(&self, window: &str, locked: bool) -> Result<bool, String> {
+        let mut windows = self.windows.lock().map_err(|e| e.to_string())?;
+        let activity = windows.entry(window.to_string()).or_insert(WindowActivity {
+            last_activity: 0,
+            locked: false,
+        });
+        let changed = activity.locked != locked;
+        activity.locked = locked;
+        activity.last_activity = unix_timestamp_millis();
+        Ok(changed)
+    }
+
+    pub fn is_locked(&self, window: &str) -> bool {
+        self.windows
+            .lock()
+            .map(|windows| windows.get(window).is_some_and(|activity| activity.locked))
+            .unwrap_or(false)
+    }
+
+    // Locks windows idle for at least `timeout_ms` and returns their labels
+    pub fn lock_idle(&self, timeout_ms: u64) -> Vec<String> {
+        let Ok(mut windows) = self.windows.lock() else {
+            return Vec::new();
+        };
+        let now = unix_timestamp_millis();
+        windows
+            .iter_mut()
+            .filter(|(_, activity)| {
+                !activity.locked && now.saturating_sub(activity.last_activity) >= timeout_ms
+            })
+            .map(|(label, activity)| {
+                activity.locked = true;
+                label.clone()
+            })
+            .collect()
+    }
+}
+
+impl Default for IdleLockManager {
+    fn default() -> Self {
+        Self::new()
+    }
+}
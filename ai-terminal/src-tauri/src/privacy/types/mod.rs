@@ -0,0 +1 @@
+pub mod idle_lock_manager;
@@ -0,0 +1,264 @@
+use crate::command::constants::AGENT_CHANGE_HASH_MAX_BYTES;
+use crate::command::core::command_output::emit_command_error;
+use crate::command::git_commands::git::new_git_command;
+use crate::receipts::agent_undo::{discard_run_backup, save_agent_run};
+use crate::receipts::named_paths::diff_named_paths;
+use crate::receipts::types::agent_changes::{
+    AgentChangeKind, AgentChangeStore, AgentChanges, GitSnapshot, NamedPathsSnapshot,
+    ObservedChange, SnapshotEntry,
+};
+use crate::utils::config_utils::unix_timestamp_millis;
+use sha2::{Digest, Sha256};
+use std::collections::BTreeSet;
+use std::fs;
+use std::path::Path;
+use tauri::{command, AppHandle, Emitter, Manager, Runtime, State};
+
+fn repository_root(dir: &str) -> Option<String> {
+    let output = new_git_command()
+        .args(["rev-parse", "--show-toplevel"])
+        .current_dir(dir)
+        .output()
+        .ok()?;
+    output
+        .status
+        .success()
+        .then(|| String::from_utf8_lossy(&output.stdout).trim().to_string())
+        .filter(|root| !root.is_empty())
+}
+
+// Content hash, or size and mtime for files too large to read on every agent command
+pub fn fingerprint(path: &Path) -> Option<String> {
+    let metadata = fs::symlink_metadata(path).ok()?;
+    if metadata.is_dir() {
+        return Some("directory".to_string());
+    }
+    if metadata.len() > AGENT_CHANGE_HASH_MAX_BYTES {
+        let modified = metadata
+            .modified()
+            .ok()
+            .and_then(|time| time.duration_since(std::time::UNIX_EPOCH).ok())
+            .map(|since| since.as_nanos())
+            .unwrap_or_default();
+        return Some(format!("{}:{}", metadata.len(), modified));
+    }
+    let bytes = fs::read(path).ok()?;
+    Some(
+        Sha256::digest(&bytes)
+            .iter()
+            .map(|byte| format!("{:02x}", byte))
+            .collect(),
+    )
+}
+
+// `git status --porcelain -z` entries: `XY path`, with renames followed by their old path
+fn porcelain_entries(output: &[u8]) -> Vec<(String, String)> {
+    let text = String::from_utf8_lossy(output);
+    let mut fields = text.split('\0').filter(|field| !field.is_empty());
+    let mut entries = Vec::new();
+    while let Some(field) = fields.next() {
+        if field.len() < 4 {
+            continue;
+        }
+        let (status, path) = (field[..2].to_string(), field[3..].to_string());
+        if status.contains('R') || status.contains('C') {
+            // The source of a rename shows up as deleted in its own right
+            if let Some(old_path) = fields.next() {
+                if status.contains('R') {
+                    entries.push(("D ".to_string(), old_path.to_string()));
+                }
+            }
+            entries.push(("A ".to_string(), path));
+        } else {
+            entries.push((status, path));
+        }
+    }
+    entries
+}
+
+fn snapshot_repository(root: &str) -> Option<GitSnapshot> {
+    let output = new_git_command()
+        .args(["status", "--porcelain=v1", "-z", "--untracked-files=all"])
+        .current_dir(root)
+        .output()
+        .ok()?;
+    if !output.status.success() {
+        return None;
+    }
+    let entries = porcelain_entries(&output.stdout)
+        .into_iter()
+        .map(|(status, path)| {
+            let fingerprint = fingerprint(&Path::new(root).join(&path));
+            (
+                path,
+                SnapshotEntry {
+                    status,
+                    fingerprint,
+                },
+            )
+        })
+        .collect();
+    let head = new_git_command()
+        .args(["rev-parse", "--verify", "--quiet", "HEAD"])
+        .current_dir(root)
+        .output()
+        .ok()
+        .filter(|output| output.status.success())
+        .map(|output| String::from_utf8_lossy(&output.stdout).trim().to_string());
+    Some(GitSnapshot {
+        root: root.to_string(),
+        head,
+        entries,
+    })
+}
+
+// Snapshot of the repository containing `cwd`; None outside a git work tree
+pub fn take_git_snapshot(cwd: &str) -> Option<GitSnapshot> {
+    snapshot_repository(&repository_root(cwd)?)
+}
+
+fn not_in_head(status: &str) -> bool {
+    status == "??" || status.starts_with('A')
+}
+
+fn is_deleted(status: &str) -> bool {
+    status.contains('D')
+}
+
+// Paths whose state differs between the two snapshots. A path missing from one snapshot was
+// clean then, i.e. as in HEAD: present if tracked, absent if not.
+fn diff_snapshots(before: &GitSnapshot, after: &GitSnapshot) -> Vec<ObservedChange> {
+    let paths: BTreeSet<&String> = before.entries.keys().chain(after.entries.keys()).collect();
+    paths
+        .into_iter()
+        .filter_map(|path| {
+            let old = before.entries.get(path);
+            let new = after.entries.get(path);
+            let (kind, tracked) = match (old, new) {
+                (None, Some(new)) if not_in_head(&new.status) => (AgentChangeKind::Created, false),
+                (None, Some(new)) if is_deleted(&new.status) => (AgentChangeKind::Deleted, true),
+                (None, Some(_)) => (AgentChangeKind::Modified, true),
+                (Some(old), None) if not_in_head(&old.status) => (AgentChangeKind::Deleted, false),
+                (Some(old), None) if is_deleted(&old.status) => (AgentChangeKind::Created, true),
+                (Some(_), None) => (AgentChangeKind::Modified, true),
+                (Some(old), Some(new)) => {
+                    let tracked = !not_in_head(&new.status);
+                    match (&old.fingerprint, &new.fingerprint) {
+                        (None, Some(_)) => (AgentChangeKind::Created, tracked),
+                        (Some(_), None) => (AgentChangeKind::Deleted, tracked),
+                        (Some(old), Some(new)) if old != new => {
+                            (AgentChangeKind::Modified, tracked)
+                        }
+                        _ => return None,
+                    }
+                }
+                (None, None) => return None,
+            };
+            Some(ObservedChange {
+                path: Path::new(&after.root)
+                    .join(path)
+                    .to_string_lossy()
+                    .to_string(),
+                repository: after.root.clone(),
+                kind,
+                tracked,
+            })
+        })
+        .collect()
+}
+
+// Compares the repository, and the paths the command named, with the snapshots taken before
+// an agent command and adds what changed to the session's list, announcing it with
+// agent_changes
+pub fn record_agent_changes<R: Runtime>(
+    app_handle: &AppHandle<R>,
+    session_id: &str,
+    command: &str,
+    receipt_id: &str,
+    git_before: Option<&GitSnapshot>,
+    named_before: Option<&NamedPathsSnapshot>,
+) {
+    let git = git_before.and_then(|before| Some((before, snapshot_repository(&before.root)?)));
+    let git_observed = git
+        .as_ref()
+        .map(|(before, after)| diff_snapshots(before, after))
+        .unwrap_or_default();
+    let repository = match (&git, named_before) {
+        (Some((_, after)), _) => after.root.clone(),
+        (None, Some(named)) => named.cwd.clone(),
+        (None, None) => String::new(),
+    };
+    let named_observed: Vec<ObservedChange> = named_before
+        .map(|named| diff_named_paths(named, &repository))
+        .unwrap_or_default()
+        .into_iter()
+        .filter(|change| !git_observed.iter().any(|seen| seen.path == change.path))
+        .collect();
+    if git_observed.is_empty() && named_observed.is_empty() {
+        discard_run_backup(receipt_id);
+        return;
+    }
+    if let Err(e) = save_agent_run(
+        session_id,
+        command,
+        receipt_id,
+        &repository,
+        git.as_ref()
+            .map(|(before, _)| (*before, git_observed.as_slice())),
+        named_before.map(|named| (named, named_observed.as_slice())),
+    ) {
+        emit_command_error(
+            app_handle,
+            session_id,
+            format!("Failed to save undo data for agent command: {}", e),
+        );
+    }
+    let observed = git_observed.into_iter().chain(named_observed).collect();
+    let store = app_handle.state::<AgentChangeStore>();
+    if store
+        .record(
+            session_id,
+            command,
+            receipt_id,
+            unix_timestamp_millis(),
+            observed,
+        )
+        .is_ok()
+    {
+        if let Ok(changes) = summarize(&store, session_id) {
+            let _ = app_handle.emit("agent_changes", changes);
+        }
+    }
+}
+
+fn summarize(store: &AgentChangeStore, session_id: &str) -> Result<AgentChanges, String> {
+    let changes = store.list(session_id)?;
+    let count = |kind: AgentChangeKind| changes.iter().filter(|change| change.kind == kind).count();
+    Ok(AgentChanges {
+        session_id: session_id.to_string(),
+        created: count(AgentChangeKind::Created),
+        modified: count(AgentChangeKind::Modified),
+        deleted: count(AgentChangeKind::Deleted),
+        changes,
+    })
+}
+
+// Files created, modified or deleted by the agent's commands in this session (seen by git or
+// named on the command line), with the commands responsible, for review before keeping or
+// reverting them
+#[command]
+pub fn get_agent_changes(
+    session_id: String,
+    agent_change_store: State<'_, AgentChangeStore>,
+) -> Result<AgentChanges, String> {
+    summarize(&agent_change_store, &session_id)
+}
+
+// Starts a new review period, e.g. once the user has accepted or reverted the changes
+#[command]
+pub fn clear_agent_changes(
+    session_id: String,
+    agent_change_store: State<'_, AgentChangeStore>,
+) -> Result<(), String> {
+    agent_change_store.clear(&session_id)
+}
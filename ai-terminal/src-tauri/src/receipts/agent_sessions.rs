@@ -0,0 +1,211 @@
+use crate::command::constants::{AGENT_SESSIONS_MAX, AGENT_SESSION_OUTPUT_LINES};
+use crate::command::core::active_commands::signal_process_group;
+use crate::command::types::command_manager::CommandManager;
+use crate::command::types::command_state::CommandState;
+use crate::command::types::scrollback_manager::ScrollbackManager;
+use crate::receipts::receipt_commands::execute_agent_command;
+use crate::receipts::types::agent_session_pool::{
+    AgentSession, AgentSessionOutput, AgentSessionPool, AgentSessionStatus,
+};
+use crate::utils::ansi::render_plain_text;
+use crate::utils::config_utils::unix_timestamp_millis;
+use crate::utils::file_system_utils::expand_home;
+use std::env;
+use std::fs;
+use tauri::{command, AppHandle, Emitter, Manager, Runtime, State};
+
+const MAX_LABEL_LENGTH: usize = 64;
+
+fn validate_label(label: &str) -> Result<String, String> {
+    let label = label.trim();
+    if label.is_empty() {
+        return Err("A session label is required".to_string());
+    }
+    if label.chars().count() > MAX_LABEL_LENGTH || label.chars().any(char::is_control) {
+        return Err(format!(
+            "Session labels must be at most {} printable characters",
+            MAX_LABEL_LENGTH
+        ));
+    }
+    Ok(label.to_string())
+}
+
+fn session_status<R: Runtime>(
+    app_handle: &AppHandle<R>,
+    session: &AgentSession,
+) -> Result<AgentSessionStatus, String> {
+    let command_manager = app_handle.state::<CommandManager>();
+    let (cwd, running) = {
+        let states = command_manager.commands.lock().map_err(|e| e.to_string())?;
+        match states.get(&session.session_id) {
+            Some(state) => (
+                state.current_dir.clone(),
+                state
+                    .running
+                    .as_ref()
+                    .filter(|running| state.pid == Some(running.pid))
+                    .map(|running| running.command.clone()),
+            ),
+            None => (String::new(), None),
+        }
+    };
+    let last_run = app_handle
+        .state::<ScrollbackManager>()
+        .runs(&session.session_id)?
+        .pop();
+    Ok(AgentSessionStatus {
+        label: session.label.clone(),
+        session_id: session.session_id.clone(),
+        cwd,
+        running,
+        last_exit_code: last_run.as_ref().and_then(|run| run.exit_code),
+        last_command: last_run.map(|run| run.command),
+        opened_at: session.opened_at,
+    })
+}
+
+// Opens a new session for the agent under a label unique within the conversation, starting in
+// `cwd` (the app's working directory by default). Commands are then run by label.
+#[command]
+pub fn open_agent_session<R: Runtime>(
+    conversation_id: String,
+    label: String,
+    cwd: Option<String>,
+    app_handle: AppHandle<R>,
+    command_manager: State<'_, CommandManager>,
+    agent_session_pool: State<'_, AgentSessionPool>,
+) -> Result<AgentSessionStatus, String> {
+    let label = validate_label(&label)?;
+    let dir = match cwd.as_deref().map(str::trim).filter(|cwd| !cwd.is_empty()) {
+        Some(cwd) => expand_home(cwd)?,
+        None => env::current_dir().map_err(|e| e.to_string())?,
+    };
+    if !dir.is_dir() {
+        return Err(format!("{} is not a directory", dir.display()));
+    }
+    let session = AgentSession {
+        label,
+        session_id: format!("agent-{}", uuid::Uuid::new_v4()),
+        conversation_id,
+        opened_at: unix_timestamp_millis(),
+    };
+    agent_session_pool.insert(session.clone(), AGENT_SESSIONS_MAX)?;
+    command_manager
+        .commands
+        .lock()
+        .map_err(|e| e.to_string())?
+        .insert(
+            session.session_id.clone(),
+            CommandState::new(dir.to_string_lossy().to_string()),
+        );
+
+    let status = session_status(&app_handle, &session)?;
+    let _ = app_handle.emit("agent_session_opened", status.clone());
+    Ok(status)
+}
+
+// The conversation's agent sessions with their directory, what is running in each and how the
+// last command ended
+#[command]
+pub fn list_agent_sessions<R: Runtime>(
+    conversation_id: String,
+    app_handle: AppHandle<R>,
+    agent_session_pool: State<'_, AgentSessionPool>,
+) -> Result<Vec<AgentSessionStatus>, String> {
+    agent_session_pool
+        .list(&conversation_id)?
+        .iter()
+        .map(|session| session_status(&app_handle, session))
+        .collect()
+}
+
+// Runs a command in the labelled session through execute_agent_command, so it gets a receipt
+// like any other agent command. A session runs one command at a time; a long-running one
+// (e.g. a dev server) keeps its session busy until it exits or the session is closed.
+#[command]
+pub fn run_in_agent_session<R: Runtime>(
+    conversation_id: String,
+    label: String,
+    command: String,
+    confirmation_token: Option<String>,
+    app_handle: AppHandle<R>,
+    command_manager: State<'_, CommandManager>,
+    agent_session_pool: State<'_, AgentSessionPool>,
+) -> Result<String, String> {
+    let session = agent_session_pool.get(&conversation_id, label.trim())?;
+    if let Some(running) = session_status(&app_handle, &session)?.running {
+        return Err(format!(
+            "Session '{}' is still running `{}`; read its output, close it, or open another session",
+            session.label, running
+        ));
+    }
+    execute_agent_command(
+        command,
+        session.session_id,
+        conversation_id,
+        confirmation_token,
+        app_handle,
+        command_manager,
+    )
+}
+
+// The last `max_lines` lines of the most recent command in the labelled session, which may
+// still be running
+#[command]
+pub fn read_agent_session_output(
+    conversation_id: String,
+    label: String,
+    max_lines: Option<usize>,
+    agent_session_pool: State<'_, AgentSessionPool>,
+    scrollback_manager: State<'_, ScrollbackManager>,
+) -> Result<AgentSessionOutput, String> {
+    let session = agent_session_pool.get(&conversation_id, label.trim())?;
+    let max_lines = max_lines.unwrap_or(AGENT_SESSION_OUTPUT_LINES).max(1);
+    let run = scrollback_manager.runs(&session.session_id)?.pop();
+    let text = run
+        .as_ref()
+        .map(|run| render_plain_text(&run.output))
+        .unwrap_or_default();
+    let lines: Vec<&str> = text.lines().collect();
+    let start = lines.len().saturating_sub(max_lines);
+    Ok(AgentSessionOutput {
+        label: session.label,
+        session_id: session.session_id,
+        command: run.as_ref().map(|run| run.command.clone()),
+        finished: run.as_ref().is_some_and(|run| run.finished),
+        exit_code: run.as_ref().and_then(|run| run.exit_code),
+        output: lines[start..].join("\n"),
+        truncated: start > 0,
+    })
+}
+
+// Stops whatever the labelled session is running (SIGTERM to its process group) and forgets
+// the session, deleting its temp dir. Files it changed stay listed by get_agent_changes.
+#[command]
+pub fn close_agent_session<R: Runtime>(
+    conversation_id: String,
+    label: String,
+    app_handle: AppHandle<R>,
+    command_manager: State<'_, CommandManager>,
+    agent_session_pool: State<'_, AgentSessionPool>,
+    scrollback_manager: State<'_, ScrollbackManager>,
+) -> Result<(), String> {
+    let session = agent_session_pool.remove(&conversation_id, label.trim())?;
+    let state = command_manager
+        .commands
+        .lock()
+        .map_err(|e| e.to_string())?
+        .remove(&session.session_id);
+    if let Some(pid) = state.as_ref().and_then(|state| state.pid) {
+        // Already gone if it exited in the meantime
+        let _ = signal_process_group(pid, false);
+    }
+    if let Some(tmp_dir) = state.and_then(|state| state.tmp_dir) {
+        let _ = fs::remove_dir_all(tmp_dir);
+    }
+    if let Ok(mut sessions) = scrollback_manager.sessions.lock() {
+        sessions.remove(&session.session_id);
+    }
+    let _ = app_handle.emit("agent_session_closed", session);
+    Ok(())
+}
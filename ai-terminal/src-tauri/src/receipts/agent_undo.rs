@@ -0,0 +1,305 @@
+use crate::command::constants::{AGENT_BACKUP_MAX_FILE_BYTES, AGENT_RUN_BACKUPS_KEPT};
+use crate::command::git_commands::git::new_git_command;
+use crate::receipts::agent_changes::fingerprint;
+use crate::receipts::types::agent_changes::{
+    AgentChangeKind, AgentChangeStore, GitSnapshot, NamedPathsSnapshot, ObservedChange,
+};
+use crate::receipts::types::agent_run::{AgentRun, AgentRunFile, OriginalSource, UndoResult};
+use crate::utils::config_utils::{app_data_dir, unix_timestamp_millis};
+use std::fs;
+use std::path::{Component, Path, PathBuf};
+use tauri::{command, State};
+
+const AGENT_RUNS_DIR: &str = "agent-runs";
+const MANIFEST_FILE: &str = "manifest.json";
+const FILES_DIR: &str = "files";
+// Copies of named files, by absolute path
+const NAMED_FILES_DIR: &str = "named";
+
+// One directory per agent command (named by its receipt id) under the data directory
+fn run_dir(run_id: &str) -> Result<PathBuf, String> {
+    if run_id.is_empty()
+        || !run_id
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_')
+    {
+        return Err(format!("Invalid agent run id '{}'", run_id));
+    }
+    Ok(app_data_dir()?.join(AGENT_RUNS_DIR).join(run_id))
+}
+
+// Where the run keeps its copy of a manifest path: repository-relative paths under FILES_DIR,
+// absolute ones (named files) under NAMED_FILES_DIR
+fn backup_path(dir: &Path, path: &str) -> PathBuf {
+    let path = Path::new(path);
+    if !path.is_absolute() {
+        return dir.join(FILES_DIR).join(path);
+    }
+    let relative: PathBuf = path
+        .components()
+        .filter(|component| matches!(component, Component::Normal(_)))
+        .collect();
+    dir.join(NAMED_FILES_DIR).join(relative)
+}
+
+// Copies one file into the run's backup. Ok(false) when it cannot be: not a regular file
+// (e.g. a symlink) or too large.
+fn backup_file(dir: &Path, source: &Path, path: &str) -> Result<bool, String> {
+    let is_small_file = fs::symlink_metadata(source)
+        .is_ok_and(|metadata| metadata.is_file() && metadata.len() <= AGENT_BACKUP_MAX_FILE_BYTES);
+    if !is_small_file {
+        return Ok(false);
+    }
+    let target = backup_path(dir, path);
+    if let Some(parent) = target.parent() {
+        fs::create_dir_all(parent)
+            .map_err(|e| format!("Failed to create {}: {}", parent.display(), e))?;
+    }
+    fs::copy(source, &target)
+        .map_err(|e| format!("Failed to back up {}: {}", source.display(), e))?;
+    Ok(true)
+}
+
+// Copies the repository's dirty and untracked files aside before an agent command runs and
+// returns those that could not be. Clean tracked files need no copy: they can be restored from
+// the commit in the snapshot.
+pub fn backup_dirty_files(run_id: &str, snapshot: &GitSnapshot) -> Result<Vec<String>, String> {
+    let dir = run_dir(run_id)?;
+    let mut skipped = Vec::new();
+    for (path, entry) in &snapshot.entries {
+        if entry.fingerprint.is_none() {
+            continue;
+        }
+        let source = Path::new(&snapshot.root).join(path);
+        if !backup_file(&dir, &source, path)? {
+            skipped.push(source.to_string_lossy().to_string());
+        }
+    }
+    Ok(skipped)
+}
+
+// Copies the existing files under the paths an agent command names aside before it runs and
+// returns those that could not be
+pub fn backup_named_files(
+    run_id: &str,
+    snapshot: &NamedPathsSnapshot,
+) -> Result<Vec<String>, String> {
+    let dir = run_dir(run_id)?;
+    let mut skipped = Vec::new();
+    for (path, fingerprint) in &snapshot.entries {
+        if fingerprint.is_none() {
+            continue;
+        }
+        let display = path.to_string_lossy().to_string();
+        if !backup_file(&dir, path, &display)? {
+            skipped.push(display);
+        }
+    }
+    Ok(skipped)
+}
+
+// Drops the backup of a command that changed nothing, or never ran
+pub fn discard_run_backup(run_id: &str) {
+    if let Ok(dir) = run_dir(run_id) {
+        let _ = fs::remove_dir_all(dir);
+    }
+}
+
+fn prune_old_runs() {
+    let Ok(root) = app_data_dir().map(|dir| dir.join(AGENT_RUNS_DIR)) else {
+        return;
+    };
+    let Ok(entries) = fs::read_dir(&root) else {
+        return;
+    };
+    let mut runs: Vec<(std::time::SystemTime, PathBuf)> = entries
+        .filter_map(|entry| entry.ok())
+        .filter_map(|entry| {
+            let modified = entry.metadata().ok()?.modified().ok()?;
+            Some((modified, entry.path()))
+        })
+        .collect();
+    runs.sort_by_key(|(modified, _)| std::cmp::Reverse(*modified));
+    for (_, path) in runs.into_iter().skip(AGENT_RUN_BACKUPS_KEPT) {
+        let _ = fs::remove_dir_all(path);
+    }
+}
+
+// Writes the manifest undo_agent_run works from: each file the command changed, where its
+// original content is, and the state the command left it in. `git` holds the repository
+// snapshot from before the command and the changes git saw; `named` the same for the paths the
+// command named, minus what git already reported.
+pub fn save_agent_run(
+    session_id: &str,
+    command: &str,
+    run_id: &str,
+    repository: &str,
+    git: Option<(&GitSnapshot, &[ObservedChange])>,
+    named: Option<(&NamedPathsSnapshot, &[ObservedChange])>,
+) -> Result<(), String> {
+    let dir = run_dir(run_id)?;
+    let mut files = Vec::new();
+    if let Some((before, observed)) = git {
+        files.extend(observed.iter().map(|change| {
+            let absolute = Path::new(&change.path);
+            let path = absolute
+                .strip_prefix(&before.root)
+                .unwrap_or(absolute)
+                .to_string_lossy()
+                .to_string();
+            let original = match before.entries.get(&path) {
+                Some(entry) if entry.fingerprint.is_none() => OriginalSource::Absent,
+                Some(_) if backup_path(&dir, &path).is_file() => OriginalSource::Backup,
+                Some(_) => OriginalSource::Unavailable,
+                None if change.kind == AgentChangeKind::Created => OriginalSource::Absent,
+                None if before.head.is_some() => OriginalSource::Head,
+                None => OriginalSource::Unavailable,
+            };
+            AgentRunFile {
+                after_fingerprint: fingerprint(absolute),
+                path,
+                kind: change.kind,
+                original,
+            }
+        }));
+    }
+    if let Some((before, observed)) = named {
+        files.extend(observed.iter().map(|change| {
+            let original = match before.entries.get(Path::new(&change.path)) {
+                Some(Some(_)) if backup_path(&dir, &change.path).is_file() => {
+                    OriginalSource::Backup
+                }
+                Some(Some(_)) => OriginalSource::Unavailable,
+                // Missing before, or new under a named directory
+                Some(None) | None => OriginalSource::Absent,
+            };
+            AgentRunFile {
+                after_fingerprint: fingerprint(Path::new(&change.path)),
+                path: change.path.clone(),
+                kind: change.kind,
+                original,
+            }
+        }));
+    }
+    let run = AgentRun {
+        run_id: run_id.to_string(),
+        session_id: session_id.to_string(),
+        command: command.to_string(),
+        repository: repository.to_string(),
+        head: git.and_then(|(before, _)| before.head.clone()),
+        finished_at: unix_timestamp_millis(),
+        undone: false,
+        files,
+    };
+    fs::create_dir_all(&dir).map_err(|e| format!("Failed to create {}: {}", dir.display(), e))?;
+    write_manifest(&dir, &run)?;
+    prune_old_runs();
+    Ok(())
+}
+
+fn write_manifest(dir: &Path, run: &AgentRun) -> Result<(), String> {
+    let path = dir.join(MANIFEST_FILE);
+    let contents = serde_json::to_string_pretty(run).map_err(|e| e.to_string())?;
+    fs::write(&path, contents).map_err(|e| format!("Failed to write {}: {}", path.display(), e))
+}
+
+fn load_run(run_id: &str) -> Result<(PathBuf, AgentRun), String> {
+    let dir = run_dir(run_id)?;
+    let contents = fs::read_to_string(dir.join(MANIFEST_FILE)).map_err(|_| {
+        format!(
+            "No undo data for agent run '{}' (it changed no files git sees or the command \
+             names, or it has been pruned)",
+            run_id
+        )
+    })?;
+    let run = serde_json::from_str(&contents).map_err(|e| e.to_string())?;
+    Ok((dir, run))
+}
+
+fn restore_from_commit(repository: &str, head: &str, path: &str) -> Result<(), String> {
+    let output = new_git_command()
+        .args(["restore", "--source", head, "--worktree", "--", path])
+        .current_dir(repository)
+        .output()
+        .map_err(|e| format!("Failed to run git restore: {}", e))?;
+    if output.status.success() {
+        Ok(())
+    } else {
+        Err(String::from_utf8_lossy(&output.stderr).trim().to_string())
+    }
+}
+
+// Puts one file back how it was before the run. Ok(true) when it was removed rather than restored.
+fn undo_file(
+    dir: &Path,
+    run: &AgentRun,
+    file: &AgentRunFile,
+    target: &Path,
+) -> Result<bool, String> {
+    match file.original {
+        OriginalSource::Absent => {
+            if target.is_dir() {
+                fs::remove_dir_all(target).map_err(|e| e.to_string())?;
+            } else if fs::symlink_metadata(target).is_ok() {
+                fs::remove_file(target).map_err(|e| e.to_string())?;
+            }
+            Ok(true)
+        }
+        OriginalSource::Backup => {
+            if let Some(parent) = target.parent() {
+                fs::create_dir_all(parent).map_err(|e| e.to_string())?;
+            }
+            fs::copy(backup_path(dir, &file.path), target).map_err(|e| e.to_string())?;
+            Ok(false)
+        }
+        OriginalSource::Head => {
+            let head = run.head.as_deref().ok_or("no commit to restore from")?;
+            restore_from_commit(&run.repository, head, &file.path)?;
+            Ok(false)
+        }
+        OriginalSource::Unavailable => Err("no copy of the original was kept".to_string()),
+    }
+}
+
+// Restores the files an agent command changed: originals come back, files it created are
+// removed. Files edited again since the run are skipped unless `force` is set. Only changes git
+// sees and changes under the paths the command names are known; anything else (e.g. files a
+// script writes outside a repository) is left as is.
+#[command]
+pub fn undo_agent_run(
+    run_id: String,
+    force: Option<bool>,
+    agent_change_store: State<'_, AgentChangeStore>,
+) -> Result<UndoResult, String> {
+    let (dir, mut run) = load_run(&run_id)?;
+    if run.undone {
+        return Err(format!("Agent run '{}' has already been undone", run_id));
+    }
+    let force = force.unwrap_or(false);
+
+    let mut result = UndoResult {
+        run_id: run_id.clone(),
+        ..UndoResult::default()
+    };
+    for file in &run.files {
+        let target = Path::new(&run.repository).join(&file.path);
+        let display = target.to_string_lossy().to_string();
+        if !force && fingerprint(&target) != file.after_fingerprint {
+            result.skipped.push(display);
+            continue;
+        }
+        match undo_file(&dir, &run, file, &target) {
+            Ok(true) => result.removed.push(display),
+            Ok(false) => result.restored.push(display),
+            Err(e) => result.failed.push(format!("{}: {}", display, e)),
+        }
+    }
+
+    // A partial undo can be retried, e.g. with force once skipped files have been checked
+    if result.skipped.is_empty() && result.failed.is_empty() {
+        run.undone = true;
+        write_manifest(&dir, &run)?;
+        agent_change_store.forget_run(&run.session_id, &run_id)?;
+    }
+    Ok(result)
+}
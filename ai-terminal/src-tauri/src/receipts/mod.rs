@@ -0,0 +1,7 @@
+pub mod agent_changes;
+pub mod agent_sessions;
+pub mod agent_undo;
+pub mod named_paths;
+pub mod receipt_commands;
+pub mod recorder;
+pub mod types;
@@ -0,0 +1,421 @@
+use crate::command::constants::{AGENT_BACKUP_MAX_NAMED_BYTES, AGENT_BACKUP_MAX_NAMED_FILES};
+use crate::command::core::cost_warnings::is_command_prefix;
+use crate::receipts::agent_changes::fingerprint;
+use crate::receipts::types::agent_changes::{AgentChangeKind, NamedPathsSnapshot, ObservedChange};
+use std::collections::{BTreeSet, HashMap};
+use std::fs;
+use std::path::{Component, Path, PathBuf};
+
+// Programs whose arguments are not files they write: they only read them, or (git) what they
+// change in the work tree already shows up in the repository snapshot
+const NON_WRITING_PROGRAMS: [&str; 21] = [
+    "cat", "cd", "cmp", "diff", "du", "echo", "file", "git", "grep", "head", "less", "ls", "more",
+    "printf", "pwd", "rg", "stat", "tail", "tree", "wc", "which",
+];
+// find writes nothing unless given one of these
+const FIND_WRITING_ACTIONS: [&str; 4] = ["-delete", "-exec", "-execdir", "-ok"];
+// Devices and kernel interfaces, e.g. `> /dev/null`, are not files to back up
+const SPECIAL_DIRS: [&str; 3] = ["/dev", "/proc", "/sys"];
+
+struct Word {
+    text: String,
+    // Follows `>` or `>>`
+    redirect: bool,
+    // Follows `<`
+    input: bool,
+}
+
+#[derive(Clone, Copy)]
+struct Budget {
+    files: usize,
+    bytes: u64,
+}
+
+impl Default for Budget {
+    fn default() -> Self {
+        Self {
+            files: AGENT_BACKUP_MAX_NAMED_FILES,
+            bytes: AGENT_BACKUP_MAX_NAMED_BYTES,
+        }
+    }
+}
+
+// Words of one segment with quotes removed
+fn segment_words(segment: &str) -> Vec<Word> {
+    let mut words = Vec::new();
+    let mut text = String::new();
+    let mut started = false;
+    let mut quote = None;
+    let (mut redirect, mut input) = (false, false);
+    let mut push = |text: &mut String, redirect: &mut bool, input: &mut bool| {
+        words.push(Word {
+            text: std::mem::take(text),
+            redirect: std::mem::take(redirect),
+            input: std::mem::take(input),
+        });
+    };
+    for c in segment.chars() {
+        match quote {
+            Some(open) if c == open => quote = None,
+            Some(_) => text.push(c),
+            None if c == '\'' || c == '"' => {
+                quote = Some(c);
+                started = true;
+            }
+            None if c == '>' || c == '<' => {
+                // The `2` of `2>` is a file descriptor, not an argument
+                if started && !text.chars().all(|c| c.is_ascii_digit()) {
+                    push(&mut text, &mut redirect, &mut input);
+                }
+                text.clear();
+                started = false;
+                redirect = c == '>';
+                input = c == '<';
+            }
+            None if c.is_whitespace() => {
+                if started {
+                    push(&mut text, &mut redirect, &mut input);
+                    started = false;
+                }
+            }
+            None => {
+                text.push(c);
+                started = true;
+            }
+        }
+    }
+    if started {
+        push(&mut text, &mut redirect, &mut input);
+    }
+    words
+}
+
+// Lexically, like the shell: `~` is the home directory and `..` drops the previous component
+fn resolve(dir: &Path, word: &str) -> PathBuf {
+    let joined = match word.strip_prefix('~') {
+        Some(rest) if rest.is_empty() || rest.starts_with('/') => dirs::home_dir()
+            .unwrap_or_default()
+            .join(rest.trim_start_matches('/')),
+        _ => dir.join(word),
+    };
+    let mut path = PathBuf::new();
+    for component in joined.components() {
+        match component {
+            Component::CurDir => {}
+            Component::ParentDir => {
+                path.pop();
+            }
+            other => path.push(other),
+        }
+    }
+    path
+}
+
+// `*` and `?`, as in a glob's last component
+fn wildcard_matches(pattern: &str, name: &str) -> bool {
+    let pattern: Vec<char> = pattern.chars().collect();
+    let name: Vec<char> = name.chars().collect();
+    let (mut p, mut n) = (0, 0);
+    let mut star: Option<(usize, usize)> = None;
+    while n < name.len() {
+        if p < pattern.len() && (pattern[p] == '?' || pattern[p] == name[n]) {
+            p += 1;
+            n += 1;
+        } else if p < pattern.len() && pattern[p] == '*' {
+            star = Some((p, n));
+            p += 1;
+        } else if let Some((star_p, star_n)) = star {
+            p = star_p + 1;
+            n = star_n + 1;
+            star = Some((star_p, star_n + 1));
+        } else {
+            return false;
+        }
+    }
+    pattern[p..].iter().all(|&c| c == '*')
+}
+
+// The word as a path, or the entries its last component matches when that is a glob
+fn expand(dir: &Path, word: &str) -> Vec<PathBuf> {
+    let path = resolve(dir, word);
+    let name = path
+        .file_name()
+        .map(|name| name.to_string_lossy().to_string())
+        .unwrap_or_default();
+    if !name.contains(['*', '?']) {
+        return vec![path];
+    }
+    let Some(entries) = path.parent().and_then(|parent| fs::read_dir(parent).ok()) else {
+        return Vec::new();
+    };
+    entries
+        .flatten()
+        .filter(|entry| {
+            let entry_name = entry.file_name().to_string_lossy().to_string();
+            // Like the shell, only a pattern starting with `.` matches hidden files
+            (name.starts_with('.') || !entry_name.starts_with('.'))
+                && wildcard_matches(&name, &entry_name)
+        })
+        .map(|entry| entry.path())
+        .collect()
+}
+
+// Arguments and redirection targets of the command's programs, resolved against `cwd` and any
+// `cd` along the way. None when it writes no file it names, e.g. `ls src` or `git status`.
+fn named_command_paths(command: &str, cwd: &Path) -> Option<Vec<PathBuf>> {
+    let mut dir = cwd.to_path_buf();
+    let mut paths = BTreeSet::new();
+    let mut writes = false;
+    for segment in command.split([';', '&', '|', '\n']) {
+        let words = segment_words(segment);
+        let mut arguments = words
+            .iter()
+            .filter(|word| !word.redirect && !word.input)
+            .map(|word| word.text.as_str())
+            .skip_while(|word| is_command_prefix(word));
+        let program = arguments.next().unwrap_or_default();
+        let arguments: Vec<&str> = arguments.collect();
+        // The `1` left over from splitting `2>&1` at the `&`
+        if program.chars().all(|c| c.is_ascii_digit()) && !program.is_empty() {
+            continue;
+        }
+        let program = Path::new(program)
+            .file_name()
+            .map(|name| name.to_string_lossy().to_string())
+            .unwrap_or_default();
+        if program == "cd" {
+            match arguments.first() {
+                Some(target) if !target.starts_with('-') => dir = resolve(&dir, target),
+                Some(_) => {}
+                None => dir = dirs::home_dir().unwrap_or(dir),
+            }
+            continue;
+        }
+        let writes_arguments = !program.is_empty()
+            && if program == "find" {
+                arguments
+                    .iter()
+                    .any(|argument| FIND_WRITING_ACTIONS.contains(argument))
+            } else {
+                !NON_WRITING_PROGRAMS.contains(&program.as_str())
+            };
+        let redirects: Vec<&str> = words
+            .iter()
+            .filter(|word| {
+                word.redirect && !SPECIAL_DIRS.iter().any(|dir| word.text.starts_with(dir))
+            })
+            .map(|word| word.text.as_str())
+            .collect();
+        writes |= writes_arguments || !redirects.is_empty();
+        let named = redirects
+            .into_iter()
+            .chain(arguments.into_iter().filter(|_| writes_arguments))
+            .filter(|word| !word.is_empty() && !word.starts_with('-'))
+            .filter(|word| !word.contains(['$', '`']));
+        for word in named {
+            paths.extend(
+                expand(&dir, word)
+                    .into_iter()
+                    .filter(|path| !SPECIAL_DIRS.iter().any(|special| path.starts_with(special))),
+            );
+        }
+    }
+    writes.then(|| paths.into_iter().collect())
+}
+
+// Regular files and symlinks at or under `path`, without following symlinks. False once the
+// budget runs out.
+fn collect_files(path: &Path, files: &mut Vec<PathBuf>, budget: &mut Budget) -> bool {
+    let Ok(metadata) = fs::symlink_metadata(path) else {
+        return true;
+    };
+    if metadata.is_dir() {
+        let Ok(entries) = fs::read_dir(path) else {
+            return true;
+        };
+        return entries
+            .flatten()
+            .all(|entry| collect_files(&entry.path(), files, budget));
+    }
+    if !metadata.is_file() && !metadata.is_symlink() {
+        return true;
+    }
+    if budget.files == 0 || budget.bytes < metadata.len() {
+        return false;
+    }
+    budget.files -= 1;
+    budget.bytes -= metadata.len();
+    files.push(path.to_path_buf());
+    true
+}
+
+// Fingerprints of the files under the paths the command names, taken before it runs, and the
+// paths left out for holding more than a backup takes. None when it writes no file it names.
+pub fn snapshot_named_paths(command: &str, cwd: &str) -> Option<(NamedPathsSnapshot, Vec<String>)> {
+    // Canonical like the repository root git reports, so both name a file the same way
+    let dir = fs::canonicalize(cwd).unwrap_or_else(|_| PathBuf::from(cwd));
+    let mut budget = Budget::default();
+    let mut snapshot = NamedPathsSnapshot {
+        cwd: dir.to_string_lossy().to_string(),
+        roots: Vec::new(),
+        entries: HashMap::new(),
+    };
+    let mut too_large = Vec::new();
+    for root in named_command_paths(command, &dir)? {
+        let mut files = Vec::new();
+        let mut root_budget = budget;
+        if !collect_files(&root, &mut files, &mut root_budget) {
+            too_large.push(root.to_string_lossy().to_string());
+            continue;
+        }
+        budget = root_budget;
+        if fs::symlink_metadata(&root).is_err() {
+            snapshot.entries.insert(root.clone(), None);
+        }
+        for file in files {
+            let fingerprint = fingerprint(&file);
+            snapshot.entries.insert(file, fingerprint);
+        }
+        snapshot.roots.push(root);
+    }
+    Some((snapshot, too_large))
+}
+
+// Files under the snapshot's paths that the command created, modified or deleted
+pub fn diff_named_paths(before: &NamedPathsSnapshot, repository: &str) -> Vec<ObservedChange> {
+    let mut changes: Vec<(PathBuf, AgentChangeKind)> = before
+        .entries
+        .iter()
+        .filter_map(|(path, old)| {
+            let kind = match (old, fingerprint(path)) {
+                (None, Some(_)) => AgentChangeKind::Created,
+                (Some(_), None) => AgentChangeKind::Deleted,
+                (Some(old), Some(new)) if *old != new => AgentChangeKind::Modified,
+                _ => return None,
+            };
+            Some((path.clone(), kind))
+        })
+        .collect();
+    // Files new under a named directory; past the budget the rest go unnoticed
+    let mut files = Vec::new();
+    let mut budget = Budget::default();
+    for root in &before.roots {
+        collect_files(root, &mut files, &mut budget);
+    }
+    changes.extend(
+        files
+            .into_iter()
+            .filter(|file| !before.entries.contains_key(file))
+            .map(|file| (file, AgentChangeKind::Created)),
+    );
+    changes.sort_by(|a, b| a.0.cmp(&b.0));
+    changes
+        .into_iter()
+        .map(|(path, kind)| ObservedChange {
+            path: path.to_string_lossy().to_string(),
+            repository: repository.to_string(),
+            kind,
+            tracked: false,
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn named(command: &str) -> Option<Vec<String>> {
+        named_command_paths(command, Path::new("/work")).map(|paths| {
+            paths
+                .into_iter()
+                .map(|path| path.to_string_lossy().to_string())
+                .collect()
+        })
+    }
+
+    #[test]
+    fn arguments_and_redirections_are_named() {
+        assert_eq!(
+            named("rm -rf build 'my notes.txt'"),
+            Some(vec![
+                "/work/build".to_string(),
+                "/work/my notes.txt".to_string()
+            ])
+        );
+        assert_eq!(
+            named("echo hi > out.txt 2>>err.log"),
+            Some(vec![
+                "/work/err.log".to_string(),
+                "/work/out.txt".to_string()
+            ])
+        );
+        assert_eq!(
+            named("sudo mv ../a.txt ./b.txt"),
+            Some(vec!["/a.txt".to_string(), "/work/b.txt".to_string()])
+        );
+        assert_eq!(
+            named("cd sub && touch x"),
+            Some(vec!["/work/sub/x".to_string()])
+        );
+    }
+
+    #[test]
+    fn commands_that_only_read_name_nothing() {
+        assert_eq!(named("ls -la src | grep foo"), None);
+        assert_eq!(named("cat < input.txt"), None);
+        assert_eq!(named("git status 2>&1"), None);
+        assert_eq!(named("find . -name '*.rs'"), None);
+        assert_eq!(named("echo hi > /dev/null"), None);
+        assert_eq!(
+            named("find build -delete"),
+            Some(vec!["/work/build".to_string()])
+        );
+    }
+
+    #[test]
+    fn wildcards() {
+        assert!(wildcard_matches("*.rs", "main.rs"));
+        assert!(wildcard_matches("a?c*", "abcdef"));
+        assert!(wildcard_matches("*a*b", "xxaxxb"));
+        assert!(!wildcard_matches("*.rs", "main.rs.bak"));
+        assert!(!wildcard_matches("a?c", "ac"));
+    }
+
+    #[test]
+    fn changes_under_named_paths_are_found() {
+        let dir = std::env::temp_dir().join(format!("named-paths-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(dir.join("logs")).unwrap();
+        fs::write(dir.join("logs/a.log"), "a").unwrap();
+        fs::write(dir.join("logs/b.log"), "b").unwrap();
+        fs::write(dir.join("keep.txt"), "keep").unwrap();
+        let cwd = dir.to_string_lossy().to_string();
+
+        let (snapshot, too_large) =
+            snapshot_named_paths("rm logs/a.log; echo x >> logs/b.log; touch new.txt", &cwd)
+                .unwrap();
+        assert!(too_large.is_empty());
+        fs::remove_file(dir.join("logs/a.log")).unwrap();
+        fs::write(dir.join("logs/b.log"), "bx").unwrap();
+        fs::write(dir.join("new.txt"), "").unwrap();
+        fs::write(dir.join("keep.txt"), "changed").unwrap();
+
+        let changes: Vec<(String, AgentChangeKind)> = diff_named_paths(&snapshot, &cwd)
+            .into_iter()
+            .map(|change| {
+                let path = Path::new(&change.path);
+                let relative = path.strip_prefix(&snapshot.cwd).unwrap();
+                (relative.to_string_lossy().to_string(), change.kind)
+            })
+            .collect();
+        let _ = fs::remove_dir_all(&dir);
+        assert_eq!(
+            changes,
+            vec![
+                ("logs/a.log".to_string(), AgentChangeKind::Deleted),
+                ("logs/b.log".to_string(), AgentChangeKind::Modified),
+                ("new.txt".to_string(), AgentChangeKind::Created),
+            ]
+        );
+    }
+}
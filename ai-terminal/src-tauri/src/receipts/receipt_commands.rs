@@ -0,0 +1,194 @@
+use crate::command::core::command_output::emit_command_error;
+use crate::command::core::execute_command::{execute_command, COMMAND_STARTED_MESSAGE};
+use crate::command::core::pty_fallback::PTY_FALLBACK_MARKER;
+use crate::command::types::command_manager::CommandManager;
+use crate::command::types::confirmation_manager::CONFIRMATION_REQUIRED_MARKER;
+use crate::receipts::agent_changes::take_git_snapshot;
+use crate::receipts::agent_undo::{backup_dirty_files, backup_named_files, discard_run_backup};
+use crate::receipts::named_paths::snapshot_named_paths;
+use crate::receipts::recorder::complete_command_receipt;
+use crate::receipts::types::agent_changes::{GitSnapshot, NamedPathsSnapshot};
+use crate::receipts::types::command_receipt::{CommandReceipt, PendingReceipt};
+use crate::receipts::types::receipt_store::ReceiptStore;
+use crate::utils::config_utils::unix_timestamp_millis;
+use tauri::{command, AppHandle, Manager, Runtime, State};
+
+// Copies aside what an agent command may change and returns the files that cannot be copied
+// at all (too large, not regular files), which make it not undoable
+fn back_up_files(
+    receipt_id: &str,
+    git_snapshot: Option<&GitSnapshot>,
+    named: Option<&(NamedPathsSnapshot, Vec<String>)>,
+) -> Result<Vec<String>, String> {
+    let mut skipped = Vec::new();
+    if let Some(snapshot) = git_snapshot {
+        skipped.extend(backup_dirty_files(receipt_id, snapshot)?);
+    }
+    if let Some((snapshot, too_large)) = named {
+        skipped.extend(too_large.iter().cloned());
+        skipped.extend(backup_named_files(receipt_id, snapshot)?);
+    }
+    Ok(skipped)
+}
+
+fn run_with_receipt<R: Runtime>(
+    command: String,
+    session_id: String,
+    conversation_id: String,
+    replay_of: Option<String>,
+    confirmation_token: Option<String>,
+    app_handle: AppHandle<R>,
+    command_manager: State<'_, CommandManager>,
+) -> Result<String, String> {
+    let (cwd, remote) = command_manager
+        .commands
+        .lock()
+        .map_err(|e| e.to_string())?
+        .get(&session_id)
+        .map(|state| (state.current_dir.clone(), state.is_ssh_session_active))
+        .unwrap_or_default();
+    // Commands forwarded to an SSH session change files elsewhere
+    let local = !remote && !cwd.is_empty();
+    let git_snapshot = local.then(|| take_git_snapshot(&cwd)).flatten();
+    let named = local
+        .then(|| snapshot_named_paths(&command, &cwd))
+        .flatten();
+    let receipt_id = uuid::Uuid::new_v4().to_string();
+    // Without the copies the run could not be undone, so it does not start if they fail
+    let skipped = match back_up_files(&receipt_id, git_snapshot.as_ref(), named.as_ref()) {
+        Ok(skipped) => skipped,
+        Err(e) => {
+            discard_run_backup(&receipt_id);
+            return Err(format!(
+                "Could not back up files before running the agent command: {}",
+                e
+            ));
+        }
+    };
+    let undo_warning = if remote {
+        Some("it runs on the remote host".to_string())
+    } else if !skipped.is_empty() {
+        Some(format!(
+            "these could not be backed up: {}",
+            skipped.join(", ")
+        ))
+    } else if git_snapshot.is_none()
+        && named
+            .as_ref()
+            .is_some_and(|(snapshot, _)| snapshot.roots.is_empty())
+    {
+        Some("it runs outside a git repository and names no file it changes".to_string())
+    } else {
+        None
+    };
+    if let Some(reason) = &undo_warning {
+        emit_command_error(
+            &app_handle,
+            &session_id,
+            format!("Warning: this agent command cannot be undone: {}", reason),
+        );
+    }
+    app_handle.state::<ReceiptStore>().begin(
+        &session_id,
+        PendingReceipt {
+            id: receipt_id.clone(),
+            conversation_id,
+            command: command.clone(),
+            cwd,
+            started_at: unix_timestamp_millis(),
+            replay_of,
+            git_snapshot,
+            named_paths: named.map(|(snapshot, _)| snapshot),
+            undo_warning,
+        },
+    )?;
+
+    // Spawned commands complete the receipt from their wait thread; builtins such as cd
+    // (and failures to start) finish here with whatever they returned
+    match execute_command(
+        command,
+        session_id.clone(),
+        None,
+        confirmation_token,
+        None,
+        app_handle.clone(),
+        command_manager,
+    ) {
+        Ok(message) if message == COMMAND_STARTED_MESSAGE => {}
+        // Held for confirmation: nothing ran, so there is nothing to record yet
+        Ok(message) if message == CONFIRMATION_REQUIRED_MARKER => {
+            app_handle.state::<ReceiptStore>().take_pending(&session_id);
+            discard_run_backup(&receipt_id);
+            return Ok(message);
+        }
+        // Moved to a PTY, which reports no exit status
+        Ok(message) if message == PTY_FALLBACK_MARKER => {
+            complete_command_receipt(&app_handle, &session_id, None, Some(message))
+        }
+        Ok(message) => complete_command_receipt(&app_handle, &session_id, Some(0), Some(message)),
+        Err(error) => complete_command_receipt(&app_handle, &session_id, None, Some(error.into())),
+    }
+    Ok(receipt_id)
+}
+
+// Runs a command on behalf of the AI agent and returns the id of the receipt that will be
+// stored with the conversation once it finishes (announced via the command_receipt event).
+// Commands held by a cost rule return the confirmation marker instead of a receipt id.
+// The receipt id also names the run for undo_agent_run, which covers files git sees and the
+// paths the command names; the receipt says when a run cannot be undone, e.g. files too large
+// to back up, and a warning is shown before it starts.
+#[command]
+pub fn execute_agent_command<R: Runtime>(
+    command: String,
+    session_id: String,
+    conversation_id: String,
+    confirmation_token: Option<String>,
+    app_handle: AppHandle<R>,
+    command_manager: State<'_, CommandManager>,
+) -> Result<String, String> {
+    run_with_receipt(
+        command,
+        session_id,
+        conversation_id,
+        None,
+        confirmation_token,
+        app_handle,
+        command_manager,
+    )
+}
+
+#[command]
+pub fn list_command_receipts(
+    conversation_id: String,
+    receipt_store: State<'_, ReceiptStore>,
+) -> Result<Vec<CommandReceipt>, String> {
+    receipt_store.list(&conversation_id)
+}
+
+// Re-runs a receipt's command in the session; the new receipt links back via replayOf so the
+// two output hashes can be compared
+#[command]
+pub fn replay_command_receipt<R: Runtime>(
+    conversation_id: String,
+    receipt_id: String,
+    session_id: String,
+    confirmation_token: Option<String>,
+    app_handle: AppHandle<R>,
+    command_manager: State<'_, CommandManager>,
+) -> Result<String, String> {
+    let original = app_handle
+        .state::<ReceiptStore>()
+        .list(&conversation_id)?
+        .into_iter()
+        .find(|receipt| receipt.id == receipt_id)
+        .ok_or_else(|| format!("No receipt '{}' in this conversation", receipt_id))?;
+    run_with_receipt(
+        original.command,
+        session_id,
+        conversation_id,
+        Some(receipt_id),
+        confirmation_token,
+        app_handle,
+        command_manager,
+    )
+}
@@ -0,0 +1,92 @@
+use crate::command::constants::RECEIPT_OUTPUT_PREVIEW_BYTES;
+use crate::command::core::command_output::emit_command_error;
+use crate::command::types::scrollback_manager::ScrollbackManager;
+use crate::receipts::agent_changes::record_agent_changes;
+use crate::receipts::types::command_receipt::CommandReceipt;
+use crate::receipts::types::receipt_store::ReceiptStore;
+use crate::utils::config_utils::unix_timestamp_millis;
+use sha2::{Digest, Sha256};
+use tauri::{AppHandle, Emitter, Manager, Runtime};
+
+pub fn output_sha256(output: &str) -> String {
+    Sha256::digest(output.as_bytes())
+        .iter()
+        .map(|byte| format!("{:02x}", byte))
+        .collect()
+}
+
+fn truncate_output(output: &str) -> (String, bool) {
+    if output.len() <= RECEIPT_OUTPUT_PREVIEW_BYTES {
+        return (output.to_string(), false);
+    }
+    let mut end = RECEIPT_OUTPUT_PREVIEW_BYTES;
+    while !output.is_char_boundary(end) {
+        end -= 1;
+    }
+    (output[..end].to_string(), true)
+}
+
+// Completes the session's pending agent receipt, if any. `output` is used as given for
+// builtins that never reach the scrollback run log; otherwise the session's last finished
+// run supplies it.
+pub fn complete_command_receipt<R: Runtime>(
+    app_handle: &AppHandle<R>,
+    session_id: &str,
+    exit_code: Option<i32>,
+    output: Option<String>,
+) {
+    let store = app_handle.state::<ReceiptStore>();
+    let Some(pending) = store.take_pending(session_id) else {
+        return;
+    };
+    let output = output.unwrap_or_else(|| {
+        app_handle
+            .state::<ScrollbackManager>()
+            .runs(session_id)
+            .ok()
+            .and_then(|runs| runs.into_iter().rev().find(|run| run.finished))
+            .map(|run| run.output)
+            .unwrap_or_default()
+    });
+    let (preview, output_truncated) = truncate_output(&output);
+    if pending.git_snapshot.is_some() || pending.named_paths.is_some() {
+        record_agent_changes(
+            app_handle,
+            session_id,
+            &pending.command,
+            &pending.id,
+            pending.git_snapshot.as_ref(),
+            pending.named_paths.as_ref(),
+        );
+    }
+
+    let receipt = CommandReceipt {
+        id: pending.id,
+        conversation_id: pending.conversation_id,
+        session_id: session_id.to_string(),
+        command: pending.command,
+        cwd: pending.cwd,
+        started_at: pending.started_at,
+        duration_ms: unix_timestamp_millis().saturating_sub(pending.started_at),
+        exit_code,
+        output_sha256: output_sha256(&output),
+        output_bytes: output.len(),
+        output: preview,
+        output_truncated,
+        replay_of: pending.replay_of,
+        undoable: pending.undo_warning.is_none(),
+        undo_warning: pending.undo_warning,
+    };
+    match store.append(&receipt) {
+        Ok(()) => {
+            let _ = app_handle.emit("command_receipt", receipt);
+        }
+        Err(e) => {
+            emit_command_error(
+                app_handle,
+                session_id,
+                format!("Failed to store receipt: {}", e),
+            );
+        }
+    }
+}
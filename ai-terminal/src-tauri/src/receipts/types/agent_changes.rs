@@ -0,0 +1,169 @@
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::path::PathBuf;
+use std::sync::Mutex;
+
+// Working tree state of a repository's dirty paths, taken around an agent command
+#[derive(Clone, Debug)]
+pub struct GitSnapshot {
+    pub root: String,
+    // Commit checked out, which clean tracked files can be restored from
+    pub head: Option<String>,
+    // Porcelain status and content fingerprint (None when missing) by repository-relative path
+    pub entries: HashMap<String, SnapshotEntry>,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct SnapshotEntry {
+    pub status: String,
+    pub fingerprint: Option<String>,
+}
+
+// Files under the paths an agent command names (arguments and redirection targets), taken
+// before it runs; covers what git does not see, e.g. ignored files or no repository at all
+#[derive(Clone, Debug)]
+pub struct NamedPathsSnapshot {
+    // Directory the command ran in, which stands in for the repository outside one
+    pub cwd: String,
+    // The named paths, absolute; walked again after the command
+    pub roots: Vec<PathBuf>,
+    // Fingerprint (None when missing) of each file under the roots
+    pub entries: HashMap<PathBuf, Option<String>>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum AgentChangeKind {
+    Created,
+    Modified,
+    Deleted,
+}
+
+#[derive(Serialize, Clone, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct AgentFileChange {
+    pub path: String,
+    pub repository: String,
+    pub kind: AgentChangeKind,
+    // In HEAD, so `git restore` brings it back; otherwise reverting a creation means deleting it
+    pub tracked: bool,
+    // Agent commands that touched the file, oldest first
+    pub commands: Vec<String>,
+    pub receipt_ids: Vec<String>,
+    pub changed_at: u64,
+}
+
+#[derive(Serialize, Clone, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct AgentChanges {
+    pub session_id: String,
+    pub created: usize,
+    pub modified: usize,
+    pub deleted: usize,
+    pub changes: Vec<AgentFileChange>,
+}
+
+// What one agent command did to a file, before merging into the session's list
+pub struct ObservedChange {
+    pub path: String,
+    pub repository: String,
+    pub kind: AgentChangeKind,
+    pub tracked: bool,
+}
+
+// Files changed by agent commands, per session, since the session started or was last cleared
+pub struct AgentChangeStore {
+    changes: Mutex<HashMap<String, Vec<AgentFileChange>>>,
+}
+
+impl AgentChangeStore {
+    pub fn new() -> Self {
+        Self {
+            changes: Mutex::new(HashMap::new()),
+        }
+    }
+
+    // Folds one command's changes in: created then modified stays created, created then
+    // deleted drops the entry, deleted then created counts as modified
+    pub fn record(
+        &self,
+        session_id: &str,
+        command: &str,
+        receipt_id: &str,
+        changed_at: u64,
+        observed: Vec<ObservedChange>,
+    ) -> Result<(), String> {
+        let mut sessions = self.changes.lock().map_err(|e| e.to_string())?;
+        let changes = sessions.entry(session_id.to_string()).or_default();
+        for change in observed {
+            let Some(index) = changes
+                .iter()
+                .position(|existing| existing.path == change.path)
+            else {
+                changes.push(AgentFileChange {
+                    path: change.path,
+                    repository: change.repository,
+                    kind: change.kind,
+                    tracked: change.tracked,
+                    commands: vec![command.to_string()],
+                    receipt_ids: vec![receipt_id.to_string()],
+                    changed_at,
+                });
+                continue;
+            };
+            let existing = &mut changes[index];
+            let kind = match (existing.kind, change.kind) {
+                (AgentChangeKind::Created, AgentChangeKind::Deleted) => None,
+                (AgentChangeKind::Created, _) => Some(AgentChangeKind::Created),
+                (AgentChangeKind::Deleted, AgentChangeKind::Created) => {
+                    Some(AgentChangeKind::Modified)
+                }
+                (_, kind) => Some(kind),
+            };
+            match kind {
+                Some(kind) => {
+                    existing.kind = kind;
+                    existing.changed_at = changed_at;
+                    existing.commands.push(command.to_string());
+                    existing.receipt_ids.push(receipt_id.to_string());
+                }
+                None => {
+                    changes.remove(index);
+                }
+            }
+        }
+        Ok(())
+    }
+
+    pub fn list(&self, session_id: &str) -> Result<Vec<AgentFileChange>, String> {
+        let sessions = self.changes.lock().map_err(|e| e.to_string())?;
+        Ok(sessions.get(session_id).cloned().unwrap_or_default())
+    }
+
+    // After an undo: files only that run touched drop out, others stop listing it
+    pub fn forget_run(&self, session_id: &str, run_id: &str) -> Result<(), String> {
+        let mut sessions = self.changes.lock().map_err(|e| e.to_string())?;
+        if let Some(changes) = sessions.get_mut(session_id) {
+            changes.retain(|change| change.receipt_ids.iter().any(|id| id != run_id));
+            for change in changes.iter_mut() {
+                while let Some(index) = change.receipt_ids.iter().position(|id| id == run_id) {
+                    change.receipt_ids.remove(index);
+                    change.commands.remove(index);
+                }
+            }
+        }
+        Ok(())
+    }
+
+    pub fn clear(&self, session_id: &str) -> Result<(), String> {
+        let mut sessions = self.changes.lock().map_err(|e| e.to_string())?;
+        sessions.remove(session_id);
+        Ok(())
+    }
+}
+
+impl Default for AgentChangeStore {
+    fn default() -> Self {
+        Self::new()
+    }
+}
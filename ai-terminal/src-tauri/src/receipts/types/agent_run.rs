@@ -0,0 +1,52 @@
+use crate::receipts::types::agent_changes::AgentChangeKind;
+use serde::{Deserialize, Serialize};
+
+// Where a changed file's content from before the agent command can be found
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum OriginalSource {
+    // Copied into the run's backup directory (it was dirty, untracked or named by the command)
+    Backup,
+    // Unchanged from the commit checked out at the time
+    Head,
+    // The file did not exist, so undoing removes it
+    Absent,
+    // Too large to back up, not a regular file, or unreadable
+    Unavailable,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct AgentRunFile {
+    // Relative to the repository, or absolute for a file under a path the command named
+    pub path: String,
+    pub kind: AgentChangeKind,
+    pub original: OriginalSource,
+    // State the command left the file in; undo leaves files changed since then alone
+    pub after_fingerprint: Option<String>,
+}
+
+// manifest.json in a run's backup directory
+#[derive(Serialize, Deserialize, Clone, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct AgentRun {
+    pub run_id: String,
+    pub session_id: String,
+    pub command: String,
+    pub repository: String,
+    pub head: Option<String>,
+    pub finished_at: u64,
+    pub undone: bool,
+    pub files: Vec<AgentRunFile>,
+}
+
+#[derive(Serialize, Clone, Debug, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct UndoResult {
+    pub run_id: String,
+    pub restored: Vec<String>,
+    pub removed: Vec<String>,
+    // Changed again after the run; pass force to restore them anyway
+    pub skipped: Vec<String>,
+    pub failed: Vec<String>,
+}
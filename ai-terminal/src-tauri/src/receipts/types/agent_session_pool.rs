@@ -0,0 +1,116 @@
+use serde::Serialize;
+use std::collections::HashMap;
+use std::sync::Mutex;
+
+// A session the agent opened for a conversation, addressed by its label
+#[derive(Serialize, Clone, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct AgentSession {
+    pub label: String,
+    pub session_id: String,
+    pub conversation_id: String,
+    pub opened_at: u64,
+}
+
+#[derive(Serialize, Clone, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct AgentSessionStatus {
+    pub label: String,
+    pub session_id: String,
+    pub cwd: String,
+    // Command still running in the session, if any
+    pub running: Option<String>,
+    pub last_command: Option<String>,
+    pub last_exit_code: Option<i32>,
+    pub opened_at: u64,
+}
+
+#[derive(Serialize, Clone, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct AgentSessionOutput {
+    pub label: String,
+    pub session_id: String,
+    pub command: Option<String>,
+    pub finished: bool,
+    pub exit_code: Option<i32>,
+    // Last lines of the most recent command's output, as plain text
+    pub output: String,
+    pub truncated: bool,
+}
+
+// Sessions opened with open_agent_session, by conversation, so the agent can run a server in
+// one, tests in another and tail logs in a third
+pub struct AgentSessionPool {
+    sessions: Mutex<HashMap<String, Vec<AgentSession>>>,
+}
+
+impl AgentSessionPool {
+    pub fn new() -> Self {
+        Self {
+            sessions: Mutex::new(HashMap::new()),
+        }
+    }
+
+    pub fn insert(&self, session: AgentSession, max: usize) -> Result<(), String> {
+        let mut conversations = self.sessions.lock().map_err(|e| e.to_string())?;
+        let sessions = conversations
+            .entry(session.conversation_id.clone())
+            .or_default();
+        if sessions
+            .iter()
+            .any(|existing| existing.label == session.label)
+        {
+            return Err(format!(
+                "A session labelled '{}' is already open",
+                session.label
+            ));
+        }
+        if sessions.len() >= max {
+            return Err(format!(
+                "At most {} agent sessions can be open at once; close one first",
+                max
+            ));
+        }
+        sessions.push(session);
+        Ok(())
+    }
+
+    pub fn get(&self, conversation_id: &str, label: &str) -> Result<AgentSession, String> {
+        let conversations = self.sessions.lock().map_err(|e| e.to_string())?;
+        conversations
+            .get(conversation_id)
+            .and_then(|sessions| sessions.iter().find(|session| session.label == label))
+            .cloned()
+            .ok_or_else(|| format!("No agent session labelled '{}'", label))
+    }
+
+    // Oldest first
+    pub fn list(&self, conversation_id: &str) -> Result<Vec<AgentSession>, String> {
+        let conversations = self.sessions.lock().map_err(|e| e.to_string())?;
+        Ok(conversations
+            .get(conversation_id)
+            .cloned()
+            .unwrap_or_default())
+    }
+
+    pub fn remove(&self, conversation_id: &str, label: &str) -> Result<AgentSession, String> {
+        let mut conversations = self.sessions.lock().map_err(|e| e.to_string())?;
+        let missing = || format!("No agent session labelled '{}'", label);
+        let sessions = conversations.get_mut(conversation_id).ok_or_else(missing)?;
+        let index = sessions
+            .iter()
+            .position(|session| session.label == label)
+            .ok_or_else(missing)?;
+        let session = sessions.remove(index);
+        if sessions.is_empty() {
+            conversations.remove(conversation_id);
+        }
+        Ok(session)
+    }
+}
+
+impl Default for AgentSessionPool {
+    fn default() -> Self {
+        Self::new()
+    }
+}
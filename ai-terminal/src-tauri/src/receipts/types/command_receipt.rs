@@ -0,0 +1,50 @@
+use crate::receipts::types::agent_changes::{GitSnapshot, NamedPathsSnapshot};
+use serde::{Deserialize, Serialize};
+
+// What actually happened when an agent ran a command, kept with the conversation so the
+// AI's account of it can be checked afterwards
+#[derive(Serialize, Deserialize, Clone, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct CommandReceipt {
+    pub id: String,
+    pub conversation_id: String,
+    pub session_id: String,
+    pub command: String,
+    pub cwd: String,
+    pub started_at: u64,
+    pub duration_ms: u64,
+    pub exit_code: Option<i32>,
+    // SHA-256 of the captured output, hex encoded
+    pub output_sha256: String,
+    pub output_bytes: usize,
+    pub output: String,
+    pub output_truncated: bool,
+    // Receipt this run re-executed, when it was a replay
+    pub replay_of: Option<String>,
+    // Whether undo_agent_run can put back what the command changed, and if not why
+    #[serde(default = "default_true")]
+    pub undoable: bool,
+    #[serde(default)]
+    pub undo_warning: Option<String>,
+}
+
+fn default_true() -> bool {
+    true
+}
+
+// A receipt opened by execute_agent_command and completed when the command exits
+#[derive(Clone, Debug)]
+pub struct PendingReceipt {
+    pub id: String,
+    pub conversation_id: String,
+    pub command: String,
+    pub cwd: String,
+    pub started_at: u64,
+    pub replay_of: Option<String>,
+    // Repository state before the command, to work out which files it changed
+    pub git_snapshot: Option<GitSnapshot>,
+    // State of the paths the command names, for changes git does not see
+    pub named_paths: Option<NamedPathsSnapshot>,
+    // Why the run cannot be undone, when it cannot
+    pub undo_warning: Option<String>,
+}
@@ -0,0 +1,5 @@
+pub mod agent_changes;
+pub mod agent_run;
+pub mod agent_session_pool;
+pub mod command_receipt;
+pub mod receipt_store;
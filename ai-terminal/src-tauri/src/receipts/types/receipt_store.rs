@@ -0,0 +1,78 @@
+use crate::receipts::types::command_receipt::{CommandReceipt, PendingReceipt};
+use crate::utils::config_utils::app_data_dir;
+use std::collections::HashMap;
+use std::fs::{self, OpenOptions};
+use std::io::Write;
+use std::path::PathBuf;
+use std::sync::Mutex;
+
+const RECEIPTS_DIR: &str = "receipts";
+
+// Receipts are written to one JSONL file per conversation under the data directory
+pub struct ReceiptStore {
+    // Agent commands still running, by session (a session runs one command at a time)
+    pending: Mutex<HashMap<String, PendingReceipt>>,
+}
+
+fn receipts_path(conversation_id: &str) -> Result<PathBuf, String> {
+    if conversation_id.is_empty()
+        || !conversation_id
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_')
+    {
+        return Err(format!("Invalid conversation id '{}'", conversation_id));
+    }
+    let dir = app_data_dir()?.join(RECEIPTS_DIR);
+    fs::create_dir_all(&dir).map_err(|e| format!("Failed to create {}: {}", dir.display(), e))?;
+    Ok(dir.join(format!("{}.jsonl", conversation_id)))
+}
+
+impl ReceiptStore {
+    pub fn new() -> Self {
+        Self {
+            pending: Mutex::new(HashMap::new()),
+        }
+    }
+
+    pub fn begin(&self, session_id: &str, pending: PendingReceipt) -> Result<(), String> {
+        // Validate up front so a bad id fails the call instead of losing the receipt later
+        receipts_path(&pending.conversation_id)?;
+        let mut receipts = self.pending.lock().map_err(|e| e.to_string())?;
+        receipts.insert(session_id.to_string(), pending);
+        Ok(())
+    }
+
+    pub fn take_pending(&self, session_id: &str) -> Option<PendingReceipt> {
+        self.pending.lock().ok()?.remove(session_id)
+    }
+
+    pub fn append(&self, receipt: &CommandReceipt) -> Result<(), String> {
+        let path = receipts_path(&receipt.conversation_id)?;
+        let line = serde_json::to_string(receipt).map_err(|e| e.to_string())?;
+        let mut file = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&path)
+            .map_err(|e| format!("Failed to open {}: {}", path.display(), e))?;
+        writeln!(file, "{}", line).map_err(|e| format!("Failed to write receipt: {}", e))
+    }
+
+    pub fn list(&self, conversation_id: &str) -> Result<Vec<CommandReceipt>, String> {
+        let path = receipts_path(conversation_id)?;
+        let contents = match fs::read_to_string(&path) {
+            Ok(contents) => contents,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(Vec::new()),
+            Err(e) => return Err(format!("Failed to read {}: {}", path.display(), e)),
+        };
+        Ok(contents
+            .lines()
+            .filter_map(|line| serde_json::from_str(line).ok())
+            .collect())
+    }
+}
+
+impl Default for ReceiptStore {
+    fn default() -> Self {
+        Self::new()
+    }
+}
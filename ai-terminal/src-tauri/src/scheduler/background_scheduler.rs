@@ -0,0 +1,136 @@
+use crate::command::constants::{
+    DAILY_DIGEST_CHECK_INTERVAL_MS, DND_CHECK_INTERVAL_MS, GIT_FETCH_TASK_INTERVAL_MS,
+    IDLE_LOCK_CHECK_INTERVAL_MS, MAX_CONCURRENT_BACKGROUND_TASKS, OLLAMA_HEALTH_INTERVAL_MS,
+    PATH_CACHE_REFRESH_INTERVAL_MS, POWER_CHECK_INTERVAL_MS, SCHEDULER_TICK_MS,
+    STATE_SNAPSHOT_INTERVAL_MS, TLDR_REFRESH_TASK_INTERVAL_MS,
+};
+use crate::command::docs::cheatsheets::refresh_cheatsheets;
+use crate::command::git_commands::background_fetch::fetch_session_repositories;
+use crate::command::recovery::state_snapshot::write_state_snapshot;
+use crate::history::daily_digest::run_daily_digest;
+use crate::notifications::notification_commands::poll_dnd;
+use crate::ollama::health::check_ollama_health;
+use crate::privacy::idle_lock::lock_idle_windows;
+use crate::scheduler::types::task_scheduler::{
+    BackgroundScheduler, BackgroundTaskStatus, BatteryPolicy,
+};
+use crate::utils::path_executables::refresh_path_executables;
+use crate::utils::power::on_battery_power;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use tauri::{command, AppHandle, Manager, Runtime};
+
+// Periodic work owned by the scheduler; new background features register here instead of
+// starting their own timer threads
+fn register_default_tasks<R: Runtime>(scheduler: &BackgroundScheduler<R>) {
+    scheduler.register(
+        "idle_lock",
+        IDLE_LOCK_CHECK_INTERVAL_MS,
+        0,
+        BatteryPolicy::Run,
+        Arc::new(lock_idle_windows),
+    );
+    scheduler.register(
+        "do_not_disturb",
+        DND_CHECK_INTERVAL_MS,
+        1_000,
+        BatteryPolicy::Run,
+        Arc::new(poll_dnd),
+    );
+    scheduler.register(
+        "git_fetch",
+        GIT_FETCH_TASK_INTERVAL_MS,
+        GIT_FETCH_TASK_INTERVAL_MS / 2,
+        BatteryPolicy::Skip,
+        Arc::new(fetch_session_repositories),
+    );
+    scheduler.register(
+        "path_executables",
+        PATH_CACHE_REFRESH_INTERVAL_MS,
+        30_000,
+        BatteryPolicy::Slow,
+        Arc::new(|_: &AppHandle<R>| {
+            refresh_path_executables();
+            Ok(())
+        }),
+    );
+    scheduler.register(
+        "state_snapshot",
+        STATE_SNAPSHOT_INTERVAL_MS,
+        0,
+        BatteryPolicy::Slow,
+        Arc::new(write_state_snapshot),
+    );
+    scheduler.register(
+        "ollama_health",
+        OLLAMA_HEALTH_INTERVAL_MS,
+        10_000,
+        BatteryPolicy::Slow,
+        Arc::new(check_ollama_health),
+    );
+    scheduler.register(
+        "tldr_refresh",
+        TLDR_REFRESH_TASK_INTERVAL_MS,
+        60_000,
+        BatteryPolicy::Skip,
+        Arc::new(refresh_cheatsheets),
+    );
+    scheduler.register(
+        "daily_digest",
+        DAILY_DIGEST_CHECK_INTERVAL_MS,
+        0,
+        BatteryPolicy::Run,
+        Arc::new(run_daily_digest),
+    );
+}
+
+// Starts the single ticker thread; due tasks run on short-lived workers, at most
+// MAX_CONCURRENT_BACKGROUND_TASKS at a time
+pub fn start_background_scheduler<R: Runtime>(app_handle: &AppHandle<R>) {
+    register_default_tasks(&app_handle.state::<BackgroundScheduler<R>>());
+    let app_handle = app_handle.clone();
+    std::thread::spawn(move || {
+        let on_battery = Arc::new(AtomicBool::new(on_battery_power()));
+        let mut power_checked = Instant::now();
+        loop {
+            std::thread::sleep(Duration::from_millis(SCHEDULER_TICK_MS));
+            if power_checked.elapsed() >= Duration::from_millis(POWER_CHECK_INTERVAL_MS) {
+                on_battery.store(on_battery_power(), Ordering::Relaxed);
+                power_checked = Instant::now();
+            }
+            let due = app_handle.state::<BackgroundScheduler<R>>().take_due(
+                MAX_CONCURRENT_BACKGROUND_TASKS,
+                on_battery.load(Ordering::Relaxed),
+            );
+            for (name, job) in due {
+                let app_handle = app_handle.clone();
+                let on_battery = on_battery.clone();
+                std::thread::spawn(move || {
+                    let result = job(&app_handle);
+                    app_handle.state::<BackgroundScheduler<R>>().finish(
+                        name,
+                        result,
+                        on_battery.load(Ordering::Relaxed),
+                    );
+                });
+            }
+        }
+    });
+}
+
+#[command]
+pub fn list_background_tasks<R: Runtime>(
+    app_handle: AppHandle<R>,
+) -> Result<Vec<BackgroundTaskStatus>, String> {
+    app_handle.state::<BackgroundScheduler<R>>().statuses()
+}
+
+// Runs a task on the next tick, e.g. a git fetch after the user switched branches
+#[command]
+pub fn run_background_task<R: Runtime>(
+    name: String,
+    app_handle: AppHandle<R>,
+) -> Result<(), String> {
+    app_handle.state::<BackgroundScheduler<R>>().trigger(&name)
+}
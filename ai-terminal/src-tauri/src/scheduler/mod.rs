@@ -0,0 +1,3 @@
+pub mod background_scheduler;
+pub mod startup;
+pub mod types;
@@ -0,0 +1,64 @@
+use crate::command::constants::STARTUP_WARMUP_DELAY_MS;
+use crate::command::environment::session_tmpdir::sweep_stale_session_tmpdirs;
+use crate::history::types::history_store::HistoryStore;
+use crate::ollama::health::check_ollama_health;
+use crate::scheduler::types::startup_profiler::{StartupPhase, StartupProfiler};
+use crate::utils::path_executables::list_path_executables;
+use serde::Serialize;
+use std::thread;
+use std::time::Duration;
+use tauri::{command, AppHandle, Manager, Runtime, State};
+
+#[derive(Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct StartupProfile {
+    pub phases: Vec<StartupPhase>,
+    // None until setup has finished
+    pub ready_ms: Option<f64>,
+    // Time spent in phases that ran before the first window
+    pub blocking_ms: f64,
+}
+
+// Loads what startup no longer waits for: the history file, the PATH executable cache and the
+// first Ollama probe, and sweeps session temp dirs left by instances that crashed. Runs once,
+// shortly after the first window is up, so it does not compete with it; each piece also loads on
+// demand if it is needed sooner.
+pub fn start_startup_warmup<R: Runtime>(app_handle: &AppHandle<R>) {
+    let app_handle = app_handle.clone();
+    thread::spawn(move || {
+        thread::sleep(Duration::from_millis(STARTUP_WARMUP_DELAY_MS));
+        let profiler = app_handle.state::<StartupProfiler>();
+        profiler.measure_background("history_load", || {
+            drop(app_handle.state::<HistoryStore>().entries())
+        });
+        profiler.measure_background("path_executables", list_path_executables);
+        profiler.measure_background("tmpdir_sweep", sweep_stale_session_tmpdirs);
+        // Unreachable is a result here, not an error; ollama_health reports it
+        let _ = profiler.measure_background("ollama_probe", || check_ollama_health(&app_handle));
+    });
+}
+
+#[command]
+pub fn get_startup_profile(
+    startup_profiler: State<'_, StartupProfiler>,
+) -> Result<StartupProfile, String> {
+    let mut phases = startup_profiler
+        .phases
+        .lock()
+        .map_err(|e| e.to_string())?
+        .clone();
+    phases.sort_by(|a, b| a.started_ms.total_cmp(&b.started_ms));
+    let blocking_ms = phases
+        .iter()
+        .filter(|phase| !phase.background)
+        .map(|phase| phase.duration_ms)
+        .sum();
+    Ok(StartupProfile {
+        phases,
+        ready_ms: *startup_profiler
+            .ready_ms
+            .lock()
+            .map_err(|e| e.to_string())?,
+        blocking_ms,
+    })
+}
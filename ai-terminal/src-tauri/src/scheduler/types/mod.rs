@@ -0,0 +1,2 @@
+pub mod startup_profiler;
+pub mod task_scheduler;
@@ -0,0 +1,71 @@
+use serde::Serialize;
+use std::sync::Mutex;
+use std::time::Instant;
+
+#[derive(Serialize, Clone, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct StartupPhase {
+    pub name: String,
+    // Milliseconds since the profiler was created, at the top of main
+    pub started_ms: f64,
+    pub duration_ms: f64,
+    // Deferred work that ran after the first window was up
+    pub background: bool,
+}
+
+// Timings of app startup: manager construction, PATH probing, setup steps and the warm-up
+// deferred to after the first window
+pub struct StartupProfiler {
+    started: Instant,
+    pub phases: Mutex<Vec<StartupPhase>>,
+    // When setup finished and the first window was shown
+    pub ready_ms: Mutex<Option<f64>>,
+}
+
+impl StartupProfiler {
+    pub fn new() -> Self {
+        Self {
+            started: Instant::now(),
+            phases: Mutex::new(Vec::new()),
+            ready_ms: Mutex::new(None),
+        }
+    }
+
+    pub fn elapsed_ms(&self) -> f64 {
+        self.started.elapsed().as_secs_f64() * 1000.0
+    }
+
+    fn record<T>(&self, name: &str, background: bool, step: impl FnOnce() -> T) -> T {
+        let started_ms = self.elapsed_ms();
+        let result = step();
+        if let Ok(mut phases) = self.phases.lock() {
+            phases.push(StartupPhase {
+                name: name.to_string(),
+                started_ms,
+                duration_ms: self.elapsed_ms() - started_ms,
+                background,
+            });
+        }
+        result
+    }
+
+    pub fn measure<T>(&self, name: &str, step: impl FnOnce() -> T) -> T {
+        self.record(name, false, step)
+    }
+
+    pub fn measure_background<T>(&self, name: &str, step: impl FnOnce() -> T) -> T {
+        self.record(name, true, step)
+    }
+
+    pub fn mark_ready(&self) {
+        if let Ok(mut ready_ms) = self.ready_ms.lock() {
+            ready_ms.get_or_insert(self.elapsed_ms());
+        }
+    }
+}
+
+impl Default for StartupProfiler {
+    fn default() -> Self {
+        Self::new()
+    }
+}
@@ -0,0 +1,166 @@
+use crate::command::constants::BATTERY_INTERVAL_MULTIPLIER;
+use crate::utils::config_utils::unix_timestamp_millis;
+use serde::Serialize;
+use std::sync::{Arc, Mutex};
+use tauri::{AppHandle, Runtime, Wry};
+
+pub type BackgroundJob<R> = Arc<dyn Fn(&AppHandle<R>) -> Result<(), String> + Send + Sync>;
+
+// How a task behaves while the machine runs on battery
+#[derive(Serialize, Clone, Copy, Debug, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum BatteryPolicy {
+    // Cheap or user-facing checks run as usual
+    Run,
+    // Run BATTERY_INTERVAL_MULTIPLIER times less often
+    Slow,
+    // Network or disk heavy work waits for AC power
+    Skip,
+}
+
+struct ScheduledTask<R: Runtime> {
+    name: &'static str,
+    interval_ms: u64,
+    jitter_ms: u64,
+    battery_policy: BatteryPolicy,
+    job: BackgroundJob<R>,
+    next_run: u64,
+    last_run: Option<u64>,
+    last_error: Option<String>,
+    running: bool,
+}
+
+#[derive(Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct BackgroundTaskStatus {
+    pub name: String,
+    pub interval_ms: u64,
+    pub battery_policy: BatteryPolicy,
+    pub next_run: u64,
+    pub last_run: Option<u64>,
+    pub last_error: Option<String>,
+    pub running: bool,
+}
+
+// Random delay in 0..=jitter_ms so tasks (and app instances) do not fire in lockstep
+fn jitter(jitter_ms: u64) -> u64 {
+    if jitter_ms == 0 {
+        return 0;
+    }
+    (uuid::Uuid::new_v4().as_u128() % u128::from(jitter_ms + 1)) as u64
+}
+
+// All periodic background work, driven by a single ticker thread
+pub struct BackgroundScheduler<R: Runtime = Wry> {
+    tasks: Mutex<Vec<ScheduledTask<R>>>,
+}
+
+impl<R: Runtime> BackgroundScheduler<R> {
+    pub fn new() -> Self {
+        Self {
+            tasks: Mutex::new(Vec::new()),
+        }
+    }
+
+    // Adds a task; its first run happens on the next tick, spread out by its jitter
+    pub fn register(
+        &self,
+        name: &'static str,
+        interval_ms: u64,
+        jitter_ms: u64,
+        battery_policy: BatteryPolicy,
+        job: BackgroundJob<R>,
+    ) {
+        if let Ok(mut tasks) = self.tasks.lock() {
+            tasks.retain(|task| task.name != name);
+            tasks.push(ScheduledTask {
+                name,
+                interval_ms,
+                jitter_ms,
+                battery_policy,
+                job,
+                next_run: unix_timestamp_millis() + jitter(jitter_ms),
+                last_run: None,
+                last_error: None,
+                running: false,
+            });
+        }
+    }
+
+    // Marks up to `slots` due tasks as running and hands them out, most overdue first
+    pub fn take_due(
+        &self,
+        slots: usize,
+        on_battery: bool,
+    ) -> Vec<(&'static str, BackgroundJob<R>)> {
+        let Ok(mut tasks) = self.tasks.lock() else {
+            return Vec::new();
+        };
+        let now = unix_timestamp_millis();
+        let running = tasks.iter().filter(|task| task.running).count();
+        let mut due: Vec<&mut ScheduledTask<R>> = tasks
+            .iter_mut()
+            .filter(|task| !task.running && task.next_run <= now)
+            .filter(|task| !(on_battery && task.battery_policy == BatteryPolicy::Skip))
+            .collect();
+        due.sort_by_key(|task| task.next_run);
+        due.into_iter()
+            .take(slots.saturating_sub(running))
+            .map(|task| {
+                task.running = true;
+                (task.name, task.job.clone())
+            })
+            .collect()
+    }
+
+    pub fn finish(&self, name: &str, result: Result<(), String>, on_battery: bool) {
+        let Ok(mut tasks) = self.tasks.lock() else {
+            return;
+        };
+        if let Some(task) = tasks.iter_mut().find(|task| task.name == name) {
+            let now = unix_timestamp_millis();
+            let interval = if on_battery && task.battery_policy == BatteryPolicy::Slow {
+                task.interval_ms * BATTERY_INTERVAL_MULTIPLIER
+            } else {
+                task.interval_ms
+            };
+            task.running = false;
+            task.last_run = Some(now);
+            task.last_error = result.err();
+            task.next_run = now + interval + jitter(task.jitter_ms);
+        }
+    }
+
+    // Makes a task due on the next tick
+    pub fn trigger(&self, name: &str) -> Result<(), String> {
+        let mut tasks = self.tasks.lock().map_err(|e| e.to_string())?;
+        let task = tasks
+            .iter_mut()
+            .find(|task| task.name == name)
+            .ok_or_else(|| format!("No background task named '{}'", name))?;
+        task.next_run = 0;
+        Ok(())
+    }
+
+    pub fn statuses(&self) -> Result<Vec<BackgroundTaskStatus>, String> {
+        let tasks = self.tasks.lock().map_err(|e| e.to_string())?;
+        Ok(tasks
+            .iter()
+            .map(|task| BackgroundTaskStatus {
+                name: task.name.to_string(),
+                interval_ms: task.interval_ms,
+                battery_policy: task.battery_policy,
+                next_run: task.next_run,
+                last_run: task.last_run,
+                last_error: task.last_error.clone(),
+                running: task.running,
+            })
+            .collect())
+    }
+}
+
+impl<R: Runtime> Default for BackgroundScheduler<R> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
@@ -0,0 +1,2 @@
+pub mod serial_commands;
+pub mod types;
@@ -0,0 +1,245 @@
+use crate::command::core::pty::{PtyExitEvent, PtyOutputEvent};
+use crate::command::output::post_processors::process_output;
+use crate::command::output::progress::{finish_progress, track_progress};
+use crate::command::types::scrollback_manager::{OutputStream, ScrollbackManager};
+use crate::command::windows::session_windows::emit_session_event;
+use crate::serial::types::serial_manager::{SerialManager, SerialSession};
+use serde::Serialize;
+use serialport::SerialPortType;
+use std::io::{ErrorKind, Read, Write};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::Duration;
+use tauri::{command, AppHandle, Manager, Runtime, State};
+
+// Read timeout for the reader thread; bounds how long closing a session takes to be noticed
+const SERIAL_READ_TIMEOUT_MS: u64 = 100;
+
+#[derive(Serialize, Clone, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct SerialPortSummary {
+    pub name: String,
+    // usb, pci, bluetooth or unknown
+    pub kind: String,
+    pub description: Option<String>,
+    pub vid: Option<u16>,
+    pub pid: Option<u16>,
+    pub serial_number: Option<String>,
+}
+
+#[command]
+pub fn list_serial_ports() -> Result<Vec<SerialPortSummary>, String> {
+    let ports =
+        serialport::available_ports().map_err(|e| format!("Failed to list serial ports: {}", e))?;
+    Ok(ports
+        .into_iter()
+        .map(|port| match port.port_type {
+            SerialPortType::UsbPort(usb) => SerialPortSummary {
+                name: port.port_name,
+                kind: "usb".to_string(),
+                description: match (usb.manufacturer, usb.product) {
+                    (Some(manufacturer), Some(product)) => {
+                        Some(format!("{} {}", manufacturer, product))
+                    }
+                    (manufacturer, product) => product.or(manufacturer),
+                },
+                vid: Some(usb.vid),
+                pid: Some(usb.pid),
+                serial_number: usb.serial_number,
+            },
+            other => SerialPortSummary {
+                name: port.port_name,
+                kind: match other {
+                    SerialPortType::PciPort => "pci",
+                    SerialPortType::BluetoothPort => "bluetooth",
+                    _ => "unknown",
+                }
+                .to_string(),
+                description: None,
+                vid: None,
+                pid: None,
+                serial_number: None,
+            },
+        })
+        .collect())
+}
+
+// Splits off the longest valid UTF-8 prefix, keeping an incomplete trailing sequence for the
+// next read; invalid bytes are replaced rather than stalling the stream
+fn take_utf8(pending: &mut Vec<u8>) -> String {
+    match std::str::from_utf8(pending) {
+        Ok(text) => {
+            let text = text.to_string();
+            pending.clear();
+            text
+        }
+        Err(err) if err.error_len().is_none() => {
+            let valid_up_to = err.valid_up_to();
+            let text = String::from_utf8_lossy(&pending[..valid_up_to]).to_string();
+            pending.drain(..valid_up_to);
+            text
+        }
+        Err(_) => {
+            let text = String::from_utf8_lossy(pending).to_string();
+            pending.clear();
+            text
+        }
+    }
+}
+
+// Opens a serial port as a terminal session: output arrives as pty_output events and lands in
+// the session scrollback exactly like a local shell
+#[command]
+pub fn open_serial_session<R: Runtime>(
+    session_id: String,
+    port: String,
+    baud: u32,
+    app_handle: AppHandle<R>,
+    serial_manager: State<'_, SerialManager>,
+) -> Result<(), String> {
+    let mut sessions = serial_manager.sessions.lock().map_err(|e| e.to_string())?;
+    if sessions.contains_key(&session_id) {
+        return Err(format!("Serial session '{}' already exists", session_id));
+    }
+
+    let serial = serialport::new(&port, baud)
+        .timeout(Duration::from_millis(SERIAL_READ_TIMEOUT_MS))
+        .open()
+        .map_err(|e| format!("Failed to open {}: {}", port, e))?;
+    let mut reader = serial
+        .try_clone()
+        .map_err(|e| format!("Failed to clone {}: {}", port, e))?;
+    let closed = Arc::new(AtomicBool::new(false));
+
+    sessions.insert(
+        session_id.clone(),
+        SerialSession {
+            port_name: port,
+            baud_rate: baud,
+            port: Arc::new(Mutex::new(serial)),
+            closed: closed.clone(),
+        },
+    );
+    drop(sessions);
+
+    thread::spawn(move || {
+        let mut buffer = [0u8; 4096];
+        let mut pending: Vec<u8> = Vec::new();
+        let success = loop {
+            if closed.load(Ordering::SeqCst) {
+                break true;
+            }
+            match reader.read(&mut buffer) {
+                Ok(0) => break true,
+                Ok(n) => {
+                    pending.extend_from_slice(&buffer[..n]);
+                    let data = take_utf8(&mut pending);
+                    if data.is_empty() {
+                        continue;
+                    }
+                    let data = process_output(&app_handle, &session_id, OutputStream::Pty, data);
+                    app_handle.state::<ScrollbackManager>().append(
+                        &session_id,
+                        OutputStream::Pty,
+                        &data,
+                    );
+                    track_progress(&app_handle, &session_id, &data);
+                    emit_session_event(
+                        &app_handle,
+                        &session_id,
+                        "pty_output",
+                        PtyOutputEvent {
+                            session_id: session_id.clone(),
+                            data,
+                        },
+                    );
+                }
+                Err(e) if e.kind() == ErrorKind::TimedOut || e.kind() == ErrorKind::Interrupted => {
+                }
+                // Device unplugged or similar
+                Err(_) => break false,
+            }
+        };
+
+        finish_progress(&app_handle, &session_id);
+        if let Ok(mut sessions) = app_handle.state::<SerialManager>().sessions.lock() {
+            sessions.remove(&session_id);
+        }
+        emit_session_event(
+            &app_handle,
+            &session_id,
+            "pty_exit",
+            PtyExitEvent {
+                session_id: session_id.clone(),
+                success,
+            },
+        );
+    });
+
+    Ok(())
+}
+
+fn with_port<T>(
+    serial_manager: &SerialManager,
+    session_id: &str,
+    f: impl FnOnce(&mut Box<dyn serialport::SerialPort>) -> Result<T, String>,
+) -> Result<T, String> {
+    let port = {
+        let sessions = serial_manager.sessions.lock().map_err(|e| e.to_string())?;
+        sessions
+            .get(session_id)
+            .map(|session| session.port.clone())
+            .ok_or_else(|| format!("Serial session '{}' not found", session_id))?
+    };
+    let mut port = port.lock().map_err(|e| e.to_string())?;
+    f(&mut port)
+}
+
+#[command]
+pub fn serial_write(
+    session_id: String,
+    data: String,
+    serial_manager: State<'_, SerialManager>,
+) -> Result<(), String> {
+    with_port(&serial_manager, &session_id, |port| {
+        port.write_all(data.as_bytes())
+            .map_err(|e| format!("Failed to write to serial port: {}", e))?;
+        port.flush()
+            .map_err(|e| format!("Failed to flush serial port: {}", e))
+    })
+}
+
+// Drives the modem control lines, e.g. toggling DTR to reset an Arduino or ESP32
+#[command]
+pub fn set_serial_signals(
+    session_id: String,
+    dtr: Option<bool>,
+    rts: Option<bool>,
+    serial_manager: State<'_, SerialManager>,
+) -> Result<(), String> {
+    with_port(&serial_manager, &session_id, |port| {
+        if let Some(dtr) = dtr {
+            port.write_data_terminal_ready(dtr)
+                .map_err(|e| format!("Failed to set DTR: {}", e))?;
+        }
+        if let Some(rts) = rts {
+            port.write_request_to_send(rts)
+                .map_err(|e| format!("Failed to set RTS: {}", e))?;
+        }
+        Ok(())
+    })
+}
+
+#[command]
+pub fn close_serial_session(
+    session_id: String,
+    serial_manager: State<'_, SerialManager>,
+) -> Result<(), String> {
+    let sessions = serial_manager.sessions.lock().map_err(|e| e.to_string())?;
+    if let Some(session) = sessions.get(&session_id) {
+        // The reader thread notices within one read timeout, releases the port and emits pty_exit
+        session.closed.store(true, Ordering::SeqCst);
+    }
+    Ok(())
+}
@@ -0,0 +1,30 @@
+use serialport::SerialPort;
+use std::collections::HashMap;
+use std::sync::atomic::AtomicBool;
+use std::sync::{Arc, Mutex};
+
+// An open serial console; the reader thread owns a clone of `port` and stops once `closed` is set
+pub struct SerialSession {
+    pub port_name: String,
+    pub baud_rate: u32,
+    pub port: Arc<Mutex<Box<dyn SerialPort>>>,
+    pub closed: Arc<AtomicBool>,
+}
+
+pub struct SerialManager {
+    pub sessions: Mutex<HashMap<String, SerialSession>>,
+}
+
+impl SerialManager {
+    pub fn new() -> Self {
+        Self {
+            sessions: Mutex::new(HashMap::new()),
+        }
+    }
+}
+
+impl Default for SerialManager {
+    fn default() -> Self {
+        Self::new()
+    }
+}
@@ -0,0 +1,288 @@
+use crate::command::types::learned_command_store::{LearnedCommandStore, LEARNED_COMMANDS_FILE};
+use crate::command::types::macro_manager::{MacroManager, MACROS_CONFIG_FILE};
+use crate::history::types::history_store::{HistoryStore, DIRECTORY_HISTORY_FILE, HISTORY_FILE};
+use crate::history::types::sync_config::HISTORY_SYNC_CONFIG_FILE;
+use crate::hooks::types::hook_manager::{HookManager, HOOKS_CONFIG_FILE};
+use crate::layout::types::layout_manager::{
+    LayoutManager, LAYOUTS_CONFIG_FILE, SESSION_META_CONFIG_FILE,
+};
+use crate::settings::types::settings_manager::{SettingsManager, SETTINGS_CONFIG_FILE};
+use crate::utils::config_utils::{
+    app_config_dir, app_data_dir, load_json_config, unix_timestamp_millis,
+};
+use crate::utils::crypto::{open, seal, Cipher, SealedData};
+use base64::engine::general_purpose::STANDARD;
+use base64::Engine;
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::{Component, Path, PathBuf};
+use tauri::{command, AppHandle, Manager, Runtime};
+
+const BACKUP_FORMAT: &str = "ai-terminal-backup";
+const BACKUP_VERSION: u32 = 1;
+const BACKUP_NAME: &str = "backup";
+// Data files worth moving to another machine; logs, receipts and caches stay behind
+const BACKED_UP_DATA_FILES: [&str; 2] = [HISTORY_FILE, DIRECTORY_HISTORY_FILE];
+// Config files that belong to one machine. The history sync config holds the device id that
+// keys this machine's synced entries; a copy on another machine would write over them.
+const MACHINE_CONFIG_FILES: [&str; 1] = [HISTORY_SYNC_CONFIG_FILE];
+
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+enum BackupRoot {
+    // app_config_dir: settings, workspaces, macros, hooks, ...
+    Config,
+    // app_data_dir
+    Data,
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct BackupFile {
+    root: BackupRoot,
+    // Relative to the root, with `/` separators
+    path: String,
+    contents: String,
+}
+
+// Decrypted contents of a backup
+#[derive(Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct BackupArchive {
+    created_at: u64,
+    files: Vec<BackupFile>,
+}
+
+// The backup file, encrypted with the passphrase so it can be carried over untrusted storage
+#[derive(Serialize, Deserialize)]
+struct EncryptedBackup {
+    format: String,
+    version: u32,
+    #[serde(flatten)]
+    sealed: SealedData,
+}
+
+#[derive(Serialize, Clone, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct BackupReport {
+    pub path: String,
+    pub files: usize,
+    pub bytes: u64,
+    pub created_at: u64,
+}
+
+#[derive(Serialize, Clone, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct RestoreReport {
+    pub files: usize,
+    // When the backup was taken
+    pub created_at: u64,
+}
+
+fn encrypt_backup(passphrase: &str, plaintext: &[u8]) -> Result<Vec<u8>, String> {
+    serde_json::to_vec(&EncryptedBackup {
+        format: BACKUP_FORMAT.to_string(),
+        version: BACKUP_VERSION,
+        sealed: seal(Cipher::ChaCha20Poly1305, passphrase, plaintext, BACKUP_NAME)?,
+    })
+    .map_err(|e| e.to_string())
+}
+
+fn decrypt_backup(passphrase: &str, data: &[u8]) -> Result<Vec<u8>, String> {
+    let backup: EncryptedBackup = serde_json::from_slice(data)
+        .ok()
+        .filter(|backup: &EncryptedBackup| backup.format == BACKUP_FORMAT)
+        .ok_or("The file is not an AI Terminal backup")?;
+    if backup.version != BACKUP_VERSION {
+        return Err(format!(
+            "The backup uses format version {}, which this version cannot read",
+            backup.version
+        ));
+    }
+    open(passphrase, &backup.sealed, BACKUP_NAME)
+}
+
+fn relative_path(root: &Path, path: &Path) -> Option<String> {
+    let parts: Vec<String> = path
+        .strip_prefix(root)
+        .ok()?
+        .components()
+        .map(|component| component.as_os_str().to_string_lossy().to_string())
+        .collect();
+    Some(parts.join("/"))
+}
+
+// Every regular file under `dir` apart from MACHINE_CONFIG_FILES, recursively; symlinks are
+// not followed
+fn collect_config_files(
+    root: &Path,
+    dir: &Path,
+    files: &mut Vec<BackupFile>,
+) -> Result<(), String> {
+    let entries =
+        fs::read_dir(dir).map_err(|e| format!("Failed to read {}: {}", dir.display(), e))?;
+    for entry in entries.flatten() {
+        let path = entry.path();
+        let Ok(file_type) = entry.file_type() else {
+            continue;
+        };
+        if file_type.is_dir() {
+            collect_config_files(root, &path, files)?;
+        } else if file_type.is_file() {
+            let Some(relative) = relative_path(root, &path) else {
+                continue;
+            };
+            if MACHINE_CONFIG_FILES.contains(&relative.as_str()) {
+                continue;
+            }
+            let contents =
+                fs::read(&path).map_err(|e| format!("Failed to read {}: {}", path.display(), e))?;
+            files.push(BackupFile {
+                root: BackupRoot::Config,
+                path: relative,
+                contents: STANDARD.encode(contents),
+            });
+        }
+    }
+    Ok(())
+}
+
+fn collect_backup_files() -> Result<Vec<BackupFile>, String> {
+    let mut files = Vec::new();
+    let config_dir = app_config_dir()?;
+    collect_config_files(&config_dir, &config_dir, &mut files)?;
+    let data_dir = app_data_dir()?;
+    for name in BACKED_UP_DATA_FILES {
+        // Not there until the first command or directory change
+        if let Ok(contents) = fs::read(data_dir.join(name)) {
+            files.push(BackupFile {
+                root: BackupRoot::Data,
+                path: name.to_string(),
+                contents: STANDARD.encode(contents),
+            });
+        }
+    }
+    Ok(files)
+}
+
+// Only plain relative paths, so a crafted backup cannot write outside the app's directories
+fn restore_target(base: &Path, relative: &str) -> Result<PathBuf, String> {
+    let path = Path::new(relative);
+    let is_plain = path
+        .components()
+        .all(|component| matches!(component, Component::Normal(_)));
+    if relative.is_empty() || !is_plain {
+        return Err(format!("The backup contains an invalid path: {}", relative));
+    }
+    Ok(base.join(path))
+}
+
+fn write_restored_file(path: &Path, contents: &[u8]) -> Result<(), String> {
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)
+            .map_err(|e| format!("Failed to create {}: {}", parent.display(), e))?;
+    }
+    let mut temp_path = path.as_os_str().to_owned();
+    temp_path.push(".restore");
+    fs::write(&temp_path, contents)
+        .map_err(|e| format!("Failed to write {}: {}", path.display(), e))?;
+    fs::rename(&temp_path, path).map_err(|e| format!("Failed to replace {}: {}", path.display(), e))
+}
+
+// Makes the managers that cache their files read the restored ones
+fn reload_restored_state<R: Runtime>(app_handle: &AppHandle<R>) -> Result<(), String> {
+    *app_handle
+        .state::<SettingsManager>()
+        .settings
+        .lock()
+        .map_err(|e| e.to_string())? = load_json_config(SETTINGS_CONFIG_FILE);
+    let layout_manager = app_handle.state::<LayoutManager>();
+    *layout_manager
+        .workspaces
+        .lock()
+        .map_err(|e| e.to_string())? = load_json_config(LAYOUTS_CONFIG_FILE);
+    *layout_manager
+        .session_meta
+        .lock()
+        .map_err(|e| e.to_string())? = load_json_config(SESSION_META_CONFIG_FILE);
+    *app_handle
+        .state::<MacroManager>()
+        .macros
+        .lock()
+        .map_err(|e| e.to_string())? = load_json_config(MACROS_CONFIG_FILE);
+    *app_handle
+        .state::<HookManager>()
+        .hooks
+        .lock()
+        .map_err(|e| e.to_string())? = load_json_config(HOOKS_CONFIG_FILE);
+    *app_handle
+        .state::<LearnedCommandStore>()
+        .commands
+        .lock()
+        .map_err(|e| e.to_string())? = load_json_config(LEARNED_COMMANDS_FILE);
+    app_handle.state::<HistoryStore>().reload()
+}
+
+// Writes the config directory (settings, workspaces, macros, hooks, ...) and the command and
+// directory history to `path`, encrypted with `passphrase`. The passphrase is never stored.
+#[command]
+pub async fn backup_app_data(path: String, passphrase: String) -> Result<BackupReport, String> {
+    if passphrase.is_empty() {
+        return Err("A passphrase is required to encrypt the backup".to_string());
+    }
+    let archive = BackupArchive {
+        created_at: unix_timestamp_millis(),
+        files: collect_backup_files()?,
+    };
+    let plaintext = serde_json::to_vec(&archive).map_err(|e| e.to_string())?;
+    let encrypted = encrypt_backup(&passphrase, &plaintext)?;
+    fs::write(&path, &encrypted).map_err(|e| format!("Failed to write {}: {}", path, e))?;
+    Ok(BackupReport {
+        path,
+        files: archive.files.len(),
+        bytes: encrypted.len() as u64,
+        created_at: archive.created_at,
+    })
+}
+
+// Restores a backup_app_data file, e.g. on a new machine. Files in the backup replace the local
+// ones; files it does not contain are kept. Nothing is written unless the whole backup decrypts
+// and checks out.
+#[command]
+pub async fn restore_app_data<R: Runtime>(
+    path: String,
+    passphrase: String,
+    app_handle: AppHandle<R>,
+) -> Result<RestoreReport, String> {
+    let data = fs::read(&path).map_err(|e| format!("Failed to read {}: {}", path, e))?;
+    let plaintext = decrypt_backup(&passphrase, &data)?;
+    let archive: BackupArchive = serde_json::from_slice(&plaintext)
+        .map_err(|e| format!("The backup is corrupted: {}", e))?;
+
+    let config_dir = app_config_dir()?;
+    let data_dir = app_data_dir()?;
+    let mut restored = Vec::with_capacity(archive.files.len());
+    for file in &archive.files {
+        // Backups taken before these were left out may still contain them
+        if file.root == BackupRoot::Config && MACHINE_CONFIG_FILES.contains(&file.path.as_str()) {
+            continue;
+        }
+        let base = match file.root {
+            BackupRoot::Config => &config_dir,
+            BackupRoot::Data => &data_dir,
+        };
+        let contents = STANDARD
+            .decode(&file.contents)
+            .map_err(|e| format!("The backup is corrupted: {}", e))?;
+        restored.push((restore_target(base, &file.path)?, contents));
+    }
+    for (target, contents) in &restored {
+        write_restored_file(target, contents)?;
+    }
+
+    reload_restored_state(&app_handle)?;
+    Ok(RestoreReport {
+        files: restored.len(),
+        created_at: archive.created_at,
+    })
+}
@@ -0,0 +1,3 @@
+pub mod backup;
+pub mod settings_commands;
+pub mod types;
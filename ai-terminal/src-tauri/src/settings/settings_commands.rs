@@ -0,0 +1,68 @@
+use crate::command::autocomplete::command_names::validate_custom_commands;
+use crate::command::core::color_control::validate_color_rules;
+use crate::command::core::cost_warnings::validate_cost_rules;
+use crate::command::core::pager_disabling::validate_pager_rules;
+use crate::command::core::protected_sessions::validate_danger_patterns;
+use crate::command::core::pty_fallback::validate_tty_commands;
+use crate::history::daily_digest::validate_digest_time;
+use crate::ollama::context_budget::validate_context_window;
+use crate::settings::types::app_settings::AppSettings;
+use crate::settings::types::settings_manager::SettingsManager;
+use crate::utils::env_scrubbing::is_env_var_allowed;
+use serde::Serialize;
+use tauri::{command, State};
+
+#[command]
+pub fn get_settings(settings_manager: State<'_, SettingsManager>) -> Result<AppSettings, String> {
+    let settings = settings_manager
+        .settings
+        .lock()
+        .map_err(|e| e.to_string())?;
+    Ok(settings.clone())
+}
+
+#[command]
+pub fn update_settings(
+    settings: AppSettings,
+    settings_manager: State<'_, SettingsManager>,
+) -> Result<AppSettings, String> {
+    validate_cost_rules(&settings.cost_rules)?;
+    validate_danger_patterns(&settings.danger_patterns)?;
+    validate_pager_rules(&settings.pager_rules)?;
+    validate_color_rules(&settings.color_rules)?;
+    validate_tty_commands(&settings.tty_commands)?;
+    validate_custom_commands(&settings.custom_commands)?;
+    validate_context_window(settings.ai_context_window_tokens)?;
+    validate_digest_time(&settings.daily_digest_time)?;
+    let mut current = settings_manager
+        .settings
+        .lock()
+        .map_err(|e| e.to_string())?;
+    settings_manager.persist(&settings)?;
+    *current = settings;
+    Ok(current.clone())
+}
+
+#[derive(Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct EnvironmentPreview {
+    pub propagated: Vec<String>,
+    pub removed: Vec<String>,
+}
+
+// Names (never values) of the variables the current settings pass to or withhold from children
+#[command]
+pub fn preview_environment_scrubbing(
+    settings_manager: State<'_, SettingsManager>,
+) -> Result<EnvironmentPreview, String> {
+    let settings = settings_manager.current();
+    let (mut propagated, mut removed): (Vec<String>, Vec<String>) = std::env::vars()
+        .map(|(name, _)| name)
+        .partition(|name| is_env_var_allowed(&settings, name));
+    propagated.sort();
+    removed.sort();
+    Ok(EnvironmentPreview {
+        propagated,
+        removed,
+    })
+}
@@ -0,0 +1,379 @@
+use serde::{Deserialize, Serialize};
+
+// Variables matching these patterns are not passed to spawned commands unless allow-listed
+pub const DEFAULT_ENV_DENYLIST: &[&str] = &[
+    "*_TOKEN",
+    "*_SECRET",
+    "*_SECRET_KEY",
+    "*_PASSWORD",
+    "*_API_KEY",
+    "*_APIKEY",
+    "*_PRIVATE_KEY",
+    "*_CREDENTIALS",
+    "AWS_SECRET_ACCESS_KEY",
+    "AWS_SESSION_TOKEN",
+    "SSH_ASKPASS_PASSWORD",
+    "TAURI_*",
+];
+
+// Paths whose content and mentions are kept out of context sent to hosted AI providers
+pub const DEFAULT_AI_CONTEXT_EXCLUSIONS: &[&str] =
+    &["~/.ssh", "~/.aws", "~/.gnupg", "~/.kube", "*.pem", "*.key"];
+
+// Warns (and optionally asks for confirmation) before a command matching `pattern` runs
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct CostRule {
+    // Regex matched against each command in a chain, after sudo and VAR=value prefixes
+    pub pattern: String,
+    pub message: String,
+    #[serde(default)]
+    pub require_confirmation: bool,
+}
+
+// (pattern, message, require_confirmation)
+const DEFAULT_COST_RULES: &[(&str, &str, bool)] = &[
+    (
+        r"^(terraform|tofu)\s+(.*\s)?(apply|destroy)\b",
+        "Applies infrastructure changes that can create, replace or delete billable resources",
+        true,
+    ),
+    (
+        r"^(pulumi\s+(up|destroy)|cdk\s+(deploy|destroy))\b",
+        "Deploys infrastructure that can create or delete billable resources",
+        true,
+    ),
+    (
+        r"^aws\s+ec2\s+run-instances\b",
+        "Launches EC2 instances, billed for as long as they run",
+        true,
+    ),
+    (
+        r"^aws\s+rds\s+create-db-(instance|cluster)\b",
+        "Creates an RDS database, billed hourly; provisioning takes several minutes",
+        true,
+    ),
+    (
+        r"^eksctl\s+create\s+cluster\b",
+        "Creates an EKS cluster, billed hourly; this usually takes 15-20 minutes",
+        true,
+    ),
+    (
+        r"^gcloud\s+compute\s+instances\s+create\b",
+        "Creates Compute Engine VMs, billed for as long as they run",
+        true,
+    ),
+    (
+        r"^gcloud\s+container\s+clusters\s+create\b",
+        "Creates a GKE cluster, billed hourly; this usually takes several minutes",
+        true,
+    ),
+    (
+        r"^az\s+(vm|aks)\s+create\b",
+        "Creates Azure compute resources, billed for as long as they run",
+        true,
+    ),
+    (
+        r"^aws\s+s3\s+(sync|cp\s+.*--recursive)\b",
+        "May transfer large amounts of data, which can incur request and egress charges",
+        false,
+    ),
+];
+
+pub fn default_cost_rules() -> Vec<CostRule> {
+    DEFAULT_COST_RULES
+        .iter()
+        .map(|(pattern, message, require_confirmation)| CostRule {
+            pattern: pattern.to_string(),
+            message: message.to_string(),
+            require_confirmation: *require_confirmation,
+        })
+        .collect()
+}
+
+// Commands that protected sessions run only after confirmation; regexes matched like cost rules
+pub const DEFAULT_DANGER_PATTERNS: &[&str] = &[
+    r"^rm\s+(.*\s)?(-\S*[rRf]|--recursive|--force)\b",
+    r"^(dd|mkfs(\.\w+)?|fdisk|parted|wipefs|shred)\b",
+    r"^(shutdown|reboot|halt|poweroff)\b",
+    r"^(chmod|chown)\s+(.*\s)?-\S*R",
+    r"^systemctl\s+(stop|restart|disable|mask)\b",
+    r"^git\s+(.*\s)?(push\s+(.*\s)?(-f|--force\S*)|reset\s+--hard|clean\s+-\S*f)",
+    r"^kubectl\s+(.*\s)?(delete|drain|scale)\b",
+    r"^helm\s+(uninstall|delete)\b",
+    r"^(terraform|tofu)\s+(.*\s)?destroy\b",
+    r"^docker\s+(.*\s)?(rm|rmi|prune)\b",
+    r"(?i)\b(drop|truncate)\s+(table|database|schema)\b",
+];
+
+// How execute_command keeps one program from waiting on a pager
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct PagerRule {
+    // Program name (`git`, `systemctl`); a path matches by its file name
+    pub program: String,
+    // Inserted right after the program name, e.g. --no-pager
+    #[serde(default)]
+    pub flag: Option<String>,
+    // false leaves this program, and the PAGER variables of commands running it, untouched
+    #[serde(default = "default_true")]
+    pub enabled: bool,
+}
+
+fn default_true() -> bool {
+    true
+}
+
+// (program, flag)
+const DEFAULT_PAGER_RULES: &[(&str, Option<&str>)] = &[
+    ("git", Some("--no-pager")),
+    ("systemctl", Some("--no-pager")),
+    ("journalctl", Some("--no-pager")),
+    ("loginctl", Some("--no-pager")),
+    ("coredumpctl", Some("--no-pager")),
+    ("busctl", Some("--no-pager")),
+    ("aws", Some("--no-cli-pager")),
+    ("psql", Some("--pset=pager=off")),
+    ("man", None),
+];
+
+pub fn default_pager_rules() -> Vec<PagerRule> {
+    DEFAULT_PAGER_RULES
+        .iter()
+        .map(|(program, flag)| PagerRule {
+            program: program.to_string(),
+            flag: flag.map(str::to_string),
+            enabled: true,
+        })
+        .collect()
+}
+
+// Keeps color in a program's output when it runs without a terminal
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct ColorRule {
+    // Program name (`ls`, `grep`); a path matches by its file name
+    pub program: String,
+    // Inserted right after the program name, e.g. --color=always
+    #[serde(default)]
+    pub flag: Option<String>,
+    #[serde(default = "default_true")]
+    pub enabled: bool,
+}
+
+// (program, flag); BSD ls has no --color and relies on CLICOLOR_FORCE instead
+const DEFAULT_COLOR_RULES: &[(&str, Option<&str>)] = &[
+    (
+        "ls",
+        if cfg!(target_os = "macos") {
+            None
+        } else {
+            Some("--color=always")
+        },
+    ),
+    ("grep", Some("--color=always")),
+    ("diff", Some("--color=always")),
+    ("git", Some("-c color.ui=always")),
+    ("cargo", Some("--color=always")),
+    ("ip", Some("-color=always")),
+    ("jq", Some("-C")),
+    ("tree", Some("-C")),
+];
+
+pub fn default_color_rules() -> Vec<ColorRule> {
+    DEFAULT_COLOR_RULES
+        .iter()
+        .map(|(program, flag)| ColorRule {
+            program: program.to_string(),
+            flag: flag.map(str::to_string),
+            enabled: true,
+        })
+        .collect()
+}
+
+// Whether execute_command asks programs for colored output although stdout is a pipe
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum ColorMode {
+    // Programs decide for themselves, which usually means no color
+    #[default]
+    Off,
+    // Color variables for every command, plus the flag of each matching color rule
+    Force,
+    // As Force, and commands matching a color rule run in a throwaway PTY; their stderr
+    // then arrives on stdout
+    Pty,
+}
+
+// Commands that need a real terminal; matched like cost rules, after sudo and VAR=value prefixes
+const DEFAULT_TTY_COMMANDS: &[&str] = &[
+    r"^(vi|vim|nvim|nano|emacs|micro|hx)(\s|$)",
+    r"^(less|more|most|man)(\s|$)",
+    r"^(top|htop|btop|atop|glances|watch|nmtui|ncdu|tig|lazygit|ranger|mc)(\s|$)",
+    r"^(tmux|screen|zellij)(\s|$)",
+    r"^ssh\s+(.*\s)?-[a-zA-Z]*t[a-zA-Z]*(\s|$)",
+    r"^(docker|podman)\s+(exec|run)\s+(.*\s)?(-[a-zA-Z]*(it|ti)[a-zA-Z]*|--tty|-t)(\s|$)",
+    r"^kubectl\s+(exec|run|attach)\s+(.*\s)?(-[a-zA-Z]*(it|ti)[a-zA-Z]*|--tty|-t)(\s|$)",
+];
+
+pub fn default_tty_commands() -> Vec<String> {
+    DEFAULT_TTY_COMMANDS
+        .iter()
+        .map(|pattern| pattern.to_string())
+        .collect()
+}
+
+// How the cd builtin treats symlinks, mirroring the shells' `cd -L` / `cd -P`
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum CdMode {
+    // Keep the path as typed, resolving `..` lexically so symlinked directories stay visible
+    #[default]
+    Logical,
+    // Resolve symlinks to the real directory
+    Physical,
+}
+
+// What happens to completion notifications and bells while the OS is in do-not-disturb
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum DndBehavior {
+    // Hold them and deliver once do-not-disturb ends
+    #[default]
+    Queue,
+    // Drop them
+    Suppress,
+}
+
+// What a terminal bell (BEL) rung by a program in a PTY session does
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum BellAction {
+    // Play the system alert sound
+    Sound,
+    // Flash the dock or taskbar icon of the session's window
+    Flash,
+    // Emit visual_bell so the session's pane flashes
+    #[default]
+    Visual,
+    // Ignore it
+    Off,
+}
+
+// When context for AI requests goes through redaction and ai_context_exclusions
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum AiContextFilterMode {
+    // Only when the provider is not on this machine (e.g. Ollama on a remote host)
+    #[default]
+    Hosted,
+    Always,
+    Off,
+}
+
+// User preferences persisted to settings.json; missing fields fall back to their defaults
+#[derive(Serialize, Deserialize, Clone, Debug)]
+#[serde(default, rename_all = "camelCase")]
+pub struct AppSettings {
+    // Append every executed command to the hash-chained audit log
+    pub audit_log_enabled: bool,
+    // Filter the app environment before it is copied into spawned commands
+    pub env_scrubbing_enabled: bool,
+    // Glob-style names (`*` wildcard); the allowlist wins over the denylist
+    pub env_allowlist: Vec<String>,
+    pub env_denylist: Vec<String>,
+    pub cd_mode: CdMode,
+    // Load .env files (and approved .envrc files) when a session enters their directory
+    pub directory_env_enabled: bool,
+    // Emit cost_warning for commands matching cost_rules before they run
+    pub cost_warnings_enabled: bool,
+    pub cost_rules: Vec<CostRule>,
+    // Commands that sessions marked protected (set_session_protected) hold until confirmed
+    pub danger_patterns: Vec<String>,
+    // Emit port_conflict when a dev server is about to start on a port already in use
+    pub port_conflict_warnings_enabled: bool,
+    // Run non-interactive commands with PAGER=cat and friends, plus each rule's no-pager flag
+    pub pager_disabling_enabled: bool,
+    pub pager_rules: Vec<PagerRule>,
+    pub command_color: ColorMode,
+    pub color_rules: Vec<ColorRule>,
+    // Run commands matching tty_commands in a short-lived PTY instead of on pipes
+    pub pty_fallback_enabled: bool,
+    pub tty_commands: Vec<String>,
+    // Extra program names offered when completing the first word
+    pub custom_commands: Vec<String>,
+    // Offer programs from history once they have run learn_command_threshold times
+    pub learn_commands_enabled: bool,
+    pub learn_command_threshold: u32,
+    // Lock a window after this many minutes without input; 0 disables the idle lock
+    pub idle_lock_minutes: u32,
+    // Ask for the OS login (Touch ID, polkit, Windows Hello) before unlocking
+    pub idle_lock_requires_os_auth: bool,
+    pub dnd_behavior: DndBehavior,
+    // Sessions can override it with set_bell_action
+    pub bell_action: BellAction,
+    // Keep what full-screen apps (vim, htop) draw on the alternate screen in the scrollback
+    // and transcripts
+    pub alt_screen_scrollback: bool,
+    // Periodically fetch the repositories sessions are in so ahead/behind counts stay current
+    pub background_git_fetch: bool,
+    pub ai_context_filter: AiContextFilterMode,
+    // Replace API keys, tokens and passwords with [REDACTED] in filtered context
+    pub ai_context_redaction: bool,
+    // Globs (`*`, `?`, `**`); without a `/` they match any path component, e.g. `*.pem`.
+    // Sessions inside a match send no context, and lines mentioning one are dropped.
+    pub ai_context_exclusions: Vec<String>,
+    // Model context window (sent to Ollama as num_ctx); ask_ai context is trimmed to fit it
+    pub ai_context_window_tokens: usize,
+    // Summarize each day's commands per project with the local model at daily_digest_time
+    pub daily_digest_enabled: bool,
+    // Local time as HH:MM
+    pub daily_digest_time: String,
+}
+
+impl Default for AppSettings {
+    fn default() -> Self {
+        Self {
+            audit_log_enabled: false,
+            env_scrubbing_enabled: true,
+            env_allowlist: Vec::new(),
+            env_denylist: DEFAULT_ENV_DENYLIST
+                .iter()
+                .map(|pattern| pattern.to_string())
+                .collect(),
+            cd_mode: CdMode::default(),
+            directory_env_enabled: true,
+            cost_warnings_enabled: true,
+            cost_rules: default_cost_rules(),
+            danger_patterns: DEFAULT_DANGER_PATTERNS
+                .iter()
+                .map(|pattern| pattern.to_string())
+                .collect(),
+            port_conflict_warnings_enabled: true,
+            pager_disabling_enabled: true,
+            pager_rules: default_pager_rules(),
+            command_color: ColorMode::default(),
+            color_rules: default_color_rules(),
+            pty_fallback_enabled: true,
+            tty_commands: default_tty_commands(),
+            custom_commands: Vec::new(),
+            learn_commands_enabled: true,
+            learn_command_threshold: 3,
+            idle_lock_minutes: 0,
+            idle_lock_requires_os_auth: false,
+            dnd_behavior: DndBehavior::default(),
+            bell_action: BellAction::default(),
+            alt_screen_scrollback: false,
+            background_git_fetch: true,
+            ai_context_filter: AiContextFilterMode::default(),
+            ai_context_redaction: true,
+            ai_context_exclusions: DEFAULT_AI_CONTEXT_EXCLUSIONS
+                .iter()
+                .map(|pattern| pattern.to_string())
+                .collect(),
+            ai_context_window_tokens: 8192,
+            daily_digest_enabled: false,
+            daily_digest_time: "18:00".to_string(),
+        }
+    }
+}
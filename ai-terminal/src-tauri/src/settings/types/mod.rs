@@ -0,0 +1,2 @@
+pub mod app_settings;
+pub mod settings_manager;
@@ -0,0 +1,35 @@
+use crate::settings::types::app_settings::AppSettings;
+use crate::utils::config_utils::{load_json_config, save_json_config};
+use std::sync::Mutex;
+
+pub const SETTINGS_CONFIG_FILE: &str = "settings.json";
+
+pub struct SettingsManager {
+    pub settings: Mutex<AppSettings>,
+}
+
+impl SettingsManager {
+    pub fn new() -> Self {
+        Self {
+            settings: Mutex::new(load_json_config(SETTINGS_CONFIG_FILE)),
+        }
+    }
+
+    // Snapshot of the current settings; falls back to defaults if the lock is poisoned
+    pub fn current(&self) -> AppSettings {
+        self.settings
+            .lock()
+            .map(|settings| settings.clone())
+            .unwrap_or_default()
+    }
+
+    pub fn persist(&self, settings: &AppSettings) -> Result<(), String> {
+        save_json_config(SETTINGS_CONFIG_FILE, settings)
+    }
+}
+
+impl Default for SettingsManager {
+    fn default() -> Self {
+        Self::new()
+    }
+}
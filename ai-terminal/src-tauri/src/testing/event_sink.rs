@@ -0,0 +1,134 @@
+use serde_json::Value;
+use std::sync::{Arc, Condvar, Mutex};
+use std::time::{Duration, Instant};
+use tauri::{Listener, Runtime};
+
+// Events the backend emits during command execution and PTY sessions
+pub const RECORDED_EVENTS: &[&str] = &[
+    "command_output",
+    "command_error",
+    "command_end",
+    "command_failed",
+    "command_retry_attempt",
+    "command_retry_result",
+    "command_forwarded_to_ssh",
+    "command_progress",
+    "command_receipt",
+    "agent_changes",
+    "agent_session_opened",
+    "agent_session_closed",
+    "git_status_updated",
+    "file_operation_progress",
+    "command_suggestion",
+    "command_trace",
+    "output_annotations",
+    "structured_output",
+    "input_requested",
+    "confirmation_required",
+    "cost_warning",
+    "port_conflict",
+    "http_response",
+    "ssh_pre_exec_password_request",
+    "ssh_session_started",
+    "ssh_connection_phase",
+    "ssh_session_ended",
+    "ssh_connected",
+    "ssh_disconnected",
+    "pty_output",
+    "pty_exit",
+    "visual_bell",
+    "alt_screen_changed",
+    "macro_playback_finished",
+    "pty_fallback",
+    "pty_reconnecting",
+    "pty_reconnected",
+    "env_loaded",
+    "env_unloaded",
+    "env_approval_required",
+];
+
+#[derive(Clone, Debug)]
+pub struct RecordedEvent {
+    pub name: String,
+    pub payload: Value,
+}
+
+// Collects emitted events in order so tests can assert on them instead of a webview
+#[derive(Clone, Default)]
+pub struct EventSink {
+    events: Arc<(Mutex<Vec<RecordedEvent>>, Condvar)>,
+}
+
+impl EventSink {
+    pub fn attach<R: Runtime, L: Listener<R>>(listener: &L) -> Self {
+        let sink = Self::default();
+        for name in RECORDED_EVENTS {
+            let events = sink.events.clone();
+            listener.listen_any(*name, move |event| {
+                let payload = serde_json::from_str(event.payload()).unwrap_or(Value::Null);
+                let (recorded, changed) = &*events;
+                if let Ok(mut recorded) = recorded.lock() {
+                    recorded.push(RecordedEvent {
+                        name: name.to_string(),
+                        payload,
+                    });
+                }
+                changed.notify_all();
+            });
+        }
+        sink
+    }
+
+    pub fn events(&self) -> Vec<RecordedEvent> {
+        self.events
+            .0
+            .lock()
+            .map(|events| events.clone())
+            .unwrap_or_default()
+    }
+
+    pub fn len(&self) -> usize {
+        self.events.0.lock().map(|events| events.len()).unwrap_or(0)
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    pub fn named(&self, name: &str) -> Vec<RecordedEvent> {
+        self.events()
+            .into_iter()
+            .filter(|event| event.name == name)
+            .collect()
+    }
+
+    // Concatenated `data` of the command_output/command_error-style `name` events recorded
+    // after index `from`
+    pub fn text_since(&self, from: usize, name: &str) -> String {
+        self.events()
+            .iter()
+            .skip(from)
+            .filter(|event| event.name == name)
+            .filter_map(|event| event.payload["data"].as_str())
+            .collect()
+    }
+
+    // Blocks until an event matching `predicate` is recorded at or after index `from`
+    pub fn wait_for(
+        &self,
+        from: usize,
+        timeout: Duration,
+        predicate: impl Fn(&RecordedEvent) -> bool,
+    ) -> Option<RecordedEvent> {
+        let deadline = Instant::now() + timeout;
+        let (recorded, changed) = &*self.events;
+        let mut events = recorded.lock().ok()?;
+        loop {
+            if let Some(event) = events.iter().skip(from).find(|event| predicate(event)) {
+                return Some(event.clone());
+            }
+            let remaining = deadline.checked_duration_since(Instant::now())?;
+            events = changed.wait_timeout(events, remaining).ok()?.0;
+        }
+    }
+}
@@ -0,0 +1,345 @@
+use crate::appearance::types::appearance_manager::AppearanceManager;
+use crate::audit::audit_log::AuditLog;
+use crate::command::core::execute_command::execute_command;
+use crate::command::core::pty::{pty_close_session, pty_create_session, pty_write};
+use crate::command::ssh::remote_exec::{ssh_exec, SshExecResult};
+use crate::command::types::bell_manager::BellManager;
+use crate::command::types::command_manager::CommandManager;
+use crate::command::types::command_state::CommandState;
+use crate::command::types::confirmation_manager::{
+    ConfirmationManager, CONFIRMATION_REQUIRED_EVENT,
+};
+use crate::command::types::copy_mode_manager::CopyModeManager;
+use crate::command::types::file_operation_manager::FileOperationManager;
+use crate::command::types::git_status_cache::GitStatusCache;
+use crate::command::types::learned_command_store::LearnedCommandStore;
+use crate::command::types::line_editor_manager::LineEditorManager;
+use crate::command::types::macro_manager::MacroManager;
+use crate::command::types::output_processor_manager::OutputProcessorManager;
+use crate::command::types::progress_tracker::ProgressTracker;
+use crate::command::types::pty_manager::PtyManager;
+use crate::command::types::recovery_manager::RecoveryManager;
+use crate::command::types::screen_model_manager::ScreenModelManager;
+use crate::command::types::scrollback_manager::ScrollbackManager;
+use crate::command::types::share_manager::ShareManager;
+use crate::command::types::timeline_manager::TimelineManager;
+use crate::command::types::window_router::WindowRouter;
+use crate::history::types::history_store::HistoryStore;
+use crate::hooks::types::hook_manager::HookManager;
+use crate::layout::types::layout_manager::LayoutManager;
+use crate::notifications::types::notification_manager::NotificationManager;
+use crate::ollama::types::chat_store::ChatStore;
+use crate::privacy::types::idle_lock_manager::IdleLockManager;
+use crate::receipts::agent_undo::undo_agent_run;
+use crate::receipts::receipt_commands::execute_agent_command;
+use crate::receipts::types::agent_changes::AgentChangeStore;
+use crate::receipts::types::agent_run::UndoResult;
+use crate::receipts::types::agent_session_pool::AgentSessionPool;
+use crate::receipts::types::command_receipt::CommandReceipt;
+use crate::receipts::types::receipt_store::ReceiptStore;
+use crate::scheduler::types::startup_profiler::StartupProfiler;
+use crate::scheduler::types::task_scheduler::BackgroundScheduler;
+use crate::serial::types::serial_manager::SerialManager;
+use crate::settings::types::settings_manager::SettingsManager;
+use crate::testing::event_sink::{EventSink, RecordedEvent};
+use crate::testing::mock_ssh::MockSsh;
+use crate::utils::config_utils::set_test_app_root;
+use std::time::Duration;
+use tauri::test::{mock_builder, mock_context, noop_assets, MockRuntime};
+use tauri::{App, AppHandle, Manager};
+
+pub const DEFAULT_TIMEOUT: Duration = Duration::from_secs(10);
+
+// Output and events of one execute_command call, gathered until its command_end
+pub struct CommandOutcome {
+    pub result: Result<String, String>,
+    pub stdout: String,
+    pub stderr: String,
+    pub end: Option<RecordedEvent>,
+}
+
+// The backend on tauri's mock runtime, with the same managed state as the app and every
+// emitted event recorded. Config and data (settings, history, audit log) live in a scratch
+// dir shared by the harnesses of one test binary, never in the user's own dirs:
+//
+//     let harness = TestHarness::new();
+//     harness.run("s1", "cd /tmp");
+//     assert_eq!(harness.current_dir("s1").as_deref(), Some("/tmp"));
+pub struct TestHarness {
+    app: App<MockRuntime>,
+    pub events: EventSink,
+    mock_ssh: Option<MockSsh>,
+}
+
+impl TestHarness {
+    pub fn new() -> Self {
+        set_test_app_root(
+            std::env::temp_dir().join(format!("ai-terminal-harness-{}", uuid::Uuid::new_v4())),
+        );
+        let app = mock_builder()
+            .manage(CommandManager::new())
+            .manage(PtyManager::new())
+            .manage(ScrollbackManager::new())
+            .manage(HookManager::new())
+            .manage(ConfirmationManager::new())
+            .manage(ShareManager::new())
+            .manage(TimelineManager::new())
+            .manage(SettingsManager::new())
+            .manage(AuditLog::new())
+            .manage(HistoryStore::new())
+            .manage(SerialManager::new())
+            .manage(ReceiptStore::new())
+            .manage(AgentChangeStore::new())
+            .manage(AgentSessionPool::new())
+            .manage(ProgressTracker::new())
+            .manage(BellManager::new())
+            .manage(IdleLockManager::new())
+            .manage(LineEditorManager::new())
+            .manage(CopyModeManager::new())
+            .manage(ScreenModelManager::new())
+            .manage(MacroManager::new())
+            .manage(WindowRouter::new())
+            .manage(LayoutManager::new())
+            .manage(NotificationManager::new())
+            .manage(AppearanceManager::new())
+            .manage(BackgroundScheduler::<MockRuntime>::new())
+            .manage(RecoveryManager::new())
+            .manage(OutputProcessorManager::new())
+            .manage(LearnedCommandStore::new())
+            .manage(ChatStore::new())
+            .manage(GitStatusCache::new())
+            .manage(FileOperationManager::new())
+            .manage(StartupProfiler::new())
+            .build(mock_context(noop_assets()))
+            .expect("failed to build the mock app");
+        let events = EventSink::attach(app.handle());
+        Self {
+            app,
+            events,
+            mock_ssh: None,
+        }
+    }
+
+    // Routes `ssh`/`sshpass` to local shells so SSH sessions can be driven without a server.
+    // Only commands run through the harness see the fakes.
+    pub fn with_mock_ssh(mut self) -> Self {
+        self.mock_ssh = Some(MockSsh::install().expect("failed to install the mock ssh"));
+        self
+    }
+
+    pub fn handle(&self) -> &AppHandle<MockRuntime> {
+        self.app.handle()
+    }
+
+    pub fn execute(
+        &self,
+        session_id: &str,
+        command: &str,
+        ssh_password: Option<&str>,
+    ) -> Result<String, String> {
+        self.execute_with_token(session_id, command, ssh_password, None)
+    }
+
+    // Like execute, with the token of a confirmation_required event
+    pub fn execute_with_token(
+        &self,
+        session_id: &str,
+        command: &str,
+        ssh_password: Option<&str>,
+        confirmation_token: Option<&str>,
+    ) -> Result<String, String> {
+        if let Some(mock_ssh) = &self.mock_ssh {
+            self.set_session_env(session_id, "PATH", &mock_ssh.search_path()?);
+        }
+        let handle = self.handle().clone();
+        execute_command(
+            command.to_string(),
+            session_id.to_string(),
+            ssh_password.map(str::to_string),
+            confirmation_token.map(str::to_string),
+            None,
+            handle.clone(),
+            self.handle().state::<CommandManager>(),
+        )
+        .map_err(String::from)
+    }
+
+    // Runs a command and waits for its command_end (builtins such as cd emit it right away).
+    // Commands forwarded to an active SSH session have no command_end; use wait_for instead.
+    pub fn run(&self, session_id: &str, command: &str) -> CommandOutcome {
+        self.run_with_token(session_id, command, None)
+    }
+
+    pub fn run_with_token(
+        &self,
+        session_id: &str,
+        command: &str,
+        confirmation_token: Option<&str>,
+    ) -> CommandOutcome {
+        let from = self.events.len();
+        let result = self.execute_with_token(session_id, command, None, confirmation_token);
+        let end = result
+            .as_ref()
+            .ok()
+            .and_then(|_| self.wait_for(from, "command_end"));
+        CommandOutcome {
+            stdout: self.events.text_since(from, "command_output"),
+            stderr: self.events.text_since(from, "command_error"),
+            result,
+            end,
+        }
+    }
+
+    // Runs a command as the AI agent and waits for its receipt; returns the receipt (and
+    // undo) id
+    pub fn run_agent_command(&self, session_id: &str, command: &str) -> Result<String, String> {
+        let from = self.events.len();
+        let receipt_id = execute_agent_command(
+            command.to_string(),
+            session_id.to_string(),
+            format!("test-{}", session_id),
+            None,
+            self.handle().clone(),
+            self.handle().state::<CommandManager>(),
+        )?;
+        self.wait_for(from, "command_receipt")
+            .ok_or("no command_receipt event")?;
+        Ok(receipt_id)
+    }
+
+    // Receipts of the session's run_agent_command calls, oldest first
+    pub fn command_receipts(&self, session_id: &str) -> Vec<CommandReceipt> {
+        self.handle()
+            .state::<ReceiptStore>()
+            .list(&format!("test-{}", session_id))
+            .unwrap_or_default()
+    }
+
+    pub fn undo_agent_run(&self, run_id: &str, force: bool) -> Result<UndoResult, String> {
+        undo_agent_run(
+            run_id.to_string(),
+            Some(force),
+            self.handle().state::<AgentChangeStore>(),
+        )
+    }
+
+    pub fn ssh_exec(
+        &self,
+        connection: &str,
+        command: &str,
+        confirmation_token: Option<&str>,
+    ) -> Result<SshExecResult, String> {
+        tauri::async_runtime::block_on(ssh_exec(
+            connection.to_string(),
+            command.to_string(),
+            None,
+            confirmation_token.map(str::to_string),
+            self.handle().clone(),
+        ))
+        .map_err(String::from)
+    }
+
+    pub fn wait_for(&self, from: usize, name: &str) -> Option<RecordedEvent> {
+        self.events
+            .wait_for(from, DEFAULT_TIMEOUT, |event| event.name == name)
+    }
+
+    // Token of the first confirmation_required event since `from`
+    pub fn confirmation_token(&self, from: usize) -> Option<String> {
+        self.wait_for(from, CONFIRMATION_REQUIRED_EVENT)
+            .and_then(|event| event.payload["token"].as_str().map(str::to_string))
+    }
+
+    // Marks the session protected without persisting it to the user's config
+    pub fn protect_session(&self, session_id: &str) {
+        let layout_manager = self.handle().state::<LayoutManager>();
+        let mut session_meta = layout_manager.session_meta.lock().unwrap();
+        session_meta
+            .entry(session_id.to_string())
+            .or_default()
+            .protected = true;
+    }
+
+    // Layered over the environment of the session's commands, like a cloud profile's variables
+    pub fn set_session_env(&self, session_id: &str, name: &str, value: &str) {
+        let command_manager = self.handle().state::<CommandManager>();
+        let mut states = command_manager.commands.lock().unwrap();
+        states
+            .entry(session_id.to_string())
+            .or_insert_with(|| {
+                CommandState::new(
+                    std::env::current_dir()
+                        .unwrap_or_default()
+                        .to_string_lossy()
+                        .to_string(),
+                )
+            })
+            .cloud_env
+            .insert(name.to_string(), value.to_string());
+    }
+
+    pub fn current_dir(&self, session_id: &str) -> Option<String> {
+        let command_manager = self.handle().state::<CommandManager>();
+        let states = command_manager.commands.lock().ok()?;
+        states
+            .get(session_id)
+            .map(|state| state.current_dir.clone())
+    }
+
+    pub fn is_ssh_active(&self, session_id: &str) -> bool {
+        let command_manager = self.handle().state::<CommandManager>();
+        let states = match command_manager.commands.lock() {
+            Ok(states) => states,
+            Err(_) => return false,
+        };
+        states
+            .get(session_id)
+            .is_some_and(|state| state.is_ssh_session_active)
+    }
+
+    pub fn open_pty(&self, session_id: &str, cols: u16, rows: u16) -> Result<(), String> {
+        pty_create_session(
+            session_id.to_string(),
+            cols,
+            rows,
+            self.handle().clone(),
+            self.handle().state::<PtyManager>(),
+        )
+    }
+
+    pub fn write_pty(&self, session_id: &str, data: &str) -> Result<String, String> {
+        self.write_pty_with_token(session_id, data, None)
+    }
+
+    pub fn write_pty_with_token(
+        &self,
+        session_id: &str,
+        data: &str,
+        confirmation_token: Option<&str>,
+    ) -> Result<String, String> {
+        pty_write(
+            session_id.to_string(),
+            data.to_string(),
+            confirmation_token.map(str::to_string),
+            self.handle().clone(),
+            self.handle().state::<PtyManager>(),
+            self.handle().state::<MacroManager>(),
+        )
+    }
+
+    pub fn close_pty(&self, session_id: &str) -> Result<(), String> {
+        pty_close_session(
+            session_id.to_string(),
+            None,
+            self.handle().clone(),
+            self.handle().state::<PtyManager>(),
+        )
+        .map(|_| ())
+    }
+}
+
+impl Default for TestHarness {
+    fn default() -> Self {
+        Self::new()
+    }
+}
@@ -0,0 +1,53 @@
+use std::fs;
+use std::path::PathBuf;
+
+// Stand-in for the remote side: ignores options and host and runs a local shell on stdin,
+// which is all the SSH session state machine talks to. Exec channels (-T, from ssh_exec) run
+// their command, the last argument, instead.
+const MOCK_SSH: &str = "#!/bin/sh\ncase \" $* \" in *\" -T \"*) for last; do :; done; exec /bin/sh -c \"$last\" ;; esac\nexec /bin/sh\n";
+// Drops `-p <password>` and runs the rest, like sshpass after authenticating
+const MOCK_SSHPASS: &str = "#!/bin/sh\nshift 2\nexec \"$@\"\n";
+
+// Fake `ssh` and `sshpass` in a scratch dir, removed with the value. The process PATH is left
+// alone; commands find the fakes through the PATH from `search_path`.
+pub struct MockSsh {
+    dir: PathBuf,
+}
+
+impl MockSsh {
+    pub fn install() -> Result<Self, String> {
+        let dir =
+            std::env::temp_dir().join(format!("ai-terminal-mock-ssh-{}", uuid::Uuid::new_v4()));
+        fs::create_dir_all(&dir)
+            .map_err(|e| format!("Failed to create {}: {}", dir.display(), e))?;
+        for (name, script) in [("ssh", MOCK_SSH), ("sshpass", MOCK_SSHPASS)] {
+            let path = dir.join(name);
+            fs::write(&path, script)
+                .map_err(|e| format!("Failed to write {}: {}", path.display(), e))?;
+            #[cfg(unix)]
+            {
+                use std::os::unix::fs::PermissionsExt;
+                fs::set_permissions(&path, fs::Permissions::from_mode(0o755))
+                    .map_err(|e| e.to_string())?;
+            }
+        }
+        Ok(Self { dir })
+    }
+
+    // The app's PATH with the fakes in front
+    pub fn search_path(&self) -> Result<String, String> {
+        let mut paths = vec![self.dir.clone()];
+        if let Some(path) = std::env::var_os("PATH") {
+            paths.extend(std::env::split_paths(&path));
+        }
+        std::env::join_paths(paths)
+            .map(|joined| joined.to_string_lossy().to_string())
+            .map_err(|e| e.to_string())
+    }
+}
+
+impl Drop for MockSsh {
+    fn drop(&mut self) {
+        let _ = fs::remove_dir_all(&self.dir);
+    }
+}
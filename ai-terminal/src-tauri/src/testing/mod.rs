@@ -0,0 +1,3 @@
+pub mod event_sink;
+pub mod harness;
+pub mod mock_ssh;
@@ -0,0 +1,465 @@
+// Minimal ANSI escape sequence handling shared by transcript export and output retention
+
+// Longest unterminated escape (e.g. an OSC title) PlainTextRenderer holds for the next chunk
+const MAX_PENDING_ESCAPE_BYTES: usize = 4096;
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum AnsiToken {
+    Text(String),
+    // Control Sequence Introducer: parameters plus the final byte (e.g. 'm' for SGR)
+    Csi { params: Vec<u16>, final_byte: char },
+    // Single C0 control character such as '\r', '\n' or '\x08'
+    Control(char),
+}
+
+pub fn tokenize(input: &str) -> Vec<AnsiToken> {
+    let mut tokens = Vec::new();
+    let mut text = String::new();
+    let mut chars = input.chars().peekable();
+
+    let flush = |text: &mut String, tokens: &mut Vec<AnsiToken>| {
+        if !text.is_empty() {
+            tokens.push(AnsiToken::Text(std::mem::take(text)));
+        }
+    };
+
+    while let Some(c) = chars.next() {
+        match c {
+            '\x1b' => {
+                flush(&mut text, &mut tokens);
+                match chars.peek() {
+                    Some('[') => {
+                        chars.next();
+                        let mut raw_params = String::new();
+                        let mut final_byte = None;
+                        for next in chars.by_ref() {
+                            if ('\x40'..='\x7e').contains(&next) {
+                                final_byte = Some(next);
+                                break;
+                            }
+                            raw_params.push(next);
+                        }
+                        if let Some(final_byte) = final_byte {
+                            let params = raw_params
+                                .trim_start_matches(['?', '>', '='])
+                                .split([';', ':'])
+                                .map(|p| p.parse::<u16>().unwrap_or(0))
+                                .collect();
+                            tokens.push(AnsiToken::Csi { params, final_byte });
+                        }
+                    }
+                    Some(']') => {
+                        // OSC: skip until BEL or ST (ESC \)
+                        chars.next();
+                        while let Some(next) = chars.next() {
+                            if next == '\x07' {
+                                break;
+                            }
+                            if next == '\x1b' && chars.peek() == Some(&'\\') {
+                                chars.next();
+                                break;
+                            }
+                        }
+                    }
+                    Some(_) => {
+                        // Two-character escape (charset selection, keypad mode, ...)
+                        chars.next();
+                    }
+                    None => {}
+                }
+            }
+            '\n' | '\t' => text.push(c),
+            c if c.is_control() => {
+                flush(&mut text, &mut tokens);
+                tokens.push(AnsiToken::Control(c));
+            }
+            _ => text.push(c),
+        }
+    }
+    flush(&mut text, &mut tokens);
+    tokens
+}
+
+// Removes escape sequences and control characters, keeping line structure
+pub fn strip_ansi(input: &str) -> String {
+    tokenize(input)
+        .into_iter()
+        .filter_map(|token| match token {
+            AnsiToken::Text(text) => Some(text),
+            _ => None,
+        })
+        .collect()
+}
+
+// Keeps text and SGR colors but drops cursor movement, erases, titles and bells, and turns
+// \r\n into \n, for output that is rendered as styled text rather than in a terminal emulator
+pub fn normalize_ansi(input: &str) -> String {
+    let tokens = tokenize(input);
+    let mut normalized = String::with_capacity(input.len());
+    for (index, token) in tokens.iter().enumerate() {
+        match token {
+            AnsiToken::Text(text) => normalized.push_str(text),
+            AnsiToken::Csi {
+                params,
+                final_byte: 'm',
+            } => {
+                let params: Vec<String> = params.iter().map(|p| p.to_string()).collect();
+                normalized.push_str(&format!("\x1b[{}m", params.join(";")));
+            }
+            AnsiToken::Control('\r') => {
+                let before_newline = matches!(
+                    tokens.get(index + 1),
+                    Some(AnsiToken::Text(next)) if next.starts_with('\n')
+                );
+                if !before_newline {
+                    normalized.push('\r');
+                }
+            }
+            _ => {}
+        }
+    }
+    normalized
+}
+
+#[derive(Clone, Default, PartialEq)]
+struct SgrStyle {
+    foreground: Option<String>,
+    background: Option<String>,
+    bold: bool,
+    dim: bool,
+    italic: bool,
+    underline: bool,
+    inverse: bool,
+}
+
+impl SgrStyle {
+    fn is_plain(&self) -> bool {
+        *self == SgrStyle::default()
+    }
+
+    fn to_css(&self) -> String {
+        let (fg, bg) = if self.inverse {
+            (self.background.clone(), self.foreground.clone())
+        } else {
+            (self.foreground.clone(), self.background.clone())
+        };
+        let mut css = Vec::new();
+        if let Some(fg) = fg {
+            css.push(format!("color:{}", fg));
+        }
+        if let Some(bg) = bg {
+            css.push(format!("background-color:{}", bg));
+        }
+        if self.bold {
+            css.push("font-weight:bold".to_string());
+        }
+        if self.dim {
+            css.push("opacity:0.7".to_string());
+        }
+        if self.italic {
+            css.push("font-style:italic".to_string());
+        }
+        if self.underline {
+            css.push("text-decoration:underline".to_string());
+        }
+        css.join(";")
+    }
+
+    fn apply(&mut self, params: &[u16]) {
+        if params.is_empty() {
+            *self = SgrStyle::default();
+            return;
+        }
+        let mut i = 0;
+        while i < params.len() {
+            match params[i] {
+                0 => *self = SgrStyle::default(),
+                1 => self.bold = true,
+                2 => self.dim = true,
+                3 => self.italic = true,
+                4 => self.underline = true,
+                7 => self.inverse = true,
+                22 => {
+                    self.bold = false;
+                    self.dim = false;
+                }
+                23 => self.italic = false,
+                24 => self.underline = false,
+                27 => self.inverse = false,
+                code @ 30..=37 => self.foreground = Some(ansi_color(code - 30)),
+                code @ 90..=97 => self.foreground = Some(ansi_color(code - 90 + 8)),
+                39 => self.foreground = None,
+                code @ 40..=47 => self.background = Some(ansi_color(code - 40)),
+                code @ 100..=107 => self.background = Some(ansi_color(code - 100 + 8)),
+                49 => self.background = None,
+                code @ (38 | 48) => {
+                    let color = match params.get(i + 1) {
+                        Some(5) => {
+                            let color = params.get(i + 2).map(|n| ansi_color(*n));
+                            i += 2;
+                            color
+                        }
+                        Some(2) => {
+                            let rgb = (
+                                params.get(i + 2).copied().unwrap_or(0),
+                                params.get(i + 3).copied().unwrap_or(0),
+                                params.get(i + 4).copied().unwrap_or(0),
+                            );
+                            i += 4;
+                            Some(format!("#{:02x}{:02x}{:02x}", rgb.0, rgb.1, rgb.2))
+                        }
+                        _ => None,
+                    };
+                    if code == 38 {
+                        self.foreground = color;
+                    } else {
+                        self.background = color;
+                    }
+                }
+                _ => {}
+            }
+            i += 1;
+        }
+    }
+}
+
+// xterm 256-color palette as CSS hex colors
+fn ansi_color(index: u16) -> String {
+    const BASE: [&str; 16] = [
+        "#000000", "#cd3131", "#0dbc79", "#e5e510", "#2472c8", "#bc3fbc", "#11a8cd", "#e5e5e5",
+        "#666666", "#f14c4c", "#23d18b", "#f5f543", "#3b8eea", "#d670d6", "#29b8db", "#ffffff",
+    ];
+    match index {
+        0..=15 => BASE[index as usize].to_string(),
+        16..=231 => {
+            let n = index - 16;
+            let level = |v: u16| if v == 0 { 0 } else { v * 40 + 55 };
+            format!(
+                "#{:02x}{:02x}{:02x}",
+                level(n / 36),
+                level((n / 6) % 6),
+                level(n % 6)
+            )
+        }
+        _ => {
+            let gray = (index.min(255) - 232) * 10 + 8;
+            format!("#{:02x}{:02x}{:02x}", gray, gray, gray)
+        }
+    }
+}
+
+pub fn escape_html(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&#39;"),
+            _ => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+// Converts colored terminal output into HTML spans with inline styles
+pub fn ansi_to_html(input: &str) -> String {
+    let mut html = String::new();
+    let mut style = SgrStyle::default();
+    let mut span_open = false;
+
+    for token in tokenize(input) {
+        match token {
+            AnsiToken::Text(text) => {
+                if !span_open && !style.is_plain() {
+                    html.push_str(&format!("<span style=\"{}\">", style.to_css()));
+                    span_open = true;
+                }
+                html.push_str(&escape_html(&text));
+            }
+            AnsiToken::Csi {
+                params,
+                final_byte: 'm',
+            } => {
+                let mut next_style = style.clone();
+                next_style.apply(&params);
+                if next_style != style && span_open {
+                    html.push_str("</span>");
+                    span_open = false;
+                }
+                style = next_style;
+            }
+            _ => {}
+        }
+    }
+    if span_open {
+        html.push_str("</span>");
+    }
+    html
+}
+
+// Incrementally renders terminal output to plain text, resolving carriage returns,
+// backspaces, line erases and cursor movement so progress bars collapse to their final state
+#[derive(Default)]
+pub struct PlainTextRenderer {
+    lines: Vec<Vec<char>>,
+    row: usize,
+    col: usize,
+    // Escape sequence split across chunks, completed by the next feed
+    pending: String,
+    max_lines: usize,
+}
+
+impl PlainTextRenderer {
+    pub fn new(max_lines: usize) -> Self {
+        Self {
+            lines: vec![Vec::new()],
+            max_lines,
+            ..Default::default()
+        }
+    }
+
+    pub fn feed(&mut self, data: &str) {
+        let mut input = std::mem::take(&mut self.pending);
+        input.push_str(data);
+        let split_at = incomplete_escape_start(&input).unwrap_or(input.len());
+        // An escape this long is not going to end; drop it rather than buffer without bound
+        if input.len() - split_at <= MAX_PENDING_ESCAPE_BYTES {
+            self.pending = input[split_at..].to_string();
+        }
+
+        for token in tokenize(&input[..split_at]) {
+            match token {
+                AnsiToken::Text(text) => {
+                    for c in text.chars() {
+                        match c {
+                            '\n' => self.new_line(),
+                            '\t' => {
+                                let spaces = 8 - (self.col % 8);
+                                for _ in 0..spaces {
+                                    self.put(' ');
+                                }
+                            }
+                            _ => self.put(c),
+                        }
+                    }
+                }
+                AnsiToken::Control('\r') => self.col = 0,
+                AnsiToken::Control('\x08') => self.col = self.col.saturating_sub(1),
+                AnsiToken::Control(_) => {}
+                AnsiToken::Csi { params, final_byte } => {
+                    let n = params.first().copied().unwrap_or(0).max(1) as usize;
+                    match final_byte {
+                        'A' => self.row = self.row.saturating_sub(n),
+                        'B' => {
+                            self.row = (self.row + n).min(self.lines.len() - 1);
+                        }
+                        'C' => self.col += n,
+                        'D' => self.col = self.col.saturating_sub(n),
+                        'G' => self.col = n - 1,
+                        'K' => {
+                            let line = &mut self.lines[self.row];
+                            match params.first().copied().unwrap_or(0) {
+                                0 => line.truncate(self.col),
+                                1 => {
+                                    for cell in line.iter_mut().take(self.col + 1) {
+                                        *cell = ' ';
+                                    }
+                                }
+                                _ => line.clear(),
+                            }
+                        }
+                        _ => {}
+                    }
+                }
+            }
+        }
+    }
+
+    fn put(&mut self, c: char) {
+        let line = &mut self.lines[self.row];
+        while line.len() < self.col {
+            line.push(' ');
+        }
+        if self.col < line.len() {
+            line[self.col] = c;
+        } else {
+            line.push(c);
+        }
+        self.col += 1;
+    }
+
+    fn new_line(&mut self) {
+        self.row += 1;
+        self.col = 0;
+        if self.row == self.lines.len() {
+            self.lines.push(Vec::new());
+        }
+        if self.max_lines > 0 && self.lines.len() > self.max_lines {
+            let excess = self.lines.len() - self.max_lines;
+            self.lines.drain(..excess);
+            self.row = self.row.saturating_sub(excess);
+        }
+    }
+
+    pub fn text(&self) -> String {
+        self.lines
+            .iter()
+            .map(|line| line.iter().collect::<String>().trim_end().to_string())
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+}
+
+// Byte offset of a trailing escape sequence that has not been terminated yet
+fn incomplete_escape_start(input: &str) -> Option<usize> {
+    let start = input.rfind('\x1b')?;
+    let mut rest = input[start + 1..].chars();
+    let complete = match rest.next() {
+        None => false,
+        Some('[') => rest.any(|c| ('\x40'..='\x7e').contains(&c)),
+        Some(']') => {
+            let body = &input[start + 2..];
+            body.contains('\x07') || body.contains("\x1b\\")
+        }
+        Some(_) => true,
+    };
+    if complete {
+        None
+    } else {
+        Some(start)
+    }
+}
+
+// One-shot plain-text rendering of a complete chunk of output
+pub fn render_plain_text(input: &str) -> String {
+    let mut renderer = PlainTextRenderer::new(0);
+    renderer.feed(input);
+    renderer.text()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn escape_split_across_feeds_is_held_until_complete() {
+        let mut renderer = PlainTextRenderer::new(0);
+        renderer.feed("a\x1b]0;ti");
+        renderer.feed("tle\x07b");
+        assert_eq!(renderer.text(), "ab");
+    }
+
+    #[test]
+    fn unterminated_escape_is_dropped_past_the_cap() {
+        let mut renderer = PlainTextRenderer::new(0);
+        renderer.feed("a\x1b]0;");
+        for _ in 0..10 {
+            renderer.feed(&"x".repeat(1024));
+        }
+        assert!(renderer.pending.len() <= MAX_PENDING_ESCAPE_BYTES);
+        renderer.feed("\x1b[1mb");
+        assert!(renderer.text().ends_with('b'));
+    }
+}
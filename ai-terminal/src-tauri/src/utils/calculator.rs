@@ -0,0 +1,524 @@
+// Arithmetic evaluation and unit conversion backing the /calc special command
+
+// Deep enough for any real expression; bounds the parser's recursion on input like "((((...".
+const MAX_NESTING_DEPTH: usize = 100;
+
+#[derive(Clone, Copy, PartialEq)]
+enum Dimension {
+    Data,
+    Length,
+    Mass,
+    Time,
+    Temperature,
+}
+
+struct Unit {
+    names: &'static [&'static str],
+    dimension: Dimension,
+    // Multiplier to the dimension's base unit (bytes, meters, grams, seconds, kelvin)
+    factor: f64,
+}
+
+const fn unit(names: &'static [&'static str], dimension: Dimension, factor: f64) -> Unit {
+    Unit {
+        names,
+        dimension,
+        factor,
+    }
+}
+
+const UNITS: &[Unit] = &[
+    // Data units are case-sensitive: B is a byte, b a bit
+    unit(&["B", "byte", "bytes"], Dimension::Data, 1.0),
+    unit(&["b", "bit", "bits"], Dimension::Data, 0.125),
+    unit(&["kB", "KB"], Dimension::Data, 1e3),
+    unit(&["MB"], Dimension::Data, 1e6),
+    unit(&["GB"], Dimension::Data, 1e9),
+    unit(&["TB"], Dimension::Data, 1e12),
+    unit(&["KiB"], Dimension::Data, 1024.0),
+    unit(&["MiB"], Dimension::Data, 1048576.0),
+    unit(&["GiB"], Dimension::Data, 1073741824.0),
+    unit(&["TiB"], Dimension::Data, 1099511627776.0),
+    unit(&["kb", "Kb", "kbit"], Dimension::Data, 125.0),
+    unit(&["Mb", "Mbit"], Dimension::Data, 1.25e5),
+    unit(&["Gb", "Gbit"], Dimension::Data, 1.25e8),
+    unit(&["Tb", "Tbit"], Dimension::Data, 1.25e11),
+    unit(&["mm"], Dimension::Length, 0.001),
+    unit(&["cm"], Dimension::Length, 0.01),
+    unit(&["m", "meter", "meters"], Dimension::Length, 1.0),
+    unit(&["km"], Dimension::Length, 1000.0),
+    unit(&["in", "inch", "inches"], Dimension::Length, 0.0254),
+    unit(&["ft", "foot", "feet"], Dimension::Length, 0.3048),
+    unit(&["yd", "yard", "yards"], Dimension::Length, 0.9144),
+    unit(&["mi", "mile", "miles"], Dimension::Length, 1609.344),
+    unit(&["mg"], Dimension::Mass, 0.001),
+    unit(&["g", "gram", "grams"], Dimension::Mass, 1.0),
+    unit(&["kg"], Dimension::Mass, 1000.0),
+    unit(&["oz"], Dimension::Mass, 28.349523125),
+    unit(&["lb", "lbs"], Dimension::Mass, 453.59237),
+    unit(&["ms"], Dimension::Time, 0.001),
+    unit(&["s", "sec", "secs", "seconds"], Dimension::Time, 1.0),
+    unit(&["min", "mins", "minutes"], Dimension::Time, 60.0),
+    unit(&["h", "hr", "hrs", "hours"], Dimension::Time, 3600.0),
+    unit(&["d", "day", "days"], Dimension::Time, 86400.0),
+    unit(&["wk", "week", "weeks"], Dimension::Time, 604800.0),
+    unit(&["c", "celsius"], Dimension::Temperature, 1.0),
+    unit(&["f", "fahrenheit"], Dimension::Temperature, 1.0),
+    unit(&["k", "kelvin"], Dimension::Temperature, 1.0),
+];
+
+fn find_unit(name: &str) -> Option<&'static Unit> {
+    let lower = name.to_lowercase();
+    UNITS.iter().find(|unit| {
+        let name = if unit.dimension == Dimension::Data {
+            name
+        } else {
+            lower.as_str()
+        };
+        unit.names.contains(&name)
+    })
+}
+
+// Temperatures are affine, so they go through kelvin instead of a plain factor
+fn to_kelvin(value: f64, unit: &Unit) -> f64 {
+    match unit.names[0] {
+        "c" => value + 273.15,
+        "f" => (value - 32.0) * 5.0 / 9.0 + 273.15,
+        _ => value,
+    }
+}
+
+fn from_kelvin(value: f64, unit: &Unit) -> f64 {
+    match unit.names[0] {
+        "c" => value - 273.15,
+        "f" => (value - 273.15) * 9.0 / 5.0 + 32.0,
+        _ => value,
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Token {
+    Number(f64),
+    Ident(String),
+    Op(char),
+    LParen,
+    RParen,
+    Comma,
+}
+
+fn tokenize(input: &str) -> Result<Vec<Token>, String> {
+    let mut tokens = Vec::new();
+    let mut chars = input.chars().peekable();
+
+    while let Some(&c) = chars.peek() {
+        match c {
+            c if c.is_whitespace() => {
+                chars.next();
+            }
+            '0'..='9' | '.' => {
+                let mut number = String::new();
+                while let Some(&d) = chars.peek() {
+                    if d.is_ascii_digit() || d == '.' || d == '_' {
+                        if d != '_' {
+                            number.push(d);
+                        }
+                        chars.next();
+                    } else if (d == 'e' || d == 'E') && !number.is_empty() {
+                        // Scientific notation such as 1.5e3, only when an exponent follows
+                        let mut lookahead = chars.clone();
+                        lookahead.next();
+                        let mut exponent = String::from("e");
+                        if let Some(&sign @ ('+' | '-')) = lookahead.peek() {
+                            exponent.push(sign);
+                            lookahead.next();
+                        }
+                        if !lookahead.peek().is_some_and(|x| x.is_ascii_digit()) {
+                            break;
+                        }
+                        number.push_str(&exponent);
+                        chars = lookahead;
+                    } else {
+                        break;
+                    }
+                }
+                // Hex literals such as 0xff
+                if number == "0" && matches!(chars.peek(), Some('x') | Some('X')) {
+                    chars.next();
+                    let mut hex = String::new();
+                    while let Some(&h) = chars.peek() {
+                        if h.is_ascii_hexdigit() {
+                            hex.push(h);
+                            chars.next();
+                        } else {
+                            break;
+                        }
+                    }
+                    let value = u64::from_str_radix(&hex, 16)
+                        .map_err(|_| format!("Invalid hex literal: 0x{}", hex))?;
+                    tokens.push(Token::Number(value as f64));
+                    continue;
+                }
+                let value = number
+                    .parse::<f64>()
+                    .map_err(|_| format!("Invalid number: {}", number))?;
+                tokens.push(Token::Number(value));
+            }
+            c if c.is_alphabetic() => {
+                let mut ident = String::new();
+                while let Some(&a) = chars.peek() {
+                    if a.is_alphanumeric() || a == '_' {
+                        ident.push(a);
+                        chars.next();
+                    } else {
+                        break;
+                    }
+                }
+                tokens.push(Token::Ident(ident));
+            }
+            '+' | '-' | '*' | '/' | '%' | '^' => {
+                chars.next();
+                // Accept ** as an alias for ^
+                if c == '*' && chars.peek() == Some(&'*') {
+                    chars.next();
+                    tokens.push(Token::Op('^'));
+                } else {
+                    tokens.push(Token::Op(c));
+                }
+            }
+            '(' => {
+                chars.next();
+                tokens.push(Token::LParen);
+            }
+            ')' => {
+                chars.next();
+                tokens.push(Token::RParen);
+            }
+            ',' => {
+                chars.next();
+                tokens.push(Token::Comma);
+            }
+            _ => return Err(format!("Unexpected character '{}'", c)),
+        }
+    }
+    Ok(tokens)
+}
+
+// Recursive-descent parser: expr := term (('+'|'-') term)*, term := unary (('*'|'/'|'%') unary)*,
+// unary := '-' unary | power, power := primary ('^' unary)?
+struct Parser {
+    tokens: Vec<Token>,
+    pos: usize,
+    // Every recursive rule goes through unary, which counts its nesting here
+    depth: usize,
+}
+
+impl Parser {
+    fn peek(&self) -> Option<&Token> {
+        self.tokens.get(self.pos)
+    }
+
+    fn next(&mut self) -> Option<Token> {
+        let token = self.tokens.get(self.pos).cloned();
+        self.pos += 1;
+        token
+    }
+
+    fn expr(&mut self) -> Result<f64, String> {
+        let mut value = self.term()?;
+        while let Some(Token::Op(op @ ('+' | '-'))) = self.peek().cloned() {
+            self.pos += 1;
+            let rhs = self.term()?;
+            value = if op == '+' { value + rhs } else { value - rhs };
+        }
+        Ok(value)
+    }
+
+    fn term(&mut self) -> Result<f64, String> {
+        let mut value = self.unary()?;
+        loop {
+            match self.peek().cloned() {
+                Some(Token::Op(op @ ('*' | '/' | '%'))) => {
+                    self.pos += 1;
+                    let rhs = self.unary()?;
+                    value = match op {
+                        '*' => value * rhs,
+                        '/' if rhs == 0.0 => return Err("Division by zero".to_string()),
+                        '/' => value / rhs,
+                        _ => value % rhs,
+                    };
+                }
+                // Implicit multiplication: 2(3+4), 2pi
+                Some(Token::LParen) | Some(Token::Ident(_)) => {
+                    let rhs = self.unary()?;
+                    value *= rhs;
+                }
+                _ => return Ok(value),
+            }
+        }
+    }
+
+    fn unary(&mut self) -> Result<f64, String> {
+        if self.depth >= MAX_NESTING_DEPTH {
+            return Err("Expression is nested too deeply".to_string());
+        }
+        self.depth += 1;
+        let value = self.signed();
+        self.depth -= 1;
+        value
+    }
+
+    fn signed(&mut self) -> Result<f64, String> {
+        match self.peek() {
+            Some(Token::Op('-')) => {
+                self.pos += 1;
+                Ok(-self.unary()?)
+            }
+            Some(Token::Op('+')) => {
+                self.pos += 1;
+                self.unary()
+            }
+            _ => self.power(),
+        }
+    }
+
+    fn power(&mut self) -> Result<f64, String> {
+        let base = self.primary()?;
+        if let Some(Token::Op('^')) = self.peek() {
+            self.pos += 1;
+            // Right-associative
+            let exponent = self.unary()?;
+            return Ok(base.powf(exponent));
+        }
+        Ok(base)
+    }
+
+    fn primary(&mut self) -> Result<f64, String> {
+        match self.next() {
+            Some(Token::Number(value)) => Ok(value),
+            Some(Token::LParen) => {
+                let value = self.expr()?;
+                match self.next() {
+                    Some(Token::RParen) => Ok(value),
+                    _ => Err("Missing closing parenthesis".to_string()),
+                }
+            }
+            Some(Token::Ident(name)) => {
+                if let Some(Token::LParen) = self.peek() {
+                    self.pos += 1;
+                    let mut args = vec![self.expr()?];
+                    while let Some(Token::Comma) = self.peek() {
+                        self.pos += 1;
+                        args.push(self.expr()?);
+                    }
+                    match self.next() {
+                        Some(Token::RParen) => call_function(&name, &args),
+                        _ => Err(format!("Missing closing parenthesis after {}(", name)),
+                    }
+                } else {
+                    match name.to_lowercase().as_str() {
+                        "pi" => Ok(std::f64::consts::PI),
+                        "e" => Ok(std::f64::consts::E),
+                        "tau" => Ok(std::f64::consts::TAU),
+                        _ => Err(format!("Unknown identifier: {}", name)),
+                    }
+                }
+            }
+            Some(token) => Err(format!("Unexpected token: {:?}", token)),
+            None => Err("Unexpected end of expression".to_string()),
+        }
+    }
+}
+
+fn call_function(name: &str, args: &[f64]) -> Result<f64, String> {
+    let single = |f: fn(f64) -> f64| -> Result<f64, String> {
+        match args {
+            [x] => Ok(f(*x)),
+            _ => Err(format!("{}() takes exactly one argument", name)),
+        }
+    };
+    match name.to_lowercase().as_str() {
+        "sqrt" => single(f64::sqrt),
+        "abs" => single(f64::abs),
+        "round" => single(f64::round),
+        "floor" => single(f64::floor),
+        "ceil" => single(f64::ceil),
+        "sin" => single(f64::sin),
+        "cos" => single(f64::cos),
+        "tan" => single(f64::tan),
+        "ln" => single(f64::ln),
+        "log" | "log10" => single(f64::log10),
+        "log2" => single(f64::log2),
+        "exp" => single(f64::exp),
+        "min" | "max" if !args.is_empty() => {
+            let pick = if name.eq_ignore_ascii_case("min") {
+                f64::min
+            } else {
+                f64::max
+            };
+            Ok(args.iter().copied().fold(args[0], pick))
+        }
+        _ => Err(format!("Unknown function: {}", name)),
+    }
+}
+
+pub fn evaluate(expression: &str) -> Result<f64, String> {
+    let tokens = tokenize(expression)?;
+    if tokens.is_empty() {
+        return Err("Empty expression".to_string());
+    }
+    let mut parser = Parser {
+        tokens,
+        pos: 0,
+        depth: 0,
+    };
+    let value = parser.expr()?;
+    if parser.pos < parser.tokens.len() {
+        return Err(format!("Unexpected token: {:?}", parser.tokens[parser.pos]));
+    }
+    if !value.is_finite() {
+        return Err("Result is not a finite number".to_string());
+    }
+    Ok(value)
+}
+
+pub fn format_number(value: f64) -> String {
+    if value.fract() == 0.0 && value.abs() < 1e15 {
+        return format!("{}", value as i64);
+    }
+    let formatted = format!("{:.10}", value);
+    formatted
+        .trim_end_matches('0')
+        .trim_end_matches('.')
+        .to_string()
+}
+
+// Splits "3*(2^10) bytes to MiB" into the source expression, source unit and target unit
+fn split_conversion(input: &str) -> Option<(&str, &str, &str)> {
+    let (source, target) = input
+        .rsplit_once(" to ")
+        .or_else(|| input.rsplit_once(" in "))?;
+    let target = target.trim();
+    let source = source.trim_end();
+    let split = source
+        .rfind(|c: char| !c.is_alphabetic())
+        .map(|i| i + 1)
+        .unwrap_or(0);
+    let source_unit = &source[split..];
+    if find_unit(source_unit).is_none() || find_unit(target).is_none() {
+        return None;
+    }
+    Some((source[..split].trim_end(), source_unit, target))
+}
+
+// Evaluates "/calc" input: a plain expression or "<expr> <unit> to <unit>"
+pub fn calculate(input: &str) -> Result<String, String> {
+    let input = input.trim();
+    if input.is_empty() {
+        return Err(
+            "Usage: /calc <expression> [unit to unit], e.g. /calc 3*(2^10) bytes to KiB"
+                .to_string(),
+        );
+    }
+
+    let Some((expression, from_name, to_name)) = split_conversion(input) else {
+        return evaluate(input).map(format_number);
+    };
+    let (from, to) = match (find_unit(from_name), find_unit(to_name)) {
+        (Some(from), Some(to)) => (from, to),
+        _ => return Err("Unknown unit".to_string()),
+    };
+
+    if from.dimension != to.dimension {
+        return Err(format!(
+            "Cannot convert {} to {}: incompatible units",
+            from.names[0], to.names[0]
+        ));
+    }
+    let value = evaluate(expression)?;
+    let converted = if from.dimension == Dimension::Temperature {
+        from_kelvin(to_kelvin(value, from), to)
+    } else {
+        value * from.factor / to.factor
+    };
+    Ok(format!(
+        "{} {} = {} {}",
+        format_number(value),
+        from_name,
+        format_number(converted),
+        to_name
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn eval(expression: &str) -> String {
+        evaluate(expression).map(format_number).unwrap()
+    }
+
+    #[test]
+    fn arithmetic() {
+        assert_eq!(eval("1 + 2 * 3"), "7");
+        assert_eq!(eval("(1 + 2) * 3"), "9");
+        assert_eq!(eval("2^3^2"), "512");
+        assert_eq!(eval("2**10"), "1024");
+        assert_eq!(eval("-2^2"), "-4");
+        assert_eq!(eval("10 % 3"), "1");
+        assert_eq!(eval("2(3 + 4)"), "14");
+        assert_eq!(eval("2pi"), "6.2831853072");
+        assert_eq!(eval("0.1 + 0.2"), "0.3");
+    }
+
+    #[test]
+    fn literals_and_functions() {
+        assert_eq!(eval("0xff + 1e3"), "1255");
+        assert_eq!(eval("1_000 * 1.5E-3"), "1.5");
+        assert_eq!(eval("sqrt(16) + abs(-2)"), "6");
+        assert_eq!(eval("max(1, 5, 3) - min(4, 2)"), "3");
+        assert_eq!(eval("log2(1024)"), "10");
+    }
+
+    #[test]
+    fn errors() {
+        assert_eq!(evaluate("1/0").unwrap_err(), "Division by zero");
+        assert_eq!(evaluate("foo").unwrap_err(), "Unknown identifier: foo");
+        assert_eq!(evaluate("nope(1)").unwrap_err(), "Unknown function: nope");
+        assert_eq!(
+            evaluate("sqrt(1, 2)").unwrap_err(),
+            "sqrt() takes exactly one argument"
+        );
+        assert_eq!(evaluate("1 +").unwrap_err(), "Unexpected end of expression");
+        assert_eq!(evaluate("(1").unwrap_err(), "Missing closing parenthesis");
+        assert_eq!(evaluate("1 $ 2").unwrap_err(), "Unexpected character '$'");
+        assert_eq!(
+            evaluate("2^10000").unwrap_err(),
+            "Result is not a finite number"
+        );
+        let nested = format!("{}1{}", "(".repeat(200), ")".repeat(200));
+        assert_eq!(
+            evaluate(&nested).unwrap_err(),
+            "Expression is nested too deeply"
+        );
+        assert!(calculate("  ").unwrap_err().starts_with("Usage"));
+    }
+
+    #[test]
+    fn unit_conversions() {
+        assert_eq!(
+            calculate("3*(2^10) bytes to KiB").unwrap(),
+            "3072 bytes = 3 KiB"
+        );
+        assert_eq!(calculate("1 Gb to MB").unwrap(), "1 Gb = 125 MB");
+        assert_eq!(calculate("1 GB in Gb").unwrap(), "1 GB = 8 Gb");
+        assert_eq!(calculate("100 c to f").unwrap(), "100 c = 212 f");
+        assert_eq!(calculate("0 C to K").unwrap(), "0 C = 273.15 K");
+        assert_eq!(calculate("1 mile to km").unwrap(), "1 mile = 1.609344 km");
+        assert_eq!(
+            calculate("1 kg to m").unwrap_err(),
+            "Cannot convert kg to m: incompatible units"
+        );
+        // Data units are case-sensitive, so "mb" is not one
+        assert!(calculate("1 mb to kb").is_err());
+    }
+}
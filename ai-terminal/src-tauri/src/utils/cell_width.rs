@@ -0,0 +1,87 @@
+use serde::Serialize;
+use tauri::command;
+use unicode_segmentation::UnicodeSegmentation;
+use unicode_width::UnicodeWidthStr;
+
+// Terminal cells one grapheme cluster occupies. Emoji sequences (ZWJ families, flags, keycaps,
+// VS16 presentation) render as a single wide glyph; nothing is wider than two cells.
+pub fn grapheme_width(grapheme: &str) -> usize {
+    if grapheme.contains(['\u{200D}', '\u{FE0F}']) {
+        return 2;
+    }
+    UnicodeWidthStr::width(grapheme).min(2)
+}
+
+pub fn display_width(text: &str) -> usize {
+    text.graphemes(true).map(grapheme_width).sum()
+}
+
+// Replaces tabs with spaces up to the next tab stop, counting wide characters as two
+// columns so tables stay aligned in viewers that measure tabs differently
+pub fn expand_tabs(text: &str, tab_width: usize) -> String {
+    if !text.contains('\t') {
+        return text.to_string();
+    }
+    let mut expanded = String::with_capacity(text.len());
+    let mut column = 0;
+    for grapheme in text.graphemes(true) {
+        match grapheme {
+            "\t" => {
+                let spaces = tab_width - column % tab_width;
+                expanded.extend(std::iter::repeat_n(' ', spaces));
+                column += spaces;
+            }
+            "\n" | "\r\n" | "\r" => {
+                expanded.push_str(grapheme);
+                column = 0;
+            }
+            _ => {
+                expanded.push_str(grapheme);
+                column += grapheme_width(grapheme);
+            }
+        }
+    }
+    expanded
+}
+
+#[derive(Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct GraphemeCell {
+    // UTF-16 offsets, matching JavaScript string indices
+    pub start: usize,
+    pub end: usize,
+    // Cell column the grapheme starts at
+    pub column: usize,
+    pub width: usize,
+}
+
+#[derive(Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct CellMeasurement {
+    pub width: usize,
+    pub graphemes: Vec<GraphemeCell>,
+}
+
+// Cell layout of a single line, so the input line can place the cursor on CJK and emoji
+#[command]
+pub fn measure_cells(text: String) -> CellMeasurement {
+    let mut graphemes = Vec::new();
+    let mut offset = 0;
+    let mut column = 0;
+    for grapheme in text.graphemes(true) {
+        let length = grapheme.encode_utf16().count();
+        let width = grapheme_width(grapheme);
+        graphemes.push(GraphemeCell {
+            start: offset,
+            end: offset + length,
+            column,
+            width,
+        });
+        offset += length;
+        column += width;
+    }
+    CellMeasurement {
+        width: column,
+        graphemes,
+    }
+}
@@ -1,10 +1,16 @@
 use crate::command::types::command_manager::CommandManager;
+use crate::ollama::mock_provider::mock_models_listing;
+use crate::ollama::types::ai_provider::AiProvider;
 use crate::ollama::types::ollama_model_list::OllamaModelList;
-use tauri::State;
+use crate::utils::calculator::calculate;
+use crate::utils::http_request::run_http_command;
+use tauri::{AppHandle, Runtime, State};
 
-// Handle special commands like /help, /models, /model
-pub async fn handle_special_command(
+// Handle special commands like /help, /models, /model, /provider, /calc, /http
+pub async fn handle_special_command<R: Runtime>(
     command: String,
+    session_id: Option<String>,
+    app_handle: &AppHandle<R>,
     command_manager: State<'_, CommandManager>,
 ) -> Result<String, String> {
     match command.as_str() {
@@ -12,7 +18,10 @@ pub async fn handle_special_command(
                 /help - Show this help message\n\
                 /models - List available models\n\
                 /model [name] - Show current model or switch to a different model\n\
-                /host [url] - Show current API host or set a new one"
+                /host [url] - Show current API host or set a new one\n\
+                /provider [ollama|mock] - Show current AI provider or switch providers\n\
+                /calc <expr> [unit to unit] - Evaluate math or convert units, e.g. /calc 3*(2^10) bytes to KiB\n\
+                /http [METHOD] <url> [-H 'Name: value'] [-d body] - Send an HTTP request and show the response with timings"
             .to_string()),
         "/models" => {
             // Get list of available models from Ollama API
@@ -21,6 +30,9 @@ pub async fn handle_special_command(
             // Scope the mutex lock to drop it before any async operations
             {
                 let ollama_state = command_manager.ollama.lock().map_err(|e| e.to_string())?;
+                if ollama_state.provider == AiProvider::Mock {
+                    return Ok(mock_models_listing());
+                }
                 api_host = ollama_state.api_host.clone();
                 // MutexGuard is dropped here
             }
@@ -47,6 +59,10 @@ pub async fn handle_special_command(
             }
             Ok(result)
         }
+        cmd if cmd == "/calc" || cmd.starts_with("/calc ") => calculate(&cmd["/calc".len()..]),
+        cmd if cmd == "/http" || cmd.starts_with("/http ") => {
+            run_http_command(app_handle, session_id.as_deref(), cmd).await
+        }
         cmd if cmd.starts_with("/model") => {
             let parts: Vec<&str> = cmd.split_whitespace().collect();
 
@@ -72,6 +88,23 @@ pub async fn handle_special_command(
                 Err("Invalid model command. Use /model [name] to switch models.".to_string())
             }
         }
+        cmd if cmd.starts_with("/provider") => {
+            let parts: Vec<&str> = cmd.split_whitespace().collect();
+            let mut ollama_state = command_manager.ollama.lock().map_err(|e| e.to_string())?;
+            match parts.get(1) {
+                None => Ok(format!(
+                    "Current AI provider: {}",
+                    ollama_state.provider.as_str()
+                )),
+                Some(name) => {
+                    ollama_state.provider = AiProvider::parse(name)?;
+                    Ok(format!(
+                        "Switched AI provider to: {}",
+                        ollama_state.provider.as_str()
+                    ))
+                }
+            }
+        }
         cmd if cmd.starts_with("/host") => {
             let parts: Vec<&str> = cmd.split_whitespace().collect();
 
@@ -0,0 +1,63 @@
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use std::fs;
+use std::path::PathBuf;
+
+// Replaces the user's config and data dirs for the whole process once set, so harness tests
+// never read or write the user's settings, history or audit log
+#[cfg(feature = "test-harness")]
+static TEST_APP_ROOT: std::sync::OnceLock<PathBuf> = std::sync::OnceLock::new();
+
+// The first call wins; returns the root in use
+#[cfg(feature = "test-harness")]
+pub fn set_test_app_root(root: PathBuf) -> &'static PathBuf {
+    TEST_APP_ROOT.get_or_init(|| root)
+}
+
+fn base_dir(user_dir: Option<PathBuf>, _test_subdir: &str) -> Option<PathBuf> {
+    #[cfg(feature = "test-harness")]
+    if let Some(root) = TEST_APP_ROOT.get() {
+        return Some(root.join(_test_subdir));
+    }
+    user_dir
+}
+
+// Directory holding the app's persisted JSON configuration (e.g. ~/.config/ai-terminal)
+pub fn app_config_dir() -> Result<PathBuf, String> {
+    let dir = base_dir(dirs::config_dir(), "config")
+        .ok_or("Could not determine config directory")?
+        .join("ai-terminal");
+    fs::create_dir_all(&dir).map_err(|e| format!("Failed to create {}: {}", dir.display(), e))?;
+    Ok(dir)
+}
+
+// Directory for app-generated data such as logs and history (e.g. ~/.local/share/ai-terminal)
+pub fn app_data_dir() -> Result<PathBuf, String> {
+    let dir = base_dir(dirs::data_dir(), "data")
+        .ok_or("Could not determine data directory")?
+        .join("ai-terminal");
+    fs::create_dir_all(&dir).map_err(|e| format!("Failed to create {}: {}", dir.display(), e))?;
+    Ok(dir)
+}
+
+// Loads a JSON config file, falling back to the default value if it is missing or unreadable
+pub fn load_json_config<T: DeserializeOwned + Default>(file_name: &str) -> T {
+    app_config_dir()
+        .ok()
+        .and_then(|dir| fs::read_to_string(dir.join(file_name)).ok())
+        .and_then(|contents| serde_json::from_str(&contents).ok())
+        .unwrap_or_default()
+}
+
+pub fn save_json_config<T: Serialize>(file_name: &str, value: &T) -> Result<(), String> {
+    let path = app_config_dir()?.join(file_name);
+    let contents = serde_json::to_string_pretty(value).map_err(|e| e.to_string())?;
+    fs::write(&path, contents).map_err(|e| format!("Failed to write {}: {}", path.display(), e))
+}
+
+pub fn unix_timestamp_millis() -> u64 {
+    std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map(|d| d.as_millis() as u64)
+        .unwrap_or(0)
+}
@@ -0,0 +1,107 @@
+use aes_gcm::aead::rand_core::RngCore;
+use aes_gcm::aead::{Aead, AeadCore, KeyInit, Nonce, OsRng};
+use aes_gcm::Aes256Gcm;
+use argon2::Argon2;
+use base64::engine::general_purpose::STANDARD;
+use base64::Engine;
+use chacha20poly1305::ChaCha20Poly1305;
+use serde::{Deserialize, Serialize};
+
+const SALT_BYTES: usize = 16;
+// Both ciphers take a 96-bit nonce
+const NONCE_BYTES: usize = 12;
+
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Cipher {
+    // Synced history; data written before the cipher was recorded uses it too
+    #[default]
+    #[serde(rename = "aes-256-gcm")]
+    Aes256Gcm,
+    // Backups
+    #[serde(rename = "chacha20-poly1305")]
+    ChaCha20Poly1305,
+}
+
+// Ciphertext under a key derived from a passphrase with Argon2id, base64-encoded for embedding
+// in JSON files (synced history, backups). Whoever stores it never sees the key.
+#[derive(Serialize, Deserialize)]
+pub struct SealedData {
+    #[serde(default)]
+    pub cipher: Cipher,
+    pub salt: String,
+    pub nonce: String,
+    pub ciphertext: String,
+}
+
+fn derive_key(passphrase: &str, salt: &[u8]) -> Result<[u8; 32], String> {
+    let mut key = [0u8; 32];
+    Argon2::default()
+        .hash_password_into(passphrase.as_bytes(), salt, &mut key)
+        .map_err(|e| format!("Failed to derive the encryption key: {}", e))?;
+    Ok(key)
+}
+
+// (nonce, ciphertext)
+fn encrypt_with<C: KeyInit + Aead + AeadCore>(
+    key: &[u8],
+    plaintext: &[u8],
+) -> Option<(Vec<u8>, Vec<u8>)> {
+    let cipher = C::new_from_slice(key).ok()?;
+    let nonce = C::generate_nonce(&mut OsRng);
+    let ciphertext = cipher.encrypt(&nonce, plaintext).ok()?;
+    Some((nonce.to_vec(), ciphertext))
+}
+
+fn decrypt_with<C: KeyInit + Aead>(key: &[u8], nonce: &[u8], ciphertext: &[u8]) -> Option<Vec<u8>> {
+    C::new_from_slice(key)
+        .ok()?
+        .decrypt(Nonce::<C>::from_slice(nonce), ciphertext)
+        .ok()
+}
+
+// `what` names the data in error messages, e.g. "synced history"
+pub fn seal(
+    cipher: Cipher,
+    passphrase: &str,
+    plaintext: &[u8],
+    what: &str,
+) -> Result<SealedData, String> {
+    let mut salt = [0u8; SALT_BYTES];
+    OsRng.fill_bytes(&mut salt);
+    let key = derive_key(passphrase, &salt)?;
+    let (nonce, ciphertext) = match cipher {
+        Cipher::Aes256Gcm => encrypt_with::<Aes256Gcm>(&key, plaintext),
+        Cipher::ChaCha20Poly1305 => encrypt_with::<ChaCha20Poly1305>(&key, plaintext),
+    }
+    .ok_or_else(|| format!("Failed to encrypt the {}", what))?;
+    Ok(SealedData {
+        cipher,
+        salt: STANDARD.encode(salt),
+        nonce: STANDARD.encode(nonce),
+        ciphertext: STANDARD.encode(ciphertext),
+    })
+}
+
+pub fn open(passphrase: &str, sealed: &SealedData, what: &str) -> Result<Vec<u8>, String> {
+    let decode = |value: &str| {
+        STANDARD
+            .decode(value)
+            .map_err(|e| format!("The {} is corrupted: {}", what, e))
+    };
+    let nonce = decode(&sealed.nonce)?;
+    if nonce.len() != NONCE_BYTES {
+        return Err(format!("The {} is corrupted: bad nonce", what));
+    }
+    let ciphertext = decode(&sealed.ciphertext)?;
+    let key = derive_key(passphrase, &decode(&sealed.salt)?)?;
+    match sealed.cipher {
+        Cipher::Aes256Gcm => decrypt_with::<Aes256Gcm>(&key, &nonce, &ciphertext),
+        Cipher::ChaCha20Poly1305 => decrypt_with::<ChaCha20Poly1305>(&key, &nonce, &ciphertext),
+    }
+    .ok_or_else(|| {
+        format!(
+            "Could not decrypt the {}: wrong passphrase or corrupted data",
+            what
+        )
+    })
+}
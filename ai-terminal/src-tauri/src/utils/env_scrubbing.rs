@@ -0,0 +1,40 @@
+use crate::settings::types::app_settings::AppSettings;
+use std::collections::HashMap;
+
+// Case-insensitive match of a variable name against a pattern with `*` wildcards
+pub fn env_pattern_matches(pattern: &str, name: &str) -> bool {
+    let pattern = pattern.to_uppercase();
+    let name = name.to_uppercase();
+    let parts: Vec<&str> = pattern.split('*').collect();
+    if parts.len() == 1 {
+        return pattern == name;
+    }
+
+    let (first, last) = (parts[0], parts[parts.len() - 1]);
+    if !name.starts_with(first) || !name[first.len()..].ends_with(last) {
+        return false;
+    }
+    let mut rest = &name[first.len()..name.len() - last.len()];
+    for part in &parts[1..parts.len() - 1] {
+        match rest.find(part) {
+            Some(index) => rest = &rest[index + part.len()..],
+            None => return false,
+        }
+    }
+    true
+}
+
+pub fn is_env_var_allowed(settings: &AppSettings, name: &str) -> bool {
+    if !settings.env_scrubbing_enabled {
+        return true;
+    }
+    let matches = |patterns: &[String]| patterns.iter().any(|p| env_pattern_matches(p, name));
+    matches(&settings.env_allowlist) || !matches(&settings.env_denylist)
+}
+
+// The app environment with denied variables removed, ready to hand to a child process
+pub fn scrubbed_environment(settings: &AppSettings) -> HashMap<String, String> {
+    std::env::vars()
+        .filter(|(name, _)| is_env_var_allowed(settings, name))
+        .collect()
+}
@@ -1,8 +1,82 @@
 use crate::command::types::command_manager::CommandManager;
+use crate::settings::types::app_settings::CdMode;
 use std::env;
+use std::fs;
+use std::path::{Component, Path, PathBuf};
 use std::process::Command;
 use tauri::{command, State};
 
+// Resolves a leading ~ to the user's home directory
+pub fn expand_home(path: &str) -> Result<PathBuf, String> {
+    if let Some(rest) = path.strip_prefix('~') {
+        let home = dirs::home_dir().ok_or("Could not determine home directory")?;
+        Ok(home.join(rest.trim_start_matches('/')))
+    } else {
+        Ok(PathBuf::from(path))
+    }
+}
+
+// Relative paths are taken from the session's directory, like the commands typed in it
+pub fn resolve_session_path(
+    command_manager: &CommandManager,
+    session_id: Option<&str>,
+    path: &str,
+) -> Result<PathBuf, String> {
+    let path = expand_home(path.trim())?;
+    if path.is_absolute() {
+        return Ok(path);
+    }
+    let base = session_id
+        .and_then(|session_id| {
+            command_manager
+                .commands
+                .lock()
+                .ok()?
+                .get(session_id)
+                .map(|state| PathBuf::from(&state.current_dir))
+        })
+        .map(Ok)
+        .unwrap_or_else(|| std::env::current_dir().map_err(|e| e.to_string()))?;
+    Ok(base.join(path))
+}
+
+// Splits leading -L/-P flags off cd arguments; the last flag wins, as in POSIX shells
+pub fn split_cd_flags(args: &str, default_mode: CdMode) -> (CdMode, &str) {
+    let mut mode = default_mode;
+    let mut rest = args.trim();
+    loop {
+        let (flag, remainder) = rest.split_once(' ').unwrap_or((rest, ""));
+        mode = match flag {
+            "-L" => CdMode::Logical,
+            "-P" => CdMode::Physical,
+            _ => return (mode, rest),
+        };
+        rest = remainder.trim_start();
+    }
+}
+
+// Removes `.` and resolves `..` against the preceding component without touching the disk
+pub fn normalize_logical_path(path: &Path) -> PathBuf {
+    let mut normalized = PathBuf::new();
+    for component in path.components() {
+        match component {
+            Component::CurDir => {}
+            Component::ParentDir => {
+                normalized.pop();
+            }
+            other => normalized.push(other),
+        }
+    }
+    normalized
+}
+
+pub fn resolve_cd_target(path: &Path, mode: CdMode) -> PathBuf {
+    match mode {
+        CdMode::Logical => normalize_logical_path(path),
+        CdMode::Physical => fs::canonicalize(path).unwrap_or_else(|_| normalize_logical_path(path)),
+    }
+}
+
 pub fn get_shell_path() -> Option<String> {
     // First try to get the user's default shell
     let shell = if cfg!(target_os = "windows") {
@@ -0,0 +1,419 @@
+// Request parsing, sending and formatting backing the /http special command
+
+use crate::command::constants::{HTTP_COMMAND_TIMEOUT_SECS, MAX_HTTP_COMMAND_BODY_BYTES};
+use crate::command::core::command_end::{emit_command_end, CommandEndEvent};
+use crate::command::core::command_output::{emit_command_error, emit_command_output};
+use crate::command::types::scrollback_manager::{OutputStream, ScrollbackManager};
+use crate::command::windows::session_windows::emit_session_event;
+use serde::Serialize;
+use std::net::{SocketAddr, ToSocketAddrs};
+use std::time::{Duration, Instant};
+use tauri::{AppHandle, Manager, Runtime};
+
+pub const HTTP_RESPONSE_EVENT: &str = "http_response";
+
+const USAGE: &str =
+    "Usage: /http [METHOD] <url> [-H 'Name: value']... [-d <body> | --json <body>] \
+     [--timeout <secs>], e.g. /http GET https://api.github.com/zen";
+
+const METHODS: &[&str] = &["GET", "POST", "PUT", "PATCH", "DELETE", "HEAD", "OPTIONS"];
+
+#[derive(Debug, PartialEq)]
+pub struct HttpRequestSpec {
+    pub method: String,
+    pub url: String,
+    pub headers: Vec<(String, String)>,
+    pub body: Option<String>,
+    pub timeout: Duration,
+}
+
+#[derive(Serialize, Clone, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct HttpTiming {
+    // Name resolution, done up front so the request reuses the address
+    pub dns_ms: u64,
+    // Request sent until the response headers arrived: connect, TLS and server time
+    pub first_byte_ms: u64,
+    pub download_ms: u64,
+    pub total_ms: u64,
+}
+
+#[derive(Serialize, Clone, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct HttpResponse {
+    pub method: String,
+    pub url: String,
+    // Final URL after redirects
+    pub final_url: String,
+    pub remote_addr: Option<String>,
+    pub version: String,
+    pub status: u16,
+    pub reason: Option<String>,
+    pub headers: Vec<(String, String)>,
+    pub body: String,
+    pub body_bytes: usize,
+    pub body_truncated: bool,
+    pub timing: HttpTiming,
+}
+
+// Splits like a shell would: whitespace separates words, quotes and backslashes group them
+fn split_words(input: &str) -> Result<Vec<String>, String> {
+    let mut words = Vec::new();
+    let mut current = String::new();
+    let mut in_word = false;
+    let mut chars = input.chars();
+
+    while let Some(c) = chars.next() {
+        match c {
+            '\'' => {
+                in_word = true;
+                loop {
+                    match chars.next() {
+                        Some('\'') => break,
+                        Some(inner) => current.push(inner),
+                        None => return Err("Unterminated single quote".to_string()),
+                    }
+                }
+            }
+            '"' => {
+                in_word = true;
+                loop {
+                    match chars.next() {
+                        Some('"') => break,
+                        Some('\\') => match chars.next() {
+                            Some(escaped @ ('"' | '\\' | '$' | '`')) => current.push(escaped),
+                            Some(other) => {
+                                current.push('\\');
+                                current.push(other);
+                            }
+                            None => return Err("Unterminated double quote".to_string()),
+                        },
+                        Some(inner) => current.push(inner),
+                        None => return Err("Unterminated double quote".to_string()),
+                    }
+                }
+            }
+            '\\' => {
+                in_word = true;
+                if let Some(escaped) = chars.next() {
+                    current.push(escaped);
+                }
+            }
+            c if c.is_whitespace() => {
+                if in_word {
+                    words.push(std::mem::take(&mut current));
+                    in_word = false;
+                }
+            }
+            other => {
+                in_word = true;
+                current.push(other);
+            }
+        }
+    }
+    if in_word {
+        words.push(current);
+    }
+    Ok(words)
+}
+
+// Parses the arguments after "/http", curl-style: a body without a method means POST
+pub fn parse_http_command(args: &str) -> Result<HttpRequestSpec, String> {
+    let words = split_words(args)?;
+    let mut method = None;
+    let mut url = None;
+    let mut headers = Vec::new();
+    let mut body = None;
+    let mut timeout = Duration::from_secs(HTTP_COMMAND_TIMEOUT_SECS);
+
+    let mut iter = words.into_iter();
+    while let Some(word) = iter.next() {
+        let mut value_for = |flag: &str| {
+            iter.next()
+                .ok_or_else(|| format!("{} needs a value. {}", flag, USAGE))
+        };
+        match word.as_str() {
+            "-H" | "--header" => {
+                let header = value_for(&word)?;
+                let (name, value) = header
+                    .split_once(':')
+                    .ok_or_else(|| format!("Header '{}' is not in 'Name: value' form", header))?;
+                headers.push((name.trim().to_string(), value.trim().to_string()));
+            }
+            "-d" | "--data" => body = Some(value_for(&word)?),
+            "--json" => {
+                body = Some(value_for(&word)?);
+                headers.push(("Content-Type".to_string(), "application/json".to_string()));
+                headers.push(("Accept".to_string(), "application/json".to_string()));
+            }
+            "-X" | "--request" => method = Some(value_for(&word)?.to_ascii_uppercase()),
+            "-t" | "--timeout" => {
+                let value = value_for(&word)?;
+                let seconds = value
+                    .parse::<f64>()
+                    .ok()
+                    .filter(|seconds| *seconds > 0.0)
+                    .ok_or_else(|| format!("Invalid timeout '{}'", value))?;
+                timeout = Duration::from_secs_f64(seconds);
+            }
+            flag if flag.starts_with('-') => {
+                return Err(format!("Unknown option '{}'. {}", flag, USAGE));
+            }
+            _ if method.is_none()
+                && url.is_none()
+                && METHODS.contains(&word.to_ascii_uppercase().as_str()) =>
+            {
+                method = Some(word.to_ascii_uppercase());
+            }
+            _ if url.is_none() => url = Some(word),
+            _ => return Err(format!("Unexpected argument '{}'. {}", word, USAGE)),
+        }
+    }
+
+    let url = url.ok_or_else(|| USAGE.to_string())?;
+    // Like curl, a bare host means plain http
+    let url = if url.contains("://") {
+        url
+    } else {
+        format!("http://{}", url)
+    };
+    let method = method.unwrap_or_else(|| if body.is_some() { "POST" } else { "GET" }.to_string());
+    Ok(HttpRequestSpec {
+        method,
+        url,
+        headers,
+        body,
+        timeout,
+    })
+}
+
+fn elapsed_ms(since: Instant) -> u64 {
+    since.elapsed().as_millis() as u64
+}
+
+// Resolves the URL's host ahead of the request so its cost shows up separately
+async fn resolve_host(url: &reqwest::Url) -> Result<(u64, Option<SocketAddr>), String> {
+    let Some(host) = url.host_str().map(str::to_string) else {
+        return Ok((0, None));
+    };
+    if url
+        .host()
+        .is_some_and(|host| !matches!(host, url::Host::Domain(_)))
+    {
+        return Ok((0, None));
+    }
+    let port = url.port_or_known_default().unwrap_or(80);
+    let started = Instant::now();
+    let address = tauri::async_runtime::spawn_blocking(move || {
+        (host.as_str(), port)
+            .to_socket_addrs()
+            .map(|mut addresses| addresses.next())
+            .map_err(|e| format!("Could not resolve '{}': {}", host, e))
+    })
+    .await
+    .map_err(|e| e.to_string())??;
+    Ok((elapsed_ms(started), address))
+}
+
+fn describe_request_error(error: reqwest::Error, timeout: Duration) -> String {
+    if error.is_timeout() {
+        format!("Request timed out after {:.1}s", timeout.as_secs_f64())
+    } else if error.is_connect() {
+        format!("Could not connect: {}", error)
+    } else {
+        format!("Request failed: {}", error)
+    }
+}
+
+pub async fn send_http_request(spec: &HttpRequestSpec) -> Result<HttpResponse, String> {
+    let url =
+        reqwest::Url::parse(&spec.url).map_err(|e| format!("Invalid URL '{}': {}", spec.url, e))?;
+    let method = reqwest::Method::from_bytes(spec.method.as_bytes())
+        .map_err(|_| format!("Invalid method '{}'", spec.method))?;
+
+    let started = Instant::now();
+    let (dns_ms, address) = resolve_host(&url).await?;
+    let mut builder = reqwest::Client::builder().timeout(spec.timeout);
+    if let (Some(host), Some(address)) = (url.host_str(), address) {
+        builder = builder.resolve(host, address);
+    }
+    let client = builder.build().map_err(|e| e.to_string())?;
+
+    let mut request = client.request(method, url);
+    for (name, value) in &spec.headers {
+        request = request.header(name, value);
+    }
+    if let Some(body) = &spec.body {
+        request = request.body(body.clone());
+    }
+
+    let sent = Instant::now();
+    let mut response = request
+        .send()
+        .await
+        .map_err(|e| describe_request_error(e, spec.timeout))?;
+    let first_byte_ms = elapsed_ms(sent);
+
+    let final_url = response.url().to_string();
+    let remote_addr = response.remote_addr().map(|addr| addr.to_string());
+    let version = format!("{:?}", response.version());
+    let status = response.status();
+    let headers = response
+        .headers()
+        .iter()
+        .map(|(name, value)| {
+            (
+                name.to_string(),
+                String::from_utf8_lossy(value.as_bytes()).to_string(),
+            )
+        })
+        .collect::<Vec<_>>();
+
+    let downloading = Instant::now();
+    let mut bytes = Vec::new();
+    let mut body_bytes = 0;
+    while let Some(chunk) = response
+        .chunk()
+        .await
+        .map_err(|e| describe_request_error(e, spec.timeout))?
+    {
+        body_bytes += chunk.len();
+        let room = MAX_HTTP_COMMAND_BODY_BYTES.saturating_sub(bytes.len());
+        bytes.extend_from_slice(&chunk[..chunk.len().min(room)]);
+    }
+    let download_ms = elapsed_ms(downloading);
+
+    let body = match String::from_utf8(bytes) {
+        Ok(text) => text,
+        Err(error) => format!("[{} bytes of binary content]", error.into_bytes().len()),
+    };
+    Ok(HttpResponse {
+        method: spec.method.clone(),
+        url: spec.url.clone(),
+        final_url,
+        remote_addr,
+        version,
+        status: status.as_u16(),
+        reason: status.canonical_reason().map(str::to_string),
+        headers,
+        body,
+        body_truncated: body_bytes > MAX_HTTP_COMMAND_BODY_BYTES,
+        body_bytes,
+        timing: HttpTiming {
+            dns_ms,
+            first_byte_ms,
+            download_ms,
+            total_ms: elapsed_ms(started),
+        },
+    })
+}
+
+// Status line, headers and body as curl -i prints them, with JSON bodies pretty-printed
+pub fn format_http_response(response: &HttpResponse) -> String {
+    let mut text = format!(
+        "{} {}{}\n",
+        response.version,
+        response.status,
+        response
+            .reason
+            .as_ref()
+            .map(|reason| format!(" {}", reason))
+            .unwrap_or_default()
+    );
+    for (name, value) in &response.headers {
+        text.push_str(&format!("{}: {}\n", name, value));
+    }
+    text.push('\n');
+
+    let pretty = serde_json::from_str::<serde_json::Value>(&response.body)
+        .ok()
+        .filter(|value| value.is_object() || value.is_array())
+        .and_then(|value| serde_json::to_string_pretty(&value).ok());
+    text.push_str(pretty.as_deref().unwrap_or(&response.body));
+    if !text.ends_with('\n') {
+        text.push('\n');
+    }
+    if response.body_truncated {
+        text.push_str(&format!(
+            "[body truncated: showing {} of {} bytes]\n",
+            MAX_HTTP_COMMAND_BODY_BYTES, response.body_bytes
+        ));
+    }
+    text
+}
+
+fn format_timing(timing: &HttpTiming) -> String {
+    format!(
+        "dns {} ms, first byte {} ms, download {} ms, total {} ms",
+        timing.dns_ms, timing.first_byte_ms, timing.download_ms, timing.total_ms
+    )
+}
+
+// Runs "/http ...". With a session the response goes out as command_output like any command's
+// output and lands in the scrollback, so format_last_output can query it; the structured
+// response follows on http_response.
+pub async fn run_http_command<R: Runtime>(
+    app_handle: &AppHandle<R>,
+    session_id: Option<&str>,
+    command: &str,
+) -> Result<String, String> {
+    let spec = parse_http_command(command.trim_start_matches("/http"))?;
+    let started = Instant::now();
+    if let Some(session_id) = session_id {
+        app_handle
+            .state::<ScrollbackManager>()
+            .append(session_id, OutputStream::Input, command);
+    }
+
+    let result = send_http_request(&spec).await;
+    let Some(session_id) = session_id else {
+        return result.map(|response| {
+            format!(
+                "{}\n{}",
+                format_http_response(&response),
+                format_timing(&response.timing)
+            )
+        });
+    };
+
+    let scrollback = app_handle.state::<ScrollbackManager>();
+    match result {
+        Ok(response) => {
+            let output = format_http_response(&response);
+            emit_command_output(app_handle, session_id, output.clone());
+            scrollback.append(session_id, OutputStream::Stdout, &output);
+            scrollback.finish_run(session_id, Some(0));
+            emit_session_event(
+                app_handle,
+                session_id,
+                HTTP_RESPONSE_EVENT,
+                response.clone(),
+            );
+
+            let success = response.status < 400;
+            emit_command_end(
+                app_handle,
+                CommandEndEvent {
+                    exit_code: Some(0),
+                    duration_ms: Some(elapsed_ms(started)),
+                    ..CommandEndEvent::new(session_id, success, format!("HTTP {}", response.status))
+                },
+            );
+            Ok(format!("{}\n{}", output, format_timing(&response.timing)))
+        }
+        Err(error) => {
+            emit_command_error(app_handle, session_id, format!("{}\n", error));
+            scrollback.append(session_id, OutputStream::Stderr, &error);
+            scrollback.finish_run(session_id, Some(1));
+            emit_command_end(
+                app_handle,
+                CommandEndEvent {
+                    exit_code: Some(1),
+                    duration_ms: Some(elapsed_ms(started)),
+                    ..CommandEndEvent::new(session_id, false, "Command failed.")
+                },
+            );
+            Err(error)
+        }
+    }
+}
@@ -0,0 +1,1205 @@
+// A jq subset evaluated over serde_json values, backing format_last_output.
+//
+// Supported: `.`, `..`, `.foo`, `."foo"`, `.[0]`, `.[-1]`, `.[]`, `.[2:5]`, `?`, `|`, `,`,
+// literals, `[...]` and `{key: expr}` construction, `+ - * / %`, comparisons, `and`/`or`,
+// `//`, and the builtins in `call_builtin`.
+
+use serde_json::{Map, Number, Value};
+use std::cmp::Ordering;
+
+// Deep enough for any real query; bounds the recursion of the parser on input like "[[[[..."
+// and of the evaluator, which also recurses once per link of chains like `.a.b.c...`.
+const MAX_NESTING_DEPTH: usize = 100;
+// Keeps filter_depth and dropping the parsed filter from recursing too deeply themselves
+const MAX_QUERY_TOKENS: usize = 1000;
+
+#[derive(Debug, Clone, PartialEq)]
+enum Token {
+    Dot,
+    DotDot,
+    Ident(String),
+    Str(String),
+    Number(f64),
+    Op(&'static str),
+    Pipe,
+    Comma,
+    Colon,
+    Question,
+    LParen,
+    RParen,
+    LBracket,
+    RBracket,
+    LBrace,
+    RBrace,
+}
+
+fn tokenize(input: &str) -> Result<Vec<Token>, String> {
+    let mut tokens = Vec::new();
+    let mut chars = input.chars().peekable();
+
+    while let Some(&c) = chars.peek() {
+        match c {
+            c if c.is_whitespace() => {
+                chars.next();
+            }
+            '.' => {
+                chars.next();
+                if chars.peek() == Some(&'.') {
+                    chars.next();
+                    tokens.push(Token::DotDot);
+                } else {
+                    tokens.push(Token::Dot);
+                }
+            }
+            '0'..='9' => {
+                let mut number = String::new();
+                while let Some(&d) = chars.peek() {
+                    if d.is_ascii_digit() || d == '.' || d == 'e' || d == 'E' {
+                        number.push(d);
+                        chars.next();
+                    } else {
+                        break;
+                    }
+                }
+                let value = number
+                    .parse::<f64>()
+                    .map_err(|_| format!("Invalid number: {}", number))?;
+                tokens.push(Token::Number(value));
+            }
+            '"' => {
+                chars.next();
+                let mut text = String::new();
+                loop {
+                    match chars.next() {
+                        Some('"') => break,
+                        Some('\\') => match chars.next() {
+                            Some('n') => text.push('\n'),
+                            Some('t') => text.push('\t'),
+                            Some(escaped) => text.push(escaped),
+                            None => return Err("Unterminated string".to_string()),
+                        },
+                        Some(other) => text.push(other),
+                        None => return Err("Unterminated string".to_string()),
+                    }
+                }
+                tokens.push(Token::Str(text));
+            }
+            c if c.is_alphabetic() || c == '_' || c == '$' => {
+                let mut ident = String::new();
+                while let Some(&a) = chars.peek() {
+                    if a.is_alphanumeric() || a == '_' || a == '$' {
+                        ident.push(a);
+                        chars.next();
+                    } else {
+                        break;
+                    }
+                }
+                tokens.push(Token::Ident(ident));
+            }
+            '|' => {
+                chars.next();
+                tokens.push(Token::Pipe);
+            }
+            ',' => {
+                chars.next();
+                tokens.push(Token::Comma);
+            }
+            ':' => {
+                chars.next();
+                tokens.push(Token::Colon);
+            }
+            '?' => {
+                chars.next();
+                tokens.push(Token::Question);
+            }
+            '(' => {
+                chars.next();
+                tokens.push(Token::LParen);
+            }
+            ')' => {
+                chars.next();
+                tokens.push(Token::RParen);
+            }
+            '[' => {
+                chars.next();
+                tokens.push(Token::LBracket);
+            }
+            ']' => {
+                chars.next();
+                tokens.push(Token::RBracket);
+            }
+            '{' => {
+                chars.next();
+                tokens.push(Token::LBrace);
+            }
+            '}' => {
+                chars.next();
+                tokens.push(Token::RBrace);
+            }
+            '=' | '!' | '<' | '>' | '/' | '+' | '-' | '*' | '%' => {
+                chars.next();
+                let next = chars.peek().copied();
+                let op = match (c, next) {
+                    ('=', Some('=')) => "==",
+                    ('!', Some('=')) => "!=",
+                    ('<', Some('=')) => "<=",
+                    ('>', Some('=')) => ">=",
+                    ('/', Some('/')) => "//",
+                    ('<', _) => "<",
+                    ('>', _) => ">",
+                    ('/', _) => "/",
+                    ('+', _) => "+",
+                    ('-', _) => "-",
+                    ('*', _) => "*",
+                    ('%', _) => "%",
+                    _ => return Err(format!("Unexpected character '{}'", c)),
+                };
+                if op.len() == 2 {
+                    chars.next();
+                }
+                tokens.push(Token::Op(op));
+            }
+            _ => return Err(format!("Unexpected character '{}'", c)),
+        }
+    }
+    Ok(tokens)
+}
+
+#[derive(Debug, Clone)]
+enum Filter {
+    Identity,
+    Recurse,
+    Literal(Value),
+    // target[index]; `.foo` is Index(Identity, "foo")
+    Index(Box<Filter>, Box<Filter>),
+    Slice(Box<Filter>, Option<Box<Filter>>, Option<Box<Filter>>),
+    Iterate(Box<Filter>),
+    // Errors become no output
+    Try(Box<Filter>),
+    Pipe(Box<Filter>, Box<Filter>),
+    Comma(Box<Filter>, Box<Filter>),
+    Array(Option<Box<Filter>>),
+    Object(Vec<(Filter, Filter)>),
+    Binary(&'static str, Box<Filter>, Box<Filter>),
+    And(Box<Filter>, Box<Filter>),
+    Or(Box<Filter>, Box<Filter>),
+    Alternative(Box<Filter>, Box<Filter>),
+    Negate(Box<Filter>),
+    Call(String, Vec<Filter>),
+}
+
+// pipe := comma ('|' comma)*, comma := alt (',' alt)*, alt := or ('//' or)*,
+// or := and ('or' and)*, and := compare ('and' compare)*, compare := sum (cmp sum)?,
+// sum := product (('+'|'-') product)*, product := postfix (('*'|'/'|'%') postfix)*
+struct Parser {
+    tokens: Vec<Token>,
+    pos: usize,
+    depth: usize,
+}
+
+impl Parser {
+    fn peek(&self) -> Option<&Token> {
+        self.tokens.get(self.pos)
+    }
+
+    fn next(&mut self) -> Option<Token> {
+        let token = self.tokens.get(self.pos).cloned();
+        self.pos += 1;
+        token
+    }
+
+    fn eat(&mut self, token: &Token) -> bool {
+        if self.peek() == Some(token) {
+            self.pos += 1;
+            true
+        } else {
+            false
+        }
+    }
+
+    fn expect(&mut self, token: Token) -> Result<(), String> {
+        if self.eat(&token) {
+            Ok(())
+        } else {
+            Err(format!("Expected {:?}, found {:?}", token, self.peek()))
+        }
+    }
+
+    fn is_keyword(&self, word: &str) -> bool {
+        matches!(self.peek(), Some(Token::Ident(ident)) if ident == word)
+    }
+
+    fn pipe(&mut self) -> Result<Filter, String> {
+        let mut left = self.comma()?;
+        while self.eat(&Token::Pipe) {
+            let right = self.comma()?;
+            left = Filter::Pipe(Box::new(left), Box::new(right));
+        }
+        Ok(left)
+    }
+
+    fn comma(&mut self) -> Result<Filter, String> {
+        let mut left = self.alternative()?;
+        while self.eat(&Token::Comma) {
+            let right = self.alternative()?;
+            left = Filter::Comma(Box::new(left), Box::new(right));
+        }
+        Ok(left)
+    }
+
+    fn alternative(&mut self) -> Result<Filter, String> {
+        let mut left = self.or()?;
+        while self.eat(&Token::Op("//")) {
+            let right = self.or()?;
+            left = Filter::Alternative(Box::new(left), Box::new(right));
+        }
+        Ok(left)
+    }
+
+    fn or(&mut self) -> Result<Filter, String> {
+        let mut left = self.and()?;
+        while self.is_keyword("or") {
+            self.pos += 1;
+            let right = self.and()?;
+            left = Filter::Or(Box::new(left), Box::new(right));
+        }
+        Ok(left)
+    }
+
+    fn and(&mut self) -> Result<Filter, String> {
+        let mut left = self.compare()?;
+        while self.is_keyword("and") {
+            self.pos += 1;
+            let right = self.compare()?;
+            left = Filter::And(Box::new(left), Box::new(right));
+        }
+        Ok(left)
+    }
+
+    fn compare(&mut self) -> Result<Filter, String> {
+        let left = self.sum()?;
+        if let Some(Token::Op(op @ ("==" | "!=" | "<" | "<=" | ">" | ">="))) = self.peek() {
+            let op = *op;
+            self.pos += 1;
+            let right = self.sum()?;
+            return Ok(Filter::Binary(op, Box::new(left), Box::new(right)));
+        }
+        Ok(left)
+    }
+
+    fn sum(&mut self) -> Result<Filter, String> {
+        let mut left = self.product()?;
+        while let Some(Token::Op(op @ ("+" | "-"))) = self.peek() {
+            let op = *op;
+            self.pos += 1;
+            let right = self.product()?;
+            left = Filter::Binary(op, Box::new(left), Box::new(right));
+        }
+        Ok(left)
+    }
+
+    fn product(&mut self) -> Result<Filter, String> {
+        let mut left = self.postfix()?;
+        while let Some(Token::Op(op @ ("*" | "/" | "%"))) = self.peek() {
+            let op = *op;
+            self.pos += 1;
+            let right = self.postfix()?;
+            left = Filter::Binary(op, Box::new(left), Box::new(right));
+        }
+        Ok(left)
+    }
+
+    // `[...]` after a term: iterate, index or slice
+    fn bracket_suffix(&mut self, target: Filter) -> Result<Filter, String> {
+        if self.eat(&Token::RBracket) {
+            return Ok(Filter::Iterate(Box::new(target)));
+        }
+        if self.eat(&Token::Colon) {
+            let end = self.pipe()?;
+            self.expect(Token::RBracket)?;
+            return Ok(Filter::Slice(Box::new(target), None, Some(Box::new(end))));
+        }
+        let index = self.pipe()?;
+        if self.eat(&Token::Colon) {
+            let end = if self.peek() == Some(&Token::RBracket) {
+                None
+            } else {
+                Some(Box::new(self.pipe()?))
+            };
+            self.expect(Token::RBracket)?;
+            return Ok(Filter::Slice(Box::new(target), Some(Box::new(index)), end));
+        }
+        self.expect(Token::RBracket)?;
+        Ok(Filter::Index(Box::new(target), Box::new(index)))
+    }
+
+    // The key after a dot: `.foo`, `."foo bar"` or `.[...]`
+    fn dot_suffix(&mut self, target: Filter) -> Result<Option<Filter>, String> {
+        match self.peek().cloned() {
+            Some(Token::Ident(name)) => {
+                self.pos += 1;
+                Ok(Some(Filter::Index(
+                    Box::new(target),
+                    Box::new(Filter::Literal(Value::String(name))),
+                )))
+            }
+            Some(Token::Str(name)) => {
+                self.pos += 1;
+                Ok(Some(Filter::Index(
+                    Box::new(target),
+                    Box::new(Filter::Literal(Value::String(name))),
+                )))
+            }
+            Some(Token::LBracket) => {
+                self.pos += 1;
+                self.bracket_suffix(target).map(Some)
+            }
+            _ => Ok(None),
+        }
+    }
+
+    fn postfix(&mut self) -> Result<Filter, String> {
+        let mut filter = self.primary()?;
+        loop {
+            if self.eat(&Token::Question) {
+                filter = Filter::Try(Box::new(filter));
+            } else if self.peek() == Some(&Token::Dot) {
+                self.pos += 1;
+                filter = self
+                    .dot_suffix(filter)?
+                    .ok_or_else(|| "Expected a key after '.'".to_string())?;
+            } else if self.eat(&Token::LBracket) {
+                filter = self.bracket_suffix(filter)?;
+            } else {
+                return Ok(filter);
+            }
+        }
+    }
+
+    fn object(&mut self) -> Result<Filter, String> {
+        let mut entries = Vec::new();
+        if self.eat(&Token::RBrace) {
+            return Ok(Filter::Object(entries));
+        }
+        loop {
+            let key = match self.next() {
+                Some(Token::Ident(name)) | Some(Token::Str(name)) => {
+                    Filter::Literal(Value::String(name))
+                }
+                Some(Token::LParen) => {
+                    let key = self.pipe()?;
+                    self.expect(Token::RParen)?;
+                    key
+                }
+                other => return Err(format!("Unexpected {:?} in object", other)),
+            };
+            // `{name}` is short for `{name: .name}`
+            let value = if self.eat(&Token::Colon) {
+                self.alternative()?
+            } else {
+                Filter::Index(Box::new(Filter::Identity), Box::new(key.clone()))
+            };
+            entries.push((key, value));
+            if self.eat(&Token::RBrace) {
+                return Ok(Filter::Object(entries));
+            }
+            self.expect(Token::Comma)?;
+        }
+    }
+
+    fn primary(&mut self) -> Result<Filter, String> {
+        if self.depth >= MAX_NESTING_DEPTH {
+            return Err("Query is nested too deeply".to_string());
+        }
+        self.depth += 1;
+        let filter = self.term();
+        self.depth -= 1;
+        filter
+    }
+
+    fn term(&mut self) -> Result<Filter, String> {
+        match self.next() {
+            Some(Token::Dot) => Ok(self
+                .dot_suffix(Filter::Identity)?
+                .unwrap_or(Filter::Identity)),
+            Some(Token::DotDot) => Ok(Filter::Recurse),
+            Some(Token::Number(value)) => Ok(Filter::Literal(number_value(value))),
+            Some(Token::Str(text)) => Ok(Filter::Literal(Value::String(text))),
+            Some(Token::Op("-")) => Ok(Filter::Negate(Box::new(self.postfix()?))),
+            Some(Token::LParen) => {
+                let inner = self.pipe()?;
+                self.expect(Token::RParen)?;
+                Ok(inner)
+            }
+            Some(Token::LBracket) => {
+                if self.eat(&Token::RBracket) {
+                    return Ok(Filter::Array(None));
+                }
+                let inner = self.pipe()?;
+                self.expect(Token::RBracket)?;
+                Ok(Filter::Array(Some(Box::new(inner))))
+            }
+            Some(Token::LBrace) => self.object(),
+            Some(Token::Ident(name)) => match name.as_str() {
+                "true" => Ok(Filter::Literal(Value::Bool(true))),
+                "false" => Ok(Filter::Literal(Value::Bool(false))),
+                "null" => Ok(Filter::Literal(Value::Null)),
+                _ => {
+                    // Builtins here take at most one argument
+                    let mut args = Vec::new();
+                    if self.eat(&Token::LParen) {
+                        args.push(self.pipe()?);
+                        self.expect(Token::RParen)?;
+                    }
+                    Ok(Filter::Call(name, args))
+                }
+            },
+            other => Err(format!("Unexpected {:?}", other)),
+        }
+    }
+}
+
+fn filter_depth(filter: &Filter) -> usize {
+    let children: Vec<&Filter> = match filter {
+        Filter::Identity | Filter::Recurse | Filter::Literal(_) => Vec::new(),
+        Filter::Iterate(inner) | Filter::Try(inner) | Filter::Negate(inner) => vec![inner],
+        Filter::Array(inner) => inner.iter().map(|inner| inner.as_ref()).collect(),
+        Filter::Index(left, right)
+        | Filter::Pipe(left, right)
+        | Filter::Comma(left, right)
+        | Filter::Binary(_, left, right)
+        | Filter::And(left, right)
+        | Filter::Or(left, right)
+        | Filter::Alternative(left, right) => vec![left, right],
+        Filter::Slice(target, start, end) => std::iter::once(target.as_ref())
+            .chain(start.as_deref())
+            .chain(end.as_deref())
+            .collect(),
+        Filter::Object(entries) => entries
+            .iter()
+            .flat_map(|(key, value)| [key, value])
+            .collect(),
+        Filter::Call(_, args) => args.iter().collect(),
+    };
+    1 + children.into_iter().map(filter_depth).max().unwrap_or(0)
+}
+
+fn number_value(value: f64) -> Value {
+    if value.fract() == 0.0 && value.abs() < 9e15 {
+        Value::from(value as i64)
+    } else {
+        Number::from_f64(value)
+            .map(Value::Number)
+            .unwrap_or(Value::Null)
+    }
+}
+
+fn type_name(value: &Value) -> &'static str {
+    match value {
+        Value::Null => "null",
+        Value::Bool(_) => "boolean",
+        Value::Number(_) => "number",
+        Value::String(_) => "string",
+        Value::Array(_) => "array",
+        Value::Object(_) => "object",
+    }
+}
+
+fn is_truthy(value: &Value) -> bool {
+    !matches!(value, Value::Null | Value::Bool(false))
+}
+
+// jq's total order: null < false < true < numbers < strings < arrays < objects
+fn compare_values(a: &Value, b: &Value) -> Ordering {
+    fn rank(value: &Value) -> u8 {
+        match value {
+            Value::Null => 0,
+            Value::Bool(false) => 1,
+            Value::Bool(true) => 2,
+            Value::Number(_) => 3,
+            Value::String(_) => 4,
+            Value::Array(_) => 5,
+            Value::Object(_) => 6,
+        }
+    }
+    match (a, b) {
+        (Value::Number(x), Value::Number(y)) => x
+            .as_f64()
+            .partial_cmp(&y.as_f64())
+            .unwrap_or(Ordering::Equal),
+        (Value::String(x), Value::String(y)) => x.cmp(y),
+        (Value::Array(x), Value::Array(y)) => x
+            .iter()
+            .zip(y)
+            .map(|(x, y)| compare_values(x, y))
+            .find(|ordering| *ordering != Ordering::Equal)
+            .unwrap_or_else(|| x.len().cmp(&y.len())),
+        (Value::Object(x), Value::Object(y)) => {
+            let mut x_keys: Vec<&String> = x.keys().collect();
+            let mut y_keys: Vec<&String> = y.keys().collect();
+            x_keys.sort();
+            y_keys.sort();
+            x_keys.cmp(&y_keys).then_with(|| {
+                x_keys
+                    .iter()
+                    .map(|key| compare_values(&x[*key], &y[*key]))
+                    .find(|ordering| *ordering != Ordering::Equal)
+                    .unwrap_or(Ordering::Equal)
+            })
+        }
+        _ => rank(a).cmp(&rank(b)),
+    }
+}
+
+fn as_number(value: &Value) -> Option<f64> {
+    value.as_f64()
+}
+
+fn arithmetic(op: &str, left: &Value, right: &Value) -> Result<Value, String> {
+    let cannot = || {
+        format!(
+            "Cannot apply {} to {} and {}",
+            op,
+            type_name(left),
+            type_name(right)
+        )
+    };
+    match (op, left, right) {
+        ("+", Value::Null, other) | ("+", other, Value::Null) => Ok(other.clone()),
+        ("+", Value::String(x), Value::String(y)) => Ok(Value::String(format!("{}{}", x, y))),
+        ("+", Value::Array(x), Value::Array(y)) => {
+            Ok(Value::Array(x.iter().chain(y).cloned().collect()))
+        }
+        ("+", Value::Object(x), Value::Object(y)) => {
+            let mut merged = x.clone();
+            merged.extend(y.iter().map(|(key, value)| (key.clone(), value.clone())));
+            Ok(Value::Object(merged))
+        }
+        ("-", Value::Array(x), Value::Array(y)) => Ok(Value::Array(
+            x.iter().filter(|item| !y.contains(item)).cloned().collect(),
+        )),
+        ("/", Value::String(x), Value::String(y)) => Ok(Value::Array(
+            x.split(y.as_str())
+                .map(|part| Value::String(part.to_string()))
+                .collect(),
+        )),
+        _ => {
+            let (Some(x), Some(y)) = (as_number(left), as_number(right)) else {
+                return Err(cannot());
+            };
+            let result = match op {
+                "+" => x + y,
+                "-" => x - y,
+                "*" => x * y,
+                "/" if y == 0.0 => return Err("Division by zero".to_string()),
+                "/" => x / y,
+                "%" => match (x as i64).checked_rem(y as i64) {
+                    Some(remainder) => remainder as f64,
+                    None if y as i64 == 0 => return Err("Modulo by zero".to_string()),
+                    // i64::MIN % -1, which jq answers with 0
+                    None => 0.0,
+                },
+                _ => return Err(cannot()),
+            };
+            Ok(number_value(result))
+        }
+    }
+}
+
+fn index_value(target: &Value, index: &Value) -> Result<Value, String> {
+    match (target, index) {
+        (Value::Null, _) => Ok(Value::Null),
+        (Value::Object(map), Value::String(key)) => {
+            Ok(map.get(key).cloned().unwrap_or(Value::Null))
+        }
+        (Value::Array(items), Value::Number(number)) => {
+            let position = number.as_f64().unwrap_or(0.0) as i64;
+            let position = if position < 0 {
+                items.len() as i64 + position
+            } else {
+                position
+            };
+            Ok(usize::try_from(position)
+                .ok()
+                .and_then(|position| items.get(position))
+                .cloned()
+                .unwrap_or(Value::Null))
+        }
+        _ => Err(format!(
+            "Cannot index {} with {}",
+            type_name(target),
+            match index {
+                Value::String(key) => format!("\"{}\"", key),
+                other => type_name(other).to_string(),
+            }
+        )),
+    }
+}
+
+fn slice_bounds(length: usize, start: Option<&Value>, end: Option<&Value>) -> (usize, usize) {
+    let resolve = |bound: Option<&Value>, default: usize| match bound.and_then(Value::as_f64) {
+        Some(value) if value < 0.0 => (length as f64 + value).max(0.0) as usize,
+        Some(value) => (value as usize).min(length),
+        None => default,
+    };
+    let start = resolve(start, 0);
+    let end = resolve(end, length).max(start);
+    (start, end)
+}
+
+fn recurse(value: &Value, out: &mut Vec<Value>) {
+    out.push(value.clone());
+    match value {
+        Value::Array(items) => items.iter().for_each(|item| recurse(item, out)),
+        Value::Object(map) => map.values().for_each(|item| recurse(item, out)),
+        _ => {}
+    }
+}
+
+fn single(filter: &Filter, input: &Value, name: &str) -> Result<Value, String> {
+    eval(filter, input)?
+        .into_iter()
+        .next()
+        .ok_or_else(|| format!("{} argument produced no value", name))
+}
+
+fn call_builtin(name: &str, args: &[Filter], input: &Value) -> Result<Vec<Value>, String> {
+    let one = |value: Value| Ok(vec![value]);
+    match (name, args) {
+        ("empty", []) => Ok(Vec::new()),
+        ("not", []) => one(Value::Bool(!is_truthy(input))),
+        ("type", []) => one(Value::String(type_name(input).to_string())),
+        ("length", []) => one(match input {
+            Value::Null => Value::from(0),
+            Value::Bool(_) => return Err("boolean has no length".to_string()),
+            Value::Number(number) => number_value(number.as_f64().unwrap_or(0.0).abs()),
+            Value::String(text) => Value::from(text.chars().count()),
+            Value::Array(items) => Value::from(items.len()),
+            Value::Object(map) => Value::from(map.len()),
+        }),
+        ("keys", []) => match input {
+            Value::Object(map) => {
+                let mut keys: Vec<&String> = map.keys().collect();
+                keys.sort();
+                one(Value::Array(
+                    keys.into_iter()
+                        .map(|key| Value::String(key.clone()))
+                        .collect(),
+                ))
+            }
+            Value::Array(items) => one(Value::Array((0..items.len()).map(Value::from).collect())),
+            other => Err(format!("{} has no keys", type_name(other))),
+        },
+        ("values", []) => Ok(if is_truthy(input) {
+            vec![input.clone()]
+        } else {
+            Vec::new()
+        }),
+        ("has", [key]) => {
+            let key = single(key, input, "has")?;
+            one(Value::Bool(match (input, &key) {
+                (Value::Object(map), Value::String(key)) => map.contains_key(key),
+                (Value::Array(items), Value::Number(position)) => position
+                    .as_f64()
+                    .is_some_and(|position| position >= 0.0 && (position as usize) < items.len()),
+                _ => {
+                    return Err(format!(
+                        "Cannot check whether {} has a key",
+                        type_name(input)
+                    ))
+                }
+            }))
+        }
+        ("select", [condition]) => Ok(eval(condition, input)?
+            .iter()
+            .filter(|result| is_truthy(result))
+            .map(|_| input.clone())
+            .collect()),
+        ("map", [filter]) => {
+            let items = iterate(input)?;
+            let mut mapped = Vec::new();
+            for item in items {
+                mapped.extend(eval(filter, &item)?);
+            }
+            one(Value::Array(mapped))
+        }
+        ("to_entries", []) => match input {
+            Value::Object(map) => one(Value::Array(
+                map.iter()
+                    .map(|(key, value)| serde_json::json!({ "key": key, "value": value }))
+                    .collect(),
+            )),
+            other => Err(format!("{} has no entries", type_name(other))),
+        },
+        ("from_entries", []) => {
+            let mut map = Map::new();
+            for entry in iterate(input)? {
+                let key = ["key", "k", "name", "Name", "Key"]
+                    .iter()
+                    .find_map(|field| entry.get(field).filter(|key| !key.is_null()))
+                    .ok_or("from_entries needs key/value objects")?;
+                let key = match key {
+                    Value::String(key) => key.clone(),
+                    other => other.to_string(),
+                };
+                let value = ["value", "v", "Value"]
+                    .iter()
+                    .find_map(|field| entry.get(field))
+                    .cloned()
+                    .unwrap_or(Value::Null);
+                map.insert(key, value);
+            }
+            one(Value::Object(map))
+        }
+        ("add", []) => {
+            let mut total = Value::Null;
+            for item in iterate(input)? {
+                total = arithmetic("+", &total, &item)?;
+            }
+            one(total)
+        }
+        ("sort", []) => {
+            let mut items = iterate(input)?;
+            items.sort_by(compare_values);
+            one(Value::Array(items))
+        }
+        ("sort_by", [key]) => {
+            let mut keyed = iterate(input)?
+                .into_iter()
+                .map(|item| Ok((eval(key, &item)?, item)))
+                .collect::<Result<Vec<_>, String>>()?;
+            keyed.sort_by(|(a, _), (b, _)| {
+                compare_values(&Value::Array(a.clone()), &Value::Array(b.clone()))
+            });
+            one(Value::Array(
+                keyed.into_iter().map(|(_, item)| item).collect(),
+            ))
+        }
+        ("unique", []) => {
+            let mut items = iterate(input)?;
+            items.sort_by(compare_values);
+            items.dedup();
+            one(Value::Array(items))
+        }
+        ("reverse", []) => match input {
+            Value::String(text) => one(Value::String(text.chars().rev().collect())),
+            Value::Null => one(Value::Array(Vec::new())),
+            _ => {
+                let mut items = iterate(input)?;
+                items.reverse();
+                one(Value::Array(items))
+            }
+        },
+        ("min" | "max", []) => {
+            let items = iterate(input)?;
+            let found = if name == "min" {
+                items.into_iter().min_by(compare_values)
+            } else {
+                items.into_iter().max_by(compare_values)
+            };
+            one(found.unwrap_or(Value::Null))
+        }
+        ("first", []) => index_value(input, &Value::from(0)).map(|value| vec![value]),
+        ("last", []) => index_value(input, &Value::from(-1)).map(|value| vec![value]),
+        ("first", [filter]) => Ok(eval(filter, input)?.into_iter().take(1).collect()),
+        ("tostring", []) => one(match input {
+            Value::String(_) => input.clone(),
+            other => Value::String(other.to_string()),
+        }),
+        ("tonumber", []) => match input {
+            Value::Number(_) => one(input.clone()),
+            Value::String(text) => text
+                .trim()
+                .parse::<f64>()
+                .map(|value| vec![number_value(value)])
+                .map_err(|_| format!("Cannot parse '{}' as a number", text)),
+            other => Err(format!("Cannot convert {} to a number", type_name(other))),
+        },
+        ("ascii_downcase" | "ascii_upcase", []) => match input {
+            Value::String(text) => one(Value::String(if name == "ascii_downcase" {
+                text.to_ascii_lowercase()
+            } else {
+                text.to_ascii_uppercase()
+            })),
+            other => Err(format!("{} is not a string", type_name(other))),
+        },
+        ("join", [separator]) => {
+            let separator = match single(separator, input, "join")? {
+                Value::String(separator) => separator,
+                other => return Err(format!("Cannot join with {}", type_name(&other))),
+            };
+            let parts = iterate(input)?
+                .into_iter()
+                .map(|item| match item {
+                    Value::Null => Ok(String::new()),
+                    Value::String(text) => Ok(text),
+                    Value::Number(_) | Value::Bool(_) => Ok(item.to_string()),
+                    other => Err(format!("Cannot join {}", type_name(&other))),
+                })
+                .collect::<Result<Vec<String>, String>>()?;
+            one(Value::String(parts.join(&separator)))
+        }
+        ("split", [separator]) => match (input, single(separator, input, "split")?) {
+            (Value::String(text), Value::String(separator)) => one(Value::Array(
+                text.split(separator.as_str())
+                    .map(|part| Value::String(part.to_string()))
+                    .collect(),
+            )),
+            _ => Err("split needs a string and a string separator".to_string()),
+        },
+        ("contains", [needle]) => {
+            let needle = single(needle, input, "contains")?;
+            one(Value::Bool(contains(input, &needle)))
+        }
+        ("test", [pattern]) => match (input, single(pattern, input, "test")?) {
+            (Value::String(text), Value::String(pattern)) => {
+                let regex = regex::Regex::new(&pattern)
+                    .map_err(|e| format!("Invalid regex '{}': {}", pattern, e))?;
+                one(Value::Bool(regex.is_match(text)))
+            }
+            _ => Err("test needs a string and a string pattern".to_string()),
+        },
+        ("startswith" | "endswith", [affix]) => match (input, single(affix, input, name)?) {
+            (Value::String(text), Value::String(affix)) => {
+                one(Value::Bool(if name == "startswith" {
+                    text.starts_with(&affix)
+                } else {
+                    text.ends_with(&affix)
+                }))
+            }
+            _ => Err(format!("{} needs strings", name)),
+        },
+        _ => Err(format!("{}/{} is not defined", name, args.len())),
+    }
+}
+
+fn contains(haystack: &Value, needle: &Value) -> bool {
+    match (haystack, needle) {
+        (Value::String(text), Value::String(part)) => text.contains(part.as_str()),
+        (Value::Array(items), Value::Array(wanted)) => wanted
+            .iter()
+            .all(|wanted| items.iter().any(|item| contains(item, wanted))),
+        (Value::Object(map), Value::Object(wanted)) => wanted
+            .iter()
+            .all(|(key, wanted)| map.get(key).is_some_and(|value| contains(value, wanted))),
+        _ => haystack == needle,
+    }
+}
+
+fn iterate(value: &Value) -> Result<Vec<Value>, String> {
+    match value {
+        Value::Array(items) => Ok(items.clone()),
+        Value::Object(map) => Ok(map.values().cloned().collect()),
+        other => Err(format!("Cannot iterate over {}", type_name(other))),
+    }
+}
+
+// Every combination of the outputs of two filters, right-hand side outermost like jq
+fn cartesian(
+    left: &Filter,
+    right: &Filter,
+    input: &Value,
+    combine: impl Fn(&Value, &Value) -> Result<Value, String>,
+) -> Result<Vec<Value>, String> {
+    let lefts = eval(left, input)?;
+    let mut out = Vec::new();
+    for right in eval(right, input)? {
+        for left in &lefts {
+            out.push(combine(left, &right)?);
+        }
+    }
+    Ok(out)
+}
+
+fn eval(filter: &Filter, input: &Value) -> Result<Vec<Value>, String> {
+    match filter {
+        Filter::Identity => Ok(vec![input.clone()]),
+        Filter::Recurse => {
+            let mut out = Vec::new();
+            recurse(input, &mut out);
+            Ok(out)
+        }
+        Filter::Literal(value) => Ok(vec![value.clone()]),
+        Filter::Index(target, index) => {
+            let mut out = Vec::new();
+            for target in eval(target, input)? {
+                for index in eval(index, input)? {
+                    out.push(index_value(&target, &index)?);
+                }
+            }
+            Ok(out)
+        }
+        Filter::Slice(target, start, end) => {
+            let start = match start {
+                Some(start) => Some(single(start, input, "slice")?),
+                None => None,
+            };
+            let end = match end {
+                Some(end) => Some(single(end, input, "slice")?),
+                None => None,
+            };
+            eval(target, input)?
+                .into_iter()
+                .map(|target| match &target {
+                    Value::Null => Ok(Value::Null),
+                    Value::Array(items) => {
+                        let (from, to) = slice_bounds(items.len(), start.as_ref(), end.as_ref());
+                        Ok(Value::Array(items[from..to].to_vec()))
+                    }
+                    Value::String(text) => {
+                        let chars: Vec<char> = text.chars().collect();
+                        let (from, to) = slice_bounds(chars.len(), start.as_ref(), end.as_ref());
+                        Ok(Value::String(chars[from..to].iter().collect()))
+                    }
+                    other => Err(format!("Cannot slice {}", type_name(other))),
+                })
+                .collect()
+        }
+        Filter::Iterate(target) => {
+            let mut out = Vec::new();
+            for target in eval(target, input)? {
+                out.extend(iterate(&target)?);
+            }
+            Ok(out)
+        }
+        Filter::Try(inner) => Ok(eval(inner, input).unwrap_or_default()),
+        Filter::Pipe(left, right) => {
+            let mut out = Vec::new();
+            for value in eval(left, input)? {
+                out.extend(eval(right, &value)?);
+            }
+            Ok(out)
+        }
+        Filter::Comma(left, right) => {
+            let mut out = eval(left, input)?;
+            out.extend(eval(right, input)?);
+            Ok(out)
+        }
+        Filter::Array(inner) => Ok(vec![Value::Array(match inner {
+            Some(inner) => eval(inner, input)?,
+            None => Vec::new(),
+        })]),
+        Filter::Object(entries) => {
+            let mut objects = vec![Map::new()];
+            for (key, value) in entries {
+                let keys = eval(key, input)?;
+                let values = eval(value, input)?;
+                let mut next = Vec::new();
+                for object in &objects {
+                    for key in &keys {
+                        let Value::String(key) = key else {
+                            return Err(format!(
+                                "Object keys must be strings, not {}",
+                                type_name(key)
+                            ));
+                        };
+                        for value in &values {
+                            let mut object = object.clone();
+                            object.insert(key.clone(), value.clone());
+                            next.push(object);
+                        }
+                    }
+                }
+                objects = next;
+            }
+            Ok(objects.into_iter().map(Value::Object).collect())
+        }
+        Filter::Binary(op, left, right) => cartesian(left, right, input, |left, right| {
+            let ordering = compare_values(left, right);
+            match *op {
+                "==" => Ok(Value::Bool(ordering == Ordering::Equal)),
+                "!=" => Ok(Value::Bool(ordering != Ordering::Equal)),
+                "<" => Ok(Value::Bool(ordering == Ordering::Less)),
+                "<=" => Ok(Value::Bool(ordering != Ordering::Greater)),
+                ">" => Ok(Value::Bool(ordering == Ordering::Greater)),
+                ">=" => Ok(Value::Bool(ordering != Ordering::Less)),
+                _ => arithmetic(op, left, right),
+            }
+        }),
+        Filter::And(left, right) => {
+            let mut out = Vec::new();
+            for left in eval(left, input)? {
+                if !is_truthy(&left) {
+                    out.push(Value::Bool(false));
+                    continue;
+                }
+                out.extend(
+                    eval(right, input)?
+                        .iter()
+                        .map(|right| Value::Bool(is_truthy(right))),
+                );
+            }
+            Ok(out)
+        }
+        Filter::Or(left, right) => {
+            let mut out = Vec::new();
+            for left in eval(left, input)? {
+                if is_truthy(&left) {
+                    out.push(Value::Bool(true));
+                    continue;
+                }
+                out.extend(
+                    eval(right, input)?
+                        .iter()
+                        .map(|right| Value::Bool(is_truthy(right))),
+                );
+            }
+            Ok(out)
+        }
+        Filter::Alternative(left, right) => {
+            let truthy: Vec<Value> = eval(left, input)
+                .unwrap_or_default()
+                .into_iter()
+                .filter(is_truthy)
+                .collect();
+            if truthy.is_empty() {
+                eval(right, input)
+            } else {
+                Ok(truthy)
+            }
+        }
+        Filter::Negate(inner) => eval(inner, input)?
+            .iter()
+            .map(|value| arithmetic("-", &Value::from(0), value))
+            .collect(),
+        Filter::Call(name, args) => call_builtin(name, args, input),
+    }
+}
+
+pub struct JsonQuery {
+    filter: Filter,
+}
+
+impl JsonQuery {
+    pub fn parse(query: &str) -> Result<Self, String> {
+        let tokens = tokenize(query)?;
+        if tokens.is_empty() {
+            return Ok(Self {
+                filter: Filter::Identity,
+            });
+        }
+        if tokens.len() > MAX_QUERY_TOKENS {
+            return Err(format!(
+                "Query is too long (more than {} tokens)",
+                MAX_QUERY_TOKENS
+            ));
+        }
+        let mut parser = Parser {
+            tokens,
+            pos: 0,
+            depth: 0,
+        };
+        let filter = parser.pipe()?;
+        if let Some(token) = parser.peek() {
+            return Err(format!("Unexpected {:?} in query", token));
+        }
+        if filter_depth(&filter) > MAX_NESTING_DEPTH {
+            return Err("Query is nested too deeply".to_string());
+        }
+        Ok(Self { filter })
+    }
+
+    // Every value the query produces for `input`, in order
+    pub fn run(&self, input: &Value) -> Result<Vec<Value>, String> {
+        eval(&self.filter, input)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn query(query: &str, input: Value) -> Result<Vec<Value>, String> {
+        JsonQuery::parse(query)?.run(&input)
+    }
+
+    fn one(text: &str, input: Value) -> Value {
+        let mut values = query(text, input).unwrap();
+        assert_eq!(values.len(), 1, "{} produced {:?}", text, values);
+        values.remove(0)
+    }
+
+    #[test]
+    fn paths() {
+        let input = json!({"a": {"b": [10, 20, 30]}, "key with space": 1});
+        assert_eq!(one(".", json!(5)), json!(5));
+        assert_eq!(one(".a.b[0]", input.clone()), json!(10));
+        assert_eq!(one(".a.b[-1]", input.clone()), json!(30));
+        assert_eq!(one(".a.b[1:]", input.clone()), json!([20, 30]));
+        assert_eq!(one(".a.b[:2]", input.clone()), json!([10, 20]));
+        assert_eq!(one(".\"key with space\"", input.clone()), json!(1));
+        assert_eq!(one(".missing", input.clone()), Value::Null);
+        assert_eq!(one(".a.b[7]", input.clone()), Value::Null);
+        assert_eq!(
+            query(".a.b[]", input.clone()).unwrap(),
+            vec![json!(10), json!(20), json!(30)]
+        );
+        assert_eq!(query("..", json!([1])).unwrap(), vec![json!([1]), json!(1)]);
+    }
+
+    #[test]
+    fn pipes_and_construction() {
+        let input = json!([{"name": "a", "size": 3}, {"name": "b", "size": 1}]);
+        assert_eq!(
+            one("[.[] | select(.size > 2) | .name]", input.clone()),
+            json!(["a"])
+        );
+        assert_eq!(
+            one("map({name, big: (.size > 2)})", input.clone()),
+            json!([{"name": "a", "big": true}, {"name": "b", "big": false}])
+        );
+        assert_eq!(
+            one("sort_by(.size) | first | .name", input.clone()),
+            json!("b")
+        );
+        assert_eq!(
+            query(".[0].name, .[1].name", input).unwrap(),
+            vec![json!("a"), json!("b")]
+        );
+        assert_eq!(one(".x // \"default\"", json!({})), json!("default"));
+        assert_eq!(one("[.[]?]", json!(3)), json!([]));
+    }
+
+    #[test]
+    fn arithmetic_and_comparisons() {
+        assert_eq!(one("1 + 2 * 3", Value::Null), json!(7));
+        assert_eq!(one("(1 + 2) * 3", Value::Null), json!(9));
+        assert_eq!(one("7 % 3", Value::Null), json!(1));
+        assert_eq!(one("-7 % 3", Value::Null), json!(-1));
+        assert_eq!(one("1 / 4", Value::Null), json!(0.25));
+        assert_eq!(one("\"a\" + \"b\"", Value::Null), json!("ab"));
+        assert_eq!(one("[1, 2, 3] - [2]", Value::Null), json!([1, 3]));
+        assert_eq!(one("null + 1", Value::Null), json!(1));
+        assert_eq!(one("1 < 2 and \"a\" == \"a\"", Value::Null), json!(true));
+        assert_eq!(one("null < false", Value::Null), json!(true));
+        // The i64 cast saturates; the remainder must not overflow
+        assert_eq!(one("-1e19 % -1", Value::Null), json!(0));
+    }
+
+    #[test]
+    fn errors() {
+        assert!(query("1 / 0", Value::Null).is_err());
+        assert!(query("1 % 0", Value::Null).is_err());
+        assert!(query(".a", json!([1])).is_err());
+        assert!(query("{} + 1", Value::Null).is_err());
+        assert!(JsonQuery::parse(".a[").is_err());
+        assert!(JsonQuery::parse("\"open").is_err());
+        assert!(JsonQuery::parse(".a )").is_err());
+        assert!(JsonQuery::parse("#").is_err());
+    }
+
+    #[test]
+    fn deep_nesting_is_rejected() {
+        let nested = "[".repeat(100_000) + &"]".repeat(100_000);
+        assert!(JsonQuery::parse(&nested).is_err());
+        let chained = ".a".repeat(100_000);
+        assert!(JsonQuery::parse(&chained).is_err());
+
+        let shallow = "[".repeat(50) + "1" + &"]".repeat(50);
+        assert!(JsonQuery::parse(&shallow).is_ok());
+        assert!(JsonQuery::parse(&".a".repeat(200)).is_err());
+        assert_eq!(one(&".a".repeat(90), Value::Null), Value::Null);
+        assert_eq!(one(&("1+".repeat(90) + "1"), Value::Null), json!(91));
+    }
+}
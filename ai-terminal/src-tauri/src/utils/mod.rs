@@ -1,3 +1,18 @@
+pub mod ansi;
+pub mod calculator;
+pub mod cell_width;
 pub mod command;
+pub mod config_utils;
+pub mod crypto;
+pub mod env_scrubbing;
 pub mod file_system_utils;
+pub mod http_request;
+pub mod json_query;
 pub mod operating_system_utils;
+pub mod os_auth;
+pub mod path_executables;
+pub mod power;
+pub mod process_output;
+pub mod screen_model;
+pub mod string_utils;
+pub mod token_estimate;
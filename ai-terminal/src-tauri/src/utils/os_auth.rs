@@ -0,0 +1,70 @@
+use std::process::Command;
+
+// Shows the operating system's own authentication prompt (Touch ID / password on macOS,
+// polkit on Linux, Windows Hello on Windows). Ok(false) means the user cancelled or failed;
+// Err means no prompt is available on this system.
+pub fn authenticate_user(reason: &str) -> Result<bool, String> {
+    let mut command = platform_command(reason)?;
+    let status = command
+        .status()
+        .map_err(|e| format!("Failed to show the authentication prompt: {}", e))?;
+    Ok(status.success())
+}
+
+// LocalAuthentication's device-owner policy: Touch ID, or the logged-in user's own password,
+// so standard (non-admin) accounts can authenticate too. The reply arrives on another queue,
+// so the script spins the run loop until it has; throwing makes osascript exit non-zero.
+#[cfg(target_os = "macos")]
+fn platform_command(reason: &str) -> Result<Command, String> {
+    let reason = serde_json::to_string(reason).map_err(|e| e.to_string())?;
+    let script = format!(
+        r#"ObjC.import('Foundation');
+ObjC.import('LocalAuthentication');
+// LAPolicyDeviceOwnerAuthentication
+var policy = 2;
+var context = $.LAContext.alloc.init;
+var finished = false;
+var authenticated = false;
+context.evaluatePolicyLocalizedReasonReply(policy, {}, function (success, error) {{
+  authenticated = success;
+  finished = true;
+}});
+while (!finished) {{
+  $.NSRunLoop.currentRunLoop.runUntilDate($.NSDate.dateWithTimeIntervalSinceNow(0.1));
+}}
+if (!authenticated) {{
+  throw new Error('Authentication failed');
+}}"#,
+        reason
+    );
+    let mut command = Command::new("osascript");
+    command.args(["-l", "JavaScript", "-e", &script]);
+    Ok(command)
+}
+
+#[cfg(target_os = "windows")]
+fn platform_command(reason: &str) -> Result<Command, String> {
+    let script = format!(
+        r#"Add-Type -AssemblyName System.Runtime.WindowsRuntime
+$asTask = [System.WindowsRuntimeSystemExtensions].GetMethods() | Where-Object {{ $_.Name -eq 'AsTask' -and $_.GetParameters().Count -eq 1 -and $_.GetParameters()[0].ParameterType.Name -eq 'IAsyncOperation`1' }} | Select-Object -First 1
+[Windows.Security.Credentials.UI.UserConsentVerifier,Windows.Security.Credentials.UI,ContentType=WindowsRuntime] | Out-Null
+$operation = [Windows.Security.Credentials.UI.UserConsentVerifier]::RequestVerificationAsync('{}')
+$task = $asTask.MakeGenericMethod([Windows.Security.Credentials.UI.UserConsentVerificationResult]).Invoke($null, @($operation))
+$task.Wait()
+if ($task.Result -eq 'Verified') {{ exit 0 }} else {{ exit 1 }}"#,
+        reason.replace('\'', "''")
+    );
+    let mut command = Command::new("powershell");
+    command.args(["-NoProfile", "-NonInteractive", "-Command", &script]);
+    Ok(command)
+}
+
+#[cfg(not(any(target_os = "macos", target_os = "windows")))]
+fn platform_command(_reason: &str) -> Result<Command, String> {
+    if crate::utils::path_executables::find_executable("pkexec").is_none() {
+        return Err("No authentication prompt available (pkexec not found)".to_string());
+    }
+    let mut command = Command::new("pkexec");
+    command.arg("true");
+    Ok(command)
+}
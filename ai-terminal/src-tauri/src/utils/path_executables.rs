@@ -0,0 +1,86 @@
+use crate::utils::file_system_utils::get_shell_path;
+use std::collections::BTreeSet;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::{Mutex, OnceLock};
+
+// PATH value the scan was made for, plus the executable names found
+type ExecutableCache = Option<(String, Vec<String>)>;
+
+// Executables found on PATH, cached per PATH value since scanning can touch thousands of files
+static EXECUTABLE_CACHE: OnceLock<Mutex<ExecutableCache>> = OnceLock::new();
+
+fn current_path() -> String {
+    std::env::var("PATH")
+        .ok()
+        .or_else(get_shell_path)
+        .unwrap_or_default()
+}
+
+fn is_executable(path: &Path) -> bool {
+    let metadata = match fs::metadata(path) {
+        Ok(metadata) => metadata,
+        Err(_) => return false,
+    };
+    if !metadata.is_file() {
+        return false;
+    }
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        metadata.permissions().mode() & 0o111 != 0
+    }
+    #[cfg(not(unix))]
+    {
+        true
+    }
+}
+
+fn scan_path(path_value: &str) -> Vec<String> {
+    let mut names = BTreeSet::new();
+    for dir in std::env::split_paths(path_value) {
+        let entries = match fs::read_dir(&dir) {
+            Ok(entries) => entries,
+            Err(_) => continue,
+        };
+        for entry in entries.flatten() {
+            if is_executable(&entry.path()) {
+                names.insert(entry.file_name().to_string_lossy().to_string());
+            }
+        }
+    }
+    names.into_iter().collect()
+}
+
+pub fn list_path_executables() -> Vec<String> {
+    let path_value = current_path();
+    let cache = EXECUTABLE_CACHE.get_or_init(|| Mutex::new(None));
+    let mut cache = match cache.lock() {
+        Ok(cache) => cache,
+        Err(_) => return scan_path(&path_value),
+    };
+    match cache.as_ref() {
+        Some((cached_path, executables)) if *cached_path == path_value => executables.clone(),
+        _ => {
+            let executables = scan_path(&path_value);
+            *cache = Some((path_value, executables.clone()));
+            executables
+        }
+    }
+}
+
+// Rescans PATH even if it is unchanged, picking up tools installed since the last scan
+pub fn refresh_path_executables() {
+    let path_value = current_path();
+    let executables = scan_path(&path_value);
+    if let Ok(mut cache) = EXECUTABLE_CACHE.get_or_init(|| Mutex::new(None)).lock() {
+        *cache = Some((path_value, executables));
+    }
+}
+
+// Full path of the first executable called `name` on PATH
+pub fn find_executable(name: &str) -> Option<PathBuf> {
+    std::env::split_paths(&current_path())
+        .map(|dir| dir.join(name))
+        .find(|candidate| is_executable(candidate))
+}
@@ -0,0 +1,34 @@
+// Whether the machine is running on battery. Desktops, and systems where the power source
+// cannot be read, count as mains powered.
+#[cfg(target_os = "linux")]
+pub fn on_battery_power() -> bool {
+    let Ok(supplies) = std::fs::read_dir("/sys/class/power_supply") else {
+        return false;
+    };
+    let mut has_mains = false;
+    for supply in supplies.flatten() {
+        let path = supply.path();
+        let kind = std::fs::read_to_string(path.join("type")).unwrap_or_default();
+        if kind.trim() == "Mains" {
+            has_mains = true;
+            let online = std::fs::read_to_string(path.join("online")).unwrap_or_default();
+            if online.trim() == "1" {
+                return false;
+            }
+        }
+    }
+    has_mains
+}
+
+#[cfg(target_os = "macos")]
+pub fn on_battery_power() -> bool {
+    std::process::Command::new("pmset")
+        .args(["-g", "batt"])
+        .output()
+        .is_ok_and(|output| String::from_utf8_lossy(&output.stdout).contains("'Battery Power'"))
+}
+
+#[cfg(not(any(target_os = "linux", target_os = "macos")))]
+pub fn on_battery_power() -> bool {
+    false
+}
@@ -0,0 +1,554 @@
+// Minimal terminal screen model fed with PTY output. It follows the cursor, scroll region and
+// the modes the backend needs (alternate screen above all) without keeping screen contents.
+use serde::Serialize;
+use unicode_width::UnicodeWidthChar;
+
+const TAB_WIDTH: usize = 8;
+// Longer control sequences are malformed; give up on them instead of buffering forever
+const MAX_SEQUENCE_LEN: usize = 256;
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+enum ParseState {
+    #[default]
+    Ground,
+    // After ESC
+    Escape,
+    // ESC plus an intermediate byte, e.g. the charset selections ESC ( B
+    EscapeIntermediate,
+    Csi,
+    // OSC, DCS, APC, PM or SOS string, ended by BEL or ST
+    String,
+    // ESC inside such a string, possibly the start of ST
+    StringEscape,
+}
+
+// Which mouse events the app asked for with DECSET 9, 1000, 1002 or 1003
+#[derive(Serialize, Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub enum MouseTracking {
+    // The terminal handles the mouse itself (selection, scrolling)
+    #[default]
+    Off,
+    // Presses only
+    X10,
+    // Presses, releases and the wheel
+    Normal,
+    // Also motion while a button is held
+    ButtonEvent,
+    // Also motion without a button
+    AnyEvent,
+}
+
+// How mouse reports are encoded, set with DECSET 1005, 1006 or 1015
+#[derive(Serialize, Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub enum MouseEncoding {
+    // ESC [ M and three bytes; coordinates past 223 cannot be reported
+    #[default]
+    Default,
+    Utf8,
+    Sgr,
+    Urxvt,
+}
+
+#[derive(Serialize, Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct MouseMode {
+    pub tracking: MouseTracking,
+    pub encoding: MouseEncoding,
+}
+
+#[derive(Serialize, Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct CursorPosition {
+    pub row: usize,
+    pub col: usize,
+}
+
+#[derive(Serialize, Clone, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct ScreenState {
+    pub rows: usize,
+    pub cols: usize,
+    // 0-based
+    pub cursor: CursorPosition,
+    // A full-screen app (vim, htop, less) has switched to the alternate screen
+    pub alt_screen: bool,
+    // Inclusive, 0-based rows of the DECSTBM scroll region
+    pub scroll_top: usize,
+    pub scroll_bottom: usize,
+    pub cursor_visible: bool,
+    pub bracketed_paste: bool,
+    pub mouse: MouseMode,
+}
+
+pub struct ScreenModel {
+    rows: usize,
+    cols: usize,
+    cursor: CursorPosition,
+    // Printed in the last column; the next character wraps first
+    wrap_pending: bool,
+    // DECSC / CSI s
+    saved_cursor: Option<CursorPosition>,
+    // Cursor of the main screen while mode 1049 shows the alternate one
+    main_cursor: Option<CursorPosition>,
+    alt_screen: bool,
+    scroll_top: usize,
+    scroll_bottom: usize,
+    cursor_visible: bool,
+    bracketed_paste: bool,
+    mouse: MouseMode,
+    state: ParseState,
+    // Raw text of the escape sequence being parsed, kept across feeds
+    sequence: String,
+}
+
+impl ScreenModel {
+    pub fn new(rows: usize, cols: usize) -> Self {
+        let rows = rows.max(1);
+        Self {
+            rows,
+            cols: cols.max(1),
+            cursor: CursorPosition::default(),
+            wrap_pending: false,
+            saved_cursor: None,
+            main_cursor: None,
+            alt_screen: false,
+            scroll_top: 0,
+            scroll_bottom: rows - 1,
+            cursor_visible: true,
+            bracketed_paste: false,
+            mouse: MouseMode::default(),
+            state: ParseState::Ground,
+            sequence: String::new(),
+        }
+    }
+
+    pub fn alt_screen(&self) -> bool {
+        self.alt_screen
+    }
+
+    pub fn mouse(&self) -> MouseMode {
+        self.mouse
+    }
+
+    pub fn state(&self) -> ScreenState {
+        ScreenState {
+            rows: self.rows,
+            cols: self.cols,
+            cursor: self.cursor,
+            alt_screen: self.alt_screen,
+            scroll_top: self.scroll_top,
+            scroll_bottom: self.scroll_bottom,
+            cursor_visible: self.cursor_visible,
+            bracketed_paste: self.bracketed_paste,
+            mouse: self.mouse,
+        }
+    }
+
+    // Like xterm, a resize resets the scroll region and keeps the cursor on the screen
+    pub fn resize(&mut self, rows: usize, cols: usize) {
+        self.rows = rows.max(1);
+        self.cols = cols.max(1);
+        self.scroll_top = 0;
+        self.scroll_bottom = self.rows - 1;
+        self.cursor = self.clamp(self.cursor);
+        self.wrap_pending = false;
+    }
+
+    // Applies a chunk of output and returns the part written to the main screen, i.e. `data`
+    // without what full-screen apps draw on the alternate screen and without the sequences
+    // switching screens. A sequence split across chunks is returned with the next one.
+    pub fn feed(&mut self, data: &str) -> String {
+        let mut main_output = String::with_capacity(data.len());
+        for c in data.chars() {
+            if self.state == ParseState::Ground && c != '\x1b' {
+                if !self.alt_screen {
+                    main_output.push(c);
+                }
+                self.ground(c);
+                continue;
+            }
+
+            self.sequence.push(c);
+            let was_alt = self.alt_screen;
+            let finished = self.sequence_char(c);
+            if finished || self.sequence.len() > MAX_SEQUENCE_LEN {
+                if !was_alt && !self.alt_screen {
+                    main_output.push_str(&self.sequence);
+                }
+                self.sequence.clear();
+                self.state = ParseState::Ground;
+            }
+        }
+        main_output
+    }
+
+    fn ground(&mut self, c: char) {
+        match c {
+            '\r' => {
+                self.cursor.col = 0;
+                self.wrap_pending = false;
+            }
+            '\n' | '\x0b' | '\x0c' => self.line_feed(),
+            '\x08' => {
+                self.cursor.col = self.cursor.col.saturating_sub(1);
+                self.wrap_pending = false;
+            }
+            '\t' => {
+                let next_stop = (self.cursor.col / TAB_WIDTH + 1) * TAB_WIDTH;
+                self.cursor.col = next_stop.min(self.cols - 1);
+            }
+            c if c.is_control() => {}
+            c => self.print(c),
+        }
+    }
+
+    fn print(&mut self, c: char) {
+        let width = c.width().unwrap_or(0);
+        if width == 0 {
+            return;
+        }
+        if self.wrap_pending || self.cursor.col + width > self.cols {
+            self.cursor.col = 0;
+            self.line_feed();
+        }
+        self.cursor.col += width;
+        if self.cursor.col >= self.cols {
+            self.cursor.col = self.cols - 1;
+            self.wrap_pending = true;
+        }
+    }
+
+    // Moves down a line; at the bottom of the scroll region the region scrolls instead
+    fn line_feed(&mut self) {
+        self.wrap_pending = false;
+        if self.cursor.row != self.scroll_bottom && self.cursor.row + 1 < self.rows {
+            self.cursor.row += 1;
+        }
+    }
+
+    fn reverse_index(&mut self) {
+        self.wrap_pending = false;
+        if self.cursor.row != self.scroll_top {
+            self.cursor.row = self.cursor.row.saturating_sub(1);
+        }
+    }
+
+    fn clamp(&self, cursor: CursorPosition) -> CursorPosition {
+        CursorPosition {
+            row: cursor.row.min(self.rows - 1),
+            col: cursor.col.min(self.cols - 1),
+        }
+    }
+
+    fn move_to(&mut self, row: usize, col: usize) {
+        self.cursor = self.clamp(CursorPosition { row, col });
+        self.wrap_pending = false;
+    }
+
+    // Returns true once the sequence is complete
+    fn sequence_char(&mut self, c: char) -> bool {
+        match self.state {
+            ParseState::Ground => {
+                self.state = ParseState::Escape;
+                false
+            }
+            ParseState::Escape => {
+                match c {
+                    '[' => self.state = ParseState::Csi,
+                    ']' | 'P' | '_' | '^' | 'X' => self.state = ParseState::String,
+                    ' '..='/' => self.state = ParseState::EscapeIntermediate,
+                    '\x1b' => return false,
+                    _ => {
+                        self.escape(c);
+                        return true;
+                    }
+                }
+                false
+            }
+            ParseState::EscapeIntermediate => true,
+            ParseState::Csi => {
+                if ('\x40'..='\x7e').contains(&c) {
+                    let params = self.sequence[2..self.sequence.len() - 1].to_string();
+                    self.csi(&params, c);
+                    return true;
+                }
+                false
+            }
+            ParseState::String => {
+                match c {
+                    '\x07' => return true,
+                    '\x1b' => self.state = ParseState::StringEscape,
+                    _ => {}
+                }
+                false
+            }
+            ParseState::StringEscape => {
+                if c == '\\' {
+                    return true;
+                }
+                self.state = ParseState::String;
+                false
+            }
+        }
+    }
+
+    fn escape(&mut self, c: char) {
+        match c {
+            '7' => self.saved_cursor = Some(self.cursor),
+            '8' => {
+                let saved = self.saved_cursor.unwrap_or_default();
+                self.move_to(saved.row, saved.col);
+            }
+            'D' => self.line_feed(),
+            'E' => {
+                self.line_feed();
+                self.cursor.col = 0;
+            }
+            'M' => self.reverse_index(),
+            'c' => *self = ScreenModel::new(self.rows, self.cols),
+            _ => {}
+        }
+    }
+
+    fn csi(&mut self, params: &str, final_byte: char) {
+        let private = params.starts_with('?');
+        let values: Vec<usize> = params
+            .trim_start_matches(['?', '>', '=', '<'])
+            .split([';', ':'])
+            .map(|value| value.trim_end_matches([' ', '!', '"', '$', '\'']))
+            .map(|value| value.parse().unwrap_or(0))
+            .collect();
+        // The first parameter as a count or 1-based coordinate, where 0 means 1
+        let n = values.first().copied().unwrap_or(0).max(1);
+        let second = values.get(1).copied().unwrap_or(0).max(1);
+        // Vertical moves stop at the scroll region's margins when they start inside it
+        let top = if self.cursor.row >= self.scroll_top {
+            self.scroll_top
+        } else {
+            0
+        };
+        let bottom = if self.cursor.row <= self.scroll_bottom {
+            self.scroll_bottom
+        } else {
+            self.rows - 1
+        };
+        let CursorPosition { row, col } = self.cursor;
+
+        match final_byte {
+            'h' | 'l' if private => {
+                for mode in values {
+                    self.set_private_mode(mode, final_byte == 'h');
+                }
+            }
+            'A' => self.move_to(row.saturating_sub(n).max(top), col),
+            'B' | 'e' => self.move_to((row + n).min(bottom), col),
+            'C' | 'a' => self.move_to(row, col + n),
+            'D' => self.move_to(row, col.saturating_sub(n)),
+            'E' => self.move_to((row + n).min(bottom), 0),
+            'F' => self.move_to(row.saturating_sub(n).max(top), 0),
+            'G' | '`' => self.move_to(row, n - 1),
+            'd' => self.move_to(n - 1, col),
+            'H' | 'f' => self.move_to(n - 1, second - 1),
+            'r' if !private => {
+                let region_top = n - 1;
+                let region_bottom = values
+                    .get(1)
+                    .copied()
+                    .filter(|bottom| *bottom > 0)
+                    .unwrap_or(self.rows)
+                    .min(self.rows)
+                    - 1;
+                if region_top < region_bottom {
+                    self.scroll_top = region_top;
+                    self.scroll_bottom = region_bottom;
+                } else {
+                    self.scroll_top = 0;
+                    self.scroll_bottom = self.rows - 1;
+                }
+                self.move_to(0, 0);
+            }
+            's' if !private => self.saved_cursor = Some(self.cursor),
+            'u' if !private => {
+                let saved = self.saved_cursor.unwrap_or_default();
+                self.move_to(saved.row, saved.col);
+            }
+            _ => {}
+        }
+    }
+
+    fn set_private_mode(&mut self, mode: usize, enabled: bool) {
+        let tracking = match mode {
+            9 => Some(MouseTracking::X10),
+            1000 => Some(MouseTracking::Normal),
+            1002 => Some(MouseTracking::ButtonEvent),
+            1003 => Some(MouseTracking::AnyEvent),
+            _ => None,
+        };
+        let encoding = match mode {
+            1005 => Some(MouseEncoding::Utf8),
+            1006 => Some(MouseEncoding::Sgr),
+            1015 => Some(MouseEncoding::Urxvt),
+            _ => None,
+        };
+        // Resetting a mode other than the current one leaves the current one alone
+        if let Some(tracking) = tracking {
+            if enabled {
+                self.mouse.tracking = tracking;
+            } else if self.mouse.tracking == tracking {
+                self.mouse.tracking = MouseTracking::Off;
+            }
+        }
+        if let Some(encoding) = encoding {
+            if enabled {
+                self.mouse.encoding = encoding;
+            } else if self.mouse.encoding == encoding {
+                self.mouse.encoding = MouseEncoding::Default;
+            }
+        }
+
+        match mode {
+            25 => self.cursor_visible = enabled,
+            2004 => self.bracketed_paste = enabled,
+            47 | 1047 => self.alt_screen = enabled,
+            1048 => {
+                if enabled {
+                    self.saved_cursor = Some(self.cursor);
+                } else {
+                    let saved = self.saved_cursor.unwrap_or_default();
+                    self.move_to(saved.row, saved.col);
+                }
+            }
+            1049 => {
+                if enabled && !self.alt_screen {
+                    self.main_cursor = Some(self.cursor);
+                    self.alt_screen = true;
+                } else if !enabled && self.alt_screen {
+                    self.alt_screen = false;
+                    let main_cursor = self.main_cursor.take().unwrap_or(self.cursor);
+                    self.move_to(main_cursor.row, main_cursor.col);
+                }
+            }
+            _ => {}
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn at(row: usize, col: usize) -> CursorPosition {
+        CursorPosition { row, col }
+    }
+
+    #[test]
+    fn printing_wraps_at_the_last_column() {
+        let mut screen = ScreenModel::new(3, 5);
+        screen.feed("abcde");
+        assert_eq!(screen.state().cursor, at(0, 4));
+        screen.feed("f");
+        assert_eq!(screen.state().cursor, at(1, 1));
+        // The tab stops at the last column, where a wide character no longer fits
+        screen.feed("\r\n\t你");
+        assert_eq!(screen.state().cursor, at(2, 2));
+        // Line feeds at the bottom scroll instead of moving further
+        screen.feed("\n\n");
+        assert_eq!(screen.state().cursor, at(2, 2));
+        screen.feed("\x08\r");
+        assert_eq!(screen.state().cursor, at(2, 0));
+    }
+
+    #[test]
+    fn cursor_movement_is_clamped_to_the_screen() {
+        let mut screen = ScreenModel::new(24, 80);
+        screen.feed("\x1b[2;3H");
+        assert_eq!(screen.state().cursor, at(1, 2));
+        screen.feed("\x1b[100B\x1b[200C");
+        assert_eq!(screen.state().cursor, at(23, 79));
+        screen.feed("\x1b[5G\x1b[A");
+        assert_eq!(screen.state().cursor, at(22, 4));
+        screen.feed("\x1b7\x1b[H\x1b8");
+        assert_eq!(screen.state().cursor, at(22, 4));
+        screen.resize(10, 40);
+        assert_eq!(screen.state().cursor, at(9, 4));
+    }
+
+    #[test]
+    fn scroll_region_bounds_line_feeds() {
+        let mut screen = ScreenModel::new(10, 80);
+        screen.feed("\x1b[3;5r");
+        let state = screen.state();
+        assert_eq!((state.scroll_top, state.scroll_bottom), (2, 4));
+        assert_eq!(state.cursor, at(0, 0));
+        screen.feed("\x1b[4;1H\n\n\n");
+        assert_eq!(screen.state().cursor, at(4, 0));
+        screen.feed("\x1bM\x1bM\x1bM");
+        assert_eq!(screen.state().cursor, at(2, 0));
+        screen.feed("\x1b[r");
+        let state = screen.state();
+        assert_eq!((state.scroll_top, state.scroll_bottom), (0, 9));
+    }
+
+    #[test]
+    fn alternate_screen_output_is_left_out() {
+        let mut screen = ScreenModel::new(24, 80);
+        let main = screen.feed("before\x1b[?1049h\x1b[Hdrawn\x1b[?1049lafter");
+        assert_eq!(main, "beforeafter");
+        assert!(!screen.alt_screen());
+        // Back where the main screen left off
+        assert_eq!(screen.state().cursor, at(0, 11));
+    }
+
+    #[test]
+    fn sequences_split_across_chunks() {
+        let mut screen = ScreenModel::new(24, 80);
+        assert_eq!(screen.feed("a\x1b[3"), "a");
+        assert_eq!(screen.feed("1mb"), "\x1b[31mb");
+        assert_eq!(screen.feed("\x1b[?10"), "");
+        assert_eq!(screen.feed("49hvim"), "");
+        assert!(screen.alt_screen());
+        assert_eq!(screen.feed("\x1b[?1049l$ "), "$ ");
+        // Strings end with BEL or ST
+        assert_eq!(
+            screen.feed("\x1b]0;title\x07\x1b]2;x\x1b\\ok"),
+            "\x1b]0;title\x07\x1b]2;x\x1b\\ok"
+        );
+    }
+
+    #[test]
+    fn unterminated_sequences_are_given_up_on() {
+        let mut screen = ScreenModel::new(24, 80);
+        // Overflows MAX_SEQUENCE_LEN on its last character
+        let garbage = format!("\x1b]{}", "x".repeat(MAX_SEQUENCE_LEN - 1));
+        assert_eq!(screen.feed(&garbage), garbage);
+        assert_eq!(screen.feed("ok"), "ok");
+        assert_eq!(screen.state().cursor, at(0, 2));
+    }
+
+    #[test]
+    fn modes() {
+        let mut screen = ScreenModel::new(24, 80);
+        screen.feed("\x1b[?1000;1006h\x1b[?25l\x1b[?2004h");
+        let state = screen.state();
+        assert_eq!(
+            state.mouse,
+            MouseMode {
+                tracking: MouseTracking::Normal,
+                encoding: MouseEncoding::Sgr
+            }
+        );
+        assert!(!state.cursor_visible);
+        assert!(state.bracketed_paste);
+        // Resetting another tracking mode leaves the current one alone
+        screen.feed("\x1b[?1002l");
+        assert_eq!(screen.mouse().tracking, MouseTracking::Normal);
+        screen.feed("\x1b[?1000l");
+        assert_eq!(screen.mouse().tracking, MouseTracking::Off);
+        screen.feed("\x1bc");
+        let state = screen.state();
+        assert!(state.cursor_visible && !state.bracketed_paste);
+        assert_eq!(state.mouse, MouseMode::default());
+    }
+}
@@ -0,0 +1,82 @@
+// Levenshtein edit distance over chars
+pub fn edit_distance(a: &str, b: &str) -> usize {
+    let a: Vec<char> = a.chars().collect();
+    let b: Vec<char> = b.chars().collect();
+    if a.is_empty() {
+        return b.len();
+    }
+    if b.is_empty() {
+        return a.len();
+    }
+
+    let mut previous: Vec<usize> = (0..=b.len()).collect();
+    let mut current = vec![0; b.len() + 1];
+    for (i, ca) in a.iter().enumerate() {
+        current[0] = i + 1;
+        for (j, cb) in b.iter().enumerate() {
+            let substitution = previous[j] + usize::from(ca != cb);
+            current[j + 1] = substitution.min(previous[j + 1] + 1).min(current[j] + 1);
+        }
+        std::mem::swap(&mut previous, &mut current);
+    }
+    previous[b.len()]
+}
+
+// Returns candidates within `max_distance` of `target`, closest first
+pub fn closest_matches<'a, I>(
+    target: &str,
+    candidates: I,
+    max_distance: usize,
+    limit: usize,
+) -> Vec<String>
+where
+    I: IntoIterator<Item = &'a String>,
+{
+    let mut scored: Vec<(usize, &String)> = candidates
+        .into_iter()
+        .filter(|candidate| candidate.as_str() != target)
+        .map(|candidate| (edit_distance(target, candidate), candidate))
+        .filter(|(distance, _)| *distance <= max_distance)
+        .collect();
+    scored.sort_by(|a, b| a.0.cmp(&b.0).then_with(|| a.1.cmp(b.1)));
+    scored.dedup_by(|a, b| a.1 == b.1);
+    scored
+        .into_iter()
+        .take(limit)
+        .map(|(_, candidate)| candidate.clone())
+        .collect()
+}
+
+// Case-insensitive subsequence match of `pattern` in `text`, scoring consecutive runs and
+// matches at word starts higher. Returns the score and the matched char indices.
+pub fn fuzzy_match(pattern: &str, text: &str) -> Option<(i64, Vec<usize>)> {
+    let pattern: Vec<char> = pattern.to_lowercase().chars().collect();
+    if pattern.is_empty() {
+        return Some((0, Vec::new()));
+    }
+    let text: Vec<char> = text.chars().collect();
+
+    let mut indices = Vec::with_capacity(pattern.len());
+    let mut score = 0;
+    let mut next = 0;
+    for (i, c) in text.iter().enumerate() {
+        if next < pattern.len() && c.to_lowercase().eq(pattern[next].to_lowercase()) {
+            score += 1;
+            if indices.last().is_some_and(|&last| last + 1 == i) {
+                score += 5;
+            }
+            if i == 0 || !text[i - 1].is_alphanumeric() {
+                score += 3;
+            }
+            indices.push(i);
+            next += 1;
+        }
+    }
+    if next < pattern.len() {
+        return None;
+    }
+    // Prefer tighter matches
+    let span = indices[indices.len() - 1] - indices[0] + 1;
+    score -= (span - pattern.len()) as i64;
+    Some((score, indices))
+}
@@ -0,0 +1,41 @@
+use regex::Regex;
+use std::sync::OnceLock;
+
+// cl100k_base's pre-tokenization pattern, minus the `\s+(?!\S)` lookahead the regex crate
+// does not support
+fn pre_tokenizer() -> &'static Regex {
+    static PATTERN: OnceLock<Regex> = OnceLock::new();
+    PATTERN.get_or_init(|| {
+        Regex::new(
+            r"(?i:'s|'t|'re|'ve|'m|'ll|'d)|[^\r\n\p{L}\p{N}]?\p{L}+|\p{N}{1,3}| ?[^\s\p{L}\p{N}]+[\r\n]*|\s*[\r\n]+|\s+",
+        )
+        .expect("valid pre-tokenizer pattern")
+    })
+}
+
+// Tokens a tiktoken BPE merges one pre-token into, judged by its length: common English words
+// are a single token, longer ones split every ~7 letters, other scripts roughly per character
+fn piece_cost(piece: &str) -> usize {
+    let chars = piece.chars().count();
+    if piece.trim().is_empty() {
+        return 1;
+    }
+    if piece.chars().any(|c| c.is_alphabetic()) {
+        let non_ascii = piece.chars().filter(|c| !c.is_ascii()).count();
+        let ascii = chars - non_ascii;
+        return (1 + ascii.saturating_sub(1) / 7 + non_ascii).max(1);
+    }
+    if piece.chars().all(|c| c.is_numeric()) {
+        return 1;
+    }
+    chars.div_ceil(3).max(1)
+}
+
+// Approximate token count in the cl100k_base (tiktoken) encoding, without shipping its
+// vocabulary. Errs slightly high, which is the safe side when fitting a context window.
+pub fn estimate_tokens(text: &str) -> usize {
+    pre_tokenizer()
+        .find_iter(text)
+        .map(|piece| piece_cost(piece.as_str()))
+        .sum()
+}
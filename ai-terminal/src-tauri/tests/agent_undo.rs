@@ -0,0 +1,147 @@
+#![cfg(feature = "test-harness")]
+
+use ai_terminal_lib::testing::harness::TestHarness;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+fn git(dir: &Path, args: &[&str]) {
+    let status = Command::new("git")
+        .args(args)
+        .current_dir(dir)
+        .status()
+        .unwrap();
+    assert!(status.success(), "git {:?} failed", args);
+}
+
+fn scratch_dir(name: &str) -> PathBuf {
+    let dir = std::env::temp_dir().join(format!("ai-terminal-{}-{}", name, std::process::id()));
+    let _ = fs::remove_dir_all(&dir);
+    fs::create_dir_all(&dir).unwrap();
+    dir
+}
+
+fn scratch_repository(name: &str) -> PathBuf {
+    let dir = scratch_dir(name);
+    git(&dir, &["init", "--quiet"]);
+    fs::write(dir.join("tracked.txt"), "committed\n").unwrap();
+    git(&dir, &["add", "tracked.txt"]);
+    git(
+        &dir,
+        &[
+            "-c",
+            "user.name=Test",
+            "-c",
+            "user.email=test@localhost",
+            "commit",
+            "--quiet",
+            "-m",
+            "initial",
+        ],
+    );
+    dir
+}
+
+#[test]
+fn undo_restores_files_changed_by_an_agent_command() {
+    let harness = TestHarness::new();
+    let repository = scratch_repository("agent-undo");
+    // Uncommitted work only survives through the run's backup
+    fs::write(repository.join("tracked.txt"), "edited\n").unwrap();
+    fs::write(repository.join("notes.txt"), "draft\n").unwrap();
+    harness.run("agent", &format!("cd {}", repository.display()));
+
+    let run_id = harness
+        .run_agent_command(
+            "agent",
+            "echo overwritten > tracked.txt; rm notes.txt; echo new > created.txt",
+        )
+        .unwrap();
+    assert_eq!(
+        fs::read_to_string(repository.join("tracked.txt")).unwrap(),
+        "overwritten\n"
+    );
+
+    let result = harness.undo_agent_run(&run_id, false).unwrap();
+    assert!(result.failed.is_empty(), "{:?}", result.failed);
+    assert!(result.skipped.is_empty(), "{:?}", result.skipped);
+    assert_eq!(
+        fs::read_to_string(repository.join("tracked.txt")).unwrap(),
+        "edited\n"
+    );
+    assert_eq!(
+        fs::read_to_string(repository.join("notes.txt")).unwrap(),
+        "draft\n"
+    );
+    assert!(!repository.join("created.txt").exists());
+
+    assert!(harness.undo_agent_run(&run_id, false).is_err());
+}
+
+#[test]
+fn files_edited_after_the_run_are_skipped_unless_forced() {
+    let harness = TestHarness::new();
+    let repository = scratch_repository("agent-undo-skip");
+    harness.run("agent-skip", &format!("cd {}", repository.display()));
+
+    let run_id = harness
+        .run_agent_command("agent-skip", "echo agent > tracked.txt")
+        .unwrap();
+    fs::write(repository.join("tracked.txt"), "user\n").unwrap();
+
+    let result = harness.undo_agent_run(&run_id, false).unwrap();
+    assert_eq!(result.skipped.len(), 1);
+    assert_eq!(
+        fs::read_to_string(repository.join("tracked.txt")).unwrap(),
+        "user\n"
+    );
+
+    let result = harness.undo_agent_run(&run_id, true).unwrap();
+    assert_eq!(result.restored.len(), 1);
+    assert_eq!(
+        fs::read_to_string(repository.join("tracked.txt")).unwrap(),
+        "committed\n"
+    );
+}
+
+#[test]
+fn named_files_outside_git_can_be_undone() {
+    let harness = TestHarness::new();
+    let dir = scratch_dir("agent-undo-plain");
+    fs::write(dir.join("notes.txt"), "draft\n").unwrap();
+    harness.run("agent-plain", &format!("cd {}", dir.display()));
+
+    let run_id = harness
+        .run_agent_command("agent-plain", "rm notes.txt; echo new > created.txt")
+        .unwrap();
+    assert!(harness.command_receipts("agent-plain")[0].undoable);
+
+    let result = harness.undo_agent_run(&run_id, false).unwrap();
+    assert!(result.failed.is_empty(), "{:?}", result.failed);
+    assert_eq!(
+        fs::read_to_string(dir.join("notes.txt")).unwrap(),
+        "draft\n"
+    );
+    assert!(!dir.join("created.txt").exists());
+}
+
+#[test]
+fn unnamed_changes_outside_git_are_reported_as_not_undoable() {
+    let harness = TestHarness::new();
+    let dir = scratch_dir("agent-undo-script");
+    fs::write(dir.join("generate.sh"), "echo new > created.txt\n").unwrap();
+    harness.run("agent-script", &format!("cd {}", dir.display()));
+
+    let from = harness.events.len();
+    let run_id = harness
+        .run_agent_command("agent-script", "sh < generate.sh")
+        .unwrap();
+    let receipt = &harness.command_receipts("agent-script")[0];
+    assert!(!receipt.undoable);
+    assert!(receipt.undo_warning.is_some());
+    let warning = harness.wait_for(from, "command_error").unwrap();
+    assert!(warning.payload.to_string().contains("cannot be undone"));
+
+    assert!(harness.undo_agent_run(&run_id, false).is_err());
+    assert!(dir.join("created.txt").exists());
+}
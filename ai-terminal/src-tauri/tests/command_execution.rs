@@ -0,0 +1,93 @@
+#![cfg(feature = "test-harness")]
+
+use ai_terminal_lib::testing::harness::{TestHarness, DEFAULT_TIMEOUT};
+use std::fs;
+use std::path::PathBuf;
+
+fn scratch_dir(name: &str) -> PathBuf {
+    let dir = std::env::temp_dir().join(format!("ai-terminal-{}-{}", name, std::process::id()));
+    fs::create_dir_all(&dir).unwrap();
+    fs::canonicalize(dir).unwrap()
+}
+
+#[test]
+fn cd_changes_the_directory_later_commands_run_in() {
+    let harness = TestHarness::new();
+    let dir = scratch_dir("cd");
+    fs::create_dir_all(dir.join("nested")).unwrap();
+    let dir_text = dir.to_string_lossy().to_string();
+
+    let outcome = harness.run("s1", &format!("cd {}", dir_text));
+    assert!(outcome.result.is_ok(), "{:?}", outcome.result);
+    assert_eq!(outcome.end.unwrap().payload["success"], true);
+    assert_eq!(
+        harness.current_dir("s1").as_deref(),
+        Some(dir_text.as_str())
+    );
+
+    let outcome = harness.run("s1", "pwd");
+    assert_eq!(outcome.stdout.trim(), dir_text);
+
+    // Relative to the session's directory
+    harness.run("s1", "cd nested");
+    let nested = dir.join("nested").to_string_lossy().to_string();
+    assert_eq!(harness.current_dir("s1").as_deref(), Some(nested.as_str()));
+
+    // A failed cd leaves the directory alone
+    let outcome = harness.run("s1", "cd does-not-exist");
+    assert!(outcome.result.is_err());
+    assert_eq!(harness.current_dir("s1").as_deref(), Some(nested.as_str()));
+
+    // Other sessions keep their own directory
+    harness.run("s2", "cd /");
+    assert_eq!(harness.current_dir("s2").as_deref(), Some("/"));
+    assert_eq!(harness.current_dir("s1").as_deref(), Some(nested.as_str()));
+}
+
+#[test]
+fn commands_in_an_ssh_session_run_on_the_remote_shell() {
+    let harness = TestHarness::new().with_mock_ssh();
+
+    let from = harness.events.len();
+    harness
+        .execute("remote", "ssh user@example.com", Some("secret"))
+        .unwrap();
+    assert!(harness.wait_for(from, "ssh_session_started").is_some());
+    assert!(harness.is_ssh_active("remote"));
+
+    let from = harness.events.len();
+    harness
+        .execute("remote", "echo from-the-remote-side", None)
+        .unwrap();
+    assert!(harness.wait_for(from, "command_forwarded_to_ssh").is_some());
+    let output = harness.events.wait_for(from, DEFAULT_TIMEOUT, |event| {
+        event.name == "command_output"
+            && event.payload["sessionId"] == "remote"
+            && event.payload["data"]
+                .as_str()
+                .is_some_and(|data| data.contains("from-the-remote-side"))
+    });
+    assert!(output.is_some());
+
+    // The local session directory is not what remote commands see
+    let local_dir = harness.current_dir("remote");
+    harness.execute("remote", "cd /", None).unwrap();
+    assert_eq!(harness.current_dir("remote"), local_dir);
+
+    let from = harness.events.len();
+    harness.execute("remote", "exit", None).unwrap();
+    assert!(harness.wait_for(from, "ssh_session_ended").is_some());
+    assert!(!harness.is_ssh_active("remote"));
+}
+
+#[test]
+fn denylisted_variables_do_not_reach_commands() {
+    let harness = TestHarness::new();
+    std::env::set_var("AI_TERMINAL_SCRUB_TEST_TOKEN", "leaked-secret");
+    std::env::set_var("AI_TERMINAL_SCRUB_TEST_VISIBLE", "kept-value");
+
+    let outcome = harness.run("scrub", "env");
+    assert!(outcome.stdout.contains("kept-value"), "{}", outcome.stdout);
+    assert!(!outcome.stdout.contains("AI_TERMINAL_SCRUB_TEST_TOKEN"));
+    assert!(!outcome.stdout.contains("leaked-secret"));
+}
@@ -0,0 +1,149 @@
+#![cfg(feature = "test-harness")]
+
+use ai_terminal_lib::command::types::confirmation_manager::CONFIRMATION_REQUIRED_MARKER;
+use ai_terminal_lib::testing::harness::{TestHarness, DEFAULT_TIMEOUT};
+use std::path::{Path, PathBuf};
+use std::thread;
+use std::time::{Duration, Instant};
+
+fn scratch_dir(name: &str) -> PathBuf {
+    let dir = std::env::temp_dir().join(format!("ai-terminal-{}-{}", name, std::process::id()));
+    std::fs::create_dir_all(&dir).unwrap();
+    dir
+}
+
+fn wait_until_removed(path: &Path) -> bool {
+    let deadline = Instant::now() + DEFAULT_TIMEOUT;
+    while path.exists() {
+        if Instant::now() >= deadline {
+            return false;
+        }
+        thread::sleep(Duration::from_millis(20));
+    }
+    true
+}
+
+#[test]
+fn dangerous_command_waits_for_confirmation() {
+    let harness = TestHarness::new();
+    harness.protect_session("prod");
+    let target = scratch_dir("execute");
+    let command = format!("rm -rf {}", target.display());
+
+    let from = harness.events.len();
+    assert_eq!(
+        harness.execute("prod", &command, None).as_deref(),
+        Ok(CONFIRMATION_REQUIRED_MARKER)
+    );
+    assert!(target.exists());
+    let token = harness
+        .confirmation_token(from)
+        .expect("no confirmation_required event");
+
+    let outcome = harness.run_with_token("prod", &command, Some(&token));
+    assert_ne!(outcome.result.as_deref(), Ok(CONFIRMATION_REQUIRED_MARKER));
+    assert!(outcome.end.is_some());
+    assert!(!target.exists());
+
+    // Tokens are single-use
+    assert_eq!(
+        harness
+            .execute_with_token("prod", &command, None, Some(&token))
+            .as_deref(),
+        Ok(CONFIRMATION_REQUIRED_MARKER)
+    );
+}
+
+#[test]
+fn unprotected_and_harmless_commands_run_directly() {
+    let harness = TestHarness::new();
+    harness.protect_session("prod");
+    let target = scratch_dir("unprotected");
+
+    let outcome = harness.run("prod", "echo hello");
+    assert_eq!(outcome.stdout.trim(), "hello");
+
+    let outcome = harness.run("dev", &format!("rm -rf {}", target.display()));
+    assert_ne!(outcome.result.as_deref(), Ok(CONFIRMATION_REQUIRED_MARKER));
+    assert!(!target.exists());
+}
+
+#[test]
+fn typed_dangerous_line_is_held_until_confirmed() {
+    let harness = TestHarness::new();
+    harness.open_pty("prod-pty", 80, 24).unwrap();
+    harness.protect_session("prod-pty");
+    let target = scratch_dir("pty");
+
+    let from = harness.events.len();
+    assert_eq!(
+        harness
+            .write_pty("prod-pty", &format!("rm -rf {}\r", target.display()))
+            .as_deref(),
+        Ok(CONFIRMATION_REQUIRED_MARKER)
+    );
+    let token = harness
+        .confirmation_token(from)
+        .expect("no confirmation_required event");
+    thread::sleep(Duration::from_millis(200));
+    assert!(target.exists());
+
+    // Only the Enter is sent again; the line is still at the prompt
+    assert_eq!(
+        harness
+            .write_pty_with_token("prod-pty", "\r", Some(&token))
+            .as_deref(),
+        Ok("")
+    );
+    assert!(wait_until_removed(&target));
+}
+
+#[test]
+fn typed_lines_that_cannot_be_followed_need_confirmation() {
+    let harness = TestHarness::new();
+    harness.open_pty("prod-edit", 80, 24).unwrap();
+    harness.protect_session("prod-edit");
+
+    assert_eq!(
+        harness.write_pty("prod-edit", "echo ok\r").as_deref(),
+        Ok("")
+    );
+    // Up arrow recalls a history line the backend never saw typed
+    assert_eq!(
+        harness.write_pty("prod-edit", "\x1b[A\r").as_deref(),
+        Ok(CONFIRMATION_REQUIRED_MARKER)
+    );
+    // Ctrl-U clears the line, so what follows can be checked again
+    assert_eq!(
+        harness.write_pty("prod-edit", "\x15ls\r").as_deref(),
+        Ok("")
+    );
+}
+
+#[test]
+fn ssh_exec_to_a_protected_sessions_host_needs_confirmation() {
+    let harness = TestHarness::new().with_mock_ssh();
+    let from = harness.events.len();
+    harness
+        .execute("prod-ssh", "ssh deploy@prod-host", Some("secret"))
+        .unwrap();
+    assert!(harness.wait_for(from, "ssh_session_started").is_some());
+    harness.protect_session("prod-ssh");
+    let target = scratch_dir("ssh-exec");
+    let command = format!("rm -rf {}", target.display());
+
+    // Through the session, and by naming its host, with or without the user
+    for connection in ["prod-ssh", "deploy@prod-host", "prod-host"] {
+        assert_eq!(
+            harness
+                .ssh_exec(connection, &command, None)
+                .err()
+                .as_deref(),
+            Some(CONFIRMATION_REQUIRED_MARKER),
+            "{}",
+            connection
+        );
+    }
+    assert!(target.exists());
+    harness.execute("prod-ssh", "exit", None).unwrap();
+}
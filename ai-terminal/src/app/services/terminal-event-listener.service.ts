@@ -2,10 +2,32 @@ import { Injectable } from '@angular/core';
 import { listen, UnlistenFn } from '@tauri-apps/api/event';
 import { SSH_PRE_EXEC_PASSWORD_EVENT } from '../constants/ssh.constants';
 
+export interface ResourceUsage {
+  peakRssBytes: number;
+  userCpuMs: number;
+  systemCpuMs: number;
+  blockReads: number;
+  blockWrites: number;
+}
+
+export interface CommandEndPayload {
+  sessionId: string;
+  success: boolean;
+  message: string;
+  exitCode: number | null;
+  durationMs: number | null;
+  resources: ResourceUsage | null;
+}
+
+export interface CommandOutputPayload {
+  sessionId: string;
+  data: string;
+}
+
 export interface TerminalEventHandlers {
-  onCommandOutput: (payload: string) => void | Promise<void>;
-  onCommandError: (payload: string) => void | Promise<void>;
-  onCommandEnd: (payload: string) => void | Promise<void>;
+  onCommandOutput: (payload: CommandOutputPayload) => void | Promise<void>;
+  onCommandError: (payload: CommandOutputPayload) => void | Promise<void>;
+  onCommandEnd: (payload: CommandEndPayload) => void | Promise<void>;
   onCommandForwardedToSsh: () => void | Promise<void>;
   onSshPreExecPasswordRequest: (payload: string) => void | Promise<void>;
   onRemoteDirectoryUpdated: (payload: string) => void | Promise<void>;
@@ -19,15 +41,15 @@ export interface TerminalEventHandlers {
 export class TerminalEventListenerService {
   async registerListeners(handlers: TerminalEventHandlers): Promise<UnlistenFn[]> {
     const unlistenCommandOutput = await listen('command_output', async (event) => {
-      await handlers.onCommandOutput(event.payload as string);
+      await handlers.onCommandOutput(event.payload as CommandOutputPayload);
     });
 
     const unlistenCommandError = await listen('command_error', async (event) => {
-      await handlers.onCommandError(event.payload as string);
+      await handlers.onCommandError(event.payload as CommandOutputPayload);
     });
 
     const unlistenCommandEnd = await listen('command_end', async (event) => {
-      await handlers.onCommandEnd(event.payload as string);
+      await handlers.onCommandEnd(event.payload as CommandEndPayload);
     });
 
     const unlistenCommandForwarded = await listen('command_forwarded_to_ssh', async () => {